@@ -0,0 +1,43 @@
+/*
+ * csd.h - C interface to the code-scan-docs matrix
+ *
+ * Build the shared library with `cargo build --release --no-default-features`
+ * and link against libcsd. Functions returning NULL record a message readable
+ * with csd_last_error(); strings returned by the library must be released with
+ * csd_string_free().
+ */
+#ifndef CSD_H
+#define CSD_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define CSD_FFI_VERSION 1
+
+typedef struct CsdMatrix CsdMatrix;
+
+uint32_t csd_ffi_version(void);
+const char *csd_last_error(void);
+
+CsdMatrix *csd_matrix_load(const char *path);
+void csd_matrix_free(CsdMatrix *matrix);
+size_t csd_matrix_file_count(CsdMatrix *matrix);
+
+/* JSON arrays of project-relative paths */
+char *csd_matrix_dependencies(CsdMatrix *matrix, const char *file);
+char *csd_matrix_dependents(CsdMatrix *matrix, const char *file);
+
+/* NULL with no error set when the file has no summary */
+char *csd_matrix_file_summary(CsdMatrix *matrix, const char *file);
+
+void csd_string_free(char *value);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CSD_H */
@@ -14,6 +14,7 @@ from typing import List, Optional, Tuple, Dict
 from csd_plugin_sdk import (
     BaseAnalyzer,
     CodeElement,
+    Export,
     Import,
     Relationship,
     ExternalDependency,
@@ -74,6 +75,8 @@ class PythonAnalyzer(BaseAnalyzer):
             ".flake8",
             ".pylintrc",
         ]
+        # Source roots from the project's manifests find `src`-layout packages
+        self.wants_project_context = True
 
     def can_analyze(self, file_path: str, content_preview: str) -> Tuple[bool, float]:
         """Check if this plugin can analyze the given file."""
@@ -141,7 +144,7 @@ class PythonAnalyzer(BaseAnalyzer):
 
         elements = self._extract_elements(tree, input_data.content)
         imports = self._extract_imports(tree, input_data)
-        exports = self._extract_exports(tree)
+        exports = self._extract_exports(tree, input_data)
         relationships = self._extract_relationships(imports, input_data)
 
         # Calculate token information
@@ -213,23 +216,59 @@ class PythonAnalyzer(BaseAnalyzer):
     def _extract_elements(self, tree: ast.AST, content: str) -> List[CodeElement]:
         """Extract code elements (functions, classes, etc.) from AST."""
         elements = []
+        parents = self._class_parents(tree)
 
         for node in ast.walk(tree):
+            element = None
             if isinstance(node, ast.FunctionDef):
-                elements.append(self._create_function_element(node, content))
+                element = self._create_function_element(node, content)
             elif isinstance(node, ast.AsyncFunctionDef):
-                elements.append(self._create_async_function_element(node, content))
+                element = self._create_async_function_element(node, content)
             elif isinstance(node, ast.ClassDef):
-                elements.append(self._create_class_element(node, content))
+                element = self._create_class_element(node, content)
             elif isinstance(node, (ast.Assign, ast.AnnAssign)) and hasattr(
                 node, "lineno"
             ):
-                var_element = self._create_variable_element(node, content)
-                if var_element:
-                    elements.append(var_element)
+                element = self._create_variable_element(node, content)
+
+            if element is None:
+                continue
+            parent = parents.get(node)
+            if parent:
+                element.parent = parent
+                element.qualified_name = f"{parent}.{element.name}"
+            elements.append(element)
 
         return elements
 
+    def _class_parents(self, tree: ast.AST) -> Dict[ast.AST, str]:
+        """Map each definition in a class body to the class's qualified name."""
+        parents: Dict[ast.AST, str] = {}
+
+        def visit(node: ast.AST, qualname: Optional[str]) -> None:
+            for child in ast.iter_child_nodes(node):
+                if qualname and isinstance(
+                    child,
+                    (
+                        ast.FunctionDef,
+                        ast.AsyncFunctionDef,
+                        ast.ClassDef,
+                        ast.Assign,
+                        ast.AnnAssign,
+                    ),
+                ):
+                    parents[child] = qualname
+                if isinstance(child, ast.ClassDef):
+                    visit(child, f"{qualname}.{child.name}" if qualname else child.name)
+                elif isinstance(child, (ast.FunctionDef, ast.AsyncFunctionDef)):
+                    # Definitions local to a function aren't reachable by name
+                    visit(child, None)
+                else:
+                    visit(child, qualname)
+
+        visit(tree, None)
+        return parents
+
     def _extract_docstring_and_tokens(
         self, node: ast.AST, content: str
     ) -> Tuple[Optional[str], int]:
@@ -433,7 +472,10 @@ class PythonAnalyzer(BaseAnalyzer):
             if isinstance(node, ast.Import):
                 for alias in node.names:
                     import_type = detect_import_type(
-                        alias.name, input_data.project_root, input_data.file_path
+                        alias.name,
+                        input_data.project_root,
+                        input_data.file_path,
+                        input_data.project,
                     )
                     imports.append(
                         Import(
@@ -449,7 +491,10 @@ class PythonAnalyzer(BaseAnalyzer):
                 items = [alias.name for alias in node.names]
 
                 import_type = detect_import_type(
-                    module_name, input_data.project_root, input_data.file_path
+                    module_name,
+                    input_data.project_root,
+                    input_data.file_path,
+                    input_data.project,
                 )
 
                 imports.append(
@@ -463,27 +508,60 @@ class PythonAnalyzer(BaseAnalyzer):
 
         return imports
 
-    def _extract_exports(self, tree: ast.Module) -> List[str]:
-        """Extract what this module exports (defined at module level)."""
+    def _extract_exports(
+        self, tree: ast.Module, input_data: PluginInput
+    ) -> List[Export]:
+        """Extract what this module exports (defined or imported at module level).
+
+        In a package's __init__.py, names imported from local modules are re-exports
+        of the file that defines them.
+        """
         exports = []
+        is_package = Path(input_data.file_path).name == "__init__.py"
 
         for node in tree.body:
             if isinstance(node, ast.FunctionDef):
                 if not node.name.startswith("_"):
-                    exports.append(node.name)
+                    exports.append(Export(name=node.name, kind="function"))
             elif isinstance(node, ast.AsyncFunctionDef):
                 if not node.name.startswith("_"):
-                    exports.append(node.name)
+                    exports.append(Export(name=node.name, kind="function"))
             elif isinstance(node, ast.ClassDef):
                 if not node.name.startswith("_"):
-                    exports.append(node.name)
+                    exports.append(Export(name=node.name, kind="class"))
             elif isinstance(node, ast.Assign):
                 for target in node.targets:
                     if isinstance(target, ast.Name) and not target.id.startswith("_"):
-                        exports.append(target.id)
+                        exports.append(Export(name=target.id, kind="variable"))
+            elif isinstance(node, ast.ImportFrom) and is_package:
+                exports.extend(self._extract_reexports(node, input_data))
 
         return exports
 
+    def _extract_reexports(
+        self, node: ast.ImportFrom, input_data: PluginInput
+    ) -> List[Export]:
+        """Exports for `from .module import a as b` in a package's __init__.py."""
+        module_name = node.module or ""
+        if node.level == 0:
+            import_type = detect_import_type(
+                module_name,
+                input_data.project_root,
+                input_data.file_path,
+                input_data.project,
+            )
+            if import_type != "local":
+                return []
+        source = self._resolve_import_path(module_name, input_data)
+        if source is None:
+            return []
+
+        return [
+            Export(name=alias.name, kind="import", alias=alias.asname, source=source)
+            for alias in node.names
+            if alias.name != "*" and not (alias.asname or alias.name).startswith("_")
+        ]
+
     def _extract_relationships(
         self, imports: List[Import], input_data: PluginInput
     ) -> List[Relationship]:
@@ -516,11 +594,17 @@ class PythonAnalyzer(BaseAnalyzer):
 
         module_path = module_name.replace(".", "/")
 
+        search_dirs = [project_root, current_dir]
+        if input_data.project:
+            source_roots = input_data.project.get("source_roots", [])
+            search_dirs += [project_root / root for root in source_roots]
         potential_paths = [
-            project_root / f"{module_path}.py",
-            project_root / f"{module_path}/__init__.py",
-            current_dir / f"{module_path}.py",
-            current_dir / f"{module_path}/__init__.py",
+            path
+            for directory in search_dirs
+            for path in (
+                directory / f"{module_path}.py",
+                directory / module_path / "__init__.py",
+            )
         ]
 
         for path in potential_paths:
@@ -10,6 +10,7 @@ from typing import List, Dict, Any, Optional, Set, Tuple, Union
 from csd_plugin_sdk import (
     BaseAnalyzer,
     CodeElement,
+    Export,
     Import,
     Relationship,
     ExternalDependency,
@@ -115,7 +116,7 @@ class RustAnalyzer(BaseAnalyzer):
 
         elements = self._extract_elements(content, lines)
         imports = self._extract_imports(content, lines, input_data)
-        exports = self._extract_exports(content, lines)
+        exports = self._extract_exports(content, lines, input_data)
         relationships = self._extract_relationships(imports, input_data)
 
         # Calculate token information
@@ -296,8 +297,31 @@ class RustAnalyzer(BaseAnalyzer):
                         )
                         break
 
+        self._assign_parents(elements)
         return elements
 
+    def _assign_parents(self, elements: List[CodeElement]) -> None:
+        """Qualify items inside an impl or trait block with its type, e.g. Config::load."""
+        containers = [e for e in elements if e.element_type in ("impl", "trait")]
+
+        for element in elements:
+            if element.element_type in ("impl", "trait"):
+                continue
+            enclosing = [
+                c
+                for c in containers
+                if c.line_start < element.line_start and element.line_end <= c.line_end
+            ]
+            if not enclosing:
+                continue
+            # The innermost block is the one that starts last
+            container = max(enclosing, key=lambda c: c.line_start)
+            # `impl Trait for Type` belongs to Type
+            match = re.search(r"\bfor\s+(\w+)", container.signature or "")
+            parent = match.group(1) if container.element_type == "impl" and match else None
+            element.parent = parent or container.name
+            element.qualified_name = f"{element.parent}::{element.name}"
+
     def _extract_element_documentation(
         self, lines: List[str], element_line: int
     ) -> Optional[str]:
@@ -479,27 +503,56 @@ class RustAnalyzer(BaseAnalyzer):
 
             return "third_party"
 
-    def _extract_exports(self, content: str, lines: List[str]) -> List[str]:
-        """Extract public items that this module exports."""
-        exports = []
+    def _extract_exports(
+        self, content: str, lines: List[str], input_data: PluginInput
+    ) -> List[Export]:
+        """Extract public items that this module exports, including `pub use` re-exports."""
+        exports: Dict[str, Export] = {}
 
         pub_patterns = [
-            r"^\s*pub\s+fn\s+(\w+)",
-            r"^\s*pub\s+struct\s+(\w+)",
-            r"^\s*pub\s+enum\s+(\w+)",
-            r"^\s*pub\s+trait\s+(\w+)",
-            r"^\s*pub\s+type\s+(\w+)",
-            r"^\s*pub\s+const\s+(\w+)",
-            r"^\s*pub\s+mod\s+(\w+)",
+            (r"^\s*pub\s+fn\s+(\w+)", "function"),
+            (r"^\s*pub\s+struct\s+(\w+)", "struct"),
+            (r"^\s*pub\s+enum\s+(\w+)", "enum"),
+            (r"^\s*pub\s+trait\s+(\w+)", "trait"),
+            (r"^\s*pub\s+type\s+(\w+)", "type"),
+            (r"^\s*pub\s+const\s+(\w+)", "constant"),
+            (r"^\s*pub\s+mod\s+(\w+)", "module"),
         ]
 
         for line in lines:
-            for pattern in pub_patterns:
+            for pattern, kind in pub_patterns:
                 match = re.search(pattern, line)
                 if match:
-                    exports.append(match.group(1))
+                    exports[match.group(1)] = Export(name=match.group(1), kind=kind)
 
-        return list(set(exports))
+            match = re.search(r"^\s*pub\s+use\s+([^;]+);", line)
+            if match:
+                for export in self._parse_reexport(match.group(1).strip(), input_data):
+                    exports[export.exported_name] = export
+
+        return list(exports.values())
+
+    def _parse_reexport(
+        self, use_statement: str, input_data: PluginInput
+    ) -> List[Export]:
+        """Turn `pub use a::b::{C, D as E}` into exports of C and E, sourced from a::b."""
+        module, items = self._parse_use_statement(use_statement)
+        source = None
+        if self._determine_rust_import_type(module, input_data.project_root) == "local":
+            source = self._resolve_rust_module_path(module, input_data)
+
+        exports = []
+        for item in items:
+            # Globs re-export whatever the module exports, which isn't known here
+            if item == "*" or item == "self":
+                continue
+            name, _, alias = (part.strip() for part in item.partition(" as "))
+            if alias == "_":
+                continue
+            exports.append(
+                Export(name=name, kind="use", alias=alias or None, source=source)
+            )
+        return exports
 
     def _extract_relationships(
         self, imports: List[Import], input_data: PluginInput
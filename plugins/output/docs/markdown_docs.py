@@ -35,7 +35,7 @@ class LLMMarkdownDocsPlugin(BaseOutputPlugin):
         super().__init__()
         self.name = "llm_markdown_docs"
         self.version = "1.0.0"
-        self.supported_output_types = ["documentation"]
+        self.supported_output_types = ["documentation", "changelog"]
         self.supported_formats = ["markdown"]
 
         # Set up logging
@@ -43,7 +43,8 @@ class LLMMarkdownDocsPlugin(BaseOutputPlugin):
 
     def can_generate(self, output_type: str, format: str) -> Tuple[bool, float]:
         """Check if this plugin can generate the requested output."""
-        if output_type.lower() == "documentation" and format.lower() == "markdown":
+        output_type = output_type.lower()
+        if output_type in self.supported_output_types and format.lower() == "markdown":
             return True, 1.0
         return False, 0.0
 
@@ -56,9 +57,31 @@ class LLMMarkdownDocsPlugin(BaseOutputPlugin):
         self, input_data: OutputPluginInput
     ) -> OutputPluginResult:
         """Async implementation of documentation generation."""
+        if input_data.format_options.get("output_type") == "changelog":
+            return await self._generate_changelog(input_data)
 
         # Load the matrix data
         matrix_data = self._load_matrix_from_file(input_data.matrix_path)
+        # Oversized files and functions, measured by csd against the `quality` limits
+        hotspots = input_data.format_options.get("hotspots")
+        if hotspots is not None:
+            matrix_data["hotspots"] = hotspots
+        # Documentation density and the largest modules without any documentation
+        documentation = input_data.format_options.get("documentation")
+        if documentation is not None:
+            matrix_data["documentation"] = documentation
+        # Relative paths ordered outward from the entrypoints
+        traversal_order = input_data.format_options.get("traversal_order")
+        if traversal_order is not None:
+            matrix_data["traversal_order"] = traversal_order
+        # Architecture decision records and the files each one is tagged on
+        adrs = input_data.format_options.get("adrs")
+        if adrs is not None:
+            matrix_data["adrs"] = adrs
+        # Owners, review status and notes on files and elements, by path and element ID
+        annotations = input_data.format_options.get("annotations")
+        if annotations is not None:
+            matrix_data["annotations"] = annotations
 
         # Ensure output directory exists
         self._ensure_output_directory(input_data.output_dir)
@@ -87,6 +110,8 @@ class LLMMarkdownDocsPlugin(BaseOutputPlugin):
 
         # Look for existing template or use default
         template_content = await self._find_or_create_template(input_data, matrix_data)
+        # `{{ git.tag }}` and friends, for version and provenance headers
+        template_content = self._fill_template_variables(template_content, input_data)
 
         # Process the template
         if llm_client:
@@ -111,6 +136,7 @@ class LLMMarkdownDocsPlugin(BaseOutputPlugin):
                 output_path,
                 "markdown",
                 {
+                    "language": input_data.format_options.get("language"),
                     "llm_enhanced": llm_client is not None,
                     "template_source": (
                         "existing"
@@ -127,15 +153,63 @@ class LLMMarkdownDocsPlugin(BaseOutputPlugin):
             output_type="documentation",
             outputs=outputs,
             metadata={
-                "total_files_documented": len(matrix_data.get("files", {})),
+                "total_files_documented": len(self._maintained_files(matrix_data)),
                 "llm_enhanced": llm_client is not None,
                 "matrix_timestamp": matrix_data.get("metadata", {}).get(
                     "scan_timestamp"
                 ),
+                "scan_stats": matrix_data.get("metadata", {}).get("scan_stats"),
                 "model_used": llm_config.model if llm_config else None,
             },
         )
 
+    async def _generate_changelog(
+        self, input_data: OutputPluginInput
+    ) -> OutputPluginResult:
+        """Polish the changelog draft csd built from the matrix diff and commits."""
+        draft = input_data.format_options.get("draft", "")
+        self._ensure_output_directory(input_data.output_dir)
+
+        content = draft
+        llm_config = self._create_llm_config(input_data.plugin_config)
+        if llm_config and self._should_use_llm(input_data.format_options):
+            llm_client = LLMClient(llm_config)
+            if await llm_client.test_connection():
+                response = await llm_client.generate(
+                    prompt=(
+                        "Rewrite this draft changelog for users of the project. Keep "
+                        "the component headings, summarize related commits into single "
+                        "entries, call out breaking API changes first and leave out "
+                        "file lists. Return only the Markdown."
+                    ),
+                    context=draft,
+                )
+                if response.success and response.content.strip():
+                    content = response.content
+                else:
+                    self.logger.warning(f"Changelog polishing failed: {response.error}")
+            else:
+                self.logger.warning("LLM connection test failed, keeping the draft")
+
+        content = self._fill_template_variables(content, input_data)
+        output_path = str(Path(input_data.output_dir) / "CHANGELOG.md")
+        with open(output_path, "w", encoding="utf-8") as f:
+            f.write(content)
+
+        return OutputPluginResult(
+            plugin_name=self.name,
+            plugin_version=self.version,
+            output_type="changelog",
+            outputs=[
+                self._create_generated_output(
+                    output_path,
+                    "markdown",
+                    {"llm_enhanced": content != draft},
+                )
+            ],
+            metadata={},
+        )
+
     def _create_llm_config(
         self, plugin_config: Optional[Dict[str, Any]]
     ) -> Optional[LLMConfig]:
@@ -240,12 +314,36 @@ Installation instructions for this project.
 Basic usage examples and getting started guide.
 <!-- /CSD:SECTION:usage -->
 
+## Architecture
+
+<!-- CSD:SECTION:architecture -->
+Overview of the project's main components and how they relate.
+<!-- /CSD:SECTION:architecture -->
+
+## Hotspots
+
+<!-- CSD:SECTION:hotspots -->
+Files and functions that have grown past the project's size limits.
+<!-- /CSD:SECTION:hotspots -->
+
 ## API Reference
 
 <!-- CSD:SECTION:api_reference -->
 Detailed API documentation and reference.
 <!-- /CSD:SECTION:api_reference -->
 
+## Data Dictionary
+
+<!-- CSD:SECTION:data_dictionary -->
+The tables the project stores data in, their columns and the code using them.
+<!-- /CSD:SECTION:data_dictionary -->
+
+## Glossary
+
+<!-- CSD:SECTION:glossary -->
+Domain terms used throughout the code and what they mean.
+<!-- /CSD:SECTION:glossary -->
+
 ## Contributing
 
 Contributions are welcome! Please feel free to submit a Pull Request.
@@ -266,6 +364,7 @@ This project is licensed under the terms specified in the LICENSE file.
 
         processor = SectionProcessor(llm_client)
         sections = processor.extract_sections(template_content)
+        language = input_data.format_options.get("language")
 
         if not sections:
             self.logger.warning(
@@ -276,23 +375,38 @@ This project is licensed under the terms specified in the LICENSE file.
         enhanced_content = template_content
 
         # Process each section
-        for section in sections:
+        for index, section in enumerate(sections):
             self.logger.info(f"Processing section: {section['name']}")
+            self.report_progress(index, len(sections), f"section {section['name']}")
 
             # Build context for this section
             context = self._build_section_context(section["name"], matrix_data)
 
             # Get custom prompt if configured
             section_prompt = self._get_section_prompt(
-                section["name"], input_data.plugin_config
+                section["name"], input_data.plugin_config, language
             )
 
+            # Reuse a section finished before an interrupted run
+            cache = input_data.section_cache
+            cached = cache.load(section["name"]) if cache else None
+            if cached is not None:
+                enhanced_content = processor.replace_section_content(
+                    enhanced_content, section, cached
+                )
+                self.logger.info(f"♻️  Reused cached section: {section['name']}")
+                continue
+
             # Enhance the section
             try:
                 enhanced_section_content = await processor.enhance_section(
-                    section, context, section_prompt
+                    section, context, section_prompt, language
                 )
 
+                # Only cache sections the LLM actually rewrote; failures return the original
+                if cache and enhanced_section_content != section["original_content"]:
+                    cache.store(section["name"], enhanced_section_content)
+
                 # Replace in the full document
                 enhanced_content = processor.replace_section_content(
                     enhanced_content, section, enhanced_section_content
@@ -345,8 +459,16 @@ This project is licensed under the terms specified in the LICENSE file.
             return self._build_installation_context(matrix_data)
         elif section_name == "api_reference":
             return self._build_api_context(matrix_data)
+        elif section_name == "architecture":
+            return self._build_architecture_context(matrix_data)
         elif section_name == "usage":
             return self._build_usage_context(matrix_data)
+        elif section_name == "hotspots":
+            return self._build_hotspots_context(matrix_data)
+        elif section_name == "glossary":
+            return self._build_glossary_context(matrix_data)
+        elif section_name == "data_dictionary":
+            return self._build_data_dictionary_context(matrix_data)
         else:
             # Generic context
             return self._build_generic_context(matrix_data)
@@ -359,7 +481,7 @@ This project is licensed under the terms specified in the LICENSE file.
         metadata = matrix_data.get("metadata", {})
         context_parts.append(f"Project root: {metadata.get('project_root', 'Unknown')}")
         context_parts.append(
-            f"Languages detected: {', '.join(metadata.get('plugins_used', []))}"
+            f"Languages detected: {', '.join(self._plugin_names(metadata))}"
         )
         context_parts.append(f"Total files: {metadata.get('total_files', 0)}")
 
@@ -373,10 +495,10 @@ This project is licensed under the terms specified in the LICENSE file.
                 )
 
         # File structure overview
-        files = matrix_data.get("files", {})
+        files = self._ordered_files(matrix_data)
         if files:
             context_parts.append(f"\nKey files:")  # noqa: F541
-            for file_path, file_info in list(files.items())[:5]:  # First 5 files
+            for file_path, file_info in files[:5]:  # First 5 files
                 context_parts.append(f"  - {file_path}")
 
         return "\n".join(context_parts)
@@ -421,13 +543,12 @@ This project is licensed under the terms specified in the LICENSE file.
 
     def _build_api_context(self, matrix_data: Dict[str, Any]) -> str:
         """Build context for API reference section."""
-        context_parts = []
+        context_parts = self._undocumented_lines(matrix_data)
 
         # Public functions and classes
-        files = matrix_data.get("files", {})
         public_elements = []
 
-        for file_path, file_info in files.items():
+        for file_path, file_info in self._ordered_files(matrix_data):
             elements = file_info.get("elements", [])
             for element in elements:
                 # Consider public if name doesn't start with _ or has public metadata
@@ -462,8 +583,310 @@ This project is licensed under the terms specified in the LICENSE file.
                 if signature:
                     context_parts.append(f"    Signature: {signature}")
 
+        annotations = self._annotation_lines(matrix_data)
+        if annotations:
+            context_parts.append(
+                "\nMaintainer annotations (state owners and review status as given):"
+            )
+            context_parts.extend(f"  {line}" for line in annotations)
+
         return "\n".join(context_parts)
 
+    def _ordered_files(
+        self, matrix_data: Dict[str, Any]
+    ) -> List[Tuple[str, Dict[str, Any]]]:
+        """Matrix files in csd's traversal order, starting from the entrypoints."""
+        files = self._maintained_files(matrix_data)
+        order = matrix_data.get("traversal_order")
+        if not order:
+            return list(files.items())
+
+        rank = {path: index for index, path in enumerate(order)}
+        return sorted(
+            files.items(),
+            key=lambda item: rank.get(
+                item[1].get("relative_path", item[0]), len(rank)
+            ),
+        )
+
+    def _plugin_names(self, metadata: Dict[str, Any]) -> List[str]:
+        """Names of the plugins behind the matrix; older matrices list bare names."""
+        return [
+            plugin["name"] if isinstance(plugin, dict) else plugin
+            for plugin in metadata.get("plugins_used", [])
+        ]
+
+    def _maintained_files(self, matrix_data: Dict[str, Any]) -> Dict[str, Any]:
+        """Matrix files without vendored code, which csd scans but the docs leave out."""
+        return {
+            path: info
+            for path, info in matrix_data.get("files", {}).items()
+            if info.get("category") != "vendored"
+        }
+
+    def _undocumented_lines(self, matrix_data: Dict[str, Any]) -> List[str]:
+        """List the modules csd found no documentation in, largest first."""
+        documentation = matrix_data.get("documentation")
+        if not documentation or not documentation.get("undocumented"):
+            return []
+
+        lines = [
+            "Undocumented modules (describe these first, the code has no docs "
+            f"to fall back on; {documentation.get('undocumented_files', 0)} of "
+            f"{documentation.get('source_files', 0)} source files):"
+        ]
+        for module in documentation["undocumented"]:
+            lines.append(f"  - {module['path']} ({module['tokens']} tokens)")
+        return lines
+
+    def _annotation_lines(self, matrix_data: Dict[str, Any]) -> List[str]:
+        """One line per annotated file, then per annotated element, with its notes."""
+        annotations = matrix_data.get("annotations") or {}
+
+        def describe(label: str, annotation: Dict[str, Any]) -> List[str]:
+            details = []
+            if annotation.get("owner"):
+                details.append(f"owner: {annotation['owner']}")
+            if annotation.get("review"):
+                details.append(f"review: {annotation['review']}")
+            line = f"- {label}"
+            if details:
+                line += f" ({', '.join(details)})"
+            lines = [line]
+            for note in annotation.get("notes", []):
+                author = f" ({note['author']})" if note.get("author") else ""
+                lines.append(f"  - {note.get('text', '')}{author}")
+            return lines
+
+        lines = []
+        for path, annotation in annotations.get("files", {}).items():
+            lines.extend(describe(f"`{path}`", annotation))
+        for element in annotations.get("elements", {}).values():
+            label = f"`{element.get('name')}` in `{element.get('file')}`"
+            lines.extend(describe(label, element))
+        return lines
+
+    def _adr_lines(self, adrs: List[Dict[str, Any]]) -> List[str]:
+        """Index the architecture decision records, linking each to its file."""
+        lines = []
+        for adr in adrs:
+            files = adr.get("files", [])
+            lines.append(
+                f"- [ADR {adr.get('number', 0):04}: {adr.get('title', '')}]"
+                f"({adr.get('path', '')}) - {adr.get('status') or 'Unknown'}, "
+                f"{len(files)} files"
+            )
+        return lines
+
+    def _interaction_lines(self, matrix_data: Dict[str, Any]) -> List[str]:
+        """Describe the services seen talking to the project at runtime."""
+        interactions = matrix_data.get("project_info", {}).get(
+            "external_interactions", []
+        )
+        lines = []
+        for interaction in interactions:
+            arrow = "->" if interaction.get("direction") == "outgoing" else "<-"
+            details = [f"{interaction.get('calls', 0)} calls"]
+            if interaction.get("errors"):
+                details.append(f"{interaction['errors']} errors")
+            if interaction.get("avg_latency_ms") is not None:
+                details.append(f"{interaction['avg_latency_ms']} ms avg")
+            line = f"- this project {arrow} **{interaction.get('service', 'unknown')}** ({', '.join(details)})"
+            operations = interaction.get("operations", [])
+            if operations:
+                line += ": " + ", ".join(f"`{op}`" for op in operations[:3])
+            lines.append(line)
+        return lines
+
+    def _deployment_lines(self, matrix_data: Dict[str, Any]) -> List[str]:
+        """Describe what runs where: images, services and workloads with their code."""
+        deployments = matrix_data.get("project_info", {}).get("deployments", [])
+        kinds = {
+            "image": "image",
+            "compose_service": "compose service",
+            "kubernetes": "Kubernetes",
+            "terraform": "Terraform",
+        }
+        lines = []
+        for deployment in deployments[:20]:  # Limit to prevent token overflow
+            kind = kinds.get(deployment.get("kind"), deployment.get("kind", "unknown"))
+            if deployment.get("resource_type"):
+                kind += f" {deployment['resource_type']}"
+            line = (
+                f"- **{deployment.get('name', 'unknown')}** ({kind}, "
+                f"`{deployment.get('manifest', '')}`)"
+            )
+            files = deployment.get("files", [])
+            if files:
+                # The top directories give the gist without listing every file
+                directories = sorted(
+                    {str(Path(path).parent) for path in files} - {"."}
+                )[:3]
+                where = ", ".join(f"`{d}/`" for d in directories) or f"`{files[0]}`"
+                noun = "file" if len(files) == 1 else "files"
+                line += f": packages {len(files)} {noun} from {where}"
+            elif deployment.get("image"):
+                line += f": runs `{deployment['image']}`"
+            if deployment.get("depends_on"):
+                line += f"; depends on {', '.join(deployment['depends_on'])}"
+            lines.append(line)
+        return lines
+
+    def _build_architecture_context(self, matrix_data: Dict[str, Any]) -> str:
+        """Build context for architecture section from the matrix components."""
+        components = matrix_data.get("project_info", {}).get("components", [])
+        adrs = matrix_data.get("adrs", [])
+        interactions = self._interaction_lines(matrix_data)
+        deployments = self._deployment_lines(matrix_data)
+        if not components:
+            context = self._build_generic_context(matrix_data)
+            if adrs:
+                context += "\n\nArchitecture decisions:\n" + "\n".join(
+                    self._adr_lines(adrs)
+                )
+            if interactions:
+                context += "\n\nRuntime interactions with other services:\n" + "\n".join(
+                    interactions
+                )
+            if deployments:
+                context += "\n\nDeployment units (what runs where):\n" + "\n".join(
+                    deployments
+                )
+            return context
+
+        # Map each file to its component to summarize cross-component edges
+        component_of = {
+            file_path: component.get("name", "unknown")
+            for component in components
+            for file_path in component.get("files", [])
+        }
+        links: Dict[Tuple[str, str], int] = {}
+        for rel in matrix_data.get("relationships", []):
+            source = component_of.get(rel.get("from_file"))
+            target = component_of.get(rel.get("to_file"))
+            if source and target and source != target:
+                links[(source, target)] = links.get((source, target), 0) + 1
+
+        context_parts = [f"Components ({len(components)}):"]
+        for component in components[:15]:  # Limit to prevent token overflow
+            files = component.get("files", [])
+            context_parts.append(
+                f"  - {component.get('name', 'unknown')} "
+                f"({len(files)} files, {component.get('source', 'detected')})"
+            )
+            for file_path in files[:5]:
+                context_parts.append(f"      {file_path}")
+
+        if links:
+            context_parts.append("\nDependencies between components:")
+            for (source, target), count in sorted(
+                links.items(), key=lambda item: -item[1]
+            )[:15]:
+                context_parts.append(f"  - {source} -> {target} ({count} edges)")
+
+        if adrs:
+            context_parts.append("\nArchitecture decisions (link to them by path):")
+            context_parts.extend(self._adr_lines(adrs))
+
+        if interactions:
+            context_parts.append(
+                "\nRuntime interactions with other services (observed, not inferred):"
+            )
+            context_parts.extend(interactions)
+
+        if deployments:
+            context_parts.append("\nDeployment units (what runs where):")
+            context_parts.extend(deployments)
+
+        return "\n".join(context_parts)
+
+    def _hotspot_lines(self, hotspots: Dict[str, Any]) -> List[str]:
+        """List the oversized files and functions from a csd hotspot report."""
+        thresholds = hotspots.get("thresholds", {})
+        lines = [
+            f"Files over {thresholds.get('max_file_tokens')} tokens: "
+            f"{hotspots.get('files_over_limit', 0)}"
+        ]
+        for file_info in hotspots.get("files", []):
+            lines.append(f"- `{file_info['path']}` ({file_info['tokens']} tokens)")
+
+        lines.append(
+            f"\nFunctions over {thresholds.get('max_function_lines')} lines or "
+            f"complexity {thresholds.get('max_function_complexity')}: "
+            f"{hotspots.get('functions_over_limit', 0)}"
+        )
+        for function in hotspots.get("functions", []):
+            complexity = function.get("complexity")
+            detail = f"{function['lines']} lines"
+            if complexity is not None:
+                detail += f", complexity {complexity}"
+            lines.append(
+                f"- `{function['name']}` in `{function['path']}` "
+                f"(line {function['line_start']}, {detail})"
+            )
+        return lines
+
+    def _glossary_terms(self, matrix_data: Dict[str, Any]) -> List[Dict[str, Any]]:
+        """Candidate domain terms csd ranked over identifiers and summaries."""
+        return matrix_data.get("project_info", {}).get("glossary_candidates", [])
+
+    def _build_glossary_context(self, matrix_data: Dict[str, Any]) -> str:
+        """Build context for the glossary section from the candidate terms."""
+        terms = self._glossary_terms(matrix_data)
+        if not terms:
+            return self._build_generic_context(matrix_data)
+
+        context_parts = [
+            "Candidate domain terms, most distinctive first. Define the ones that are "
+            "specific to this project and skip generic programming vocabulary:"
+        ]
+        for term in terms:
+            files = ", ".join(term.get("files", [])[:3])
+            context_parts.append(
+                f"  - {term['term']} ({term.get('occurrences', 0)} uses, e.g. in {files})"
+            )
+        return "\n".join(context_parts)
+
+    def _table_users(self, matrix_data: Dict[str, Any]) -> Dict[str, List[str]]:
+        """Files touching each table, keyed by lowercased table name."""
+        users: Dict[str, List[str]] = {}
+        for access in matrix_data.get("data_model", {}).get("accesses", []):
+            users.setdefault(access["table"].lower(), []).append(access["file"])
+        return users
+
+    def _build_data_dictionary_context(self, matrix_data: Dict[str, Any]) -> str:
+        """Build context for the data dictionary from the tables csd found."""
+        tables = matrix_data.get("data_model", {}).get("tables", [])
+        if not tables:
+            return self._build_generic_context(matrix_data)
+
+        users = self._table_users(matrix_data)
+        context_parts = [
+            "Tables from the project's migrations and ORM models. Explain what each "
+            "one stores and how the code uses it:"
+        ]
+        for table in tables[:30]:  # Limit to prevent token overflow
+            columns = ", ".join(
+                f"{column['name']} {column.get('data_type', '')}".strip()
+                for column in table.get("columns", [])
+            )
+            line = f"  - {table['name']}"
+            if table.get("model"):
+                line += f" (model {table['model']})"
+            line += f": {columns or 'no columns found'}"
+            files = users.get(table["name"].lower(), [])
+            if files:
+                line += f"; used by {', '.join(files[:5])}"
+            context_parts.append(line)
+        return "\n".join(context_parts)
+
+    def _build_hotspots_context(self, matrix_data: Dict[str, Any]) -> str:
+        """Build context for the hotspots section from the csd size report."""
+        hotspots = matrix_data.get("hotspots")
+        if not hotspots:
+            return self._build_generic_context(matrix_data)
+        return "\n".join(self._hotspot_lines(hotspots))
+
     def _build_usage_context(self, matrix_data: Dict[str, Any]) -> str:
         """Build context for usage section."""
         # Similar to API context but focus on main entry points
@@ -483,14 +906,31 @@ This project is licensed under the terms specified in the LICENSE file.
         return "\n".join(context_parts)
 
     def _get_section_prompt(
-        self, section_name: str, plugin_config: Optional[Dict[str, Any]]
+        self,
+        section_name: str,
+        plugin_config: Optional[Dict[str, Any]],
+        language: Optional[str] = None,
     ) -> Optional[str]:
-        """Get custom prompt for a section from plugin config."""
+        """
+        Get custom prompt for a section from plugin config. Prompts written in the
+        target language under `prompts.languages.<code>.section_prompts` take
+        precedence over the shared `prompts.section_prompts`.
+        """
         if not plugin_config:
             return None
 
-        prompts = plugin_config.get("prompts", {}).get("section_prompts", {})
-        return cast(Optional[str], prompts.get(section_name))
+        prompts = plugin_config.get("prompts", {})
+        if language:
+            native = (
+                prompts.get("languages", {})
+                .get(language, {})
+                .get("section_prompts", {})
+                .get(section_name)
+            )
+            if native:
+                return cast(str, native)
+
+        return cast(Optional[str], prompts.get("section_prompts", {}).get(section_name))
 
     def _generate_fallback_content(
         self, section_name: str, matrix_data: Dict[str, Any]
@@ -500,7 +940,9 @@ This project is licensed under the terms specified in the LICENSE file.
         if section_name == "project_overview":
             metadata = matrix_data.get("metadata", {})
             project_name = Path(metadata.get("project_root", "Project")).name
-            languages = ", ".join(metadata.get("plugins_used", ["Multiple languages"]))
+            languages = ", ".join(
+                self._plugin_names(metadata) or ["Multiple languages"]
+            )
             total_files = metadata.get("total_files", 0)
 
             return f"""This project ({project_name}) contains {total_files} files written in {languages}.
@@ -527,7 +969,7 @@ The codebase includes {len(matrix_data.get("external_dependencies", []))} extern
                 return "## Installation\n\nClone this repository and follow the setup instructions for your development environment."
 
         elif section_name == "api_reference":
-            files = matrix_data.get("files", {})
+            files = self._maintained_files(matrix_data)
             public_functions = []
 
             for file_path, file_info in files.items():
@@ -540,12 +982,98 @@ The codebase includes {len(matrix_data.get("external_dependencies", []))} extern
                             f"- `{element.get('name', 'unknown')}` in {file_path}"
                         )
 
+            annotations = self._annotation_lines(matrix_data)
+            notes = ""
+            if annotations:
+                notes = "\n\n### Annotations\n\n" + "\n".join(annotations)
             if public_functions:
-                return "## API Reference\n\nMain public functions:\n\n" + "\n".join(
-                    public_functions[:10]
+                return (
+                    "## API Reference\n\nMain public functions:\n\n"
+                    + "\n".join(public_functions[:10])
+                    + notes
                 )
             else:
-                return "## API Reference\n\nAPI documentation is available in the source code."
+                return (
+                    "## API Reference\n\nAPI documentation is available in the source code."
+                    + notes
+                )
+
+        elif section_name == "architecture":
+            components = matrix_data.get("project_info", {}).get("components", [])
+            adrs = matrix_data.get("adrs", [])
+            subsections = ""
+            if adrs:
+                subsections = "\n\n### Decisions\n\n" + "\n".join(self._adr_lines(adrs))
+            interactions = self._interaction_lines(matrix_data)
+            if interactions:
+                subsections += "\n\n### Runtime Topology\n\n" + "\n".join(interactions)
+            deployments = self._deployment_lines(matrix_data)
+            if deployments:
+                subsections += "\n\n### Deployment\n\n" + "\n".join(deployments)
+            if not components:
+                return (
+                    "## Architecture\n\nNo component structure was detected for this project."
+                    + subsections
+                )
+
+            lines = []
+            for component in components:
+                files = component.get("files", [])
+                lines.append(
+                    f"- **{component.get('name', 'unknown')}** ({len(files)} files)"
+                )
+            return (
+                "## Architecture\n\nMain components:\n\n" + "\n".join(lines) + subsections
+            )
+
+        elif section_name == "glossary":
+            terms = self._glossary_terms(matrix_data)
+            if not terms:
+                return "## Glossary\n\nNo domain terms were found for this project."
+
+            lines = ["| Term | Uses | Found in |", "| --- | --- | --- |"]
+            for term in terms:
+                files = ", ".join(f"`{path}`" for path in term.get("files", [])[:3])
+                lines.append(f"| {term['term']} | {term.get('occurrences', 0)} | {files} |")
+            return "## Glossary\n\n" + "\n".join(lines)
+
+        elif section_name == "data_dictionary":
+            tables = matrix_data.get("data_model", {}).get("tables", [])
+            if not tables:
+                return "## Data Dictionary\n\nNo database tables were found for this project."
+
+            users = self._table_users(matrix_data)
+            parts = []
+            for table in tables:
+                defined_in = ", ".join(f"`{path}`" for path in table.get("defined_in", []))
+                part = f"### {table['name']}\n\n"
+                if table.get("model"):
+                    part += f"Mapped by `{table['model']}`. "
+                part += f"Defined in {defined_in}.\n\n"
+                part += "| Column | Type | Key | Null |\n| --- | --- | --- | --- |\n"
+                for column in table.get("columns", []):
+                    key = "PK" if column.get("primary_key") else ""
+                    null = "NOT NULL" if column.get("not_null") else ""
+                    part += (
+                        f"| {column['name']} | {column.get('data_type', '')} "
+                        f"| {key} | {null} |\n"
+                    )
+                files = users.get(table["name"].lower(), [])
+                if files:
+                    part += "\nUsed by " + ", ".join(f"`{path}`" for path in files[:5])
+                    part += "\n"
+                parts.append(part.rstrip())
+            return "## Data Dictionary\n\n" + "\n\n".join(parts)
+
+        elif section_name == "hotspots":
+            hotspots = matrix_data.get("hotspots")
+            if not hotspots:
+                return "## Hotspots\n\nNo size report is available for this project."
+            if not hotspots.get("files_over_limit") and not hotspots.get(
+                "functions_over_limit"
+            ):
+                return "## Hotspots\n\nNo files or functions exceed the size limits."
+            return "## Hotspots\n\n" + "\n".join(self._hotspot_lines(hotspots))
 
         elif section_name == "usage":
             return """## Usage
@@ -5,9 +5,11 @@ from ._version import __version__
 # Import main classes that plugins will use
 from .base.input import (
     BaseAnalyzer,
+    ContentPreview,
     PluginInput,
     PluginOutput,
     CodeElement,
+    Export,
     Import,
     Relationship,
     ExternalDependency,
@@ -17,28 +19,52 @@ from .base.input import (
 from .base.output import (
     BaseOutputPlugin,
     OutputPluginInput,
+    SectionCache,
     OutputPluginResult,
     calculate_file_metrics,
     extract_dependencies,
+    fill_template_variables,
 )
-from .utils.llm import LLMClient, LLMConfig, SectionProcessor
+from .base.transform import (
+    BaseTransformPlugin,
+    TransformPluginInput,
+    MatrixPatch,
+    TagUpdate,
+    SummaryUpdate,
+)
+from .base.rule import BaseRulePlugin, RulePluginInput, RuleFinding, Suggestion
+from .utils.llm import LLMClient, LLMConfig, SectionProcessor, is_offline
 
 __all__ = [
     "__version__",
     "BaseAnalyzer",
+    "ContentPreview",
     "PluginInput",
     "PluginOutput",
     "BaseOutputPlugin",
     "OutputPluginInput",
+    "SectionCache",
     "OutputPluginResult",
+    "BaseTransformPlugin",
+    "TransformPluginInput",
+    "MatrixPatch",
+    "TagUpdate",
+    "SummaryUpdate",
+    "BaseRulePlugin",
+    "RulePluginInput",
+    "RuleFinding",
+    "Suggestion",
     "LLMClient",
     "LLMConfig",
     "SectionProcessor",
+    "is_offline",
     "calculate_file_metrics",
     "extract_dependencies",
+    "fill_template_variables",
     "calculate_complexity",
     "detect_import_type",
     "CodeElement",
+    "Export",
     "Import",
     "Relationship",
     "ExternalDependency",
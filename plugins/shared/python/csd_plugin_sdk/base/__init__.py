@@ -2,9 +2,11 @@
 
 from .input import (
     BaseAnalyzer,
+    ContentPreview,
     PluginInput,
     PluginOutput,
     CodeElement,
+    Export,
     Import,
     Relationship,
     ExternalDependency,
@@ -14,23 +16,44 @@ from .input import (
 from .output import (
     BaseOutputPlugin,
     OutputPluginInput,
+    SectionCache,
     OutputPluginResult,
     calculate_file_metrics,
     extract_dependencies,
 )
+from .transform import (
+    BaseTransformPlugin,
+    TransformPluginInput,
+    MatrixPatch,
+    TagUpdate,
+    SummaryUpdate,
+)
+from .rule import BaseRulePlugin, RulePluginInput, RuleFinding, Suggestion
 
 __all__ = [
     "BaseAnalyzer",
+    "ContentPreview",
     "PluginInput",
     "PluginOutput",
     "BaseOutputPlugin",
     "OutputPluginInput",
+    "SectionCache",
     "OutputPluginResult",
+    "BaseTransformPlugin",
+    "TransformPluginInput",
+    "MatrixPatch",
+    "TagUpdate",
+    "SummaryUpdate",
+    "BaseRulePlugin",
+    "RulePluginInput",
+    "RuleFinding",
+    "Suggestion",
     "calculate_file_metrics",
     "extract_dependencies",
     "calculate_complexity",
     "detect_import_type",
     "CodeElement",
+    "Export",
     "Import",
     "Relationship",
     "ExternalDependency",
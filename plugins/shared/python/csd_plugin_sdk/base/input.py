@@ -5,6 +5,9 @@ All language plugins should inherit from this class.
 """
 
 import io
+import os
+import socket
+import struct
 import typing
 import json
 import sys
@@ -15,6 +18,13 @@ from pathlib import Path
 from typing import Dict, List, Optional, Any, Tuple
 from dataclasses import dataclass, asdict
 
+# Set to "1" when the core keeps the plugin running and sends one message per line
+PERSISTENT_ENV = "CSD_PERSISTENT"
+
+# Where to connect for length-prefixed JSON frames instead, and the token to send first
+SOCKET_ENV = "CSD_SOCKET"
+SOCKET_TOKEN_ENV = "CSD_SOCKET_TOKEN"
+
 
 @dataclass
 class CodeElement:
@@ -30,6 +40,8 @@ class CodeElement:
     calls: List[str] = None
     metadata: Dict[str, Any] = None
     tokens: Optional[int] = None  # NEW: Token count for this element
+    qualified_name: Optional[str] = None  # e.g. "Config.load" for a method
+    parent: Optional[str] = None  # Qualified name of the enclosing class/impl
 
     def __post_init__(self):
         """Initialize default values for calls and metadata."""
@@ -55,6 +67,21 @@ class Import:
             self.items = []
 
 
+@dataclass
+class Export:
+    """Representation of a name a file exports."""
+
+    name: str
+    kind: Optional[str] = None  # e.g. "function", "class"
+    alias: Optional[str] = None  # Name it is exported as, when renamed
+    source: Optional[str] = None  # File a re-exported name is defined in
+
+    @property
+    def exported_name(self) -> str:
+        """The name importers use."""
+        return self.alias or self.name
+
+
 @dataclass
 class Relationship:
     """Representation of a relationship between files."""
@@ -65,6 +92,8 @@ class Relationship:
     details: str
     line_number: Optional[int] = None
     strength: float = 1.0
+    from_element: Optional[str] = None  # e.g. calling function name
+    to_element: Optional[str] = None  # e.g. called function name
 
 
 @dataclass
@@ -86,7 +115,7 @@ class PluginOutput:
     file_hash: str
     elements: List[CodeElement]
     imports: List[Import]
-    exports: List[str]
+    exports: List[Export]
     relationships: List[Relationship]
     external_dependencies: List[ExternalDependency]
     file_summary: Optional[str] = None
@@ -108,8 +137,33 @@ class PluginInput:
     relative_path: str
     content: str
     project_root: str
+    # This scan's scratch directory; it is removed once the scan finishes
     cache_dir: str
     plugin_config: Optional[Dict[str, Any]] = None
+    # Set instead of `content` when the core passes the file by path
+    content_path: Optional[str] = None
+    # `content` was cut down to the configured scanning.max_file_tokens
+    truncated: bool = False
+    # Digest of the project's manifests (packages, dependencies, source_roots); only
+    # sent when the plugin sets wants_project_context
+    project: Optional[Dict[str, Any]] = None
+
+
+@dataclass
+class ContentPreview:
+    """What the core shows a plugin in can_analyze."""
+
+    content_preview: str
+    # A chunk from the middle of files larger than the preview
+    content_sample: Optional[str] = None
+    file_size: Optional[int] = None
+    extension: Optional[str] = None
+
+    def text(self) -> str:
+        """Head and middle sample joined, for indicator matching."""
+        if self.content_sample:
+            return f"{self.content_preview}\n{self.content_sample}"
+        return self.content_preview
 
 
 class BaseAnalyzer(ABC):
@@ -122,6 +176,11 @@ class BaseAnalyzer(ABC):
         self.plugin_type = "input"
         self.supported_extensions = []
         self.supported_filenames = []
+        # JSON Schema for this plugin's `config` in .csdrc.yaml, checked by `csd validate`
+        # and before a scan
+        self.config_schema: Optional[Dict[str, Any]] = None
+        # Ask the core for PluginInput.project
+        self.wants_project_context = False
 
     @abstractmethod
     def can_analyze(self, file_path: str, content_preview: str) -> Tuple[bool, float]:
@@ -130,7 +189,8 @@ class BaseAnalyzer(ABC):
 
         Args:
             file_path: Path to the file.
-            content_preview: First ~500 characters of the file.
+            content_preview: Start of the file (without a BOM), followed by a
+                sample from the middle of larger files.
 
         Returns:
             Tuple containing a boolean indicating if analysis is possible
@@ -138,6 +198,17 @@ class BaseAnalyzer(ABC):
         """
         pass
 
+    def can_analyze_preview(
+        self, file_path: str, preview: ContentPreview
+    ) -> Tuple[bool, float]:
+        """
+        Check if this plugin can analyze the given file, with the full preview.
+
+        Override to use the file size or to treat the head and the middle
+        sample separately; the default passes both to can_analyze.
+        """
+        return self.can_analyze(file_path, preview.text())
+
     @abstractmethod
     def analyze(self, input_data: PluginInput) -> PluginOutput:
         """
@@ -153,7 +224,7 @@ class BaseAnalyzer(ABC):
 
     def get_info(self) -> Dict[str, Any]:
         """Return plugin information with type identification."""
-        return {
+        info = {
             "name": self.name,
             "version": self.version,
             "plugin_type": self.plugin_type,
@@ -161,7 +232,13 @@ class BaseAnalyzer(ABC):
             "supported_filenames": self.supported_filenames,
             "supported_output_types": None,
             "supported_formats": None,
+            "capabilities": ["content_path", "analyze_batch", "persistent", "socket"],
         }
+        if self.wants_project_context:
+            info["capabilities"].append("project_context")
+        if self.config_schema is not None:
+            info["config_schema"] = self.config_schema
+        return info
 
     def _generate_cache_filename(self, input_data: PluginInput) -> str:
         """Generate a unique cache filename for this analysis."""
@@ -182,8 +259,11 @@ class BaseAnalyzer(ABC):
         # Convert dataclass to dict for JSON serialization
         result_dict = asdict(result)
 
-        with open(cache_path, "w", encoding="utf-8") as f:
+        # Write to a temp file and rename so the core never reads a partial result
+        temp_path = cache_path.with_name(f".{cache_path.name}.{os.getpid()}.tmp")
+        with open(temp_path, "w", encoding="utf-8") as f:
             json.dump(result_dict, f, indent=2, ensure_ascii=False)
+        os.replace(temp_path, cache_path)
         return cache_filename
 
     def run(self):
@@ -192,6 +272,13 @@ class BaseAnalyzer(ABC):
             typing.cast(io.TextIOWrapper, sys.stdout).reconfigure(line_buffering=True)
             typing.cast(io.TextIOWrapper, sys.stderr).reconfigure(line_buffering=True)
 
+            if os.environ.get(SOCKET_ENV):
+                self._serve_socket(os.environ[SOCKET_ENV])
+                return
+            if os.environ.get(PERSISTENT_ENV) == "1":
+                self._serve()
+                return
+
             try:
                 input_data = sys.stdin.read().strip()
             except Exception as e:
@@ -223,14 +310,7 @@ class BaseAnalyzer(ABC):
                 self._send_error(f"Invalid JSON: {e}")
                 return
 
-            if message.get("type") == "can_analyze":
-                self._handle_can_analyze(message)
-            elif message.get("type") == "analyze":
-                self._handle_analyze(message)
-            elif message.get("type") == "get_info":
-                self._handle_get_info()
-            else:
-                self._send_error(f"Unknown message type: {message.get('type')}")
+            self._dispatch(message)
 
         except Exception as e:
             import traceback
@@ -238,13 +318,87 @@ class BaseAnalyzer(ABC):
             error_details = traceback.format_exc()
             self._send_error(f"Plugin error: {e}", error_details)
 
+    def _serve(self):
+        """Answer one JSON message per stdin line until stdin closes.
+
+        The core's plugin pool keeps the process running between messages this way;
+        every message gets exactly one response line.
+        """
+        for line in sys.stdin:
+            line = line.strip()
+            if not line:
+                continue
+            try:
+                message = json.loads(line)
+            except json.JSONDecodeError as e:
+                self._send_error(f"Invalid JSON: {e}")
+                continue
+            try:
+                self._dispatch(message)
+            except Exception as e:
+                import traceback
+
+                self._send_error(f"Plugin error: {e}", traceback.format_exc())
+
+    def _serve_socket(self, address: str):
+        """Answer framed messages over the socket at `address` until it closes.
+
+        `address` is "unix:<path>" or "tcp:<host>:<port>". Each frame is a big-endian
+        4-byte length and that many bytes of JSON; the first one sent is the token.
+        Responses go back as frames, so stdout is free for the plugin's own output.
+        """
+        kind, _, target = address.partition(":")
+        if kind == "unix":
+            conn = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+            conn.connect(target)
+        else:
+            host, _, port = target.rpartition(":")
+            conn = socket.create_connection((host, int(port)))
+
+        with conn:
+            self._socket = conn
+            _write_frame(conn, {"token": os.environ.get(SOCKET_TOKEN_ENV, "")})
+            while True:
+                frame = _read_frame(conn)
+                if frame is None:
+                    return
+                try:
+                    message = json.loads(frame)
+                except json.JSONDecodeError as e:
+                    self._send_error(f"Invalid JSON: {e}")
+                    continue
+                try:
+                    self._dispatch(message)
+                except Exception as e:
+                    import traceback
+
+                    self._send_error(f"Plugin error: {e}", traceback.format_exc())
+
+    def _dispatch(self, message: Dict[str, Any]):
+        """Send the response to one message."""
+        if message.get("type") == "can_analyze":
+            self._handle_can_analyze(message)
+        elif message.get("type") == "analyze":
+            self._handle_analyze(message)
+        elif message.get("type") == "analyze_batch":
+            self._handle_analyze_batch(message)
+        elif message.get("type") == "get_info":
+            self._handle_get_info()
+        else:
+            self._send_error(f"Unknown message type: {message.get('type')}")
+
     def _handle_can_analyze(self, message: Dict[str, Any]):
         """Handle can_analyze request."""
         try:
             file_path = message["file_path"]
-            content_preview = message["content_preview"]
+            preview = ContentPreview(
+                content_preview=message["content_preview"],
+                content_sample=message.get("content_sample"),
+                file_size=message.get("file_size"),
+                extension=message.get("extension"),
+            )
 
-            can_analyze, confidence = self.can_analyze(file_path, content_preview)
+            can_analyze, confidence = self.can_analyze_preview(file_path, preview)
 
             response = {
                 "status": "can_analyze",
@@ -257,28 +411,35 @@ class BaseAnalyzer(ABC):
         except Exception as e:
             self._send_error(f"Error in can_analyze: {e}")
 
-    def _handle_analyze(self, message: Dict[str, Any]):
-        """Handle analyze request."""
-        try:
-            input_dict = message["input"]
-            input_data = PluginInput(**input_dict)
+    def _analyze_to_cache(self, input_dict: Dict[str, Any]) -> Tuple[str, int]:
+        """Analyze one input and return its cache filename and processing time."""
+        input_data = PluginInput(**input_dict)
+        if input_data.content_path and not input_data.content:
+            with open(input_data.content_path, encoding="utf-8", errors="replace") as f:
+                input_data.content = f.read()
 
-            start_time = time.time()
-            result = self.analyze(input_data)
-            end_time = time.time()
+        start_time = time.time()
+        result = self.analyze(input_data)
+        end_time = time.time()
 
-            result.processing_time_ms = int((end_time - start_time) * 1000)
-            result.plugin_version = self.version
+        result.processing_time_ms = int((end_time - start_time) * 1000)
+        result.plugin_version = self.version
 
-            cache_filename = self._generate_cache_filename(input_data)
-            actual_filename = self._write_to_cache(
-                result, cache_filename, input_data.cache_dir
-            )
+        cache_filename = self._generate_cache_filename(input_data)
+        actual_filename = self._write_to_cache(
+            result, cache_filename, input_data.cache_dir
+        )
+        return actual_filename, result.processing_time_ms
+
+    def _handle_analyze(self, message: Dict[str, Any]):
+        """Handle analyze request."""
+        try:
+            cache_file, processing_time_ms = self._analyze_to_cache(message["input"])
 
             response = {
                 "status": "success",
-                "cache_file": actual_filename,
-                "processing_time_ms": result.processing_time_ms,
+                "cache_file": cache_file,
+                "processing_time_ms": processing_time_ms,
             }
 
             self._send_response(response)
@@ -286,6 +447,25 @@ class BaseAnalyzer(ABC):
         except Exception as e:
             self._send_error(f"Error in analyze: {e}")
 
+    def _handle_analyze_batch(self, message: Dict[str, Any]):
+        """Handle analyze_batch request; one failing file doesn't fail the batch."""
+        try:
+            results = []
+            for input_dict in message["inputs"]:
+                result = {"relative_path": input_dict.get("relative_path", "")}
+                try:
+                    cache_file, processing_time_ms = self._analyze_to_cache(input_dict)
+                    result["cache_file"] = cache_file
+                    result["processing_time_ms"] = processing_time_ms
+                except Exception as e:
+                    result["error"] = str(e)
+                results.append(result)
+
+            self._send_response({"status": "batch_success", "results": results})
+
+        except Exception as e:
+            self._send_error(f"Error in analyze_batch: {e}")
+
     def _handle_get_info(self):
         """Handle get_info request."""
         try:
@@ -298,7 +478,11 @@ class BaseAnalyzer(ABC):
             self._send_error(f"Error in get_info: {e}")
 
     def _send_response(self, response: Dict[str, Any]):
-        """Send a response to stdout."""
+        """Send a response to stdout, or as a frame when serving a socket."""
+        conn = getattr(self, "_socket", None)
+        if conn is not None:
+            _write_frame(conn, response)
+            return
         json_response = json.dumps(response)
         print(json_response)
         sys.stdout.flush()
@@ -309,6 +493,31 @@ class BaseAnalyzer(ABC):
         self._send_response(response)
 
 
+def _write_frame(conn: socket.socket, payload: Dict[str, Any]):
+    """Send `payload` as a length-prefixed JSON frame."""
+    data = json.dumps(payload).encode("utf-8")
+    conn.sendall(struct.pack(">I", len(data)) + data)
+
+
+def _read_frame(conn: socket.socket) -> Optional[bytes]:
+    """Receive one frame, or None once the core has closed the connection."""
+    header = _read_exact(conn, 4)
+    if header is None:
+        return None
+    (length,) = struct.unpack(">I", header)
+    return _read_exact(conn, length)
+
+
+def _read_exact(conn: socket.socket, size: int) -> Optional[bytes]:
+    data = b""
+    while len(data) < size:
+        chunk = conn.recv(size - len(data))
+        if not chunk:
+            return None
+        data += chunk
+    return data
+
+
 def calculate_complexity(code: str, element_start: int, element_end: int) -> int:
     """
     Calculate a simple complexity score for a code element.
@@ -347,7 +556,12 @@ def calculate_complexity(code: str, element_start: int, element_end: int) -> int
     return max(1, complexity)
 
 
-def detect_import_type(module_name: str, project_root: str, file_path: str) -> str:
+def detect_import_type(
+    module_name: str,
+    project_root: str,
+    file_path: str,
+    project: Optional[Dict[str, Any]] = None,
+) -> str:
     """
     Detect the type of import based on the module name and project structure.
 
@@ -355,6 +569,8 @@ def detect_import_type(module_name: str, project_root: str, file_path: str) -> s
         module_name: The name of the module being imported.
         project_root: The root directory of the project.
         file_path: The path to the current file.
+        project: PluginInput.project, whose source roots and Python packages
+            also count as local.
 
     Returns:
         A string indicating the type: 'relative', 'local', 'standard', or 'third_party'.
@@ -365,11 +581,17 @@ def detect_import_type(module_name: str, project_root: str, file_path: str) -> s
     project_path = Path(project_root)
     file_dir = Path(file_path).parent
 
+    search_dirs = [project_path, file_dir]
+    if project:
+        search_dirs += [project_path / r for r in project.get("source_roots", [])]
+    module_path = module_name.replace(".", "/")
     potential_paths = [
-        project_path / f"{module_name.replace('.', '/')}.py",
-        project_path / f"{module_name.replace('.', '/')}/__init__.py",
-        file_dir / f"{module_name.replace('.', '/')}.py",
-        file_dir / f"{module_name.replace('.', '/')}/__init__.py",
+        path
+        for directory in search_dirs
+        for path in (
+            directory / f"{module_path}.py",
+            directory / module_path / "__init__.py",
+        )
     ]
 
     for path in potential_paths:
@@ -411,6 +633,16 @@ def detect_import_type(module_name: str, project_root: str, file_path: str) -> s
     }
 
     root_module = module_name.split(".")[0]
+    if project:
+        # Distribution names are normalized to `my-package`; the module is `my_package`
+        local_packages = {
+            package["name"].replace("-", "_")
+            for package in project.get("packages", [])
+            if package.get("ecosystem") == "python"
+        }
+        if root_module.lower() in local_packages:
+            return "local"
+
     if root_module in standard_modules:
         return "standard"
 
@@ -4,6 +4,8 @@ Base class for CSD output plugins.
 All output plugins should inherit from this class.
 """
 import io
+import os
+import re
 import typing
 import json
 import sys
@@ -12,7 +14,44 @@ import hashlib
 from abc import ABC, abstractmethod
 from pathlib import Path
 from typing import Dict, List, Optional, Any, Tuple
-from dataclasses import dataclass, asdict
+from dataclasses import dataclass, asdict, field
+
+from .transform import MatrixPatch
+
+
+@dataclass
+class SectionCache:
+    """
+    Finished sections kept by the core across interrupted runs. Store each section
+    once it is complete; on `csd docs --resume` the sections listed in `completed`
+    can be loaded instead of regenerated.
+    """
+
+    dir: str
+    resume: bool = False
+    completed: List[str] = field(default_factory=list)
+
+    @staticmethod
+    def _stem(section: str) -> str:
+        return re.sub(r"[^A-Za-z0-9_-]", "_", section)
+
+    def load(self, section: str) -> Optional[str]:
+        """Return the cached content of a completed section, if any."""
+        stem = self._stem(section)
+        if stem not in self.completed:
+            return None
+        try:
+            return (Path(self.dir) / f"{stem}.md").read_text(encoding="utf-8")
+        except OSError:
+            return None
+
+    def store(self, section: str, content: str):
+        """Cache a finished section; the marker is written last."""
+        stem = self._stem(section)
+        cache_dir = Path(self.dir)
+        cache_dir.mkdir(parents=True, exist_ok=True)
+        (cache_dir / f"{stem}.md").write_text(content, encoding="utf-8")
+        (cache_dir / f"{stem}.done").touch()
 
 
 @dataclass
@@ -25,11 +64,16 @@ class OutputPluginInput:
     cache_dir: str
     plugin_config: Optional[Dict[str, Any]] = None
     format_options: Dict[str, Any] = None
+    section_cache: Optional[SectionCache] = None
+    # paths/globs/tags/depth the matrix was filtered by; None for the whole project
+    scope: Optional[Dict[str, Any]] = None
 
     def __post_init__(self):
         """Initialize default values after dataclass initialization."""
         if self.format_options is None:
             self.format_options = {}
+        if isinstance(self.section_cache, dict):
+            self.section_cache = SectionCache(**self.section_cache)
 
 
 @dataclass
@@ -58,6 +102,9 @@ class OutputPluginResult:
     outputs: List[GeneratedOutput]
     processing_time_ms: int = 0
     metadata: Dict[str, Any] = None
+    # Matrix changes worked out while generating (e.g. summaries written for
+    # undocumented code); the core validates them and saves them into the matrix
+    patch: Optional[MatrixPatch] = None
 
     def __post_init__(self):
         """Initialize default metadata if not provided."""
@@ -75,6 +122,8 @@ class BaseOutputPlugin(ABC):
         self.plugin_type = "output"
         self.supported_output_types = []
         self.supported_formats = []
+        # JSON Schema for this plugin's `config` in .csdrc.yaml
+        self.config_schema: Optional[Dict[str, Any]] = None
 
     @abstractmethod
     def can_generate(self, output_type: str, format: str) -> Tuple[bool, float]:
@@ -105,7 +154,7 @@ class BaseOutputPlugin(ABC):
 
     def get_info(self) -> Dict[str, Any]:
         """Return plugin information."""
-        return {
+        info = {
             "name": self.name,
             "version": self.version,
             "plugin_type": self.plugin_type,
@@ -114,6 +163,31 @@ class BaseOutputPlugin(ABC):
             "supported_output_types": self.supported_output_types,
             "supported_formats": self.supported_formats,
         }
+        if self.config_schema is not None:
+            info["config_schema"] = self.config_schema
+        return info
+
+    def report_progress(
+        self, completed: int, total: Optional[int] = None, message: Optional[str] = None
+    ):
+        """
+        Report progress to the core, which extends its timeout while progress keeps
+        advancing and shows it instead of elapsed-time messages. A no-op when the
+        core didn't provide a progress file.
+        """
+        progress_path = os.environ.get("CSD_PROGRESS_FILE")
+        if not progress_path:
+            return
+
+        progress = {"completed": completed, "total": total, "message": message}
+        temp_path = f"{progress_path}.tmp"
+        try:
+            with open(temp_path, "w", encoding="utf-8") as f:
+                json.dump(progress, f)
+            os.replace(temp_path, progress_path)
+        except OSError:
+            # Progress is advisory; never fail generation over it
+            pass
 
     def _generate_output_filename(
         self, base_name: str, format: str, output_dir: str
@@ -189,6 +263,17 @@ class BaseOutputPlugin(ABC):
         """Ensure the output directory exists."""
         Path(output_dir).mkdir(parents=True, exist_ok=True)
 
+    def _fill_template_variables(
+        self, content: str, input_data: OutputPluginInput
+    ) -> str:
+        """Replace `{{ git.branch }}`-style variables with format_options values.
+
+        Variables name a path into format_options, e.g. `git.tag` or `language`.
+        Unknown variables are left as written so templates stay readable when
+        metadata (such as git outside a repository) is missing.
+        """
+        return fill_template_variables(content, input_data.format_options)
+
     def run(self):
         """Main entry point for plugin execution."""
         try:
@@ -306,6 +391,25 @@ class BaseOutputPlugin(ABC):
         self._send_response(response)
 
 
+_TEMPLATE_VARIABLE = re.compile(r"\{\{\s*([A-Za-z_][\w.]*)\s*\}\}")
+
+
+def fill_template_variables(content: str, variables: Dict[str, Any]) -> str:
+    """Replace `{{ a.b }}` with `variables["a"]["b"]` where it is a plain value."""
+
+    def replace(match: "re.Match[str]") -> str:
+        value: Any = variables
+        for key in match.group(1).split("."):
+            if not isinstance(value, dict) or key not in value:
+                return match.group(0)
+            value = value[key]
+        if value is None or isinstance(value, (dict, list)):
+            return match.group(0)
+        return str(value)
+
+    return _TEMPLATE_VARIABLE.sub(replace, content)
+
+
 def calculate_file_metrics(matrix_data: Dict[str, Any]) -> Dict[str, Any]:
     """
     Calculate basic file-level metrics from matrix data.
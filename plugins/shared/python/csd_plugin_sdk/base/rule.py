@@ -0,0 +1,165 @@
+#!/usr/bin/env python3
+"""
+Base class for CSD rule plugins.
+Rule plugins are custom quality checks: they read the project matrix (or just
+the files in their configured scope) and return findings that `csd quality`
+reports alongside its own checks.
+"""
+import io
+import typing
+import json
+import sys
+import time
+from abc import ABC, abstractmethod
+from typing import Dict, List, Optional, Any
+from dataclasses import dataclass, asdict, field
+
+SEVERITIES = ("info", "warning", "error")
+
+
+@dataclass
+class RulePluginInput:
+    """Input data for rule plugins."""
+
+    matrix_path: str
+    project_root: str
+    cache_dir: str
+    plugin_config: Optional[Dict[str, Any]] = None
+
+
+@dataclass
+class Suggestion:
+    """A proposed fix, with an optional action a tool could apply.
+
+    Actions are dicts with a "kind" of move_file (from, to), split_file (file,
+    into), split_function (file, function, into) or move_element (file, element,
+    to); paths are relative to the project root.
+    """
+
+    summary: str
+    action: Optional[Dict[str, Any]] = None
+
+
+@dataclass
+class RuleFinding:
+    """A problem found on a file, or on one of its elements."""
+
+    rule: str
+    message: str
+    file: str
+    severity: str = "warning"
+    element: Optional[str] = None
+    line: Optional[int] = None
+    suggestions: List[Suggestion] = field(default_factory=list)
+
+
+class BaseRulePlugin(ABC):
+    """Base class for all CSD rule plugins."""
+
+    def __init__(self):
+        """Initialize the base rule plugin."""
+        self.name = self.__class__.__name__
+        self.version = "1.0.0"
+        self.plugin_type = "rule"
+        # JSON Schema for this plugin's `config` in .csdrc.yaml
+        self.config_schema: Optional[Dict[str, Any]] = None
+
+    @abstractmethod
+    def check(
+        self, input_data: RulePluginInput, matrix: Dict[str, Any]
+    ) -> List[RuleFinding]:
+        """
+        Check the project matrix.
+
+        Args:
+            input_data: Rule input containing the matrix path and configuration
+            matrix: The parsed project matrix
+
+        Returns:
+            Findings, with files relative to the project root
+        """
+        pass
+
+    def get_info(self) -> Dict[str, Any]:
+        """Return plugin information."""
+        info = {
+            "name": self.name,
+            "version": self.version,
+            "plugin_type": self.plugin_type,
+            "supported_extensions": [],
+            "supported_filenames": [],
+            "supported_output_types": None,
+            "supported_formats": None,
+        }
+        if self.config_schema is not None:
+            info["config_schema"] = self.config_schema
+        return info
+
+    def run(self):
+        """Main entry point for plugin execution."""
+        try:
+            typing.cast(io.TextIOWrapper, sys.stdout).reconfigure(line_buffering=True)
+            typing.cast(io.TextIOWrapper, sys.stderr).reconfigure(line_buffering=True)
+
+            input_data = sys.stdin.read().strip()
+            if not input_data:
+                self._send_error("No input received")
+                return
+
+            try:
+                message = json.loads(input_data)
+            except json.JSONDecodeError as e:
+                self._send_error(f"Invalid JSON: {e}")
+                return
+
+            if message.get("type") == "check":
+                self._handle_check(message)
+            elif message.get("type") == "get_info":
+                self._send_response({"status": "info", **self.get_info()})
+            else:
+                self._send_error(f"Unknown message type: {message.get('type')}")
+
+        except Exception as e:
+            import traceback
+
+            error_details = traceback.format_exc()
+            self._send_error(f"Plugin error: {e}", error_details)
+
+    def _handle_check(self, message: Dict[str, Any]):
+        """Handle check request."""
+        try:
+            input_data = RulePluginInput(**message["input"])
+            with open(input_data.matrix_path, "r", encoding="utf-8") as f:
+                matrix = json.load(f)
+
+            start_time = time.time()
+            findings = self.check(input_data, matrix)
+            end_time = time.time()
+
+            for finding in findings:
+                if finding.severity not in SEVERITIES:
+                    raise ValueError(
+                        f"Finding for {finding.file} has severity "
+                        f"'{finding.severity}' (expected {', '.join(SEVERITIES)})"
+                    )
+
+            response = {
+                "status": "check_success",
+                "findings": [asdict(finding) for finding in findings],
+                "processing_time_ms": int((end_time - start_time) * 1000),
+            }
+
+            self._send_response(response)
+
+        except Exception as e:
+            self._send_error(f"Error in check: {e}")
+
+    def _send_response(self, response: Dict[str, Any]):
+        """Send a response to stdout."""
+        print(json.dumps(response))
+        sys.stdout.flush()
+
+    def _send_error(self, message: str, details: Optional[str] = None):
+        """Send an error response."""
+        response = {"status": "error", "message": message, "details": details}
+        self._send_response(response)
@@ -0,0 +1,158 @@
+#!/usr/bin/env python3
+"""
+Base class for CSD transform plugins.
+Transform plugins read the project matrix after a scan and return a patch
+(added relationships, tags, summaries) that the core applies before output
+plugins run.
+"""
+import io
+import typing
+import json
+import sys
+import time
+from abc import ABC, abstractmethod
+from typing import Dict, List, Optional, Any
+from dataclasses import dataclass, field, asdict
+
+from .input import Relationship
+
+
+@dataclass
+class TransformPluginInput:
+    """Input data for transform plugins."""
+
+    matrix_path: str
+    project_root: str
+    cache_dir: str
+    plugin_config: Optional[Dict[str, Any]] = None
+
+
+@dataclass
+class TagUpdate:
+    """Tags to add to a file, by project-relative path."""
+
+    file: str
+    tags: List[str]
+
+
+@dataclass
+class SummaryUpdate:
+    """Replacement summary for a file, or one of its elements."""
+
+    file: str
+    summary: str
+    element: Optional[str] = None
+
+
+@dataclass
+class MatrixPatch:
+    """Modifications for the core to apply to the matrix."""
+
+    relationships: List[Relationship] = field(default_factory=list)
+    tags: List[TagUpdate] = field(default_factory=list)
+    summaries: List[SummaryUpdate] = field(default_factory=list)
+
+
+class BaseTransformPlugin(ABC):
+    """Base class for all CSD transform plugins."""
+
+    def __init__(self):
+        """Initialize the base transform plugin."""
+        self.name = self.__class__.__name__
+        self.version = "1.0.0"
+        self.plugin_type = "transform"
+        # JSON Schema for this plugin's `config` in .csdrc.yaml
+        self.config_schema: Optional[Dict[str, Any]] = None
+
+    @abstractmethod
+    def transform(
+        self, input_data: TransformPluginInput, matrix: Dict[str, Any]
+    ) -> MatrixPatch:
+        """
+        Compute modifications to the project matrix.
+
+        Args:
+            input_data: Transform input containing the matrix path and configuration
+            matrix: The parsed project matrix
+
+        Returns:
+            MatrixPatch describing what to add or update
+        """
+        pass
+
+    def get_info(self) -> Dict[str, Any]:
+        """Return plugin information."""
+        info = {
+            "name": self.name,
+            "version": self.version,
+            "plugin_type": self.plugin_type,
+            "supported_extensions": [],
+            "supported_filenames": [],
+            "supported_output_types": None,
+            "supported_formats": None,
+        }
+        if self.config_schema is not None:
+            info["config_schema"] = self.config_schema
+        return info
+
+    def run(self):
+        """Main entry point for plugin execution."""
+        try:
+            typing.cast(io.TextIOWrapper, sys.stdout).reconfigure(line_buffering=True)
+            typing.cast(io.TextIOWrapper, sys.stderr).reconfigure(line_buffering=True)
+
+            input_data = sys.stdin.read().strip()
+            if not input_data:
+                self._send_error("No input received")
+                return
+
+            try:
+                message = json.loads(input_data)
+            except json.JSONDecodeError as e:
+                self._send_error(f"Invalid JSON: {e}")
+                return
+
+            if message.get("type") == "transform":
+                self._handle_transform(message)
+            elif message.get("type") == "get_info":
+                self._send_response({"status": "info", **self.get_info()})
+            else:
+                self._send_error(f"Unknown message type: {message.get('type')}")
+
+        except Exception as e:
+            import traceback
+
+            error_details = traceback.format_exc()
+            self._send_error(f"Plugin error: {e}", error_details)
+
+    def _handle_transform(self, message: Dict[str, Any]):
+        """Handle transform request."""
+        try:
+            input_data = TransformPluginInput(**message["input"])
+            with open(input_data.matrix_path, "r", encoding="utf-8") as f:
+                matrix = json.load(f)
+
+            start_time = time.time()
+            patch = self.transform(input_data, matrix)
+            end_time = time.time()
+
+            response = {
+                "status": "transform_success",
+                "patch": asdict(patch),
+                "processing_time_ms": int((end_time - start_time) * 1000),
+            }
+
+            self._send_response(response)
+
+        except Exception as e:
+            self._send_error(f"Error in transform: {e}")
+
+    def _send_response(self, response: Dict[str, Any]):
+        """Send a response to stdout."""
+        print(json.dumps(response))
+        sys.stdout.flush()
+
+    def _send_error(self, message: str, details: Optional[str] = None):
+        """Send an error response."""
+        response = {"status": "error", "message": message, "details": details}
+        self._send_response(response)
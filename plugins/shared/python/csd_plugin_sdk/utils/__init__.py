@@ -1,5 +1,5 @@
 """Utility functions for CSD plugins."""
 
-from .llm import LLMClient, LLMConfig, SectionProcessor
+from .llm import LLMClient, LLMConfig, SectionProcessor, is_offline
 
-__all__ = ["LLMClient", "LLMConfig", "SectionProcessor"]
+__all__ = ["LLMClient", "LLMConfig", "SectionProcessor", "is_offline"]
@@ -6,10 +6,35 @@ Provides shared LLM client functionality and token management.
 
 import aiohttp
 import asyncio
+import ipaddress
 import logging
+import os
 import time
 from typing import Dict, Any, Optional, List, cast
 from dataclasses import dataclass
+from urllib.parse import urlsplit
+
+# Set to "1" by csd in offline mode; only endpoints on this machine may be used
+OFFLINE_ENV = "CSD_OFFLINE"
+
+
+def is_offline() -> bool:
+    """Whether csd runs in offline mode."""
+    return os.environ.get(OFFLINE_ENV) == "1"
+
+
+def is_local_url(url: str) -> bool:
+    """Whether a URL points at localhost, a loopback address or a Unix socket."""
+    parts = urlsplit(url)
+    if parts.scheme == "unix" or parts.scheme.endswith("+unix"):
+        return True
+    host = (parts.hostname or "").lower()
+    if host == "localhost" or host.endswith(".localhost"):
+        return True
+    try:
+        return ipaddress.ip_address(host).is_loopback
+    except ValueError:
+        return False
 
 
 @dataclass
@@ -98,6 +123,11 @@ class LLMClient:
     """Client for interacting with LLM APIs."""
 
     def __init__(self, config: LLMConfig):
+        if is_offline() and not is_local_url(config.base_url):
+            raise ValueError(
+                f"Offline mode forbids network access: LLM endpoint {config.base_url} "
+                "is not a local provider"
+            )
         self.config = config
         self.token_manager = TokenManager(config.model)
         self.logger = logging.getLogger(__name__)
@@ -286,10 +316,16 @@ class SectionProcessor:
         section: Dict[str, Any],
         context: str,
         section_prompt: Optional[str] = None,
+        language: Optional[str] = None,
     ) -> str:
-        """Enhance a single section with LLM."""
+        """Enhance a single section with LLM, writing in `language` when given."""
         if section_prompt is None:
             section_prompt = self._get_default_prompt(section["name"])
+        if language:
+            section_prompt = (
+                f"{section_prompt}\n\nWrite the section in the language with code "
+                f"'{language}', keeping code, identifiers and file paths unchanged."
+            )
 
         full_context = f"Original content:\n{section['original_content']}\n\nProject context:\n{context}"
 
@@ -1,12 +1,22 @@
 use clap::{Parser, Subcommand, ValueEnum};
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::core::annotations::ReviewStatus;
+use crate::core::coverage::CoverageFormat;
+use crate::core::interactions::InteractionSource;
+use crate::core::traces::TraceFormat;
+use crate::utils::config::ScanLevel;
 
 #[derive(Parser, Debug, Clone)]
 #[command(
     name = "csd",
     about = "A comprehensive code analysis and documentation tool",
     version,
-    author
+    author,
+    after_help = "Exit codes: 0 success, 1 error, 2 finished with warnings (--fail-on warnings), \
+                  3 quality gate failed, 4 configuration error, 5 plugin failure"
 )]
 pub struct Args {
     #[command(subcommand)]
@@ -23,16 +33,73 @@ pub struct Args {
     /// Project root directory
     #[arg(short, long, global = true)]
     pub project: Option<PathBuf>,
+
+    /// Forbid network access: remote plugins and non-local LLM providers become errors
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Only log errors; command output is unchanged
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Print stable, tab-separated records for scripts instead of human-facing output
+    /// (`plugins`, `stats`, `query` and `api diff`); implies --quiet
+    #[arg(long, global = true)]
+    pub porcelain: bool,
+
+    /// Only show files carrying this tag, e.g. `layer:core` (repeatable; `stats`,
+    /// `quality`, `graph`, `docs`, `query` and `export`). `docs` scopes to the tagged
+    /// files like `--scope` does, keeping `--scope-depth` levels of their dependencies
+    #[arg(long = "tag", global = true, value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Leave out files carrying this tag, e.g. `generated` (repeatable)
+    #[arg(long = "exclude-tag", global = true, value_name = "TAG")]
+    pub exclude_tags: Vec<String>,
+
+    /// Exit with a failure code when a command that otherwise succeeds sees warnings
+    /// (2), files a plugin failed on (5) or API drift from the baseline (3); repeatable
+    /// or comma-separated
+    #[arg(long, global = true, value_enum, value_delimiter = ',')]
+    pub fail_on: Vec<FailOn>,
+
+    /// Report where the command spent its time (walking, hashing, plugins, serde,
+    /// saving) on stderr; with `=FILE`, also write the report to FILE as JSON
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        num_args = 0..=1,
+        require_equals = true
+    )]
+    pub profile_perf: Option<Option<PathBuf>>,
+}
+
+impl Args {
+    /// Log level for the run: errors only under --quiet or --porcelain
+    pub fn log_level(&self) -> log::LevelFilter {
+        if self.quiet || self.porcelain {
+            log::LevelFilter::Error
+        } else {
+            log::LevelFilter::Info
+        }
+    }
 }
 
 #[derive(Subcommand, Debug, Clone)]
 pub enum Command {
     /// Initialize project analysis and build the matrix
+    #[command(visible_alias = "scan")]
     Init {
         /// Path to the project directory
         #[arg(value_name = "PATH")]
         path: Option<PathBuf>,
 
+        /// Further directories scanned into the same matrix alongside PATH; paths in
+        /// the matrix are then prefixed with each directory's name
+        #[arg(value_name = "MORE_PATHS")]
+        more_paths: Vec<PathBuf>,
+
         /// Output format for the results
         #[arg(short, long, default_value = "json")]
         output: OutputFormat,
@@ -48,19 +115,64 @@ pub enum Command {
         /// Include test files in analysis
         #[arg(long)]
         include_tests: bool,
+
+        /// Fail when a file or directory can't be read for lack of permission instead
+        /// of leaving it out of the matrix
+        #[arg(long)]
+        strict: bool,
+
+        /// `structure` records files, hashes, imports and exports without running
+        /// plugins or transforms, for a usable matrix in seconds; `full` (the default,
+        /// or `scanning.level`) adds elements, relationships and summaries
+        #[arg(long, value_enum)]
+        level: Option<ScanLevel>,
     },
 
-    /// Analyze code quality based on existing matrix
+    /// Analyze code quality based on existing matrix. Oversized files and functions
+    /// count as warnings unless a `csd-ignore` pragma or .csd_suppressions.yaml entry
+    /// covers them; expired suppressions count too. Configured rule plugins run as well:
+    /// their warning and error findings count as warnings, plugins that fail as plugin
+    /// errors. With imported coverage, the riskiest files (complex, coupled and poorly
+    /// covered) are listed too.
     Quality {
         /// Path to the matrix file
         #[arg(short, long)]
         matrix: Option<PathBuf>,
 
+        /// Have the LLM draft refactoring suggestions, citing facts from the matrix, for
+        /// the top N findings (5 without a value)
+        #[arg(
+            long,
+            value_name = "N",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "5"
+        )]
+        suggest: Option<usize>,
+
         /// Specific quality metrics to calculate
         #[arg(long)] // Removed short flag to avoid conflict with matrix
         metrics: Vec<QualityMetric>,
     },
 
+    /// Load test coverage into the matrix
+    Coverage {
+        #[command(subcommand)]
+        action: CoverageAction,
+    },
+
+    /// Weight the matrix's relationships by what runs
+    Trace {
+        #[command(subcommand)]
+        action: TraceAction,
+    },
+
+    /// Record the services the project talks to at runtime
+    Services {
+        #[command(subcommand)]
+        action: ServicesAction,
+    },
+
     /// Generate documentation from analysis
     Docs {
         /// Path to the matrix file
@@ -74,6 +186,359 @@ pub enum Command {
         /// Output directory for documentation
         #[arg(short, long)]
         output_dir: Option<PathBuf>,
+
+        /// Reuse sections cached by an interrupted run instead of regenerating them
+        #[arg(long)]
+        resume: bool,
+
+        /// Only document files at or under this path, or matching this glob (repeatable)
+        #[arg(long, value_name = "PATH|GLOB")]
+        scope: Vec<String>,
+
+        /// Levels of dependencies of in-scope files to include alongside them
+        #[arg(long, default_value_t = 1)]
+        scope_depth: usize,
+
+        /// Write a single generated document to stdout instead of an output directory;
+        /// status goes to stderr
+        #[arg(long, conflicts_with = "output_dir")]
+        stdout: bool,
+
+        /// Instead of generating, report links and code spans in the project's markdown
+        /// that name files or elements no longer in the matrix (counts as drift for
+        /// --fail-on)
+        #[arg(long, conflicts_with_all = ["stdout", "resume"])]
+        check_drift: bool,
+    },
+
+    /// Draft a changelog from structural changes and commit messages since a tag or an
+    /// earlier matrix, grouped by component
+    Changelog {
+        /// Tag, commit or matrix file to compare against
+        #[arg(long, value_name = "TAG|MATRIX")]
+        since: String,
+
+        /// Path to the current matrix file
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+
+        /// Output directory for the draft
+        #[arg(short, long)]
+        output_dir: Option<PathBuf>,
+
+        /// Have an output plugin supporting the `changelog` type rewrite the draft
+        #[arg(long)]
+        polish: bool,
+    },
+
+    /// Show matrix statistics and dependency graph analysis
+    Stats {
+        /// Path to the matrix file
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+
+        /// Output format for the results
+        #[arg(short, long, default_value = "pretty")]
+        output: OutputFormat,
+
+        /// Number of files to list per ranking
+        #[arg(long, default_value = "5")]
+        top: usize,
+    },
+
+    /// Query relationships recorded in the matrix
+    Query {
+        /// Path to the matrix file
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+
+        /// Only relationships originating from this file (project-relative)
+        #[arg(long)]
+        from: Option<PathBuf>,
+
+        /// Only relationships pointing at this file (project-relative)
+        #[arg(long)]
+        to: Option<PathBuf>,
+
+        /// Only relationships of these types (import, call, inheritance, ...)
+        #[arg(
+            long = "type",
+            visible_alias = "types",
+            value_name = "TYPE",
+            value_delimiter = ','
+        )]
+        relationship_types: Vec<String>,
+
+        /// Only relationships reported by this plugin
+        #[arg(long)]
+        source_plugin: Option<String>,
+
+        /// Only relationships at least this strong (0.0 to 1.0)
+        #[arg(long)]
+        min_strength: Option<f32>,
+
+        /// Leave out test relationships and those from or to test files
+        #[arg(long)]
+        exclude_tests: bool,
+
+        /// Output format for the results
+        #[arg(short, long, default_value = "pretty")]
+        output: OutputFormat,
+    },
+
+    /// Show how project metrics evolved across scans
+    Trend {
+        /// Output format for the history
+        #[arg(long, default_value = "table")]
+        format: TrendFormat,
+
+        /// Only show the most recent N scans
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+
+        /// Output file path (defaults to stdout)
+        #[arg(short = 'f', long)]
+        output_file: Option<PathBuf>,
+    },
+
+    /// Time discovery, hashing and token estimation with plugins disabled, compared with
+    /// earlier runs; useful for tuning ignore patterns and size limits
+    Bench {
+        /// Directory to benchmark (defaults to the current directory)
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+
+        /// Repeat the benchmark and report the fastest run
+        #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..))]
+        runs: u32,
+
+        /// Don't record the result in the benchmark history
+        #[arg(long)]
+        no_record: bool,
+
+        /// Output format for the results
+        #[arg(short, long, default_value = "pretty")]
+        output: OutputFormat,
+    },
+
+    /// Explain whether a file would be scanned, what excludes it, and which plugin claims it
+    Explain {
+        /// File to explain, relative to the current directory
+        path: PathBuf,
+    },
+
+    /// Write the matrix for sharing, optionally redacted per the `redaction` config
+    Export {
+        /// Path to the matrix file
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+
+        /// Output file path (defaults to stdout)
+        #[arg(short = 'f', long)]
+        output_file: Option<PathBuf>,
+
+        /// Output file path as an argument; `-` writes to stdout
+        #[arg(value_name = "FILE", conflicts_with = "output_file")]
+        destination: Option<PathBuf>,
+
+        /// Output format (json or yaml) for the matrix
+        #[arg(short, long, default_value = "json")]
+        output: OutputFormat,
+
+        /// What to export: the matrix itself, a weighted dependency adjacency matrix or a
+        /// design structure matrix (DSM)
+        #[arg(long, value_enum, default_value = "matrix")]
+        format: ExportFormat,
+
+        /// Aggregate the adjacency matrix or DSM by directory instead of by file
+        #[arg(long)]
+        directories: bool,
+
+        /// Strip summaries, signatures and metadata according to the redaction rules
+        #[arg(long)]
+        redact: bool,
+
+        /// Also replace file paths with salted hashes (implies --redact)
+        #[arg(long)]
+        hash_paths: bool,
+
+        /// Only export relationships of these types (import, call, inheritance, ...)
+        #[arg(
+            long = "type",
+            visible_alias = "types",
+            value_name = "TYPE",
+            value_delimiter = ','
+        )]
+        relationship_types: Vec<String>,
+
+        /// Only export relationships at least this strong (0.0 to 1.0)
+        #[arg(long)]
+        min_strength: Option<f32>,
+
+        /// Leave out test relationships and those from or to test files
+        #[arg(long)]
+        exclude_tests: bool,
+
+        /// Only export files in this language (python, rust, ...), the edges among them
+        /// and their external dependencies
+        #[arg(long)]
+        language: Option<String>,
+    },
+
+    /// Show the dependency neighborhood of a file or directory
+    Graph {
+        /// Path to the matrix file
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+
+        /// File or directory (project-relative) to center the graph on
+        #[arg(long)]
+        focus: PathBuf,
+
+        /// Levels of dependencies and dependents to include around the focus
+        #[arg(long, default_value = "1")]
+        depth: usize,
+
+        /// A Graphviz graph, or the subset matrix as JSON for use with --matrix
+        #[arg(long, value_enum, default_value = "dot")]
+        format: GraphFormat,
+
+        /// Output file path (defaults to stdout)
+        #[arg(short = 'f', long)]
+        output_file: Option<PathBuf>,
+
+        /// Only follow relationships of these types (import, call, inheritance, ...)
+        #[arg(
+            long = "type",
+            visible_alias = "types",
+            value_name = "TYPE",
+            value_delimiter = ','
+        )]
+        relationship_types: Vec<String>,
+
+        /// Only follow relationships at least this strong (0.0 to 1.0)
+        #[arg(long)]
+        min_strength: Option<f32>,
+
+        /// Leave out test relationships and those from or to test files
+        #[arg(long)]
+        exclude_tests: bool,
+    },
+
+    /// Inspect and repair matrix files
+    Matrix {
+        #[command(subcommand)]
+        action: MatrixAction,
+    },
+
+    /// Extract and compare the project's public API surface
+    Api {
+        #[command(subcommand)]
+        action: ApiAction,
+    },
+
+    /// List TODO/FIXME markers and the issues they reference (JIRA-123, #456), tagging
+    /// files with the open ones and flagging markers whose issue is closed
+    Debt {
+        /// Path to the matrix file
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+
+        /// Look issues up in the configured issue_trackers
+        #[arg(long)]
+        verify: bool,
+
+        /// Output format for the results
+        #[arg(short, long, default_value = "pretty")]
+        output: OutputFormat,
+    },
+
+    /// Record architecture decisions in docs/adr and link them to the files they affect
+    Adr {
+        #[command(subcommand)]
+        action: AdrAction,
+    },
+
+    /// Attach notes, an owner or a review status to a file or element. They're kept in
+    /// .csd_annotations.yaml, survive rescans and show up in docs and the API. Without
+    /// changes, lists the annotations.
+    Annotate {
+        /// Project-relative file; lists every annotation when left out
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+
+        /// Annotate this element of the file (function, class, ...) rather than the file
+        #[arg(short, long, value_name = "NAME", requires = "path")]
+        element: Option<String>,
+
+        /// Add a note
+        #[arg(long, value_name = "TEXT", requires = "path")]
+        note: Option<String>,
+
+        /// Who wrote the note
+        #[arg(long, value_name = "NAME", requires = "note")]
+        author: Option<String>,
+
+        /// Set the owner, e.g. a team or a person
+        #[arg(long, value_name = "OWNER", requires = "path")]
+        owner: Option<String>,
+
+        /// Set the review status
+        #[arg(long, value_enum, requires = "path")]
+        review: Option<ReviewStatus>,
+
+        /// Remove the existing annotation first
+        #[arg(long, requires = "path")]
+        clear: bool,
+
+        /// Path to the matrix file elements are looked up in
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+    },
+
+    /// Keep scanning on a schedule, recording metrics history, sending notifications
+    /// and serving the matrix over HTTP
+    Daemon {
+        /// Time between scheduled scans, e.g. 30m, 6h or 1d
+        #[arg(long, default_value = "6h", value_parser = parse_interval)]
+        interval: Duration,
+
+        /// Also scan when new commits arrive. Registered git projects are fetched and
+        /// fast-forwarded; directory projects are rescanned when their HEAD moves.
+        #[arg(long)]
+        on_commits: bool,
+
+        /// How often to check for new commits with --on-commits
+        #[arg(long, default_value = "5m", value_parser = parse_interval)]
+        poll: Duration,
+
+        /// Address the HTTP API listens on
+        #[arg(long, default_value = "127.0.0.1:7878")]
+        listen: SocketAddr,
+
+        /// Don't serve the HTTP API
+        #[arg(long)]
+        no_api: bool,
+
+        /// Scan and serve the registered projects instead of the current directory,
+        /// from FILE or the default registry
+        #[arg(
+            long,
+            value_name = "FILE",
+            num_args = 0..=1,
+            require_equals = true
+        )]
+        registry: Option<Option<PathBuf>>,
+    },
+
+    /// Register the projects `csd daemon --registry` scans and serves
+    Registry {
+        /// Registry file; registry.yaml in the user configuration directory by default
+        #[arg(long)]
+        file: Option<PathBuf>,
+
+        #[command(subcommand)]
+        action: RegistryAction,
     },
 
     /// List available plugins
@@ -83,11 +548,368 @@ pub enum Command {
         detailed: bool,
     },
 
+    /// Create and manage local plugins
+    Plugin {
+        #[command(subcommand)]
+        action: PluginAction,
+    },
+
+    /// Check Python, plugins, cache, git, the LLM endpoint, config and disk space
+    Doctor {
+        /// Output format for the results
+        #[arg(short, long, default_value = "pretty")]
+        output: OutputFormat,
+    },
+
+    /// Check each enabled plugin's config against the schema the plugin declares
+    Validate {
+        /// Output format for the results
+        #[arg(short, long, default_value = "pretty")]
+        output: OutputFormat,
+    },
+
     /// Initialize a new configuration file
     Config {
         /// Force overwrite existing configuration
         #[arg(long)]
         force: bool,
+
+        /// Use the user-level config (~/.config/csd/config.yaml) instead of the project's
+        #[arg(long, global = true)]
+        global: bool,
+
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
+}
+
+impl Command {
+    /// Subcommand name as typed on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Init { .. } => "init",
+            Command::Quality { .. } => "quality",
+            Command::Coverage { .. } => "coverage",
+            Command::Trace { .. } => "trace",
+            Command::Services { .. } => "services",
+            Command::Docs { .. } => "docs",
+            Command::Changelog { .. } => "changelog",
+            Command::Stats { .. } => "stats",
+            Command::Query { .. } => "query",
+            Command::Trend { .. } => "trend",
+            Command::Bench { .. } => "bench",
+            Command::Explain { .. } => "explain",
+            Command::Export { .. } => "export",
+            Command::Graph { .. } => "graph",
+            Command::Matrix { .. } => "matrix",
+            Command::Api { .. } => "api",
+            Command::Debt { .. } => "debt",
+            Command::Adr { .. } => "adr",
+            Command::Annotate { .. } => "annotate",
+            Command::Daemon { .. } => "daemon",
+            Command::Registry { .. } => "registry",
+            Command::Plugins { .. } => "plugins",
+            Command::Plugin { .. } => "plugin",
+            Command::Doctor { .. } => "doctor",
+            Command::Validate { .. } => "validate",
+            Command::Config { .. } => "config",
+        }
+    }
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigAction {
+    /// Rewrite a legacy `plugins:` configuration into input_plugins/output_plugins
+    Migrate {
+        /// Print the migrated configuration instead of writing it
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Do not keep a `.bak` copy of the original file
+        #[arg(long)]
+        no_backup: bool,
+    },
+
+    /// Print a configuration value (dot-path, e.g. `llm.model`)
+    Get {
+        #[arg(value_name = "KEY")]
+        key: String,
+    },
+
+    /// Set a configuration value (dot-path, e.g. `llm.model deepseek-coder`)
+    Set {
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        /// Value, parsed as YAML (numbers, booleans and lists keep their types)
+        #[arg(value_name = "VALUE")]
+        value: String,
+    },
+
+    /// Remove a configuration value
+    Unset {
+        #[arg(value_name = "KEY")]
+        key: String,
+    },
+
+    /// List all configuration values as dot-paths
+    List,
+
+    /// Store a secret (read from stdin) in the OS keyring and reference it from the config
+    SetSecret {
+        #[arg(value_name = "KEY")]
+        key: String,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum PluginAction {
+    /// Generate a skeleton Python plugin with a test harness and register it in the config
+    New {
+        /// Plugin name, used as the Python module name (e.g. my_lang)
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Kind of plugin to generate
+        #[arg(short = 't', long = "type", default_value = "input")]
+        plugin_type: PluginKind,
+
+        /// Plugins directory; files go in <DIR>/<type>/<name>/
+        #[arg(long, default_value = "plugins")]
+        dir: PathBuf,
+
+        /// File extension an input plugin claims (repeatable; defaults to .<name>)
+        #[arg(short, long = "extension")]
+        extensions: Vec<String>,
+
+        /// Only generate the files, without adding the plugin to the config
+        #[arg(long)]
+        no_register: bool,
+
+        /// Overwrite existing plugin files
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Run a plugin through the protocol with fixture inputs and report conformance
+    Test {
+        /// Configured plugin name, or the path to a plugin script
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Which configured plugin to use when several types share the name
+        #[arg(short = 't', long = "type")]
+        plugin_type: Option<PluginKind>,
+
+        /// File for an input plugin to analyze (repeatable; defaults to a sample per
+        /// advertised extension)
+        #[arg(long = "fixture")]
+        fixtures: Vec<PathBuf>,
+
+        /// Matrix for output, transform and rule plugins (defaults to a small canned one)
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+
+        /// Seconds before a single exchange is abandoned
+        #[arg(long, default_value = "300")]
+        timeout: u64,
+
+        /// Output format for the report
+        #[arg(short, long, default_value = "pretty")]
+        output: OutputFormat,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum MatrixAction {
+    /// Check for dangling relationships, duplicate paths, missing hashes, files without
+    /// tokens and inconsistent fields, e.g. in third-party plugin output
+    Lint {
+        /// Path to the matrix file
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+
+        /// Repair the fixable issues and save the matrix (the original is kept as a backup)
+        #[arg(long)]
+        fix: bool,
+
+        /// Output format for the results
+        #[arg(short, long, default_value = "pretty")]
+        output: OutputFormat,
+    },
+
+    /// Strip heavy optional fields and rewrite the matrix, reporting the size saved.
+    /// Without flags, element metadata, signatures and summaries all go.
+    Compact {
+        /// Path to the matrix file
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+
+        /// Strip plugin metadata blobs from elements
+        #[arg(long)]
+        metadata: bool,
+
+        /// Strip element signatures
+        #[arg(long)]
+        signatures: bool,
+
+        /// Strip file and element summaries
+        #[arg(long)]
+        summaries: bool,
+
+        /// Drop elements, imports and exports, keeping only files and relationships
+        #[arg(long)]
+        graph_only: bool,
+
+        /// Output format for the results
+        #[arg(short, long, default_value = "pretty")]
+        output: OutputFormat,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ApiAction {
+    /// Write the public API surface (exported elements with signatures) as JSON
+    Extract {
+        /// Path to the matrix file
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+
+        /// Output file path (defaults to stdout)
+        #[arg(short = 'f', long)]
+        output_file: Option<PathBuf>,
+    },
+
+    /// Compare two API surfaces (or matrices) and report breaking changes
+    Diff {
+        /// Older surface or matrix file
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+
+        /// Newer surface or matrix file (defaults to the current matrix)
+        #[arg(value_name = "NEW")]
+        new: Option<PathBuf>,
+
+        /// Output format for the results
+        #[arg(short, long, default_value = "pretty")]
+        output: OutputFormat,
+
+        /// Exit with an error when the diff contains breaking changes
+        #[arg(long)]
+        fail_on_breaking: bool,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum AdrAction {
+    /// Write the next numbered ADR from a template and update the index
+    New {
+        /// Title of the decision, e.g. "Use event bus"
+        #[arg(value_name = "TITLE")]
+        title: String,
+
+        /// Files the decision affects; tagged `adr:NNNN` in the matrix on the next scan
+        #[arg(long = "affects", value_name = "PATH|GLOB")]
+        affects: Vec<String>,
+    },
+
+    /// Rewrite docs/adr/README.md and tag the matrix with each ADR's files
+    Index {
+        /// Path to the matrix file to tag
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum CoverageAction {
+    /// Map an lcov or Cobertura report onto the matrix's files and elements, replacing
+    /// the last import. Rescanning drops coverage, so import after each scan.
+    Import {
+        /// Coverage report, e.g. coverage.lcov or coverage.xml
+        #[arg(value_name = "REPORT")]
+        report: PathBuf,
+
+        /// Report format; detected from the content when left out
+        #[arg(long, value_enum)]
+        format: Option<CoverageFormat>,
+
+        /// Path to the matrix file
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum TraceAction {
+    /// Match the calls in collapsed profiler stacks or a JSON call graph to elements.
+    /// Edges they exercise get `runtime_calls` and a higher strength; calls with no
+    /// call edge get one. Replaces the last import; rescanning drops it.
+    Import {
+        /// Trace file, e.g. perf.folded or calls.json
+        #[arg(value_name = "TRACE")]
+        trace: PathBuf,
+
+        /// Trace format; detected from the content when left out
+        #[arg(long, value_enum)]
+        format: Option<TraceFormat>,
+
+        /// Path to the matrix file
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ServicesAction {
+    /// Read an OpenTelemetry service graph or a JSON-lines access log into the matrix's
+    /// external interactions, which docs show as the runtime topology. Replaces the last
+    /// import of the same kind; rescanning drops them.
+    Import {
+        /// Service graph (Jaeger dependencies JSON or Prometheus metrics) or access log
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// What the file is; detected from the content when left out
+        #[arg(long, value_enum)]
+        format: Option<InteractionSource>,
+
+        /// This project's name in the service graph (default: the project directory's)
+        #[arg(long, value_name = "NAME")]
+        service: Option<String>,
+
+        /// Path to the matrix file
+        #[arg(short, long)]
+        matrix: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum RegistryAction {
+    /// Register a project directory or git URL under an id
+    Add {
+        /// Names the project in /projects/{id}/ API routes
+        id: String,
+
+        /// Project directory, or a git URL to clone
+        source: String,
+
+        /// Branch to check out of a git URL
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Config file relative to the project root (default: its .csdrc.*)
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+
+    /// List the registered projects
+    List,
+
+    /// Unregister a project; a git checkout is left on disk
+    Remove {
+        /// Id of the project
+        id: String,
     },
 }
 
@@ -98,6 +920,45 @@ pub enum OutputFormat {
     Pretty,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum GraphFormat {
+    Dot,
+    Matrix,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum FailOn {
+    Warnings,
+    PluginErrors,
+    Drift,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum PluginKind {
+    Input,
+    Output,
+    Transform,
+    Rule,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Matrix,
+    AdjacencyCsv,
+    // Needs a build with the `parquet` feature and an --output-file
+    Parquet,
+    // Design structure matrix: interactive HTML page, or the ordered matrix as CSV
+    DsmHtml,
+    DsmCsv,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum TrendFormat {
+    Table,
+    Csv,
+    Json,
+}
+
 #[derive(ValueEnum, Clone, Debug)]
 pub enum QualityMetric {
     Complexity,
@@ -105,6 +966,8 @@ pub enum QualityMetric {
     Maintainability,
     Security,
     Performance,
+    /// Routes and RPCs out of step with the project's OpenAPI specs and .proto files
+    Contracts,
     All,
 }
 
@@ -114,3 +977,26 @@ pub enum DocFormat {
     Html,
     Pdf,
 }
+
+/// `90s`, `30m`, `6h` or `1d`; a bare number is seconds
+pub fn parse_interval(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("'{value}' is not a duration like 30m or 6h"))?;
+    let seconds = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return Err(format!("Unknown unit '{unit}'; use s, m, h or d")),
+    };
+    if number == 0 {
+        return Err("Interval must be longer than zero".to_string());
+    }
+    Ok(Duration::from_secs(number * seconds))
+}
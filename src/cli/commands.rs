@@ -1,74 +1,688 @@
 use anyhow::Result;
+use chrono::Utc;
 use log::{debug, info, warn};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::cli::args::{Args, Command};
+use crate::cli::args::{
+    AdrAction, ApiAction, Args, Command, ConfigAction, CoverageAction, ExportFormat, FailOn,
+    GraphFormat, MatrixAction, PluginAction, PluginKind, QualityMetric, RegistryAction,
+    ServicesAction, TraceAction,
+};
+use crate::cli::exit::{ExitCode, ExitError, Findings};
+use crate::cli::porcelain;
+use crate::core::adjacency::{AdjacencyLevel, AdjacencyMatrix};
+use crate::core::adr::{create_adr, link_adrs, load_adrs, write_index};
+use crate::core::annotations::{find_target, path_key, Annotation, Annotations, Note};
+use crate::core::api::ApiSurface;
+use crate::core::bench::{BenchHistory, BenchReport, BenchResult};
+use crate::core::categories::FileCategory;
+use crate::core::compact::{compact_matrix, CompactOptions};
+use crate::core::coverage::CoverageFormat;
+use crate::core::doctor::Doctor;
+use crate::core::dsm::{render_dsm_html, Dsm};
+use crate::core::events::{detect_changes, EventLog, MatrixEvent};
+use crate::core::facade::{Csd, ScanReport};
+use crate::core::focus::FocusGraph;
+use crate::core::graph_analysis::GraphAnalysis;
+use crate::core::history::{snapshots_to_csv, MetricsHistory, MetricsSnapshot};
+use crate::core::interactions::InteractionSource;
+use crate::core::lint::{fix_matrix, lint_matrix};
+use crate::core::matrix::{CategoryRollup, ProjectMatrix, RelationshipFilter};
+use crate::core::redact::{cached_salt, redact_matrix};
 use crate::core::scanner::ProjectScanner;
-use crate::plugins::interface::{OutputPluginInput, OutputPluginInterface};
+use crate::core::suggestions::draft_suggestions;
+use crate::core::traces::TraceFormat;
+use crate::core::validate::validate_plugin_configs;
+use crate::core::view::MatrixView;
+use crate::llm::client::LlmClient;
+use crate::output::manifest::DOCS_MANIFEST_FILE;
+use crate::plugins::conformance::{CheckStatus, ConformanceRunner};
+use crate::plugins::interface::{FindingSeverity, OutputScope, PluginType};
 use crate::plugins::manager::PluginManager;
-use crate::utils::config::Config;
+use crate::plugins::scaffold::PluginScaffold;
+use crate::server::auth::{Authenticator, API_TOKEN_ENV};
+use crate::server::http::{ApiServer, ProjectState, ScanRun, ServerState};
+use crate::server::registry::{project_config, ProjectRegistry, RegisteredProject};
+use crate::utils::config::{
+    find_config_file, find_global_config_file, format_config_value, get_config_value,
+    global_config_dir, list_config_values, merge_config_values, migrate_config_text,
+    parse_config_value, set_config_value, unset_config_value, Config, ConfigFormat, NotifyCommand,
+    PluginRuntime, RedactionConfig, ScanLevel, DEPRECATED_KEYS,
+};
+use crate::utils::git::{head_commit, strip_credentials};
+use crate::utils::notify::{with_delta, Notification, Notifier};
+use crate::utils::profile;
+use crate::utils::secrets::{
+    is_secret_key, keyring_reference, redact_config_values, store_secret, REDACTED,
+};
 
 pub async fn handle_command(args: Args) -> Result<()> {
+    let profile_output = args.profile_perf.clone();
+    let command = args.command.name();
+    if profile_output.is_some() {
+        profile::enable();
+    }
+
+    let result = run_command(args).await;
+
+    if let Some(report) = profile::finish(command) {
+        report.print_table();
+        if let Some(Some(path)) = profile_output {
+            tokio::fs::write(&path, serde_json::to_string_pretty(&report)?).await?;
+            eprintln!("Profile written to {}", path.display());
+        }
+    }
+    result
+}
+
+async fn run_command(args: Args) -> Result<()> {
+    // Diagnoses configurations that fail to load, so it loads its own
+    if let Command::Doctor { output } = &args.command {
+        return handle_doctor(&args, output.clone()).await;
+    }
+
     // Load configuration
-    let config = load_config(&args).await?;
+    let config_path = config_file_path(&args);
+    let mut config = load_config(&args)
+        .await
+        .map_err(|e| ExitError::new(ExitCode::Config, format!("{e:#}")))?;
+    config.offline |= args.offline;
+    let view = MatrixView::new(args.tags.clone(), args.exclude_tags.clone());
 
     match args.command {
         Command::Init {
             path,
+            more_paths,
             output,
             output_file,
             no_llm,
             include_tests,
-        } => handle_init(path, output, output_file, no_llm, include_tests, &config).await,
-        Command::Quality { matrix, metrics } => handle_quality(matrix, metrics, &config).await,
+            strict,
+            level,
+        } => {
+            config.scanning.fail_on_permission_denied |= strict;
+            if let Some(level) = level {
+                config.scanning.level = level;
+            }
+            let started = Instant::now();
+            let outcome = handle_init(
+                path,
+                more_paths,
+                output,
+                output_file,
+                no_llm,
+                include_tests,
+                &config,
+            )
+            .await;
+            let artifacts = vec![("Matrix".to_string(), default_matrix_path(&config))];
+            notify_finished(
+                &config,
+                NotifyCommand::Scan,
+                Path::new("."),
+                started,
+                &outcome,
+                &args.fail_on,
+                artifacts,
+            )
+            .await;
+            outcome?.enforce(&args.fail_on)
+        }
+        Command::Quality {
+            matrix,
+            suggest,
+            metrics,
+        } => handle_quality(matrix, suggest, metrics, &view, &config)
+            .await?
+            .enforce(&args.fail_on),
+        Command::Coverage { action } => match action {
+            CoverageAction::Import {
+                report,
+                format,
+                matrix,
+            } => handle_coverage_import(&report, format, matrix, &config).await,
+        },
+        Command::Services { action } => match action {
+            ServicesAction::Import {
+                file,
+                format,
+                service,
+                matrix,
+            } => handle_services_import(&file, format, service.as_deref(), matrix, &config).await,
+        },
+        Command::Trace { action } => match action {
+            TraceAction::Import {
+                trace,
+                format,
+                matrix,
+            } => handle_trace_import(&trace, format, matrix, &config).await,
+        },
         Command::Docs {
             matrix,
             format,
             output_dir,
-        } => handle_docs(matrix, format, output_dir, &config).await,
-        Command::Plugins { detailed } => handle_plugins(detailed, &config).await,
-        Command::Config { force } => handle_config(force).await,
+            resume,
+            scope,
+            scope_depth,
+            stdout,
+            check_drift,
+        } => {
+            let started = Instant::now();
+            let output_directory = output_dir
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(&config.output_dir));
+            let outcome = if check_drift {
+                handle_docs_drift(matrix, args.porcelain, &config).await
+            } else {
+                // Tags scope the docs rather than cut the matrix, so in-scope files
+                // keep --scope-depth levels of their untagged dependencies
+                let scope = docs_scope(scope, args.tags.clone(), scope_depth);
+                let view = MatrixView::new(Vec::new(), args.exclude_tags.clone());
+                handle_docs(
+                    matrix, format, output_dir, resume, scope, &view, stdout, &config,
+                )
+                .await
+            };
+            let mut artifacts = Vec::new();
+            if !check_drift && !stdout {
+                let manifest = output_directory.join(DOCS_MANIFEST_FILE);
+                artifacts.push(("Docs".to_string(), output_directory));
+                if manifest.exists() {
+                    artifacts.push(("Manifest".to_string(), manifest));
+                }
+            }
+            notify_finished(
+                &config,
+                NotifyCommand::Docs,
+                Path::new("."),
+                started,
+                &outcome,
+                &args.fail_on,
+                artifacts,
+            )
+            .await;
+            outcome?.enforce(&args.fail_on)
+        }
+        Command::Changelog {
+            since,
+            matrix,
+            output_dir,
+            polish,
+        } => handle_changelog(since, matrix, output_dir, polish, &config).await,
+        Command::Stats {
+            matrix,
+            output,
+            top,
+        } => handle_stats(matrix, output, top, args.porcelain, &view, &config).await,
+        Command::Query {
+            matrix,
+            from,
+            to,
+            relationship_types,
+            source_plugin,
+            min_strength,
+            exclude_tests,
+            output,
+        } => {
+            let filter = RelationshipFilter {
+                from_file: from,
+                to_file: to,
+                source_plugin,
+                ..relationship_filter(&relationship_types, min_strength, exclude_tests)?
+            };
+            handle_query(matrix, filter, output, args.porcelain, &view, &config).await
+        }
+        Command::Trend {
+            format,
+            limit,
+            output_file,
+        } => handle_trend(format, limit, output_file, &config).await,
+        Command::Bench {
+            path,
+            runs,
+            no_record,
+            output,
+        } => handle_bench(path, runs, !no_record, output, &config).await,
+        Command::Explain { path } => handle_explain(&path, &config),
+        Command::Export {
+            matrix,
+            output_file,
+            destination,
+            output,
+            format,
+            directories,
+            redact,
+            hash_paths,
+            relationship_types,
+            min_strength,
+            exclude_tests,
+            language,
+        } => {
+            let filter = relationship_filter(&relationship_types, min_strength, exclude_tests)?;
+            // --hash-paths implies --redact
+            let redaction = (redact || hash_paths).then(|| {
+                let mut rules = config.redaction.clone();
+                rules.hash_paths |= hash_paths;
+                rules
+            });
+            let level = if directories {
+                AdjacencyLevel::Directory
+            } else {
+                AdjacencyLevel::File
+            };
+            // `-` is stdout, the same as giving no file at all
+            let output_file = output_file
+                .or(destination)
+                .filter(|path| path.as_os_str() != "-");
+            handle_export(
+                matrix,
+                output_file,
+                output,
+                format,
+                level,
+                filter,
+                language.as_deref(),
+                redaction,
+                &view,
+                &config,
+            )
+            .await
+        }
+        Command::Graph {
+            matrix,
+            focus,
+            depth,
+            format,
+            output_file,
+            relationship_types,
+            min_strength,
+            exclude_tests,
+        } => {
+            let filter = relationship_filter(&relationship_types, min_strength, exclude_tests)?;
+            handle_graph(
+                matrix,
+                &focus,
+                depth,
+                format,
+                output_file,
+                filter,
+                &view,
+                &config,
+            )
+            .await
+        }
+        Command::Matrix { action } => match action {
+            MatrixAction::Lint {
+                matrix,
+                fix,
+                output,
+            } => handle_matrix_lint(matrix, fix, output, &config).await,
+            MatrixAction::Compact {
+                matrix,
+                metadata,
+                signatures,
+                summaries,
+                graph_only,
+                output,
+            } => {
+                let options = CompactOptions {
+                    metadata,
+                    signatures,
+                    summaries,
+                    graph_only,
+                };
+                let options = if options.is_empty() {
+                    CompactOptions::all()
+                } else {
+                    options
+                };
+                handle_matrix_compact(matrix, &options, output, &config).await
+            }
+        },
+        Command::Api { action } => match action {
+            ApiAction::Extract {
+                matrix,
+                output_file,
+            } => handle_api_extract(matrix, output_file, &config).await,
+            ApiAction::Diff {
+                old,
+                new,
+                output,
+                fail_on_breaking,
+            } => handle_api_diff(old, new, output, fail_on_breaking, args.porcelain, &config)
+                .await?
+                .enforce(&args.fail_on),
+        },
+        Command::Debt {
+            matrix,
+            verify,
+            output,
+        } => handle_debt(matrix, verify, output, &config)
+            .await?
+            .enforce(&args.fail_on),
+        Command::Daemon {
+            interval,
+            on_commits,
+            poll,
+            listen,
+            no_api,
+            registry,
+        } => {
+            handle_daemon(
+                interval,
+                on_commits.then_some(poll),
+                (!no_api).then_some(listen),
+                registry,
+                &args.fail_on,
+                &config,
+            )
+            .await
+        }
+        Command::Registry { file, action } => handle_registry(file, action).await,
+        Command::Adr { action } => match action {
+            AdrAction::New { title, affects } => handle_adr_new(&title, &affects).await,
+            AdrAction::Index { matrix } => handle_adr_index(matrix, &config).await,
+        },
+        Command::Annotate {
+            path,
+            element,
+            note,
+            author,
+            owner,
+            review,
+            clear,
+            matrix,
+        } => {
+            let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(&config));
+            let change = AnnotationChange {
+                note,
+                author,
+                owner,
+                review,
+                clear,
+            };
+            match path {
+                Some(path) if change.is_some() => {
+                    handle_annotate(&path, element.as_deref(), change, &matrix_path).await
+                }
+                path => handle_annotations(path.as_deref(), &matrix_path).await,
+            }
+        }
+        Command::Plugins { detailed } => handle_plugins(detailed, args.porcelain, &config).await,
+        Command::Plugin {
+            action:
+                PluginAction::New {
+                    name,
+                    plugin_type,
+                    dir,
+                    extensions,
+                    no_register,
+                    force,
+                },
+        } => {
+            let scaffold =
+                PluginScaffold::new(&name, plugin_type.into())?.with_extensions(&extensions);
+            handle_plugin_new(&config_path, scaffold, &dir, !no_register, force).await
+        }
+        Command::Plugin {
+            action:
+                PluginAction::Test {
+                    name,
+                    plugin_type,
+                    fixtures,
+                    matrix,
+                    timeout,
+                    output,
+                },
+        } => {
+            let runner = plugin_conformance_runner(&name, plugin_type, &config)
+                .await?
+                .with_fixtures(fixtures)
+                .with_matrix(matrix)
+                .with_timeout(std::time::Duration::from_secs(timeout));
+            handle_plugin_test(runner, output).await
+        }
+        Command::Doctor { .. } => unreachable!("handled before the configuration is loaded"),
+        Command::Validate { output } => handle_validate(output, &config).await,
+        Command::Config {
+            force,
+            global,
+            action,
+        } => {
+            let target = if global {
+                global_config_target()?
+            } else {
+                config_path
+            };
+            match action {
+                None => handle_config(&target, force).await,
+                Some(ConfigAction::Migrate { dry_run, no_backup }) => {
+                    handle_config_migrate(&target, dry_run, no_backup).await
+                }
+                Some(ConfigAction::Get { key }) => {
+                    handle_config_get(&config_view(&target, global, &config).await?, &key)
+                }
+                Some(ConfigAction::Set { key, value }) => {
+                    handle_config_set(&target, global, &key, Some(&value)).await
+                }
+                Some(ConfigAction::Unset { key }) => {
+                    handle_config_set(&target, global, &key, None).await
+                }
+                Some(ConfigAction::List) => {
+                    handle_config_list(&config_view(&target, global, &config).await?)
+                }
+                Some(ConfigAction::SetSecret { key }) => {
+                    handle_config_set_secret(&target, global, &key).await
+                }
+            }
+        }
+    }
+}
+
+impl From<PluginKind> for PluginType {
+    fn from(kind: PluginKind) -> Self {
+        match kind {
+            PluginKind::Input => PluginType::Input,
+            PluginKind::Output => PluginType::Output,
+            PluginKind::Transform => PluginType::Transform,
+            PluginKind::Rule => PluginType::Rule,
+        }
     }
 }
 
+/// User-level config file to edit with `--global`, existing or not
+fn global_config_target() -> Result<PathBuf> {
+    find_global_config_file()
+        .or_else(|| global_config_dir().map(|dir| dir.join("config.yaml")))
+        .ok_or_else(|| anyhow::anyhow!("Could not determine the user config directory"))
+}
+
+fn config_file_path(args: &Args) -> PathBuf {
+    args.config
+        .clone()
+        .or_else(|| find_config_file(Path::new(".")))
+        .unwrap_or_else(|| PathBuf::from(".csdrc.yaml"))
+}
+
 async fn load_config(args: &Args) -> Result<Config> {
-    let default_path = PathBuf::from(".csdrc.yaml");
-    let config_path = args.config.as_ref().unwrap_or(&default_path);
+    let config_path = &config_file_path(args);
+    let global_path = find_global_config_file();
+    let project_path = config_path.exists().then_some(config_path.as_path());
 
-    if config_path.exists() {
-        debug!("Loading configuration from: {}", config_path.display());
-        Config::load(config_path).await
-    } else {
+    if let Some(ref global_path) = global_path {
+        debug!("Using user configuration from: {}", global_path.display());
+    }
+    if project_path.is_none() {
         warn!("Configuration file not found, using defaults");
-        Ok(Config::default())
+    }
+
+    Config::load_layered(global_path.as_deref(), project_path).await
+}
+
+/// Matrix written by `csd init` for the current directory
+fn default_matrix_path(config: &Config) -> PathBuf {
+    config.resolve_cache_dir(Path::new(".")).join("matrix.json")
+}
+
+/// Whether a command and its --fail-on gate passed, and if not why
+fn gate_status(outcome: &Result<Findings>, fail_on: &[FailOn]) -> (bool, String) {
+    match outcome {
+        Ok(findings) => match findings.enforce(fail_on) {
+            Ok(()) => (true, "passed".to_string()),
+            Err(e) => (false, format!("failed: {e}")),
+        },
+        Err(e) => (false, format!("error: {e:#}")),
+    }
+}
+
+/// Post a summary of a finished `scan` or `docs` run to the webhooks that want it:
+/// the --fail-on outcome, the matrix totals with their change since the previous
+/// scan, and where the artifacts are
+async fn notify_finished(
+    config: &Config,
+    command: NotifyCommand,
+    root: &Path,
+    started: Instant,
+    outcome: &Result<Findings>,
+    fail_on: &[FailOn],
+    artifacts: Vec<(String, PathBuf)>,
+) {
+    if config
+        .notifications
+        .webhooks_for(command, false)
+        .next()
+        .is_none()
+    {
+        return;
+    }
+
+    let (success, gate) = gate_status(outcome, fail_on);
+
+    let mut facts = Vec::new();
+    let history = MetricsHistory::new(&config.resolve_cache_dir(root))
+        .load()
+        .await
+        .unwrap_or_default();
+    // A scan that succeeded appended the latest snapshot; compare it with the one before
+    let (current, previous) = match (command, outcome.is_ok()) {
+        (NotifyCommand::Scan, false) => (None, None),
+        (NotifyCommand::Scan, true) => (history.last(), history.iter().rev().nth(1)),
+        (NotifyCommand::Docs | NotifyCommand::Events, _) => (history.last(), None),
+    };
+    if let Some(current) = current {
+        let delta =
+            |field: fn(&MetricsSnapshot) -> u64| with_delta(field(current), previous.map(field));
+        facts.push(("Files".to_string(), delta(|s| s.total_files as u64)));
+        facts.push((
+            "Relationships".to_string(),
+            delta(|s| s.total_relationships as u64),
+        ));
+        facts.push(("Tokens".to_string(), delta(|s| s.total_tokens)));
+        facts.push((
+            "External dependencies".to_string(),
+            delta(|s| s.external_dependencies as u64),
+        ));
+    }
+    if let Ok(findings) = outcome {
+        facts.push(("Warnings".to_string(), findings.warnings.to_string()));
+        facts.push((
+            "Plugin errors".to_string(),
+            findings.plugin_errors.to_string(),
+        ));
+        if findings.drift > 0 {
+            facts.push(("Drift".to_string(), findings.drift.to_string()));
+        }
+    }
+
+    let project = root
+        .canonicalize()
+        .ok()
+        .and_then(|dir| {
+            dir.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| ".".to_string());
+    let notification = Notification {
+        command,
+        project,
+        success,
+        gate,
+        duration: started.elapsed(),
+        facts,
+        artifacts: artifacts
+            .into_iter()
+            .map(|(label, path)| {
+                let path = path.strip_prefix(root).unwrap_or(&path);
+                (label, path.to_string_lossy().replace('\\', "/"))
+            })
+            .collect(),
+    };
+    match Notifier::new(config.offline) {
+        Ok(notifier) => {
+            notifier
+                .notify_all(&config.notifications, &notification)
+                .await
+        }
+        Err(e) => warn!("Notifications not sent: {e:#}"),
+    }
+}
+
+/// Filter from the --type, --min-strength and --exclude-tests flags
+fn relationship_filter(
+    types: &[String],
+    min_strength: Option<f32>,
+    exclude_tests: bool,
+) -> Result<RelationshipFilter> {
+    Ok(RelationshipFilter {
+        relationship_types: types.iter().map(|t| t.parse()).collect::<Result<_>>()?,
+        min_strength,
+        exclude_tests,
+        ..RelationshipFilter::default()
+    })
+}
+
+fn print_records(records: Vec<String>) {
+    for record in records {
+        println!("{record}");
     }
 }
 
 async fn handle_init(
     path: Option<PathBuf>,
+    more_paths: Vec<PathBuf>,
     output: crate::cli::args::OutputFormat,
     output_file: Option<PathBuf>,
     _no_llm: bool,
     _include_tests: bool,
     config: &Config,
-) -> Result<()> {
+) -> Result<Findings> {
     info!("Initializing project and building matrix...");
 
     let project_path = path.unwrap_or_else(|| PathBuf::from("."));
+    let csd = if more_paths.is_empty() {
+        Csd::new(config.clone()).with_root(&project_path)
+    } else {
+        // Several roots share the current directory's cache and config
+        let mut roots = vec![project_path];
+        roots.extend(more_paths);
+        Csd::new(config.clone()).with_roots(roots)
+    };
 
-    // Create and configure scanner
-    let scanner = ProjectScanner::new(config.clone()).with_root(&project_path);
-
-    // Perform the scan and build matrix
-    let mut matrix = scanner.scan_to_matrix().await?;
-
-    // Print matrix summary
-    matrix.print_summary();
-
-    // Save the matrix to cache (this is the primary deliverable)
-    let matrix_path = project_path.join(".csd_cache").join("matrix.json");
-    matrix.save(&matrix_path).await?;
-    info!("Matrix saved to: {}", matrix_path.display());
+    // Scan, save the matrix (the primary deliverable) and run transform plugins over it
+    let ScanReport {
+        matrix, transforms, ..
+    } = csd.scan().await?;
+    for (name, summary) in transforms {
+        println!(
+            "🔧 Transform {name}: +{} relationships, {} tags, {} summaries",
+            summary.relationships, summary.tags, summary.summaries
+        );
+    }
 
     // Optional: export matrix to additional formats if requested
     if let Some(output_path) = output_file {
@@ -90,7 +704,7 @@ async fn handle_init(
                     matrix.metadata.total_files,
                     matrix.relationships.len(),
                     matrix.external_dependencies.len(),
-                    matrix.metadata.plugins_used.join(", ")
+                    matrix.metadata.plugin_names().join(", ")
                 );
                 tokio::fs::write(&output_path, summary).await?;
                 info!("Matrix summary exported to: {}", output_path.display());
@@ -99,25 +713,82 @@ async fn handle_init(
     }
 
     info!("Project initialized successfully. Use 'csd quality', 'csd docs', or other commands to analyze the matrix.");
+    if matrix.metadata.scan_level == ScanLevel::Structure {
+        info!("This is a structure-level matrix; run 'csd scan --level full' to add elements, relationships and summaries.");
+    }
 
-    Ok(())
+    let stats = &matrix.metadata.scan_stats;
+    Ok(Findings {
+        warnings: stats.failed_files + stats.skipped_deadline + stats.skipped_permission.len(),
+        plugin_errors: stats.plugins.values().map(|p| p.failed).sum(),
+        drift: 0,
+    })
 }
 
 async fn handle_quality(
     matrix: Option<PathBuf>,
-    _metrics: Vec<crate::cli::args::QualityMetric>,
+    suggest: Option<usize>,
+    metrics: Vec<QualityMetric>,
+    view: &MatrixView,
     config: &Config,
-) -> Result<()> {
+) -> Result<Findings> {
     debug!("Analyzing code quality...");
 
-    let matrix_path = matrix.unwrap_or_else(|| PathBuf::from(".csd_cache/matrix.json"));
-
-    if !matrix_path.exists() {
-        return Err(anyhow::anyhow!(
-            "Matrix file not found: {}. Run 'csd init' first.",
-            matrix_path.display()
-        ));
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+    let csd = Csd::new(config.clone())
+        .with_matrix_path(&matrix_path)
+        .with_view(view.clone());
+    csd.quality().await?.print_summary(5);
+    let mut hotspots = csd.hotspots().await?;
+    let mut rules = csd.rules().await?;
+    if let Some(top) = suggest {
+        let client = LlmClient::new(&config.llm, config.offline)?;
+        let matrix = csd.load_view().await?;
+        let drafted = draft_suggestions(&client, &matrix, &mut hotspots, &mut rules, top).await?;
+        if drafted.failed > 0 {
+            warn!(
+                "The LLM gave no usable suggestions for {} of {} findings",
+                drafted.failed, drafted.findings
+            );
+        }
     }
+    hotspots.print_summary();
+    rules.print_summary();
+    // Coverage shows when there is some, or when asked for
+    let coverage_asked = metrics
+        .iter()
+        .any(|metric| matches!(metric, QualityMetric::Coverage | QualityMetric::All));
+    if metrics.is_empty() || coverage_asked {
+        let risk = csd.coverage_risk().await?;
+        if risk.covered_files > 0 || coverage_asked {
+            risk.print_summary();
+        }
+    }
+    // Contracts too, counting as drift from the specs
+    let contracts_asked = metrics
+        .iter()
+        .any(|metric| matches!(metric, QualityMetric::Contracts | QualityMetric::All));
+    let mut contract_drift = 0;
+    if metrics.is_empty() || contracts_asked {
+        let drift = csd.contract_drift().await?;
+        if drift.contracts > 0 || contracts_asked {
+            drift.print_summary();
+            contract_drift = drift.count();
+        }
+    }
+    csd.documentation().await?.print_summary();
+    println!();
+    // Unsuppressed offenders and rule findings above info, and expired suppressions so
+    // they get renewed or fixed
+    let findings = Findings {
+        warnings: hotspots.files_over_limit
+            + hotspots.functions_over_limit
+            + hotspots.expired_suppressions.len()
+            + rules.count(FindingSeverity::Warning)
+            + rules.count(FindingSeverity::Error),
+        plugin_errors: rules.failed.len(),
+        drift: contract_drift,
+    };
 
     // Find quality analysis output plugins
     let quality_plugins = config.find_output_plugins_for_type("quality_report", "json");
@@ -130,7 +801,7 @@ async fn handle_quality(
                 name, plugin_config.output_types, plugin_config.formats
             );
         }
-        return Ok(());
+        return Ok(findings);
     }
 
     println!("Quality analysis functionality will be implemented using output plugins:");
@@ -141,18 +812,31 @@ async fn handle_quality(
     // TODO: Implement quality analysis using output plugins
     println!("Quality analysis functionality will be implemented here");
 
-    Ok(())
+    Ok(findings)
+}
+
+fn docs_scope(scope: Vec<String>, tags: Vec<String>, depth: usize) -> OutputScope {
+    OutputScope {
+        tags,
+        depth,
+        ..OutputScope::from_patterns(scope)
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_docs(
     matrix: Option<PathBuf>,
     format: crate::cli::args::DocFormat,
     output_dir: Option<PathBuf>,
+    resume: bool,
+    scope: OutputScope,
+    view: &MatrixView,
+    stdout: bool,
     config: &Config,
-) -> Result<()> {
+) -> Result<Findings> {
     debug!("Generating documentation...");
 
-    let matrix_path = matrix.unwrap_or_else(|| PathBuf::from(".csd_cache/matrix.json"));
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
     let output_directory = output_dir.unwrap_or_else(|| PathBuf::from(&config.output_dir));
 
     if !matrix_path.exists() {
@@ -173,207 +857,1805 @@ async fn handle_docs(
     let doc_plugins = config.find_output_plugins_for_type("documentation", format_str);
 
     if doc_plugins.is_empty() {
-        println!("No documentation plugins found for format '{format_str}'. Available plugins:");
+        let mut message =
+            format!("No documentation plugins found for format '{format_str}'. Available plugins:");
         for (name, plugin_config) in config.get_enabled_output_plugins() {
             if plugin_config
                 .output_types
                 .contains(&"documentation".to_string())
             {
-                println!("  {} - Formats: {:?}", name, plugin_config.formats);
+                message.push_str(&format!(
+                    "\n  {} - Formats: {:?}",
+                    name, plugin_config.formats
+                ));
             }
         }
-        return Ok(());
-    }
-
-    info!("Generating documentation using plugins: {doc_plugins:?}");
-
-    // Use the first available plugin for now
-    let plugin_name = &doc_plugins[0];
-    let plugin_config = config.get_output_plugin(plugin_name).unwrap();
-
-    // Create the output directory
-    tokio::fs::create_dir_all(&output_directory).await?;
-
-    // Set up plugin communication
-    use crate::plugins::communication::OutputPluginCommunicator;
-
-    // Resolve plugin path with the new plugin_type structure
-    let plugin_path = match &plugin_config.source {
-        crate::utils::config::PluginSource::Builtin { name, plugin_type } => {
-            PathBuf::from(format!("plugins/output/{plugin_type}/{name}.py"))
-        }
-        crate::utils::config::PluginSource::Local { path } => PathBuf::from(path),
-        _ => {
-            return Err(anyhow::anyhow!(
-                "Plugin source type not yet supported: {:?}",
-                plugin_config.source
-            ));
+        // With --stdout, stdout is reserved for the document
+        if stdout {
+            eprintln!("{message}");
+        } else {
+            println!("{message}");
         }
-    };
-
-    if !plugin_path.exists() {
-        return Err(anyhow::anyhow!(
-            "Output plugin file not found: {}",
-            plugin_path.display()
-        ));
+        // Nothing was generated
+        return Ok(Findings {
+            warnings: 1,
+            ..Findings::default()
+        });
     }
 
-    // Create plugin input
-    let plugin_input = OutputPluginInput {
-        matrix_path: matrix_path.clone(),
-        project_root: std::env::current_dir()?,
-        output_dir: output_directory.clone(),
-        cache_dir: ".csd_cache".to_string(),
-        plugin_config: plugin_config
-            .config
-            .as_ref()
-            .map(|v| serde_json::to_value(v).unwrap_or(serde_json::Value::Null)),
-        format_options: serde_json::json!({
-            "format": format_str,
-            "output_type": "documentation"
-        }),
-    };
-
-    // Create and configure communicator
-    let mut communicator =
-        OutputPluginCommunicator::new(plugin_path).with_cache_dir(PathBuf::from(".csd_cache"));
+    let csd = Csd::new(config.clone())
+        .with_matrix_path(&matrix_path)
+        .with_output_dir(&output_directory)
+        .with_resume(resume)
+        .with_scope(scope)
+        .with_view(view.clone());
 
-    if let Some(ref python_exe) = config.python_executable {
-        communicator = communicator.with_python_executable(python_exe.clone());
-    } else {
-        communicator = communicator.with_python_auto_detect();
+    if stdout {
+        use std::io::Write;
+        let (result, content) = csd
+            .generate_document(format_str)
+            .await
+            .map_err(|e| ExitError::new(ExitCode::Plugin, format!("{e:#}")))?;
+        let mut out = std::io::stdout().lock();
+        out.write_all(&content)?;
+        out.flush()?;
+        eprintln!(
+            "📚 Documentation generated by {} v{}",
+            result.plugin_name, result.plugin_version
+        );
+        let issues: Vec<&String> = result.outputs.iter().flat_map(|o| &o.issues).collect();
+        for issue in &issues {
+            eprintln!("   ⚠️  {issue}");
+        }
+        return Ok(Findings {
+            warnings: issues.len(),
+            ..Findings::default()
+        });
     }
 
-    // Generate documentation
-    match communicator.generate(plugin_input).await {
-        Ok(result) => {
-            info!("Documentation generated successfully!");
-            println!(
+    // Generate documentation, once per configured language
+    let report = csd
+        .generate_docs(format_str)
+        .await
+        .map_err(|e| ExitError::new(ExitCode::Plugin, format!("{e:#}")))?;
+    info!("Documentation generated successfully!");
+    for pass in &report.passes {
+        let result = &pass.result;
+        match pass.language {
+            Some(ref language) => println!(
+                "📚 Documentation ({language}) generated by {} v{}",
+                result.plugin_name, result.plugin_version
+            ),
+            None => println!(
                 "📚 Documentation generated by {} v{}",
                 result.plugin_name, result.plugin_version
-            );
-            println!("📁 Output directory: {}", output_directory.display());
-            println!("📄 Generated {} files:", result.outputs.len());
+            ),
+        }
+        println!("📁 Output directory: {}", pass.output_dir.display());
+        println!("📄 Generated {} files:", result.outputs.len());
 
-            for output in &result.outputs {
-                let size_kb = output.size_bytes as f64 / 1024.0;
-                println!(
-                    "   {} ({:.1} KB) - {}",
-                    output.output_path.display(),
-                    size_kb,
-                    output.content_type
-                );
+        for output in &result.outputs {
+            let size_kb = output.size_bytes as f64 / 1024.0;
+            println!(
+                "   {} ({:.1} KB) - {}",
+                output.output_path.display(),
+                size_kb,
+                output.content_type
+            );
+            for issue in &output.issues {
+                println!("     ⚠️  {issue}");
             }
-
-            println!("⏱️  Processing time: {}ms", result.processing_time_ms);
         }
-        Err(e) => {
-            return Err(anyhow::anyhow!("Documentation generation failed: {}", e));
+
+        if let Some(ref summary) = pass.write_back {
+            println!(
+                "🔧 Wrote back to the matrix: +{} relationships, {} tags, {} summaries ({} skipped)",
+                summary.relationships, summary.tags, summary.summaries, summary.skipped
+            );
         }
+        println!("⏱️  Processing time: {}ms", result.processing_time_ms);
     }
+    println!("🗂️  Manifest: {}", report.manifest_path.display());
 
-    Ok(())
+    Ok(Findings {
+        warnings: report
+            .passes
+            .iter()
+            .flat_map(|pass| &pass.result.outputs)
+            .map(|output| output.issues.len())
+            .sum(),
+        ..Findings::default()
+    })
 }
 
-async fn handle_plugins(detailed: bool, config: &Config) -> Result<()> {
-    debug!("Listing available plugins...");
+async fn handle_docs_drift(
+    matrix: Option<PathBuf>,
+    porcelain: bool,
+    config: &Config,
+) -> Result<Findings> {
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+    let report = Csd::new(config.clone())
+        .with_matrix_path(&matrix_path)
+        .check_doc_drift()
+        .await?;
 
-    let plugin_manager = PluginManager::new(config.clone());
-    let plugins = plugin_manager.discover_plugins().await?;
+    if porcelain {
+        print_records(porcelain::doc_drift_records(&report));
+    } else if report.stale.is_empty() {
+        println!(
+            "📚 {} references in {} markdown files all resolve",
+            report.references_checked, report.docs_checked
+        );
+    } else {
+        println!(
+            "📚 {} of {} references in {} markdown files no longer resolve:",
+            report.stale.len(),
+            report.references_checked,
+            report.docs_checked
+        );
+        for stale in &report.stale {
+            println!(
+                "   {}:{}: {} `{}`",
+                stale.doc.display(),
+                stale.line,
+                stale.kind,
+                stale.reference
+            );
+        }
+    }
 
-    if detailed {
-        println!("=== Input Plugins (Code Analyzers) ===");
-        let input_plugins: Vec<_> = plugins
-            .iter()
-            .filter(|p| p.plugin_type == "input")
-            .collect();
+    Ok(Findings {
+        drift: report.stale.len(),
+        ..Findings::default()
+    })
+}
 
-        if input_plugins.is_empty() {
-            println!("No input plugins configured.");
-        } else {
-            for plugin in input_plugins {
-                println!("Plugin: {}", plugin.name);
-                println!("  Type: Input (Code Analyzer)");
-                println!("  Path: {}", plugin.path.display());
-                println!("  Extensions: {}", plugin.extensions.join(", "));
-                println!("  Filenames: {}", plugin.filenames.join(", "));
-                println!("  Source: {:?}", plugin.source);
-                println!("  Enabled: {}", plugin.enabled);
-                println!();
-            }
-        }
+async fn handle_changelog(
+    since: String,
+    matrix: Option<PathBuf>,
+    output_dir: Option<PathBuf>,
+    polish: bool,
+    config: &Config,
+) -> Result<()> {
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+    if !matrix_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Matrix file not found: {}. Run 'csd init' first.",
+            matrix_path.display()
+        ));
+    }
+    let output_directory = output_dir.unwrap_or_else(|| PathBuf::from(&config.output_dir));
 
-        println!("=== Output Plugins (Documentation Generators, etc.) ===");
-        let output_plugins: Vec<_> = plugins
-            .iter()
-            .filter(|p| p.plugin_type == "output")
-            .collect();
+    let report = Csd::new(config.clone())
+        .with_matrix_path(&matrix_path)
+        .with_output_dir(&output_directory)
+        .generate_changelog(&since, polish)
+        .await?;
 
-        if output_plugins.is_empty() {
-            println!("No output plugins configured.");
-        } else {
-            for plugin in output_plugins {
-                println!("Plugin: {}", plugin.name);
-                println!("  Type: Output (Generator)");
-                println!("  Path: {}", plugin.path.display());
-                println!("  Output Types: {}", plugin.output_types.join(", "));
-                println!("  Formats: {}", plugin.formats.join(", "));
-                println!("  Source: {:?}", plugin.source);
-                println!("  Enabled: {}", plugin.enabled);
-                println!();
-            }
-        }
+    let changelog = &report.changelog;
+    if changelog.is_empty() {
+        println!("📝 No changes since {since}");
     } else {
-        println!("Input Plugins:");
-        for plugin in plugins.iter().filter(|p| p.plugin_type == "input") {
-            let all_patterns: Vec<String> = plugin
-                .extensions
-                .iter()
-                .chain(plugin.filenames.iter())
-                .cloned()
-                .collect();
-            println!("  {} - {}", plugin.name, all_patterns.join(", "));
-        }
-
-        println!("\nOutput Plugins:");
-        for plugin in plugins.iter().filter(|p| p.plugin_type == "output") {
+        println!(
+            "📝 Changes since {since} in {} components:",
+            changelog.components.len()
+        );
+        for component in &changelog.components {
+            let breaking = if component.is_breaking() {
+                " (breaking)"
+            } else {
+                ""
+            };
             println!(
-                "  {} - Types: {}, Formats: {}",
-                plugin.name,
-                plugin.output_types.join(","),
-                plugin.formats.join(",")
+                "   {}: {} commits, {} API changes, {} files{breaking}",
+                component.component,
+                component.commits.len(),
+                component.api_added.len()
+                    + component.api_removed.len()
+                    + component.api_changed.len(),
+                component.files_added.len()
+                    + component.files_removed.len()
+                    + component.files_modified.len()
             );
         }
     }
-
-    // Show configuration summary
-    let summary = config.get_plugin_summary();
-    println!("\n📊 Plugin Summary:");
-    println!(
-        "  Input plugins: {} enabled / {} total",
-        summary.enabled_input_plugins, summary.total_input_plugins
-    );
-    println!(
-        "  Output plugins: {} enabled / {} total",
-        summary.enabled_output_plugins, summary.total_output_plugins
-    );
-
+    for output in &report.outputs {
+        println!("📄 {}", output.output_path.display());
+    }
+    println!("🗂️  Manifest: {}", report.manifest_path.display());
     Ok(())
 }
 
-async fn handle_config(force: bool) -> Result<()> {
-    debug!("Initializing configuration...");
-
-    let config_path = PathBuf::from(".csdrc.yaml");
+async fn handle_stats(
+    matrix: Option<PathBuf>,
+    output: crate::cli::args::OutputFormat,
+    top: usize,
+    porcelain: bool,
+    view: &MatrixView,
+    config: &Config,
+) -> Result<()> {
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
 
-    if config_path.exists() && !force {
+    if !matrix_path.exists() {
         return Err(anyhow::anyhow!(
-            "Configuration file already exists. Use --force to overwrite."
+            "Matrix file not found: {}. Run 'csd init' first.",
+            matrix_path.display()
         ));
     }
 
-    let default_config = Config::default();
-    default_config.save(&config_path).await?;
+    let mut matrix = view.apply(ProjectMatrix::load(&matrix_path).await?)?;
+    let analysis = GraphAnalysis::analyze(&mut matrix);
+    if porcelain {
+        print_records(porcelain::stats_records(&matrix, &analysis, top));
+        return Ok(());
+    }
+
+    // Machine-readable output carries the category rollups next to the graph metrics
+    #[derive(serde::Serialize)]
+    struct StatsReport<'a> {
+        #[serde(flatten)]
+        analysis: &'a GraphAnalysis,
+        categories: BTreeMap<FileCategory, CategoryRollup>,
+    }
+    let report = StatsReport {
+        analysis: &analysis,
+        categories: matrix.category_rollups(),
+    };
+
+    match output {
+        crate::cli::args::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?)
+        }
+        crate::cli::args::OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&report)?),
+        crate::cli::args::OutputFormat::Pretty => {
+            matrix.print_summary();
+            analysis.print_summary(top);
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_query(
+    matrix: Option<PathBuf>,
+    filter: RelationshipFilter,
+    output: crate::cli::args::OutputFormat,
+    porcelain: bool,
+    view: &MatrixView,
+    config: &Config,
+) -> Result<()> {
+    debug!("Querying matrix relationships...");
+
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+
+    if !matrix_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Matrix file not found: {}. Run 'csd init' first.",
+            matrix_path.display()
+        ));
+    }
+
+    // Queries only touch edges, so leave file nodes unparsed unless their tags matter
+    let (handle, full);
+    let relationships = if view.is_empty() {
+        handle = ProjectMatrix::open(&matrix_path).await?;
+        handle.query_relationships(&filter)
+    } else {
+        full = view.apply(ProjectMatrix::load(&matrix_path).await?)?;
+        full.query_relationships(&filter)
+    };
+    if porcelain {
+        print_records(porcelain::relationship_records(&relationships));
+        return Ok(());
+    }
+
+    match output {
+        crate::cli::args::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&relationships)?)
+        }
+        crate::cli::args::OutputFormat::Yaml => {
+            print!("{}", serde_yaml::to_string(&relationships)?)
+        }
+        crate::cli::args::OutputFormat::Pretty => {
+            let endpoint = |file: &Path, element: &Option<String>| match element {
+                Some(element) => format!("{}::{element}", file.display()),
+                None => file.display().to_string(),
+            };
+            for r in &relationships {
+                println!(
+                    "{} -> {} ({:?}, strength {:.2}, from {})",
+                    endpoint(&r.from_file, &r.from_element),
+                    endpoint(&r.to_file, &r.to_element),
+                    r.relationship_type,
+                    r.strength,
+                    r.source_plugin.as_deref().unwrap_or("unknown")
+                );
+            }
+            println!("{} relationships matched", relationships.len());
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_bench(
+    path: Option<PathBuf>,
+    runs: u32,
+    record: bool,
+    output: crate::cli::args::OutputFormat,
+    config: &Config,
+) -> Result<()> {
+    let root = path.unwrap_or_else(|| PathBuf::from("."));
+    let scanner = ProjectScanner::new(config.clone()).with_root(&root);
+
+    let mut fastest: Option<BenchResult> = None;
+    for run in 1..=runs {
+        let result = scanner.bench().await?;
+        debug!("Benchmark run {run}/{runs}: {:.1} ms", result.total_ms());
+        if fastest
+            .as_ref()
+            .is_none_or(|best| result.total_ms() < best.total_ms())
+        {
+            fastest = Some(result);
+        }
+    }
+    let result = fastest.ok_or_else(|| anyhow::anyhow!("At least one run is required"))?;
+
+    let history = BenchHistory::new(&config.resolve_cache_dir(&root));
+    let report = BenchReport::new(result, &history.load().await?);
+    if record {
+        history.append(&report.result).await?;
+    }
+
+    match output {
+        crate::cli::args::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?)
+        }
+        crate::cli::args::OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&report)?),
+        crate::cli::args::OutputFormat::Pretty => report.print_summary(),
+    }
+    Ok(())
+}
+
+async fn handle_trend(
+    format: crate::cli::args::TrendFormat,
+    limit: Option<usize>,
+    output_file: Option<PathBuf>,
+    config: &Config,
+) -> Result<()> {
+    debug!("Loading scan history...");
+
+    let history = MetricsHistory::new(&config.resolve_cache_dir(Path::new(".")));
+    let mut snapshots = history.load().await?;
+
+    if snapshots.is_empty() {
+        println!(
+            "No scan history found at {}. Run 'csd init' to record a scan.",
+            history.path().display()
+        );
+        return Ok(());
+    }
+
+    if let Some(limit) = limit {
+        let skip = snapshots.len().saturating_sub(limit);
+        snapshots.drain(..skip);
+    }
+
+    let rendered = match format {
+        crate::cli::args::TrendFormat::Csv => snapshots_to_csv(&snapshots),
+        crate::cli::args::TrendFormat::Json => serde_json::to_string_pretty(&snapshots)? + "\n",
+        crate::cli::args::TrendFormat::Table => {
+            let mut table = format!(
+                "{:<20} {:>7} {:>10} {:>9} {:>6} {:>9} {:>10}\n",
+                "Scanned", "Files", "Tokens", "Edges", "Deps", "Coupling", "Complexity"
+            );
+            for s in &snapshots {
+                table.push_str(&format!(
+                    "{:<20} {:>7} {:>10} {:>9} {:>6} {:>9} {:>10}\n",
+                    s.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                    s.total_files,
+                    s.total_tokens,
+                    s.total_relationships,
+                    s.external_dependencies,
+                    s.max_coupling,
+                    s.average_complexity
+                        .map(|c| format!("{c:.2}"))
+                        .unwrap_or_else(|| "-".to_string())
+                ));
+            }
+            if let (Some(first), Some(last)) = (snapshots.first(), snapshots.last()) {
+                table.push_str(&format!(
+                    "\nChange over {} scans: files {:+}, tokens {:+}, edges {:+}, deps {:+}\n",
+                    snapshots.len(),
+                    last.total_files as i64 - first.total_files as i64,
+                    last.total_tokens as i64 - first.total_tokens as i64,
+                    last.total_relationships as i64 - first.total_relationships as i64,
+                    last.external_dependencies as i64 - first.external_dependencies as i64
+                ));
+            }
+            table
+        }
+    };
+
+    match output_file {
+        Some(path) => {
+            tokio::fs::write(&path, rendered).await?;
+            info!("Trend exported to: {}", path.display());
+        }
+        None => print!("{rendered}"),
+    }
+
+    Ok(())
+}
+
+fn handle_explain(path: &Path, config: &Config) -> Result<()> {
+    let explanation = ProjectScanner::new(config.clone()).explain(path);
+
+    println!("📄 {}", path.display());
+    if let Some(ref relative_path) = explanation.relative_path {
+        println!("  Matrix path: {}", relative_path.display());
+    }
+    match explanation.exclusion {
+        None => println!("  ✅ Scanned"),
+        Some(ref exclusion) => println!("  ❌ Not scanned: {exclusion}"),
+    }
+
+    match explanation.candidates.as_slice() {
+        [] => println!(
+            "  No input plugin matches; it would be recorded without analysis as {}",
+            if explanation.is_text {
+                "text"
+            } else {
+                "binary"
+            }
+        ),
+        [plugin] => println!("  Plugin: {plugin}"),
+        candidates => println!(
+            "  Plugins: {} (routed by can_analyze confidence, {} by pattern order)",
+            candidates.join(", "),
+            explanation.plugin.as_deref().unwrap_or_default()
+        ),
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_export(
+    matrix: Option<PathBuf>,
+    output_file: Option<PathBuf>,
+    output: crate::cli::args::OutputFormat,
+    format: ExportFormat,
+    level: AdjacencyLevel,
+    filter: RelationshipFilter,
+    language: Option<&str>,
+    redaction: Option<RedactionConfig>,
+    view: &MatrixView,
+    config: &Config,
+) -> Result<()> {
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+
+    if !matrix_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Matrix file not found: {}. Run 'csd init' first.",
+            matrix_path.display()
+        ));
+    }
+
+    let mut matrix = view.apply(ProjectMatrix::load(&matrix_path).await?)?;
+    if let Some(language) = language {
+        let subset = matrix.language_subset(language);
+        if subset.files.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No {language} files in the matrix (languages: {})",
+                matrix.languages().join(", ")
+            ));
+        }
+        info!(
+            "Kept {} of {} files in {language}",
+            subset.files.len(),
+            matrix.files.len()
+        );
+        matrix = subset;
+    }
+    let removed = matrix.retain_relationships(&filter);
+    if removed > 0 {
+        info!("Filtered out {removed} relationships");
+    }
+    if let Some(mut rules) = redaction {
+        if rules.hash_paths && rules.salt.is_none() {
+            rules.salt = Some(cached_salt(&config.resolve_cache_dir(Path::new("."))).await?);
+        }
+        let summary = redact_matrix(&mut matrix, &rules)?;
+        info!(
+            "Redacted {} summaries, {} signatures, {} metadata entries, {} paths",
+            summary.summaries, summary.signatures, summary.metadata, summary.hashed_paths
+        );
+    }
+
+    if format == ExportFormat::Parquet {
+        let Some(path) = output_file else {
+            return Err(anyhow::anyhow!("Parquet export needs --output-file"));
+        };
+        AdjacencyMatrix::from_matrix(&matrix, level).write_parquet(&path)?;
+        println!("📦 Exported adjacency matrix to {}", path.display());
+        return Ok(());
+    }
+
+    let rendered = match (format, output) {
+        (ExportFormat::AdjacencyCsv, _) => AdjacencyMatrix::from_matrix(&matrix, level).to_csv(),
+        (ExportFormat::DsmCsv, _) => {
+            Dsm::from_adjacency(&AdjacencyMatrix::from_matrix(&matrix, level)).to_csv()
+        }
+        (ExportFormat::DsmHtml, _) => {
+            // Both views go in the page; --directories picks the one shown first
+            let files =
+                Dsm::from_adjacency(&AdjacencyMatrix::from_matrix(&matrix, AdjacencyLevel::File));
+            let directories = Dsm::from_adjacency(&AdjacencyMatrix::from_matrix(
+                &matrix,
+                AdjacencyLevel::Directory,
+            ));
+            let mut views = vec![("files", &files), ("directories", &directories)];
+            if level == AdjacencyLevel::Directory {
+                views.reverse();
+            }
+            let title = std::path::absolute(&matrix.metadata.project_root)
+                .ok()
+                .and_then(|root| root.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_else(|| "Project".to_string());
+            render_dsm_html(&title, &views)?
+        }
+        (_, crate::cli::args::OutputFormat::Json) => serde_json::to_string_pretty(&matrix)? + "\n",
+        (_, crate::cli::args::OutputFormat::Yaml) => serde_yaml::to_string(&matrix)?,
+        (_, crate::cli::args::OutputFormat::Pretty) => {
+            return Err(anyhow::anyhow!("Export supports json or yaml output"))
+        }
+    };
+
+    match output_file {
+        Some(path) => {
+            tokio::fs::write(&path, rendered).await?;
+            let what = match format {
+                ExportFormat::Matrix => "matrix",
+                ExportFormat::AdjacencyCsv | ExportFormat::Parquet => "adjacency matrix",
+                ExportFormat::DsmHtml | ExportFormat::DsmCsv => "design structure matrix",
+            };
+            println!("📦 Exported {what} to {}", path.display());
+        }
+        None => print!("{rendered}"),
+    }
+
+    Ok(())
+}
+
+async fn handle_matrix_lint(
+    matrix: Option<PathBuf>,
+    fix: bool,
+    output: crate::cli::args::OutputFormat,
+    config: &Config,
+) -> Result<()> {
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+
+    if !matrix_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Matrix file not found: {}. Run 'csd init' first.",
+            matrix_path.display()
+        ));
+    }
+
+    let mut matrix = ProjectMatrix::load(&matrix_path).await.map_err(|e| {
+        anyhow::anyhow!("{} is not a readable matrix: {e:#}", matrix_path.display())
+    })?;
+    let report = if fix {
+        let report = fix_matrix(&mut matrix);
+        if report.fixed > 0 {
+            matrix.save(&matrix_path).await?;
+            info!("Fixed {} issues in {}", report.fixed, matrix_path.display());
+        }
+        report
+    } else {
+        lint_matrix(&matrix)
+    };
+
+    match output {
+        crate::cli::args::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?)
+        }
+        crate::cli::args::OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&report)?),
+        crate::cli::args::OutputFormat::Pretty => report.print_summary(),
+    }
+
+    if !report.is_clean() {
+        return Err(ExitError::new(
+            ExitCode::QualityGate,
+            format!("{} matrix issues", report.issues.len()),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+async fn handle_matrix_compact(
+    matrix: Option<PathBuf>,
+    options: &CompactOptions,
+    output: crate::cli::args::OutputFormat,
+    config: &Config,
+) -> Result<()> {
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+
+    if !matrix_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Matrix file not found: {}. Run 'csd init' first.",
+            matrix_path.display()
+        ));
+    }
+
+    let bytes_before = tokio::fs::metadata(&matrix_path).await?.len();
+    let mut matrix = ProjectMatrix::load(&matrix_path).await?;
+    let mut summary = compact_matrix(&mut matrix, options);
+    // The original is kept as a backup
+    matrix.save(&matrix_path).await?;
+    summary.bytes_before = bytes_before;
+    summary.bytes_after = tokio::fs::metadata(&matrix_path).await?.len();
+
+    match output {
+        crate::cli::args::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&summary)?)
+        }
+        crate::cli::args::OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&summary)?),
+        crate::cli::args::OutputFormat::Pretty => summary.print_summary(),
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_graph(
+    matrix: Option<PathBuf>,
+    focus: &Path,
+    depth: usize,
+    format: GraphFormat,
+    output_file: Option<PathBuf>,
+    filter: RelationshipFilter,
+    view: &MatrixView,
+    config: &Config,
+) -> Result<()> {
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+
+    if !matrix_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Matrix file not found: {}. Run 'csd init' first.",
+            matrix_path.display()
+        ));
+    }
+
+    // Filter first, so the neighborhood only grows along the edges asked for
+    let mut matrix = view.apply(ProjectMatrix::load(&matrix_path).await?)?;
+    matrix.retain_relationships(&filter);
+    let graph = FocusGraph::new(&matrix, focus, depth)?;
+    info!(
+        "Focus on {} files; {} files and {} relationships within {depth} levels",
+        graph.focus.len(),
+        graph.matrix.files.len(),
+        graph.matrix.relationships.len()
+    );
+
+    match (format, output_file) {
+        (GraphFormat::Dot, None) => print!("{}", graph.to_dot()),
+        (GraphFormat::Dot, Some(path)) => {
+            tokio::fs::write(&path, graph.to_dot()).await?;
+            println!("📦 Exported focus graph to {}", path.display());
+        }
+        (GraphFormat::Matrix, None) => {
+            println!("{}", serde_json::to_string_pretty(&graph.matrix)?)
+        }
+        (GraphFormat::Matrix, Some(path)) => {
+            graph.matrix.save(&path).await?;
+            println!("📦 Exported subset matrix to {}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_api_extract(
+    matrix: Option<PathBuf>,
+    output_file: Option<PathBuf>,
+    config: &Config,
+) -> Result<()> {
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+
+    if !matrix_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Matrix file not found: {}. Run 'csd init' first.",
+            matrix_path.display()
+        ));
+    }
+
+    let surface = ApiSurface::from_matrix(&ProjectMatrix::load(&matrix_path).await?);
+
+    match output_file {
+        Some(path) => {
+            surface.save(&path).await?;
+            println!(
+                "📦 Extracted {} public API items to {}",
+                surface.items.len(),
+                path.display()
+            );
+        }
+        None => println!("{}", serde_json::to_string_pretty(&surface)?),
+    }
+
+    Ok(())
+}
+
+/// A project `csd daemon` scans
+struct DaemonProject {
+    state: Arc<ProjectState>,
+    root: PathBuf,
+    config: Config,
+    // Registered git projects are fetched before every scan
+    remote: bool,
+    next_scan: Instant,
+    scanned_commit: Option<String>,
+}
+
+impl DaemonProject {
+    fn new(id: &str, root: PathBuf, config: Config, remote: bool) -> Self {
+        let cache_dir = config.resolve_cache_dir(&root);
+        Self {
+            state: Arc::new(
+                ProjectState::new(id, cache_dir.join("matrix.json"), cache_dir)
+                    .with_root(root.clone()),
+            ),
+            root,
+            config,
+            remote,
+            next_scan: Instant::now(),
+            scanned_commit: None,
+        }
+    }
+}
+
+/// Scan each project now and every `interval` after, and also when `commit_poll` finds
+/// new commits, until interrupted. Without a registry the project is the current
+/// directory. Failed scans are reported and the daemon carries on.
+async fn handle_daemon(
+    interval: Duration,
+    commit_poll: Option<Duration>,
+    listen: Option<SocketAddr>,
+    registry: Option<Option<PathBuf>>,
+    fail_on: &[FailOn],
+    config: &Config,
+) -> Result<()> {
+    let registry = match registry {
+        Some(path) => Some(ProjectRegistry::load(&registry_path(path)?)?),
+        None => None,
+    };
+    let mut projects = match registry {
+        None => vec![DaemonProject::new(
+            "default",
+            PathBuf::from("."),
+            config.clone(),
+            false,
+        )],
+        Some(ref registry) => {
+            if registry.projects.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "No projects in {}; register one with 'csd registry add'",
+                    registry.path().display()
+                ));
+            }
+            let mut projects = Vec::new();
+            for project in &registry.projects {
+                let root = registry.update(project, config.offline);
+                let loaded = match root {
+                    Ok(root) => project_config(project, &root)
+                        .await
+                        .map(|project_config| (root, project_config)),
+                    Err(e) => Err(e),
+                };
+                match loaded {
+                    Ok((root, mut project_config)) => {
+                        project_config.offline |= config.offline;
+                        projects.push(DaemonProject::new(
+                            &project.id,
+                            root,
+                            project_config,
+                            project.is_remote(),
+                        ));
+                    }
+                    Err(e) => warn!("Skipping project '{}': {e:#}", project.id),
+                }
+            }
+            projects
+        }
+    };
+
+    let auth = Authenticator::from_config(&config.api);
+    if let Some(addr) = listen {
+        if auth.is_open() && !addr.ip().is_loopback() {
+            return Err(anyhow::anyhow!(
+                "Serving on {addr} without API tokens would expose the matrix to anyone \
+                 who can reach it; configure api.tokens or set {API_TOKEN_ENV}, or listen \
+                 on a loopback address"
+            ));
+        }
+    }
+    let state = Arc::new(
+        ServerState::new(
+            projects
+                .iter()
+                .map(|project| project.state.clone())
+                .collect(),
+        )
+        .with_auth(auth),
+    );
+    let server = match listen {
+        Some(addr) => {
+            let server = ApiServer::bind(addr, state.clone())?;
+            println!("🌐 HTTP API listening on http://{}", server.local_addr()?);
+            Some(tokio::spawn(server.run()))
+        }
+        None => None,
+    };
+    if commit_poll.is_some() && config.offline {
+        warn!("Offline mode: not fetching, so only commits made in a checkout trigger scans");
+    }
+
+    let shutdown = tokio::signal::ctrl_c();
+    tokio::pin!(shutdown);
+    'daemon: loop {
+        for project in projects.iter_mut() {
+            // A scan requested over the API counts as due now
+            let requested = project.state.rescan.swap(false, Ordering::SeqCst);
+            let due = requested || Instant::now() >= project.next_scan;
+            // Only checkouts the daemon cloned are moved; a local project's new commits
+            // are picked up from its HEAD as they are
+            if project.remote && (commit_poll.is_some() || due) {
+                update_checkout(registry.as_ref(), project);
+            }
+            let new_commits = commit_poll.is_some()
+                && project.scanned_commit.is_some()
+                && head_commit(&project.root) != project.scanned_commit;
+            if new_commits || due {
+                tokio::select! {
+                    _ = daemon_scan(project, interval, fail_on) => {}
+                    _ = &mut shutdown => break 'daemon,
+                }
+            }
+        }
+
+        let now = Instant::now();
+        let wait = projects
+            .iter()
+            .map(|project| project.next_scan.saturating_duration_since(now))
+            .min()
+            .unwrap_or(interval);
+        let wait = commit_poll.map_or(wait, |poll| wait.min(poll));
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = state.wake.notified() => {}
+            _ = &mut shutdown => break,
+        }
+    }
+
+    println!("Stopping the daemon");
+    if let Some(server) = server {
+        server.abort();
+    }
+    Ok(())
+}
+
+// Fetch a registered git project's checkout and fast-forward it onto its upstream
+fn update_checkout(registry: Option<&ProjectRegistry>, project: &DaemonProject) {
+    let id = &project.state.id;
+    let Some((registry, registered)) =
+        registry.and_then(|registry| Some((registry, registry.get(id)?)))
+    else {
+        return;
+    };
+    if let Err(e) = registry.update(registered, project.config.offline) {
+        warn!("Could not update the checkout of '{id}': {e:#}");
+    }
+}
+
+async fn daemon_scan(project: &mut DaemonProject, interval: Duration, fail_on: &[FailOn]) {
+    let state = project.state.clone();
+    state.status.write().await.scanning = true;
+    let started_at = Utc::now();
+    let started = Instant::now();
+    // Kept to tell what the scan changed
+    let previous = ProjectMatrix::load(&state.matrix_path).await.ok();
+    let outcome = handle_init(
+        Some(project.root.clone()),
+        Vec::new(),
+        crate::cli::args::OutputFormat::Pretty,
+        None,
+        false,
+        false,
+        &project.config,
+    )
+    .await;
+    match (&outcome, previous) {
+        (Ok(_), Some(previous)) => record_events(project, previous).await,
+        (Ok(_), None) => {}
+        (Err(e), _) => warn!("Scheduled scan of '{}' failed: {e:#}", state.id),
+    }
+    let artifacts = vec![("Matrix".to_string(), state.matrix_path.clone())];
+    notify_finished(
+        &project.config,
+        NotifyCommand::Scan,
+        &project.root,
+        started,
+        &outcome,
+        fail_on,
+        artifacts,
+    )
+    .await;
+
+    let (success, gate) = gate_status(&outcome, fail_on);
+    project.scanned_commit = head_commit(&project.root);
+    project.next_scan = Instant::now() + interval;
+    let mut status = state.status.write().await;
+    status.scanning = false;
+    status.scans += 1;
+    status.last_scan = Some(ScanRun {
+        started_at,
+        finished_at: Utc::now(),
+        success,
+        gate,
+        commit: project.scanned_commit.clone(),
+    });
+    status.next_scan = chrono::Duration::from_std(interval)
+        .ok()
+        .map(|interval| Utc::now() + interval);
+    println!(
+        "🕒 Next scheduled scan of {} at {}",
+        state.id,
+        status
+            .next_scan
+            .map(|at| at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string())
+            .unwrap_or_default()
+    );
+}
+
+// Log the architectural changes since the previous scan and post them to the webhooks
+// subscribed to events
+async fn record_events(project: &DaemonProject, mut previous: ProjectMatrix) {
+    let state = &project.state;
+    let mut current = match ProjectMatrix::load(&state.matrix_path).await {
+        Ok(current) => current,
+        Err(e) => {
+            warn!("Could not compare the scan of '{}': {e:#}", state.id);
+            return;
+        }
+    };
+    let timestamp = current.metadata.scan_timestamp;
+    let events: Vec<MatrixEvent> = detect_changes(&mut previous, &mut current)
+        .into_iter()
+        .map(|change| MatrixEvent::new(timestamp, &state.id, change))
+        .collect();
+    if events.is_empty() {
+        return;
+    }
+
+    println!(
+        "📣 {} architectural change(s) in {}:",
+        events.len(),
+        state.id
+    );
+    for event in &events {
+        println!("   {}", event.summary);
+    }
+    if let Err(e) = EventLog::new(&state.cache_dir).append(&events).await {
+        warn!("Could not record the events of '{}': {e:#}", state.id);
+    }
+    match Notifier::new(project.config.offline) {
+        Ok(notifier) => {
+            notifier
+                .notify_events(&project.config.notifications, &state.id, &events)
+                .await
+        }
+        Err(e) => warn!("Events not sent: {e:#}"),
+    }
+}
+
+/// `--registry FILE`, or `registry.yaml` in the user config directory
+fn registry_path(path: Option<PathBuf>) -> Result<PathBuf> {
+    path.or_else(ProjectRegistry::default_path).ok_or_else(|| {
+        anyhow::anyhow!("No user configuration directory for the registry; pass --registry=FILE")
+    })
+}
+
+async fn handle_registry(file: Option<PathBuf>, action: RegistryAction) -> Result<()> {
+    let mut registry = ProjectRegistry::load(&registry_path(file)?)?;
+    match action {
+        RegistryAction::Add {
+            id,
+            source,
+            branch,
+            config,
+        } => {
+            registry.add(RegisteredProject {
+                id: id.clone(),
+                source,
+                branch,
+                config,
+            })?;
+            registry.save()?;
+            println!("✅ Registered '{id}' in {}", registry.path().display());
+        }
+        RegistryAction::List => {
+            if registry.projects.is_empty() {
+                println!("No projects registered in {}", registry.path().display());
+            }
+            for project in &registry.projects {
+                let branch = project
+                    .branch
+                    .as_ref()
+                    .map(|branch| format!(" ({branch})"))
+                    .unwrap_or_default();
+                println!(
+                    "{}  {}{branch}",
+                    project.id,
+                    strip_credentials(&project.source)
+                );
+            }
+        }
+        RegistryAction::Remove { id } => {
+            let project = registry.remove(&id)?;
+            registry.save()?;
+            println!("🗑️  Unregistered '{id}'");
+            if project.is_remote() {
+                let checkout = registry.root(&project);
+                if checkout.exists() {
+                    println!("   Its checkout is left at {}", checkout.display());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn handle_debt(
+    matrix: Option<PathBuf>,
+    verify: bool,
+    output: crate::cli::args::OutputFormat,
+    config: &Config,
+) -> Result<Findings> {
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+    let report = Csd::new(config.clone())
+        .with_matrix_path(&matrix_path)
+        .debt(verify)
+        .await?;
+    let stale = report.stale_markers();
+
+    match output {
+        crate::cli::args::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?)
+        }
+        crate::cli::args::OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&report)?),
+        crate::cli::args::OutputFormat::Pretty => {
+            let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+            for marker in &report.markers {
+                *counts.entry(marker.marker.as_str()).or_default() += 1;
+            }
+            let counts: Vec<String> = counts
+                .iter()
+                .map(|(marker, count)| format!("{count} {marker}"))
+                .collect();
+            println!(
+                "🧾 {} markers ({}) referencing {} issues",
+                report.markers.len(),
+                counts.join(", "),
+                report.issues.len()
+            );
+            for issue in &report.issues {
+                println!(
+                    "   {} ({:?}): {} files",
+                    issue.key,
+                    issue.status,
+                    issue.files.len()
+                );
+            }
+            if !stale.is_empty() {
+                println!("⚠️  Markers referencing closed issues:");
+                for marker in &stale {
+                    println!(
+                        "   {}:{}: {} {}",
+                        marker.file.display(),
+                        marker.line,
+                        marker.marker,
+                        marker.text
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(Findings {
+        warnings: stale.len(),
+        ..Findings::default()
+    })
+}
+
+async fn handle_coverage_import(
+    report: &Path,
+    format: Option<CoverageFormat>,
+    matrix: Option<PathBuf>,
+    config: &Config,
+) -> Result<()> {
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+    let import = Csd::new(config.clone())
+        .with_matrix_path(&matrix_path)
+        .import_coverage(report, format)
+        .await?;
+
+    println!(
+        "🧪 Imported coverage for {} files and {} elements: {}% of {} lines",
+        import.files, import.elements, import.total.percent, import.total.lines_found
+    );
+    if !import.unmatched.is_empty() {
+        println!(
+            "   {} report files aren't in the matrix, e.g. {}",
+            import.unmatched.len(),
+            import.unmatched[0].display()
+        );
+    }
+    Ok(())
+}
+
+async fn handle_trace_import(
+    trace: &Path,
+    format: Option<TraceFormat>,
+    matrix: Option<PathBuf>,
+    config: &Config,
+) -> Result<()> {
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+    let import = Csd::new(config.clone())
+        .with_matrix_path(&matrix_path)
+        .import_traces(trace, format)
+        .await?;
+
+    println!(
+        "🔥 Imported {} runtime calls across files: {} edges strengthened, {} call edges added",
+        import.calls, import.strengthened, import.added
+    );
+    if import.unexercised_imports > 0 {
+        println!(
+            "   {} imports were never exercised by the trace",
+            import.unexercised_imports
+        );
+    }
+    if import.unmatched_frames > 0 {
+        println!(
+            "   {} frames matched no element of the matrix",
+            import.unmatched_frames
+        );
+    }
+    Ok(())
+}
+
+async fn handle_services_import(
+    file: &Path,
+    format: Option<InteractionSource>,
+    service: Option<&str>,
+    matrix: Option<PathBuf>,
+    config: &Config,
+) -> Result<()> {
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+    let import = Csd::new(config.clone())
+        .with_matrix_path(&matrix_path)
+        .import_interactions(file, format, service)
+        .await?;
+
+    println!(
+        "🛰️  Recorded {} calls with {} services",
+        import.calls,
+        import.downstream.len() + import.upstream.len()
+    );
+    if !import.downstream.is_empty() {
+        println!("   Calls: {}", import.downstream.join(", "));
+    }
+    if !import.upstream.is_empty() {
+        println!("   Called by: {}", import.upstream.join(", "));
+    }
+    if import.skipped > 0 {
+        println!(
+            "   {} entries skipped: not naming a service, or between other services",
+            import.skipped
+        );
+    }
+    Ok(())
+}
+
+async fn handle_adr_new(title: &str, affects: &[String]) -> Result<()> {
+    let root = Path::new(".");
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let adr = create_adr(root, title, affects, &date)?;
+    write_index(root, &load_adrs(root)?)?;
+
+    println!("📐 Created ADR {:04}: {}", adr.number, adr.path.display());
+    if !affects.is_empty() {
+        println!("   Run 'csd adr index' or 'csd scan' to tag the affected files");
+    }
+    Ok(())
+}
+
+async fn handle_adr_index(matrix: Option<PathBuf>, config: &Config) -> Result<()> {
+    let root = Path::new(".");
+    let mut adrs = load_adrs(root)?;
+    let index_path = write_index(root, &adrs)?;
+    println!("📐 Indexed {} ADRs in {}", adrs.len(), index_path.display());
+
+    let matrix_path = matrix.unwrap_or_else(|| default_matrix_path(config));
+    if !matrix_path.exists() {
+        println!("   No matrix at {}; skipped tagging", matrix_path.display());
+        return Ok(());
+    }
+    let mut matrix = ProjectMatrix::load(&matrix_path).await?;
+    link_adrs(&mut adrs, &mut matrix)?;
+    matrix.save(&matrix_path).await?;
+    for adr in &adrs {
+        println!("   {} {}: {} files", adr.tag(), adr.title, adr.files.len());
+    }
+    Ok(())
+}
+
+/// What `csd annotate` was asked to change
+struct AnnotationChange {
+    note: Option<String>,
+    author: Option<String>,
+    owner: Option<String>,
+    review: Option<crate::core::annotations::ReviewStatus>,
+    clear: bool,
+}
+
+impl AnnotationChange {
+    fn is_some(&self) -> bool {
+        self.note.is_some() || self.owner.is_some() || self.review.is_some() || self.clear
+    }
+
+    fn apply(self, annotation: &mut Annotation) {
+        if self.clear {
+            *annotation = Annotation::default();
+        }
+        if self.owner.is_some() {
+            annotation.owner = self.owner;
+        }
+        if self.review.is_some() {
+            annotation.review = self.review;
+        }
+        if let Some(text) = self.note {
+            annotation.notes.push(Note {
+                text,
+                author: self.author,
+                date: Utc::now(),
+            });
+        }
+    }
+}
+
+// Project-relative form of a path given on the command line
+fn project_relative(path: &Path) -> PathBuf {
+    let path = match std::env::current_dir() {
+        Ok(cwd) => path.strip_prefix(&cwd).unwrap_or(path),
+        Err(_) => path,
+    };
+    PathBuf::from(path_key(path))
+}
+
+async fn handle_annotate(
+    path: &Path,
+    element: Option<&str>,
+    change: AnnotationChange,
+    matrix_path: &Path,
+) -> Result<()> {
+    let path = project_relative(path);
+    let mut annotations = Annotations::load(Path::new("."))?;
+
+    let (target, element_id) = match element {
+        // Elements are keyed by the ID the last scan gave them
+        Some(name) => {
+            if !matrix_path.exists() {
+                return Err(anyhow::anyhow!(
+                    "No matrix at {}; run 'csd scan' before annotating elements",
+                    matrix_path.display()
+                ));
+            }
+            let matrix = ProjectMatrix::load(matrix_path).await?;
+            let (file, element) = find_target(&matrix, &path, Some(name))?;
+            let element = element.expect("find_target returns the named element");
+            change.apply(annotations.element_mut(file, element));
+            (
+                format!("{} in {}", element.qualified_name(), path.display()),
+                Some(element.element_id.clone()),
+            )
+        }
+        None => {
+            if !path.exists() {
+                warn!("{} doesn't exist; annotating it anyway", path.display());
+            }
+            change.apply(annotations.file_mut(&path));
+            (path.display().to_string(), None)
+        }
+    };
+    annotations.save()?;
+
+    let annotation = match element_id {
+        Some(id) => annotations.element(&id).map(|element| &element.annotation),
+        None => annotations.file(&path),
+    };
+    match annotation {
+        Some(annotation) => println!("📝 {target}: {}", annotation.describe()),
+        None => println!("📝 Cleared the annotation on {target}"),
+    }
+    Ok(())
+}
+
+async fn handle_annotations(path: Option<&Path>, matrix_path: &Path) -> Result<()> {
+    let annotations = Annotations::load(Path::new("."))?;
+    let path = path.map(|path| path_key(&project_relative(path)));
+    let wanted = |file: &str| path.as_deref().is_none_or(|path| path == file);
+    let print_notes = |annotation: &Annotation| {
+        for note in &annotation.notes {
+            let author = note
+                .author
+                .as_deref()
+                .map(|author| format!(" ({author})"))
+                .unwrap_or_default();
+            println!(
+                "      {}{author}: {}",
+                note.date.format("%Y-%m-%d"),
+                note.text
+            );
+        }
+    };
+
+    let mut shown = 0;
+    for (file, annotation) in annotations.files.iter().filter(|(file, _)| wanted(file)) {
+        println!("📝 {file}: {}", annotation.describe());
+        print_notes(annotation);
+        shown += 1;
+    }
+    for element in annotations
+        .elements
+        .values()
+        .filter(|element| wanted(&path_key(&element.file)))
+    {
+        println!(
+            "📝 {} in {}: {}",
+            element.name,
+            element.file.display(),
+            element.annotation.describe()
+        );
+        print_notes(&element.annotation);
+        shown += 1;
+    }
+    if shown == 0 {
+        println!("No annotations yet; add one with 'csd annotate <path> --note \"...\"'");
+        return Ok(());
+    }
+
+    // Renamed or moved elements get new IDs and leave their annotations behind
+    if matrix_path.exists() {
+        let matrix = ProjectMatrix::load(matrix_path).await?;
+        let orphans = annotations.orphans(&matrix);
+        let orphans: Vec<_> = orphans
+            .iter()
+            .filter(|(_, element)| wanted(&path_key(&element.file)))
+            .collect();
+        if !orphans.is_empty() {
+            println!("⚠️  Annotated elements the last scan no longer has:");
+            for (_, element) in orphans {
+                println!("   {} in {}", element.name, element.file.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn handle_api_diff(
+    old: PathBuf,
+    new: Option<PathBuf>,
+    output: crate::cli::args::OutputFormat,
+    fail_on_breaking: bool,
+    porcelain: bool,
+    config: &Config,
+) -> Result<Findings> {
+    let new_path = new.unwrap_or_else(|| default_matrix_path(config));
+    for path in [&old, &new_path] {
+        if !path.exists() {
+            return Err(anyhow::anyhow!("API file not found: {}", path.display()));
+        }
+    }
+
+    let before = ApiSurface::load(&old).await?;
+    let after = ApiSurface::load(&new_path).await?;
+    let diff = after.diff(&before);
+    for change in &diff.plugin_changes {
+        warn!(
+            "Plugin {} changed from {} to {} between the two scans; results may not be comparable",
+            change.name, change.before, change.after
+        );
+    }
+
+    match output {
+        // Plugin version changes are records of their own
+        _ if porcelain => print_records(porcelain::api_diff_records(&diff)),
+        crate::cli::args::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&diff)?)
+        }
+        crate::cli::args::OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&diff)?),
+        crate::cli::args::OutputFormat::Pretty => {
+            let describe = |item: &crate::core::api::ApiItem| {
+                format!(
+                    "{}::{} ({}){}",
+                    item.file.display(),
+                    item.name,
+                    item.kind,
+                    item.signature
+                        .as_ref()
+                        .map(|s| format!(": {s}"))
+                        .unwrap_or_default()
+                )
+            };
+            for item in &diff.removed {
+                println!("❌ removed {}", describe(item));
+            }
+            for change in &diff.changed {
+                println!("⚠️  changed {}", describe(&change.before));
+                println!("        now {}", describe(&change.after));
+            }
+            for item in &diff.added {
+                println!("➕ added   {}", describe(item));
+            }
+            println!(
+                "\n📊 {} added, {} removed, {} changed — suggested bump: {:?}",
+                diff.added.len(),
+                diff.removed.len(),
+                diff.changed.len(),
+                diff.required_bump()
+            );
+        }
+    }
+
+    if fail_on_breaking && diff.is_breaking() {
+        return Err(ExitError::new(
+            ExitCode::QualityGate,
+            format!(
+                "Breaking API changes detected ({} removed, {} changed)",
+                diff.removed.len(),
+                diff.changed.len()
+            ),
+        )
+        .into());
+    }
+
+    // Plugin version changes make the comparison itself suspect, so they're warnings
+    Ok(Findings {
+        warnings: diff.plugin_changes.len(),
+        plugin_errors: 0,
+        drift: diff.added.len() + diff.removed.len() + diff.changed.len(),
+    })
+}
+
+async fn handle_doctor(args: &Args, output: crate::cli::args::OutputFormat) -> Result<()> {
+    let config = load_config(args).await.map(|mut config| {
+        config.offline |= args.offline;
+        config
+    });
+    let report = Doctor::new(config, ".").run().await;
+
+    match output {
+        crate::cli::args::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?)
+        }
+        crate::cli::args::OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&report)?),
+        crate::cli::args::OutputFormat::Pretty => report.print_summary(),
+    }
+
+    if !report.passed() {
+        return Err(anyhow::anyhow!(
+            "{} environment checks failed",
+            report.count(crate::plugins::conformance::CheckStatus::Fail)
+        ));
+    }
+    Ok(())
+}
+
+async fn handle_validate(output: crate::cli::args::OutputFormat, config: &Config) -> Result<()> {
+    let report = validate_plugin_configs(config, Path::new(".")).await?;
+
+    match output {
+        crate::cli::args::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?)
+        }
+        crate::cli::args::OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&report)?),
+        crate::cli::args::OutputFormat::Pretty => report.print_summary(),
+    }
+
+    if !report.passed() {
+        return Err(ExitError::new(
+            ExitCode::Config,
+            format!(
+                "{} plugin configs don't match their schema",
+                report.count(crate::plugins::conformance::CheckStatus::Fail)
+            ),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+async fn handle_plugins(detailed: bool, porcelain: bool, config: &Config) -> Result<()> {
+    debug!("Listing available plugins...");
+
+    let plugin_manager = PluginManager::new(config.clone());
+    let plugins = plugin_manager.discover_plugins().await?;
+    if porcelain {
+        print_records(porcelain::plugin_records(&plugins));
+        return Ok(());
+    }
+
+    if detailed {
+        println!("=== Input Plugins (Code Analyzers) ===");
+        let input_plugins: Vec<_> = plugins
+            .iter()
+            .filter(|p| p.plugin_type == "input")
+            .collect();
+
+        if input_plugins.is_empty() {
+            println!("No input plugins configured.");
+        } else {
+            for plugin in input_plugins {
+                println!("Plugin: {}", plugin.name);
+                println!("  Type: Input (Code Analyzer)");
+                println!("  Path: {}", plugin.path.display());
+                println!("  Extensions: {}", plugin.extensions.join(", "));
+                println!("  Filenames: {}", plugin.filenames.join(", "));
+                println!("  Source: {:?}", plugin.source);
+                println!("  Enabled: {}", plugin.enabled);
+                println!();
+            }
+        }
+
+        println!("=== Output Plugins (Documentation Generators, etc.) ===");
+        let output_plugins: Vec<_> = plugins
+            .iter()
+            .filter(|p| p.plugin_type == "output")
+            .collect();
+
+        if output_plugins.is_empty() {
+            println!("No output plugins configured.");
+        } else {
+            for plugin in output_plugins {
+                println!("Plugin: {}", plugin.name);
+                println!("  Type: Output (Generator)");
+                println!("  Path: {}", plugin.path.display());
+                println!("  Output Types: {}", plugin.output_types.join(", "));
+                println!("  Formats: {}", plugin.formats.join(", "));
+                println!("  Source: {:?}", plugin.source);
+                println!("  Enabled: {}", plugin.enabled);
+                println!();
+            }
+        }
+
+        let transform_plugins: Vec<_> = plugins
+            .iter()
+            .filter(|p| p.plugin_type == "transform")
+            .collect();
+        if !transform_plugins.is_empty() {
+            println!("=== Transform Plugins (Matrix Post-processors) ===");
+            for plugin in transform_plugins {
+                println!("Plugin: {}", plugin.name);
+                println!("  Type: Transform");
+                println!("  Path: {}", plugin.path.display());
+                println!("  Source: {:?}", plugin.source);
+                println!("  Enabled: {}", plugin.enabled);
+                println!();
+            }
+        }
+
+        let rule_plugins: Vec<_> = plugins.iter().filter(|p| p.plugin_type == "rule").collect();
+        if !rule_plugins.is_empty() {
+            println!("=== Rule Plugins (Custom Quality Checks) ===");
+            for plugin in rule_plugins {
+                println!("Plugin: {}", plugin.name);
+                println!("  Type: Rule");
+                println!("  Path: {}", plugin.path.display());
+                println!("  Source: {:?}", plugin.source);
+                println!("  Enabled: {}", plugin.enabled);
+                println!();
+            }
+        }
+    } else {
+        println!("Input Plugins:");
+        for plugin in plugins.iter().filter(|p| p.plugin_type == "input") {
+            let all_patterns: Vec<String> = plugin
+                .extensions
+                .iter()
+                .chain(plugin.filenames.iter())
+                .cloned()
+                .collect();
+            println!("  {} - {}", plugin.name, all_patterns.join(", "));
+        }
+
+        println!("\nOutput Plugins:");
+        for plugin in plugins.iter().filter(|p| p.plugin_type == "output") {
+            println!(
+                "  {} - Types: {}, Formats: {}",
+                plugin.name,
+                plugin.output_types.join(","),
+                plugin.formats.join(",")
+            );
+        }
+
+        let transform_names: Vec<&str> = plugins
+            .iter()
+            .filter(|p| p.plugin_type == "transform")
+            .map(|p| p.name.as_str())
+            .collect();
+        if !transform_names.is_empty() {
+            println!("\nTransform Plugins (in run order):");
+            for name in transform_names {
+                println!("  {name}");
+            }
+        }
+
+        let rule_names: Vec<&str> = plugins
+            .iter()
+            .filter(|p| p.plugin_type == "rule")
+            .map(|p| p.name.as_str())
+            .collect();
+        if !rule_names.is_empty() {
+            println!("\nRule Plugins:");
+            for name in rule_names {
+                println!("  {name}");
+            }
+        }
+    }
+
+    // Show configuration summary
+    let summary = config.get_plugin_summary();
+    println!("\n📊 Plugin Summary:");
+    println!(
+        "  Input plugins: {} enabled / {} total",
+        summary.enabled_input_plugins, summary.total_input_plugins
+    );
+    println!(
+        "  Output plugins: {} enabled / {} total",
+        summary.enabled_output_plugins, summary.total_output_plugins
+    );
+
+    Ok(())
+}
+
+/// Runner for a configured plugin by name, or for a plugin script given by path
+async fn plugin_conformance_runner(
+    name: &str,
+    kind: Option<PluginKind>,
+    config: &Config,
+) -> Result<ConformanceRunner> {
+    let expected_type = kind.map(PluginType::from);
+    let (plugin_path, runtime) = if Path::new(name).is_file() {
+        (
+            PathBuf::from(name),
+            PluginRuntime::for_path(Path::new(name)),
+        )
+    } else {
+        let plugins = PluginManager::new(config.clone())
+            .discover_plugins()
+            .await?;
+        let mut matches = plugins.into_iter().filter(|plugin| {
+            plugin.name == name
+                && expected_type
+                    .as_ref()
+                    .is_none_or(|expected| plugin.plugin_type == expected.as_str())
+        });
+        let plugin = matches.next().ok_or_else(|| {
+            anyhow::anyhow!("No enabled plugin named '{name}' (pass a path to test a script)")
+        })?;
+        if matches.next().is_some() {
+            return Err(anyhow::anyhow!(
+                "Several plugins are named '{name}'; choose one with --type"
+            ));
+        }
+        (plugin.path, plugin.runtime)
+    };
+
+    let work_dir = config
+        .resolve_cache_dir(Path::new("."))
+        .join("conformance")
+        .join(uuid::Uuid::new_v4().to_string());
+    Ok(ConformanceRunner::new(plugin_path, work_dir)
+        .with_python_executable(config.python_executable.clone())
+        .with_node_executable(config.node_executable.clone())
+        .with_wasm_executable(config.wasm_executable.clone())
+        .with_runtime(runtime)
+        .with_expected_type(expected_type))
+}
+
+async fn handle_plugin_test(
+    runner: ConformanceRunner,
+    output: crate::cli::args::OutputFormat,
+) -> Result<()> {
+    let report = runner.run().await?;
+
+    match output {
+        crate::cli::args::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?)
+        }
+        crate::cli::args::OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&report)?),
+        crate::cli::args::OutputFormat::Pretty => report.print_summary(),
+    }
+
+    if !report.passed() {
+        return Err(ExitError::new(
+            ExitCode::Plugin,
+            format!(
+                "Plugin failed {} conformance checks",
+                report.count(CheckStatus::Fail)
+            ),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+async fn handle_plugin_new(
+    config_path: &Path,
+    scaffold: PluginScaffold,
+    plugins_root: &Path,
+    register: bool,
+    force: bool,
+) -> Result<()> {
+    let written = scaffold.write(plugins_root, force)?;
+    println!(
+        "🧩 Created {} plugin '{}' ({}):",
+        scaffold.category(),
+        scaffold.name,
+        scaffold.class_name()
+    );
+    for path in &written {
+        println!("   {}", path.display());
+    }
+
+    if register {
+        let mut tree = read_config_tree(config_path, false).await?;
+        let key = scaffold.config_key();
+        set_config_value(&mut tree, &key, scaffold.config_value(&written[0])?)?;
+        write_config_tree(config_path, false, &tree, &key).await?;
+        println!("✅ Registered {key} in {}", config_path.display());
+    }
+
+    println!(
+        "\nRun the sample tests with: python -m pytest {}",
+        written[1].display()
+    );
+    if register {
+        println!(
+            "Check protocol conformance with: csd plugin test {}",
+            scaffold.name
+        );
+    }
+    Ok(())
+}
+
+async fn handle_config(config_path: &Path, force: bool) -> Result<()> {
+    debug!("Initializing configuration...");
+
+    if config_path.exists() && !force {
+        return Err(anyhow::anyhow!(
+            "Configuration file already exists. Use --force to overwrite."
+        ));
+    }
+
+    let default_config = Config::default();
+    default_config.save(config_path).await?;
 
     println!("✅ Created configuration file: {}", config_path.display());
 
@@ -392,3 +2674,185 @@ async fn handle_config(force: bool) -> Result<()> {
 
     Ok(())
 }
+
+async fn handle_config_migrate(config_path: &Path, dry_run: bool, no_backup: bool) -> Result<()> {
+    debug!("Migrating configuration: {}", config_path.display());
+
+    if !config_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Configuration file not found: {}",
+            config_path.display()
+        ));
+    }
+
+    let content = tokio::fs::read_to_string(config_path).await?;
+    let result = migrate_config_text(&content, ConfigFormat::from_path(config_path))?;
+
+    if !result.changed() {
+        println!(
+            "✅ {} already uses the current configuration format",
+            config_path.display()
+        );
+        return Ok(());
+    }
+
+    for key in &result.deprecated_keys {
+        let hint = DEPRECATED_KEYS
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, hint)| *hint)
+            .unwrap_or_default();
+        warn!("Deprecated key `{key}`: {hint}");
+    }
+    for name in &result.migration.skipped {
+        warn!("Legacy plugin '{name}' has neither file patterns nor output types; dropping it");
+    }
+
+    if dry_run {
+        print!("{}", result.content);
+        return Ok(());
+    }
+
+    if !no_backup {
+        let backup_path = PathBuf::from(format!("{}.bak", config_path.display()));
+        tokio::fs::copy(config_path, &backup_path).await?;
+        println!("💾 Backed up original to: {}", backup_path.display());
+    }
+
+    tokio::fs::write(config_path, &result.content).await?;
+
+    println!("✅ Migrated configuration: {}", config_path.display());
+    if !result.migration.input_plugins.is_empty() {
+        println!(
+            "  Input plugins: {}",
+            result.migration.input_plugins.join(", ")
+        );
+    }
+    if !result.migration.output_plugins.is_empty() {
+        println!(
+            "  Output plugins: {}",
+            result.migration.output_plugins.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// Read a configuration file as a YAML tree. A missing project file starts from the
+/// defaults; a missing user-level file starts empty since it is only a layer.
+async fn read_config_tree(config_path: &Path, global: bool) -> Result<serde_yaml::Value> {
+    if config_path.exists() {
+        let content = tokio::fs::read_to_string(config_path).await?;
+        ConfigFormat::from_path(config_path).parse(&content)
+    } else if global {
+        Ok(serde_yaml::Value::Mapping(serde_yaml::Mapping::new()))
+    } else {
+        Ok(serde_yaml::to_value(Config::default())?)
+    }
+}
+
+/// Values shown by `get`/`list`: the user-level file with `--global`, otherwise the
+/// effective configuration after layering. Secrets are always redacted.
+async fn config_view(target: &Path, global: bool, config: &Config) -> Result<serde_yaml::Value> {
+    let mut tree = if global {
+        read_config_tree(target, true).await?
+    } else {
+        serde_yaml::to_value(config)?
+    };
+    redact_config_values(&mut tree);
+    Ok(tree)
+}
+
+fn handle_config_get(tree: &serde_yaml::Value, key: &str) -> Result<()> {
+    match get_config_value(tree, key) {
+        Some(value @ (serde_yaml::Value::Mapping(_) | serde_yaml::Value::Sequence(_))) => {
+            print!("{}", serde_yaml::to_string(value)?);
+            Ok(())
+        }
+        Some(value) => {
+            println!("{}", format_config_value(value));
+            Ok(())
+        }
+        None => Err(anyhow::anyhow!("Configuration key not found: {key}")),
+    }
+}
+
+async fn handle_config_set(
+    config_path: &Path,
+    global: bool,
+    key: &str,
+    value: Option<&str>,
+) -> Result<()> {
+    let mut tree = read_config_tree(config_path, global).await?;
+
+    match value {
+        Some(raw) => {
+            if is_secret_key(key) && !raw.starts_with("${") {
+                warn!(
+                    "'{key}' looks like a secret; prefer `${{ENV_VAR}}` or `csd config set-secret {key}` over a literal value"
+                );
+            }
+            set_config_value(&mut tree, key, parse_config_value(raw))?
+        }
+        None => {
+            if unset_config_value(&mut tree, key).is_none() {
+                return Err(anyhow::anyhow!("Configuration key not found: {key}"));
+            }
+        }
+    }
+
+    write_config_tree(config_path, global, &tree, key).await?;
+
+    match value {
+        Some(_) if is_secret_key(key) => println!("✅ Set {key} = {REDACTED}"),
+        Some(raw) => println!("✅ Set {key} = {raw}"),
+        None => println!("✅ Unset {key}"),
+    }
+    Ok(())
+}
+
+/// Write an edited config tree, refusing to write a file that would no longer load
+async fn write_config_tree(
+    config_path: &Path,
+    global: bool,
+    tree: &serde_yaml::Value,
+    key: &str,
+) -> Result<()> {
+    let mut effective = if global {
+        serde_yaml::to_value(Config::default())?
+    } else {
+        serde_yaml::Value::Null
+    };
+    merge_config_values(&mut effective, tree.clone());
+    serde_yaml::from_value::<Config>(effective)
+        .map_err(|e| anyhow::anyhow!("Refusing to update '{key}': {e}"))?;
+
+    if let Some(parent) = config_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let content = ConfigFormat::from_path(config_path).render(tree)?;
+    tokio::fs::write(config_path, content).await?;
+    Ok(())
+}
+
+async fn handle_config_set_secret(config_path: &Path, global: bool, key: &str) -> Result<()> {
+    use std::io::BufRead;
+
+    eprintln!("Enter the value for {key} (input is read from stdin):");
+    let mut secret = String::new();
+    std::io::stdin().lock().read_line(&mut secret)?;
+    let secret = secret.trim_end_matches(['\r', '\n']);
+    if secret.is_empty() {
+        return Err(anyhow::anyhow!("No secret value provided for '{key}'"));
+    }
+
+    store_secret(key, secret)?;
+    handle_config_set(config_path, global, key, Some(&keyring_reference(key))).await
+}
+
+fn handle_config_list(tree: &serde_yaml::Value) -> Result<()> {
+    for (key, value) in list_config_values(tree) {
+        println!("{key} = {value}");
+    }
+    Ok(())
+}
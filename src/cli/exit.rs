@@ -0,0 +1,108 @@
+// src/cli/exit.rs - The exit codes csd promises to scripts and CI, and --fail-on policy
+use std::fmt;
+
+use crate::cli::args::FailOn;
+
+/// Process exit codes. Codes other than 0 and 1 are only ever used with these
+/// meanings; any other error exits with 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    Success = 0,
+    Error = 1,
+    // The command finished, but with warnings --fail-on asked to fail on
+    Partial = 2,
+    // A gate such as --fail-on-breaking or --fail-on drift failed
+    QualityGate = 3,
+    // The configuration could not be loaded
+    Config = 4,
+    // A plugin failed, or --fail-on plugin-errors saw files a plugin failed on
+    Plugin = 5,
+}
+
+impl ExitCode {
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// Exit code for a command's result: the one an `ExitError` in the error chain
+    /// carries, or `Error`
+    pub fn of(result: &anyhow::Result<()>) -> Self {
+        match result {
+            Ok(()) => ExitCode::Success,
+            Err(e) => e
+                .chain()
+                .find_map(|cause| cause.downcast_ref::<ExitError>())
+                .map_or(ExitCode::Error, |e| e.code),
+        }
+    }
+}
+
+/// An error that exits with a specific code
+#[derive(Debug)]
+pub struct ExitError {
+    pub code: ExitCode,
+    pub message: String,
+}
+
+impl ExitError {
+    pub fn new(code: ExitCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ExitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ExitError {}
+
+/// What a command that succeeded noticed along the way. Plugin errors are warnings
+/// too, since they leave results incomplete.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Findings {
+    pub warnings: usize,
+    pub plugin_errors: usize,
+    pub drift: usize,
+}
+
+impl Findings {
+    /// Fail with the most severe code among the findings `fail_on` asks about
+    pub fn enforce(&self, fail_on: &[FailOn]) -> anyhow::Result<()> {
+        if fail_on.contains(&FailOn::PluginErrors) && self.plugin_errors > 0 {
+            return Err(ExitError::new(
+                ExitCode::Plugin,
+                format!(
+                    "{} plugin errors (--fail-on plugin-errors)",
+                    self.plugin_errors
+                ),
+            )
+            .into());
+        }
+        if fail_on.contains(&FailOn::Drift) && self.drift > 0 {
+            return Err(ExitError::new(
+                ExitCode::QualityGate,
+                format!(
+                    "{} changes since the baseline, stale references or contract mismatches (--fail-on drift)",
+                    self.drift
+                ),
+            )
+            .into());
+        }
+        if fail_on.contains(&FailOn::Warnings) && self.warnings > 0 {
+            return Err(ExitError::new(
+                ExitCode::Partial,
+                format!(
+                    "Finished with {} warnings (--fail-on warnings)",
+                    self.warnings
+                ),
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+// src/cli/porcelain.rs - Stable, tab-separated records for scripts (--porcelain)
+//
+// Each line is one record whose first field names its kind. Fields are only ever
+// appended at the end, so scripts that read by position keep working.
+use std::path::Path;
+
+use crate::core::api::ApiDiff;
+use crate::core::doc_drift::DocDriftReport;
+use crate::core::graph_analysis::GraphAnalysis;
+use crate::core::matrix::{ProjectMatrix, Relationship};
+use crate::plugins::manager::PluginInfo;
+
+/// Join `fields` with tabs, escaping backslashes, tabs and line breaks inside them
+pub fn record<S: AsRef<str>>(fields: impl IntoIterator<Item = S>) -> String {
+    fields
+        .into_iter()
+        .map(|field| {
+            field
+                .as_ref()
+                .replace('\\', "\\\\")
+                .replace('\t', "\\t")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r")
+        })
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+/// `plugin  <type>  <name>  <enabled>  <source>  <path>  <patterns or output types>`,
+/// sorted by type then name
+pub fn plugin_records(plugins: &[PluginInfo]) -> Vec<String> {
+    let mut plugins: Vec<&PluginInfo> = plugins.iter().collect();
+    plugins.sort_by(|a, b| (&a.plugin_type, &a.name).cmp(&(&b.plugin_type, &b.name)));
+    plugins
+        .into_iter()
+        .map(|plugin| {
+            let handles: Vec<&str> = plugin
+                .extensions
+                .iter()
+                .chain(&plugin.filenames)
+                .chain(&plugin.output_types)
+                .map(String::as_str)
+                .collect();
+            record([
+                "plugin".to_string(),
+                plugin.plugin_type.clone(),
+                plugin.name.clone(),
+                plugin.enabled.to_string(),
+                plugin.source.describe(),
+                plugin.path.display().to_string(),
+                handles.join(","),
+            ])
+        })
+        .collect()
+}
+
+/// `relationship  <from>  <from element>  <to>  <to element>  <type>  <strength>  <plugin>`;
+/// missing elements and plugins are empty fields
+pub fn relationship_records(relationships: &[&Relationship]) -> Vec<String> {
+    relationships
+        .iter()
+        .map(|r| {
+            record([
+                "relationship".to_string(),
+                path(&r.from_file),
+                r.from_element.clone().unwrap_or_default(),
+                path(&r.to_file),
+                r.to_element.clone().unwrap_or_default(),
+                format!("{:?}", r.relationship_type).to_lowercase(),
+                format!("{:.2}", r.strength),
+                r.source_plugin
+                    .as_ref()
+                    .map(|p| p.to_string())
+                    .unwrap_or_default(),
+            ])
+        })
+        .collect()
+}
+
+/// `files` and `relationships` counts, `layers`, one `category` record per category, then the top `top` files by betweenness and
+/// pagerank, every cycle (its files in the remaining fields) and articulation point
+pub fn stats_records(matrix: &ProjectMatrix, analysis: &GraphAnalysis, top: usize) -> Vec<String> {
+    let mut records = vec![
+        record(["files".to_string(), matrix.files.len().to_string()]),
+        record([
+            "relationships".to_string(),
+            matrix.relationships.len().to_string(),
+        ]),
+        record(["layers".to_string(), analysis.layers.len().to_string()]),
+    ];
+    for (category, rollup) in matrix.category_rollups() {
+        records.push(record([
+            "category".to_string(),
+            category.to_string(),
+            rollup.files.to_string(),
+            rollup.size_bytes.to_string(),
+            rollup.tokens.to_string(),
+        ]));
+    }
+    for (kind, ranked) in [
+        ("betweenness", &analysis.betweenness),
+        ("pagerank", &analysis.pagerank),
+    ] {
+        for (file, score) in ranked.iter().take(top) {
+            records.push(record([
+                kind.to_string(),
+                path(file),
+                format!("{score:.6}"),
+            ]));
+        }
+    }
+    for cycle in &analysis.cycles {
+        records.push(record(
+            std::iter::once("cycle".to_string()).chain(cycle.iter().map(|f| path(f))),
+        ));
+    }
+    for file in &analysis.articulation_points {
+        records.push(record(["articulation".to_string(), path(file)]));
+    }
+    records
+}
+
+/// `removed|changed|added  <file>  <name>  <kind>  <signature>` (the new one for
+/// changes), `plugin  <name>  <before>  <after>` for plugin version changes, and a
+/// final `bump  <major|minor|patch>`
+pub fn api_diff_records(diff: &ApiDiff) -> Vec<String> {
+    let item = |status: &str, item: &crate::core::api::ApiItem| {
+        record([
+            status.to_string(),
+            path(&item.file),
+            item.name.clone(),
+            item.kind.clone(),
+            item.signature.clone().unwrap_or_default(),
+        ])
+    };
+
+    let mut records: Vec<String> = diff
+        .removed
+        .iter()
+        .map(|i| item("removed", i))
+        .chain(diff.changed.iter().map(|c| item("changed", &c.after)))
+        .chain(diff.added.iter().map(|i| item("added", i)))
+        .collect();
+    for change in &diff.plugin_changes {
+        records.push(record([
+            "plugin",
+            &change.name,
+            &change.before,
+            &change.after,
+        ]));
+    }
+    records.push(record([
+        "bump".to_string(),
+        format!("{:?}", diff.required_bump()).to_lowercase(),
+    ]));
+    records
+}
+
+/// `stale  <file|symbol>  <doc>  <line>  <reference>`, in document order
+pub fn doc_drift_records(report: &DocDriftReport) -> Vec<String> {
+    report
+        .stale
+        .iter()
+        .map(|stale| {
+            record([
+                "stale".to_string(),
+                stale.kind.to_string(),
+                path(&stale.doc),
+                stale.line.to_string(),
+                stale.reference.clone(),
+            ])
+        })
+        .collect()
+}
+
+fn path(path: &Path) -> String {
+    path.display().to_string()
+}
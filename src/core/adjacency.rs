@@ -0,0 +1,168 @@
+// src/core/adjacency.rs - Weighted file/directory adjacency matrices for external analysis
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::matrix::ProjectMatrix;
+
+/// What each row and column of the adjacency matrix stands for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AdjacencyLevel {
+    #[default]
+    File,
+    // Parent directory of each file ("." for the project root)
+    Directory,
+}
+
+/// Dense dependency matrix: `weights[i][j]` is the summed strength of the edges from
+/// `labels[i]` to `labels[j]`. Labels are project-relative paths, sorted, and include
+/// nodes without any edges so every file or directory has a row.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdjacencyMatrix {
+    pub labels: Vec<String>,
+    pub weights: Vec<Vec<f32>>,
+}
+
+impl AdjacencyMatrix {
+    /// Edges to or from paths outside the matrix's files are dropped
+    pub fn from_matrix(matrix: &ProjectMatrix, level: AdjacencyLevel) -> Self {
+        let label_of = |relative_path: &Path| match level {
+            AdjacencyLevel::File => relative_path.to_string_lossy().to_string(),
+            AdjacencyLevel::Directory => match relative_path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => {
+                    parent.to_string_lossy().to_string()
+                }
+                _ => ".".to_string(),
+            },
+        };
+
+        // Relationships may name a file by its matrix key or its relative path
+        let mut labels_by_path: BTreeMap<&PathBuf, String> = BTreeMap::new();
+        for (key, file) in &matrix.files {
+            let label = label_of(&file.relative_path);
+            labels_by_path.insert(key, label.clone());
+            labels_by_path.insert(&file.relative_path, label);
+        }
+
+        let mut labels: Vec<String> = labels_by_path.values().cloned().collect();
+        labels.sort();
+        labels.dedup();
+        let index: BTreeMap<&str, usize> = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.as_str(), i))
+            .collect();
+
+        let mut weights = vec![vec![0.0; labels.len()]; labels.len()];
+        for relationship in &matrix.relationships {
+            let (Some(from), Some(to)) = (
+                labels_by_path.get(&relationship.from_file),
+                labels_by_path.get(&relationship.to_file),
+            ) else {
+                continue;
+            };
+            weights[index[from.as_str()]][index[to.as_str()]] += relationship.strength;
+        }
+
+        Self { labels, weights }
+    }
+
+    /// CSV with a `source` column followed by one column per target, so
+    /// `pandas.read_csv(path, index_col=0)` yields the matrix directly
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("source");
+        for label in &self.labels {
+            csv.push(',');
+            csv.push_str(&csv_field(label));
+        }
+        csv.push('\n');
+
+        for (label, row) in self.labels.iter().zip(&self.weights) {
+            csv.push_str(&csv_field(label));
+            for weight in row {
+                csv.push(',');
+                csv.push_str(&weight.to_string());
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Same layout as the CSV: a string `source` column and one FLOAT column per target
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet(&self, path: &Path) -> Result<()> {
+        use anyhow::Context;
+        use parquet::basic::{LogicalType, Repetition, Type as PhysicalType};
+        use parquet::data_type::{ByteArray, ByteArrayType, FloatType};
+        use parquet::file::properties::WriterProperties;
+        use parquet::file::writer::SerializedFileWriter;
+        use parquet::schema::types::Type;
+        use std::sync::Arc;
+
+        let column = |name: &str, physical: PhysicalType| {
+            Type::primitive_type_builder(name, physical)
+                .with_repetition(Repetition::REQUIRED)
+                .with_logical_type(
+                    (physical == PhysicalType::BYTE_ARRAY).then_some(LogicalType::String),
+                )
+                .build()
+                .map(Arc::new)
+        };
+        let mut fields = vec![column("source", PhysicalType::BYTE_ARRAY)?];
+        for label in &self.labels {
+            fields.push(column(label, PhysicalType::FLOAT)?);
+        }
+        let schema = Type::group_type_builder("adjacency")
+            .with_fields(fields)
+            .build()?;
+
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut writer = SerializedFileWriter::new(
+            file,
+            Arc::new(schema),
+            Arc::new(WriterProperties::builder().build()),
+        )?;
+        let mut row_group = writer.next_row_group()?;
+
+        let sources: Vec<ByteArray> = self.labels.iter().map(|l| l.as_str().into()).collect();
+        let mut source_column = row_group
+            .next_column()?
+            .context("Parquet schema is missing the source column")?;
+        source_column
+            .typed::<ByteArrayType>()
+            .write_batch(&sources, None, None)?;
+        source_column.close()?;
+
+        for target in 0..self.labels.len() {
+            let values: Vec<f32> = self.weights.iter().map(|row| row[target]).collect();
+            let mut target_column = row_group
+                .next_column()?
+                .context("Parquet schema is missing a target column")?;
+            target_column
+                .typed::<FloatType>()
+                .write_batch(&values, None, None)?;
+            target_column.close()?;
+        }
+
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "parquet"))]
+    pub fn write_parquet(&self, _path: &Path) -> Result<()> {
+        Err(anyhow::anyhow!(
+            "Parquet export is not available: csd was built without the `parquet` feature"
+        ))
+    }
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
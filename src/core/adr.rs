@@ -0,0 +1,239 @@
+// src/core/adr.rs - Architecture decision records under docs/adr, linked to the files
+// they affect through `adr:NNNN` tags
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::core::matrix::ProjectMatrix;
+use crate::core::transform::{apply_patch, PatchSummary};
+use crate::plugins::interface::{MatrixPatch, OutputScope, TagUpdate};
+
+/// Where ADRs live, relative to the project root
+pub const ADR_DIR: &str = "docs/adr";
+
+/// Index of all ADRs, written next to them
+pub const ADR_INDEX_FILE: &str = "README.md";
+
+/// One decision. The file starts with `# <number>. <title>` followed by `Date:`,
+/// `Status:` and `Affects:` lines; `Affects` lists directories or globs, comma
+/// separated, naming the files the decision applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Adr {
+    pub number: u32,
+    pub title: String,
+    pub status: String,
+    pub date: String,
+    // Relative to the project root
+    pub path: PathBuf,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affects: Vec<String>,
+    // Matrix files carrying this ADR's tag; filled in by `link_adrs` or `files_in`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<PathBuf>,
+}
+
+impl Adr {
+    /// `adr:0007`
+    pub fn tag(&self) -> String {
+        format!("adr:{:04}", self.number)
+    }
+
+    /// Relative paths of the matrix files tagged with this ADR, sorted
+    pub fn files_in(&self, matrix: &ProjectMatrix) -> Vec<PathBuf> {
+        let tag = self.tag();
+        let mut files: Vec<PathBuf> = matrix
+            .files
+            .values()
+            .filter(|file| {
+                file.metadata["tags"]
+                    .as_array()
+                    .is_some_and(|tags| tags.iter().any(|t| t.as_str() == Some(tag.as_str())))
+            })
+            .map(|file| file.relative_path.clone())
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn parse(content: &str, path: PathBuf) -> Option<Self> {
+        let mut lines = content.lines();
+        let heading = lines.next()?.strip_prefix("# ")?;
+        let (number, title) = heading.split_once(". ")?;
+        let mut adr = Self {
+            number: number.trim().parse().ok()?,
+            title: title.trim().to_string(),
+            status: String::new(),
+            date: String::new(),
+            path,
+            affects: Vec::new(),
+            files: Vec::new(),
+        };
+        // The header ends at the first section
+        for line in lines.take_while(|line| !line.starts_with("## ")) {
+            if let Some(status) = line.strip_prefix("Status:") {
+                adr.status = status.trim().to_string();
+            } else if let Some(date) = line.strip_prefix("Date:") {
+                adr.date = date.trim().to_string();
+            } else if let Some(affects) = line.strip_prefix("Affects:") {
+                adr.affects = affects
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|pattern| !pattern.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+        }
+        Some(adr)
+    }
+}
+
+/// The ADRs in `<project_root>/docs/adr`, by number. Markdown files that don't start
+/// with an ADR heading (such as the index) are skipped; a missing directory is empty.
+pub fn load_adrs(project_root: &Path) -> Result<Vec<Adr>> {
+    let dir = project_root.join(ADR_DIR);
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+    let mut adrs = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "md") {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let relative = path
+            .strip_prefix(project_root)
+            .unwrap_or(&path)
+            .to_path_buf();
+        if let Some(adr) = Adr::parse(&content, relative) {
+            adrs.push(adr);
+        }
+    }
+    adrs.sort_by_key(|adr| adr.number);
+    Ok(adrs)
+}
+
+/// Write a new ADR numbered after the existing ones, in the `Proposed` state, and
+/// return it
+pub fn create_adr(project_root: &Path, title: &str, affects: &[String], date: &str) -> Result<Adr> {
+    let title = title.trim();
+    let slug = slugify(title);
+    if slug.is_empty() {
+        return Err(anyhow::anyhow!(
+            "ADR title '{title}' needs letters or digits"
+        ));
+    }
+    let number = load_adrs(project_root)?
+        .last()
+        .map_or(1, |adr| adr.number + 1);
+    let path = PathBuf::from(ADR_DIR).join(format!("{number:04}-{slug}.md"));
+
+    let content = format!(
+        "# {number}. {title}\n\n\
+         Date: {date}\n\
+         Status: Proposed\n\
+         Affects: {}\n\n\
+         ## Context\n\n\
+         What is the issue motivating this decision?\n\n\
+         ## Decision\n\n\
+         What change is being proposed or made?\n\n\
+         ## Consequences\n\n\
+         What becomes easier or harder because of this change?\n",
+        affects.join(", ")
+    );
+    let absolute = project_root.join(&path);
+    std::fs::create_dir_all(project_root.join(ADR_DIR))?;
+    std::fs::write(&absolute, content)
+        .with_context(|| format!("Failed to write {}", absolute.display()))?;
+
+    Ok(Adr {
+        number,
+        title: title.to_string(),
+        status: "Proposed".to_string(),
+        date: date.to_string(),
+        path,
+        affects: affects.to_vec(),
+        files: Vec::new(),
+    })
+}
+
+/// Markdown table of the ADRs, with links relative to the ADR directory
+pub fn index_markdown(adrs: &[Adr]) -> String {
+    let mut md = String::from("# Architecture Decision Records\n\n");
+    if adrs.is_empty() {
+        md.push_str("No decisions recorded yet. Add one with `csd adr new \"<title>\"`.\n");
+        return md;
+    }
+    md.push_str("| ADR | Title | Status | Date | Affects |\n");
+    md.push_str("| --- | --- | --- | --- | --- |\n");
+    for adr in adrs {
+        let file = adr
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let affects: Vec<String> = adr.affects.iter().map(|a| format!("`{a}`")).collect();
+        md.push_str(&format!(
+            "| [{:04}]({file}) | {} | {} | {} | {} |\n",
+            adr.number,
+            adr.title.replace('|', "\\|"),
+            adr.status,
+            adr.date,
+            affects.join(", ")
+        ));
+    }
+    md
+}
+
+/// Write the index into the ADR directory and return its path
+pub fn write_index(project_root: &Path, adrs: &[Adr]) -> Result<PathBuf> {
+    let path = project_root.join(ADR_DIR).join(ADR_INDEX_FILE);
+    std::fs::create_dir_all(project_root.join(ADR_DIR))?;
+    std::fs::write(&path, index_markdown(adrs))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Tag the files each ADR affects with its `adr:NNNN` tag and record them in
+/// `adr.files`. ADRs without `Affects` patterns tag nothing.
+pub fn link_adrs(adrs: &mut [Adr], matrix: &mut ProjectMatrix) -> Result<PatchSummary> {
+    let mut patch = MatrixPatch {
+        relationships: Vec::new(),
+        tags: Vec::new(),
+        summaries: Vec::new(),
+    };
+    for adr in adrs.iter_mut() {
+        let scope = OutputScope::from_patterns(adr.affects.iter().cloned());
+        if scope.is_empty() {
+            continue;
+        }
+        let mut files: Vec<PathBuf> = scope
+            .select(matrix)?
+            .iter()
+            .filter_map(|key| matrix.files.get(key))
+            .map(|file| file.relative_path.clone())
+            .collect();
+        files.sort();
+        for file in &files {
+            patch.tags.push(TagUpdate {
+                file: file.to_string_lossy().replace('\\', "/"),
+                tags: vec![adr.tag()],
+            });
+        }
+        adr.files = files;
+    }
+    Ok(apply_patch(matrix, patch, "adr"))
+}
+
+fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
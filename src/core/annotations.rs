@@ -0,0 +1,245 @@
+// src/core/annotations.rs - Notes, owners and review status people attach to files and
+// elements. They live in a file of their own next to the config, keyed by relative path
+// and element ID, so rescans never overwrite them.
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::matrix::{CodeElement, FileNode, ProjectMatrix};
+use crate::utils::file_utils::{normalize_lexically, write_atomic_sync};
+
+/// Annotations file, relative to the project root; meant to be committed
+pub const ANNOTATIONS_FILE: &str = ".csd_annotations.yaml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum ReviewStatus {
+    NeedsReview,
+    ChangesRequested,
+    Approved,
+}
+
+impl std::fmt::Display for ReviewStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ReviewStatus::NeedsReview => "needs-review",
+            ReviewStatus::ChangesRequested => "changes-requested",
+            ReviewStatus::Approved => "approved",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Note {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub date: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review: Option<ReviewStatus>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<Note>,
+}
+
+impl Annotation {
+    pub fn is_empty(&self) -> bool {
+        self.owner.is_none() && self.review.is_none() && self.notes.is_empty()
+    }
+
+    /// `owner: payments · review: approved · 2 notes`
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(ref owner) = self.owner {
+            parts.push(format!("owner: {owner}"));
+        }
+        if let Some(review) = self.review {
+            parts.push(format!("review: {review}"));
+        }
+        match self.notes.len() {
+            0 => {}
+            1 => parts.push("1 note".to_string()),
+            n => parts.push(format!("{n} notes")),
+        }
+        parts.join(" · ")
+    }
+}
+
+/// An element's annotation, with where the element was when it was annotated: element
+/// IDs change when an element is renamed, moved or re-signed, and the annotation should
+/// still say what it was about
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ElementAnnotation {
+    pub file: PathBuf,
+    pub name: String,
+    #[serde(flatten)]
+    pub annotation: Annotation,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotations {
+    #[serde(skip)]
+    path: PathBuf,
+    // By relative path, with `/` separators
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub files: BTreeMap<String, Annotation>,
+    // By element ID
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub elements: BTreeMap<String, ElementAnnotation>,
+}
+
+impl Annotations {
+    /// The project's annotations; none when the file doesn't exist yet
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let path = project_root.join(ANNOTATIONS_FILE);
+        let mut annotations: Self = if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_yaml::from_str(&content)
+                .with_context(|| format!("Invalid annotations in {}", path.display()))?
+        } else {
+            Self::default()
+        };
+        annotations.path = path;
+        Ok(annotations)
+    }
+
+    /// Write the file back, dropping annotations that no longer say anything
+    pub fn save(&mut self) -> Result<()> {
+        self.files.retain(|_, annotation| !annotation.is_empty());
+        self.elements
+            .retain(|_, element| !element.annotation.is_empty());
+        write_atomic_sync(&self.path, serde_yaml::to_string(self)?.as_bytes())
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.elements.is_empty()
+    }
+
+    pub fn file(&self, relative_path: &Path) -> Option<&Annotation> {
+        self.files.get(&path_key(relative_path))
+    }
+
+    pub fn element(&self, element_id: &str) -> Option<&ElementAnnotation> {
+        self.elements.get(element_id)
+    }
+
+    pub fn file_mut(&mut self, relative_path: &Path) -> &mut Annotation {
+        self.files.entry(path_key(relative_path)).or_default()
+    }
+
+    /// The element's annotation, created on first use. The recorded file and name are
+    /// refreshed so they describe the element as it is now.
+    pub fn element_mut(&mut self, file: &FileNode, element: &CodeElement) -> &mut Annotation {
+        let entry = self
+            .elements
+            .entry(element.element_id.clone())
+            .or_insert_with(|| ElementAnnotation {
+                file: PathBuf::new(),
+                name: String::new(),
+                annotation: Annotation::default(),
+            });
+        entry.file = PathBuf::from(path_key(&file.relative_path));
+        entry.name = element.qualified_name().to_string();
+        &mut entry.annotation
+    }
+
+    /// Only the annotations on files and elements of `matrix`, e.g. for docs generated
+    /// from a scoped matrix
+    pub fn in_matrix(&self, matrix: &ProjectMatrix) -> Self {
+        let mut files = BTreeMap::new();
+        let mut elements = BTreeMap::new();
+        for file in matrix.files.values() {
+            if let Some(annotation) = self.file(&file.relative_path) {
+                files.insert(path_key(&file.relative_path), annotation.clone());
+            }
+            for element in &file.elements {
+                if let Some(annotation) = self.element(&element.element_id) {
+                    elements.insert(element.element_id.clone(), annotation.clone());
+                }
+            }
+        }
+        Self {
+            path: self.path.clone(),
+            files,
+            elements,
+        }
+    }
+
+    /// Element annotations whose element the scan no longer has, by ID
+    pub fn orphans<'a>(
+        &'a self,
+        matrix: &ProjectMatrix,
+    ) -> Vec<(&'a String, &'a ElementAnnotation)> {
+        let current: std::collections::HashSet<&str> = matrix
+            .files
+            .values()
+            .flat_map(|file| file.elements.iter())
+            .map(|element| element.element_id.as_str())
+            .collect();
+        self.elements
+            .iter()
+            .filter(|(id, _)| !current.contains(id.as_str()))
+            .collect()
+    }
+}
+
+/// `src/lib.rs` for `./src/lib.rs` or `src\lib.rs`
+pub fn path_key(path: &Path) -> String {
+    normalize_lexically(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// The file at `relative_path` in the matrix and, with `name`, the one element in it
+/// that name refers to
+pub fn find_target<'a>(
+    matrix: &'a ProjectMatrix,
+    relative_path: &Path,
+    name: Option<&str>,
+) -> Result<(&'a FileNode, Option<&'a CodeElement>)> {
+    let key = path_key(relative_path);
+    let file = matrix
+        .files
+        .values()
+        .find(|file| path_key(&file.relative_path) == key)
+        .with_context(|| format!("{key} is not in the last scan"))?;
+    let Some(name) = name else {
+        return Ok((file, None));
+    };
+    let found: Vec<&CodeElement> = file
+        .elements
+        .iter()
+        .filter(|element| element.matches_name(name))
+        .collect();
+    match found.as_slice() {
+        [element] => Ok((file, Some(element))),
+        [] => Err(anyhow::anyhow!("No element '{name}' in {key}")),
+        several => Err(anyhow::anyhow!(
+            "'{name}' matches {} elements in {key} ({}); qualify the name",
+            several.len(),
+            several
+                .iter()
+                .map(|element| format!(
+                    "{} at line {}",
+                    element.qualified_name(),
+                    element.line_start
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
@@ -0,0 +1,196 @@
+// src/core/api.rs - Language-agnostic public API surface and semver-style diffing
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::matrix::{CodeElement, FileNode, PluginUsage, ProjectMatrix};
+use crate::utils::file_utils::write_atomic;
+
+/// One exported element
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiItem {
+    pub file: PathBuf, // Project-relative
+    pub name: String,  // Qualified name
+    pub kind: String,
+    pub signature: Option<String>,
+}
+
+/// Normalized public API of a project, sorted by file then name
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiSurface {
+    pub csd_version: String,
+    // Plugins behind the matrix the surface came from
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugins: Vec<PluginUsage>,
+    pub items: Vec<ApiItem>,
+}
+
+impl ApiSurface {
+    pub fn from_matrix(matrix: &ProjectMatrix) -> Self {
+        let mut items: Vec<ApiItem> = matrix
+            .files
+            .values()
+            .flat_map(|file| {
+                file.elements
+                    .iter()
+                    .filter(move |element| is_public(file, element))
+                    .map(move |element| ApiItem {
+                        file: file.relative_path.clone(),
+                        name: element.qualified_name().to_string(),
+                        kind: format!("{:?}", element.element_type).to_lowercase(),
+                        signature: element.signature.as_ref().map(|s| normalize_signature(s)),
+                    })
+            })
+            .collect();
+        items.sort_by(|a, b| (&a.file, &a.name).cmp(&(&b.file, &b.name)));
+        items.dedup_by(|a, b| a.file == b.file && a.name == b.name);
+
+        Self {
+            csd_version: matrix.metadata.csd_version.clone(),
+            plugins: matrix.metadata.plugins_used.clone(),
+            items,
+        }
+    }
+
+    /// Load a surface written by `csd api extract`, or extract one from a matrix file
+    pub async fn load(path: &Path) -> Result<Self> {
+        let json = tokio::fs::read_to_string(path).await?;
+        if let Ok(surface) = serde_json::from_str::<ApiSurface>(&json) {
+            return Ok(surface);
+        }
+        match serde_json::from_str::<ProjectMatrix>(&json) {
+            Ok(matrix) => Ok(Self::from_matrix(&matrix)),
+            Err(e) => Err(anyhow::anyhow!(
+                "{} is neither an API surface nor a matrix: {e}",
+                path.display()
+            )),
+        }
+    }
+
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        write_atomic(path, serde_json::to_string_pretty(self)?).await
+    }
+
+    /// Compare against an older surface
+    pub fn diff(&self, previous: &ApiSurface) -> ApiDiff {
+        let index = |surface: &ApiSurface| -> BTreeMap<(PathBuf, String), ApiItem> {
+            surface
+                .items
+                .iter()
+                .map(|item| ((item.file.clone(), item.name.clone()), item.clone()))
+                .collect()
+        };
+        let before = index(previous);
+        let after = index(self);
+
+        let mut diff = ApiDiff::default();
+        for (key, old) in &before {
+            match after.get(key) {
+                None => diff.removed.push(old.clone()),
+                Some(new) if new.kind != old.kind || new.signature != old.signature => {
+                    diff.changed.push(ApiChange {
+                        before: old.clone(),
+                        after: new.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        diff.added = after
+            .iter()
+            .filter(|(key, _)| !before.contains_key(*key))
+            .map(|(_, item)| item.clone())
+            .collect();
+        diff.plugin_changes = plugin_changes(&previous.plugins, &self.plugins);
+        diff
+    }
+}
+
+/// A plugin that reported different versions for the two sides of a diff
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PluginVersionChange {
+    pub name: String,
+    pub before: String,
+    pub after: String,
+}
+
+// Plugins both sides used with known, differing versions
+fn plugin_changes(before: &[PluginUsage], after: &[PluginUsage]) -> Vec<PluginVersionChange> {
+    before
+        .iter()
+        .filter_map(|old| {
+            let new = after.iter().find(|new| new.name == old.name)?;
+            match (&old.version, &new.version) {
+                (Some(before), Some(after)) if before != after => Some(PluginVersionChange {
+                    name: old.name.clone(),
+                    before: before.clone(),
+                    after: after.clone(),
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// An item whose kind or signature changed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiChange {
+    pub before: ApiItem,
+    pub after: ApiItem,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum SemverBump {
+    Patch,
+    Minor,
+    Major,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiDiff {
+    pub added: Vec<ApiItem>,
+    pub removed: Vec<ApiItem>,
+    pub changed: Vec<ApiChange>,
+    // When non-empty, some differences may come from the plugins rather than the code
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugin_changes: Vec<PluginVersionChange>,
+}
+
+impl ApiDiff {
+    /// Removals and signature changes break callers; additions don't
+    pub fn is_breaking(&self) -> bool {
+        !self.removed.is_empty() || !self.changed.is_empty()
+    }
+
+    pub fn required_bump(&self) -> SemverBump {
+        if self.is_breaking() {
+            SemverBump::Major
+        } else if !self.added.is_empty() {
+            SemverBump::Minor
+        } else {
+            SemverBump::Patch
+        }
+    }
+}
+
+/// Whether a plugin marked the element as exported. Plugins report this either through
+/// `is_public`/`visibility` metadata or the file's `exports` list.
+fn is_public(file: &FileNode, element: &CodeElement) -> bool {
+    if let Some(is_public) = element.metadata.get("is_public").and_then(|v| v.as_bool()) {
+        return is_public;
+    }
+    if let Some(visibility) = element.metadata.get("visibility").and_then(|v| v.as_str()) {
+        // `pub(crate)` and `pub(super)` stay inside the crate
+        return visibility == "pub";
+    }
+    file.exports
+        .iter()
+        .any(|export| export.source.is_none() && export.name == element.name)
+}
+
+/// Collapse whitespace so reformatting alone doesn't count as a change
+fn normalize_signature(signature: &str) -> String {
+    signature.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,194 @@
+// src/core/bench.rs - Throughput of the plugin-free scan stages, tracked across runs
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+use crate::utils::cache::CacheLock;
+
+/// One benchmark run. Only aggregate numbers are kept and the target is identified by
+/// a hash of its path, so results can be shared without revealing the project.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BenchResult {
+    pub timestamp: DateTime<Utc>,
+    pub csd_version: String,
+    // See `target_id`
+    pub target: String,
+    pub files: usize,
+    pub skipped_files: usize,
+    pub bytes: u64,
+    pub tokens: u64,
+    // Walking the tree, applying ignore rules and matching plugins
+    pub discovery_ms: f64,
+    pub hashing_ms: f64,
+    // Reading text files and estimating their tokens
+    pub tokenize_ms: f64,
+}
+
+impl BenchResult {
+    pub fn total_ms(&self) -> f64 {
+        self.discovery_ms + self.hashing_ms + self.tokenize_ms
+    }
+
+    pub fn files_per_sec(&self) -> f64 {
+        per_second(self.files as f64, self.total_ms())
+    }
+
+    pub fn mb_per_sec(&self) -> f64 {
+        per_second(self.bytes as f64 / (1024.0 * 1024.0), self.total_ms())
+    }
+}
+
+fn per_second(amount: f64, ms: f64) -> f64 {
+    if ms > 0.0 {
+        amount * 1000.0 / ms
+    } else {
+        0.0
+    }
+}
+
+/// Anonymous, stable identifier for a benchmarked directory
+pub fn target_id(root: &Path) -> String {
+    let root = root
+        .canonicalize()
+        .or_else(|_| std::path::absolute(root))
+        .unwrap_or_else(|_| root.to_path_buf());
+    format!("{:x}", Sha256::digest(root.to_string_lossy().as_bytes()))[..16].to_string()
+}
+
+/// A run alongside earlier runs of the same target
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BenchReport {
+    pub result: BenchResult,
+    pub previous: Option<BenchResult>,
+    // Highest files/sec so far, when that isn't the previous run
+    pub best: Option<BenchResult>,
+}
+
+impl BenchReport {
+    /// Compare against the runs in `history` that benchmarked the same target
+    pub fn new(result: BenchResult, history: &[BenchResult]) -> Self {
+        let earlier: Vec<&BenchResult> = history
+            .iter()
+            .filter(|run| run.target == result.target)
+            .collect();
+        let previous = earlier.last().map(|run| (*run).clone());
+        let best = earlier
+            .iter()
+            .max_by(|a, b| a.files_per_sec().total_cmp(&b.files_per_sec()))
+            .filter(|best| Some(**best) != earlier.last().copied())
+            .map(|run| (*run).clone());
+        Self {
+            result,
+            previous,
+            best,
+        }
+    }
+
+    pub fn print_summary(&self) {
+        let r = &self.result;
+        println!("\n⏱️  Scan benchmark (plugins disabled)");
+        println!("=====================================");
+        println!(
+            "Files: {} ({} skipped), {:.2} MB, {} tokens",
+            r.files,
+            r.skipped_files,
+            r.bytes as f64 / (1024.0 * 1024.0),
+            r.tokens
+        );
+        println!("  discovery: {:>9.1} ms", r.discovery_ms);
+        println!("  hashing:   {:>9.1} ms", r.hashing_ms);
+        println!("  tokenize:  {:>9.1} ms", r.tokenize_ms);
+        println!("  total:     {:>9.1} ms", r.total_ms());
+        println!(
+            "Throughput: {:.0} files/sec, {:.2} MB/sec",
+            r.files_per_sec(),
+            r.mb_per_sec()
+        );
+
+        for (label, run) in [("previous", &self.previous), ("best", &self.best)] {
+            if let Some(run) = run {
+                println!(
+                    "vs {label} run ({}): files/sec {}, MB/sec {}",
+                    run.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                    change(r.files_per_sec(), run.files_per_sec()),
+                    change(r.mb_per_sec(), run.mb_per_sec())
+                );
+            }
+        }
+        if self.previous.is_none() {
+            println!("No earlier runs of this target to compare with");
+        }
+    }
+}
+
+fn change(current: f64, earlier: f64) -> String {
+    if earlier > 0.0 {
+        format!("{:+.1}%", (current - earlier) / earlier * 100.0)
+    } else {
+        "n/a".to_string()
+    }
+}
+
+/// Benchmark results stored as JSON lines under `<cache_dir>/history/bench.jsonl`
+pub struct BenchHistory {
+    path: PathBuf,
+}
+
+impl BenchHistory {
+    pub fn new(cache_dir: &Path) -> Self {
+        Self {
+            path: cache_dir.join("history").join("bench.jsonl"),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub async fn append(&self, result: &BenchResult) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let mut line = serde_json::to_string(result)?;
+        line.push('\n');
+
+        let _lock = CacheLock::exclusive(&self.path).await?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// All recorded runs, oldest first; unreadable lines are skipped
+    pub async fn load(&self) -> Result<Vec<BenchResult>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = {
+            let _lock = CacheLock::shared(&self.path).await.ok();
+            tokio::fs::read_to_string(&self.path).await?
+        };
+        let mut results: Vec<BenchResult> = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(result) => Some(result),
+                Err(e) => {
+                    log::warn!("Skipping unreadable benchmark entry: {e}");
+                    None
+                }
+            })
+            .collect();
+        results.sort_by_key(|r| r.timestamp);
+        Ok(results)
+    }
+}
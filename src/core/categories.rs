@@ -0,0 +1,200 @@
+// src/core/categories.rs - Classify files (source, test, docs, ...) for per-category metrics
+use anyhow::{Context, Result};
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::utils::config::CategoryRule;
+
+/// What role a file plays in the project
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum FileCategory {
+    #[default]
+    Source,
+    Test,
+    Config,
+    Docs,
+    Asset,
+    Build,
+    // Generated code the project doesn't maintain by hand
+    Generated,
+    // Third-party code checked into the tree. Scanned so its dependencies are extracted,
+    // but left out of quality metrics, docs and token budgets.
+    Vendored,
+}
+
+impl FileCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileCategory::Source => "source",
+            FileCategory::Test => "test",
+            FileCategory::Config => "config",
+            FileCategory::Docs => "docs",
+            FileCategory::Asset => "asset",
+            FileCategory::Build => "build",
+            FileCategory::Generated => "generated",
+            FileCategory::Vendored => "vendored",
+        }
+    }
+
+    /// Whether files in this category are the project's own code, measured and documented
+    pub fn is_maintained(&self) -> bool {
+        *self != FileCategory::Vendored
+    }
+}
+
+impl std::fmt::Display for FileCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Built-in rules, checked in order after any configured ones. Patterns without a `/`
+/// match the file name; the others match the project-relative path.
+const DEFAULT_RULES: &[(FileCategory, &[&str])] = &[
+    (
+        FileCategory::Vendored,
+        &["vendor/**", "third_party/**", "node_modules/**"],
+    ),
+    (
+        FileCategory::Generated,
+        &[
+            "*.min.js",
+            "*.min.css",
+            "*.map",
+            "*_pb2.py",
+            "*.pb.go",
+            "*.generated.*",
+            "**/generated/**",
+        ],
+    ),
+    (
+        FileCategory::Test,
+        &[
+            "tests/**",
+            "test/**",
+            "**/tests/**",
+            "**/__tests__/**",
+            "test_*",
+            "*_test.*",
+            "*.test.*",
+            "*.spec.*",
+            "conftest.py",
+        ],
+    ),
+    (
+        FileCategory::Build,
+        &[
+            "Makefile",
+            "Dockerfile",
+            "CMakeLists.txt",
+            "build.rs",
+            "setup.py",
+            "requirements*.txt",
+            "*.lock",
+            "*.gradle",
+            ".github/**",
+        ],
+    ),
+    (
+        FileCategory::Docs,
+        &[
+            "docs/**",
+            "*.md",
+            "*.rst",
+            "*.adoc",
+            "README*",
+            "LICENSE*",
+            "CHANGELOG*",
+        ],
+    ),
+    (
+        FileCategory::Config,
+        &[
+            "*.toml",
+            "*.yaml",
+            "*.yml",
+            "*.json",
+            "*.ini",
+            "*.cfg",
+            "*.conf",
+            "*.properties",
+            ".env*",
+        ],
+    ),
+    (
+        FileCategory::Asset,
+        &[
+            "*.png", "*.jpg", "*.jpeg", "*.gif", "*.svg", "*.ico", "*.webp", "*.woff", "*.woff2",
+            "*.ttf", "*.otf", "*.mp3", "*.mp4", "*.pdf",
+        ],
+    ),
+];
+
+/// Compiled category rules; the first matching rule wins and anything unmatched is source
+pub struct FileClassifier {
+    rules: Vec<(FileCategory, Vec<Pattern>)>,
+}
+
+impl Default for FileClassifier {
+    fn default() -> Self {
+        Self::new(&[]).expect("built-in category patterns are valid")
+    }
+}
+
+static BUILTIN: LazyLock<FileClassifier> = LazyLock::new(FileClassifier::default);
+
+impl FileClassifier {
+    /// Shared classifier with only the built-in rules
+    pub fn builtin() -> &'static FileClassifier {
+        &BUILTIN
+    }
+
+    /// Configured rules take precedence over the built-in ones
+    pub fn new(custom: &[CategoryRule]) -> Result<Self> {
+        let compile = |pattern: &str| {
+            Pattern::new(pattern).with_context(|| format!("Invalid category pattern: {pattern}"))
+        };
+
+        let mut rules = Vec::new();
+        for rule in custom {
+            let patterns = rule
+                .patterns
+                .iter()
+                .map(|p| compile(p))
+                .collect::<Result<_>>()?;
+            rules.push((rule.category, patterns));
+        }
+        for (category, patterns) in DEFAULT_RULES {
+            let patterns = patterns.iter().map(|p| compile(p)).collect::<Result<_>>()?;
+            rules.push((*category, patterns));
+        }
+        Ok(Self { rules })
+    }
+
+    pub fn classify(&self, relative_path: &Path) -> FileCategory {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+        let file_name = Path::new(relative_path.file_name().unwrap_or_default());
+
+        self.rules
+            .iter()
+            .find(|(_, patterns)| {
+                patterns.iter().any(|pattern| {
+                    if pattern.as_str().contains('/') {
+                        pattern.matches_path_with(relative_path, options)
+                    } else {
+                        pattern.matches_path_with(file_name, options)
+                    }
+                })
+            })
+            .map(|(category, _)| *category)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,215 @@
+// src/core/changelog.rs - Draft changelog from the structural difference between two
+// scans plus the commits in between, grouped by component
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::core::api::{ApiItem, ApiSurface};
+use crate::core::matrix::ProjectMatrix;
+use crate::utils::git::Commit;
+
+/// Written to the docs output directory
+pub const CHANGELOG_FILE: &str = "CHANGELOG.draft.md";
+
+// Component of files directly in the project root
+const ROOT_COMPONENT: &str = "(root)";
+
+/// Everything that changed since `since`, one entry per component that changed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Changelog {
+    // The tag, commit or matrix file the changes are relative to
+    pub since: String,
+    pub components: Vec<ComponentChanges>,
+}
+
+/// Changes within one directory. Paths are relative to the project root; API entries
+/// are qualified names.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComponentChanges {
+    pub component: String,
+    pub commits: Vec<Commit>,
+    pub api_added: Vec<ApiItem>,
+    pub api_removed: Vec<ApiItem>,
+    // (before, after)
+    pub api_changed: Vec<(ApiItem, ApiItem)>,
+    pub files_added: Vec<PathBuf>,
+    pub files_removed: Vec<PathBuf>,
+    pub files_modified: Vec<PathBuf>,
+}
+
+impl ComponentChanges {
+    /// Removed or changed API breaks callers
+    pub fn is_breaking(&self) -> bool {
+        !self.api_removed.is_empty() || !self.api_changed.is_empty()
+    }
+}
+
+impl Changelog {
+    /// Compare `after` against `before`. Files are matched by relative path, so the two
+    /// matrices may come from different checkouts. Each commit is listed under the
+    /// component holding most of the files it touched.
+    pub fn build(
+        since: &str,
+        before: &ProjectMatrix,
+        after: &ProjectMatrix,
+        commits: Vec<Commit>,
+    ) -> Result<Self> {
+        if before.metadata.hash_algorithm != after.metadata.hash_algorithm {
+            return Err(anyhow::anyhow!(
+                "Can't compare file hashes: the current matrix used {} and {since} {}",
+                after.metadata.hash_algorithm,
+                before.metadata.hash_algorithm
+            ));
+        }
+        let mut components: BTreeMap<String, ComponentChanges> = BTreeMap::new();
+        let hashes = |matrix: &ProjectMatrix| -> HashMap<PathBuf, String> {
+            matrix
+                .files
+                .values()
+                .map(|file| (file.relative_path.clone(), file.hash.clone()))
+                .collect()
+        };
+        let (old, new) = (hashes(before), hashes(after));
+        for (path, hash) in &new {
+            match old.get(path) {
+                None => entry(&mut components, component_of(path))
+                    .files_added
+                    .push(path.clone()),
+                Some(old_hash) if old_hash != hash => entry(&mut components, component_of(path))
+                    .files_modified
+                    .push(path.clone()),
+                Some(_) => {}
+            }
+        }
+        for path in old.keys().filter(|path| !new.contains_key(*path)) {
+            entry(&mut components, component_of(path))
+                .files_removed
+                .push(path.clone());
+        }
+
+        let api = ApiSurface::from_matrix(after).diff(&ApiSurface::from_matrix(before));
+        for item in api.added {
+            entry(&mut components, component_of(&item.file))
+                .api_added
+                .push(item);
+        }
+        for item in api.removed {
+            entry(&mut components, component_of(&item.file))
+                .api_removed
+                .push(item);
+        }
+        for change in api.changed {
+            entry(&mut components, component_of(&change.after.file))
+                .api_changed
+                .push((change.before, change.after));
+        }
+
+        for commit in commits {
+            let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+            for file in &commit.files {
+                *counts.entry(component_of(file)).or_default() += 1;
+            }
+            // Ties go to the first component by name
+            let component = counts
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+                .map_or_else(|| ROOT_COMPONENT.to_string(), |(component, _)| component);
+            entry(&mut components, component).commits.push(commit);
+        }
+
+        let mut components: Vec<ComponentChanges> = components.into_values().collect();
+        for component in &mut components {
+            component.files_added.sort();
+            component.files_removed.sort();
+            component.files_modified.sort();
+        }
+        Ok(Self {
+            since: since.to_string(),
+            components,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Markdown draft: per component its commits, then API changes, then files
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# Changelog (draft, since {})\n", self.since);
+        if self.is_empty() {
+            md.push_str("\nNo changes.\n");
+            return md;
+        }
+        for component in &self.components {
+            md.push_str(&format!("\n## {}\n", component.component));
+            if component.is_breaking() {
+                md.push_str("\n**Breaking:** public API was removed or changed.\n");
+            }
+            if !component.commits.is_empty() {
+                md.push_str("\n### Commits\n\n");
+                for commit in &component.commits {
+                    md.push_str(&format!("- {} ({})\n", commit.subject, commit.hash));
+                }
+            }
+
+            let mut api = Vec::new();
+            for item in &component.api_added {
+                api.push(format!("- Added `{}` ({})", item.name, item.kind));
+            }
+            for item in &component.api_removed {
+                api.push(format!("- Removed `{}` ({})", item.name, item.kind));
+            }
+            for (before, after) in &component.api_changed {
+                api.push(format!(
+                    "- Changed `{}`: `{}` → `{}`",
+                    after.name,
+                    before.signature.as_deref().unwrap_or_default(),
+                    after.signature.as_deref().unwrap_or_default()
+                ));
+            }
+            if !api.is_empty() {
+                md.push_str(&format!("\n### API\n\n{}\n", api.join("\n")));
+            }
+
+            let mut files = Vec::new();
+            for (verb, paths) in [
+                ("Added", &component.files_added),
+                ("Removed", &component.files_removed),
+                ("Modified", &component.files_modified),
+            ] {
+                for path in paths {
+                    files.push(format!("- {verb} `{}`", display_path(path)));
+                }
+            }
+            if !files.is_empty() {
+                md.push_str(&format!("\n### Files\n\n{}\n", files.join("\n")));
+            }
+        }
+        md
+    }
+}
+
+fn entry(
+    components: &mut BTreeMap<String, ComponentChanges>,
+    component: String,
+) -> &mut ComponentChanges {
+    components
+        .entry(component.clone())
+        .or_insert_with(|| ComponentChanges {
+            component,
+            ..ComponentChanges::default()
+        })
+}
+
+/// The directory a file belongs to, e.g. `src/core` for `src/core/scanner.rs`
+pub fn component_of(path: &Path) -> String {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => display_path(parent),
+        _ => ROOT_COMPONENT.to_string(),
+    }
+}
+
+fn display_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
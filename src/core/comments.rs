@@ -0,0 +1,206 @@
+// src/core/comments.rs - Comment and documentation token counts for files no plugin analyzes
+use std::path::Path;
+
+use crate::core::matrix::estimate_code_tokens;
+
+/// How a language marks comments. Doc markers are checked before plain ones, since
+/// they usually extend them (`///` vs `//`).
+#[derive(Debug, PartialEq, Eq)]
+pub struct CommentSyntax {
+    pub line: &'static [&'static str],
+    pub doc_line: &'static [&'static str],
+    pub block: &'static [(&'static str, &'static str)],
+    pub doc_block: &'static [(&'static str, &'static str)],
+}
+
+const C_LIKE: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    doc_line: &["///", "//!"],
+    block: &[("/*", "*/")],
+    doc_block: &[("/**", "*/"), ("/*!", "*/")],
+};
+
+const PYTHON: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    doc_line: &[],
+    block: &[],
+    doc_block: &[("\"\"\"", "\"\"\""), ("'''", "'''")],
+};
+
+const HASH: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    doc_line: &[],
+    block: &[],
+    doc_block: &[],
+};
+
+const RUBY: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    doc_line: &[],
+    block: &[("=begin", "=end")],
+    doc_block: &[],
+};
+
+const DASH: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    doc_line: &["---"],
+    block: &[("--[[", "]]"), ("{-", "-}")],
+    doc_block: &[("{-|", "-}")],
+};
+
+const MARKUP: CommentSyntax = CommentSyntax {
+    line: &[],
+    doc_line: &[],
+    block: &[("<!--", "-->")],
+    doc_block: &[],
+};
+
+/// Comment syntax for `path`'s extension, or None for languages not in the table
+pub fn syntax_for(path: &Path) -> Option<&'static CommentSyntax> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let syntax = match extension.as_str() {
+        "rs" | "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "java" | "kt" | "kts" | "scala"
+        | "go" | "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "cs" | "swift" | "dart" | "php"
+        | "groovy" | "proto" => &C_LIKE,
+        "py" | "pyi" => &PYTHON,
+        "sh" | "bash" | "zsh" | "pl" | "r" | "ps1" | "toml" | "yaml" | "yml" | "cmake" => &HASH,
+        "rb" => &RUBY,
+        "sql" | "lua" | "hs" => &DASH,
+        "html" | "htm" | "xml" | "vue" | "svelte" => &MARKUP,
+        _ => return None,
+    };
+    Some(syntax)
+}
+
+/// `(line, comment)` for each line with a comment on it: the text from the comment
+/// marker on, or the whole line inside a block comment. Lines are 1-based; strings
+/// aren't parsed, so a marker inside one counts.
+pub fn comments_in<'a>(content: &'a str, syntax: &CommentSyntax) -> Vec<(usize, &'a str)> {
+    let mut found = Vec::new();
+    let mut block_end: Option<&str> = None;
+    for (index, line) in content.lines().enumerate() {
+        let comment = match block_end {
+            Some(end) => {
+                if line.contains(end) {
+                    block_end = None;
+                }
+                Some(line)
+            }
+            None => comment_start(line, syntax, &mut block_end).map(|start| &line[start..]),
+        };
+        if let Some(comment) = comment {
+            found.push((index + 1, comment));
+        }
+    }
+    found
+}
+
+// Byte offset where a comment starts on `line`; notes a block comment left open
+fn comment_start<'a>(
+    line: &str,
+    syntax: &'a CommentSyntax,
+    block_end: &mut Option<&'a str>,
+) -> Option<usize> {
+    let line_start = syntax
+        .line
+        .iter()
+        .chain(syntax.doc_line)
+        .filter_map(|prefix| line.find(prefix))
+        .min();
+    let block_start = syntax
+        .block
+        .iter()
+        .chain(syntax.doc_block)
+        .filter_map(|(open, close)| line.find(open).map(|at| (at, *open, *close)))
+        .min_by_key(|(at, _, _)| *at);
+    match (line_start, block_start) {
+        (Some(line_at), Some((block_at, _, _))) if line_at <= block_at => Some(line_at),
+        (_, Some((at, open, close))) => {
+            if !line[at + open.len()..].contains(close) {
+                *block_end = Some(close);
+            }
+            Some(at)
+        }
+        (line_at, None) => line_at,
+    }
+}
+
+/// Estimated tokens in documentation comments (doc comments, docstrings) and in other
+/// whole-line comments
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CommentTokens {
+    pub documentation: u64,
+    pub comment: u64,
+}
+
+/// Split `content`'s comment lines into documentation and plain comments. Comments after
+/// code on the same line count as code, and strings aren't parsed, so this is an estimate
+/// on the same scale as `estimate_code_tokens`.
+pub fn count_comment_tokens(content: &str, syntax: &CommentSyntax) -> CommentTokens {
+    let mut documentation = String::new();
+    let mut comment = String::new();
+    // End marker of the block being read, and whether it documents
+    let mut open_block: Option<(&str, bool)> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some((end, is_doc)) = open_block {
+            let text = if is_doc {
+                &mut documentation
+            } else {
+                &mut comment
+            };
+            push_line(text, trimmed);
+            if trimmed.contains(end) {
+                open_block = None;
+            }
+            continue;
+        }
+
+        if starts_with_any(trimmed, syntax.doc_line) {
+            push_line(&mut documentation, trimmed);
+        } else if let Some((start, end)) = block_start(trimmed, syntax.doc_block) {
+            push_line(&mut documentation, trimmed);
+            if !trimmed[start.len()..].contains(end) {
+                open_block = Some((end, true));
+            }
+        } else if let Some((start, end)) = block_start(trimmed, syntax.block) {
+            push_line(&mut comment, trimmed);
+            if !trimmed[start.len()..].contains(end) {
+                open_block = Some((end, false));
+            }
+        } else if starts_with_any(trimmed, syntax.line) {
+            push_line(&mut comment, trimmed);
+        }
+    }
+
+    CommentTokens {
+        documentation: count_tokens(&documentation),
+        comment: count_tokens(&comment),
+    }
+}
+
+// `estimate_code_tokens` counts at least one token, even for no text
+fn count_tokens(text: &str) -> u64 {
+    if text.is_empty() {
+        0
+    } else {
+        estimate_code_tokens(text)
+    }
+}
+
+fn starts_with_any(line: &str, prefixes: &[&str]) -> bool {
+    prefixes.iter().any(|prefix| line.starts_with(prefix))
+}
+
+fn block_start<'a>(line: &str, blocks: &[(&'a str, &'a str)]) -> Option<(&'a str, &'a str)> {
+    blocks
+        .iter()
+        .find(|(start, _)| line.starts_with(start))
+        .copied()
+}
+
+fn push_line(text: &mut String, line: &str) {
+    text.push_str(line);
+    text.push('\n');
+}
@@ -0,0 +1,132 @@
+// src/core/compact.rs - Strip heavy optional fields from a matrix for consumers that only
+// need part of it, such as the dependency graph
+use serde::Serialize;
+
+use crate::core::matrix::ProjectMatrix;
+
+/// What `compact_matrix` strips
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompactOptions {
+    // Plugin metadata blobs on elements; file metadata holds tags and stays
+    pub metadata: bool,
+    pub signatures: bool,
+    // File and element summaries, and the glossary terms drawn from them
+    pub summaries: bool,
+    // Elements, imports and exports altogether, leaving files and relationships
+    pub graph_only: bool,
+}
+
+impl CompactOptions {
+    /// Metadata, signatures and summaries: everything but the structure
+    pub fn all() -> Self {
+        Self {
+            metadata: true,
+            signatures: true,
+            summaries: true,
+            graph_only: false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// What compaction removed, for reporting
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CompactSummary {
+    pub metadata: usize,
+    pub signatures: usize,
+    pub summaries: usize,
+    pub elements: usize,
+    pub imports: usize,
+    pub exports: usize,
+    // Sizes of the saved matrix before and after, filled in by the caller
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+impl CompactSummary {
+    pub fn bytes_saved(&self) -> u64 {
+        self.bytes_before.saturating_sub(self.bytes_after)
+    }
+
+    pub fn print_summary(&self) {
+        println!("🗜️  Matrix Compaction:");
+        for (count, what) in [
+            (self.metadata, "metadata blobs"),
+            (self.signatures, "signatures"),
+            (self.summaries, "summaries"),
+            (self.elements, "elements"),
+            (self.imports, "imports"),
+            (self.exports, "exports"),
+        ] {
+            if count > 0 {
+                println!("   Stripped {count} {what}");
+            }
+        }
+        let percent = if self.bytes_before > 0 {
+            self.bytes_saved() as f64 * 100.0 / self.bytes_before as f64
+        } else {
+            0.0
+        };
+        println!(
+            "   {} → {} bytes ({} saved, {percent:.1}%)",
+            self.bytes_before,
+            self.bytes_after,
+            self.bytes_saved()
+        );
+    }
+}
+
+/// Strip the fields `options` names in place. Element IDs are kept as they are, so
+/// references to elements still resolve after signatures are gone.
+pub fn compact_matrix(matrix: &mut ProjectMatrix, options: &CompactOptions) -> CompactSummary {
+    let mut summary = CompactSummary::default();
+
+    for file in matrix.files.values_mut() {
+        if options.summaries {
+            summary.summaries += usize::from(file.file_summary.take().is_some());
+        }
+        if options.graph_only {
+            summary.elements += std::mem::take(&mut file.elements).len();
+            summary.imports += std::mem::take(&mut file.imports).len();
+            summary.exports += std::mem::take(&mut file.exports).len();
+            continue;
+        }
+        for element in &mut file.elements {
+            if options.summaries {
+                summary.summaries += usize::from(element.summary.take().is_some());
+            }
+            if options.signatures {
+                summary.signatures += usize::from(element.signature.take().is_some());
+            }
+            if options.metadata && !element.metadata.is_null() {
+                // Older plugins reported qualified names there
+                if element.qualified_name.is_none() {
+                    element.qualified_name = element
+                        .metadata
+                        .get("qualified_name")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+                }
+                element.metadata = serde_json::Value::Null;
+                summary.metadata += 1;
+            }
+        }
+    }
+    if options.graph_only {
+        // The elements they pointed at are gone
+        for relationship in &mut matrix.relationships {
+            relationship.from_element = None;
+            relationship.to_element = None;
+        }
+    }
+    if options.summaries {
+        summary.summaries += matrix.project_info.glossary_candidates.len();
+        matrix.project_info.glossary_candidates.clear();
+    }
+    matrix.invalidate_graph();
+
+    summary
+}
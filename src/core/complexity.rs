@@ -0,0 +1,75 @@
+// src/core/complexity.rs - Language-agnostic complexity estimate for elements plugins leave unscored
+use crate::core::matrix::{CodeElement, ElementType};
+
+/// Metadata flag set on elements whose `complexity_score` came from `estimate_complexity`
+/// rather than the plugin
+pub const ESTIMATED_FLAG: &str = "complexity_estimated";
+
+// Keywords that open a branch in common languages. `else` is left out so `else if`
+// counts once, like the cyclomatic complexity plugins report.
+const BRANCH_KEYWORDS: &[&str] = &[
+    "if", "elif", "elsif", "unless", "for", "foreach", "while", "until", "loop", "case", "when",
+    "catch", "except", "rescue", "guard",
+];
+
+// Short-circuit operators add a path each
+const BRANCH_OPERATORS: &[&str] = &["&&", "||", "?:", "??"];
+
+// Whole-line comment markers; those lines can't branch
+const COMMENT_PREFIXES: &[&str] = &["//", "#", "/*", "*", "--", ";"];
+
+/// Rough cyclomatic complexity of `code`: 1 plus its branch keywords and short-circuit
+/// operators. Strings aren't parsed, so this is an estimate for when no plugin knows better.
+pub fn estimate_complexity(code: &str) -> u32 {
+    let mut complexity = 1;
+    for line in code.lines() {
+        let line = line.trim_start();
+        if COMMENT_PREFIXES
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        {
+            continue;
+        }
+        let keywords = line
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|word| BRANCH_KEYWORDS.contains(word))
+            .count();
+        let operators: usize = BRANCH_OPERATORS
+            .iter()
+            .map(|op| line.matches(op).count())
+            .sum();
+        complexity += (keywords + operators) as u32;
+    }
+    complexity
+}
+
+/// Score the functions and methods a plugin left without `complexity_score` from their
+/// lines in `content`, flagging each with `ESTIMATED_FLAG`. Returns how many were scored.
+pub fn fill_missing_complexity(elements: &mut [CodeElement], content: &str) -> usize {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut filled = 0;
+    for element in elements.iter_mut().filter(|e| needs_estimate(e)) {
+        let start = element.line_start.max(1) as usize - 1;
+        let end = (element.line_end as usize).min(lines.len());
+        if start >= end {
+            continue;
+        }
+
+        element.complexity_score = Some(estimate_complexity(&lines[start..end].join("\n")));
+        if !element.metadata.is_object() {
+            element.metadata = serde_json::json!({});
+        }
+        element.metadata[ESTIMATED_FLAG] = serde_json::Value::Bool(true);
+        filled += 1;
+    }
+    filled
+}
+
+/// Whether `element` is a function or method without a plugin-reported complexity
+pub fn needs_estimate(element: &CodeElement) -> bool {
+    element.complexity_score.is_none()
+        && matches!(
+            element.element_type,
+            ElementType::Function | ElementType::Method
+        )
+}
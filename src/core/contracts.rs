@@ -0,0 +1,940 @@
+// src/core/contracts.rs - API contracts read from OpenAPI specs and .proto files, linked
+// to the code handling their operations, and checked against the routes the code declares
+use chrono::Utc;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::core::categories::{FileCategory, FileClassifier};
+use crate::core::data_model::contains_word;
+use crate::core::matrix::{FileNode, ProjectMatrix, Relationship, RelationshipType};
+
+/// Recorded as the `source_plugin` of relationships linked here
+pub const CONTRACTS_SOURCE: &str = "contracts";
+
+const HTTP_METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch"];
+
+// Spring's `@GetMapping` and friends
+const SPRING_MAPPINGS: &[(&str, &str)] = &[
+    ("GetMapping", "GET"),
+    ("PostMapping", "POST"),
+    ("PutMapping", "PUT"),
+    ("PatchMapping", "PATCH"),
+    ("DeleteMapping", "DELETE"),
+];
+
+// Receivers whose `get("/...")` is an HTTP client call or a lookup, not a route
+const CLIENT_RECEIVERS: &[&str] = &[
+    "requests",
+    "httpx",
+    "axios",
+    "client",
+    "session",
+    "http",
+    "https",
+    "fetch",
+    "request",
+    "superagent",
+    "api",
+    "$http",
+    "cache",
+    "params",
+    "headers",
+    "environ",
+    "config",
+];
+
+// Files with these extensions are searched for routes and RPC handlers
+const CODE_EXTENSIONS: &[&str] = &[
+    "py", "rs", "js", "jsx", "mjs", "ts", "tsx", "go", "java", "kt", "rb", "php", "cs",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContractKind {
+    OpenApi,
+    Protobuf,
+}
+
+/// An OpenAPI spec or a .proto file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contract {
+    // `info.title`, or the proto package
+    pub name: String,
+    pub kind: ContractKind,
+    // Relative path of the spec
+    pub spec: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    // Path the spec's routes are served under: `basePath`, or the path of the first server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<String>,
+    pub operations: Vec<Operation>,
+    // Proto messages, or OpenAPI schemas
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<Message>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Operation {
+    // `GET /users/{id}`, or `UserService.GetUser`
+    pub name: String,
+    // `operationId` or summary of an OpenAPI operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    // Request and response message or schema names
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    // Relative paths of the files handling it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub handlers: Vec<PathBuf>,
+}
+
+impl Operation {
+    fn named(name: String) -> Self {
+        Self {
+            name,
+            description: None,
+            input: None,
+            output: None,
+            handlers: Vec::new(),
+        }
+    }
+
+    // `GET /users/{id}` -> (`GET`, `/users/{id}`)
+    fn http(&self) -> Option<(&str, &str)> {
+        self.name.split_once(' ')
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    pub name: String,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Field {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_type: Option<String>,
+}
+
+/// An HTTP route declared in code
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Route {
+    pub file: PathBuf,
+    pub line: u32,
+    // Upper case; `ANY` when the declaration doesn't say
+    pub method: String,
+    pub path: String,
+}
+
+/// Read the project's contracts and the routes its code declares into the matrix, and
+/// add a contract relationship from each handler file to the spec it implements
+pub fn link_contracts(matrix: &mut ProjectMatrix) {
+    let classifier = FileClassifier::builtin();
+    let mut files: Vec<&FileNode> = matrix.files.values().collect();
+    files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+    let mut contracts = Vec::new();
+    let mut routes = Vec::new();
+    let mut handlers: Vec<&FileNode> = Vec::new();
+    for file in files {
+        let relative = file.relative_path.as_path();
+        let extension = relative
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        let is_spec = matches!(extension, "yaml" | "yml" | "json" | "proto");
+        let category = file
+            .category
+            .unwrap_or_else(|| classifier.classify(relative));
+        let is_handler = CODE_EXTENSIONS.contains(&extension) && category == FileCategory::Source;
+        if !(is_spec || is_handler) {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&file.path) else {
+            debug!("Could not read {} for contracts", file.path.display());
+            continue;
+        };
+        if extension == "proto" {
+            contracts.push(parse_proto(relative, &content));
+        } else if is_spec {
+            contracts.extend(parse_openapi(relative, &content));
+        } else {
+            routes.extend(extract_routes(relative, &content));
+            handlers.push(file);
+        }
+    }
+    routes.sort();
+
+    // RPC handlers are found by name, so handler files are read again only for protos
+    let rpcs: Vec<String> = contracts
+        .iter()
+        .filter(|c| c.kind == ContractKind::Protobuf)
+        .flat_map(|c| c.operations.iter().map(|op| op.name.clone()))
+        .collect();
+    let rpc_handlers: Vec<(&Path, Vec<&String>)> = if rpcs.is_empty() {
+        Vec::new()
+    } else {
+        handlers
+            .iter()
+            .filter_map(|file| {
+                let content = std::fs::read_to_string(&file.path).ok()?;
+                let handled: Vec<&String> = rpcs
+                    .iter()
+                    .filter(|rpc| handles_rpc(&content, rpc))
+                    .collect();
+                Some((file.relative_path.as_path(), handled))
+            })
+            .collect()
+    };
+
+    let mut relationships = Vec::new();
+    for contract in &mut contracts {
+        for operation in &mut contract.operations {
+            let mut linked: Vec<(&Path, Option<u32>)> = match contract.kind {
+                ContractKind::OpenApi => routes
+                    .iter()
+                    .filter(|route| implements(route, operation, contract.base_path.as_deref()))
+                    .map(|route| (route.file.as_path(), Some(route.line)))
+                    .collect(),
+                ContractKind::Protobuf => rpc_handlers
+                    .iter()
+                    .filter(|(_, handled)| handled.contains(&&operation.name))
+                    .map(|(path, _)| (*path, None))
+                    .collect(),
+            };
+            linked.dedup_by(|a, b| a.0 == b.0);
+            operation.handlers = linked.iter().map(|(path, _)| path.to_path_buf()).collect();
+            for (handler, line) in linked {
+                relationships.push(Relationship {
+                    from_file: handler.to_path_buf(),
+                    to_file: contract.spec.clone(),
+                    relationship_type: RelationshipType::Contract,
+                    details: format!("implements {}", operation.name),
+                    line_number: line,
+                    strength: 0.9,
+                    source_plugin: Some(CONTRACTS_SOURCE.into()),
+                    detected_at: Some(Utc::now()),
+                    from_element: None,
+                    to_element: None,
+                    runtime_calls: None,
+                });
+            }
+        }
+    }
+
+    for relationship in relationships {
+        matrix.add_relationship(relationship);
+    }
+    matrix.project_info.contracts = contracts;
+    matrix.project_info.routes = routes;
+}
+
+// Whether `route` serves the OpenAPI `operation`
+fn implements(route: &Route, operation: &Operation, base_path: Option<&str>) -> bool {
+    let Some((method, path)) = operation.http() else {
+        return false;
+    };
+    if route.method != "ANY" && route.method != method {
+        return false;
+    }
+    let route_path = normalize_path(&route.path);
+    route_path == normalize_path(path)
+        || base_path.is_some_and(|base| route_path == normalize_path(&format!("{base}/{path}")))
+}
+
+// Whether code defines a handler for the RPC `Service.Method`: it names the service (as
+// `UserServiceServicer`, `UserServiceServer`, `impl UserService for`, ...) other than
+// through a client stub, and the method in its Go or Python, Rust or Java spelling
+fn handles_rpc(content: &str, rpc: &str) -> bool {
+    let Some((service, method)) = rpc.rsplit_once('.') else {
+        return false;
+    };
+    let serves = content.match_indices(service).any(|(at, _)| {
+        let rest = &content[at + service.len()..];
+        !rest.starts_with("Stub") && !rest.starts_with("Client")
+    });
+    if !serves {
+        return false;
+    }
+    let mut lower_camel = method.to_string();
+    if let Some(first) = lower_camel.get_mut(..1) {
+        first.make_ascii_lowercase();
+    }
+    [method.to_string(), snake_case(method), lower_camel]
+        .iter()
+        .any(|name| contains_word(content, name))
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (index, c) in name.char_indices() {
+        if c.is_uppercase() && index > 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+/// `/users/{id}/`, `/users/<int:id>` and `/users/:id` all become `/users/{}`
+pub fn normalize_path(path: &str) -> String {
+    let segments: Vec<&str> = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|segment| {
+            if segment.starts_with(['{', '<', ':', '[']) {
+                "{}"
+            } else {
+                segment
+            }
+        })
+        .collect();
+    format!("/{}", segments.join("/"))
+}
+
+/// Routes and RPCs the contracts describe that no code implements, and routes the code
+/// declares that no OpenAPI spec describes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContractDrift {
+    pub contracts: usize,
+    pub operations: usize,
+    // (spec, operation)
+    pub unimplemented: Vec<(PathBuf, String)>,
+    // Only checked when the project has an OpenAPI spec
+    pub undocumented: Vec<Route>,
+}
+
+impl ContractDrift {
+    pub fn check(matrix: &ProjectMatrix) -> Self {
+        let contracts = &matrix.project_info.contracts;
+        let mut drift = Self {
+            contracts: contracts.len(),
+            operations: contracts.iter().map(|c| c.operations.len()).sum(),
+            ..Self::default()
+        };
+        for contract in contracts {
+            for operation in &contract.operations {
+                if operation.handlers.is_empty() {
+                    drift
+                        .unimplemented
+                        .push((contract.spec.clone(), operation.name.clone()));
+                }
+            }
+        }
+        let specs: Vec<&Contract> = contracts
+            .iter()
+            .filter(|c| c.kind == ContractKind::OpenApi)
+            .collect();
+        if !specs.is_empty() {
+            drift.undocumented = matrix
+                .project_info
+                .routes
+                .iter()
+                .filter(|route| {
+                    !specs.iter().any(|spec| {
+                        spec.operations
+                            .iter()
+                            .any(|op| implements(route, op, spec.base_path.as_deref()))
+                    })
+                })
+                .cloned()
+                .collect();
+        }
+        drift
+    }
+
+    /// Operations and routes out of step with the contracts
+    pub fn count(&self) -> usize {
+        self.unimplemented.len() + self.undocumented.len()
+    }
+
+    pub fn print_summary(&self) {
+        println!("\n📜 API Contracts:");
+        if self.contracts == 0 {
+            println!("   No OpenAPI specs or .proto files found");
+            return;
+        }
+        println!(
+            "   {} operations in {} contracts",
+            self.operations, self.contracts
+        );
+        if self.count() == 0 {
+            println!("   Code and contracts agree");
+            return;
+        }
+        if !self.unimplemented.is_empty() {
+            println!("   In the contract but not in code:");
+            for (spec, operation) in &self.unimplemented {
+                println!("     {operation} ({})", spec.display());
+            }
+        }
+        if !self.undocumented.is_empty() {
+            println!("   In code but not in any spec:");
+            for route in &self.undocumented {
+                println!(
+                    "     {} {} ({}:{})",
+                    route.method,
+                    route.path,
+                    route.file.display(),
+                    route.line
+                );
+            }
+        }
+    }
+}
+
+// --- OpenAPI ---
+
+/// The operations and schemas of an OpenAPI 3 or Swagger 2 document; other YAML and
+/// JSON give none
+pub fn parse_openapi(relative: &Path, content: &str) -> Option<Contract> {
+    if !content.contains("openapi") && !content.contains("swagger") {
+        return None;
+    }
+    let document: Value = serde_yaml::from_str(content).ok()?;
+    document
+        .get("openapi")
+        .or_else(|| document.get("swagger"))?;
+    let text = |value: Option<&Value>| value.and_then(Value::as_str).map(str::to_string);
+
+    let info = document.get("info");
+    let name = text(info.and_then(|i| i.get("title"))).unwrap_or_else(|| {
+        relative
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("api")
+            .to_string()
+    });
+    let base_path = text(document.get("basePath")).or_else(|| {
+        let url = text(document.get("servers")?.get(0)?.get("url"))?;
+        let path = match url.split_once("://") {
+            Some((_, rest)) => rest.find('/').map_or("", |slash| &rest[slash..]),
+            None => url.as_str(),
+        };
+        (!path.is_empty() && path != "/").then(|| path.trim_end_matches('/').to_string())
+    });
+
+    let mut operations = Vec::new();
+    for (path, item) in document
+        .get("paths")
+        .and_then(Value::as_mapping)
+        .into_iter()
+        .flatten()
+    {
+        let Some(path) = path.as_str() else {
+            continue;
+        };
+        for method in HTTP_METHODS {
+            let Some(spec) = item.get(*method) else {
+                continue;
+            };
+            let mut operation = Operation::named(format!("{} {path}", method.to_uppercase()));
+            operation.description =
+                text(spec.get("operationId")).or_else(|| text(spec.get("summary")));
+            operation.input = spec.get("requestBody").and_then(schema_name).or_else(|| {
+                // Swagger 2 puts the body among the parameters
+                spec.get("parameters")?
+                    .as_sequence()?
+                    .iter()
+                    .find(|p| p.get("in").and_then(Value::as_str) == Some("body"))
+                    .and_then(schema_name)
+            });
+            operation.output =
+                spec.get("responses")
+                    .and_then(Value::as_mapping)
+                    .and_then(|responses| {
+                        responses
+                            .iter()
+                            .filter(|(status, _)| {
+                                status.as_str().is_some_and(|s| s.starts_with('2'))
+                                    || status.as_u64().is_some_and(|s| (200..300).contains(&s))
+                            })
+                            .find_map(|(_, response)| schema_name(response))
+                    });
+            operations.push(operation);
+        }
+    }
+
+    let schemas = document
+        .get("components")
+        .and_then(|c| c.get("schemas"))
+        .or_else(|| document.get("definitions"))
+        .and_then(Value::as_mapping);
+    let messages = schemas
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, schema)| {
+            let fields = schema
+                .get("properties")
+                .and_then(Value::as_mapping)
+                .into_iter()
+                .flatten()
+                .filter_map(|(field, property)| {
+                    Some(Field {
+                        name: field.as_str()?.to_string(),
+                        field_type: text(property.get("type")).or_else(|| reference(property)),
+                    })
+                })
+                .collect();
+            Some(Message {
+                name: name.as_str()?.to_string(),
+                fields,
+            })
+        })
+        .collect();
+
+    Some(Contract {
+        name,
+        kind: ContractKind::OpenApi,
+        spec: relative.to_path_buf(),
+        version: text(info.and_then(|i| i.get("version"))),
+        base_path,
+        operations,
+        messages,
+    })
+}
+
+// `#/components/schemas/User` -> `User`
+fn reference(value: &Value) -> Option<String> {
+    let target = value
+        .get("$ref")
+        .or_else(|| value.get("items")?.get("$ref"))?
+        .as_str()?;
+    target.rsplit('/').next().map(str::to_string)
+}
+
+// The schema a request body, body parameter or response refers to
+fn schema_name(value: &Value) -> Option<String> {
+    if let Some(schema) = value.get("schema") {
+        return reference(schema);
+    }
+    value
+        .get("content")?
+        .as_mapping()?
+        .values()
+        .find_map(|media| reference(media.get("schema")?))
+}
+
+// --- Protobuf ---
+
+/// The services and messages of a .proto file
+pub fn parse_proto(relative: &Path, content: &str) -> Contract {
+    let tokens = proto_tokens(content);
+    let mut package = None;
+    let mut operations = Vec::new();
+    let mut messages: Vec<Message> = Vec::new();
+    // Enclosing blocks: `message` (index into `messages`), `service` (its name) or other
+    let mut scopes: Vec<ProtoScope> = Vec::new();
+    let mut index = 0;
+    let token = |i: usize| tokens.get(i).map(String::as_str).unwrap_or_default();
+    while index < tokens.len() {
+        match (token(index), scopes.last()) {
+            ("}", _) => {
+                scopes.pop();
+                index += 1;
+            }
+            ("package", None) => {
+                package = Some(token(index + 1).to_string());
+                index = skip_statement(&tokens, index);
+            }
+            ("message", _) => {
+                let name = match scopes.iter().rev().find_map(ProtoScope::message) {
+                    Some(parent) => format!("{}.{}", messages[parent].name, token(index + 1)),
+                    None => token(index + 1).to_string(),
+                };
+                messages.push(Message {
+                    name,
+                    fields: Vec::new(),
+                });
+                scopes.push(ProtoScope::Message(messages.len() - 1));
+                index += 3;
+            }
+            ("service", _) => {
+                scopes.push(ProtoScope::Service(token(index + 1).to_string()));
+                index += 3;
+            }
+            ("rpc", Some(ProtoScope::Service(service))) => {
+                let mut operation = Operation::named(format!("{service}.{}", token(index + 1)));
+                // rpc Name ( [stream] Input ) returns ( [stream] Output )
+                let types: Vec<&str> = tokens[index + 2..]
+                    .iter()
+                    .take_while(|t| *t != ";" && *t != "{")
+                    .map(String::as_str)
+                    .filter(|t| !["(", ")", "stream", "returns"].contains(t))
+                    .collect();
+                operation.input = types.first().map(|t| t.to_string());
+                operation.output = types.get(1).map(|t| t.to_string());
+                operations.push(operation);
+                index = skip_statement(&tokens, index);
+            }
+            (_, Some(ProtoScope::Message(message))) => {
+                let message = *message;
+                index = proto_field(&tokens, index, &mut messages[message], &mut scopes);
+            }
+            _ => index = skip_statement(&tokens, index),
+        }
+    }
+
+    let name = package.clone().unwrap_or_else(|| {
+        relative
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("proto")
+            .to_string()
+    });
+    Contract {
+        name,
+        kind: ContractKind::Protobuf,
+        spec: relative.to_path_buf(),
+        version: None,
+        base_path: None,
+        operations,
+        messages,
+    }
+}
+
+enum ProtoScope {
+    Message(usize),
+    Service(String),
+    Other,
+}
+
+impl ProtoScope {
+    fn message(&self) -> Option<usize> {
+        match self {
+            ProtoScope::Message(index) => Some(*index),
+            _ => None,
+        }
+    }
+}
+
+// Read the statement at `index` inside a message, adding it to `message` when it's a
+// field; returns the index after it
+fn proto_field(
+    tokens: &[String],
+    index: usize,
+    message: &mut Message,
+    scopes: &mut Vec<ProtoScope>,
+) -> usize {
+    let token = |i: usize| tokens.get(i).map(String::as_str).unwrap_or_default();
+    match token(index) {
+        // Fields of a oneof belong to the message
+        "oneof" => {
+            scopes.push(ProtoScope::Message(
+                scopes
+                    .iter()
+                    .rev()
+                    .find_map(ProtoScope::message)
+                    .unwrap_or(0),
+            ));
+            index + 3
+        }
+        "enum" | "extend" => {
+            scopes.push(ProtoScope::Other);
+            index + 3
+        }
+        "option" | "reserved" | "extensions" | ";" => skip_statement(tokens, index),
+        "map" => {
+            // map < Key , Value > name = N ;
+            let field_type = format!("map<{}, {}>", token(index + 2), token(index + 4));
+            message.fields.push(Field {
+                name: token(index + 6).to_string(),
+                field_type: Some(field_type),
+            });
+            skip_statement(tokens, index)
+        }
+        label => {
+            let (field_type, name) = match label {
+                "repeated" => (format!("repeated {}", token(index + 1)), token(index + 2)),
+                "optional" | "required" => (token(index + 1).to_string(), token(index + 2)),
+                _ => (label.to_string(), token(index + 1)),
+            };
+            if !name.is_empty() && name != "=" {
+                message.fields.push(Field {
+                    name: name.to_string(),
+                    field_type: Some(field_type),
+                });
+            }
+            skip_statement(tokens, index)
+        }
+    }
+}
+
+// The index after the `;` ending the statement at `index`, or after the block it opens
+fn skip_statement(tokens: &[String], index: usize) -> usize {
+    let mut depth = 0;
+    for (offset, token) in tokens[index..].iter().enumerate() {
+        match token.as_str() {
+            "{" => depth += 1,
+            "}" if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    return index + offset + 1;
+                }
+            }
+            // An unopened `}` closes the enclosing block; leave it to the caller
+            "}" => return index + offset,
+            ";" if depth == 0 => return index + offset + 1,
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+// Identifiers (with dots), string literals and punctuation, comments left out
+fn proto_tokens(content: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '"' | '\'' => {
+                let mut literal = String::from(c);
+                for next in chars.by_ref() {
+                    literal.push(next);
+                    if next == c {
+                        break;
+                    }
+                }
+                tokens.push(literal);
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' => {
+                let mut word = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_' || next == '.') {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.push(word);
+            }
+            c if c.is_whitespace() => {}
+            c => tokens.push(c.to_string()),
+        }
+    }
+    tokens
+}
+
+// --- Routes in code ---
+
+/// HTTP routes declared in a source file: Flask and FastAPI decorators, Express, Gin,
+/// Echo and chi style `router.get("/path", ...)` calls, Spring and NestJS annotations,
+/// actix and Rocket attributes, axum's `.route(...)` and Go's `HandleFunc`
+pub fn extract_routes(relative: &Path, content: &str) -> Vec<Route> {
+    let mut routes = Vec::new();
+    // `APIRouter(prefix=...)`, `Blueprint(url_prefix=...)` or a class-level
+    // `@RequestMapping`/`@Controller`
+    let mut prefix = String::new();
+    let lines: Vec<&str> = content.lines().collect();
+    for (index, line) in lines.iter().enumerate() {
+        let line = line.trim();
+        if line.starts_with("//") || line.starts_with('#') && !line.starts_with("#[") {
+            continue;
+        }
+        if let Some(found) = router_prefix(line) {
+            prefix = found;
+            continue;
+        }
+        let mut found = Vec::new();
+        for (open, _) in line.match_indices('(') {
+            let callee_start = line[..open]
+                .rfind(|c: char| !(c.is_alphanumeric() || "_.$".contains(c)))
+                .map_or(0, |i| i + 1);
+            let callee = &line[callee_start..open];
+            let (receiver, name) = match callee.rsplit_once('.') {
+                Some((receiver, name)) => (Some(receiver), name),
+                None => (None, callee),
+            };
+            let marker = line[..callee_start].chars().next_back();
+            let annotation = marker == Some('@') || line[..callee_start].ends_with("#[");
+            let arguments = &line[open + 1..];
+            let Some(path) = first_string(arguments) else {
+                if annotation && HTTP_METHODS.contains(&name.to_lowercase().as_str()) {
+                    // NestJS `@Get()` serves the controller's own path
+                    found.push((name.to_uppercase(), prefix.clone()));
+                }
+                continue;
+            };
+            let lower = name.to_lowercase();
+            if annotation && is_class_mapping(&lines, index) {
+                prefix = with_slash(&path);
+                break;
+            }
+            if let Some((_, method)) = SPRING_MAPPINGS.iter().find(|(n, _)| *n == name) {
+                if annotation {
+                    found.push((method.to_string(), join(&prefix, &path)));
+                }
+            } else if name == "RequestMapping" && annotation {
+                let method = arguments
+                    .split("RequestMethod.")
+                    .nth(1)
+                    .map(|m| m.chars().take_while(|c| c.is_alphabetic()).collect());
+                found.push((
+                    method.unwrap_or_else(|| "ANY".to_string()),
+                    join(&prefix, &path),
+                ));
+            } else if HTTP_METHODS.contains(&lower.as_str()) {
+                let client = receiver.is_some_and(|r| {
+                    let last = r.rsplit('.').next().unwrap_or(r);
+                    CLIENT_RECEIVERS.contains(&last.to_lowercase().as_str())
+                });
+                let is_route =
+                    annotation || (receiver.is_some() && !client && path.starts_with('/'));
+                if is_route {
+                    found.push((lower.to_uppercase(), join(&prefix, &path)));
+                }
+            } else if name == "route" && (annotation || receiver.is_some()) && path.starts_with('/')
+            {
+                let methods = route_methods(arguments);
+                for method in methods {
+                    found.push((method, join(&prefix, &path)));
+                }
+            } else if (name == "HandleFunc" || name == "Handle") && receiver.is_some() {
+                // Go 1.22 patterns carry the method: `"GET /items/{id}"`
+                let (method, path) = match path.split_once(' ') {
+                    Some((method, path)) => (method.to_string(), path.to_string()),
+                    None => (
+                        line.split(".Methods(")
+                            .nth(1)
+                            .and_then(first_string)
+                            .unwrap_or_else(|| "ANY".to_string()),
+                        path,
+                    ),
+                };
+                if path.starts_with('/') {
+                    found.push((method, join(&prefix, &path)));
+                }
+            }
+        }
+        for (method, path) in found {
+            routes.push(Route {
+                file: relative.to_path_buf(),
+                line: index as u32 + 1,
+                method,
+                path,
+            });
+        }
+    }
+    routes
+}
+
+// The first string literal in `text`, when it comes before any `)`
+fn first_string(text: &str) -> Option<String> {
+    let start = text.find(['"', '\'', '`'])?;
+    if text[..start].contains(')') {
+        return None;
+    }
+    let quote = text[start..].chars().next()?;
+    let end = text[start + 1..].find(quote)?;
+    Some(text[start + 1..start + 1 + end].to_string())
+}
+
+fn with_slash(path: &str) -> String {
+    if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{path}")
+    }
+}
+
+fn join(prefix: &str, path: &str) -> String {
+    let path = if path.is_empty() {
+        String::new()
+    } else {
+        with_slash(path)
+    };
+    match (prefix.trim_end_matches('/'), path.as_str()) {
+        ("", "") => "/".to_string(),
+        ("", path) => path.to_string(),
+        (prefix, path) => format!("{prefix}{path}"),
+    }
+}
+
+// `APIRouter(prefix="/items")` or `Blueprint("shop", __name__, url_prefix="/shop")`
+fn router_prefix(line: &str) -> Option<String> {
+    if !line.contains("APIRouter(") && !line.contains("Blueprint(") {
+        return None;
+    }
+    let (_, after) = line
+        .split_once("url_prefix=")
+        .or_else(|| line.split_once("prefix="))?;
+    first_string(after).map(|p| with_slash(&p))
+}
+
+// Whether the annotation on line `index` is a class-level `@RequestMapping` or
+// `@Controller`: only annotations stand between it and the class
+fn is_class_mapping(lines: &[&str], index: usize) -> bool {
+    let line = lines[index].trim();
+    if !line.starts_with("@RequestMapping") && !line.starts_with("@Controller") {
+        return false;
+    }
+    lines[index + 1..]
+        .iter()
+        .map(|l| l.trim())
+        .find(|l| !l.is_empty() && !l.starts_with('@'))
+        .is_some_and(|l| l.contains("class "))
+}
+
+// Methods of a Flask `route(..., methods=["GET", "POST"])` or an axum
+// `.route("/", get(list).post(create))`
+fn route_methods(arguments: &str) -> Vec<String> {
+    if let Some((_, methods)) = arguments.split_once("methods=") {
+        let list = methods.split(']').next().unwrap_or_default();
+        let methods: Vec<String> = list
+            .split(',')
+            .map(|m| {
+                m.trim()
+                    .trim_matches(|c| "[\"' ".contains(c))
+                    .to_uppercase()
+            })
+            .filter(|m| !m.is_empty())
+            .collect();
+        if !methods.is_empty() {
+            return methods;
+        }
+    }
+    let methods: BTreeSet<String> = HTTP_METHODS
+        .iter()
+        .filter(|method| {
+            arguments
+                .match_indices(&format!("{method}("))
+                .any(|(at, _)| {
+                    !arguments[..at]
+                        .chars()
+                        .next_back()
+                        .is_some_and(|c| c.is_alphanumeric() || c == '_')
+                })
+        })
+        .map(|method| method.to_uppercase())
+        .collect();
+    if methods.is_empty() {
+        // Flask routes without `methods` answer GET
+        return vec!["GET".to_string()];
+    }
+    methods.into_iter().collect()
+}
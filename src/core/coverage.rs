@@ -0,0 +1,461 @@
+// src/core/coverage.rs - Test coverage from lcov and Cobertura reports: mapped onto the
+// matrix's files and elements by `csd coverage import`, and weighed against complexity
+// and coupling by `csd quality`
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+use crate::core::annotations::path_key;
+use crate::core::matrix::{CodeElement, ElementType, FileNode, ProjectMatrix};
+
+/// Instrumented lines of a file or element, and how many of them ran
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Coverage {
+    pub lines_found: u32,
+    pub lines_hit: u32,
+    // Share of `lines_found` that ran, 0-100
+    pub percent: f64,
+}
+
+impl Coverage {
+    pub fn new(lines_found: u32, lines_hit: u32) -> Self {
+        let percent = if lines_found == 0 {
+            0.0
+        } else {
+            (f64::from(lines_hit) * 1000.0 / f64::from(lines_found)).round() / 10.0
+        };
+        Self {
+            lines_found,
+            lines_hit,
+            percent,
+        }
+    }
+
+    /// Coverage of the given lines' hit counts; `None` when none were instrumented
+    fn of<'a>(hits: impl Iterator<Item = &'a u64>) -> Option<Self> {
+        let (found, hit) = hits.fold((0u32, 0u32), |(found, hit), &count| {
+            (found + 1, hit + u32::from(count > 0))
+        });
+        (found > 0).then(|| Self::new(found, hit))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum CoverageFormat {
+    Lcov,
+    Cobertura,
+}
+
+impl CoverageFormat {
+    /// Cobertura reports are XML; anything else is taken for lcov tracefile
+    pub fn detect(content: &str) -> Self {
+        let start = content.trim_start();
+        if start.starts_with("<?xml") || start.starts_with("<coverage") {
+            CoverageFormat::Cobertura
+        } else {
+            CoverageFormat::Lcov
+        }
+    }
+}
+
+/// Hit counts by line of each file in a coverage report, under the paths the report
+/// gives
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageData {
+    pub files: BTreeMap<PathBuf, BTreeMap<u32, u64>>,
+    // Cobertura `<source>` directories its file names are relative to
+    pub sources: Vec<PathBuf>,
+}
+
+impl CoverageData {
+    pub fn parse(content: &str, format: CoverageFormat) -> Result<Self> {
+        let data = match format {
+            CoverageFormat::Lcov => Self::parse_lcov(content)?,
+            CoverageFormat::Cobertura => Self::parse_cobertura(content)?,
+        };
+        if data.files.is_empty() {
+            return Err(anyhow::anyhow!("The coverage report covers no files"));
+        }
+        Ok(data)
+    }
+
+    /// `SF:` starts a file's record, `DA:<line>,<hits>` gives a line's hits and
+    /// `end_of_record` ends it; the other records are summaries of these
+    pub fn parse_lcov(content: &str) -> Result<Self> {
+        let mut data = Self::default();
+        let mut current: Option<PathBuf> = None;
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if let Some(path) = line.strip_prefix("SF:") {
+                current = Some(PathBuf::from(path.trim()));
+                data.files.entry(PathBuf::from(path.trim())).or_default();
+            } else if let Some(record) = line.strip_prefix("DA:") {
+                let Some(ref file) = current else {
+                    return Err(anyhow::anyhow!(
+                        "lcov line {}: DA record outside a file record",
+                        number + 1
+                    ));
+                };
+                let mut fields = record.split(',');
+                let (Some(Ok(line_number)), Some(Ok(hits))) = (
+                    fields.next().map(|f| f.trim().parse::<u32>()),
+                    fields.next().map(|f| f.trim().parse::<i64>()),
+                ) else {
+                    return Err(anyhow::anyhow!(
+                        "lcov line {}: malformed DA record '{line}'",
+                        number + 1
+                    ));
+                };
+                add_hits(&mut data, file, line_number, hits.max(0) as u64);
+            } else if line == "end_of_record" {
+                current = None;
+            }
+        }
+        Ok(data)
+    }
+
+    /// Lines of each `<class>`, merged per file name. Methods repeat their class's lines,
+    /// which merging takes care of.
+    pub fn parse_cobertura(content: &str) -> Result<Self> {
+        let mut data = Self::default();
+        if !content.contains("<coverage") {
+            return Err(anyhow::anyhow!(
+                "Not a Cobertura report: no <coverage> element"
+            ));
+        }
+        let mut rest = content;
+        while let Some(start) = rest.find("<source>") {
+            let after = &rest[start + "<source>".len()..];
+            let end = after
+                .find("</source>")
+                .context("Unclosed <source> in Cobertura report")?;
+            let source = unescape(after[..end].trim());
+            if !source.is_empty() {
+                data.sources.push(PathBuf::from(source));
+            }
+            rest = &after[end..];
+        }
+
+        let mut current: Option<PathBuf> = None;
+        for (name, attributes) in xml_tags(content) {
+            match name {
+                "class" => {
+                    current = attribute(attributes, "filename").map(PathBuf::from);
+                    if let Some(ref file) = current {
+                        data.files.entry(file.clone()).or_default();
+                    }
+                }
+                "line" => {
+                    let (Some(file), Some(number), Some(hits)) = (
+                        current.as_ref(),
+                        attribute(attributes, "number").and_then(|n| n.parse::<u32>().ok()),
+                        attribute(attributes, "hits").and_then(|h| h.parse::<u64>().ok()),
+                    ) else {
+                        continue;
+                    };
+                    add_hits(&mut data, file, number, hits);
+                }
+                _ => {}
+            }
+        }
+        Ok(data)
+    }
+}
+
+// A line reported twice (by a class and its method, or by two lcov records) counts
+// the larger hit count
+fn add_hits(data: &mut CoverageData, file: &Path, line: u32, hits: u64) {
+    let lines = data.files.entry(file.to_path_buf()).or_default();
+    let count = lines.entry(line).or_insert(0);
+    *count = (*count).max(hits);
+}
+
+// Names and attribute text of the opening and empty tags, in document order; enough
+// of XML for coverage reports
+fn xml_tags(content: &str) -> impl Iterator<Item = (&str, &str)> {
+    content.split('<').skip(1).filter_map(|tag| {
+        let tag = &tag[..tag.find('>')?];
+        if tag.starts_with(['/', '?', '!']) {
+            return None;
+        }
+        let tag = tag.trim_end_matches('/');
+        let end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        Some((&tag[..end], &tag[end..]))
+    })
+}
+
+fn attribute(attributes: &str, name: &str) -> Option<String> {
+    let mut rest = attributes;
+    while let Some(at) = rest.find(name) {
+        let before = rest[..at].chars().next_back();
+        let after = rest[at + name.len()..].trim_start();
+        rest = &rest[at + name.len()..];
+        if before.is_some_and(|c| !c.is_whitespace()) {
+            continue;
+        }
+        let Some(value) = after.strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let quote = value.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let end = value[1..].find(quote)?;
+        return Some(unescape(&value[1..=end]));
+    }
+    None
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// What an import changed
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CoverageImport {
+    pub files: usize,
+    pub elements: usize,
+    // Report paths that match no file in the matrix, such as generated code
+    pub unmatched: Vec<PathBuf>,
+    // Of the matched files
+    pub total: Coverage,
+}
+
+/// Replace the matrix's coverage with the report's, matching its paths with
+/// [`ReportPaths`]
+pub fn apply_coverage(matrix: &mut ProjectMatrix, data: &CoverageData) -> CoverageImport {
+    let paths = ReportPaths::new(matrix);
+    for file in matrix.files.values_mut() {
+        file.coverage = None;
+        for element in &mut file.elements {
+            element.coverage = None;
+        }
+    }
+
+    let mut import = CoverageImport::default();
+    let (mut found, mut hit) = (0, 0);
+    let mut matched: HashSet<PathBuf> = HashSet::new();
+    for (path, lines) in &data.files {
+        let key = paths.resolve(&data.sources, path);
+        let Some(file) = key
+            .filter(|key| matched.insert(key.clone()))
+            .and_then(|key| matrix.files.get_mut(&key))
+        else {
+            import.unmatched.push(path.clone());
+            continue;
+        };
+        file.coverage = Coverage::of(lines.values());
+        if let Some(coverage) = file.coverage {
+            found += coverage.lines_found;
+            hit += coverage.lines_hit;
+        }
+        for element in &mut file.elements {
+            element.coverage = Coverage::of(
+                lines
+                    .range(element.line_start..=element.line_end)
+                    .map(|(_, h)| h),
+            );
+            import.elements += usize::from(element.coverage.is_some());
+        }
+        import.files += 1;
+    }
+    import.total = Coverage::new(found, hit);
+    import
+}
+
+/// Finds the matrix file a path in a coverage report or trace names. Paths may be
+/// absolute, relative to the project root or to one of the report's source directories,
+/// or differ from the matrix's by leading directories as long as only one file fits.
+pub struct ReportPaths {
+    project_root: PathBuf,
+    by_relative: HashMap<String, PathBuf>,
+}
+
+impl ReportPaths {
+    pub fn new(matrix: &ProjectMatrix) -> Self {
+        Self {
+            project_root: matrix.metadata.project_root.clone(),
+            by_relative: matrix
+                .files
+                .iter()
+                .map(|(key, file)| (path_key(&file.relative_path), key.clone()))
+                .collect(),
+        }
+    }
+
+    /// Key of the file in `matrix.files`
+    pub fn resolve(&self, sources: &[PathBuf], path: &Path) -> Option<PathBuf> {
+        let mut candidates: Vec<PathBuf> = vec![path.to_path_buf()];
+        candidates.extend(sources.iter().map(|source| source.join(path)));
+        for candidate in &candidates {
+            let relative = candidate
+                .strip_prefix(&self.project_root)
+                .unwrap_or(candidate.as_path());
+            if let Some(key) = self.by_relative.get(&path_key(relative)) {
+                return Some(key.clone());
+            }
+        }
+
+        // Reports made in a CI checkout or a container have other roots: take the one
+        // file whose path ends the report's, or is ended by it
+        let report_path = PathBuf::from(path_key(path));
+        let components = |p: &Path| {
+            p.components()
+                .filter(|c| matches!(c, Component::Normal(_)))
+                .count()
+        };
+        let mut fits = self.by_relative.iter().filter(|(relative, _)| {
+            let relative = Path::new(relative.as_str());
+            components(relative) > 0
+                && components(&report_path) > 0
+                && (report_path.ends_with(relative) || relative.ends_with(&report_path))
+        });
+        match (fits.next(), fits.next()) {
+            (Some((_, key)), None) => Some(key.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// A covered file's risk: uncovered code that is complex and coupled to many files
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileRisk {
+    pub path: PathBuf,
+    // 0-1: the uncovered share times the mean of complexity and coupling, each relative
+    // to the project's highest
+    pub score: f64,
+    pub coverage: Coverage,
+    // Summed over the file's functions and methods
+    pub complexity: u32,
+    // Files it depends on plus files depending on it
+    pub coupling: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RiskReport {
+    pub covered_files: usize,
+    pub total: Coverage,
+    // Riskiest first; files with no risk are left out
+    pub files: Vec<FileRisk>,
+}
+
+impl RiskReport {
+    /// The `top` riskiest of the files with coverage. Files the report didn't cover
+    /// aren't ranked: their risk is unknown.
+    pub fn from_matrix(matrix: &ProjectMatrix, top: usize) -> Self {
+        let covered: Vec<&FileNode> = matrix
+            .files
+            .values()
+            .filter(|file| file.coverage.is_some())
+            .collect();
+        let mut neighbours: HashMap<PathBuf, HashSet<PathBuf>> = HashMap::new();
+        for relationship in &matrix.relationships {
+            if relationship.from_file == relationship.to_file {
+                continue;
+            }
+            neighbours
+                .entry(relationship.from_file.clone())
+                .or_default()
+                .insert(relationship.to_file.clone());
+            neighbours
+                .entry(relationship.to_file.clone())
+                .or_default()
+                .insert(relationship.from_file.clone());
+        }
+        let coupling = |file: &FileNode| {
+            neighbours
+                .get(&file.path)
+                .or_else(|| neighbours.get(&file.relative_path))
+                .map_or(0, HashSet::len)
+        };
+
+        let measured: Vec<(&FileNode, Coverage, u32, usize)> = covered
+            .iter()
+            .filter_map(|file| Some((*file, file.coverage?, complexity(file), coupling(file))))
+            .collect();
+        let max_complexity = measured.iter().map(|m| m.2).max().unwrap_or(0).max(1);
+        let max_coupling = measured.iter().map(|m| m.3).max().unwrap_or(0).max(1);
+        let (found, hit) = measured.iter().fold((0, 0), |(found, hit), m| {
+            (found + m.1.lines_found, hit + m.1.lines_hit)
+        });
+
+        let mut files: Vec<FileRisk> = measured
+            .into_iter()
+            .map(|(file, coverage, complexity, coupling)| {
+                let uncovered = 1.0 - coverage.percent / 100.0;
+                let weight = (f64::from(complexity) / f64::from(max_complexity)
+                    + coupling as f64 / max_coupling as f64)
+                    / 2.0;
+                FileRisk {
+                    path: file.relative_path.clone(),
+                    score: (uncovered * weight * 100.0).round() / 100.0,
+                    coverage,
+                    complexity,
+                    coupling,
+                }
+            })
+            .filter(|risk| risk.score > 0.0)
+            .collect();
+        files.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        files.truncate(top);
+
+        Self {
+            covered_files: covered.len(),
+            total: Coverage::new(found, hit),
+            files,
+        }
+    }
+
+    pub fn print_summary(&self) {
+        println!("\n🧪 Test Coverage:");
+        if self.covered_files == 0 {
+            println!("   No coverage data; run 'csd coverage import <report>' after a scan");
+            return;
+        }
+        println!(
+            "   {}% of {} lines in {} files",
+            self.total.percent, self.total.lines_found, self.covered_files
+        );
+        if self.files.is_empty() {
+            return;
+        }
+        println!("   Riskiest files (complex, coupled and uncovered):");
+        for risk in &self.files {
+            println!(
+                "     {:.2}  {} ({}% covered, complexity {}, {} coupled files)",
+                risk.score,
+                risk.path.display(),
+                risk.coverage.percent,
+                risk.complexity,
+                risk.coupling
+            );
+        }
+    }
+}
+
+fn complexity(file: &FileNode) -> u32 {
+    file.elements
+        .iter()
+        .filter(|element| is_function(element))
+        .filter_map(|element| element.complexity_score)
+        .sum()
+}
+
+fn is_function(element: &CodeElement) -> bool {
+    matches!(
+        element.element_type,
+        ElementType::Function | ElementType::Method
+    )
+}
@@ -0,0 +1,1328 @@
+// src/core/data_model.rs - Tables and columns read from migrations (plain SQL as Flyway,
+// Diesel and Prisma write them, and Alembic scripts) and ORM models (SQLAlchemy, Django,
+// Prisma, Diesel), with the code files that touch each table, for the data dictionary
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::core::matrix::{FileNode, ProjectMatrix};
+
+// Directories migration tools keep their scripts in; files under them define the schema
+// rather than use it
+const MIGRATION_DIRS: &[&str] = &["migrations", "migration", "migrate", "versions"];
+
+// Files searched for queries and model uses
+const CODE_EXTENSIONS: &[&str] = &[
+    "py", "rs", "js", "jsx", "ts", "tsx", "go", "java", "kt", "rb", "php", "cs", "scala",
+];
+
+// Words that end a column's type in a SQL column definition
+const SQL_CONSTRAINTS: &[&str] = &[
+    "NOT",
+    "NULL",
+    "PRIMARY",
+    "REFERENCES",
+    "DEFAULT",
+    "UNIQUE",
+    "CHECK",
+    "CONSTRAINT",
+    "GENERATED",
+    "COLLATE",
+    "AUTO_INCREMENT",
+    "AUTOINCREMENT",
+    "IDENTITY",
+    "COMMENT",
+];
+
+// Table-level clauses of `CREATE TABLE` rather than columns
+const SQL_TABLE_CLAUSES: &[&str] = &[
+    "CONSTRAINT",
+    "PRIMARY",
+    "FOREIGN",
+    "UNIQUE",
+    "CHECK",
+    "KEY",
+    "INDEX",
+    "EXCLUDE",
+    "FULLTEXT",
+    "LIKE",
+];
+
+// Keywords a table name follows in a query
+const SQL_TABLE_KEYWORDS: &[&str] = &["from", "join", "into", "update"];
+
+const PRISMA_SCALARS: &[&str] = &[
+    "String", "Int", "BigInt", "Float", "Decimal", "Boolean", "DateTime", "Json", "Bytes",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DataModel {
+    pub tables: Vec<Table>,
+    // Code files touching a table, ordered by file then table
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accesses: Vec<TableAccess>,
+}
+
+impl DataModel {
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    pub fn table(&self, name: &str) -> Option<&Table> {
+        self.tables
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Files touching `table`, in path order
+    pub fn files_touching(&self, table: &str) -> Vec<&Path> {
+        self.accesses
+            .iter()
+            .filter(|a| a.table.eq_ignore_ascii_case(table))
+            .map(|a| a.file.as_path())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+    pub name: String,
+    pub columns: Vec<Column>,
+    // The ORM class mapped onto it, e.g. `User`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    // Relative paths of the migrations and models defining it, in path order
+    pub defined_in: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    // As written: `VARCHAR(255)`, `sa.String`'s `String`, Django's `CharField`, ...
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_type: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub primary_key: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub not_null: bool,
+}
+
+impl Column {
+    fn new(name: &str, data_type: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            data_type,
+            primary_key: false,
+            not_null: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessKind {
+    // Uses the table's ORM model
+    Orm,
+    // Names the table in a SQL query
+    Sql,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableAccess {
+    pub file: PathBuf,
+    pub table: String,
+    pub kind: AccessKind,
+}
+
+/// One statement of a migration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaChange {
+    CreateTable {
+        table: String,
+        columns: Vec<Column>,
+    },
+    AddColumn {
+        table: String,
+        column: Column,
+    },
+    DropColumn {
+        table: String,
+        column: String,
+    },
+    RenameColumn {
+        table: String,
+        from: String,
+        to: String,
+    },
+    RenameTable {
+        from: String,
+        to: String,
+    },
+    DropTable {
+        table: String,
+    },
+}
+
+/// A class mapped onto a table; Diesel's `table!` schema has no class
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Model {
+    pub class: Option<String>,
+    pub table: String,
+    pub columns: Vec<Column>,
+}
+
+/// The tables the project's migrations and models define, and the code touching them
+pub fn detect_data_model(matrix: &ProjectMatrix) -> DataModel {
+    let mut files: Vec<&FileNode> = matrix.files.values().collect();
+    files.sort_by(|a, b| natural_cmp(&a.relative_path, &b.relative_path));
+
+    let mut tables: BTreeMap<String, Table> = BTreeMap::new();
+    let mut models: Vec<(Model, &Path)> = Vec::new();
+    let mut definitions: HashSet<&Path> = HashSet::new();
+    for file in &files {
+        let relative = file.relative_path.as_path();
+        let Some(kind) = SchemaFile::of(relative) else {
+            continue;
+        };
+        let Ok(content) = std::fs::read_to_string(&file.path) else {
+            debug!("Could not read {} for the data model", file.path.display());
+            continue;
+        };
+        match kind {
+            SchemaFile::Sql => apply(&mut tables, parse_sql(&content), relative),
+            SchemaFile::Python if content.contains("alembic") && is_migration(relative) => {
+                apply(&mut tables, parse_alembic(&content), relative)
+            }
+            SchemaFile::Python if is_migration(relative) => continue,
+            SchemaFile::Python => {
+                models.extend(
+                    parse_sqlalchemy(&content)
+                        .into_iter()
+                        .map(|m| (m, relative)),
+                );
+                if let Some(app) = django_app(relative) {
+                    let found = parse_django(&content, app);
+                    models.extend(found.into_iter().map(|m| (m, relative)));
+                }
+            }
+            SchemaFile::Prisma => {
+                models.extend(parse_prisma(&content).into_iter().map(|m| (m, relative)))
+            }
+            SchemaFile::Rust => {
+                models.extend(parse_diesel(&content).into_iter().map(|m| (m, relative)))
+            }
+        }
+        definitions.insert(relative);
+    }
+    for (model, relative) in &models {
+        let table = tables
+            .entry(model.table.to_lowercase())
+            .or_insert_with(|| Table {
+                name: model.table.clone(),
+                columns: Vec::new(),
+                model: None,
+                defined_in: Vec::new(),
+            });
+        if table.model.is_none() {
+            table.model = model.class.clone();
+        }
+        for column in &model.columns {
+            match table.columns.iter_mut().find(|c| c.name == column.name) {
+                Some(existing) => {
+                    existing.data_type = existing.data_type.take().or(column.data_type.clone());
+                    existing.primary_key |= column.primary_key;
+                }
+                None => table.columns.push(column.clone()),
+            }
+        }
+        push_unique(&mut table.defined_in, relative);
+    }
+    // A file only counts as defining the schema once it did
+    definitions.retain(|path| {
+        tables
+            .values()
+            .any(|table| table.defined_in.iter().any(|p| p == path))
+    });
+
+    for table in tables.values_mut() {
+        table.defined_in.sort_by(|a, b| natural_cmp(a, b));
+    }
+
+    let accesses = if tables.is_empty() {
+        Vec::new()
+    } else {
+        find_accesses(matrix, &files, &tables, &models, &definitions)
+    };
+    DataModel {
+        tables: tables.into_values().collect(),
+        accesses,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum SchemaFile {
+    Sql,
+    Python,
+    Prisma,
+    Rust,
+}
+
+impl SchemaFile {
+    fn of(relative: &Path) -> Option<Self> {
+        let file_name = relative.file_name()?.to_str()?;
+        match relative.extension()?.to_str()? {
+            // Down migrations and Flyway undo scripts don't describe the schema
+            "sql" if file_name == "down.sql" || is_flyway_undo(file_name) => None,
+            "sql" => Some(SchemaFile::Sql),
+            "py" => Some(SchemaFile::Python),
+            "prisma" => Some(SchemaFile::Prisma),
+            "rs" => Some(SchemaFile::Rust),
+            _ => None,
+        }
+    }
+}
+
+fn is_flyway_undo(file_name: &str) -> bool {
+    file_name
+        .strip_prefix('U')
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()) && rest.contains("__"))
+}
+
+fn is_migration(relative: &Path) -> bool {
+    relative
+        .parent()
+        .into_iter()
+        .flat_map(Path::components)
+        .any(|c| MIGRATION_DIRS.iter().any(|dir| c.as_os_str() == *dir))
+}
+
+// Django names tables `<app>_<model>`, the app being the directory of `models.py` or of
+// the `models` package
+fn django_app(relative: &Path) -> Option<&str> {
+    let parent = relative.parent()?;
+    let app_dir = if relative.file_name()? == "models.py" {
+        parent
+    } else if parent.file_name()? == "models" {
+        parent.parent()?
+    } else {
+        return None;
+    };
+    app_dir.file_name()?.to_str()
+}
+
+fn push_unique(paths: &mut Vec<PathBuf>, path: &Path) {
+    if !paths.iter().any(|p| p == path) {
+        paths.push(path.to_path_buf());
+    }
+}
+
+// Apply a migration's changes in order; tables are keyed by lowercased name
+fn apply(tables: &mut BTreeMap<String, Table>, changes: Vec<SchemaChange>, relative: &Path) {
+    for change in changes {
+        match change {
+            SchemaChange::CreateTable { table, columns } => {
+                let entry = tables.entry(table.to_lowercase()).or_insert_with(|| Table {
+                    name: table.clone(),
+                    columns: Vec::new(),
+                    model: None,
+                    defined_in: Vec::new(),
+                });
+                entry.columns = columns;
+                push_unique(&mut entry.defined_in, relative);
+            }
+            SchemaChange::AddColumn { table, column } => {
+                if let Some(entry) = tables.get_mut(&table.to_lowercase()) {
+                    entry.columns.retain(|c| c.name != column.name);
+                    entry.columns.push(column);
+                    push_unique(&mut entry.defined_in, relative);
+                }
+            }
+            SchemaChange::DropColumn { table, column } => {
+                if let Some(entry) = tables.get_mut(&table.to_lowercase()) {
+                    entry
+                        .columns
+                        .retain(|c| !c.name.eq_ignore_ascii_case(&column));
+                    push_unique(&mut entry.defined_in, relative);
+                }
+            }
+            SchemaChange::RenameColumn { table, from, to } => {
+                if let Some(entry) = tables.get_mut(&table.to_lowercase()) {
+                    for column in &mut entry.columns {
+                        if column.name.eq_ignore_ascii_case(&from) {
+                            column.name = to.clone();
+                        }
+                    }
+                    push_unique(&mut entry.defined_in, relative);
+                }
+            }
+            SchemaChange::RenameTable { from, to } => {
+                if let Some(mut entry) = tables.remove(&from.to_lowercase()) {
+                    entry.name = to.clone();
+                    push_unique(&mut entry.defined_in, relative);
+                    tables.insert(to.to_lowercase(), entry);
+                }
+            }
+            SchemaChange::DropTable { table } => {
+                tables.remove(&table.to_lowercase());
+            }
+        }
+    }
+}
+
+fn find_accesses(
+    matrix: &ProjectMatrix,
+    files: &[&FileNode],
+    tables: &BTreeMap<String, Table>,
+    models: &[(Model, &Path)],
+    definitions: &HashSet<&Path>,
+) -> Vec<TableAccess> {
+    // Relationships name files by path; the data model by relative path
+    let relative: HashMap<&Path, &Path> = matrix
+        .files
+        .values()
+        .flat_map(|f| {
+            [
+                (f.path.as_path(), f.relative_path.as_path()),
+                (f.relative_path.as_path(), f.relative_path.as_path()),
+            ]
+        })
+        .collect();
+    let edges: HashSet<(&Path, &Path)> = matrix
+        .relationships
+        .iter()
+        .filter_map(|r| {
+            Some((
+                *relative.get(r.from_file.as_path())?,
+                *relative.get(r.to_file.as_path())?,
+            ))
+        })
+        .collect();
+    let classes: Vec<(&str, &str, &Path)> = models
+        .iter()
+        .filter_map(|(model, path)| Some((model.class.as_deref()?, model.table.as_str(), *path)))
+        .collect();
+
+    let mut accesses = BTreeSet::new();
+    for file in files {
+        let path = file.relative_path.as_path();
+        let is_code = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| CODE_EXTENSIONS.contains(&e));
+        if !file.is_text || !is_code || definitions.contains(path) || is_migration(path) {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&file.path) else {
+            continue;
+        };
+
+        for table in sql_tables(&content, tables) {
+            accesses.insert((path, table, AccessKind::Sql));
+        }
+        let imported = |class: &str| {
+            file.imports
+                .iter()
+                .any(|import| import.items.iter().any(|item| item == class))
+        };
+        for (class, table, model_file) in &classes {
+            if (edges.contains(&(path, *model_file)) || imported(class))
+                && contains_word(&content, class)
+            {
+                accesses.insert((path, table.to_string(), AccessKind::Orm));
+            }
+        }
+        // Diesel queries go through the `table!` module: `users::table`, `users::dsl`
+        if path.extension().is_some_and(|e| e == "rs") {
+            for table in tables.values() {
+                let module = |item: &str| format!("{}::{item}", table.name);
+                if contains_word(&content, &module("table"))
+                    || contains_word(&content, &module("dsl"))
+                {
+                    accesses.insert((path, table.name.clone(), AccessKind::Orm));
+                }
+            }
+        }
+    }
+
+    let mut accesses: Vec<TableAccess> = accesses
+        .into_iter()
+        .map(|(file, table, kind)| TableAccess {
+            file: file.to_path_buf(),
+            table,
+            kind,
+        })
+        .collect();
+    // A file both querying a table and using its model is listed once, as a model user
+    accesses.dedup_by(|later, earlier| later.file == earlier.file && later.table == earlier.table);
+    accesses
+}
+
+// Tables named after `FROM`, `JOIN`, `INTO` or `UPDATE`, as they're spelled in the schema
+fn sql_tables(content: &str, tables: &BTreeMap<String, Table>) -> BTreeSet<String> {
+    let lowered = content.to_lowercase();
+    let words: Vec<&str> = lowered
+        .split(|c: char| !(c.is_alphanumeric() || "_.\"`[]".contains(c)))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let mut found = BTreeSet::new();
+    for (index, pair) in words.windows(2).enumerate() {
+        if !SQL_TABLE_KEYWORDS.contains(&pair[0]) {
+            continue;
+        }
+        // `from users import ...` is Python, not SQL
+        if words.get(index + 2) == Some(&"import") {
+            continue;
+        }
+        if let Some(table) = tables.get(&unquote(pair[1]).to_lowercase()) {
+            found.insert(table.name.clone());
+        }
+    }
+    found
+}
+
+// Whether `word` occurs in `content` delimited by non-identifier characters
+pub(crate) fn contains_word(content: &str, word: &str) -> bool {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+    content.match_indices(word).any(|(start, _)| {
+        let before = content[..start].chars().next_back();
+        let after = content[start + word.len()..].chars().next();
+        !before.is_some_and(is_identifier) && !after.is_some_and(is_identifier)
+    })
+}
+
+// Compare paths with digit runs compared as numbers, so `V10__` sorts after `V2__`
+fn natural_cmp(a: &Path, b: &Path) -> Ordering {
+    fn chunks(path: &Path) -> Vec<(bool, String)> {
+        let mut chunks: Vec<(bool, String)> = Vec::new();
+        for c in path.to_string_lossy().chars() {
+            let digit = c.is_ascii_digit();
+            match chunks.last_mut() {
+                Some((is_digit, chunk)) if *is_digit == digit => chunk.push(c),
+                _ => chunks.push((digit, c.to_string())),
+            }
+        }
+        chunks
+    }
+    let (a, b) = (chunks(a), chunks(b));
+    for ((a_digit, a), (b_digit, b)) in a.iter().zip(&b) {
+        let order = if *a_digit && *b_digit {
+            let number = |s: &str| s.trim_start_matches('0').to_string();
+            let (a, b) = (number(a), number(b));
+            a.len().cmp(&b.len()).then_with(|| a.cmp(&b))
+        } else {
+            a.cmp(b)
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+// `"public"."users"`, `[dbo].[users]` or `` `users` `` -> `users`
+fn unquote(identifier: &str) -> String {
+    let last = identifier
+        .rsplit('.')
+        .find(|part| !part.is_empty())
+        .unwrap_or(identifier);
+    last.trim_matches(|c| "\"`[]'".contains(c)).to_string()
+}
+
+// Split at `separator` outside parentheses, brackets and quotes
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth -= 1,
+            (None, c) if c == separator && depth == 0 => {
+                parts.push(text[start..index].trim());
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(text[start..].trim());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+// The text between the bracket `text` starts with and its match
+fn bracketed(text: &str) -> Option<&str> {
+    let open = text.chars().next()?;
+    let close = match open {
+        '(' => ')',
+        '[' => ']',
+        '{' => '}',
+        _ => return None,
+    };
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for (index, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, c) if c == open => depth += 1,
+            (None, c) if c == close => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[1..index]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// --- SQL ---
+
+/// Table changes in a SQL migration or schema dump, in statement order
+pub fn parse_sql(content: &str) -> Vec<SchemaChange> {
+    let mut changes = Vec::new();
+    for statement in split_top_level(&strip_sql_comments(content), ';') {
+        let statement = statement.split_whitespace().collect::<Vec<_>>().join(" ");
+        if let Some(change) = create_table(&statement) {
+            changes.push(change);
+        } else if let Some(rest) = keywords(&statement, &["ALTER", "TABLE"]) {
+            changes.extend(alter_table(rest));
+        } else if let Some(rest) = keywords(&statement, &["DROP", "TABLE"]) {
+            let rest = keywords(rest, &["IF", "EXISTS"]).unwrap_or(rest);
+            for name in split_top_level(rest, ',') {
+                let name = name.split_whitespace().next().unwrap_or_default();
+                changes.push(SchemaChange::DropTable {
+                    table: unquote(name),
+                });
+            }
+        }
+    }
+    changes
+}
+
+fn strip_sql_comments(content: &str) -> String {
+    let mut stripped = String::with_capacity(content.len());
+    let mut rest = content;
+    while !rest.is_empty() {
+        let line_comment = rest.find("--");
+        let block_comment = rest.find("/*");
+        match (line_comment, block_comment) {
+            (Some(line), block) if block.is_none_or(|block| line < block) => {
+                stripped.push_str(&rest[..line]);
+                rest = rest[line..]
+                    .find('\n')
+                    .map_or("", |end| &rest[line + end..]);
+            }
+            (_, Some(block)) => {
+                stripped.push_str(&rest[..block]);
+                rest = rest[block..]
+                    .find("*/")
+                    .map_or("", |end| &rest[block + end + 2..]);
+            }
+            _ => {
+                stripped.push_str(rest);
+                rest = "";
+            }
+        }
+    }
+    stripped
+}
+
+// The text after `words`, when `text` starts with them in any case
+fn keywords<'a>(text: &'a str, words: &[&str]) -> Option<&'a str> {
+    let mut rest = text.trim_start();
+    for word in words {
+        let head = rest.get(..word.len())?;
+        let boundary = rest[word.len()..]
+            .chars()
+            .next()
+            .is_none_or(|c| c.is_whitespace() || c == '(');
+        if !head.eq_ignore_ascii_case(word) || !boundary {
+            return None;
+        }
+        rest = rest[word.len()..].trim_start();
+    }
+    Some(rest)
+}
+
+// The leading identifier of `text`, unquoted, and what follows it
+fn identifier(text: &str) -> Option<(String, &str)> {
+    let text = text.trim_start();
+    let end = text
+        .find(|c: char| c.is_whitespace() || c == '(' || c == ',')
+        .unwrap_or(text.len());
+    let name = unquote(&text[..end]);
+    (!name.is_empty()).then_some((name, &text[end..]))
+}
+
+fn create_table(statement: &str) -> Option<SchemaChange> {
+    let mut rest = keywords(statement, &["CREATE"])?;
+    rest = keywords(rest, &["OR", "REPLACE"]).unwrap_or(rest);
+    for modifier in ["GLOBAL", "LOCAL", "TEMPORARY", "TEMP", "UNLOGGED"] {
+        rest = keywords(rest, &[modifier]).unwrap_or(rest);
+    }
+    rest = keywords(rest, &["TABLE"])?;
+    rest = keywords(rest, &["IF", "NOT", "EXISTS"]).unwrap_or(rest);
+    let (table, rest) = identifier(rest)?;
+    let body = bracketed(rest.trim_start())?;
+
+    let mut columns: Vec<Column> = Vec::new();
+    let mut primary_keys = Vec::new();
+    for item in split_top_level(body, ',') {
+        let first = item.split_whitespace().next().unwrap_or_default();
+        if SQL_TABLE_CLAUSES
+            .iter()
+            .any(|c| first.eq_ignore_ascii_case(c))
+        {
+            let upper = item.to_uppercase();
+            if let Some(index) = upper.find("PRIMARY KEY") {
+                let after = item[index + "PRIMARY KEY".len()..].trim_start();
+                if let Some(names) = bracketed(after) {
+                    primary_keys.extend(split_top_level(names, ',').into_iter().map(unquote));
+                }
+            }
+            continue;
+        }
+        columns.extend(sql_column(item));
+    }
+    for column in &mut columns {
+        if primary_keys
+            .iter()
+            .any(|key| key.eq_ignore_ascii_case(&column.name))
+        {
+            column.primary_key = true;
+            column.not_null = true;
+        }
+    }
+    Some(SchemaChange::CreateTable { table, columns })
+}
+
+fn sql_column(definition: &str) -> Option<Column> {
+    let (name, rest) = identifier(definition)?;
+    let words: Vec<&str> = rest.split_whitespace().collect();
+    let type_words: Vec<&str> = words
+        .iter()
+        .take_while(|w| !SQL_CONSTRAINTS.iter().any(|c| w.eq_ignore_ascii_case(c)))
+        .copied()
+        .collect();
+    let mut column = Column::new(
+        &name,
+        (!type_words.is_empty()).then(|| type_words.join(" ")),
+    );
+    let upper = rest.to_uppercase();
+    column.primary_key = upper.contains("PRIMARY KEY");
+    column.not_null = column.primary_key || upper.contains("NOT NULL");
+    Some(column)
+}
+
+fn alter_table(rest: &str) -> Vec<SchemaChange> {
+    let rest = keywords(rest, &["IF", "EXISTS"]).unwrap_or(rest);
+    let rest = keywords(rest, &["ONLY"]).unwrap_or(rest);
+    let Some((table, actions)) = identifier(rest) else {
+        return Vec::new();
+    };
+    let mut changes = Vec::new();
+    for action in split_top_level(actions, ',') {
+        if let Some(rest) = keywords(action, &["ADD"]) {
+            let first = rest.split_whitespace().next().unwrap_or_default();
+            let is_column = keywords(rest, &["COLUMN"]).is_some()
+                || !SQL_TABLE_CLAUSES
+                    .iter()
+                    .any(|c| first.eq_ignore_ascii_case(c));
+            if !is_column {
+                continue;
+            }
+            let rest = keywords(rest, &["COLUMN"]).unwrap_or(rest);
+            let rest = keywords(rest, &["IF", "NOT", "EXISTS"]).unwrap_or(rest);
+            if let Some(column) = sql_column(rest) {
+                changes.push(SchemaChange::AddColumn {
+                    table: table.clone(),
+                    column,
+                });
+            }
+        } else if let Some(rest) = keywords(action, &["DROP"]) {
+            let explicit = keywords(rest, &["COLUMN"]);
+            let first = rest.split_whitespace().next().unwrap_or_default();
+            let is_other = [
+                "CONSTRAINT",
+                "INDEX",
+                "KEY",
+                "PRIMARY",
+                "FOREIGN",
+                "DEFAULT",
+            ]
+            .iter()
+            .any(|c| first.eq_ignore_ascii_case(c));
+            if explicit.is_none() && is_other {
+                continue;
+            }
+            let rest = explicit.unwrap_or(rest);
+            let rest = keywords(rest, &["IF", "EXISTS"]).unwrap_or(rest);
+            if let Some((column, _)) = identifier(rest) {
+                changes.push(SchemaChange::DropColumn {
+                    table: table.clone(),
+                    column,
+                });
+            }
+        } else if let Some(rest) = keywords(action, &["RENAME"]) {
+            if let Some(to) = keywords(rest, &["TO"]) {
+                if let Some((to, _)) = identifier(to) {
+                    changes.push(SchemaChange::RenameTable {
+                        from: table.clone(),
+                        to,
+                    });
+                }
+                continue;
+            }
+            let rest = keywords(rest, &["COLUMN"]).unwrap_or(rest);
+            let Some((from, rest)) = identifier(rest) else {
+                continue;
+            };
+            if let Some((to, _)) = keywords(rest, &["TO"]).and_then(identifier) {
+                changes.push(SchemaChange::RenameColumn {
+                    table: table.clone(),
+                    from,
+                    to,
+                });
+            }
+        }
+    }
+    changes
+}
+
+// --- Python: Alembic, SQLAlchemy, Django ---
+
+/// Table changes in the `upgrade()` of an Alembic revision, in call order
+pub fn parse_alembic(content: &str) -> Vec<SchemaChange> {
+    let start = content.find("def upgrade").unwrap_or(0);
+    let end = content[start..]
+        .find("def downgrade")
+        .map_or(content.len(), |end| start + end);
+    let upgrade = &content[start..end];
+
+    let mut changes = Vec::new();
+    // Table of the enclosing `with op.batch_alter_table(...)`
+    let mut batch_table: Option<String> = None;
+    let mut rest = upgrade;
+    while let Some(dot) = rest.find('.') {
+        let receiver_start = rest[..dot]
+            .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .map_or(0, |i| i + 1);
+        let receiver = &rest[receiver_start..dot];
+        let after = &rest[dot + 1..];
+        rest = after;
+        let Some(open) = after.find('(') else {
+            break;
+        };
+        let function = &after[..open];
+        if function.contains(|c: char| !(c.is_alphanumeric() || c == '_')) {
+            continue;
+        }
+        let Some(arguments) = bracketed(&after[open..]) else {
+            continue;
+        };
+        let arguments = split_top_level(arguments, ',');
+        // `op.add_column("users", ...)` names the table; `batch_op.add_column(...)` doesn't
+        let (table, arguments) = if receiver == "op" {
+            match arguments.first().and_then(|a| string_literal(a)) {
+                Some(table) => (Some(table), &arguments[1..]),
+                None => continue,
+            }
+        } else {
+            (batch_table.clone(), &arguments[..])
+        };
+        let Some(table) = table else {
+            continue;
+        };
+        match function {
+            "batch_alter_table" => batch_table = Some(table),
+            "create_table" => {
+                let mut columns: Vec<Column> = arguments
+                    .iter()
+                    .filter_map(|a| python_column(a, None))
+                    .collect();
+                for argument in arguments {
+                    if let Some(inner) = call_arguments(argument, "PrimaryKeyConstraint") {
+                        for key in split_top_level(inner, ',')
+                            .iter()
+                            .filter_map(|k| string_literal(k))
+                        {
+                            for column in columns.iter_mut().filter(|c| c.name == key) {
+                                column.primary_key = true;
+                                column.not_null = true;
+                            }
+                        }
+                    }
+                }
+                changes.push(SchemaChange::CreateTable { table, columns });
+            }
+            "add_column" => {
+                if let Some(column) = arguments.first().and_then(|a| python_column(a, None)) {
+                    changes.push(SchemaChange::AddColumn { table, column });
+                }
+            }
+            "drop_column" => {
+                if let Some(column) = arguments.first().and_then(|a| string_literal(a)) {
+                    changes.push(SchemaChange::DropColumn { table, column });
+                }
+            }
+            "alter_column" => {
+                let renamed = arguments
+                    .iter()
+                    .find_map(|a| keyword_argument(a, "new_column_name"));
+                if let (Some(from), Some(to)) = (
+                    arguments.first().and_then(|a| string_literal(a)),
+                    renamed.and_then(string_literal),
+                ) {
+                    changes.push(SchemaChange::RenameColumn { table, from, to });
+                }
+            }
+            "rename_table" => {
+                if let Some(to) = arguments.first().and_then(|a| string_literal(a)) {
+                    changes.push(SchemaChange::RenameTable { from: table, to });
+                }
+            }
+            "drop_table" => changes.push(SchemaChange::DropTable { table }),
+            _ => {}
+        }
+    }
+    changes
+}
+
+fn string_literal(text: &str) -> Option<String> {
+    let text = text.trim();
+    let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let inner = text[1..].strip_suffix(quote)?;
+    (!inner.contains(quote)).then(|| inner.to_string())
+}
+
+// `value` of `name=value`
+fn keyword_argument<'a>(argument: &'a str, name: &str) -> Option<&'a str> {
+    let (key, value) = argument.split_once('=')?;
+    (key.trim() == name).then(|| value.trim())
+}
+
+// The arguments of a call to `function`, with or without a module prefix, when
+// `expression` is one
+fn call_arguments<'a>(expression: &'a str, function: &str) -> Option<&'a str> {
+    let expression = expression.trim();
+    let open = expression.find('(')?;
+    let callee = &expression[..open];
+    let name = callee.rsplit('.').next()?;
+    if name != function {
+        return None;
+    }
+    bracketed(&expression[open..])
+}
+
+// `sa.String(50)` -> `String`
+fn python_type(expression: &str) -> String {
+    let callee = expression.split('(').next().unwrap_or(expression).trim();
+    callee.rsplit('.').next().unwrap_or(callee).to_string()
+}
+
+// A `Column(...)` or `mapped_column(...)` expression, named `attribute` unless it names
+// itself
+fn python_column(expression: &str, attribute: Option<&str>) -> Option<Column> {
+    let arguments = call_arguments(expression, "Column")
+        .or_else(|| call_arguments(expression, "mapped_column"))?;
+    let arguments = split_top_level(arguments, ',');
+    let name = arguments
+        .first()
+        .and_then(|a| string_literal(a))
+        .or_else(|| attribute.map(str::to_string))?;
+    let data_type = arguments
+        .iter()
+        .filter(|a| !a.contains('=') && string_literal(a).is_none())
+        .find(|a| {
+            !["ForeignKey", "Sequence", "Identity", "Computed"]
+                .iter()
+                .any(|f| call_arguments(a, f).is_some())
+        })
+        .map(|a| python_type(a));
+    let mut column = Column::new(&name, data_type);
+    for argument in &arguments {
+        if keyword_argument(argument, "primary_key") == Some("True") {
+            column.primary_key = true;
+        }
+        if keyword_argument(argument, "nullable") == Some("False") {
+            column.not_null = true;
+        }
+    }
+    column.not_null |= column.primary_key;
+    Some(column)
+}
+
+// Lines joined while brackets are open, with the indentation of their first line
+fn logical_lines(content: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+    let mut depth = 0i32;
+    for line in content.lines() {
+        let code = line.split(" #").next().unwrap_or(line);
+        if current.is_none() {
+            if code.trim().is_empty() || code.trim_start().starts_with('#') {
+                continue;
+            }
+            current = Some((code.len() - code.trim_start().len(), String::new()));
+        }
+        if let Some((_, text)) = current.as_mut() {
+            text.push_str(code.trim());
+            text.push(' ');
+        }
+        depth += code.matches(['(', '[', '{']).count() as i32;
+        depth -= code.matches([')', ']', '}']).count() as i32;
+        if depth <= 0 {
+            depth = 0;
+            lines.extend(
+                current
+                    .take()
+                    .map(|(indent, text)| (indent, text.trim_end().to_string())),
+            );
+        }
+    }
+    lines.extend(current.map(|(indent, text)| (indent, text.trim_end().to_string())));
+    lines
+}
+
+// Each `class Name(bases):` with the logical lines of its body
+fn python_classes(content: &str) -> Vec<(String, String, Vec<String>)> {
+    let lines = logical_lines(content);
+    let mut classes = Vec::new();
+    for (index, (indent, line)) in lines.iter().enumerate() {
+        let Some(rest) = line.strip_prefix("class ") else {
+            continue;
+        };
+        let name_end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_string();
+        let bases = bracketed(rest[name_end..].trim_start())
+            .unwrap_or_default()
+            .to_string();
+        let body = lines[index + 1..]
+            .iter()
+            .take_while(|(body_indent, _)| body_indent > indent)
+            .map(|(_, line)| line.clone())
+            .collect();
+        classes.push((name, bases, body));
+    }
+    classes
+}
+
+// `name = value` or `name: annotation = value`
+fn assignment(line: &str) -> Option<(&str, Option<&str>, &str)> {
+    let (target, value) = line.split_once('=')?;
+    if value.starts_with('=') || target.ends_with(['!', '<', '>']) {
+        return None;
+    }
+    let (name, annotation) = match target.split_once(':') {
+        Some((name, annotation)) => (name.trim(), Some(annotation.trim())),
+        None => (target.trim(), None),
+    };
+    name.chars()
+        .all(|c| c.is_alphanumeric() || c == '_')
+        .then_some((name, annotation, value.trim()))
+}
+
+/// SQLAlchemy declarative models: classes setting `__tablename__`
+pub fn parse_sqlalchemy(content: &str) -> Vec<Model> {
+    let mut models = Vec::new();
+    for (class, _, body) in python_classes(content) {
+        let Some(table) = body.iter().find_map(|line| {
+            let (name, _, value) = assignment(line)?;
+            (name == "__tablename__")
+                .then(|| string_literal(value))
+                .flatten()
+        }) else {
+            continue;
+        };
+        let mut columns = Vec::new();
+        for line in &body {
+            let Some((name, annotation, value)) = assignment(line) else {
+                continue;
+            };
+            let Some(mut column) = python_column(value, Some(name)) else {
+                continue;
+            };
+            // `Mapped[Optional[int]]` carries the type and nullability mapped_column leaves out
+            if let Some(inner) = annotation.and_then(|a| a.strip_prefix("Mapped[")) {
+                let inner = inner.strip_suffix(']').unwrap_or(inner);
+                let optional = inner
+                    .strip_prefix("Optional[")
+                    .and_then(|i| i.strip_suffix(']'));
+                if column.data_type.is_none() {
+                    column.data_type = Some(optional.unwrap_or(inner).replace(" | None", ""));
+                }
+                column.not_null |= optional.is_none() && !inner.contains("None");
+            }
+            columns.push(column);
+        }
+        models.push(Model {
+            class: Some(class),
+            table,
+            columns,
+        });
+    }
+    models
+}
+
+/// Django models in the app `app`: classes deriving from `models.Model` that aren't
+/// abstract
+pub fn parse_django(content: &str, app: &str) -> Vec<Model> {
+    let mut models = Vec::new();
+    for (class, bases, body) in python_classes(content) {
+        if !bases.split(',').any(|b| b.trim() == "models.Model") {
+            continue;
+        }
+        let mut table = None;
+        let mut columns = Vec::new();
+        for line in &body {
+            let Some((name, _, value)) = assignment(line) else {
+                continue;
+            };
+            match name {
+                "abstract" if value == "True" => table = Some(None),
+                "db_table" if table.is_none() => table = Some(string_literal(value)),
+                _ => {}
+            }
+            let Some(field) = value.strip_prefix("models.") else {
+                continue;
+            };
+            let Some(open) = field.find('(') else {
+                continue;
+            };
+            let field_type = &field[..open];
+            if !field_type.ends_with("Field") && field_type != "ForeignKey" {
+                continue;
+            }
+            if field_type == "ManyToManyField" {
+                continue;
+            }
+            let arguments = bracketed(&field[open..]).unwrap_or_default();
+            let arguments = split_top_level(arguments, ',');
+            let column_name = arguments
+                .iter()
+                .find_map(|a| keyword_argument(a, "db_column"))
+                .and_then(string_literal)
+                .unwrap_or_else(|| match field_type {
+                    "ForeignKey" | "OneToOneField" => format!("{name}_id"),
+                    _ => name.to_string(),
+                });
+            let mut column = Column::new(&column_name, Some(field_type.to_string()));
+            column.primary_key = arguments
+                .iter()
+                .any(|a| keyword_argument(a, "primary_key") == Some("True"));
+            column.not_null = !arguments
+                .iter()
+                .any(|a| keyword_argument(a, "null") == Some("True"));
+            columns.push(column);
+        }
+        let table = match table {
+            Some(None) => continue,
+            Some(Some(table)) => table,
+            None => format!("{}_{}", app, class.to_lowercase()),
+        };
+        if !columns.iter().any(|c| c.primary_key) {
+            let mut id = Column::new("id", Some("AutoField".to_string()));
+            id.primary_key = true;
+            id.not_null = true;
+            columns.insert(0, id);
+        }
+        models.push(Model {
+            class: Some(class),
+            table,
+            columns,
+        });
+    }
+    models
+}
+
+// --- Prisma ---
+
+/// Prisma `model` blocks, with relation fields left out
+pub fn parse_prisma(content: &str) -> Vec<Model> {
+    let blocks = prisma_blocks(content);
+    let enums: HashSet<&str> = blocks
+        .iter()
+        .filter(|(keyword, _, _)| *keyword == "enum")
+        .map(|(_, name, _)| *name)
+        .collect();
+
+    let mut models = Vec::new();
+    for (keyword, name, body) in &blocks {
+        if *keyword != "model" {
+            continue;
+        }
+        let mut table = name.to_string();
+        let mut columns: Vec<Column> = Vec::new();
+        let mut primary_keys: Vec<String> = Vec::new();
+        for line in body.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            if let Some(attribute) = line.strip_prefix("@@") {
+                if let Some(mapped) = call_arguments(attribute, "map") {
+                    table = string_literal(mapped).unwrap_or(table);
+                } else if let Some(keys) = call_arguments(attribute, "id") {
+                    let keys = bracketed(keys.trim()).unwrap_or(keys);
+                    primary_keys.extend(split_top_level(keys, ',').iter().map(|k| k.to_string()));
+                }
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let (Some(field), Some(field_type)) = (words.next(), words.next()) else {
+                continue;
+            };
+            let base = field_type.trim_end_matches('?').trim_end_matches("[]");
+            let scalar = PRISMA_SCALARS.contains(&base) || enums.contains(base);
+            if !scalar {
+                continue;
+            }
+            let attributes: Vec<&str> = words.collect();
+            let name = attributes
+                .iter()
+                .find_map(|a| call_arguments(a.strip_prefix('@')?, "map"))
+                .and_then(string_literal)
+                .unwrap_or_else(|| field.to_string());
+            let mut column = Column::new(&name, Some(base.to_string()));
+            column.primary_key = attributes
+                .iter()
+                .any(|a| *a == "@id" || a.starts_with("@id("));
+            column.not_null = !field_type.ends_with('?');
+            columns.push(column);
+        }
+        for column in &mut columns {
+            column.primary_key |= primary_keys.contains(&column.name);
+        }
+        models.push(Model {
+            class: Some(name.to_string()),
+            table,
+            columns,
+        });
+    }
+    models
+}
+
+// `model User { ... }` -> ("model", "User", body)
+fn prisma_blocks(content: &str) -> Vec<(&str, &str, &str)> {
+    let mut blocks = Vec::new();
+    let mut rest = content;
+    while let Some(open) = rest.find('{') {
+        let header: Vec<&str> = rest[..open]
+            .lines()
+            .last()
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect();
+        let Some(body) = bracketed(&rest[open..]) else {
+            break;
+        };
+        if let [keyword, name] = header.as_slice() {
+            blocks.push((*keyword, *name, body));
+        }
+        rest = &rest[open + body.len() + 2..];
+    }
+    blocks
+}
+
+// --- Diesel ---
+
+/// Diesel `table!` schemas, and structs mapped onto them with `table_name`
+pub fn parse_diesel(content: &str) -> Vec<Model> {
+    let mut models = Vec::new();
+    let mut rest = content;
+    while let Some(index) = rest.find("table!") {
+        let after = &rest[index + "table!".len()..];
+        rest = after;
+        let Some(open) = after.find('{') else {
+            break;
+        };
+        let Some(inner) = bracketed(&after[open..]) else {
+            continue;
+        };
+        let inner: String = inner
+            .lines()
+            .filter(|line| {
+                !line.trim_start().starts_with("#[") && !line.trim_start().starts_with("//")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let Some(body_open) = inner.find('{') else {
+            continue;
+        };
+        let header = inner[..body_open].trim();
+        let name_end = header.find('(').unwrap_or(header.len());
+        let table = unquote(header[..name_end].trim());
+        let keys: Vec<&str> = bracketed(&header[name_end..])
+            .map(|keys| split_top_level(keys, ','))
+            .unwrap_or_default();
+        let columns = bracketed(&inner[body_open..])
+            .map(|body| split_top_level(body, ','))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|line| {
+                let (name, column_type) = line.split_once("->")?;
+                let name = name.trim();
+                let column_type = column_type.trim();
+                let nullable = column_type
+                    .strip_prefix("Nullable<")
+                    .and_then(|t| t.strip_suffix('>'));
+                let mut column =
+                    Column::new(name, Some(nullable.unwrap_or(column_type).to_string()));
+                column.primary_key = keys.contains(&name);
+                column.not_null = nullable.is_none();
+                Some(column)
+            })
+            .collect();
+        models.push(Model {
+            class: None,
+            table,
+            columns,
+        });
+    }
+
+    // `#[diesel(table_name = users)]` or `#[table_name = "users"]` above a struct
+    let mut table_name: Option<String> = None;
+    for line in content.lines().map(str::trim) {
+        if let Some(index) = line.find("table_name") {
+            if line.starts_with("#[") {
+                let value = line[index + "table_name".len()..]
+                    .trim_start()
+                    .trim_start_matches('=')
+                    .trim();
+                let end = value.find([')', ']', ',']).unwrap_or(value.len());
+                let path = value[..end].trim();
+                table_name = Some(unquote(path.rsplit("::").next().unwrap_or(path)));
+                continue;
+            }
+        }
+        if let Some(table) = table_name.as_ref() {
+            if let Some(rest) = line
+                .strip_prefix("pub struct ")
+                .or_else(|| line.strip_prefix("struct "))
+            {
+                let end = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                models.push(Model {
+                    class: Some(rest[..end].to_string()),
+                    table: table.clone(),
+                    columns: Vec::new(),
+                });
+                table_name = None;
+            } else if !line.starts_with("#[") && !line.is_empty() {
+                table_name = None;
+            }
+        }
+    }
+    models
+}
@@ -0,0 +1,186 @@
+// src/core/debt.rs - TODO/FIXME markers in code comments and the issues they reference
+use anyhow::Result;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::comments::{comments_in, syntax_for, CommentSyntax};
+use crate::core::matrix::ProjectMatrix;
+use crate::core::transform::{apply_patch, PatchSummary};
+use crate::plugins::interface::{MatrixPatch, TagUpdate};
+use crate::utils::config::IssueTrackerConfig;
+use crate::utils::issues::{issue_keys, tracker_for, IssueClient, IssueStatus};
+
+/// Words that mark a comment as debt
+pub const MARKERS: &[&str] = &["TODO", "FIXME", "HACK", "XXX"];
+
+/// Files referencing an open or unverified issue get `issue:<key>`, e.g. `issue:PAY-12`
+pub const ISSUE_TAG_PREFIX: &str = "issue:";
+
+/// A marker comment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebtMarker {
+    // Relative to the project root
+    pub file: PathBuf,
+    // 1-based
+    pub line: usize,
+    // `TODO`, `FIXME`, ...
+    pub marker: String,
+    // The comment after the marker
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issues: Vec<String>,
+}
+
+/// An issue referenced by markers, and where
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkedIssue {
+    pub key: String,
+    pub status: IssueStatus,
+    pub files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebtReport {
+    pub markers: Vec<DebtMarker>,
+    // Sorted by key
+    pub issues: Vec<LinkedIssue>,
+}
+
+impl DebtReport {
+    /// Markers in the comments of every maintained file in the matrix whose language
+    /// has a known comment syntax, read from `root`. Unreadable files are skipped.
+    pub fn harvest(root: &Path, matrix: &ProjectMatrix) -> Self {
+        let mut markers = Vec::new();
+        for file in matrix.maintained_files() {
+            let Some(syntax) = syntax_for(&file.relative_path) else {
+                continue;
+            };
+            let Ok(content) = std::fs::read_to_string(root.join(&file.relative_path)) else {
+                continue;
+            };
+            for (line, marker, comment) in markers_in(&content, syntax) {
+                markers.push(DebtMarker {
+                    file: file.relative_path.clone(),
+                    line,
+                    marker,
+                    issues: issue_keys(&comment),
+                    text: comment,
+                });
+            }
+        }
+        markers.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+
+        let mut issues: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+        for marker in &markers {
+            for key in &marker.issues {
+                let files = issues.entry(key.clone()).or_default();
+                if !files.contains(&marker.file) {
+                    files.push(marker.file.clone());
+                }
+            }
+        }
+        Self {
+            markers,
+            issues: issues
+                .into_iter()
+                .map(|(key, files)| LinkedIssue {
+                    key,
+                    status: IssueStatus::Unverified,
+                    files,
+                })
+                .collect(),
+        }
+    }
+
+    /// Look each issue up in the tracker responsible for it. Keys no tracker claims and
+    /// failed lookups stay unverified.
+    pub async fn verify(&mut self, trackers: &[IssueTrackerConfig], offline: bool) -> Result<()> {
+        let client = IssueClient::new(offline)?;
+        for issue in &mut self.issues {
+            let Some(tracker) = tracker_for(trackers, &issue.key) else {
+                continue;
+            };
+            match client.status(tracker, &issue.key).await {
+                Ok(status) => issue.status = status,
+                Err(e) => warn!("Could not verify {}: {e:#}", issue.key),
+            }
+        }
+        Ok(())
+    }
+
+    /// Markers referencing an issue that has been closed: the work is done or dropped,
+    /// so the marker is stale
+    pub fn stale_markers(&self) -> Vec<&DebtMarker> {
+        let closed: Vec<&str> = self
+            .issues
+            .iter()
+            .filter(|issue| issue.status == IssueStatus::Closed)
+            .map(|issue| issue.key.as_str())
+            .collect();
+        self.markers
+            .iter()
+            .filter(|marker| {
+                marker
+                    .issues
+                    .iter()
+                    .any(|key| closed.contains(&key.as_str()))
+            })
+            .collect()
+    }
+
+    /// Tag the files referencing each issue that isn't closed
+    pub fn link(&self, matrix: &mut ProjectMatrix) -> PatchSummary {
+        let mut patch = MatrixPatch {
+            relationships: Vec::new(),
+            tags: Vec::new(),
+            summaries: Vec::new(),
+        };
+        for issue in &self.issues {
+            if issue.status == IssueStatus::Closed {
+                continue;
+            }
+            for file in &issue.files {
+                patch.tags.push(TagUpdate {
+                    file: file.to_string_lossy().replace('\\', "/"),
+                    tags: vec![format!("{ISSUE_TAG_PREFIX}{}", issue.key)],
+                });
+            }
+        }
+        apply_patch(matrix, patch, "debt")
+    }
+}
+
+/// `(line, marker, comment after the marker)` for each marker in a comment. A marker
+/// must be a whole uppercase word inside a line comment, or on a line of a block
+/// comment.
+pub fn markers_in(content: &str, syntax: &CommentSyntax) -> Vec<(usize, String, String)> {
+    comments_in(content, syntax)
+        .into_iter()
+        .filter_map(|(line, comment)| {
+            let (marker, rest) = find_marker(comment)?;
+            let text = rest
+                .trim_start_matches(|c: char| c == ':' || c == '-' || c.is_whitespace())
+                .trim_end()
+                .to_string();
+            Some((line, marker.to_string(), text))
+        })
+        .collect()
+}
+
+fn find_marker(comment: &str) -> Option<(&'static str, &str)> {
+    MARKERS
+        .iter()
+        .filter_map(|marker| {
+            comment.match_indices(marker).find_map(|(at, _)| {
+                let before = comment[..at].chars().next_back();
+                let after = comment[at + marker.len()..].chars().next();
+                let whole = !before.is_some_and(|c| c.is_alphanumeric() || c == '_')
+                    && !after.is_some_and(|c| c.is_alphanumeric() || c == '_');
+                whole.then(|| (at, *marker, &comment[at + marker.len()..]))
+            })
+        })
+        .min_by_key(|(at, _, _)| *at)
+        .map(|(_, marker, rest)| (marker, rest))
+}
@@ -0,0 +1,603 @@
+// src/core/deployments.rs - Deployment units read from Dockerfiles, Compose files,
+// Kubernetes manifests and Terraform, each with the project files it packages, so docs
+// can say what runs where
+use glob::{MatchOptions, Pattern};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::core::matrix::ProjectMatrix;
+use crate::utils::file_utils::normalize_lexically;
+
+const COMPOSE_FILES: &[&str] = &[
+    "docker-compose.yml",
+    "docker-compose.yaml",
+    "compose.yml",
+    "compose.yaml",
+];
+
+// Kubernetes kinds that run containers
+const WORKLOAD_KINDS: &[&str] = &[
+    "Deployment",
+    "StatefulSet",
+    "DaemonSet",
+    "ReplicaSet",
+    "Job",
+    "CronJob",
+    "Pod",
+];
+
+// Terraform attributes pointing at what a resource deploys: local paths, then images
+const TERRAFORM_PATHS: &[&str] = &["source_dir", "source_file", "source_path", "context"];
+const TERRAFORM_IMAGES: &[&str] = &["image", "image_uri"];
+
+// Docker matches `*` within one path segment, as a shell would
+const DOCKER_MATCH: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeploymentKind {
+    Image,          // Built from a Dockerfile
+    ComposeService, // A service in a Compose file
+    Kubernetes,     // A workload in a Kubernetes manifest
+    Terraform,      // A Terraform resource deploying project files or an image
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Deployment {
+    pub name: String,
+    pub kind: DeploymentKind,
+    // Relative path of the file defining it
+    pub manifest: PathBuf,
+    // e.g. `Deployment` or `aws_lambda_function`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+    // The image it runs, or for a Dockerfile, the one it builds on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    // Relative path of the Dockerfile its image is built from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dockerfile: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    // Relative paths of the project files packaged into it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<PathBuf>,
+}
+
+impl Deployment {
+    fn new(name: &str, kind: DeploymentKind, manifest: &Path) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            manifest: manifest.to_path_buf(),
+            resource_type: None,
+            image: None,
+            dockerfile: None,
+            depends_on: Vec::new(),
+            files: Vec::new(),
+        }
+    }
+}
+
+/// What a Dockerfile's final stage copies in from the build context
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dockerfile {
+    pub base_image: Option<String>,
+    pub sources: Vec<String>,
+}
+
+impl Dockerfile {
+    /// Sources of `COPY` and `ADD` in the last stage. Copies from other stages or images
+    /// and remote sources are left out; so are the copies of earlier stages, which only
+    /// reach the image through the copies they're named in.
+    pub fn parse(content: &str) -> Self {
+        let mut dockerfile = Self::default();
+        let mut instruction = String::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if instruction.is_empty() && (line.is_empty() || line.starts_with('#')) {
+                continue;
+            }
+            if let Some(continued) = line.strip_suffix('\\') {
+                instruction.push_str(continued);
+                instruction.push(' ');
+                continue;
+            }
+            instruction.push_str(line);
+            dockerfile.apply(&std::mem::take(&mut instruction));
+        }
+        if !instruction.is_empty() {
+            dockerfile.apply(&instruction);
+        }
+        dockerfile
+    }
+
+    fn apply(&mut self, instruction: &str) {
+        let (keyword, arguments) = instruction
+            .split_once(char::is_whitespace)
+            .unwrap_or((instruction, ""));
+        match keyword.to_ascii_uppercase().as_str() {
+            "FROM" => {
+                self.base_image = arguments
+                    .split_whitespace()
+                    .find(|word| !word.starts_with("--"))
+                    .map(str::to_string);
+                self.sources.clear();
+            }
+            "COPY" | "ADD" => {
+                let arguments = arguments.trim();
+                let mut words: Vec<String> = if arguments.starts_with('[') {
+                    serde_json::from_str(arguments).unwrap_or_default()
+                } else {
+                    arguments.split_whitespace().map(str::to_string).collect()
+                };
+                if words.iter().any(|word| word.starts_with("--from")) {
+                    return;
+                }
+                words.retain(|word| !word.starts_with("--"));
+                // The last word is the destination
+                words.pop();
+                self.sources.extend(words.into_iter().filter(|source| {
+                    !source.contains("://")
+                        && !source.starts_with("git@")
+                        && !source.starts_with("<<")
+                }));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The deployment units defined in the project, from the manifests among its files
+pub fn detect_deployments(matrix: &ProjectMatrix) -> Vec<Deployment> {
+    let project = ProjectFiles::new(matrix);
+    let mut deployments = Vec::new();
+    for (relative, path) in &project.files {
+        let Some(file_name) = relative.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let is_dockerfile = dockerfile_name(file_name).is_some();
+        let is_compose = COMPOSE_FILES.contains(&file_name)
+            || (file_name.starts_with("docker-compose.")
+                && (file_name.ends_with(".yml") || file_name.ends_with(".yaml")));
+        let is_yaml = file_name.ends_with(".yml") || file_name.ends_with(".yaml");
+        let is_terraform = file_name.ends_with(".tf");
+        if !(is_dockerfile || is_compose || is_yaml || is_terraform) {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(path) else {
+            debug!("Could not read {} for deployments", path.display());
+            continue;
+        };
+        if is_dockerfile {
+            deployments.push(project.image(relative, &content));
+        } else if is_compose {
+            deployments.extend(project.compose_services(relative, &content));
+        } else if is_yaml {
+            deployments.extend(kubernetes_workloads(relative, &content));
+        } else {
+            deployments.extend(project.terraform_resources(relative, &content));
+        }
+    }
+
+    // A workload running an image built here packages what the image does
+    let built: HashMap<String, (Option<PathBuf>, Vec<PathBuf>)> = deployments
+        .iter()
+        .filter(|d| d.dockerfile.is_some())
+        .flat_map(|d| {
+            let tag = d
+                .image
+                .as_deref()
+                .filter(|_| d.kind != DeploymentKind::Image);
+            let names = [Some(d.name.clone()), tag.map(image_name)];
+            names
+                .into_iter()
+                .flatten()
+                .map(move |name| (name, (d.dockerfile.clone(), d.files.clone())))
+        })
+        .collect();
+    for deployment in &mut deployments {
+        if deployment.dockerfile.is_some() {
+            continue;
+        }
+        if let Some((dockerfile, files)) = deployment
+            .image
+            .as_deref()
+            .and_then(|image| built.get(&image_name(image)))
+        {
+            deployment.dockerfile = dockerfile.clone();
+            deployment.files = files.clone();
+        }
+    }
+
+    deployments.sort_by(|a, b| (&a.manifest, a.kind, &a.name).cmp(&(&b.manifest, b.kind, &b.name)));
+    deployments
+}
+
+// The image a Dockerfile name implies: `worker` for `Dockerfile.worker` or
+// `worker.Dockerfile`, none for a plain `Dockerfile`
+fn dockerfile_name(file_name: &str) -> Option<Option<&str>> {
+    if file_name == "Dockerfile" {
+        return Some(None);
+    }
+    if let Some(name) = file_name.strip_prefix("Dockerfile.") {
+        return Some(Some(name));
+    }
+    file_name
+        .strip_suffix(".Dockerfile")
+        .or_else(|| file_name.strip_suffix(".dockerfile"))
+        .map(Some)
+}
+
+// `registry.example.com/team/api:1.2@sha256:...` -> `api`
+fn image_name(image: &str) -> String {
+    let image = image.split('@').next().unwrap_or_default();
+    let name = image.rsplit('/').next().unwrap_or_default();
+    name.split(':').next().unwrap_or_default().to_string()
+}
+
+// `${path.module}/../src` or `./src` relative to `base`, staying inside the project
+fn join(base: &Path, path: &str) -> Option<PathBuf> {
+    let path = path
+        .trim_start_matches("${path.module}/")
+        .trim_start_matches("${path.root}/")
+        .trim_start_matches("${path.cwd}/");
+    if path.contains("${") || Path::new(path).is_absolute() {
+        return None;
+    }
+    let joined = base.join(path);
+    let depth = |path: &Path| {
+        path.components().try_fold(0i32, |depth, component| {
+            let depth = match component {
+                std::path::Component::ParentDir => depth - 1,
+                std::path::Component::CurDir => depth,
+                _ => depth + 1,
+            };
+            (depth >= 0).then_some(depth)
+        })
+    };
+    depth(&joined)?;
+    Some(normalize_lexically(&joined))
+}
+
+struct ProjectFiles<'a> {
+    // Relative path -> absolute path
+    files: BTreeMap<&'a Path, &'a Path>,
+    project_name: String,
+}
+
+impl<'a> ProjectFiles<'a> {
+    fn new(matrix: &'a ProjectMatrix) -> Self {
+        Self {
+            files: matrix
+                .files
+                .values()
+                .map(|f| (f.relative_path.as_path(), f.path.as_path()))
+                .collect(),
+            project_name: matrix
+                .metadata
+                .project_root
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("app")
+                .to_string(),
+        }
+    }
+
+    fn read(&self, relative: &Path) -> Option<String> {
+        std::fs::read_to_string(self.files.get(relative)?).ok()
+    }
+
+    // Files at or under `path`
+    fn under(&self, path: &Path) -> impl Iterator<Item = &'a Path> + '_ {
+        let path = path.to_path_buf();
+        self.files
+            .keys()
+            .copied()
+            .filter(move |file| file.starts_with(&path))
+    }
+
+    fn image(&self, relative: &Path, content: &str) -> Deployment {
+        let context = relative.parent().unwrap_or(Path::new(""));
+        let file_name = relative.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let name = match dockerfile_name(file_name).flatten() {
+            Some(name) => name.to_string(),
+            None => context
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(&self.project_name)
+                .to_string(),
+        };
+        let dockerfile = Dockerfile::parse(content);
+        let mut deployment = Deployment::new(&name, DeploymentKind::Image, relative);
+        deployment.image = dockerfile.base_image.clone();
+        deployment.files = self.packaged(context, &dockerfile);
+        deployment.dockerfile = Some(relative.to_path_buf());
+        deployment
+    }
+
+    // The files a build with `context` copies in, less those `.dockerignore` excludes
+    fn packaged(&self, context: &Path, dockerfile: &Dockerfile) -> Vec<PathBuf> {
+        let ignore = DockerIgnore::new(
+            self.read(&context.join(".dockerignore"))
+                .unwrap_or_default()
+                .as_str(),
+        );
+        let mut files = BTreeSet::new();
+        for source in &dockerfile.sources {
+            let source = source.trim_start_matches("./").trim_start_matches('/');
+            if source.contains(['*', '?', '[']) {
+                let Ok(pattern) = Pattern::new(source) else {
+                    continue;
+                };
+                files.extend(self.under(context).filter(|file| {
+                    let inside = file.strip_prefix(context).unwrap_or(file);
+                    inside
+                        .ancestors()
+                        .any(|path| pattern.matches_path_with(path, DOCKER_MATCH))
+                }));
+            } else if let Some(path) = join(context, source) {
+                files.extend(self.under(&path));
+            }
+        }
+        files
+            .into_iter()
+            .filter(|file| !ignore.excludes(file.strip_prefix(context).unwrap_or(file)))
+            .map(Path::to_path_buf)
+            .collect()
+    }
+
+    fn compose_services(&self, relative: &Path, content: &str) -> Vec<Deployment> {
+        let Ok(compose) = serde_yaml::from_str::<Value>(content) else {
+            debug!("{} is not valid YAML", relative.display());
+            return Vec::new();
+        };
+        let Some(services) = compose.get("services").and_then(Value::as_mapping) else {
+            return Vec::new();
+        };
+        let base = relative.parent().unwrap_or(Path::new(""));
+        let mut deployments = Vec::new();
+        for (name, service) in services {
+            let Some(name) = name.as_str() else {
+                continue;
+            };
+            let mut deployment = Deployment::new(name, DeploymentKind::ComposeService, relative);
+            deployment.image = service
+                .get("image")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            deployment.depends_on = match service.get("depends_on") {
+                Some(Value::Sequence(names)) => names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect(),
+                Some(Value::Mapping(names)) => names
+                    .keys()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+            let (context, dockerfile) = match service.get("build") {
+                Some(Value::String(context)) => (Some(context.as_str()), None),
+                Some(build @ Value::Mapping(_)) => (
+                    Some(build.get("context").and_then(Value::as_str).unwrap_or(".")),
+                    build.get("dockerfile").and_then(Value::as_str),
+                ),
+                _ => (None, None),
+            };
+            if let Some(context) = context.and_then(|context| join(base, context)) {
+                let dockerfile = join(&context, dockerfile.unwrap_or("Dockerfile"));
+                let parsed = dockerfile
+                    .as_deref()
+                    .and_then(|path| self.read(path))
+                    .map(|content| Dockerfile::parse(&content));
+                deployment.files = match parsed {
+                    Some(parsed) => self.packaged(&context, &parsed),
+                    // An inline or missing Dockerfile could copy anything in the context
+                    None => self.under(&context).map(Path::to_path_buf).collect(),
+                };
+                deployment.dockerfile =
+                    dockerfile.filter(|path| self.files.contains_key(path.as_path()));
+            }
+            deployments.push(deployment);
+        }
+        deployments
+    }
+
+    fn terraform_resources(&self, relative: &Path, content: &str) -> Vec<Deployment> {
+        let base = relative.parent().unwrap_or(Path::new(""));
+        let blocks = terraform_blocks(content);
+        // `data.archive_file.<name>` zips a directory for a resource to deploy
+        let archives: HashMap<&str, &str> = blocks
+            .iter()
+            .filter(|b| b.block == "data" && b.resource_type == "archive_file")
+            .filter_map(|b| {
+                Some((
+                    b.name.as_str(),
+                    b.attribute(&["source_dir", "source_file"])?,
+                ))
+            })
+            .collect();
+
+        let mut deployments = Vec::new();
+        for block in blocks.iter().filter(|b| b.block == "resource") {
+            let mut deployment = Deployment::new(
+                &format!("{}.{}", block.resource_type, block.name),
+                DeploymentKind::Terraform,
+                relative,
+            );
+            deployment.resource_type = Some(block.resource_type.clone());
+            deployment.image = block.attribute(TERRAFORM_IMAGES).map(str::to_string);
+            let source = block.attribute(TERRAFORM_PATHS).or_else(|| {
+                block.values().find_map(|value| {
+                    let name = value
+                        .split("data.archive_file.")
+                        .nth(1)?
+                        .split('.')
+                        .next()?;
+                    archives.get(name).copied()
+                })
+            });
+            if let Some(path) = source.and_then(|source| join(base, source)) {
+                deployment.files = self.under(&path).map(Path::to_path_buf).collect();
+            }
+            if !deployment.files.is_empty() || deployment.image.is_some() {
+                deployments.push(deployment);
+            }
+        }
+        deployments
+    }
+}
+
+fn kubernetes_workloads(relative: &Path, content: &str) -> Vec<Deployment> {
+    let mut deployments = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(content) {
+        // Helm templates and other non-YAML stop here
+        let Ok(manifest) = Value::deserialize(document) else {
+            break;
+        };
+        let Some(kind) = manifest.get("kind").and_then(Value::as_str) else {
+            continue;
+        };
+        if manifest.get("apiVersion").is_none() || !WORKLOAD_KINDS.contains(&kind) {
+            continue;
+        }
+        let Some(name) = manifest
+            .get("metadata")
+            .and_then(|metadata| metadata.get("name"))
+            .and_then(Value::as_str)
+        else {
+            continue;
+        };
+        let mut spec = manifest.get("spec");
+        // CronJob -> Job -> Pod template -> Pod
+        for step in ["jobTemplate", "spec", "template", "spec"] {
+            if let Some(inner) = spec.and_then(|spec| spec.get(step)) {
+                spec = Some(inner);
+            }
+        }
+        let image = spec
+            .and_then(|spec| spec.get("containers"))
+            .and_then(Value::as_sequence)
+            .and_then(|containers| containers.first())
+            .and_then(|container| container.get("image"))
+            .and_then(Value::as_str);
+
+        let mut deployment = Deployment::new(name, DeploymentKind::Kubernetes, relative);
+        deployment.resource_type = Some(kind.to_string());
+        deployment.image = image.map(str::to_string);
+        deployments.push(deployment);
+    }
+    deployments
+}
+
+/// `.dockerignore` rules; the last rule matching a path decides, and `!` re-includes
+struct DockerIgnore {
+    rules: Vec<(Pattern, bool)>,
+}
+
+impl DockerIgnore {
+    fn new(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (pattern, excludes) = match line.strip_prefix('!') {
+                    Some(pattern) => (pattern, false),
+                    None => (line, true),
+                };
+                let pattern = pattern.trim_start_matches('/').trim_end_matches('/');
+                Some((Pattern::new(pattern).ok()?, excludes))
+            })
+            .collect();
+        Self { rules }
+    }
+
+    fn excludes(&self, path: &Path) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(pattern, _)| {
+                path.ancestors()
+                    .any(|path| pattern.matches_path_with(path, DOCKER_MATCH))
+            })
+            .is_some_and(|(_, excludes)| *excludes)
+    }
+}
+
+struct TerraformBlock {
+    block: String,
+    resource_type: String,
+    name: String,
+    // Top-level `key = "value"` attributes of the block
+    attributes: Vec<(String, String)>,
+}
+
+impl TerraformBlock {
+    fn attribute(&self, keys: &[&str]) -> Option<&str> {
+        keys.iter().find_map(|key| {
+            self.attributes
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str())
+        })
+    }
+
+    fn values(&self) -> impl Iterator<Item = &str> {
+        self.attributes.iter().map(|(_, value)| value.as_str())
+    }
+}
+
+// `resource "type" "name" { ... }` and `data` blocks, with the string attributes set
+// directly in them; enough of HCL to find what a resource deploys
+fn terraform_blocks(content: &str) -> Vec<TerraformBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<TerraformBlock> = None;
+    let mut depth = 0usize;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+        if depth == 0 {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if let [block @ ("resource" | "data"), resource_type, name, "{", ..] = words.as_slice()
+            {
+                current = Some(TerraformBlock {
+                    block: block.to_string(),
+                    resource_type: resource_type.trim_matches('"').to_string(),
+                    name: name.trim_matches('"').to_string(),
+                    attributes: Vec::new(),
+                });
+            }
+        } else if depth == 1 {
+            if let (Some(block), Some((key, value))) = (current.as_mut(), line.split_once('=')) {
+                let value = value.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|value| value.strip_suffix('"'))
+                    .unwrap_or(value);
+                block
+                    .attributes
+                    .push((key.trim().to_string(), value.to_string()));
+            }
+        }
+        depth += line.matches('{').count();
+        depth = depth.saturating_sub(line.matches('}').count());
+        if depth == 0 {
+            blocks.extend(current.take());
+        }
+    }
+    blocks
+}
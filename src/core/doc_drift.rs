@@ -0,0 +1,309 @@
+// src/core/doc_drift.rs - References in hand-written markdown to files and code elements
+// the matrix no longer has
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+use crate::core::matrix::ProjectMatrix;
+
+// Characters that never appear in a path or symbol reference, so spans holding them are
+// commands, globs or placeholders
+const NOT_A_REFERENCE: &[char] = &[
+    '<', '>', '*', '?', '{', '}', '$', '|', '"', '\'', '=', ',', '[', ']', '@', '%', '!',
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferenceKind {
+    // A link or a code span naming a path
+    File,
+    // A code span naming a qualified element, e.g. `Config::load` or `scanner.scan()`
+    Symbol,
+}
+
+impl std::fmt::Display for ReferenceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ReferenceKind::File => "file",
+            ReferenceKind::Symbol => "symbol",
+        })
+    }
+}
+
+/// A reference that no longer resolves
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaleReference {
+    // Markdown file, relative to the project root
+    pub doc: PathBuf,
+    // 1-based
+    pub line: usize,
+    pub reference: String,
+    pub kind: ReferenceKind,
+}
+
+/// Result of checking the project's markdown against the matrix
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocDriftReport {
+    pub docs_checked: usize,
+    pub references_checked: usize,
+    pub stale: Vec<StaleReference>,
+}
+
+impl DocDriftReport {
+    /// Check every markdown file under `root` that git doesn't ignore. Links count as
+    /// file references; code spans count when they look like a path or like a
+    /// qualified name rooted in the project (a file, directory or element name, or
+    /// `crate`), so third-party names such as `serde_json::to_value` are left alone.
+    /// Fenced code blocks are skipped.
+    pub fn check(root: &Path, matrix: &ProjectMatrix) -> Result<Self> {
+        let known = KnownNames::from_matrix(matrix);
+        let mut docs: Vec<PathBuf> = WalkBuilder::new(root)
+            .build()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .map(|entry| entry.into_path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+            .collect();
+        docs.sort();
+
+        let mut report = Self::default();
+        for path in docs {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            report.check_document(root, relative, &content, &known);
+        }
+        Ok(report)
+    }
+
+    fn check_document(&mut self, root: &Path, doc: &Path, content: &str, known: &KnownNames) {
+        self.docs_checked += 1;
+        let doc_dir = doc.parent().unwrap_or(Path::new(""));
+        let mut fence: Option<&str> = None;
+        for (index, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            if let Some(marker) = fence {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+                continue;
+            }
+            if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(*m)) {
+                fence = Some(marker);
+                continue;
+            }
+
+            let mut stale = |reference: &str, kind| {
+                self.stale.push(StaleReference {
+                    doc: doc.to_path_buf(),
+                    line: index + 1,
+                    reference: reference.to_string(),
+                    kind,
+                })
+            };
+            for target in link_targets(line) {
+                self.references_checked += 1;
+                if !known.path_exists(root, doc_dir, &target) {
+                    stale(&target, ReferenceKind::File);
+                }
+            }
+            for span in line.split('`').skip(1).step_by(2) {
+                let span = span.trim();
+                if let Some(kind) = known.classify(span) {
+                    self.references_checked += 1;
+                    let exists = match kind {
+                        ReferenceKind::File => known.path_exists(root, doc_dir, span),
+                        ReferenceKind::Symbol => known.symbol_exists(span),
+                    };
+                    if !exists {
+                        stale(span, kind);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// What the matrix knows about, for resolving references
+struct KnownNames<'a> {
+    matrix: &'a ProjectMatrix,
+    paths: HashSet<PathBuf>,
+    file_names: HashSet<String>,
+    extensions: HashSet<String>,
+    // File stems and directory names: the modules a qualified name can start from
+    modules: HashSet<String>,
+    elements: HashSet<&'a str>,
+}
+
+impl<'a> KnownNames<'a> {
+    fn from_matrix(matrix: &'a ProjectMatrix) -> Self {
+        let mut known = Self {
+            matrix,
+            paths: HashSet::new(),
+            file_names: HashSet::new(),
+            extensions: HashSet::new(),
+            modules: HashSet::new(),
+            elements: HashSet::new(),
+        };
+        for file in matrix.files.values() {
+            let path = &file.relative_path;
+            known.paths.extend(path.ancestors().map(Path::to_path_buf));
+            for component in path.components() {
+                if let Component::Normal(name) = component {
+                    let name = Path::new(name);
+                    if let Some(stem) = name.file_stem() {
+                        known.modules.insert(stem.to_string_lossy().to_string());
+                    }
+                }
+            }
+            if let Some(name) = path.file_name() {
+                known.file_names.insert(name.to_string_lossy().to_string());
+            }
+            if let Some(extension) = path.extension() {
+                known
+                    .extensions
+                    .insert(extension.to_string_lossy().to_string());
+            }
+            known
+                .elements
+                .extend(file.elements.iter().map(|e| e.name.as_str()));
+        }
+        known
+    }
+
+    /// Whether a code span refers to a file or an element, or neither
+    fn classify(&self, span: &str) -> Option<ReferenceKind> {
+        if span.is_empty()
+            || span.contains(char::is_whitespace)
+            || span.contains(NOT_A_REFERENCE)
+            || span.contains("://")
+            || span.starts_with('-')
+        {
+            return None;
+        }
+        let path = span.trim_start_matches("./");
+        if path.contains('/') {
+            let first = path.split('/').next().unwrap_or_default();
+            let has_extension = Path::new(path).extension().is_some();
+            let names_dir = path.ends_with('/') || self.paths.contains(Path::new(first));
+            return (has_extension || names_dir).then_some(ReferenceKind::File);
+        }
+        if Path::new(path)
+            .extension()
+            .is_some_and(|ext| self.extensions.contains(ext.to_string_lossy().as_ref()))
+        {
+            return Some(ReferenceKind::File);
+        }
+
+        let segments = symbol_segments(span)?;
+        let root = segments[0];
+        // `config.yaml` isn't a symbol; `Config.load` and `config.load()` are
+        let in_project = if span.contains("::") || span.ends_with(')') {
+            root == "crate" || self.modules.contains(root) || self.elements.contains(root)
+        } else {
+            self.elements.contains(root)
+        };
+        (segments.len() > 1 && in_project).then_some(ReferenceKind::Symbol)
+    }
+
+    /// A path relative to the document, or failing that to the project root; bare file
+    /// names also match any file in the matrix with that name
+    fn path_exists(&self, root: &Path, doc_dir: &Path, reference: &str) -> bool {
+        let reference = reference.trim_end_matches('/');
+        if !reference.contains('/') && self.file_names.contains(reference) {
+            return true;
+        }
+        let candidates = match reference.strip_prefix('/') {
+            Some(absolute) => vec![normalize(Path::new(absolute))],
+            None => vec![
+                normalize(&doc_dir.join(reference)),
+                normalize(Path::new(reference)),
+            ],
+        };
+        candidates
+            .iter()
+            .any(|path| self.paths.contains(path) || root.join(path).exists())
+    }
+
+    /// `Type::method` needs that method on that type; `module::name` only needs `name`
+    /// to be an element or module somewhere
+    fn symbol_exists(&self, span: &str) -> bool {
+        let Some(segments) = symbol_segments(span) else {
+            return true;
+        };
+        let (owner, name) = (segments[segments.len() - 2], segments[segments.len() - 1]);
+        if self.elements.contains(owner) && !self.modules.contains(owner) {
+            return !self
+                .matrix
+                .find_elements(&format!("{owner}::{name}"))
+                .is_empty();
+        }
+        self.elements.contains(name) || self.modules.contains(name)
+    }
+}
+
+// `Config::load()` -> ["Config", "load"]; `None` unless every segment is an identifier
+fn symbol_segments(span: &str) -> Option<Vec<&str>> {
+    let name = match span.find('(') {
+        Some(open) if span.ends_with(')') => &span[..open],
+        Some(_) => return None,
+        None => span,
+    };
+    let segments: Vec<&str> = name.split("::").flat_map(|part| part.split('.')).collect();
+    let identifier = |s: &&str| {
+        s.chars()
+            .next()
+            .is_some_and(|c| c.is_alphabetic() || c == '_')
+            && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+    };
+    segments.iter().all(identifier).then_some(segments)
+}
+
+// Targets of inline links and reference definitions that point inside the project
+fn link_targets(line: &str) -> Vec<String> {
+    let mut targets = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("](") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find(')') else {
+            break;
+        };
+        targets.push(rest[..end].to_string());
+        rest = &rest[end + 1..];
+    }
+    // [label]: target "title"
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('[') {
+        if let Some((_, target)) = trimmed.split_once("]: ") {
+            targets.push(target.to_string());
+        }
+    }
+
+    targets
+        .into_iter()
+        .filter_map(|target| {
+            // Drop a title and an anchor
+            let target = target.split_whitespace().next()?.trim_matches(['<', '>']);
+            let target = target.split('#').next()?;
+            let external = target.contains("://") || target.starts_with("mailto:");
+            (!target.is_empty() && !external).then(|| target.to_string())
+        })
+        .collect()
+}
+
+// Resolve `.` and `..` without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
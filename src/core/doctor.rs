@@ -0,0 +1,435 @@
+// src/core/doctor.rs - Environment diagnosis for `csd doctor`
+use anyhow::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::plugins::conformance::CheckStatus;
+use crate::plugins::manager::PluginManager;
+use crate::utils::config::{Config, PluginRuntime, PluginSource};
+
+/// Oldest Python the plugin SDK supports
+pub const MIN_PYTHON: (u32, u32) = (3, 8);
+
+// Free space below which scans and docs are likely to fail, or worth a warning
+const MIN_FREE_BYTES: u64 = 100 * 1024 * 1024;
+const LOW_FREE_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    // What to do about a warning or failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Warn,
+            hint: Some(hint.into()),
+            ..Self::pass(name, detail)
+        }
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Fail,
+            ..Self::warn(name, detail, hint)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.count(CheckStatus::Fail) == 0
+    }
+
+    pub fn print_summary(&self) {
+        println!("🩺 csd doctor");
+        for check in &self.checks {
+            let icon = match check.status {
+                CheckStatus::Pass => "✅",
+                CheckStatus::Warn => "⚠️ ",
+                CheckStatus::Fail => "❌",
+            };
+            println!("   {icon} {}: {}", check.name, check.detail);
+            if let Some(hint) = &check.hint {
+                println!("        → {hint}");
+            }
+        }
+        println!(
+            "\n   {} passed, {} warnings, {} failed",
+            self.count(CheckStatus::Pass),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail)
+        );
+    }
+}
+
+/// Checks that the environment can run a scan and generate docs. A configuration
+/// that fails to load is reported, and the other checks run against the defaults.
+pub struct Doctor {
+    config: Config,
+    config_error: Option<String>,
+    project_root: PathBuf,
+}
+
+impl Doctor {
+    pub fn new(config: Result<Config>, project_root: impl AsRef<Path>) -> Self {
+        let (config, config_error) = match config {
+            Ok(config) => (config, None),
+            Err(e) => (Config::default(), Some(format!("{e:#}"))),
+        };
+        Self {
+            config,
+            config_error,
+            project_root: project_root.as_ref().to_path_buf(),
+        }
+    }
+
+    pub async fn run(&self) -> DoctorReport {
+        let mut checks = vec![self.check_config(), self.check_python()];
+        checks.extend(self.check_runner(PluginRuntime::Node));
+        checks.extend(self.check_runner(PluginRuntime::Wasm));
+        checks.extend(self.check_plugins().await);
+        checks.push(self.check_cache_dir().await);
+        checks.push(self.check_git());
+        checks.push(self.check_llm().await);
+        checks.push(self.check_disk_space());
+        DoctorReport { checks }
+    }
+
+    fn check_config(&self) -> DoctorCheck {
+        match &self.config_error {
+            None => DoctorCheck::pass("config", "loaded"),
+            Some(error) => DoctorCheck::fail(
+                "config",
+                error.clone(),
+                "Fix the file, or run `csd config migrate` if it predates input_plugins",
+            ),
+        }
+    }
+
+    fn check_python(&self) -> DoctorCheck {
+        let candidates = match &self.config.python_executable {
+            Some(executable) => vec![executable.as_str()],
+            None => vec!["python", "python3"],
+        };
+        let hint = format!(
+            "Install Python {}.{}+ or set python_executable to its path",
+            MIN_PYTHON.0, MIN_PYTHON.1
+        );
+
+        for executable in &candidates {
+            let Ok(output) = Command::new(executable).arg("--version").output() else {
+                continue;
+            };
+            // Python 2 printed its version to stderr
+            let text = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            return match parse_python_version(&text) {
+                Some((major, minor, patch)) if (major, minor) >= MIN_PYTHON => {
+                    DoctorCheck::pass("python", format!("{executable} {major}.{minor}.{patch}"))
+                }
+                Some((major, minor, patch)) => DoctorCheck::fail(
+                    "python",
+                    format!("{executable} {major}.{minor}.{patch} is too old"),
+                    hint,
+                ),
+                None => DoctorCheck::fail(
+                    "python",
+                    format!("{executable} did not report a version"),
+                    hint,
+                ),
+            };
+        }
+        DoctorCheck::fail(
+            "python",
+            format!("not found (tried {})", candidates.join(", ")),
+            hint,
+        )
+    }
+
+    // Node or the WASI runner, only when an enabled plugin runs under it
+    fn check_runner(&self, wanted: PluginRuntime) -> Option<DoctorCheck> {
+        let config = &self.config;
+        let runs = |enabled: bool, runtime: PluginRuntime| enabled && runtime == wanted;
+        let used = config
+            .input_plugins
+            .values()
+            .any(|p| runs(p.enabled, p.runtime))
+            || config
+                .output_plugins
+                .values()
+                .any(|p| runs(p.enabled, p.runtime))
+            || config
+                .transform_plugins
+                .values()
+                .any(|p| runs(p.enabled, p.runtime))
+            || config
+                .rule_plugins
+                .values()
+                .any(|p| runs(p.enabled, p.runtime));
+        if !used {
+            return None;
+        }
+
+        let (name, executable, hint) = match wanted {
+            PluginRuntime::Node => (
+                "node",
+                config.node_executable.as_deref().unwrap_or("node"),
+                "Install Node.js or set node_executable to its path",
+            ),
+            PluginRuntime::Wasm => (
+                "wasm",
+                config.wasm_executable.as_deref().unwrap_or("wasmtime"),
+                "Install wasmtime or set wasm_executable to a WASI runner",
+            ),
+            PluginRuntime::Python | PluginRuntime::Binary => return None,
+        };
+        Some(match Command::new(executable).arg("--version").output() {
+            Ok(output) if output.status.success() => DoctorCheck::pass(
+                name,
+                format!(
+                    "{executable} {}",
+                    String::from_utf8_lossy(&output.stdout).trim()
+                ),
+            ),
+            _ => DoctorCheck::fail(name, format!("{executable} not found"), hint),
+        })
+    }
+
+    async fn check_plugins(&self) -> Vec<DoctorCheck> {
+        let mut plugins = match PluginManager::new(self.config.clone())
+            .discover_plugins()
+            .await
+        {
+            Ok(plugins) => plugins,
+            Err(e) => {
+                return vec![DoctorCheck::fail(
+                    "plugins",
+                    format!("{e:#}"),
+                    "Check the plugin sources in the configuration",
+                )]
+            }
+        };
+        if plugins.is_empty() {
+            return vec![DoctorCheck::warn(
+                "plugins",
+                "none enabled",
+                "Enable input_plugins so files are analyzed, not just listed",
+            )];
+        }
+
+        plugins.sort_by(|a, b| (&a.plugin_type, &a.name).cmp(&(&b.plugin_type, &b.name)));
+        plugins
+            .iter()
+            .map(|plugin| {
+                let name = format!("{} plugin {}", plugin.plugin_type, plugin.name);
+                let path = self.resolve(&plugin.path);
+                if path.is_file() {
+                    DoctorCheck::pass(name, plugin.path.display().to_string())
+                } else {
+                    DoctorCheck::fail(
+                        name,
+                        format!("{} not found", plugin.path.display()),
+                        format!(
+                            "Fix {}_plugins.{}.source or disable the plugin",
+                            plugin.plugin_type, plugin.name
+                        ),
+                    )
+                }
+            })
+            .collect()
+    }
+
+    async fn check_cache_dir(&self) -> DoctorCheck {
+        let cache_dir = self.config.resolve_cache_dir(&self.project_root);
+        let probe = cache_dir.join(format!(".doctor-{}", uuid::Uuid::new_v4()));
+        let writable = async {
+            tokio::fs::create_dir_all(&cache_dir).await?;
+            tokio::fs::write(&probe, b"ok").await?;
+            tokio::fs::remove_file(&probe).await
+        };
+        match writable.await {
+            Ok(()) => DoctorCheck::pass("cache", format!("{} is writable", cache_dir.display())),
+            Err(e) => DoctorCheck::fail(
+                "cache",
+                format!("{} is not writable: {e}", cache_dir.display()),
+                "Fix its permissions or point cache_dir elsewhere (a path or \"xdg\")",
+            ),
+        }
+    }
+
+    fn check_git(&self) -> DoctorCheck {
+        let remote_plugins: Vec<&String> = self
+            .config
+            .input_plugins
+            .iter()
+            .map(|(name, plugin)| (name, &plugin.source))
+            .chain(
+                self.config
+                    .output_plugins
+                    .iter()
+                    .map(|(name, plugin)| (name, &plugin.source)),
+            )
+            .filter(|(_, source)| {
+                matches!(
+                    source,
+                    PluginSource::Git { .. } | PluginSource::GitHub { .. }
+                )
+            })
+            .map(|(name, _)| name)
+            .collect();
+
+        match Command::new("git").arg("--version").output() {
+            Ok(output) if output.status.success() => DoctorCheck::pass(
+                "git",
+                String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            ),
+            _ if remote_plugins.is_empty() => DoctorCheck::warn(
+                "git",
+                "not found",
+                "Install git to use plugins from git or GitHub sources",
+            ),
+            _ => DoctorCheck::fail(
+                "git",
+                format!(
+                    "not found, but needed for plugins {}",
+                    remote_plugins
+                        .iter()
+                        .map(|n| n.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                "Install git",
+            ),
+        }
+    }
+
+    async fn check_llm(&self) -> DoctorCheck {
+        let llm = &self.config.llm;
+        if self.config.offline {
+            return DoctorCheck::pass("llm", "skipped in offline mode");
+        }
+
+        let timeout = Duration::from_secs(llm.timeout_seconds.clamp(1, 5));
+        let response = match reqwest::Client::builder().timeout(timeout).build() {
+            Ok(client) => client.get(&llm.base_url).send().await,
+            Err(e) => Err(e),
+        };
+        match response {
+            Ok(response) => DoctorCheck::pass(
+                "llm",
+                format!(
+                    "{} at {} answered ({})",
+                    llm.provider,
+                    llm.base_url,
+                    response.status()
+                ),
+            ),
+            Err(e) => DoctorCheck::warn(
+                "llm",
+                format!("{} at {} unreachable: {e}", llm.provider, llm.base_url),
+                "Start the server or change llm.base_url; docs are generated without LLM \
+                 enhancement until then",
+            ),
+        }
+    }
+
+    fn check_disk_space(&self) -> DoctorCheck {
+        let cache_dir = self.config.resolve_cache_dir(&self.project_root);
+        // The cache directory may not exist yet; measure the filesystem it would be on
+        let target = cache_dir
+            .ancestors()
+            .find(|dir| dir.exists())
+            .unwrap_or(Path::new("."));
+        let available = Command::new("df")
+            .arg("-Pk")
+            .arg(target)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| parse_df_available(&String::from_utf8_lossy(&output.stdout)));
+
+        let Some(bytes) = available else {
+            return DoctorCheck::warn(
+                "disk space",
+                format!("could not determine free space for {}", target.display()),
+                "Make sure the cache directory's disk isn't nearly full",
+            );
+        };
+        let detail = format!(
+            "{} MiB free for {}",
+            bytes / (1024 * 1024),
+            target.display()
+        );
+        let hint = "Free up space or point cache_dir at a larger disk";
+        if bytes < MIN_FREE_BYTES {
+            DoctorCheck::fail("disk space", detail, hint)
+        } else if bytes < LOW_FREE_BYTES {
+            DoctorCheck::warn("disk space", detail, hint)
+        } else {
+            DoctorCheck::pass("disk space", detail)
+        }
+    }
+
+    // Plugin paths in the configuration are relative to the project root
+    fn resolve(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.project_root.join(path)
+        }
+    }
+}
+
+/// (major, minor, patch) from `python --version` output such as "Python 3.11.4"
+pub fn parse_python_version(output: &str) -> Option<(u32, u32, u32)> {
+    let version = output.split_whitespace().nth(1)?;
+    let mut parts = version
+        .split('.')
+        .map(|part| part.trim_end_matches(|c: char| !c.is_ascii_digit()).parse());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next()?.ok()?;
+    let patch = parts.next().and_then(|p| p.ok()).unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+/// Available bytes from `df -Pk` output: a header, then one line whose fourth column
+/// is the free space in KiB
+pub fn parse_df_available(output: &str) -> Option<u64> {
+    let kib: u64 = output
+        .lines()
+        .nth(1)?
+        .split_whitespace()
+        .nth(3)?
+        .parse()
+        .ok()?;
+    Some(kib * 1024)
+}
@@ -0,0 +1,121 @@
+// src/core/documentation.rs - Documentation density of the source, and the modules without any
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::core::categories::{FileCategory, FileClassifier};
+use crate::core::comments::syntax_for;
+use crate::core::matrix::{FileNode, ProjectMatrix};
+
+/// A source file with neither doc comments nor documented elements
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UndocumentedModule {
+    pub path: PathBuf,
+    pub tokens: u64,
+    pub elements: usize,
+}
+
+/// Share of source tokens spent on documentation and comments. The counts cover every
+/// source file; `undocumented` keeps the largest `top`, which are where generated docs
+/// help most.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocumentationReport {
+    pub source_files: usize,
+    pub documented_files: usize,
+    pub total_tokens: u64,
+    pub documentation_tokens: u64,
+    pub comment_tokens: u64,
+    pub undocumented_files: usize,
+    pub undocumented: Vec<UndocumentedModule>,
+}
+
+impl DocumentationReport {
+    pub fn from_matrix(matrix: &ProjectMatrix, top: usize) -> Self {
+        let classifier = FileClassifier::default();
+        let mut report = Self::default();
+        let mut undocumented = Vec::new();
+
+        for file in matrix.files.values() {
+            let category = file
+                .category
+                .unwrap_or_else(|| classifier.classify(&file.relative_path));
+            if category != FileCategory::Source || !is_measurable(file) {
+                continue;
+            }
+
+            report.source_files += 1;
+            report.total_tokens += file.token_info.total_tokens;
+            report.documentation_tokens += file.token_info.documentation_tokens;
+            report.comment_tokens += file.token_info.comment_tokens;
+            if is_documented(file) {
+                report.documented_files += 1;
+            } else {
+                undocumented.push(UndocumentedModule {
+                    path: file.relative_path.clone(),
+                    tokens: file.token_info.total_tokens,
+                    elements: file.elements.len(),
+                });
+            }
+        }
+
+        undocumented.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.path.cmp(&b.path)));
+        report.undocumented_files = undocumented.len();
+        report.undocumented = undocumented.into_iter().take(top).collect();
+        report
+    }
+
+    /// Documentation tokens per source token
+    pub fn documentation_density(&self) -> f64 {
+        ratio(self.documentation_tokens, self.total_tokens)
+    }
+
+    /// Plain comment tokens per source token
+    pub fn comment_density(&self) -> f64 {
+        ratio(self.comment_tokens, self.total_tokens)
+    }
+
+    pub fn print_summary(&self) {
+        println!("\n📝 Documentation:");
+        if self.source_files == 0 {
+            println!("   No source files to measure");
+            return;
+        }
+
+        println!(
+            "   Density: {:.1}% documentation, {:.1}% comments",
+            self.documentation_density() * 100.0,
+            self.comment_density() * 100.0
+        );
+        println!(
+            "   Documented modules: {} of {}",
+            self.documented_files, self.source_files
+        );
+        if self.undocumented_files > 0 {
+            println!("   Undocumented modules: {}", self.undocumented_files);
+            for module in &self.undocumented {
+                println!("     {:>8}  {}", module.tokens, module.path.display());
+            }
+        }
+    }
+}
+
+// Files we can tell documentation apart in: analyzed by a plugin, or in a language
+// whose comment syntax the core knows
+fn is_measurable(file: &FileNode) -> bool {
+    file.is_text
+        && file.token_info.total_tokens > 0
+        && (file.plugin != "unknown" || syntax_for(&file.relative_path).is_some())
+}
+
+fn is_documented(file: &FileNode) -> bool {
+    file.token_info.documentation_tokens > 0
+        || file.file_summary.is_some()
+        || file.elements.iter().any(|e| e.summary.is_some())
+}
+
+fn ratio(part: u64, whole: u64) -> f64 {
+    if whole > 0 {
+        part as f64 / whole as f64
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,274 @@
+// src/core/dsm.rs - Design structure matrix: the dependency matrix ordered to expose
+// layering and cycles
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::core::adjacency::{csv_field, AdjacencyMatrix};
+use crate::core::graph_analysis::topological_layers;
+
+/// Adjacency matrix reordered so providers come first. Row `i` depending on column `j`
+/// is a mark at `weights[i][j]`; in a cleanly layered project every mark sits below the
+/// diagonal, and marks above it belong to dependency cycles.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Dsm {
+    pub labels: Vec<String>,
+    pub weights: Vec<Vec<f32>>,
+    // Dependency layer of each row: 0 depends on nothing, N only on lower layers
+    pub layers: Vec<usize>,
+    // Cycle each row belongs to, numbered in matrix order; rows of a cycle are adjacent
+    pub cycles: Vec<Option<usize>>,
+}
+
+impl Dsm {
+    /// Order rows by layer, keeping each cycle together and, within a layer, sorting by
+    /// path so files from the same directory cluster
+    pub fn from_adjacency(adjacency: &AdjacencyMatrix) -> Self {
+        let n = adjacency.labels.len();
+        let mut graph = petgraph::Graph::<(), f32>::with_capacity(n, 0);
+        let nodes: Vec<_> = (0..n).map(|_| graph.add_node(())).collect();
+        for (from, row) in adjacency.weights.iter().enumerate() {
+            for (to, &weight) in row.iter().enumerate() {
+                // Self-dependencies (e.g. within a directory) aren't cycles between rows
+                if weight > 0.0 && from != to {
+                    graph.add_edge(nodes[from], nodes[to], weight);
+                }
+            }
+        }
+
+        let layering = topological_layers(&graph);
+        let mut cycle_of: BTreeMap<usize, usize> = BTreeMap::new();
+        for (cycle, members) in layering.cycles.iter().enumerate() {
+            for member in members {
+                cycle_of.insert(member.index(), cycle);
+            }
+        }
+
+        let mut order: Vec<(usize, usize)> = Vec::with_capacity(n);
+        for (layer, members) in layering.layers.iter().enumerate() {
+            // A cycle sorts by its first path, so it lands where that file would
+            let mut groups: BTreeMap<(String, Option<usize>), Vec<usize>> = BTreeMap::new();
+            let mut by_cycle: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+            for member in members.iter().map(|m| m.index()) {
+                match cycle_of.get(&member) {
+                    Some(&cycle) => by_cycle.entry(cycle).or_default().push(member),
+                    None => {
+                        groups.insert((adjacency.labels[member].clone(), None), vec![member]);
+                    }
+                }
+            }
+            for (cycle, mut members) in by_cycle {
+                members.sort_by(|&a, &b| adjacency.labels[a].cmp(&adjacency.labels[b]));
+                groups.insert((adjacency.labels[members[0]].clone(), Some(cycle)), members);
+            }
+            order.extend(groups.into_values().flatten().map(|member| (member, layer)));
+        }
+
+        // Renumber cycles in the order they appear
+        let mut renumbered: BTreeMap<usize, usize> = BTreeMap::new();
+        let cycles = order
+            .iter()
+            .map(|(member, _)| {
+                cycle_of.get(member).map(|&cycle| {
+                    let next = renumbered.len();
+                    *renumbered.entry(cycle).or_insert(next)
+                })
+            })
+            .collect();
+
+        Self {
+            labels: order
+                .iter()
+                .map(|&(member, _)| adjacency.labels[member].clone())
+                .collect(),
+            weights: order
+                .iter()
+                .map(|&(from, _)| {
+                    order
+                        .iter()
+                        .map(|&(to, _)| adjacency.weights[from][to])
+                        .collect()
+                })
+                .collect(),
+            layers: order.iter().map(|&(_, layer)| layer).collect(),
+            cycles,
+        }
+    }
+
+    /// Marks above the diagonal, i.e. dependencies on something later in the order
+    pub fn upward_dependencies(&self) -> usize {
+        self.weights
+            .iter()
+            .enumerate()
+            .map(|(row, weights)| weights[row + 1..].iter().filter(|&&w| w > 0.0).count())
+            .sum()
+    }
+
+    /// The ordered matrix as CSV: `source`, `layer` and `cycle` columns, then one column
+    /// per target in the same order as the rows
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("source,layer,cycle");
+        for label in &self.labels {
+            csv.push(',');
+            csv.push_str(&csv_field(label));
+        }
+        csv.push('\n');
+
+        for (row, label) in self.labels.iter().enumerate() {
+            csv.push_str(&csv_field(label));
+            csv.push_str(&format!(",{},", self.layers[row]));
+            if let Some(cycle) = self.cycles[row] {
+                csv.push_str(&cycle.to_string());
+            }
+            for weight in &self.weights[row] {
+                csv.push(',');
+                csv.push_str(&weight.to_string());
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Self-contained HTML page showing one or more DSMs (e.g. by file and by directory),
+/// switchable with buttons. Hovering a cell highlights its row and column.
+pub fn render_dsm_html(title: &str, views: &[(&str, &Dsm)]) -> anyhow::Result<String> {
+    let data: BTreeMap<&str, &Dsm> = views.iter().copied().collect();
+    let names: Vec<&str> = views.iter().map(|(name, _)| *name).collect();
+
+    Ok(DSM_TEMPLATE
+        .replace("{{views}}", &script_json(&names)?)
+        .replace("{{data}}", &script_json(&data)?)
+        .replace("{{title}}", &html_escape(title)))
+}
+
+/// JSON safe to embed in a script element, which a literal `</` would close early
+fn script_json<T: Serialize>(value: &T) -> serde_json::Result<String> {
+    Ok(serde_json::to_string(value)?.replace("</", "<\\/"))
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const DSM_TEMPLATE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{{title}} - Design Structure Matrix</title>
+<style>
+  body { font-family: system-ui, sans-serif; margin: 1.5em; color: #222; }
+  h1 { font-size: 1.3em; margin-bottom: 0.2em; }
+  .controls { margin: 0.8em 0; display: flex; gap: 0.5em; align-items: center; }
+  .controls button.active { font-weight: bold; }
+  .summary { color: #555; margin-bottom: 0.8em; }
+  table { border-collapse: collapse; font-size: 11px; }
+  th.row { text-align: right; padding-right: 6px; white-space: nowrap; font-weight: normal; }
+  th.col { width: 16px; font-weight: normal; color: #777; }
+  td { width: 16px; height: 16px; border: 1px solid #eee; text-align: center; padding: 0; }
+  td.diag { background: #bbb; }
+  td.cycle { background: #fde2e2; }
+  td.mark { background: #4a78c2; color: #fff; }
+  td.up { background: #d33; color: #fff; }
+  tr.layer-start td, tr.layer-start th { border-top: 2px solid #999; }
+  .hl { outline: 1px solid #f90; }
+  th.match { background: #ffef9f; }
+  .legend span { display: inline-block; width: 12px; height: 12px; margin: 0 4px 0 12px; vertical-align: middle; }
+</style>
+</head>
+<body>
+<h1>{{title}}</h1>
+<div class="summary" id="summary"></div>
+<div class="controls">
+  <span id="views"></span>
+  <input id="filter" placeholder="Highlight paths containing...">
+</div>
+<div class="legend">
+  <span style="background:#4a78c2"></span>depends on (below diagonal)
+  <span style="background:#d33"></span>upward dependency
+  <span style="background:#fde2e2"></span>dependency cycle
+</div>
+<div id="matrix"></div>
+<script id="dsm-views" type="application/json">{{views}}</script>
+<script id="dsm-data" type="application/json">{{data}}</script>
+<script>
+const views = JSON.parse(document.getElementById("dsm-views").textContent);
+const data = JSON.parse(document.getElementById("dsm-data").textContent);
+
+function render(name) {
+  const dsm = data[name];
+  const n = dsm.labels.length;
+  let up = 0, cycles = new Set();
+  const table = document.createElement("table");
+  const head = table.insertRow();
+  head.appendChild(document.createElement("th"));
+  for (let j = 0; j < n; j++) {
+    const th = document.createElement("th");
+    th.className = "col";
+    th.textContent = j + 1;
+    th.title = dsm.labels[j];
+    head.appendChild(th);
+  }
+  for (let i = 0; i < n; i++) {
+    const row = table.insertRow();
+    if (i > 0 && dsm.layers[i] !== dsm.layers[i - 1]) row.className = "layer-start";
+    const th = document.createElement("th");
+    th.className = "row";
+    th.textContent = `${dsm.labels[i]}  ${i + 1}`;
+    th.title = `layer ${dsm.layers[i]}` + (dsm.cycles[i] === null ? "" : `, cycle ${dsm.cycles[i] + 1}`);
+    row.appendChild(th);
+    if (dsm.cycles[i] !== null) cycles.add(dsm.cycles[i]);
+    for (let j = 0; j < n; j++) {
+      const td = row.insertCell();
+      const weight = dsm.weights[i][j];
+      const sameCycle = dsm.cycles[i] !== null && dsm.cycles[i] === dsm.cycles[j];
+      if (i === j) td.className = "diag";
+      else if (weight > 0) { td.className = j > i ? "up" : "mark"; if (j > i) up++; }
+      else if (sameCycle) td.className = "cycle";
+      if (weight > 0) {
+        td.textContent = weight >= 10 ? "+" : Math.round(weight);
+        td.title = `${dsm.labels[i]} -> ${dsm.labels[j]} (${weight})`;
+      }
+      td.dataset.row = i;
+      td.dataset.col = j;
+    }
+  }
+  table.addEventListener("mouseover", event => {
+    const cell = event.target.closest("td");
+    table.querySelectorAll(".hl").forEach(el => el.classList.remove("hl"));
+    if (!cell) return;
+    table.rows[+cell.dataset.row + 1].cells[0].classList.add("hl");
+    table.rows[0].cells[+cell.dataset.col + 1].classList.add("hl");
+  });
+  const layers = n ? Math.max(...dsm.layers) + 1 : 0;
+  document.getElementById("summary").textContent =
+    `${n} ${name} in ${layers} layers, ${cycles.size} cycles, ${up} upward dependencies`;
+  const container = document.getElementById("matrix");
+  container.replaceChildren(table);
+  document.querySelectorAll("#views button").forEach(b => b.classList.toggle("active", b.dataset.view === name));
+  highlight();
+}
+
+function highlight() {
+  const needle = document.getElementById("filter").value.toLowerCase();
+  document.querySelectorAll("th.row").forEach(th => {
+    th.classList.toggle("match", needle !== "" && th.textContent.toLowerCase().includes(needle));
+  });
+}
+
+for (const name of views) {
+  const button = document.createElement("button");
+  button.textContent = `By ${name}`;
+  button.dataset.view = name;
+  button.onclick = () => render(name);
+  document.getElementById("views").appendChild(button);
+}
+document.getElementById("filter").addEventListener("input", highlight);
+render(views[0]);
+</script>
+</body>
+</html>
+"##;
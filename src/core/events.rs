@@ -0,0 +1,358 @@
+// src/core/events.rs - Architectural changes between two scans (cycles, dependencies,
+// entrypoints) and the append-only log the daemon keeps of them
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+use crate::core::graph_analysis::topological_layers;
+use crate::core::matrix::ProjectMatrix;
+use crate::utils::cache::CacheLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventSeverity {
+    Info,
+    // Worth alerting on: failure-only webhooks receive these
+    Warning,
+}
+
+/// One significant difference between the previous scan and the latest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MatrixChange {
+    // Files of the cycle, project-relative and sorted
+    CycleIntroduced {
+        files: Vec<PathBuf>,
+    },
+    CycleResolved {
+        files: Vec<PathBuf>,
+    },
+    DependencyAdded {
+        ecosystem: String,
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        version: Option<String>,
+    },
+    DependencyRemoved {
+        ecosystem: String,
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        version: Option<String>,
+    },
+    DependencyVersionChanged {
+        ecosystem: String,
+        name: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    EntrypointAdded {
+        file: PathBuf,
+        kind: String,
+    },
+    EntrypointRemoved {
+        file: PathBuf,
+        kind: String,
+    },
+}
+
+impl MatrixChange {
+    pub fn severity(&self) -> EventSeverity {
+        match self {
+            MatrixChange::CycleIntroduced { .. }
+            | MatrixChange::DependencyAdded { .. }
+            | MatrixChange::EntrypointRemoved { .. } => EventSeverity::Warning,
+            _ => EventSeverity::Info,
+        }
+    }
+
+    /// One line for people, e.g. "New dependency cycle: a.rs → b.rs"
+    pub fn summary(&self) -> String {
+        let versioned = |name: &str, version: &Option<String>| match version {
+            Some(version) => format!("{name} {version}"),
+            None => name.to_string(),
+        };
+        let files = |files: &[PathBuf]| {
+            files
+                .iter()
+                .map(|f| f.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" → ")
+        };
+        match self {
+            MatrixChange::CycleIntroduced { files: f } => {
+                format!("New dependency cycle: {}", files(f))
+            }
+            MatrixChange::CycleResolved { files: f } => {
+                format!("Dependency cycle resolved: {}", files(f))
+            }
+            MatrixChange::DependencyAdded {
+                ecosystem,
+                name,
+                version,
+            } => format!("New {ecosystem} dependency {}", versioned(name, version)),
+            MatrixChange::DependencyRemoved {
+                ecosystem,
+                name,
+                version,
+            } => format!(
+                "Removed {ecosystem} dependency {}",
+                versioned(name, version)
+            ),
+            MatrixChange::DependencyVersionChanged {
+                ecosystem,
+                name,
+                from,
+                to,
+            } => format!(
+                "{ecosystem} dependency {name}: {} → {}",
+                from.as_deref().unwrap_or("unversioned"),
+                to.as_deref().unwrap_or("unversioned")
+            ),
+            MatrixChange::EntrypointAdded { file, kind } => {
+                format!("New {kind} entrypoint {}", file.display())
+            }
+            MatrixChange::EntrypointRemoved { file, kind } => {
+                format!("{kind} entrypoint {} is gone", file.display())
+            }
+        }
+    }
+}
+
+/// A change as logged and posted: when the scan that found it ran, and for which project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatrixEvent {
+    pub timestamp: DateTime<Utc>,
+    pub project: String,
+    pub severity: EventSeverity,
+    pub summary: String,
+    #[serde(flatten)]
+    pub change: MatrixChange,
+}
+
+impl MatrixEvent {
+    pub fn new(timestamp: DateTime<Utc>, project: &str, change: MatrixChange) -> Self {
+        Self {
+            timestamp,
+            project: project.to_string(),
+            severity: change.severity(),
+            summary: change.summary(),
+            change,
+        }
+    }
+}
+
+/// What changed from `previous` to `current`: cycles first, then dependencies, then
+/// entrypoints, each sorted
+pub fn detect_changes(
+    previous: &mut ProjectMatrix,
+    current: &mut ProjectMatrix,
+) -> Vec<MatrixChange> {
+    let mut changes = Vec::new();
+
+    let (before, after) = (cycles(previous), cycles(current));
+    changes.extend(
+        after
+            .difference(&before)
+            .map(|files| MatrixChange::CycleIntroduced {
+                files: files.clone(),
+            }),
+    );
+    changes.extend(
+        before
+            .difference(&after)
+            .map(|files| MatrixChange::CycleResolved {
+                files: files.clone(),
+            }),
+    );
+
+    let (before, after) = (dependencies(previous), dependencies(current));
+    for (key, version) in &after {
+        let (ecosystem, name) = key.clone();
+        match before.get(key) {
+            None => changes.push(MatrixChange::DependencyAdded {
+                ecosystem,
+                name,
+                version: version.clone(),
+            }),
+            Some(previous) if previous != version => {
+                changes.push(MatrixChange::DependencyVersionChanged {
+                    ecosystem,
+                    name,
+                    from: previous.clone(),
+                    to: version.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for (key, version) in &before {
+        if !after.contains_key(key) {
+            let (ecosystem, name) = key.clone();
+            changes.push(MatrixChange::DependencyRemoved {
+                ecosystem,
+                name,
+                version: version.clone(),
+            });
+        }
+    }
+
+    let (before, after) = (entrypoints(previous), entrypoints(current));
+    changes.extend(
+        after
+            .difference(&before)
+            .map(|(file, kind)| MatrixChange::EntrypointAdded {
+                file: file.clone(),
+                kind: kind.clone(),
+            }),
+    );
+    changes.extend(
+        before
+            .difference(&after)
+            .map(|(file, kind)| MatrixChange::EntrypointRemoved {
+                file: file.clone(),
+                kind: kind.clone(),
+            }),
+    );
+    changes
+}
+
+// Each cycle as its sorted project-relative paths, so cycles compare across scans
+fn cycles(matrix: &mut ProjectMatrix) -> BTreeSet<Vec<PathBuf>> {
+    let graph = matrix.graph();
+    topological_layers(graph)
+        .cycles
+        .into_iter()
+        .map(|cycle| {
+            let mut files: Vec<PathBuf> = cycle
+                .into_iter()
+                .map(|index| graph[index].relative_path.clone())
+                .collect();
+            files.sort();
+            files
+        })
+        .collect()
+}
+
+// Version by (ecosystem, name). A dependency declared in several manifests counts once,
+// with the highest version.
+fn dependencies(matrix: &ProjectMatrix) -> BTreeMap<(String, String), Option<String>> {
+    let mut dependencies: BTreeMap<(String, String), Option<String>> = BTreeMap::new();
+    for dependency in &matrix.external_dependencies {
+        let version = dependencies
+            .entry((dependency.ecosystem.to_string(), dependency.name.clone()))
+            .or_default();
+        if newer_version(dependency.version.as_deref(), version.as_deref()) {
+            *version = dependency.version.clone();
+        }
+    }
+    dependencies
+}
+
+// By semver when both parse, ignoring requirement operators such as `^` and `==` and
+// filling in a missing minor or patch, so 1.10 beats 1.9; by string otherwise. Any
+// version beats none.
+fn newer_version(candidate: Option<&str>, current: Option<&str>) -> bool {
+    let parse = |version: &str| {
+        let version = version.trim_start_matches(|c: char| !c.is_ascii_digit());
+        [
+            version.to_string(),
+            format!("{version}.0"),
+            format!("{version}.0.0"),
+        ]
+        .iter()
+        .find_map(|v| semver::Version::parse(v).ok())
+    };
+    match (candidate, current) {
+        (Some(candidate), Some(current)) => match (parse(candidate), parse(current)) {
+            (Some(a), Some(b)) => a > b,
+            _ => candidate > current,
+        },
+        _ => candidate > current,
+    }
+}
+
+fn entrypoints(matrix: &ProjectMatrix) -> BTreeSet<(PathBuf, String)> {
+    matrix
+        .project_info
+        .entrypoints
+        .iter()
+        .map(|entrypoint| {
+            (
+                entrypoint.file_path.clone(),
+                entrypoint.entrypoint_type.clone(),
+            )
+        })
+        .collect()
+}
+
+/// Events stored as JSON lines under `<cache_dir>/history/events.jsonl`
+pub struct EventLog {
+    path: PathBuf,
+}
+
+impl EventLog {
+    pub fn new(cache_dir: &Path) -> Self {
+        Self {
+            path: cache_dir.join("history").join("events.jsonl"),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub async fn append(&self, events: &[MatrixEvent]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let mut lines = String::new();
+        for event in events {
+            lines.push_str(&serde_json::to_string(event)?);
+            lines.push('\n');
+        }
+
+        let _lock = CacheLock::exclusive(&self.path).await?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(lines.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Events after `since` (all of them without), oldest first. Unparseable lines are
+    /// skipped as in the metrics history.
+    pub async fn load(&self, since: Option<DateTime<Utc>>) -> Result<Vec<MatrixEvent>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = {
+            let _lock = CacheLock::shared(&self.path).await.ok();
+            tokio::fs::read_to_string(&self.path).await?
+        };
+        let mut events: Vec<MatrixEvent> = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(event) => Some(event),
+                Err(e) => {
+                    log::warn!("Skipping unreadable event: {e}");
+                    None
+                }
+            })
+            .filter(|event: &MatrixEvent| since.is_none_or(|since| event.timestamp > since))
+            .collect();
+        events.sort_by_key(|event| event.timestamp);
+        Ok(events)
+    }
+}
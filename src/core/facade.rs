@@ -0,0 +1,963 @@
+// src/core/facade.rs - Library entry point for embedding csd in other Rust tools
+use anyhow::{Context, Result};
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::core::adr::{link_adrs, load_adrs};
+use crate::core::annotations::Annotations;
+use crate::core::changelog::{Changelog, CHANGELOG_FILE};
+use crate::core::contracts::ContractDrift;
+use crate::core::coverage::{
+    apply_coverage, CoverageData, CoverageFormat, CoverageImport, RiskReport,
+};
+use crate::core::debt::DebtReport;
+use crate::core::doc_drift::DocDriftReport;
+use crate::core::documentation::DocumentationReport;
+use crate::core::graph_analysis::GraphAnalysis;
+use crate::core::history::{MetricsHistory, MetricsSnapshot};
+use crate::core::hotspots::HotspotReport;
+use crate::core::interactions::{
+    merge_interactions, parse_access_log, parse_service_graph, InteractionImport, InteractionSource,
+};
+use crate::core::matrix::{ProjectMatrix, SubsetOptions};
+use crate::core::rules::{run_rules, RuleReport};
+use crate::core::scanner::ProjectScanner;
+use crate::core::suppressions::Suppressions;
+use crate::core::traces::{apply_traces, TraceData, TraceFormat, TraceImport};
+use crate::core::transform::{apply_patch, run_transforms, PatchSummary};
+use crate::core::view::MatrixView;
+use crate::output::manifest::{DocsManifest, ManifestEntry};
+use crate::plugins::communication::OutputPluginCommunicator;
+use crate::plugins::interface::{
+    plugin_script_path, GeneratedOutput, MatrixPatch, OutputPluginInput, OutputPluginInterface,
+    OutputPluginResult, OutputScope, PluginType, SectionCache,
+};
+use crate::plugins::logs::prune_plugin_logs;
+use crate::utils::cache::{prune_stale_runs, CacheRun};
+use crate::utils::config::{
+    find_config_file, find_global_config_file, Config, OutputPluginConfig, OverwritePolicy,
+    ScanLevel,
+};
+use crate::utils::git::{self, CommitsSince, GitMetadata, Worktree};
+use crate::utils::hooks::{run_hooks, HookContext, HookStage};
+
+/// Result of a full scan: the saved matrix and what each transform plugin changed
+#[derive(Debug)]
+pub struct ScanReport {
+    pub matrix: ProjectMatrix,
+    pub matrix_path: PathBuf,
+    pub transforms: Vec<(String, PatchSummary)>,
+    // Paths left out because reading them was not permitted
+    pub skipped_permission: Vec<PathBuf>,
+}
+
+/// One documentation generation pass; `language` is set when `docs.languages` is configured
+#[derive(Debug)]
+pub struct DocsPass {
+    pub language: Option<String>,
+    pub output_dir: PathBuf,
+    pub result: OutputPluginResult,
+    // What of the plugin's patch was written back to the matrix, if it returned one
+    pub write_back: Option<PatchSummary>,
+}
+
+/// Result of `generate_docs`: every pass plus the manifest recording their outputs
+#[derive(Debug)]
+pub struct DocsReport {
+    pub passes: Vec<DocsPass>,
+    pub manifest_path: PathBuf,
+}
+
+/// Result of `generate_changelog`
+#[derive(Debug)]
+pub struct ChangelogReport {
+    pub changelog: Changelog,
+    // The draft, then whatever the polishing plugin wrote
+    pub outputs: Vec<GeneratedOutput>,
+    pub manifest_path: PathBuf,
+}
+
+/// The scan → matrix → docs pipeline without the CLI. Paths default to the ones
+/// `csd init` and `csd docs` use for the project root, so a matrix written here can be
+/// read by the binary and vice versa.
+///
+/// ```no_run
+/// # async fn run() -> anyhow::Result<()> {
+/// let csd = csd::Csd::load("path/to/project").await?;
+/// let report = csd.scan().await?;
+/// println!("{} files", report.matrix.metadata.total_files);
+/// let docs = csd.generate_docs("markdown").await?;
+/// println!("manifest at {}", docs.manifest_path.display());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Csd {
+    config: Config,
+    project_root: PathBuf,
+    roots: Vec<PathBuf>,
+    matrix_path: Option<PathBuf>,
+    output_dir: Option<PathBuf>,
+    resume: bool,
+    scope: Option<OutputScope>,
+    view: MatrixView,
+    // Set by `generate_document`; plugins are asked for one self-contained output
+    single_document: bool,
+}
+
+impl Csd {
+    /// Use an already-built configuration for the current directory
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            project_root: PathBuf::from("."),
+            roots: Vec::new(),
+            matrix_path: None,
+            output_dir: None,
+            resume: false,
+            scope: None,
+            view: MatrixView::default(),
+            single_document: false,
+        }
+    }
+
+    /// Load the user and project configuration layers the same way the CLI does
+    pub async fn load<P: AsRef<Path>>(project_root: P) -> Result<Self> {
+        let project_root = project_root.as_ref();
+        let config = Config::load_layered(
+            find_global_config_file().as_deref(),
+            find_config_file(project_root).as_deref(),
+        )
+        .await?;
+        Ok(Self::new(config).with_root(project_root))
+    }
+
+    pub fn with_root<P: AsRef<Path>>(mut self, project_root: P) -> Self {
+        self.project_root = project_root.as_ref().to_path_buf();
+        self
+    }
+
+    /// Scan these directories into one matrix instead of the whole project root
+    pub fn with_roots(mut self, roots: Vec<PathBuf>) -> Self {
+        self.roots = roots;
+        self
+    }
+
+    /// Read and write the matrix somewhere other than the cache directory
+    pub fn with_matrix_path<P: AsRef<Path>>(mut self, matrix_path: P) -> Self {
+        self.matrix_path = Some(matrix_path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Write documentation somewhere other than the configured `output_dir`
+    pub fn with_output_dir<P: AsRef<Path>>(mut self, output_dir: P) -> Self {
+        self.output_dir = Some(output_dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Reuse sections an interrupted `generate_docs` already finished
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Generate documentation for part of the project only. Output plugins are handed
+    /// a matrix cut down to the files in scope and their dependencies.
+    pub fn with_scope(mut self, scope: OutputScope) -> Self {
+        self.scope = Some(scope).filter(|scope| !scope.is_empty());
+        self
+    }
+
+    /// Report on, and document, only the files carrying (or not carrying) the view's
+    /// tags. Commands that write the matrix back still load all of it.
+    pub fn with_view(mut self, view: MatrixView) -> Self {
+        self.view = view;
+        self
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn project_root(&self) -> &Path {
+        &self.project_root
+    }
+
+    pub fn cache_dir(&self) -> PathBuf {
+        self.config.resolve_cache_dir(&self.project_root)
+    }
+
+    pub fn matrix_path(&self) -> PathBuf {
+        self.matrix_path
+            .clone()
+            .unwrap_or_else(|| self.cache_dir().join("matrix.json"))
+    }
+
+    pub fn output_dir(&self) -> PathBuf {
+        self.output_dir
+            .clone()
+            .unwrap_or_else(|| self.project_root.join(&self.config.output_dir))
+    }
+
+    fn hook_context(&self, output_dir: Option<PathBuf>) -> HookContext {
+        HookContext {
+            project_root: self.project_root.clone(),
+            cache_dir: self.cache_dir(),
+            matrix_path: Some(self.matrix_path()),
+            output_dir,
+        }
+    }
+
+    /// Scan the project, save the matrix, run transform plugins over it and record the
+    /// scan in the metrics history. Scan hooks run before and after.
+    pub async fn scan(&self) -> Result<ScanReport> {
+        self.config.ensure_offline_ready()?;
+        let matrix_path = self.matrix_path();
+        let hook_context = self.hook_context(None);
+        run_hooks(&self.config, HookStage::PreScan, &hook_context).await?;
+
+        let scanner = ProjectScanner::new(self.config.clone())
+            .with_root(&self.project_root)
+            .with_roots(self.roots.clone());
+        let mut matrix = scanner.scan_to_matrix().await?;
+        matrix.print_summary();
+
+        matrix.save(&matrix_path).await?;
+        info!("Matrix saved to: {}", matrix_path.display());
+
+        // Let transform plugins enrich the saved matrix before any output plugin reads it
+        let mut transforms = match self.config.scanning.level {
+            ScanLevel::Full => {
+                run_transforms(&mut matrix, &matrix_path, &self.project_root, &self.config).await?
+            }
+            ScanLevel::Structure => Vec::new(),
+        };
+        // Tag the files each ADR in docs/adr says it affects
+        let mut adrs = load_adrs(&self.project_root)?;
+        if !adrs.is_empty() {
+            let summary = link_adrs(&mut adrs, &mut matrix)?;
+            matrix.save(&matrix_path).await?;
+            transforms.push(("adr".to_string(), summary));
+        }
+        run_hooks(&self.config, HookStage::PostScan, &hook_context).await?;
+
+        // Record this scan's metrics for `csd trend`
+        let history = MetricsHistory::new(&self.cache_dir());
+        if let Err(e) = history
+            .append(&MetricsSnapshot::from_matrix(&mut matrix))
+            .await
+        {
+            warn!("Could not record scan history: {e}");
+        }
+
+        Ok(ScanReport {
+            skipped_permission: matrix.metadata.scan_stats.skipped_permission.clone(),
+            matrix,
+            matrix_path,
+            transforms,
+        })
+    }
+
+    /// Find references in the project's markdown to files and elements the last scan
+    /// no longer has
+    pub async fn check_doc_drift(&self) -> Result<DocDriftReport> {
+        let matrix = self.load_matrix().await?;
+        DocDriftReport::check(&self.project_root, &matrix)
+    }
+
+    /// Load the matrix written by the last scan
+    pub async fn load_matrix(&self) -> Result<ProjectMatrix> {
+        let matrix_path = self.matrix_path();
+        if !matrix_path.exists() {
+            return Err(anyhow::anyhow!(
+                "Matrix file not found: {}. Run 'csd init' first.",
+                matrix_path.display()
+            ));
+        }
+        ProjectMatrix::load(&matrix_path).await
+    }
+
+    /// The matrix as `with_view` scoped it, for reading only
+    pub async fn load_view(&self) -> Result<ProjectMatrix> {
+        self.view.apply(self.load_matrix().await?)
+    }
+
+    /// Graph metrics (centrality, layering, cycles) for the last scan
+    pub async fn quality(&self) -> Result<GraphAnalysis> {
+        let mut matrix = self.load_view().await?;
+        Ok(GraphAnalysis::analyze(&mut matrix))
+    }
+
+    /// TODO/FIXME markers and the issues they reference. With `verify`, issues are looked
+    /// up in the configured trackers. Files referencing issues that aren't closed are
+    /// tagged `issue:<key>` in the saved matrix.
+    pub async fn debt(&self, verify: bool) -> Result<DebtReport> {
+        let mut matrix = self.load_matrix().await?;
+        let mut report = DebtReport::harvest(&self.project_root, &matrix);
+        if verify {
+            if self.config.issue_trackers.is_empty() {
+                warn!("No issue_trackers configured; issues stay unverified");
+            }
+            report
+                .verify(&self.config.issue_trackers, self.config.offline)
+                .await?;
+        }
+        report.link(&mut matrix);
+        matrix.save(&self.matrix_path()).await?;
+        Ok(report)
+    }
+
+    /// Files and functions over the `quality` size limits in the last scan, less those
+    /// silenced by `csd-ignore` pragmas or the project's suppressions file
+    pub async fn hotspots(&self) -> Result<HotspotReport> {
+        let matrix = self.load_view().await?;
+        let suppressions = Suppressions::load(&self.project_root)?;
+        Ok(HotspotReport::with_suppressions(
+            &matrix,
+            &self.config.quality,
+            &suppressions,
+        ))
+    }
+
+    /// Findings of the enabled rule plugins on the last scan, less those silenced like
+    /// hotspots are
+    pub async fn rules(&self) -> Result<RuleReport> {
+        let matrix = self.load_view().await?;
+        let suppressions = Suppressions::load(&self.project_root)?;
+        run_rules(
+            &matrix,
+            &self.matrix_path(),
+            &self.project_root,
+            &self.config,
+            &suppressions,
+        )
+        .await
+    }
+
+    /// Map an lcov or Cobertura report onto the last scan's files and elements, replacing
+    /// earlier coverage. The format is detected from the content when not given.
+    pub async fn import_coverage(
+        &self,
+        report: &Path,
+        format: Option<CoverageFormat>,
+    ) -> Result<CoverageImport> {
+        let content = std::fs::read_to_string(report)
+            .with_context(|| format!("Failed to read coverage report {}", report.display()))?;
+        let format = format.unwrap_or_else(|| CoverageFormat::detect(&content));
+        let data = CoverageData::parse(&content, format)
+            .with_context(|| format!("Failed to parse coverage report {}", report.display()))?;
+        let mut matrix = self.load_matrix().await?;
+        let import = apply_coverage(&mut matrix, &data);
+        matrix.save(&self.matrix_path()).await?;
+        Ok(import)
+    }
+
+    /// Match the calls in a runtime trace to the last scan's elements, marking and
+    /// strengthening the edges they exercise and adding the call edges it lacks. The
+    /// format is detected from the content when not given.
+    pub async fn import_traces(
+        &self,
+        trace: &Path,
+        format: Option<TraceFormat>,
+    ) -> Result<TraceImport> {
+        let content = std::fs::read_to_string(trace)
+            .with_context(|| format!("Failed to read trace {}", trace.display()))?;
+        let format = format.unwrap_or_else(|| TraceFormat::detect(&content));
+        let data = TraceData::parse(&content, format)
+            .with_context(|| format!("Failed to parse trace {}", trace.display()))?;
+        let mut matrix = self.load_matrix().await?;
+        let import = apply_traces(&mut matrix, &data);
+        matrix.save(&self.matrix_path()).await?;
+        Ok(import)
+    }
+
+    /// Record the services this project calls, and is called by, from an OpenTelemetry
+    /// service graph or an access log, replacing what the last import from the same kind
+    /// of source recorded. `service` is this project's name in a service graph, by
+    /// default the project directory's.
+    pub async fn import_interactions(
+        &self,
+        path: &Path,
+        source: Option<InteractionSource>,
+        service: Option<&str>,
+    ) -> Result<InteractionImport> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let source = source.unwrap_or_else(|| InteractionSource::detect(&content));
+        let default_service = self
+            .project_root
+            .canonicalize()
+            .unwrap_or_else(|_| self.project_root.clone())
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (interactions, skipped) = match source {
+            InteractionSource::ServiceGraph => {
+                parse_service_graph(&content, service.unwrap_or(&default_service))
+            }
+            InteractionSource::AccessLog => parse_access_log(&content),
+        }
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        let import = InteractionImport::new(&interactions, skipped);
+        let mut matrix = self.load_matrix().await?;
+        merge_interactions(&mut matrix, source, interactions);
+        matrix.save(&self.matrix_path()).await?;
+        Ok(import)
+    }
+
+    /// Imported coverage of the last scan and its riskiest files: complex, highly coupled
+    /// and poorly covered
+    pub async fn coverage_risk(&self) -> Result<RiskReport> {
+        let matrix = self.load_view().await?;
+        Ok(RiskReport::from_matrix(&matrix, self.config.quality.top))
+    }
+
+    /// Operations of the project's API contracts that no code handles, and routes no
+    /// spec describes, as of the last scan
+    pub async fn contract_drift(&self) -> Result<ContractDrift> {
+        let matrix = self.load_view().await?;
+        Ok(ContractDrift::check(&matrix))
+    }
+
+    /// Documentation density of the last scan and its largest undocumented modules
+    pub async fn documentation(&self) -> Result<DocumentationReport> {
+        let matrix = self.load_view().await?;
+        Ok(DocumentationReport::from_matrix(
+            &matrix,
+            self.config.quality.top,
+        ))
+    }
+
+    /// Generate documentation in `format` with the first enabled output plugin that
+    /// supports it, once per configured `docs.languages` entry (into a subdirectory
+    /// named after the language) or once into the output directory. The outputs are
+    /// recorded in a manifest at the output directory root. Docs hooks run before and
+    /// after.
+    ///
+    /// Plugins cache finished sections under `<cache>/sections/<plugin>/<language>`.
+    /// The cache is cleared once every pass succeeds; with `with_resume(true)` a rerun
+    /// after a crash or timeout hands the completed sections back to the plugin.
+    ///
+    /// With `with_scope` or `with_view`, the matrix is filtered before any plugin sees it
+    /// and the subset is written to the run directory in place of the full matrix.
+    ///
+    /// A plugin may return a patch with its result (summaries it wrote, for example).
+    /// It is validated and applied like a transform's, and the matrix saved.
+    pub async fn generate_docs(&self, format: &str) -> Result<DocsReport> {
+        self.config.ensure_offline_ready()?;
+        let matrix_path = self.matrix_path();
+        if !matrix_path.exists() {
+            return Err(anyhow::anyhow!(
+                "Matrix file not found: {}. Run 'csd init' first.",
+                matrix_path.display()
+            ));
+        }
+
+        let languages = &self.config.docs.languages;
+        if let Some(invalid) = languages.iter().find(|l| !is_language_code(l)) {
+            return Err(anyhow::anyhow!(
+                "Invalid docs language '{invalid}': expected a code like 'en' or 'pt-BR'"
+            ));
+        }
+
+        let doc_plugins = self
+            .config
+            .find_output_plugins_for_type("documentation", format);
+        let Some(plugin_name) = doc_plugins.first() else {
+            return Err(anyhow::anyhow!(
+                "No documentation plugins found for format '{format}'"
+            ));
+        };
+        info!("Generating documentation using plugins: {doc_plugins:?}");
+        let plugin_config = self
+            .config
+            .get_output_plugin(plugin_name)
+            .with_context(|| format!("Output plugin '{plugin_name}' is not configured"))?;
+
+        let plugin_path = output_plugin_path(plugin_config)?;
+
+        // Handed to the plugin for its hotspots section, so it can prioritize
+        // undocumented modules, and so its sections can follow the program's structure
+        let mut matrix = self.load_view().await?;
+        if let Some(ref scope) = self.scope {
+            matrix = scoped_matrix(&matrix, scope)?;
+        }
+        let hotspots = HotspotReport::with_suppressions(
+            &matrix,
+            &self.config.quality,
+            &Suppressions::load(&self.project_root)?,
+        );
+        let documentation = DocumentationReport::from_matrix(&matrix, self.config.quality.top);
+        let traversal_order = matrix.traversal_order();
+        // Branch, tag and commit for version and provenance headers
+        let git = GitMetadata::collect(&self.project_root);
+        // Decisions and the files tagged with them, for the architecture section
+        let mut adrs = load_adrs(&self.project_root)?;
+        for adr in &mut adrs {
+            adr.files = adr.files_in(&matrix);
+        }
+        // Owners, review status and notes people attached with `csd annotate`
+        let annotations = Annotations::load(&self.project_root)?.in_matrix(&matrix);
+
+        let output_dir = self.output_dir();
+        tokio::fs::create_dir_all(&output_dir).await?;
+        prepare_output_dir(&output_dir, self.config.docs.overwrite).await?;
+
+        let cache_dir = self.cache_dir();
+        if let Err(e) = prune_plugin_logs(&cache_dir, &self.config.plugin_logs).await {
+            warn!("Failed to prune plugin logs: {e}");
+        }
+        if let Err(e) = prune_stale_runs(&cache_dir) {
+            warn!("Failed to remove stale run directories: {e}");
+        }
+        let run = CacheRun::create(&cache_dir, Uuid::new_v4())?;
+        let matrix_path = if self.scope.is_some() || !self.view.is_empty() {
+            let subset_path = run.dir().join("matrix.json");
+            matrix.save(&subset_path).await?;
+            subset_path
+        } else {
+            matrix_path
+        };
+        drop(matrix);
+        let communicator = self.output_communicator(plugin_path, plugin_config, &run);
+
+        let hook_context = self.hook_context(Some(output_dir.clone()));
+        run_hooks(&self.config, HookStage::PreDocs, &hook_context).await?;
+
+        let passes: Vec<Option<&String>> = if languages.is_empty() {
+            vec![None]
+        } else {
+            languages.iter().map(Some).collect()
+        };
+        let mut report = DocsReport {
+            passes: Vec::new(),
+            manifest_path: PathBuf::new(),
+        };
+        let mut manifest = DocsManifest::new(format);
+        let mut section_dirs = Vec::new();
+
+        for language in passes {
+            let pass_dir = match language {
+                Some(language) => output_dir.join(language),
+                None => output_dir.clone(),
+            };
+            tokio::fs::create_dir_all(&pass_dir).await?;
+
+            let mut format_options = serde_json::json!({
+                "format": format,
+                "output_type": "documentation",
+                "hotspots": hotspots,
+                "documentation": documentation,
+                "traversal_order": traversal_order
+            });
+            if let Some(ref git) = git {
+                format_options["git"] = serde_json::to_value(git)?;
+            }
+            if !adrs.is_empty() {
+                format_options["adrs"] = serde_json::to_value(&adrs)?;
+            }
+            if !annotations.is_empty() {
+                format_options["annotations"] = serde_json::to_value(&annotations)?;
+            }
+            if self.single_document {
+                format_options["single_document"] = serde_json::json!(true);
+            }
+            if let Some(language) = language {
+                info!("Generating {language} documentation");
+                format_options["language"] = serde_json::json!(language);
+            }
+
+            let section_dir = cache_dir
+                .join("sections")
+                .join(plugin_name)
+                .join(language.map_or("default", String::as_str));
+            let section_cache = prepare_section_cache(section_dir, self.resume).await?;
+            section_dirs.push(section_cache.dir.clone());
+
+            let plugin_input = OutputPluginInput {
+                matrix_path: matrix_path.clone(),
+                project_root: std::path::absolute(&self.project_root)?,
+                output_dir: pass_dir.clone(),
+                cache_dir: cache_dir.to_string_lossy().to_string(),
+                plugin_config: plugin_config
+                    .config
+                    .as_ref()
+                    .map(|v| serde_json::to_value(v).unwrap_or(serde_json::Value::Null)),
+                format_options,
+                section_cache: Some(section_cache),
+                scope: self.scope.clone(),
+            };
+            let result = communicator
+                .generate(plugin_input)
+                .await
+                .map_err(|e| anyhow::anyhow!("Documentation generation failed: {}", e))?;
+
+            manifest.entries.push(ManifestEntry {
+                language: language.cloned(),
+                output_type: "documentation".to_string(),
+                plugin: result.plugin_name.clone(),
+                plugin_version: result.plugin_version.clone(),
+                output_dir: pass_dir.clone(),
+                outputs: result.outputs.clone(),
+            });
+            report.passes.push(DocsPass {
+                language: language.cloned(),
+                output_dir: pass_dir,
+                result,
+                write_back: None,
+            });
+        }
+        self.write_back(&mut report.passes).await?;
+
+        report.manifest_path = manifest.save(&output_dir).await?;
+        for section_dir in section_dirs {
+            let _ = tokio::fs::remove_dir_all(section_dir).await;
+        }
+        run_hooks(&self.config, HookStage::PostDocs, &hook_context).await?;
+
+        Ok(report)
+    }
+
+    /// Apply the patches documentation plugins returned to the full matrix, whatever
+    /// scope or view they were shown, and save it so the work isn't lost with the run
+    async fn write_back(&self, passes: &mut [DocsPass]) -> Result<()> {
+        if passes
+            .iter()
+            .all(|pass| pass.result.patch.as_ref().is_none_or(MatrixPatch::is_empty))
+        {
+            return Ok(());
+        }
+        let mut matrix = self.load_matrix().await?;
+        let mut changed = false;
+        for pass in passes {
+            let Some(patch) = pass.result.patch.clone() else {
+                continue;
+            };
+            let summary = apply_patch(&mut matrix, patch, &pass.result.plugin_name);
+            info!(
+                "{} wrote back {} relationships, {} tags and {} summaries ({} skipped)",
+                pass.result.plugin_name,
+                summary.relationships,
+                summary.tags,
+                summary.summaries,
+                summary.skipped
+            );
+            changed |= summary.relationships + summary.tags + summary.summaries > 0;
+            pass.write_back = Some(summary);
+        }
+        if changed {
+            matrix.save(&self.matrix_path()).await?;
+        }
+        Ok(())
+    }
+
+    /// What changed between `since` and the current matrix. `since` is a matrix file,
+    /// whose scan time bounds the commits listed, or a tag or commit, whose checkout is
+    /// scanned with the same configuration (without hooks or transforms).
+    pub async fn changelog(&self, since: &str) -> Result<Changelog> {
+        let after = self.load_matrix().await?;
+        if Path::new(since).is_file() {
+            let before = ProjectMatrix::load(Path::new(since)).await?;
+            let commits = git::commits(
+                &self.project_root,
+                CommitsSince::Time(before.metadata.scan_timestamp),
+            )
+            .unwrap_or_else(|e| {
+                warn!("No commit messages for the changelog: {e}");
+                Vec::new()
+            });
+            return Changelog::build(since, &before, &after, commits);
+        }
+        if !git::resolves_to_commit(&self.project_root, since) {
+            return Err(anyhow::anyhow!(
+                "'{since}' is neither a matrix file nor a git tag or commit"
+            ));
+        }
+
+        // Outside the project, where its ignore rules (which usually cover the cache)
+        // would hide the whole checkout from the scanner
+        let checkout = std::env::temp_dir().join(format!("csd-since-{}", Uuid::new_v4()));
+        let worktree = Worktree::add(&self.project_root, &checkout, since)?;
+        info!("Scanning {since} for the changelog");
+        let before = ProjectScanner::new(self.config.clone())
+            .with_root(worktree.dir().join(git::repo_prefix(&self.project_root)))
+            .scan_to_matrix()
+            .await?;
+        let commits = git::commits(&self.project_root, CommitsSince::Rev(since))?;
+        Changelog::build(since, &before, &after, commits)
+    }
+
+    /// Write the changelog since `since` as a Markdown draft into the output directory
+    /// and record it in the docs manifest, replacing any earlier changelog entry. With
+    /// `polish`, the first output plugin for the `changelog` type is handed the draft
+    /// (as `format_options.draft`, and structured as `format_options.changelog`) to
+    /// rewrite it.
+    pub async fn generate_changelog(&self, since: &str, polish: bool) -> Result<ChangelogReport> {
+        self.config.ensure_offline_ready()?;
+        let changelog = self.changelog(since).await?;
+        let draft = changelog.to_markdown();
+
+        let output_dir = self.output_dir();
+        tokio::fs::create_dir_all(&output_dir).await?;
+        let draft_path = output_dir.join(CHANGELOG_FILE);
+        tokio::fs::write(&draft_path, &draft)
+            .await
+            .with_context(|| format!("Failed to write {}", draft_path.display()))?;
+        let mut outputs = vec![GeneratedOutput {
+            output_path: draft_path,
+            content_type: "markdown".to_string(),
+            size_bytes: draft.len() as u64,
+            checksum: format!("{:x}", Sha256::digest(draft.as_bytes())),
+            metadata: serde_json::json!({ "since": since }),
+            issues: Vec::new(),
+        }];
+        let mut plugin = ("csd".to_string(), env!("CARGO_PKG_VERSION").to_string());
+
+        if polish {
+            let plugins = self
+                .config
+                .find_output_plugins_for_type("changelog", "markdown");
+            let Some(plugin_name) = plugins.first() else {
+                return Err(anyhow::anyhow!(
+                    "No output plugin supports the 'changelog' output type; the draft is at {}",
+                    outputs[0].output_path.display()
+                ));
+            };
+            let plugin_config = self
+                .config
+                .get_output_plugin(plugin_name)
+                .with_context(|| format!("Output plugin '{plugin_name}' is not configured"))?;
+            let plugin_path = output_plugin_path(plugin_config)?;
+            let run = CacheRun::create(&self.cache_dir(), Uuid::new_v4())?;
+            let communicator = self.output_communicator(plugin_path, plugin_config, &run);
+
+            let mut format_options = serde_json::json!({
+                "format": "markdown",
+                "output_type": "changelog",
+                "changelog": changelog,
+                "draft": draft,
+            });
+            if let Some(git) = GitMetadata::collect(&self.project_root) {
+                format_options["git"] = serde_json::to_value(git)?;
+            }
+            let result = communicator
+                .generate(OutputPluginInput {
+                    matrix_path: self.matrix_path(),
+                    project_root: std::path::absolute(&self.project_root)?,
+                    output_dir: output_dir.clone(),
+                    cache_dir: self.cache_dir().to_string_lossy().to_string(),
+                    plugin_config: plugin_config
+                        .config
+                        .as_ref()
+                        .map(|v| serde_json::to_value(v).unwrap_or(serde_json::Value::Null)),
+                    format_options,
+                    section_cache: None,
+                    scope: None,
+                })
+                .await
+                .map_err(|e| anyhow::anyhow!("Changelog polishing failed: {}", e))?;
+            plugin = (result.plugin_name, result.plugin_version);
+            outputs.extend(result.outputs);
+        }
+
+        let mut manifest = DocsManifest::load(&output_dir)
+            .await
+            .unwrap_or_else(|_| DocsManifest::new("markdown"));
+        manifest.replace_output_type(ManifestEntry {
+            language: None,
+            output_type: "changelog".to_string(),
+            plugin: plugin.0,
+            plugin_version: plugin.1,
+            output_dir: output_dir.clone(),
+            outputs: outputs.clone(),
+        });
+        let manifest_path = manifest.save(&output_dir).await?;
+
+        Ok(ChangelogReport {
+            changelog,
+            outputs,
+            manifest_path,
+        })
+    }
+
+    fn output_communicator(
+        &self,
+        plugin_path: PathBuf,
+        plugin_config: &OutputPluginConfig,
+        run: &CacheRun,
+    ) -> OutputPluginCommunicator {
+        let mut communicator = OutputPluginCommunicator::new(plugin_path)
+            .with_cache_dir(self.cache_dir())
+            .with_run_dir(run.dir().to_path_buf())
+            .with_logs(self.config.plugin_logs.clone())
+            .with_offline(self.config.offline)
+            .with_runtime(plugin_config.runtime);
+        if let Some(ref node_exe) = self.config.node_executable {
+            communicator = communicator.with_node_executable(node_exe.clone());
+        }
+        if let Some(ref wasm_exe) = self.config.wasm_executable {
+            communicator = communicator.with_wasm_executable(wasm_exe.clone());
+        }
+        if let Some(ref python_exe) = self.config.python_executable {
+            communicator = communicator.with_python_executable(python_exe.clone());
+        } else {
+            communicator = communicator.with_python_auto_detect();
+        }
+        communicator
+    }
+
+    /// Generate documentation as a single document and return its content instead of
+    /// leaving files behind, for piping into other tools. The plugin writes into a
+    /// scratch run directory that is removed afterwards; it must produce exactly one
+    /// output, so at most one `docs.languages` entry may be configured.
+    pub async fn generate_document(&self, format: &str) -> Result<(OutputPluginResult, Vec<u8>)> {
+        if self.config.docs.languages.len() > 1 {
+            return Err(anyhow::anyhow!(
+                "A single document can't hold {} docs.languages; configure at most one",
+                self.config.docs.languages.len()
+            ));
+        }
+        let cache_dir = self.cache_dir();
+        let run = CacheRun::create(&cache_dir, Uuid::new_v4())?;
+        let mut csd = self.clone().with_output_dir(run.dir().join("docs"));
+        csd.single_document = true;
+
+        let mut report = csd.generate_docs(format).await?;
+        let Some(pass) = report.passes.pop() else {
+            return Err(anyhow::anyhow!("No documentation was generated"));
+        };
+        let [output] = pass.result.outputs.as_slice() else {
+            return Err(anyhow::anyhow!(
+                "Plugin {} produced {} outputs; a single document needs exactly one",
+                pass.result.plugin_name,
+                pass.result.outputs.len()
+            ));
+        };
+        let content = tokio::fs::read(&output.output_path)
+            .await
+            .with_context(|| format!("Failed to read {}", output.output_path.display()))?;
+        Ok((pass.result, content))
+    }
+}
+
+/// Resolve an output plugin's script from its configured source
+fn output_plugin_path(plugin_config: &OutputPluginConfig) -> Result<PathBuf> {
+    let plugin_path =
+        plugin_script_path(PluginType::Output, &plugin_config.source).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Plugin source type not yet supported: {:?}",
+                plugin_config.source
+            )
+        })?;
+    if !plugin_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Output plugin file not found: {}",
+            plugin_path.display()
+        ));
+    }
+    Ok(plugin_path)
+}
+
+/// The files in `scope` plus `scope.depth` levels of their dependencies. Dependents
+/// are left out: documenting one package shouldn't pull in everything that uses it.
+fn scoped_matrix(matrix: &ProjectMatrix, scope: &OutputScope) -> Result<ProjectMatrix> {
+    let files = scope.select(matrix)?;
+    if files.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No files in the matrix match the docs scope"
+        ));
+    }
+    info!("Documenting {} files in scope", files.len());
+    let options = SubsetOptions {
+        dependency_depth: scope.depth,
+        dependent_depth: 0,
+        max_tokens: None,
+    };
+    Ok(matrix.subset(&files, &options))
+}
+
+/// Language codes name output subdirectories, so only letters, digits, `-` and `_`
+fn is_language_code(language: &str) -> bool {
+    !language.is_empty()
+        && language
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Section cache for one pass: emptied for a fresh run, scanned for completion markers
+/// when resuming
+async fn prepare_section_cache(dir: PathBuf, resume: bool) -> Result<SectionCache> {
+    if !resume && dir.exists() {
+        tokio::fs::remove_dir_all(&dir)
+            .await
+            .with_context(|| format!("Failed to clear section cache: {}", dir.display()))?;
+    }
+    tokio::fs::create_dir_all(&dir).await?;
+
+    let completed = if resume {
+        SectionCache::completed_sections(&dir)
+    } else {
+        Vec::new()
+    };
+    if !completed.is_empty() {
+        info!("Resuming with {} cached sections", completed.len());
+    }
+    Ok(SectionCache {
+        dir,
+        resume,
+        completed,
+    })
+}
+
+/// Previous outputs are copied here by `OverwritePolicy::Backup`
+pub const DOCS_BACKUP_DIR: &str = ".csd_backup";
+
+/// Apply the overwrite policy to files already in the output directory
+async fn prepare_output_dir(output_dir: &Path, policy: OverwritePolicy) -> Result<()> {
+    if policy == OverwritePolicy::Overwrite {
+        return Ok(());
+    }
+
+    let existing: Vec<PathBuf> = walkdir::WalkDir::new(output_dir)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != DOCS_BACKUP_DIR)
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+    if existing.is_empty() {
+        return Ok(());
+    }
+
+    if policy == OverwritePolicy::Fail {
+        return Err(anyhow::anyhow!(
+            "Output directory {} already contains {} files and docs.overwrite is 'fail'",
+            output_dir.display(),
+            existing.len()
+        ));
+    }
+
+    let backup_root = output_dir
+        .join(DOCS_BACKUP_DIR)
+        .join(chrono::Utc::now().format("%Y%m%dT%H%M%S").to_string());
+    for file in &existing {
+        let relative = file.strip_prefix(output_dir)?;
+        let target = backup_root.join(relative);
+        if let Some(parent) = target.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::copy(file, &target)
+            .await
+            .with_context(|| format!("Failed to back up {}", file.display()))?;
+    }
+    info!(
+        "Backed up {} existing output files to {}",
+        existing.len(),
+        backup_root.display()
+    );
+    Ok(())
+}
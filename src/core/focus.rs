@@ -0,0 +1,103 @@
+// src/core/focus.rs - The dependency neighborhood of a file or directory, as a subset
+// matrix and a Graphviz graph
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+use crate::core::matrix::{ProjectMatrix, SubsetOptions};
+
+/// Files at or under a focus path plus `depth` levels of their dependencies and
+/// dependents, with every edge between them
+#[derive(Debug)]
+pub struct FocusGraph {
+    // Files the focus path names, sorted
+    pub focus: Vec<PathBuf>,
+    pub matrix: ProjectMatrix,
+}
+
+impl FocusGraph {
+    pub fn new(matrix: &ProjectMatrix, focus: &Path, depth: usize) -> Result<Self> {
+        // "./src/core/" names the same files as "src/core"
+        let focus: PathBuf = focus
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect();
+        let mut files: Vec<PathBuf> = matrix
+            .files
+            .keys()
+            .filter(|path| path.starts_with(&focus))
+            .cloned()
+            .collect();
+        if files.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No files in the matrix at or under {}",
+                focus.display()
+            ));
+        }
+        files.sort();
+
+        let options = SubsetOptions {
+            dependency_depth: depth,
+            dependent_depth: depth,
+            max_tokens: None,
+        };
+        Ok(Self {
+            matrix: matrix.subset(&files, &options),
+            focus: files,
+        })
+    }
+
+    /// Graphviz digraph with the focus files highlighted. Relationships between the same
+    /// two files are drawn as one edge labelled with their types, thicker the stronger
+    /// the strongest of them.
+    pub fn to_dot(&self) -> String {
+        let mut edges: BTreeMap<(&Path, &Path), (Vec<String>, f32)> = BTreeMap::new();
+        for r in &self.matrix.relationships {
+            let (types, strength) = edges
+                .entry((r.from_file.as_path(), r.to_file.as_path()))
+                .or_default();
+            let name = format!("{:?}", r.relationship_type).to_lowercase();
+            if !types.contains(&name) {
+                types.push(name);
+            }
+            *strength = strength.max(r.strength);
+        }
+
+        let mut files: Vec<&PathBuf> = self.matrix.files.keys().collect();
+        files.sort();
+
+        let mut dot = String::from("digraph focus {\n");
+        dot.push_str("  rankdir=LR;\n");
+        dot.push_str("  node [shape=box, fontname=\"Helvetica\"];\n");
+        for file in files {
+            let highlight = if self.focus.contains(file) {
+                " [style=filled, fillcolor=\"#ffe08a\"]"
+            } else {
+                ""
+            };
+            dot.push_str(&format!("  {}{highlight};\n", dot_id(file)));
+        }
+        for ((from, to), (mut types, strength)) in edges {
+            types.sort();
+            dot.push_str(&format!(
+                "  {} -> {} [label=\"{}\", penwidth={:.1}];\n",
+                dot_id(from),
+                dot_id(to),
+                types.join(", "),
+                1.0 + 2.0 * strength.clamp(0.0, 1.0)
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn dot_id(path: &Path) -> String {
+    format!(
+        "\"{}\"",
+        path.display()
+            .to_string()
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+    )
+}
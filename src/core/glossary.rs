@@ -0,0 +1,273 @@
+// src/core/glossary.rs - Candidate domain terms for the docs glossary, ranked by TF-IDF
+// over identifiers, docstrings and summaries
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::core::matrix::{FileNode, ProjectMatrix};
+use crate::utils::config::GlossaryConfig;
+
+// Files listed per term, most uses first
+const EXAMPLE_FILES: usize = 5;
+
+// Shorter words are mostly abbreviations like `id` or `fn`
+const MIN_TERM_LEN: usize = 3;
+
+// English filler and vocabulary every codebase shares
+const STOPWORDS: &[&str] = &[
+    "about",
+    "above",
+    "after",
+    "again",
+    "all",
+    "also",
+    "and",
+    "any",
+    "are",
+    "arg",
+    "args",
+    "as",
+    "because",
+    "been",
+    "before",
+    "being",
+    "between",
+    "both",
+    "but",
+    "by",
+    "call",
+    "called",
+    "can",
+    "class",
+    "could",
+    "does",
+    "each",
+    "else",
+    "every",
+    "false",
+    "file",
+    "files",
+    "for",
+    "from",
+    "func",
+    "function",
+    "functions",
+    "get",
+    "given",
+    "has",
+    "have",
+    "helper",
+    "here",
+    "how",
+    "if",
+    "impl",
+    "init",
+    "into",
+    "its",
+    "just",
+    "like",
+    "list",
+    "main",
+    "make",
+    "may",
+    "method",
+    "more",
+    "most",
+    "must",
+    "new",
+    "none",
+    "not",
+    "null",
+    "num",
+    "object",
+    "off",
+    "once",
+    "one",
+    "only",
+    "other",
+    "our",
+    "out",
+    "over",
+    "own",
+    "param",
+    "params",
+    "result",
+    "return",
+    "returns",
+    "same",
+    "self",
+    "set",
+    "should",
+    "some",
+    "str",
+    "string",
+    "such",
+    "test",
+    "tests",
+    "than",
+    "that",
+    "the",
+    "their",
+    "them",
+    "then",
+    "there",
+    "these",
+    "they",
+    "this",
+    "those",
+    "through",
+    "true",
+    "two",
+    "type",
+    "under",
+    "until",
+    "use",
+    "used",
+    "uses",
+    "using",
+    "util",
+    "utils",
+    "val",
+    "value",
+    "values",
+    "var",
+    "very",
+    "was",
+    "way",
+    "were",
+    "what",
+    "when",
+    "where",
+    "whether",
+    "which",
+    "while",
+    "who",
+    "why",
+    "will",
+    "with",
+    "without",
+    "would",
+    "you",
+    "your",
+];
+
+/// A term that may deserve a glossary entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlossaryTerm {
+    pub term: String,
+    pub score: f64,
+    // Uses across all files
+    pub occurrences: usize,
+    // Where it's used most, relative to the project root
+    pub files: Vec<PathBuf>,
+}
+
+/// Rank words from file names, element names, docstrings and summaries by how often
+/// they're used, discounted by how many files use them. Terms in fewer than `min_files`
+/// files or on the deny list are dropped; allowed terms are always kept and don't
+/// count against `max_terms`. Vendored files are left out.
+pub fn extract_glossary(matrix: &ProjectMatrix, config: &GlossaryConfig) -> Vec<GlossaryTerm> {
+    let allow: HashSet<String> = config.allow.iter().map(|t| t.to_lowercase()).collect();
+    let deny: HashSet<String> = config.deny.iter().map(|t| t.to_lowercase()).collect();
+
+    // term -> (relative path, uses in that file)
+    let mut uses: HashMap<String, Vec<(PathBuf, usize)>> = HashMap::new();
+    let mut documents = 0usize;
+    for file in matrix.maintained_files() {
+        documents += 1;
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for word in file_words(file) {
+            *counts.entry(word).or_default() += 1;
+        }
+        for (term, count) in counts {
+            uses.entry(term)
+                .or_default()
+                .push((file.relative_path.clone(), count));
+        }
+    }
+
+    let (mut allowed, mut ranked): (Vec<GlossaryTerm>, Vec<GlossaryTerm>) = uses
+        .into_iter()
+        .filter(|(term, _)| !deny.contains(term))
+        .filter(|(term, files)| {
+            allow.contains(term)
+                || (files.len() >= config.min_files && !STOPWORDS.contains(&term.as_str()))
+        })
+        .map(|(term, mut files)| {
+            let occurrences = files.iter().map(|(_, count)| count).sum();
+            // Smoothed so a term in every file still scores above zero
+            let idf = ((1 + documents) as f64 / (1 + files.len()) as f64).ln() + 1.0;
+            files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            GlossaryTerm {
+                score: (occurrences as f64 * idf * 1000.0).round() / 1000.0,
+                occurrences,
+                files: files
+                    .into_iter()
+                    .take(EXAMPLE_FILES)
+                    .map(|(path, _)| path)
+                    .collect(),
+                term,
+            }
+        })
+        .partition(|term| allow.contains(&term.term));
+
+    let by_score = |a: &GlossaryTerm, b: &GlossaryTerm| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.term.cmp(&b.term))
+    };
+    ranked.sort_by(by_score);
+    ranked.truncate(config.max_terms);
+    allowed.append(&mut ranked);
+    allowed.sort_by(by_score);
+    allowed
+}
+
+/// Lowercase words of `text`, with identifiers split at `snake_case` and `camelCase`
+/// boundaries: `parseHTTPRequest` gives `parse`, `http` and `request`
+pub fn split_words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphabetic() {
+            push_word(&mut words, &mut word);
+            continue;
+        }
+        let after_lower = i > 0 && chars[i - 1].is_lowercase();
+        let ends_acronym = i > 0
+            && chars[i - 1].is_uppercase()
+            && chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+        if c.is_uppercase() && (after_lower || ends_acronym) {
+            push_word(&mut words, &mut word);
+        }
+        word.extend(c.to_lowercase());
+    }
+    push_word(&mut words, &mut word);
+    words
+}
+
+fn push_word(words: &mut Vec<String>, word: &mut String) {
+    if word.chars().count() >= MIN_TERM_LEN {
+        words.push(std::mem::take(word));
+    } else {
+        word.clear();
+    }
+}
+
+fn file_words(file: &FileNode) -> Vec<String> {
+    let mut words = Vec::new();
+    if let Some(stem) = file.relative_path.file_stem() {
+        words.extend(split_words(&stem.to_string_lossy()));
+    }
+    if let Some(ref summary) = file.file_summary {
+        words.extend(split_words(summary));
+    }
+    for element in &file.elements {
+        words.extend(split_words(&element.name));
+        if let Some(ref summary) = element.summary {
+            words.extend(split_words(summary));
+        }
+    }
+    words
+}
@@ -0,0 +1,489 @@
+// src/core/graph_analysis.rs - Structural metrics over the file dependency graph
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::{Graph, NodeIndex};
+use petgraph::visit::EdgeRef;
+use petgraph::{Directed, Direction};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use crate::core::matrix::{Component, ComponentSource, ProjectGraph, ProjectMatrix};
+
+const PAGERANK_DAMPING: f64 = 0.85;
+const PAGERANK_ITERATIONS: usize = 50;
+const LABEL_PROPAGATION_ROUNDS: usize = 100;
+
+/// Manifests that mark an explicit package boundary
+const PACKAGE_MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "setup.py",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+];
+
+/// Betweenness centrality (Brandes), normalized to 0..=1. High scores mark files that sit on
+/// many shortest dependency paths — typical god-files and chokepoints.
+pub fn betweenness_centrality(graph: &ProjectGraph) -> HashMap<NodeIndex, f64> {
+    let nodes: Vec<NodeIndex> = graph.node_indices().collect();
+    let mut centrality: HashMap<NodeIndex, f64> = nodes.iter().map(|&n| (n, 0.0)).collect();
+
+    for &source in &nodes {
+        let mut stack = Vec::new();
+        let mut predecessors: HashMap<NodeIndex, Vec<NodeIndex>> = HashMap::new();
+        let mut paths: HashMap<NodeIndex, f64> = HashMap::from([(source, 1.0)]);
+        let mut distance: HashMap<NodeIndex, usize> = HashMap::from([(source, 0)]);
+        let mut queue = VecDeque::from([source]);
+
+        while let Some(node) = queue.pop_front() {
+            stack.push(node);
+            for neighbor in unique_neighbors(graph, node, Direction::Outgoing) {
+                if !distance.contains_key(&neighbor) {
+                    distance.insert(neighbor, distance[&node] + 1);
+                    queue.push_back(neighbor);
+                }
+                if distance[&neighbor] == distance[&node] + 1 {
+                    *paths.entry(neighbor).or_insert(0.0) += paths[&node];
+                    predecessors.entry(neighbor).or_default().push(node);
+                }
+            }
+        }
+
+        let mut dependency: HashMap<NodeIndex, f64> = HashMap::new();
+        while let Some(node) = stack.pop() {
+            for &pred in predecessors.get(&node).into_iter().flatten() {
+                let share = paths[&pred] / paths[&node] * (1.0 + dependency_of(&dependency, node));
+                *dependency.entry(pred).or_insert(0.0) += share;
+            }
+            if node != source {
+                *centrality.get_mut(&node).unwrap() += dependency_of(&dependency, node);
+            }
+        }
+    }
+
+    let n = nodes.len() as f64;
+    if n > 2.0 {
+        let scale = 1.0 / ((n - 1.0) * (n - 2.0));
+        centrality.values_mut().for_each(|c| *c *= scale);
+    }
+    centrality
+}
+
+fn dependency_of(dependency: &HashMap<NodeIndex, f64>, node: NodeIndex) -> f64 {
+    dependency.get(&node).copied().unwrap_or(0.0)
+}
+
+/// PageRank over dependency edges; rank flows from a file to what it depends on, so
+/// heavily (and transitively) depended-upon files score highest. Scores sum to 1.
+pub fn pagerank(graph: &ProjectGraph) -> HashMap<NodeIndex, f64> {
+    let n = graph.node_count();
+    if n == 0 {
+        return HashMap::new();
+    }
+
+    let initial = 1.0 / n as f64;
+    let mut ranks: HashMap<NodeIndex, f64> = graph.node_indices().map(|i| (i, initial)).collect();
+
+    for _ in 0..PAGERANK_ITERATIONS {
+        // Files without dependencies spread their rank evenly
+        let dangling: f64 = graph
+            .node_indices()
+            .filter(|&i| {
+                graph
+                    .edges_directed(i, Direction::Outgoing)
+                    .next()
+                    .is_none()
+            })
+            .map(|i| ranks[&i])
+            .sum();
+        let base = (1.0 - PAGERANK_DAMPING) / n as f64 + PAGERANK_DAMPING * dangling / n as f64;
+
+        let mut next: HashMap<NodeIndex, f64> = graph.node_indices().map(|i| (i, base)).collect();
+        for node in graph.node_indices() {
+            let out_degree = graph.edges_directed(node, Direction::Outgoing).count();
+            if out_degree == 0 {
+                continue;
+            }
+            let share = PAGERANK_DAMPING * ranks[&node] / out_degree as f64;
+            for edge in graph.edges_directed(node, Direction::Outgoing) {
+                *next.get_mut(&edge.target()).unwrap() += share;
+            }
+        }
+        ranks = next;
+    }
+    ranks
+}
+
+/// Files grouped into dependency layers: layer 0 depends on nothing, layer N only on
+/// lower layers. Files in a dependency cycle share a layer and are reported separately.
+#[derive(Debug, Clone, Default)]
+pub struct Layering {
+    pub layers: Vec<Vec<NodeIndex>>,
+    pub cycles: Vec<Vec<NodeIndex>>,
+}
+
+pub fn topological_layers<N, E>(graph: &Graph<N, E, Directed>) -> Layering {
+    // Tarjan returns components in reverse topological order: dependencies come first
+    let components = tarjan_scc(graph);
+    let mut component_of: HashMap<NodeIndex, usize> = HashMap::new();
+    for (index, component) in components.iter().enumerate() {
+        for &node in component {
+            component_of.insert(node, index);
+        }
+    }
+
+    let mut layering = Layering::default();
+    let mut component_layer: Vec<usize> = vec![0; components.len()];
+    for (index, component) in components.iter().enumerate() {
+        let layer = component
+            .iter()
+            .flat_map(|&node| graph.neighbors_directed(node, Direction::Outgoing))
+            .map(|dep| component_of[&dep])
+            .filter(|&dep_component| dep_component != index)
+            .map(|dep_component| component_layer[dep_component] + 1)
+            .max()
+            .unwrap_or(0);
+        component_layer[index] = layer;
+
+        if layering.layers.len() <= layer {
+            layering.layers.resize(layer + 1, Vec::new());
+        }
+        layering.layers[layer].extend(component);
+
+        let self_loop = component.len() == 1 && graph.contains_edge(component[0], component[0]);
+        if component.len() > 1 || self_loop {
+            layering.cycles.push(component.clone());
+        }
+    }
+    layering
+}
+
+/// Files whose removal would split the (undirected) dependency graph into more pieces —
+/// single points of failure connecting otherwise separate parts of the project.
+pub fn articulation_points(graph: &ProjectGraph) -> Vec<NodeIndex> {
+    let mut discovery: HashMap<NodeIndex, usize> = HashMap::new();
+    let mut low: HashMap<NodeIndex, usize> = HashMap::new();
+    let mut points: HashSet<NodeIndex> = HashSet::new();
+    let mut time = 0;
+
+    for root in graph.node_indices() {
+        if discovery.contains_key(&root) {
+            continue;
+        }
+        discovery.insert(root, time);
+        low.insert(root, time);
+        time += 1;
+
+        let mut root_children = 0;
+        // (node, parent, neighbors, next neighbor position)
+        let mut stack: Vec<(NodeIndex, Option<NodeIndex>, Vec<NodeIndex>, usize)> =
+            vec![(root, None, undirected_neighbors(graph, root), 0)];
+
+        while let Some((node, parent, neighbors, position)) = stack.last_mut() {
+            let (node, parent) = (*node, *parent);
+            if let Some(&next) = neighbors.get(*position) {
+                *position += 1;
+                if Some(next) == parent {
+                    continue;
+                }
+                if let Some(&seen) = discovery.get(&next) {
+                    let node_low = low[&node].min(seen);
+                    low.insert(node, node_low);
+                } else {
+                    discovery.insert(next, time);
+                    low.insert(next, time);
+                    time += 1;
+                    if node == root {
+                        root_children += 1;
+                    }
+                    stack.push((next, Some(node), undirected_neighbors(graph, next), 0));
+                }
+            } else {
+                stack.pop();
+                if let Some(parent) = parent {
+                    let parent_low = low[&parent].min(low[&node]);
+                    low.insert(parent, parent_low);
+                    if parent != root && low[&node] >= discovery[&parent] {
+                        points.insert(parent);
+                    }
+                }
+            }
+        }
+
+        if root_children > 1 {
+            points.insert(root);
+        }
+    }
+
+    let mut points: Vec<NodeIndex> = points.into_iter().collect();
+    points.sort();
+    points
+}
+
+/// Communities via label propagation on the undirected graph, weighted by edge strength.
+/// Nodes are visited in index order and ties go to the smallest label, so results are
+/// deterministic. Returns groups of two or more nodes; isolated files are left out.
+pub fn label_propagation(graph: &ProjectGraph) -> Vec<Vec<NodeIndex>> {
+    let mut labels: HashMap<NodeIndex, NodeIndex> = graph.node_indices().map(|i| (i, i)).collect();
+
+    for _ in 0..LABEL_PROPAGATION_ROUNDS {
+        let mut changed = false;
+        for node in graph.node_indices() {
+            let mut weights: BTreeMap<NodeIndex, f64> = BTreeMap::new();
+            for edge in graph
+                .edges_directed(node, Direction::Outgoing)
+                .chain(graph.edges_directed(node, Direction::Incoming))
+            {
+                let other = if edge.source() == node {
+                    edge.target()
+                } else {
+                    edge.source()
+                };
+                if other != node {
+                    *weights.entry(labels[&other]).or_insert(0.0) +=
+                        f64::from(edge.weight().strength.max(0.01));
+                }
+            }
+
+            // BTreeMap order makes the first maximum the smallest label
+            let best = weights.into_iter().fold(
+                None,
+                |best: Option<(NodeIndex, f64)>, (label, weight)| match best {
+                    Some((_, best_weight)) if best_weight >= weight => best,
+                    _ => Some((label, weight)),
+                },
+            );
+            if let Some((label, _)) = best {
+                if labels[&node] != label {
+                    labels.insert(node, label);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut groups: BTreeMap<NodeIndex, Vec<NodeIndex>> = BTreeMap::new();
+    for (node, label) in labels {
+        groups.entry(label).or_default().push(node);
+    }
+    groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .map(|mut group| {
+            group.sort();
+            group
+        })
+        .collect()
+}
+
+/// Propose logical components: one per package when the project has several manifests,
+/// otherwise communities in the dependency graph
+pub fn detect_components(matrix: &mut ProjectMatrix) -> Vec<Component> {
+    let mut package_roots: Vec<PathBuf> = matrix
+        .files
+        .values()
+        .filter(|f| {
+            f.relative_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| PACKAGE_MANIFESTS.contains(&n))
+        })
+        .map(|f| {
+            f.relative_path
+                .parent()
+                .unwrap_or(Path::new(""))
+                .to_path_buf()
+        })
+        .collect();
+    package_roots.sort();
+    package_roots.dedup();
+
+    let mut components = if package_roots.len() > 1 {
+        package_components(matrix, &package_roots)
+    } else {
+        let graph = matrix.graph();
+        label_propagation(graph)
+            .into_iter()
+            .map(|group| Component {
+                name: String::new(),
+                files: group
+                    .into_iter()
+                    .map(|i| graph[i].relative_path.clone())
+                    .collect(),
+                source: ComponentSource::Detected,
+            })
+            .collect()
+    };
+
+    for component in &mut components {
+        component.files.sort();
+        if component.name.is_empty() {
+            component.name = component_name(&component.files);
+        }
+    }
+    components.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.files.cmp(&b.files)));
+
+    // Keep names unique
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for component in &mut components {
+        let count = seen.entry(component.name.clone()).or_insert(0);
+        *count += 1;
+        if *count > 1 {
+            component.name = format!("{}-{}", component.name, count);
+        }
+    }
+    components
+}
+
+/// Assign every file to its deepest enclosing package root
+fn package_components(matrix: &ProjectMatrix, roots: &[PathBuf]) -> Vec<Component> {
+    let mut by_root: BTreeMap<&PathBuf, Vec<PathBuf>> = BTreeMap::new();
+    for file in matrix.files.values() {
+        if let Some(root) = roots
+            .iter()
+            .filter(|root| file.relative_path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+        {
+            by_root
+                .entry(root)
+                .or_default()
+                .push(file.relative_path.clone());
+        }
+    }
+    by_root
+        .into_iter()
+        .map(|(root, files)| Component {
+            name: if root.as_os_str().is_empty() {
+                "root".to_string()
+            } else {
+                root.display().to_string()
+            },
+            files,
+            source: ComponentSource::Package,
+        })
+        .collect()
+}
+
+/// Longest shared directory of the files, falling back to the first file's stem
+fn component_name(files: &[PathBuf]) -> String {
+    let mut prefix: Option<PathBuf> = None;
+    for file in files {
+        let dir = file.parent().unwrap_or(Path::new(""));
+        prefix = Some(match prefix {
+            None => dir.to_path_buf(),
+            Some(prefix) => prefix
+                .components()
+                .zip(dir.components())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+
+    match prefix {
+        Some(prefix) if !prefix.as_os_str().is_empty() => prefix.display().to_string(),
+        _ => files
+            .first()
+            .and_then(|f| f.file_stem())
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "component".to_string()),
+    }
+}
+
+fn unique_neighbors(graph: &ProjectGraph, node: NodeIndex, direction: Direction) -> Vec<NodeIndex> {
+    let mut neighbors: Vec<NodeIndex> = graph
+        .neighbors_directed(node, direction)
+        .filter(|&n| n != node)
+        .collect();
+    neighbors.sort();
+    neighbors.dedup();
+    neighbors
+}
+
+fn undirected_neighbors(graph: &ProjectGraph, node: NodeIndex) -> Vec<NodeIndex> {
+    let mut neighbors = unique_neighbors(graph, node, Direction::Outgoing);
+    neighbors.extend(unique_neighbors(graph, node, Direction::Incoming));
+    neighbors.sort();
+    neighbors.dedup();
+    neighbors
+}
+
+/// Graph metrics keyed by project-relative path, ready for reporting
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GraphAnalysis {
+    pub betweenness: Vec<(PathBuf, f64)>, // Highest first
+    pub pagerank: Vec<(PathBuf, f64)>,    // Highest first
+    pub layers: Vec<Vec<PathBuf>>,
+    pub cycles: Vec<Vec<PathBuf>>,
+    pub articulation_points: Vec<PathBuf>,
+}
+
+impl GraphAnalysis {
+    pub fn analyze(matrix: &mut ProjectMatrix) -> Self {
+        let graph = matrix.graph();
+        let path = |index: NodeIndex| graph[index].relative_path.clone();
+        let ranked = |scores: HashMap<NodeIndex, f64>| {
+            let mut ranked: Vec<(PathBuf, f64)> =
+                scores.into_iter().map(|(i, s)| (path(i), s)).collect();
+            ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            ranked
+        };
+        let paths = |indexes: Vec<NodeIndex>| {
+            let mut paths: Vec<PathBuf> = indexes.into_iter().map(path).collect();
+            paths.sort();
+            paths
+        };
+
+        let layering = topological_layers(graph);
+        Self {
+            betweenness: ranked(betweenness_centrality(graph)),
+            pagerank: ranked(pagerank(graph)),
+            layers: layering.layers.into_iter().map(paths).collect(),
+            cycles: layering.cycles.into_iter().map(paths).collect(),
+            articulation_points: paths(articulation_points(graph)),
+        }
+    }
+
+    /// Print the highlights: god-files, cycles and single points of failure
+    pub fn print_summary(&self, top: usize) {
+        println!("\n🕸️  Dependency Graph Analysis:");
+        println!("   Layers: {}", self.layers.len());
+
+        println!("   Most central files (betweenness):");
+        for (path, score) in self.betweenness.iter().filter(|(_, s)| *s > 0.0).take(top) {
+            println!("     {:.3}  {}", score, path.display());
+        }
+        println!("   Most depended-upon files (PageRank):");
+        for (path, score) in self.pagerank.iter().take(top) {
+            println!("     {:.3}  {}", score, path.display());
+        }
+
+        if self.cycles.is_empty() {
+            println!("   ✅ No dependency cycles");
+        } else {
+            println!(
+                "   ⚠️  Dependency cycles (layering violations): {}",
+                self.cycles.len()
+            );
+            for cycle in self.cycles.iter().take(top) {
+                let files: Vec<String> = cycle.iter().map(|p| p.display().to_string()).collect();
+                println!("     {}", files.join(" <-> "));
+            }
+        }
+
+        if !self.articulation_points.is_empty() {
+            println!(
+                "   🔗 Single points of failure: {}",
+                self.articulation_points.len()
+            );
+            for path in self.articulation_points.iter().take(top) {
+                println!("     {}", path.display());
+            }
+        }
+    }
+}
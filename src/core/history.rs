@@ -0,0 +1,139 @@
+// src/core/history.rs - Append-only metrics log for tracking a project over time
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+use crate::core::matrix::ProjectMatrix;
+use crate::utils::cache::CacheLock;
+
+/// Metrics recorded for one scan
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MetricsSnapshot {
+    pub timestamp: DateTime<Utc>,
+    pub csd_version: String,
+    pub total_files: usize,
+    pub total_tokens: u64,
+    pub total_relationships: usize,
+    pub external_dependencies: usize,
+    pub max_coupling: usize, // Highest in-degree of any file
+    pub average_complexity: Option<f64>,
+}
+
+impl MetricsSnapshot {
+    pub fn from_matrix(matrix: &mut ProjectMatrix) -> Self {
+        let metrics = matrix.calculate_metrics();
+
+        let scores: Vec<u32> = matrix
+            .files
+            .values()
+            .flat_map(|f| f.elements.iter())
+            .filter_map(|e| e.complexity_score)
+            .collect();
+        let average_complexity = (!scores.is_empty())
+            .then(|| scores.iter().map(|&s| s as f64).sum::<f64>() / scores.len() as f64);
+
+        Self {
+            timestamp: matrix.metadata.scan_timestamp,
+            csd_version: matrix.metadata.csd_version.clone(),
+            total_files: metrics.total_files,
+            total_tokens: metrics.total_tokens,
+            total_relationships: metrics.total_relationships,
+            external_dependencies: matrix.external_dependencies.len(),
+            max_coupling: metrics
+                .highly_coupled_files
+                .first()
+                .map(|(_, score)| *score)
+                .unwrap_or(0),
+            average_complexity,
+        }
+    }
+}
+
+/// Metrics history stored as JSON lines under `<cache_dir>/history/metrics.jsonl`
+pub struct MetricsHistory {
+    path: PathBuf,
+}
+
+impl MetricsHistory {
+    pub fn new(cache_dir: &Path) -> Self {
+        Self {
+            path: cache_dir.join("history").join("metrics.jsonl"),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a snapshot; one line per scan keeps the log cheap to grow
+    pub async fn append(&self, snapshot: &MetricsSnapshot) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let mut line = serde_json::to_string(snapshot)?;
+        line.push('\n');
+
+        // Concurrent scans would otherwise interleave their lines
+        let _lock = CacheLock::exclusive(&self.path).await?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Read all snapshots, oldest first. Unparseable lines (e.g. a torn final write)
+    /// are skipped rather than failing the whole history.
+    pub async fn load(&self) -> Result<Vec<MetricsSnapshot>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = {
+            let _lock = CacheLock::shared(&self.path).await.ok();
+            tokio::fs::read_to_string(&self.path).await?
+        };
+        let mut snapshots: Vec<MetricsSnapshot> = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(snapshot) => Some(snapshot),
+                Err(e) => {
+                    log::warn!("Skipping unreadable history entry: {e}");
+                    None
+                }
+            })
+            .collect();
+        snapshots.sort_by_key(|s| s.timestamp);
+        Ok(snapshots)
+    }
+}
+
+/// Render snapshots as CSV with a header row
+pub fn snapshots_to_csv(snapshots: &[MetricsSnapshot]) -> String {
+    let mut csv = String::from(
+        "timestamp,csd_version,total_files,total_tokens,total_relationships,external_dependencies,max_coupling,average_complexity\n",
+    );
+    for s in snapshots {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            s.timestamp.to_rfc3339(),
+            s.csd_version,
+            s.total_files,
+            s.total_tokens,
+            s.total_relationships,
+            s.external_dependencies,
+            s.max_coupling,
+            s.average_complexity
+                .map(|c| format!("{c:.2}"))
+                .unwrap_or_default()
+        ));
+    }
+    csv
+}
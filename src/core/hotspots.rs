@@ -0,0 +1,247 @@
+// src/core/hotspots.rs - Oversized files and functions, for quality reports and docs
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::core::matrix::{ElementType, ProjectMatrix};
+use crate::core::suggestions::{print_suggestions, Suggestion};
+use crate::core::suppressions::{
+    QualityCheck, Suppressed, SuppressedBy, Suppression, Suppressions,
+};
+use crate::utils::config::QualityConfig;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OversizedFile {
+    pub path: PathBuf,
+    pub tokens: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<Suggestion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OversizedFunction {
+    pub path: PathBuf,
+    pub name: String,
+    pub line_start: u32,
+    pub line_end: u32,
+    pub lines: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub complexity: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// Files over the token limit and functions over the line or complexity limit, vendored
+/// code aside. The counts cover every offender; the lists keep the worst `top` of each.
+/// Suppressed offenders are left out of both and listed on their own.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HotspotReport {
+    pub thresholds: QualityConfig,
+    pub files_over_limit: usize,
+    pub functions_over_limit: usize,
+    pub files: Vec<OversizedFile>,
+    pub functions: Vec<OversizedFunction>,
+    // By path, then line
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suppressed: Vec<Suppressed>,
+    // Suppressions file entries past their date, whose offenders count again
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expired_suppressions: Vec<Suppression>,
+}
+
+impl HotspotReport {
+    pub fn from_matrix(matrix: &ProjectMatrix, thresholds: &QualityConfig) -> Self {
+        Self::with_suppressions(matrix, thresholds, &Suppressions::default())
+    }
+
+    /// The report, less the offenders a pragma or unexpired suppressions file entry
+    /// covers. A function over both limits is only left out when both are suppressed.
+    pub fn with_suppressions(
+        matrix: &ProjectMatrix,
+        thresholds: &QualityConfig,
+        suppressions: &Suppressions,
+    ) -> Self {
+        let mut files = Vec::new();
+        let mut functions: Vec<(f64, OversizedFunction)> = Vec::new();
+        let mut suppressed = Vec::new();
+        for file in matrix.maintained_files() {
+            let oversized =
+                (file.token_info.total_tokens > thresholds.max_file_tokens).then(|| {
+                    OversizedFile {
+                        path: file.relative_path.clone(),
+                        tokens: file.token_info.total_tokens,
+                        suggestions: Vec::new(),
+                    }
+                });
+            let offenders: Vec<(OversizedFunction, Vec<QualityCheck>)> = file
+                .elements
+                .iter()
+                .filter(|e| matches!(e.element_type, ElementType::Function | ElementType::Method))
+                .map(|e| OversizedFunction {
+                    path: file.relative_path.clone(),
+                    name: e.qualified_name().to_string(),
+                    line_start: e.line_start,
+                    line_end: e.line_end,
+                    lines: e.line_end.saturating_sub(e.line_start) + 1,
+                    complexity: e.complexity_score,
+                    suggestions: Vec::new(),
+                })
+                .map(|f| {
+                    let mut checks = Vec::new();
+                    if f.lines > thresholds.max_function_lines {
+                        checks.push(QualityCheck::FunctionLength);
+                    }
+                    if f.complexity.unwrap_or(0) > thresholds.max_function_complexity {
+                        checks.push(QualityCheck::Complexity);
+                    }
+                    (f, checks)
+                })
+                .filter(|(_, checks)| !checks.is_empty())
+                .collect();
+            if oversized.is_none() && offenders.is_empty() {
+                continue;
+            }
+
+            // Pragmas are only read from files with offenders
+            let file_suppressions = suppressions.for_file(&file.relative_path);
+            if let Some(oversized) = oversized {
+                match file_suppressions.file(&QualityCheck::FileSize) {
+                    Some(by) => suppressed.push(Suppressed {
+                        path: oversized.path,
+                        name: None,
+                        line: None,
+                        checks: vec![QualityCheck::FileSize],
+                        by,
+                    }),
+                    None => files.push(oversized),
+                }
+            }
+            for (function, checks) in offenders {
+                let by: Vec<SuppressedBy> = checks
+                    .iter()
+                    .map_while(|check| {
+                        file_suppressions.element(
+                            &function.name,
+                            (function.line_start, function.line_end),
+                            check,
+                        )
+                    })
+                    .collect();
+                if by.len() == checks.len() {
+                    suppressed.push(Suppressed {
+                        path: function.path,
+                        name: Some(function.name),
+                        line: Some(function.line_start),
+                        checks,
+                        by: by.into_iter().next().expect("offenders exceed a limit"),
+                    });
+                } else {
+                    functions.push((severity(&function, thresholds), function));
+                }
+            }
+        }
+        files.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.path.cmp(&b.path)));
+        suppressed.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+        // Worst first: whichever limit a function exceeds by the larger factor decides
+        functions.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .total_cmp(a_score)
+                .then_with(|| a.path.cmp(&b.path))
+                .then_with(|| a.line_start.cmp(&b.line_start))
+        });
+
+        Self {
+            thresholds: thresholds.clone(),
+            files_over_limit: files.len(),
+            functions_over_limit: functions.len(),
+            files: files.into_iter().take(thresholds.top).collect(),
+            functions: functions
+                .into_iter()
+                .take(thresholds.top)
+                .map(|(_, f)| f)
+                .collect(),
+            suppressed,
+            expired_suppressions: suppressions.expired().into_iter().cloned().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files_over_limit == 0 && self.functions_over_limit == 0
+    }
+
+    pub fn print_summary(&self) {
+        println!("\n📏 Size Hotspots:");
+        self.print_suppressions();
+        if self.is_empty() {
+            println!("   ✅ No files or functions over the size limits");
+            return;
+        }
+
+        println!(
+            "   Files over {} tokens: {}",
+            self.thresholds.max_file_tokens, self.files_over_limit
+        );
+        for file in &self.files {
+            println!("     {:>8}  {}", file.tokens, file.path.display());
+            print_suggestions(&file.suggestions, 7);
+        }
+        println!(
+            "   Functions over {} lines or complexity {}: {}",
+            self.thresholds.max_function_lines,
+            self.thresholds.max_function_complexity,
+            self.functions_over_limit
+        );
+        for function in &self.functions {
+            let complexity = function
+                .complexity
+                .map(|c| format!(", complexity {c}"))
+                .unwrap_or_default();
+            println!(
+                "     {} ({}:{}, {} lines{complexity})",
+                function.name,
+                function.path.display(),
+                function.line_start,
+                function.lines
+            );
+            print_suggestions(&function.suggestions, 7);
+        }
+    }
+
+    fn print_suppressions(&self) {
+        if !self.suppressed.is_empty() {
+            let pragmas = self
+                .suppressed
+                .iter()
+                .filter(|s| matches!(s.by, SuppressedBy::Pragma { .. }))
+                .count();
+            println!(
+                "   🔕 {} offenders suppressed ({pragmas} by pragma, {} by {})",
+                self.suppressed.len(),
+                self.suppressed.len() - pragmas,
+                crate::core::suppressions::SUPPRESSIONS_FILE
+            );
+        }
+        for expired in &self.expired_suppressions {
+            println!(
+                "   ⏰ Suppression for {} expired on {}{}",
+                expired.path,
+                expired
+                    .expires
+                    .map(|date| date.to_string())
+                    .unwrap_or_default(),
+                expired
+                    .reason
+                    .as_deref()
+                    .map(|reason| format!(" ({reason})"))
+                    .unwrap_or_default()
+            );
+        }
+    }
+}
+
+fn severity(function: &OversizedFunction, thresholds: &QualityConfig) -> f64 {
+    let ratio = |value: u32, limit: u32| value as f64 / limit.max(1) as f64;
+    ratio(function.lines, thresholds.max_function_lines).max(ratio(
+        function.complexity.unwrap_or(0),
+        thresholds.max_function_complexity,
+    ))
+}
@@ -0,0 +1,338 @@
+// src/core/interactions.rs - The services this project talks to at runtime, imported from
+// OpenTelemetry service graphs or structured access logs, so docs can show the deployed
+// topology and not just what the code imports
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::core::matrix::ProjectMatrix;
+
+// Most frequent operations kept per service
+const MAX_OPERATIONS: usize = 5;
+
+// Access log fields naming the service a request went to, in order of preference:
+// OpenTelemetry attributes, then Envoy's and common proxy log fields
+const PEER_FIELDS: &[&str] = &[
+    "peer.service",
+    "server.address",
+    "upstream_service",
+    "upstream_cluster",
+    "upstream_host",
+];
+const METHOD_FIELDS: &[&str] = &["http.request.method", "http.method", "method"];
+const PATH_FIELDS: &[&str] = &["http.route", "url.path", "http.target", "path", "route"];
+const STATUS_FIELDS: &[&str] = &[
+    "http.response.status_code",
+    "http.status_code",
+    "response_code",
+    "status",
+];
+const DURATION_FIELDS: &[&str] = &["duration_ms", "latency_ms", "duration"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InteractionDirection {
+    // This project calls the service
+    Outgoing,
+    // The service calls this project
+    Incoming,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum InteractionSource {
+    /// Jaeger's dependencies JSON, or the Prometheus metrics of the OpenTelemetry
+    /// service graph connector (`traces_service_graph_request_total`)
+    ServiceGraph,
+    /// JSON lines, one request each, naming the service called in `peer.service`,
+    /// `server.address`, `upstream_service`, `upstream_cluster` or `upstream_host`
+    AccessLog,
+}
+
+impl InteractionSource {
+    pub fn detect(content: &str) -> Self {
+        // A dependencies document is one JSON value; a log is a JSON object per line
+        match serde_json::from_str::<serde_json::Value>(content) {
+            Ok(serde_json::Value::Array(_)) => InteractionSource::ServiceGraph,
+            Ok(serde_json::Value::Object(object)) if object.contains_key("data") => {
+                InteractionSource::ServiceGraph
+            }
+            _ if content.trim_start().starts_with('{') => InteractionSource::AccessLog,
+            _ => InteractionSource::ServiceGraph,
+        }
+    }
+}
+
+/// Traffic between this project and another service over the observed period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalInteraction {
+    pub service: String,
+    pub direction: InteractionDirection,
+    pub calls: u64,
+    #[serde(default)]
+    pub errors: u64,
+    // e.g. `GET /users/{id}`, most frequent first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub operations: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_latency_ms: Option<f64>,
+    pub source: InteractionSource,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// Interactions of `service` in a service graph; edges between other services are
+/// counted as skipped
+pub fn parse_service_graph(
+    content: &str,
+    service: &str,
+) -> Result<(Vec<ExternalInteraction>, usize)> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Dependency {
+        parent: String,
+        child: String,
+        #[serde(default)]
+        call_count: u64,
+    }
+    #[derive(Deserialize)]
+    struct Dependencies {
+        data: Vec<Dependency>,
+    }
+
+    // (client, server) -> (calls, failed)
+    let mut edges: BTreeMap<(String, String), (u64, u64)> = BTreeMap::new();
+    let trimmed = content.trim_start();
+    if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        let dependencies = match serde_json::from_str::<Dependencies>(trimmed) {
+            Ok(dependencies) => dependencies.data,
+            Err(_) => serde_json::from_str::<Vec<Dependency>>(trimmed)
+                .context("Not a Jaeger dependencies list: expected [{parent, child, callCount}]")?,
+        };
+        for dependency in dependencies {
+            edges
+                .entry((dependency.parent, dependency.child))
+                .or_default()
+                .0 += dependency.call_count;
+        }
+    } else {
+        for line in content.lines().map(str::trim) {
+            let failed = if line.starts_with("traces_service_graph_request_total{") {
+                false
+            } else if line.starts_with("traces_service_graph_request_failed_total{") {
+                true
+            } else {
+                continue;
+            };
+            let (Some(labels), Some(value)) = (
+                line.split_once('{')
+                    .and_then(|(_, rest)| rest.split_once('}'))
+                    .map(|(labels, _)| labels),
+                line.rsplit_once('}')
+                    .and_then(|(_, value)| value.split_whitespace().next())
+                    .and_then(|value| value.parse::<f64>().ok()),
+            ) else {
+                continue;
+            };
+            let (Some(client), Some(server)) = (label(labels, "client"), label(labels, "server"))
+            else {
+                continue;
+            };
+            let edge = edges.entry((client, server)).or_default();
+            if failed {
+                edge.1 += value as u64;
+            } else {
+                edge.0 += value as u64;
+            }
+        }
+    }
+    if edges.is_empty() {
+        return Err(anyhow::anyhow!("The service graph holds no edges"));
+    }
+
+    let observed_at = Utc::now();
+    let mut skipped = 0;
+    let mut interactions = Vec::new();
+    for ((client, server), (calls, errors)) in edges {
+        let (other, direction) = if client == service && server != service {
+            (server, InteractionDirection::Outgoing)
+        } else if server == service && client != service {
+            (client, InteractionDirection::Incoming)
+        } else {
+            skipped += 1;
+            continue;
+        };
+        interactions.push(ExternalInteraction {
+            service: other,
+            direction,
+            calls,
+            errors,
+            operations: Vec::new(),
+            avg_latency_ms: None,
+            source: InteractionSource::ServiceGraph,
+            observed_at,
+        });
+    }
+    Ok((interactions, skipped))
+}
+
+// Value of `name="value"` in a Prometheus label set
+fn label(labels: &str, name: &str) -> Option<String> {
+    labels.split(',').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key.trim() == name).then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Outgoing requests in a JSON-lines access log, per service called. Lines that aren't
+/// JSON objects or name no service are counted as skipped.
+pub fn parse_access_log(content: &str) -> Result<(Vec<ExternalInteraction>, usize)> {
+    #[derive(Default)]
+    struct Tally {
+        calls: u64,
+        errors: u64,
+        operations: HashMap<String, u64>,
+        latency_total: f64,
+        latency_count: u64,
+    }
+
+    let mut tallies: BTreeMap<String, Tally> = BTreeMap::new();
+    let mut skipped = 0;
+    for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let Ok(serde_json::Value::Object(entry)) = serde_json::from_str(line) else {
+            skipped += 1;
+            continue;
+        };
+        let field = |names: &[&str]| names.iter().find_map(|name| entry.get(*name));
+        let Some(peer) = field(PEER_FIELDS)
+            .and_then(|v| v.as_str())
+            .filter(|peer| !peer.is_empty() && *peer != "-")
+        else {
+            skipped += 1;
+            continue;
+        };
+        let tally = tallies.entry(peer.to_string()).or_default();
+        tally.calls += 1;
+
+        let status = field(STATUS_FIELDS).and_then(|v| {
+            v.as_u64()
+                .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+        });
+        if status.is_some_and(|status| status >= 500) {
+            tally.errors += 1;
+        }
+        if let Some(duration) = field(DURATION_FIELDS).and_then(|v| v.as_f64()) {
+            tally.latency_total += duration;
+            tally.latency_count += 1;
+        }
+        let method = field(METHOD_FIELDS).and_then(|v| v.as_str());
+        let path = field(PATH_FIELDS).and_then(|v| v.as_str());
+        let operation = match (method, path) {
+            (Some(method), Some(path)) => Some(format!("{method} {}", normalize_path(path))),
+            (None, Some(path)) => Some(normalize_path(path)),
+            (Some(method), None) => Some(method.to_string()),
+            (None, None) => None,
+        };
+        if let Some(operation) = operation {
+            *tally.operations.entry(operation).or_insert(0) += 1;
+        }
+    }
+    if tallies.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No access log line names the service it called (expected one of: {})",
+            PEER_FIELDS.join(", ")
+        ));
+    }
+
+    let observed_at = Utc::now();
+    let interactions = tallies
+        .into_iter()
+        .map(|(service, tally)| {
+            let mut operations: Vec<(String, u64)> = tally.operations.into_iter().collect();
+            operations.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            ExternalInteraction {
+                service,
+                direction: InteractionDirection::Outgoing,
+                calls: tally.calls,
+                errors: tally.errors,
+                operations: operations
+                    .into_iter()
+                    .take(MAX_OPERATIONS)
+                    .map(|(operation, _)| operation)
+                    .collect(),
+                avg_latency_ms: (tally.latency_count > 0).then(|| {
+                    (tally.latency_total / tally.latency_count as f64 * 10.0).round() / 10.0
+                }),
+                source: InteractionSource::AccessLog,
+                observed_at,
+            }
+        })
+        .collect();
+    Ok((interactions, skipped))
+}
+
+// `/users/42/orders?page=2` -> `/users/{id}/orders`: ids and query strings would make
+// every request its own operation
+fn normalize_path(path: &str) -> String {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    path.split('/')
+        .map(|segment| {
+            let id_like = !segment.is_empty()
+                && (segment.chars().all(|c| c.is_ascii_digit())
+                    || (segment.len() >= 16
+                        && segment.chars().all(|c| c.is_ascii_hexdigit() || c == '-')));
+            if id_like {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Replace the interactions the matrix has from `source` with `interactions`; those from
+/// the other source stay
+pub fn merge_interactions(
+    matrix: &mut ProjectMatrix,
+    source: InteractionSource,
+    interactions: Vec<ExternalInteraction>,
+) {
+    let recorded = &mut matrix.project_info.external_interactions;
+    recorded.retain(|interaction| interaction.source != source);
+    recorded.extend(interactions);
+    recorded.sort_by(|a, b| {
+        (a.direction, &a.service)
+            .cmp(&(b.direction, &b.service))
+            .then_with(|| b.calls.cmp(&a.calls))
+    });
+}
+
+/// What an import recorded
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct InteractionImport {
+    pub downstream: Vec<String>,
+    pub upstream: Vec<String>,
+    pub calls: u64,
+    // Log lines naming no service, or graph edges not involving this project
+    pub skipped: usize,
+}
+
+impl InteractionImport {
+    pub fn new(interactions: &[ExternalInteraction], skipped: usize) -> Self {
+        let names = |direction| {
+            interactions
+                .iter()
+                .filter(|i| i.direction == direction)
+                .map(|i| i.service.clone())
+                .collect()
+        };
+        Self {
+            downstream: names(InteractionDirection::Outgoing),
+            upstream: names(InteractionDirection::Incoming),
+            calls: interactions.iter().map(|i| i.calls).sum(),
+            skipped,
+        }
+    }
+}
@@ -0,0 +1,420 @@
+// src/core/lint.rs - Consistency checks for a matrix, and repairs for what can be
+// repaired (`csd matrix lint`)
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+use crate::core::matrix::{PluginUsage, ProjectMatrix};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    // A relationship endpoint that names no file in the matrix
+    DanglingRelationship,
+    // Several keys that are the same path once normalized
+    DuplicatePath,
+    MissingHash,
+    // A text file with content but no tokens, usually a plugin not reporting token_info
+    ZeroTokens,
+    // Fields that contradict each other, such as totals that don't add up
+    Schema,
+}
+
+impl LintKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintKind::DanglingRelationship => "dangling relationship",
+            LintKind::DuplicatePath => "duplicate path",
+            LintKind::MissingHash => "missing hash",
+            LintKind::ZeroTokens => "zero tokens",
+            LintKind::Schema => "schema",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintIssue {
+    pub kind: LintKind,
+    // The file the issue is about, or a relationship's source file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    pub message: String,
+    // `fix_matrix` repairs it
+    pub fixable: bool,
+}
+
+impl LintIssue {
+    fn new(kind: LintKind, path: Option<&Path>, message: String, fixable: bool) -> Self {
+        Self {
+            kind,
+            path: path.map(Path::to_path_buf),
+            message,
+            fixable,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LintReport {
+    pub issues: Vec<LintIssue>,
+    // Issues `fix_matrix` repaired; the remaining ones are in `issues`
+    pub fixed: usize,
+}
+
+impl LintReport {
+    pub fn fixable(&self) -> usize {
+        self.issues.iter().filter(|i| i.fixable).count()
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn print_summary(&self) {
+        println!("🧹 Matrix lint");
+        for issue in &self.issues {
+            let icon = if issue.fixable { "🔧" } else { "❌" };
+            match &issue.path {
+                Some(path) => println!(
+                    "   {icon} {} {}: {}",
+                    issue.kind.as_str(),
+                    path.display(),
+                    issue.message
+                ),
+                None => println!("   {icon} {}: {}", issue.kind.as_str(), issue.message),
+            }
+        }
+        if self.fixed > 0 {
+            println!("\n   Fixed {} issues", self.fixed);
+        }
+        if self.is_clean() {
+            println!("   ✅ No issues");
+        } else {
+            println!(
+                "\n   {} issues, {} fixable with --fix",
+                self.issues.len(),
+                self.fixable()
+            );
+        }
+    }
+}
+
+/// Check a matrix for dangling relationship endpoints, duplicate paths, missing hashes,
+/// files without tokens and inconsistent fields. Issues are sorted by kind, then path.
+pub fn lint_matrix(matrix: &ProjectMatrix) -> LintReport {
+    let mut issues = Vec::new();
+    let keys = KeyIndex::new(matrix);
+
+    let mut paths: Vec<&PathBuf> = matrix.files.keys().collect();
+    paths.sort();
+    for key in paths {
+        let file = &matrix.files[key];
+        let canonical = keys.canonical(key);
+        if canonical != key {
+            issues.push(LintIssue::new(
+                LintKind::DuplicatePath,
+                Some(key),
+                format!("same file as {}", canonical.display()),
+                true,
+            ));
+            continue;
+        }
+        if file.path != *key {
+            issues.push(LintIssue::new(
+                LintKind::Schema,
+                Some(key),
+                format!(
+                    "keyed by a path other than its own ({})",
+                    file.path.display()
+                ),
+                true,
+            ));
+        }
+        if file.hash.is_empty() {
+            let readable = source_path(matrix, key).is_some();
+            issues.push(LintIssue::new(
+                LintKind::MissingHash,
+                Some(key),
+                if readable {
+                    "no content hash".to_string()
+                } else {
+                    "no content hash, and the file can't be read to compute one".to_string()
+                },
+                readable,
+            ));
+        }
+        if file.is_text && file.size_bytes > 0 && file.token_info.total_tokens == 0 {
+            issues.push(LintIssue::new(
+                LintKind::ZeroTokens,
+                Some(key),
+                format!(
+                    "{} bytes of text but no tokens; plugin {} may not report token_info",
+                    file.size_bytes, file.plugin
+                ),
+                false,
+            ));
+        }
+        for element in &file.elements {
+            if element.line_start > element.line_end {
+                issues.push(LintIssue::new(
+                    LintKind::Schema,
+                    Some(key),
+                    format!(
+                        "element {} ends (line {}) before it starts (line {})",
+                        element.qualified_name(),
+                        element.line_end,
+                        element.line_start
+                    ),
+                    false,
+                ));
+            }
+        }
+        if !matrix
+            .metadata
+            .plugins_used
+            .iter()
+            .any(|p| *p.name == *file.plugin)
+        {
+            issues.push(LintIssue::new(
+                LintKind::Schema,
+                Some(key),
+                format!("plugin {} is missing from plugins_used", file.plugin),
+                true,
+            ));
+        }
+    }
+
+    // Plugins name files by relative path while the scanner may key them as `./src/..`,
+    // so endpoints only dangle when no spelling of them is in the matrix
+    for r in &matrix.relationships {
+        for endpoint in [&r.from_file, &r.to_file] {
+            if keys.resolve(endpoint).is_none() {
+                issues.push(LintIssue::new(
+                    LintKind::DanglingRelationship,
+                    Some(&r.from_file),
+                    format!(
+                        "{} -> {}: {} is not in the matrix",
+                        r.from_file.display(),
+                        r.to_file.display(),
+                        endpoint.display()
+                    ),
+                    true,
+                ));
+            }
+        }
+        if !(0.0..=1.0).contains(&r.strength) {
+            issues.push(LintIssue::new(
+                LintKind::Schema,
+                Some(&r.from_file),
+                format!(
+                    "{} -> {}: strength {} is outside 0.0 to 1.0",
+                    r.from_file.display(),
+                    r.to_file.display(),
+                    r.strength
+                ),
+                true,
+            ));
+        }
+    }
+
+    let totals = Totals::of(matrix);
+    for (field, recorded, actual) in [
+        (
+            "total_files",
+            matrix.metadata.total_files as u64,
+            totals.files as u64,
+        ),
+        (
+            "total_size_bytes",
+            matrix.metadata.total_size_bytes,
+            totals.size_bytes,
+        ),
+        ("total_tokens", matrix.metadata.total_tokens, totals.tokens),
+    ] {
+        if recorded != actual {
+            issues.push(LintIssue::new(
+                LintKind::Schema,
+                None,
+                format!("metadata.{field} is {recorded}, but the files add up to {actual}"),
+                true,
+            ));
+        }
+    }
+
+    issues.sort_by(|a, b| (a.kind, &a.path).cmp(&(b.kind, &b.path)));
+    LintReport { issues, fixed: 0 }
+}
+
+/// Repair what `lint_matrix` reports as fixable: duplicates are merged into one key
+/// (and edges that become duplicates with them), dangling relationships dropped, hashes
+/// computed from the files on disk, strengths clamped and totals recounted. Returns the
+/// issues left afterwards.
+pub fn fix_matrix(matrix: &mut ProjectMatrix) -> LintReport {
+    let before = lint_matrix(matrix).issues.len();
+
+    let keys = KeyIndex::new(matrix);
+    let merged: HashMap<PathBuf, PathBuf> = matrix
+        .files
+        .keys()
+        .filter(|key| keys.canonical(key) != key.as_path())
+        .map(|key| (key.clone(), keys.canonical(key).to_path_buf()))
+        .collect();
+    matrix.files.retain(|key, _| !merged.contains_key(key));
+    for (key, file) in &mut matrix.files {
+        file.path = key.clone();
+    }
+
+    matrix.relationships.retain_mut(|r| {
+        if keys.resolve(&r.from_file).is_none() || keys.resolve(&r.to_file).is_none() {
+            return false;
+        }
+        for endpoint in [&mut r.from_file, &mut r.to_file] {
+            if let Some(key) = merged.get(endpoint.as_path()) {
+                *endpoint = key.clone();
+            }
+        }
+        r.strength = if r.strength.is_nan() {
+            1.0
+        } else {
+            r.strength.clamp(0.0, 1.0)
+        };
+        true
+    });
+    matrix.dedup_relationships();
+
+    let missing_hashes: Vec<(PathBuf, PathBuf)> = matrix
+        .files
+        .iter()
+        .filter(|(_, file)| file.hash.is_empty())
+        .filter_map(|(key, _)| Some((key.clone(), source_path(matrix, key)?)))
+        .collect();
+    // With the algorithm the rest of the matrix was hashed with
+    let algorithm = matrix.metadata.hash_algorithm;
+    for (key, source) in missing_hashes {
+        if let Ok(hash) = algorithm.hash_file(&source) {
+            if let Some(file) = matrix.files.get_mut(&key) {
+                file.hash = hash;
+            }
+        }
+    }
+
+    let plugins: Vec<String> = matrix
+        .files
+        .values()
+        .map(|f| f.plugin.to_string())
+        .collect();
+    for plugin in plugins {
+        let used = &mut matrix.metadata.plugins_used;
+        if let Err(index) = used.binary_search_by(|p| p.name.as_str().cmp(&plugin)) {
+            used.insert(index, PluginUsage::named(&plugin));
+        }
+    }
+
+    let totals = Totals::of(matrix);
+    matrix.metadata.total_files = totals.files;
+    matrix.metadata.total_size_bytes = totals.size_bytes;
+    matrix.metadata.total_tokens = totals.tokens;
+    matrix.invalidate_graph();
+
+    let mut report = lint_matrix(matrix);
+    report.fixed = before.saturating_sub(report.issues.len());
+    report
+}
+
+/// `path` with `.` and `..` components resolved lexically and backslashes read as
+/// separators, so `./src\\lib.rs` and `src/x/../lib.rs` are both `src/lib.rs`
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let unified = PathBuf::from(path.to_string_lossy().replace('\\', "/"));
+    let mut normalized = PathBuf::new();
+    for component in unified.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+// How relationship endpoints and duplicate keys map to the keys files are stored under
+struct KeyIndex {
+    // Normalized path -> the key that wins for it
+    by_normalized: BTreeMap<PathBuf, PathBuf>,
+    // Normalized relative path -> key, for endpoints that name files by relative path
+    by_relative: HashMap<PathBuf, PathBuf>,
+}
+
+impl KeyIndex {
+    fn new(matrix: &ProjectMatrix) -> Self {
+        let mut keys: Vec<&PathBuf> = matrix.files.keys().collect();
+        // Prefer keys that are already normalized, then the first in order
+        keys.sort_by_key(|key| (normalize_path(key) != **key, *key));
+
+        let mut by_normalized = BTreeMap::new();
+        let mut by_relative = HashMap::new();
+        for key in keys {
+            by_normalized
+                .entry(normalize_path(key))
+                .or_insert_with(|| key.clone());
+            by_relative
+                .entry(normalize_path(&matrix.files[key].relative_path))
+                .or_insert_with(|| key.clone());
+        }
+        Self {
+            by_normalized,
+            by_relative,
+        }
+    }
+
+    // The key that `key` is a duplicate of, or `key` itself
+    fn canonical<'a>(&'a self, key: &'a Path) -> &'a Path {
+        self.by_normalized
+            .get(&normalize_path(key))
+            .map_or(key, PathBuf::as_path)
+    }
+
+    // The key of the file an endpoint names, by key or by relative path
+    fn resolve(&self, endpoint: &Path) -> Option<&PathBuf> {
+        let normalized = normalize_path(endpoint);
+        self.by_normalized
+            .get(&normalized)
+            .or_else(|| self.by_relative.get(&normalized))
+    }
+}
+
+struct Totals {
+    files: usize,
+    size_bytes: u64,
+    tokens: u64,
+}
+
+impl Totals {
+    fn of(matrix: &ProjectMatrix) -> Self {
+        Self {
+            files: matrix.files.len(),
+            size_bytes: matrix.files.values().map(|f| f.size_bytes).sum(),
+            tokens: matrix
+                .files
+                .values()
+                .map(|f| f.token_info.total_tokens)
+                .sum(),
+        }
+    }
+}
+
+// Where a file's content can be read: its key or its relative path, under the project
+// root unless absolute
+fn source_path(matrix: &ProjectMatrix, key: &Path) -> Option<PathBuf> {
+    let file = matrix.files.get(key)?;
+    let root = &matrix.metadata.project_root;
+    [root.join(key), root.join(&file.relative_path)]
+        .into_iter()
+        .find(|path| path.is_file())
+}
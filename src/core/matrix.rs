@@ -1,14 +1,29 @@
 // src/core/matrix.rs - Enhanced version with token counting and entrypoint detection
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use log::{debug, info};
+use log::{debug, info, warn};
 use petgraph::graph::NodeIndex;
 use petgraph::visit::EdgeRef;
 use petgraph::{Directed, Graph};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::value::RawValue;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
+use crate::core::categories::{FileCategory, FileClassifier};
+use crate::core::contracts::{Contract, Route};
+use crate::core::coverage::Coverage;
+use crate::core::data_model::DataModel;
+use crate::core::deployments::Deployment;
+use crate::core::glossary::GlossaryTerm;
+use crate::core::interactions::ExternalInteraction;
+use crate::utils::cache::CacheLock;
+use crate::utils::config::ScanLevel;
+use crate::utils::file_utils::{backup_path, write_atomic};
+use crate::utils::hashing::HashAlgorithm;
+use crate::utils::intern::IStr;
+use crate::utils::profile::{self, Stage};
+
 pub type ProjectGraph = Graph<FileNode, RelationshipEdge, Directed>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +36,10 @@ pub struct ProjectMatrix {
     // NEW: Project structure analysis
     pub project_info: ProjectInfo,
 
+    // Tables from migrations and ORM models, and the files touching them
+    #[serde(default, skip_serializing_if = "DataModel::is_empty")]
+    pub data_model: DataModel,
+
     // Transient data - rebuilt on load
     #[serde(skip)]
     graph: Option<ProjectGraph>,
@@ -36,7 +55,179 @@ pub struct ProjectMetadata {
     pub total_files: usize,
     pub total_size_bytes: u64,
     pub total_tokens: u64, // NEW: Total estimated tokens across all files
-    pub plugins_used: Vec<String>,
+    // Sorted by name
+    pub plugins_used: Vec<PluginUsage>,
+    // How the scan went; absent in matrices written before it was recorded
+    #[serde(default)]
+    pub scan_stats: ScanStats,
+    // Directories scanned into this matrix when there was more than one; relative
+    // paths of their files start with the root's name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<ScanRoot>,
+    // What computed the file hashes; matrices written before the choice existed used SHA-256
+    #[serde(default)]
+    pub hash_algorithm: HashAlgorithm,
+    // Structure scans leave out what plugins and transforms report
+    #[serde(default, skip_serializing_if = "ScanLevel::is_default")]
+    pub scan_level: ScanLevel,
+}
+
+impl ProjectMetadata {
+    pub fn plugin_names(&self) -> Vec<&str> {
+        self.plugins_used.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    /// Record the version `name` reported and where it was loaded from, if its analysis
+    /// is in this matrix
+    pub fn pin_plugin(&mut self, name: &str, version: &str, source: String) {
+        if let Some(usage) = self.plugins_used.iter_mut().find(|p| p.name == name) {
+            usage.version = Some(version.to_string());
+            usage.source = Some(source);
+        }
+    }
+}
+
+/// A plugin whose analysis went into a matrix, with the version it reported and where it
+/// was loaded from. Matrices written before versions were recorded list bare names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "PluginUsageRecord")]
+pub struct PluginUsage {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    // `PluginSource::describe`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl PluginUsage {
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: None,
+            source: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PluginUsageRecord {
+    Name(String),
+    Entry {
+        name: String,
+        #[serde(default)]
+        version: Option<String>,
+        #[serde(default)]
+        source: Option<String>,
+    },
+}
+
+impl From<PluginUsageRecord> for PluginUsage {
+    fn from(record: PluginUsageRecord) -> Self {
+        match record {
+            PluginUsageRecord::Name(name) => Self::named(&name),
+            PluginUsageRecord::Entry {
+                name,
+                version,
+                source,
+            } => Self {
+                name,
+                version,
+                source,
+            },
+        }
+    }
+}
+
+/// One of several directories scanned into a single matrix
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanRoot {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl ScanRoot {
+    /// Name each root after its directory, suffixing repeats (`app`, `app-2`)
+    pub fn named(paths: &[PathBuf]) -> Vec<ScanRoot> {
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        paths
+            .iter()
+            .map(|path| {
+                let base = std::path::absolute(path)
+                    .ok()
+                    .and_then(|absolute| {
+                        absolute
+                            .components()
+                            .rfind(|c| !matches!(c, std::path::Component::CurDir))
+                            .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    })
+                    .filter(|name| !name.is_empty() && name != "/")
+                    .unwrap_or_else(|| "root".to_string());
+                let count = seen.entry(base.clone()).or_insert(0);
+                *count += 1;
+                let name = match *count {
+                    1 => base,
+                    n => format!("{base}-{n}"),
+                };
+                ScanRoot {
+                    name,
+                    path: path.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Timing and outcome counts for the scan that produced a matrix
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScanStats {
+    // Wall-clock time from walking the tree to the finalized matrix
+    pub duration_ms: u64,
+    // Files whose analysis was reused from an earlier scan, or had to be analyzed
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+    // Files whose token estimates were reused from the token cache, or computed
+    #[serde(default)]
+    pub token_cache_hits: usize,
+    #[serde(default)]
+    pub token_cache_misses: usize,
+    // Files a plugin failed on (or that couldn't be read); kept without analysis
+    pub failed_files: usize,
+    // Files left out by ignore patterns, the size limit or unreadable metadata
+    pub skipped_files: usize,
+    // Files kept without analysis because the scan reached `scanning.max_duration_minutes`
+    #[serde(default)]
+    pub skipped_deadline: usize,
+    // Files and directories left out because reading them was not permitted, relative
+    // to the project root when under it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_permission: Vec<PathBuf>,
+    pub plugins: BTreeMap<String, PluginScanStats>,
+}
+
+/// Per-plugin share of a scan
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PluginScanStats {
+    pub files: usize,
+    pub failed: usize,
+    pub time_ms: u64,
+    // Only for plugins whose processes were pooled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<PluginPoolStats>,
+}
+
+/// How a plugin's pooled processes were used during a scan
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginPoolStats {
+    // Processes started, and messages answered by one already running
+    pub spawned: usize,
+    pub reused: usize,
+    // Most processes running at once
+    pub peak: usize,
+    // Shut down for sitting idle, or for exiting or failing a message
+    pub retired_idle: usize,
+    pub unhealthy: usize,
 }
 
 // NEW: Project-level information
@@ -46,6 +237,44 @@ pub struct ProjectInfo {
     pub project_type: ProjectType,
     pub main_language: String,
     pub token_summary: TokenSummary,
+
+    // Logical groupings of files, from package manifests or community detection
+    #[serde(default)]
+    pub components: Vec<Component>,
+
+    // Domain terms ranked by TF-IDF over identifiers and summaries, for the glossary
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub glossary_candidates: Vec<GlossaryTerm>,
+
+    // Services seen talking to this project at runtime, from `csd services import`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_interactions: Vec<ExternalInteraction>,
+
+    // Images, services and workloads from Dockerfiles, Compose, Kubernetes and Terraform
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deployments: Vec<Deployment>,
+
+    // OpenAPI specs and .proto files, with the files handling their operations
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contracts: Vec<Contract>,
+
+    // HTTP routes declared in code, checked against the contracts by `csd quality`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<Route>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Component {
+    pub name: String,
+    pub files: Vec<PathBuf>,
+    pub source: ComponentSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentSource {
+    Package,  // Bounded by a manifest (Cargo.toml, package.json, ...)
+    Detected, // Proposed by community detection over the dependency graph
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,16 +310,32 @@ pub struct FileNode {
     pub relative_path: PathBuf,
     pub hash: String,
     pub size_bytes: u64,
-    pub plugin: String,
-    pub language: Option<String>,
+    pub plugin: IStr,
+    pub language: Option<IStr>,
     pub is_text: bool,
     pub elements: Vec<CodeElement>,
     pub imports: Vec<Import>,
-    pub exports: Vec<String>,
+    pub exports: Vec<Export>,
     pub file_summary: Option<String>,
 
     // NEW: Token information
     pub token_info: TokenInfo,
+
+    // Scan details such as `plugin_confidence` when several plugins claimed the file
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub metadata: serde_json::Value,
+
+    // Only part of the file was analyzed because it exceeded `scanning.max_file_tokens`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+
+    // Source, test, docs, ...; assigned by the scanner from `categories` rules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<FileCategory>,
+
+    // From the last `csd coverage import`; absent when the report didn't cover the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<Coverage>,
 }
 
 // NEW: Token information for files and elements
@@ -106,6 +351,13 @@ pub struct TokenInfo {
 pub struct CodeElement {
     pub element_type: ElementType,
     pub name: String,
+    // Name qualified by the containers it's defined in, e.g. `Config::load` or
+    // `Config.load`, in the file's language's own notation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qualified_name: Option<String>,
+    // Qualified name of the class, impl or trait the element is defined in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
     pub signature: Option<String>,
     pub line_start: u32,
     pub line_end: u32,
@@ -116,6 +368,209 @@ pub struct CodeElement {
 
     // NEW: Token count for this element
     pub tokens: u64,
+
+    // Deterministic ID (see `element_id`); filled in when the file is added to a matrix
+    #[serde(default)]
+    pub element_id: String,
+
+    // Of the element's lines, from the last `csd coverage import`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<Coverage>,
+}
+
+impl CodeElement {
+    /// Name qualified by its container when the plugin reports one (e.g. `Class.method`).
+    /// Older plugins reported it in metadata.
+    pub fn qualified_name(&self) -> &str {
+        self.qualified_name
+            .as_deref()
+            .or_else(|| self.metadata.get("qualified_name").and_then(|v| v.as_str()))
+            .unwrap_or(&self.name)
+    }
+
+    /// Whether `name` refers to this element: a qualified name such as `Config::load`
+    /// or `Config.load` (either separator, matched against the end of this element's
+    /// qualified name), or a bare name
+    pub fn matches_name(&self, name: &str) -> bool {
+        let wanted = name_segments(name);
+        let own = name_segments(self.qualified_name());
+        match wanted.as_slice() {
+            [bare] => *bare == self.name,
+            _ => own.ends_with(&wanted),
+        }
+    }
+}
+
+// `a::b.c` -> ["a", "b", "c"]
+fn name_segments(name: &str) -> Vec<&str> {
+    name.split("::")
+        .flat_map(|part| part.split('.'))
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// Stable identifier for a code element: a hash of its relative path, qualified name and
+/// signature, so it survives line-number shifts but changes when the element is renamed,
+/// moved or re-signed
+pub fn element_id(relative_path: &Path, qualified_name: &str, signature: Option<&str>) -> String {
+    use sha2::{Digest, Sha256};
+
+    let path = relative_path.to_string_lossy().replace('\\', "/");
+    let mut hasher = Sha256::new();
+    hasher.update(path.as_bytes());
+    hasher.update([0]);
+    hasher.update(qualified_name.as_bytes());
+    hasher.update([0]);
+    hasher.update(signature.unwrap_or_default().as_bytes());
+    let digest = format!("{:x}", hasher.finalize());
+    digest[..16].to_string()
+}
+
+/// Read a matrix file, falling back to `matrix.json.bak` when the file is truncated or
+/// otherwise unreadable
+async fn read_matrix_file<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    async fn read<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+        let json = {
+            let _load = profile::span(Stage::Load);
+            tokio::fs::read_to_string(path).await?
+        };
+        let _serde = profile::span(Stage::Serde);
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    // Best effort: a matrix in a read-only location can still be read without the lock
+    let _lock = CacheLock::shared(path).await.ok();
+    match read(path).await {
+        Ok(matrix) => Ok(matrix),
+        Err(e) => {
+            let backup = backup_path(path);
+            if !backup.exists() {
+                return Err(e);
+            }
+            warn!(
+                "Matrix {} is unreadable ({e}); falling back to backup {}",
+                path.display(),
+                backup.display()
+            );
+            read(&backup).await.map_err(|backup_err| {
+                anyhow::anyhow!(
+                    "Matrix {} is unreadable ({e}) and so is its backup ({backup_err}). Run 'csd init' to rebuild it.",
+                    path.display()
+                )
+            })
+        }
+    }
+}
+
+/// On-disk matrix layout with file nodes left as raw JSON
+#[derive(Deserialize)]
+struct RawMatrix {
+    metadata: ProjectMetadata,
+    files: HashMap<PathBuf, Box<RawValue>>,
+    relationships: Vec<Relationship>,
+    external_dependencies: Vec<ExternalDependency>,
+    project_info: ProjectInfo,
+    #[serde(default)]
+    data_model: DataModel,
+}
+
+/// Lazily loaded matrix returned by `ProjectMatrix::open`. File nodes are parsed on first
+/// access and cached, so commands that only touch edges or a few files stay cheap on
+/// huge matrices.
+pub struct MatrixHandle {
+    pub metadata: ProjectMetadata,
+    pub project_info: ProjectInfo,
+    pub relationships: Vec<Relationship>,
+    pub external_dependencies: Vec<ExternalDependency>,
+    pub data_model: DataModel,
+    raw_files: HashMap<PathBuf, Box<RawValue>>,
+    loaded: HashMap<PathBuf, FileNode>,
+}
+
+impl MatrixHandle {
+    /// Paths of all files in the matrix, without parsing them
+    pub fn file_paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.raw_files.keys()
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.raw_files.len()
+    }
+
+    pub fn contains_file(&self, path: &Path) -> bool {
+        self.raw_files.contains_key(path)
+    }
+
+    /// Number of file nodes parsed so far
+    pub fn loaded_count(&self) -> usize {
+        self.loaded.len()
+    }
+
+    /// Parse (once) and return a single file node
+    pub fn file(&mut self, path: &Path) -> Result<Option<&FileNode>> {
+        if !self.loaded.contains_key(path) {
+            let Some(raw) = self.raw_files.get(path) else {
+                return Ok(None);
+            };
+            let mut file_node: FileNode = serde_json::from_str(raw.get())?;
+            assign_element_ids(&mut file_node);
+            self.loaded.insert(path.to_path_buf(), file_node);
+        }
+        Ok(self.loaded.get(path))
+    }
+
+    /// Relationships matching a filter, in matrix order. File nodes stay unparsed, so
+    /// test files are told apart by their paths.
+    pub fn query_relationships(&self, filter: &RelationshipFilter) -> Vec<&Relationship> {
+        self.relationships
+            .iter()
+            .filter(|r| filter.matches(r))
+            .collect()
+    }
+
+    /// Parse every remaining file node and build a full matrix
+    pub fn into_matrix(mut self) -> Result<ProjectMatrix> {
+        let mut matrix = ProjectMatrix::new(self.metadata.project_root.clone());
+        for (path, raw) in self.raw_files {
+            let file_node = match self.loaded.remove(&path) {
+                Some(file_node) => file_node,
+                None => {
+                    let mut file_node: FileNode = serde_json::from_str(raw.get())?;
+                    assign_element_ids(&mut file_node);
+                    file_node
+                }
+            };
+            matrix.files.insert(path, file_node);
+        }
+        matrix.metadata = self.metadata;
+        matrix.project_info = self.project_info;
+        matrix.relationships = self.relationships;
+        matrix.external_dependencies = self.external_dependencies;
+        matrix.data_model = self.data_model;
+        matrix.rebuild_graph();
+        Ok(matrix)
+    }
+}
+
+/// How far `ProjectMatrix::subset` expands beyond the requested files
+#[derive(Debug, Clone, Default)]
+pub struct SubsetOptions {
+    /// Levels of files the requested files depend on
+    pub dependency_depth: usize,
+    /// Levels of files that depend on the requested files
+    pub dependent_depth: usize,
+    /// Stop adding expansion files once their tokens would exceed this budget
+    pub max_tokens: Option<u64>,
+}
+
+/// Element-level changes between two scans, keyed by `element_id`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ElementDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Same element, different lines
+    pub moved: Vec<String>,
+    pub unchanged: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -134,13 +589,81 @@ pub enum ElementType {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Import {
-    pub module: String,
+    pub module: IStr,
     pub items: Vec<String>,
     pub alias: Option<String>,
     pub line_number: u32,
     pub import_type: ImportType,
 }
 
+/// A name a file makes available to other files. `alias` is set when the symbol is
+/// exported under another name (`pub use a::B as C`, `export { x as y }`), and `source`
+/// when it's re-exported from another project file. Matrices and plugins that predate
+/// export records list bare names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "ExportRecord")]
+pub struct Export {
+    // The symbol's name where it's defined
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    // Project-relative file the symbol is re-exported from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<PathBuf>,
+}
+
+impl Export {
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: None,
+            alias: None,
+            source: None,
+        }
+    }
+
+    /// Name other files refer to the symbol by
+    pub fn exported_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ExportRecord {
+    Name(String),
+    Entry {
+        name: String,
+        #[serde(default)]
+        kind: Option<String>,
+        #[serde(default)]
+        alias: Option<String>,
+        #[serde(default)]
+        source: Option<PathBuf>,
+    },
+}
+
+impl From<ExportRecord> for Export {
+    fn from(record: ExportRecord) -> Self {
+        match record {
+            ExportRecord::Name(name) => Self::named(&name),
+            ExportRecord::Entry {
+                name,
+                kind,
+                alias,
+                source,
+            } => Self {
+                name,
+                kind,
+                alias,
+                source,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ImportType {
     Standard,
@@ -157,6 +680,50 @@ pub struct Relationship {
     pub details: String,
     pub line_number: Option<u32>,
     pub strength: f32,
+
+    // Provenance: which plugin reported this edge and when
+    #[serde(default)]
+    pub source_plugin: Option<IStr>,
+    #[serde(default)]
+    pub detected_at: Option<DateTime<Utc>>,
+
+    // Element-level endpoints (e.g. function names) when the edge is finer than a file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_element: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_element: Option<String>,
+
+    // Calls between the endpoints seen in imported traces; unset for edges the traces
+    // never exercised
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime_calls: Option<u64>,
+}
+
+impl Relationship {
+    /// Convert an edge reported by a plugin, recording which plugin reported it and when.
+    /// Unknown relationship types fall back to `Import`.
+    pub fn from_plugin(
+        relationship: crate::plugins::interface::Relationship,
+        source_plugin: Option<IStr>,
+        detected_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            from_file: PathBuf::from(relationship.from_file),
+            to_file: PathBuf::from(relationship.to_file),
+            relationship_type: relationship
+                .relationship_type
+                .parse()
+                .unwrap_or(RelationshipType::Import),
+            details: relationship.details,
+            line_number: relationship.line_number,
+            strength: relationship.strength,
+            source_plugin,
+            detected_at: Some(detected_at),
+            from_element: relationship.from_element,
+            to_element: relationship.to_element,
+            runtime_calls: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -168,6 +735,128 @@ pub enum RelationshipType {
     Test,
     Documentation,
     Build,
+    // A handler implementing an operation of an OpenAPI spec or .proto service
+    Contract,
+}
+
+impl std::str::FromStr for RelationshipType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "import" => Ok(RelationshipType::Import),
+            "call" => Ok(RelationshipType::Call),
+            "inheritance" => Ok(RelationshipType::Inheritance),
+            "configuration" => Ok(RelationshipType::Configuration),
+            "test" => Ok(RelationshipType::Test),
+            "documentation" => Ok(RelationshipType::Documentation),
+            "build" => Ok(RelationshipType::Build),
+            "contract" => Ok(RelationshipType::Contract),
+            other => Err(anyhow::anyhow!("Unknown relationship type: {other}")),
+        }
+    }
+}
+
+/// Criteria for selecting relationships; empty fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct RelationshipFilter {
+    pub from_file: Option<PathBuf>,
+    pub to_file: Option<PathBuf>,
+    pub relationship_types: Vec<RelationshipType>,
+    pub source_plugin: Option<String>,
+    pub min_strength: Option<f32>,
+    // Drop test relationships and edges from or to test files
+    pub exclude_tests: bool,
+}
+
+impl RelationshipFilter {
+    /// Whether `relationship` is selected, telling test files apart by the built-in
+    /// category rules
+    pub fn matches(&self, relationship: &Relationship) -> bool {
+        self.matches_with(relationship, |path| {
+            FileClassifier::builtin().classify(path) == FileCategory::Test
+        })
+    }
+
+    /// `matches`, with `is_test` deciding which files `exclude_tests` drops edges of
+    pub fn matches_with(
+        &self,
+        relationship: &Relationship,
+        is_test: impl Fn(&Path) -> bool,
+    ) -> bool {
+        let touches_tests = || {
+            relationship.relationship_type == RelationshipType::Test
+                || is_test(&relationship.from_file)
+                || is_test(&relationship.to_file)
+        };
+        self.min_strength
+            .is_none_or(|min| relationship.strength >= min)
+            && !(self.exclude_tests && touches_tests())
+            && self
+                .from_file
+                .as_ref()
+                .is_none_or(|from| &relationship.from_file == from)
+            && self
+                .to_file
+                .as_ref()
+                .is_none_or(|to| &relationship.to_file == to)
+            && (self.relationship_types.is_empty()
+                || self
+                    .relationship_types
+                    .contains(&relationship.relationship_type))
+            && self
+                .source_plugin
+                .as_ref()
+                .is_none_or(|plugin| relationship.source_plugin.as_deref() == Some(plugin.as_str()))
+    }
+}
+
+/// File and name that the symbol `file` exports as `name` is defined under, following
+/// re-export chains. None when `file` defines it itself or doesn't export it.
+fn reexport_origin(
+    files: &HashMap<PathBuf, FileNode>,
+    file: &Path,
+    name: &str,
+) -> Option<(PathBuf, String)> {
+    let mut current = (file.to_path_buf(), name.to_string());
+    let mut seen = HashSet::new();
+    while let Some(export) = files.get(&current.0).and_then(|node| {
+        node.exports
+            .iter()
+            .find(|export| export.source.is_some() && export.exported_name() == current.1)
+    }) {
+        // Re-export cycles end where they started
+        if !seen.insert(current.clone()) {
+            break;
+        }
+        current = (export.source.clone()?, export.name.clone());
+    }
+    (current.0 != file || current.1 != name).then_some(current)
+}
+
+// Files without a category (matrices written before categories existed, or paths
+// outside the matrix) are classified with the built-in rules
+fn is_test_file(files: &HashMap<PathBuf, FileNode>, path: &Path) -> bool {
+    files
+        .get(path)
+        .and_then(|file| file.category)
+        .unwrap_or_else(|| FileClassifier::builtin().classify(path))
+        == FileCategory::Test
+}
+
+// Package ecosystems whose dependencies a language's code uses
+fn language_ecosystems(language: &str) -> &'static [&'static str] {
+    match language.to_ascii_lowercase().as_str() {
+        "python" => &["pip", "pypi", "python", "poetry"],
+        "rust" => &["cargo"],
+        "javascript" | "typescript" => &["npm", "yarn", "pnpm"],
+        "go" => &["go"],
+        "java" | "kotlin" | "scala" => &["maven", "gradle"],
+        "ruby" => &["gem", "rubygems", "bundler"],
+        "php" => &["composer"],
+        "csharp" | "c#" => &["nuget"],
+        _ => &[],
+    }
 }
 
 // For the graph edges
@@ -178,13 +867,142 @@ pub struct RelationshipEdge {
     pub details: String,
 }
 
+/// A code element addressed by its file and name
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ElementRef {
+    pub file: PathBuf,
+    pub name: String,
+}
+
+impl std::fmt::Display for ElementRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}::{}", self.file.display(), self.name)
+    }
+}
+
+/// Subgraph of element-to-element edges, built from relationships that carry
+/// both `from_element` and `to_element`
+#[derive(Debug, Clone, Default)]
+pub struct ElementGraph {
+    graph: Graph<ElementRef, RelationshipEdge, Directed>,
+    node_indexes: HashMap<ElementRef, NodeIndex>,
+}
+
+impl ElementGraph {
+    pub fn from_relationships<'a>(
+        relationships: impl IntoIterator<Item = &'a Relationship>,
+    ) -> Self {
+        let mut element_graph = Self::default();
+        for relationship in relationships {
+            if let (Some(from_element), Some(to_element)) =
+                (&relationship.from_element, &relationship.to_element)
+            {
+                let from = element_graph.node(ElementRef {
+                    file: relationship.from_file.clone(),
+                    name: from_element.clone(),
+                });
+                let to = element_graph.node(ElementRef {
+                    file: relationship.to_file.clone(),
+                    name: to_element.clone(),
+                });
+                element_graph.graph.add_edge(
+                    from,
+                    to,
+                    RelationshipEdge {
+                        relationship_type: relationship.relationship_type.clone(),
+                        strength: relationship.strength,
+                        details: relationship.details.clone(),
+                    },
+                );
+            }
+        }
+        element_graph
+    }
+
+    fn node(&mut self, element: ElementRef) -> NodeIndex {
+        if let Some(&index) = self.node_indexes.get(&element) {
+            return index;
+        }
+        let index = self.graph.add_node(element.clone());
+        self.node_indexes.insert(element, index);
+        index
+    }
+
+    pub fn element_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.graph.edge_count()
+    }
+
+    /// Elements with an edge pointing at `element` (e.g. its callers)
+    pub fn callers(&self, element: &ElementRef) -> Vec<&ElementRef> {
+        self.neighbors(element, petgraph::Direction::Incoming)
+    }
+
+    /// Elements `element` points at (e.g. the functions it calls)
+    pub fn callees(&self, element: &ElementRef) -> Vec<&ElementRef> {
+        self.neighbors(element, petgraph::Direction::Outgoing)
+    }
+
+    fn neighbors(&self, element: &ElementRef, direction: petgraph::Direction) -> Vec<&ElementRef> {
+        let mut neighbors: Vec<&ElementRef> = match self.node_indexes.get(element) {
+            Some(&index) => self
+                .graph
+                .neighbors_directed(index, direction)
+                .filter_map(|n| self.graph.node_weight(n))
+                .collect(),
+            None => Vec::new(),
+        };
+        neighbors.sort();
+        neighbors.dedup();
+        neighbors
+    }
+
+    /// Every element that transitively reaches `element`; the set affected by changing it
+    pub fn impacted_by(&self, element: &ElementRef) -> Vec<&ElementRef> {
+        let Some(&start) = self.node_indexes.get(element) else {
+            return Vec::new();
+        };
+        let reversed = petgraph::visit::Reversed(&self.graph);
+        let mut bfs = petgraph::visit::Bfs::new(reversed, start);
+        let mut impacted = Vec::new();
+        while let Some(index) = bfs.next(reversed) {
+            if index != start {
+                impacted.extend(self.graph.node_weight(index));
+            }
+        }
+        impacted.sort();
+        impacted
+    }
+
+    /// Shortest chain of edges from `from` to `to`, both ends included
+    pub fn call_path(&self, from: &ElementRef, to: &ElementRef) -> Option<Vec<&ElementRef>> {
+        let &start = self.node_indexes.get(from)?;
+        let &goal = self.node_indexes.get(to)?;
+        let (_, path) = petgraph::algo::astar(&self.graph, start, |n| n == goal, |_| 1, |_| 0)?;
+        Some(
+            path.into_iter()
+                .filter_map(|index| self.graph.node_weight(index))
+                .collect(),
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalDependency {
     pub name: String,
     pub version: Option<String>,
-    pub ecosystem: String,
+    pub ecosystem: IStr,
     pub dependency_type: DependencyType,
     pub source_file: PathBuf,
+
+    // Provenance: which plugin reported this dependency and when
+    #[serde(default)]
+    pub source_plugin: Option<IStr>,
+    #[serde(default)]
+    pub detected_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -206,6 +1024,10 @@ impl ProjectMatrix {
                 total_size_bytes: 0,
                 total_tokens: 0,
                 plugins_used: Vec::new(),
+                scan_stats: ScanStats::default(),
+                roots: Vec::new(),
+                hash_algorithm: HashAlgorithm::default(),
+                scan_level: ScanLevel::default(),
             },
             files: HashMap::new(),
             relationships: Vec::new(),
@@ -222,15 +1044,24 @@ impl ProjectMatrix {
                     largest_file_tokens: 0,
                     largest_file_path: None,
                 },
+                components: Vec::new(),
+                glossary_candidates: Vec::new(),
+                external_interactions: Vec::new(),
+                deployments: Vec::new(),
+                contracts: Vec::new(),
+                routes: Vec::new(),
             },
+            data_model: DataModel::default(),
             graph: None,
             node_indexes: HashMap::new(),
         }
     }
 
-    pub fn add_file(&mut self, file_node: FileNode) {
+    pub fn add_file(&mut self, mut file_node: FileNode) {
         debug!("Adding file to matrix: {}", file_node.path.display());
 
+        assign_element_ids(&mut file_node);
+
         // Update metadata
         self.metadata.total_files += 1;
         self.metadata.total_size_bytes += file_node.size_bytes;
@@ -249,8 +1080,14 @@ impl ProjectMatrix {
                 Some(file_node.relative_path.clone());
         }
 
-        if !self.metadata.plugins_used.contains(&file_node.plugin) {
-            self.metadata.plugins_used.push(file_node.plugin.clone());
+        if let Err(index) = self
+            .metadata
+            .plugins_used
+            .binary_search_by(|p| p.name.as_str().cmp(&file_node.plugin))
+        {
+            self.metadata
+                .plugins_used
+                .insert(index, PluginUsage::named(&file_node.plugin));
         }
 
         // Store the file
@@ -285,8 +1122,271 @@ impl ProjectMatrix {
         self.external_dependencies.push(dependency);
     }
 
+    /// Collapse duplicate edges (same endpoints and type) reported by several plugins or
+    /// several times, keeping the strongest; on a tie an edge with known provenance wins.
+    /// Returns the number of edges removed.
+    pub fn dedup_relationships(&mut self) -> usize {
+        let before = self.relationships.len();
+        type EdgeKey = (PathBuf, PathBuf, String, Option<String>, Option<String>);
+        let mut best: HashMap<EdgeKey, usize> = HashMap::new();
+        let mut kept: Vec<Relationship> = Vec::with_capacity(before);
+
+        for relationship in self.relationships.drain(..) {
+            let key = (
+                relationship.from_file.clone(),
+                relationship.to_file.clone(),
+                format!("{:?}", relationship.relationship_type),
+                relationship.from_element.clone(),
+                relationship.to_element.clone(),
+            );
+            match best.get(&key) {
+                Some(&index) => {
+                    if relationship_quality(&relationship) > relationship_quality(&kept[index]) {
+                        kept[index] = relationship;
+                    }
+                }
+                None => {
+                    best.insert(key, kept.len());
+                    kept.push(relationship);
+                }
+            }
+        }
+
+        self.relationships = kept;
+        let removed = before - self.relationships.len();
+        if removed > 0 {
+            debug!("Removed {removed} duplicate relationships");
+            self.graph = None;
+            self.node_indexes.clear();
+        }
+        removed
+    }
+
+    /// Relationships matching a filter, in matrix order. Test files are the ones the
+    /// scan categorized as tests.
+    pub fn query_relationships(&self, filter: &RelationshipFilter) -> Vec<&Relationship> {
+        self.relationships
+            .iter()
+            .filter(|r| filter.matches_with(r, |path| is_test_file(&self.files, path)))
+            .collect()
+    }
+
+    /// Keep only the relationships matching a filter; returns the number removed
+    pub fn retain_relationships(&mut self, filter: &RelationshipFilter) -> usize {
+        let before = self.relationships.len();
+        let files = &self.files;
+        self.relationships
+            .retain(|r| filter.matches_with(r, |path| is_test_file(files, path)));
+
+        let removed = before - self.relationships.len();
+        if removed > 0 {
+            self.graph = None;
+            self.node_indexes.clear();
+        }
+        removed
+    }
+
+    /// Look up an element by its stable ID
+    pub fn find_element(&self, element_id: &str) -> Option<(&FileNode, &CodeElement)> {
+        self.files.values().find_map(|file| {
+            file.elements
+                .iter()
+                .find(|e| e.element_id == element_id)
+                .map(|e| (file, e))
+        })
+    }
+
+    /// Elements `name` refers to (see `CodeElement::matches_name`), sorted by file
+    pub fn find_elements(&self, name: &str) -> Vec<(&FileNode, &CodeElement)> {
+        let mut found: Vec<(&FileNode, &CodeElement)> = self
+            .files
+            .values()
+            .flat_map(|file| file.elements.iter().map(move |e| (file, e)))
+            .filter(|(_, e)| e.matches_name(name))
+            .collect();
+        found.sort_by(|a, b| {
+            (&a.0.relative_path, a.1.line_start).cmp(&(&b.0.relative_path, b.1.line_start))
+        });
+        found
+    }
+
+    /// The element a reference to `name` from `from_file` means: one in the same file
+    /// if there is exactly one, otherwise the only one in the project. None when the
+    /// name is unknown or ambiguous.
+    pub fn resolve_element(&self, name: &str, from_file: &Path) -> Option<ElementRef> {
+        let found = self.find_elements(name);
+        let local: Vec<_> = found
+            .iter()
+            .filter(|(file, _)| file.relative_path == from_file)
+            .collect();
+        let (file, element) = match (local.as_slice(), found.as_slice()) {
+            ([only], _) => **only,
+            (_, [only]) => *only,
+            _ => return None,
+        };
+        Some(ElementRef {
+            file: file.relative_path.clone(),
+            name: element.qualified_name().to_string(),
+        })
+    }
+
+    /// Files added, removed or with different content since an earlier scan, sorted.
+    /// Hashes from different algorithms can't be compared, so scans that used different
+    /// ones are an error rather than every file looking changed.
+    pub fn changed_files(&self, previous: &ProjectMatrix) -> Result<Vec<PathBuf>> {
+        if self.metadata.hash_algorithm != previous.metadata.hash_algorithm {
+            return Err(anyhow::anyhow!(
+                "Can't compare file hashes: this matrix used {} and the earlier one {}",
+                self.metadata.hash_algorithm,
+                previous.metadata.hash_algorithm
+            ));
+        }
+        let mut changed: Vec<PathBuf> = self
+            .files
+            .iter()
+            .filter(|(path, file)| {
+                previous
+                    .files
+                    .get(*path)
+                    .is_none_or(|before| before.hash != file.hash)
+            })
+            .map(|(path, _)| path.clone())
+            .chain(
+                previous
+                    .files
+                    .keys()
+                    .filter(|path| !self.files.contains_key(*path))
+                    .cloned(),
+            )
+            .collect();
+        changed.sort();
+        Ok(changed)
+    }
+
+    /// Compare elements against an earlier scan to detect drift
+    pub fn diff_elements(&self, previous: &ProjectMatrix) -> ElementDiff {
+        let index = |matrix: &ProjectMatrix| -> HashMap<String, (u32, u32)> {
+            matrix
+                .files
+                .values()
+                .flat_map(|f| &f.elements)
+                .map(|e| (e.element_id.clone(), (e.line_start, e.line_end)))
+                .collect()
+        };
+        let before = index(previous);
+        let after = index(self);
+
+        let mut diff = ElementDiff::default();
+        for (id, lines) in &after {
+            match before.get(id) {
+                None => diff.added.push(id.clone()),
+                Some(old_lines) if old_lines != lines => diff.moved.push(id.clone()),
+                Some(_) => diff.unchanged += 1,
+            }
+        }
+        diff.removed = before
+            .keys()
+            .filter(|id| !after.contains_key(*id))
+            .cloned()
+            .collect();
+        diff.added.sort();
+        diff.removed.sort();
+        diff.moved.sort();
+        diff
+    }
+
+    /// Element-level subgraph for call-path and impact queries
+    pub fn element_graph(&self) -> ElementGraph {
+        ElementGraph::from_relationships(&self.relationships)
+    }
+
+    /// Point edges at the files that define symbols rather than the ones re-exporting
+    /// them: edges to a re-exported element are retargeted along the re-export chain, and
+    /// an import of re-exported names (or `*`) gains an edge to each defining file.
+    /// Returns the number of edges retargeted or added.
+    pub fn resolve_reexports(&mut self) -> usize {
+        let files = &self.files;
+        let mut retargeted = 0;
+        let mut added = Vec::new();
+
+        for relationship in &mut self.relationships {
+            if let Some(name) = &relationship.to_element {
+                if let Some((file, name)) = reexport_origin(files, &relationship.to_file, name) {
+                    relationship.to_file = file;
+                    relationship.to_element = Some(name);
+                    retargeted += 1;
+                }
+                continue;
+            }
+            if relationship.relationship_type != RelationshipType::Import {
+                continue;
+            }
+            let Some(target) = files.get(&relationship.to_file) else {
+                continue;
+            };
+            // The names the import statement behind this edge pulls in
+            let Some(import) = files.get(&relationship.from_file).and_then(|file| {
+                file.imports
+                    .iter()
+                    .find(|import| Some(import.line_number) == relationship.line_number)
+            }) else {
+                continue;
+            };
+
+            let mut origins = Vec::new();
+            for item in &import.items {
+                // `Name as Local` imports `Name`
+                let item = item.split(" as ").next().unwrap_or_default().trim();
+                if item == "*" {
+                    origins.extend(
+                        target
+                            .exports
+                            .iter()
+                            .filter(|export| export.source.is_some())
+                            .filter_map(|export| {
+                                reexport_origin(
+                                    files,
+                                    &relationship.to_file,
+                                    export.exported_name(),
+                                )
+                            }),
+                    );
+                } else {
+                    origins.extend(reexport_origin(files, &relationship.to_file, item));
+                }
+            }
+            for (file, name) in origins {
+                added.push(Relationship {
+                    to_file: file,
+                    to_element: Some(name),
+                    runtime_calls: None,
+                    details: format!(
+                        "{} (re-exported by {})",
+                        relationship.details,
+                        relationship.to_file.display()
+                    ),
+                    ..relationship.clone()
+                });
+            }
+        }
+
+        let changed = retargeted + added.len();
+        self.relationships.extend(added);
+        if changed > 0 {
+            debug!("Followed re-exports for {changed} relationships");
+            self.graph = None;
+            self.node_indexes.clear();
+        }
+        changed
+    }
+
     /// Finalize the matrix after all files are added
     pub fn finalize(&mut self) {
+        // Reach through re-exports, then drop duplicate edges before anything is derived
+        // from them
+        self.resolve_reexports();
+        self.dedup_relationships();
+
         // Calculate average tokens per file
         if self.metadata.total_files > 0 {
             self.project_info.token_summary.average_tokens_per_file =
@@ -299,6 +1399,9 @@ impl ProjectMatrix {
 
         // Determine project type and main language
         self.analyze_project_structure();
+
+        // Group files into logical components
+        self.project_info.components = crate::core::graph_analysis::detect_components(self);
     }
 
     /// Detect project entrypoints based on common patterns
@@ -309,7 +1412,7 @@ impl ProjectMatrix {
         if let Some(main_rs) = self
             .files
             .values()
-            .find(|f| f.relative_path == PathBuf::from("src/main.rs"))
+            .find(|f| f.relative_path == Path::new("src/main.rs"))
         {
             entrypoints.push(EntrypointInfo {
                 file_path: main_rs.relative_path.clone(),
@@ -322,7 +1425,7 @@ impl ProjectMatrix {
         if let Some(lib_rs) = self
             .files
             .values()
-            .find(|f| f.relative_path == PathBuf::from("src/lib.rs"))
+            .find(|f| f.relative_path == Path::new("src/lib.rs"))
         {
             entrypoints.push(EntrypointInfo {
                 file_path: lib_rs.relative_path.clone(),
@@ -392,7 +1495,7 @@ impl ProjectMatrix {
         let mut language_counts: HashMap<String, usize> = HashMap::new();
         for file in self.files.values() {
             if let Some(ref lang) = file.language {
-                *language_counts.entry(lang.clone()).or_insert(0) += 1;
+                *language_counts.entry(lang.to_string()).or_insert(0) += 1;
             }
         }
 
@@ -430,24 +1533,46 @@ impl ProjectMatrix {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        let json = serde_json::to_string_pretty(self)?;
+        let json = {
+            let _serde = profile::span(Stage::Serde);
+            serde_json::to_string_pretty(self)?
+        };
         let json_tokens = estimate_tokens(&json);
 
         // Log the matrix size in tokens
         info!("Matrix JSON size: {json_tokens} tokens");
 
-        tokio::fs::write(path, json).await?;
+        // Keep the previous matrix so a bad save can be recovered from, unless it is
+        // itself corrupt: copying it would replace the last good backup
+        let _lock = CacheLock::exclusive(path).await?;
+        let _save = profile::span(Stage::Save);
+        if let Ok(previous) = tokio::fs::read(path).await {
+            if serde_json::from_slice::<serde::de::IgnoredAny>(&previous).is_ok() {
+                write_atomic(&backup_path(path), previous).await?;
+            } else {
+                warn!(
+                    "Not backing up unreadable matrix {}; keeping the previous backup",
+                    path.display()
+                );
+            }
+        }
+        write_atomic(path, json).await?;
 
         debug!("Matrix saved successfully");
         Ok(())
     }
 
-    /// Load the matrix from a JSON file
+    /// Load the matrix from a JSON file, falling back to `matrix.json.bak` when the
+    /// file is truncated or otherwise unreadable
     pub async fn load(path: &Path) -> Result<Self> {
         debug!("Loading project matrix from: {}", path.display());
 
-        let json = tokio::fs::read_to_string(path).await?;
-        let mut matrix: ProjectMatrix = serde_json::from_str(&json)?;
+        let mut matrix: Self = read_matrix_file(path).await?;
+
+        // Matrices written before element IDs existed get them on load
+        for file_node in matrix.files.values_mut() {
+            assign_element_ids(file_node);
+        }
 
         // Rebuild the graph
         matrix.rebuild_graph();
@@ -459,42 +1584,210 @@ impl ProjectMatrix {
         Ok(matrix)
     }
 
+    /// Open the matrix for on-demand access: metadata, project info and edges are read
+    /// up front, file nodes are only deserialized when requested
+    pub async fn open(path: &Path) -> Result<MatrixHandle> {
+        debug!("Opening project matrix lazily: {}", path.display());
+
+        let raw: RawMatrix = read_matrix_file(path).await?;
+        Ok(MatrixHandle {
+            metadata: raw.metadata,
+            project_info: raw.project_info,
+            relationships: raw.relationships,
+            external_dependencies: raw.external_dependencies,
+            data_model: raw.data_model,
+            raw_files: raw.files,
+            loaded: HashMap::new(),
+        })
+    }
+
     /// Load a subset of the matrix based on file paths (for token-limited scenarios)
     pub async fn load_subset(path: &Path, file_paths: &[PathBuf]) -> Result<Self> {
-        let full_matrix = Self::load(path).await?;
-        let mut subset_matrix = ProjectMatrix::new(full_matrix.metadata.project_root.clone());
+        Self::load_subset_with(path, file_paths, &SubsetOptions::default()).await
+    }
+
+    /// Load a subset of the matrix, expanding the requested files along their edges
+    pub async fn load_subset_with(
+        path: &Path,
+        file_paths: &[PathBuf],
+        options: &SubsetOptions,
+    ) -> Result<Self> {
+        Ok(Self::load(path).await?.subset(file_paths, options))
+    }
+
+    /// Build a coherent subgraph: the requested files plus up to `options` levels of
+    /// dependencies/dependents (nearest first, within the token budget), with every edge
+    /// between included files
+    pub fn subset(&self, file_paths: &[PathBuf], options: &SubsetOptions) -> Self {
+        let mut subset_matrix = ProjectMatrix::new(self.metadata.project_root.clone());
 
         // Copy metadata
-        subset_matrix.metadata = full_matrix.metadata;
-        subset_matrix.project_info = full_matrix.project_info;
+        subset_matrix.metadata = self.metadata.clone();
+        subset_matrix.project_info = self.project_info.clone();
+        subset_matrix.data_model = self.data_model.clone();
 
-        // Copy only requested files
-        for file_path in file_paths {
-            if let Some(file_node) = full_matrix.files.get(file_path) {
-                subset_matrix
-                    .files
-                    .insert(file_path.clone(), file_node.clone());
+        // Requested files are always included
+        let mut included: std::collections::HashSet<PathBuf> = file_paths
+            .iter()
+            .filter(|p| self.files.contains_key(*p))
+            .cloned()
+            .collect();
+        let mut used_tokens: u64 = included
+            .iter()
+            .map(|p| self.files[p].token_info.total_tokens)
+            .sum();
+
+        let mut dependencies: HashMap<&PathBuf, Vec<&PathBuf>> = HashMap::new();
+        let mut dependents: HashMap<&PathBuf, Vec<&PathBuf>> = HashMap::new();
+        for relationship in &self.relationships {
+            dependencies
+                .entry(&relationship.from_file)
+                .or_default()
+                .push(&relationship.to_file);
+            dependents
+                .entry(&relationship.to_file)
+                .or_default()
+                .push(&relationship.from_file);
+        }
+
+        // Each direction walks from the requested files on its own frontier, so a
+        // dependency's other dependents (or a dependent's other dependencies) never
+        // come in. Levels alternate between them to spend the budget nearest first.
+        let requested: Vec<&PathBuf> = file_paths
+            .iter()
+            .filter(|p| included.contains(*p))
+            .collect();
+        let mut walks = [
+            (&dependencies, options.dependency_depth, requested.clone()),
+            (&dependents, options.dependent_depth, requested.clone()),
+        ]
+        .map(|(edges, depth, frontier)| {
+            let seen: std::collections::HashSet<&PathBuf> = frontier.iter().copied().collect();
+            (edges, depth, frontier, seen)
+        });
+
+        let depth = options.dependency_depth.max(options.dependent_depth);
+        for level in 1..=depth {
+            for (edges, walk_depth, frontier, seen) in &mut walks {
+                if level > *walk_depth || frontier.is_empty() {
+                    continue;
+                }
+                let mut next = Vec::new();
+                for file in frontier.iter() {
+                    for &neighbor in edges.get(*file).into_iter().flatten() {
+                        let Some(node) = self.files.get(neighbor) else {
+                            continue;
+                        };
+                        if !seen.insert(neighbor) {
+                            continue;
+                        }
+                        if !included.contains(neighbor) {
+                            let tokens = node.token_info.total_tokens;
+                            if options
+                                .max_tokens
+                                .is_some_and(|budget| used_tokens + tokens > budget)
+                            {
+                                continue;
+                            }
+                            used_tokens += tokens;
+                            included.insert(neighbor.clone());
+                        }
+                        next.push(neighbor);
+                    }
+                }
+                *frontier = next;
             }
         }
 
+        for file_path in &included {
+            subset_matrix
+                .files
+                .insert(file_path.clone(), self.files[file_path].clone());
+        }
+
         // Copy relationships between included files
-        for relationship in &full_matrix.relationships {
-            if subset_matrix.files.contains_key(&relationship.from_file)
-                && subset_matrix.files.contains_key(&relationship.to_file)
+        let mut dropped = 0;
+        for relationship in &self.relationships {
+            if included.contains(&relationship.from_file)
+                && included.contains(&relationship.to_file)
             {
                 subset_matrix.relationships.push(relationship.clone());
+            } else if included.contains(&relationship.from_file)
+                || included.contains(&relationship.to_file)
+            {
+                dropped += 1;
             }
         }
+        if dropped > 0 {
+            debug!("Subset leaves out {dropped} edges that cross its boundary");
+        }
 
         // Copy relevant external dependencies
-        for dep in &full_matrix.external_dependencies {
-            if subset_matrix.files.contains_key(&dep.source_file) {
+        for dep in &self.external_dependencies {
+            if included.contains(&dep.source_file) {
                 subset_matrix.external_dependencies.push(dep.clone());
             }
         }
 
         subset_matrix.rebuild_graph();
-        Ok(subset_matrix)
+        subset_matrix
+    }
+
+    /// Only the files `keep` accepts and the edges among them and their external
+    /// dependencies, with totals and plugins narrowed to match
+    pub fn filter_files(&self, keep: impl Fn(&FileNode) -> bool) -> Self {
+        let paths: Vec<PathBuf> = self
+            .files
+            .iter()
+            .filter(|(_, file)| keep(file))
+            .map(|(path, _)| path.clone())
+            .collect();
+        let mut subset = self.subset(&paths, &SubsetOptions::default());
+
+        let files = &subset.files;
+        subset.metadata.total_files = files.len();
+        subset.metadata.total_size_bytes = files.values().map(|f| f.size_bytes).sum();
+        subset.metadata.total_tokens = files.values().map(|f| f.token_info.total_tokens).sum();
+        subset
+            .metadata
+            .plugins_used
+            .retain(|p| files.values().any(|f| f.plugin == p.name.as_str()));
+        subset
+    }
+
+    /// Only the files written in `language` (any case), the edges among them and their
+    /// external dependencies
+    pub fn language_subset(&self, language: &str) -> Self {
+        let mut subset = self.filter_files(|file| {
+            file.language
+                .as_deref()
+                .is_some_and(|l| l.eq_ignore_ascii_case(language))
+        });
+        // Dependencies are declared in manifests, which aren't in the language themselves
+        let ecosystems = language_ecosystems(language);
+        subset.external_dependencies.extend(
+            self.external_dependencies
+                .iter()
+                .filter(|dep| !subset.files.contains_key(&dep.source_file))
+                .filter(|dep| ecosystems.contains(&dep.ecosystem.to_ascii_lowercase().as_str()))
+                .cloned(),
+        );
+        if let Some(file) = subset.files.values().next() {
+            subset.project_info.main_language = file.language.as_deref().unwrap_or("").to_string();
+        }
+        subset
+    }
+
+    /// Languages of the files in the matrix, sorted and without duplicates
+    pub fn languages(&self) -> Vec<&str> {
+        let mut languages: Vec<&str> = self
+            .files
+            .values()
+            .filter_map(|f| f.language.as_deref())
+            .collect();
+        languages.sort_unstable();
+        languages.dedup();
+        languages
     }
 
     /// Get files sorted by token count (useful for prioritizing in LLM context)
@@ -504,14 +1797,121 @@ impl ProjectMatrix {
         files
     }
 
-    /// Get a token budget breakdown for LLM context planning
+    /// Files outside the vendored category, i.e. the ones quality metrics, docs and token
+    /// budgets cover. Matrices written before categories existed are classified on the
+    /// fly with the built-in rules.
+    pub fn maintained_files(&self) -> impl Iterator<Item = &FileNode> {
+        let classifier = FileClassifier::default();
+        self.files.values().filter(move |file| {
+            file.category
+                .unwrap_or_else(|| classifier.classify(&file.relative_path))
+                .is_maintained()
+        })
+    }
+
+    /// Relative paths of every maintained file in reading order: a breadth-first walk
+    /// from the detected entrypoints (most confident first) along outgoing relationships,
+    /// each level ordered by the strength of the edges that reached it. Files the
+    /// entrypoints don't reach follow, walked the same way from whichever depends most on
+    /// others. Vendored files are left out.
+    pub fn traversal_order(&self) -> Vec<PathBuf> {
+        // Relationships name files by path; entrypoints by relative path
+        let mut relative: HashMap<&Path, &Path> = HashMap::new();
+        for file in self.maintained_files() {
+            relative.insert(&file.path, &file.relative_path);
+            relative.insert(&file.relative_path, &file.relative_path);
+        }
+
+        let mut edges: HashMap<&Path, Vec<(&Path, f32)>> = HashMap::new();
+        for relationship in &self.relationships {
+            if let (Some(&from), Some(&to)) = (
+                relative.get(relationship.from_file.as_path()),
+                relative.get(relationship.to_file.as_path()),
+            ) {
+                if from != to {
+                    edges
+                        .entry(from)
+                        .or_default()
+                        .push((to, relationship.strength));
+                }
+            }
+        }
+
+        let mut entrypoints: Vec<&EntrypointInfo> = self.project_info.entrypoints.iter().collect();
+        entrypoints.sort_by(|a, b| {
+            b.confidence
+                .total_cmp(&a.confidence)
+                .then_with(|| a.file_path.cmp(&b.file_path))
+        });
+        let mut roots: Vec<&Path> = entrypoints
+            .iter()
+            .filter_map(|e| relative.get(e.file_path.as_path()).copied())
+            .collect();
+
+        // Candidates for later walks, strongest dependers first
+        let mut rest: Vec<(&Path, f32)> = self
+            .maintained_files()
+            .map(|file| {
+                let path = file.relative_path.as_path();
+                let weight = edges
+                    .get(path)
+                    .map_or(0.0, |out| out.iter().map(|e| e.1).sum());
+                (path, weight)
+            })
+            .collect();
+        rest.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        let mut order: Vec<PathBuf> = Vec::with_capacity(rest.len());
+        let mut visited: HashSet<&Path> = HashSet::new();
+        let mut rest = rest.into_iter().map(|(path, _)| path);
+        loop {
+            let mut level: Vec<&Path> = roots
+                .drain(..)
+                .filter(|path| visited.insert(*path))
+                .collect();
+            if level.is_empty() {
+                match rest.by_ref().find(|path| !visited.contains(path)) {
+                    Some(path) => {
+                        visited.insert(path);
+                        level.push(path);
+                    }
+                    None => break,
+                }
+            }
+
+            while !level.is_empty() {
+                let mut reached: HashMap<&Path, f32> = HashMap::new();
+                for path in &level {
+                    for &(to, strength) in edges.get(path).into_iter().flatten() {
+                        if !visited.contains(to) {
+                            *reached.entry(to).or_default() += strength;
+                        }
+                    }
+                }
+                order.extend(level.iter().map(|path| path.to_path_buf()));
+
+                let mut next: Vec<(&Path, f32)> = reached.into_iter().collect();
+                next.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+                level = next.into_iter().map(|(path, _)| path).collect();
+                visited.extend(level.iter().copied());
+            }
+        }
+        order
+    }
+
+    /// Get a token budget breakdown for LLM context planning. Vendored files never
+    /// count against the budget and are always listed as excluded.
     pub fn get_token_budget_info(&self, max_tokens: u64) -> TokenBudgetInfo {
         let mut included_files = Vec::new();
         let mut remaining_tokens = max_tokens;
         let mut total_included_tokens = 0;
 
+        let maintained: HashSet<&Path> =
+            self.maintained_files().map(|f| f.path.as_path()).collect();
         for (path, file) in self.get_files_by_token_count() {
-            if file.token_info.total_tokens <= remaining_tokens {
+            if maintained.contains(path.as_path())
+                && file.token_info.total_tokens <= remaining_tokens
+            {
                 included_files.push(path.clone());
                 total_included_tokens += file.token_info.total_tokens;
                 remaining_tokens -= file.token_info.total_tokens;
@@ -576,6 +1976,12 @@ impl ProjectMatrix {
         );
     }
 
+    /// Drop the cached graph after file keys or relationships were rewritten in place
+    pub(crate) fn invalidate_graph(&mut self) {
+        self.graph = None;
+        self.node_indexes.clear();
+    }
+
     /// Ensure the graph is built
     fn ensure_graph(&mut self) {
         if self.graph.is_none() {
@@ -583,6 +1989,12 @@ impl ProjectMatrix {
         }
     }
 
+    /// The file dependency graph, built on first use
+    pub fn graph(&mut self) -> &ProjectGraph {
+        self.ensure_graph();
+        self.graph.as_ref().unwrap()
+    }
+
     /// Find all files that depend on the given file
     pub fn find_dependents(&mut self, file_path: &Path) -> Vec<&FileNode> {
         self.ensure_graph();
@@ -655,15 +2067,56 @@ impl ProjectMatrix {
             total_files: self.files.len(),
             total_relationships: self.relationships.len(),
             highly_coupled_files: coupling_scores.into_iter().take(10).collect(),
-            languages: self.metadata.plugins_used.clone(),
+            languages: self
+                .metadata
+                .plugin_names()
+                .into_iter()
+                .map(String::from)
+                .collect(),
             total_tokens: self.metadata.total_tokens,
+            categories: self.category_rollups(),
         }
     }
 
+    /// Files, size, tokens and element complexity per category. Matrices written before
+    /// categories existed are classified on the fly with the built-in rules.
+    pub fn category_rollups(&self) -> BTreeMap<FileCategory, CategoryRollup> {
+        let classifier = FileClassifier::default();
+        let mut rollups: BTreeMap<FileCategory, CategoryRollup> = BTreeMap::new();
+        let mut complexity: BTreeMap<FileCategory, (u64, usize)> = BTreeMap::new();
+
+        for file in self.files.values() {
+            let category = file
+                .category
+                .unwrap_or_else(|| classifier.classify(&file.relative_path));
+            let rollup = rollups.entry(category).or_default();
+            rollup.files += 1;
+            rollup.size_bytes += file.size_bytes;
+            rollup.tokens += file.token_info.total_tokens;
+
+            let (sum, count) = complexity.entry(category).or_default();
+            for score in file.elements.iter().filter_map(|e| e.complexity_score) {
+                *sum += u64::from(score);
+                *count += 1;
+            }
+        }
+        for (category, (sum, count)) in complexity {
+            if count > 0 {
+                rollups.get_mut(&category).unwrap().average_complexity =
+                    Some(sum as f64 / count as f64);
+            }
+        }
+
+        rollups
+    }
+
     /// Print a summary of the matrix
     pub fn print_summary(&mut self) {
         println!("\n=== Project Matrix Summary ===");
         println!("Project: {}", self.metadata.project_root.display());
+        for root in &self.metadata.roots {
+            println!("  Root {}: {}", root.name, root.path.display());
+        }
         println!(
             "Scanned: {}",
             self.metadata.scan_timestamp.format("%Y-%m-%d %H:%M:%S UTC")
@@ -678,7 +2131,7 @@ impl ProjectMatrix {
             "External dependencies: {}",
             self.external_dependencies.len()
         );
-        println!("Languages: {}", self.metadata.plugins_used.join(", "));
+        println!("Languages: {}", self.metadata.plugin_names().join(", "));
 
         // Token information
         println!("\n📊 Token Summary:");
@@ -706,6 +2159,67 @@ impl ProjectMatrix {
             );
         }
 
+        let stats = &self.metadata.scan_stats;
+        if stats.duration_ms > 0 {
+            println!("\n⏱️ Scan Statistics:");
+            println!("  Duration: {:.2}s", stats.duration_ms as f64 / 1000.0);
+            println!(
+                "  Cache: {} hits, {} misses",
+                stats.cache_hits, stats.cache_misses
+            );
+            println!(
+                "  Token cache: {} hits, {} misses",
+                stats.token_cache_hits, stats.token_cache_misses
+            );
+            println!(
+                "  Failed files: {}, skipped files: {}",
+                stats.failed_files, stats.skipped_files
+            );
+            if stats.skipped_deadline > 0 {
+                println!(
+                    "  ⚠️ Time limit reached: {} files kept without analysis",
+                    stats.skipped_deadline
+                );
+            }
+            if !stats.skipped_permission.is_empty() {
+                println!(
+                    "  Permission denied ({} paths):",
+                    stats.skipped_permission.len()
+                );
+                for path in &stats.skipped_permission {
+                    println!("    {}", path.display());
+                }
+            }
+            for (plugin, plugin_stats) in &stats.plugins {
+                println!(
+                    "  {plugin}: {} files in {:.2}s ({} failed)",
+                    plugin_stats.files,
+                    plugin_stats.time_ms as f64 / 1000.0,
+                    plugin_stats.failed
+                );
+                if let Some(pool) = &plugin_stats.pool {
+                    println!(
+                        "    pool: {} processes started, {} reused, at most {} at once, {} unhealthy",
+                        pool.spawned, pool.reused, pool.peak, pool.unhealthy
+                    );
+                }
+            }
+        }
+
+        println!("\n🗂️ Categories:");
+        for (category, rollup) in self.category_rollups() {
+            let complexity = rollup
+                .average_complexity
+                .map(|c| format!(", avg complexity {c:.1}"))
+                .unwrap_or_default();
+            println!(
+                "  {category}: {} files, {:.2} MB, {} tokens{complexity}",
+                rollup.files,
+                rollup.size_bytes as f64 / (1024.0 * 1024.0),
+                rollup.tokens
+            );
+        }
+
         // Entrypoints
         if !self.project_info.entrypoints.is_empty() {
             println!("\n🚀 Detected Entrypoints:");
@@ -719,9 +2233,21 @@ impl ProjectMatrix {
             }
         }
 
+        if !self.project_info.components.is_empty() {
+            println!("\n🧩 Components:");
+            for component in &self.project_info.components {
+                println!(
+                    "  {} ({} files, {:?})",
+                    component.name,
+                    component.files.len(),
+                    component.source
+                );
+            }
+        }
+
         // Show files scanned by language/plugin
         println!("\n📁 Files scanned:");
-        let mut by_plugin: std::collections::HashMap<String, Vec<&PathBuf>> =
+        let mut by_plugin: std::collections::HashMap<IStr, Vec<&PathBuf>> =
             std::collections::HashMap::new();
 
         for (path, file_node) in &self.files {
@@ -767,6 +2293,18 @@ pub struct ProjectMetrics {
     pub highly_coupled_files: Vec<(PathBuf, usize)>,
     pub languages: Vec<String>,
     pub total_tokens: u64,
+    pub categories: BTreeMap<FileCategory, CategoryRollup>,
+}
+
+/// Totals for one file category
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CategoryRollup {
+    pub files: usize,
+    pub size_bytes: u64,
+    pub tokens: u64,
+    // Mean element complexity; None when no element in the category reports one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub average_complexity: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -778,6 +2316,23 @@ pub struct TokenBudgetInfo {
     pub excluded_files: Vec<PathBuf>,
 }
 
+/// Ordering used when deduplicating edges: strength first, then known provenance
+fn relationship_quality(relationship: &Relationship) -> (f32, bool) {
+    (relationship.strength, relationship.source_plugin.is_some())
+}
+
+fn assign_element_ids(file_node: &mut FileNode) {
+    for element in &mut file_node.elements {
+        if element.element_id.is_empty() {
+            element.element_id = element_id(
+                &file_node.relative_path,
+                element.qualified_name(),
+                element.signature.as_deref(),
+            );
+        }
+    }
+}
+
 /// Estimate tokens in a string (rough approximation)
 /// Uses ~4 characters per token as a heuristic
 pub fn estimate_tokens(text: &str) -> u64 {
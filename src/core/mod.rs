@@ -1,3 +1,42 @@
+pub mod adjacency;
+pub mod adr;
+pub mod annotations;
+pub mod api;
+pub mod bench;
+pub mod categories;
+pub mod changelog;
+pub mod comments;
+pub mod compact;
+pub mod complexity;
+pub mod contracts;
+pub mod coverage;
+pub mod data_model;
+pub mod debt;
+pub mod deployments;
+pub mod doc_drift;
+pub mod doctor;
+pub mod documentation;
+pub mod dsm;
+pub mod events;
+pub mod facade;
+pub mod focus;
+pub mod glossary;
+pub mod graph_analysis;
+pub mod history;
+pub mod hotspots;
+pub mod interactions;
+pub mod lint;
 pub mod matrix;
+pub mod pipeline;
 pub mod project;
+pub mod redact;
+pub mod rules;
 pub mod scanner;
+pub mod structure;
+pub mod suggestions;
+pub mod suppressions;
+pub mod token_cache;
+pub mod traces;
+pub mod transform;
+pub mod validate;
+pub mod view;
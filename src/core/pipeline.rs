@@ -0,0 +1,66 @@
+// src/core/pipeline.rs - Backpressure between the scan's read, analyze and merge stages
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Batches the read stage may have ready while a plugin analyzes the current one
+pub const READ_AHEAD_BATCHES: usize = 2;
+
+/// Caps the bytes of file content held between being read and merged into the matrix.
+/// A file larger than the whole budget waits until nothing else is held and then takes
+/// all of it, so it's read alone rather than never.
+#[derive(Debug, Clone)]
+pub struct ByteBudget {
+    semaphore: Arc<Semaphore>,
+    // Counted in KiB, which keeps any realistic budget within the semaphore's u32 requests
+    capacity_kib: u32,
+}
+
+impl ByteBudget {
+    pub fn new(bytes: u64) -> Self {
+        let capacity_kib = bytes.div_ceil(1024).clamp(1, u32::MAX as u64) as u32;
+        Self {
+            semaphore: Arc::new(Semaphore::new(capacity_kib as usize)),
+            capacity_kib,
+        }
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity_kib as u64 * 1024
+    }
+
+    /// Bytes that can be reserved without waiting
+    pub fn available(&self) -> u64 {
+        self.semaphore.available_permits() as u64 * 1024
+    }
+
+    /// Wait until `bytes` fit, holding them until the permit is dropped
+    pub async fn reserve(&self, bytes: u64) -> BytePermit {
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_many_owned(self.kib(bytes))
+            .await
+            .expect("the budget's semaphore is never closed");
+        BytePermit { _permit: permit }
+    }
+
+    /// `reserve` when `bytes` fit now
+    pub fn try_reserve(&self, bytes: u64) -> Option<BytePermit> {
+        let permit = self
+            .semaphore
+            .clone()
+            .try_acquire_many_owned(self.kib(bytes))
+            .ok()?;
+        Some(BytePermit { _permit: permit })
+    }
+
+    fn kib(&self, bytes: u64) -> u32 {
+        bytes.div_ceil(1024).clamp(1, self.capacity_kib as u64) as u32
+    }
+}
+
+/// Part of a `ByteBudget`, given back when dropped
+#[derive(Debug)]
+pub struct BytePermit {
+    _permit: OwnedSemaphorePermit,
+}
@@ -1 +1,279 @@
-// TODO: Implement
+// src/core/project.rs - A digest of the project's package manifests, sent to input
+// plugins advertising CAPABILITY_PROJECT_CONTEXT
+use ignore::WalkBuilder;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// How deep below the root manifests are looked for; deeper ones are rarely packages
+/// of the project itself
+const MANIFEST_DEPTH: usize = 4;
+
+// Dependency and build output trees, skipped even when nothing ignores them
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "vendor",
+    "venv",
+    "__pycache__",
+    "dist",
+    "build",
+];
+
+/// What the project's manifests say: its own packages, what they depend on and where
+/// their code lives, so plugins can tell local imports from third-party ones
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectContext {
+    pub packages: Vec<ProjectPackage>,
+    // Declared dependency names by ecosystem, e.g. `{"python": ["requests"]}`
+    pub dependencies: BTreeMap<String, BTreeSet<String>>,
+    // Directories holding the packages' own code, relative to the project root
+    pub source_roots: Vec<PathBuf>,
+}
+
+/// A package the project itself defines
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectPackage {
+    pub name: String,
+    // `cargo`, `npm`, `python` or `go`
+    pub ecosystem: String,
+    // Relative to the project root
+    pub manifest: PathBuf,
+}
+
+impl ProjectContext {
+    /// Read the manifests under `root`. Ones that can't be parsed are skipped, so this
+    /// never fails; a project without manifests gets an empty context.
+    pub fn detect(root: &Path) -> Self {
+        let mut context = Self::default();
+        let mut manifests: Vec<PathBuf> = WalkBuilder::new(root)
+            .max_depth(Some(MANIFEST_DEPTH))
+            .filter_entry(|entry| {
+                entry.depth() == 0
+                    || !entry.file_type().is_some_and(|t| t.is_dir())
+                    || !SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref())
+            })
+            .build()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .map(|entry| entry.into_path())
+            .collect();
+        manifests.sort();
+
+        for path in manifests {
+            let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            let parsed = match file_name {
+                "Cargo.toml" => context.read_cargo(&path, &relative),
+                "package.json" => context.read_package_json(&path, &relative),
+                "pyproject.toml" => context.read_pyproject(&path, &relative),
+                "go.mod" => context.read_go_mod(&path, &relative),
+                name if is_requirements_file(name) => context.read_requirements(&path),
+                _ => continue,
+            };
+            if let Err(e) = parsed {
+                debug!("Skipping manifest {}: {e}", path.display());
+            }
+        }
+        context
+    }
+
+    /// Declared dependencies of one ecosystem
+    pub fn dependencies_of(&self, ecosystem: &str) -> impl Iterator<Item = &str> {
+        self.dependencies
+            .get(ecosystem)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    fn add_package(&mut self, name: &str, ecosystem: &str, path: &Path, relative: &Path) {
+        self.packages.push(ProjectPackage {
+            name: name.to_string(),
+            ecosystem: ecosystem.to_string(),
+            manifest: relative.to_path_buf(),
+        });
+        // The conventional `src` directory when there is one, else the package itself
+        let dir = relative.parent().unwrap_or(Path::new("")).to_path_buf();
+        let root = if path.parent().is_some_and(|p| p.join("src").is_dir()) {
+            dir.join("src")
+        } else {
+            dir
+        };
+        if !self.source_roots.contains(&root) {
+            self.source_roots.push(root);
+        }
+    }
+
+    fn add_dependency(&mut self, ecosystem: &str, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        let name = if ecosystem == "python" {
+            normalize_python_name(name)
+        } else {
+            name.to_string()
+        };
+        self.dependencies
+            .entry(ecosystem.to_string())
+            .or_default()
+            .insert(name);
+    }
+
+    fn read_cargo(&mut self, path: &Path, relative: &Path) -> anyhow::Result<()> {
+        let manifest: toml::Table = toml::from_str(&std::fs::read_to_string(path)?)?;
+        if let Some(name) = manifest
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+        {
+            self.add_package(name, "cargo", path, relative);
+        }
+        let workspace = manifest.get("workspace").and_then(|w| w.as_table());
+        let sections = ["dependencies", "dev-dependencies", "build-dependencies"]
+            .into_iter()
+            .filter_map(|section| manifest.get(section))
+            .chain(workspace.and_then(|w| w.get("dependencies")));
+        for section in sections {
+            for name in section.as_table().into_iter().flat_map(|t| t.keys()) {
+                self.add_dependency("cargo", name);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_package_json(&mut self, path: &Path, relative: &Path) -> anyhow::Result<()> {
+        let manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        if let Some(name) = manifest["name"].as_str() {
+            self.add_package(name, "npm", path, relative);
+        }
+        for section in [
+            "dependencies",
+            "devDependencies",
+            "peerDependencies",
+            "optionalDependencies",
+        ] {
+            for name in manifest[section]
+                .as_object()
+                .into_iter()
+                .flat_map(|d| d.keys())
+            {
+                self.add_dependency("npm", name);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_pyproject(&mut self, path: &Path, relative: &Path) -> anyhow::Result<()> {
+        let manifest: toml::Table = toml::from_str(&std::fs::read_to_string(path)?)?;
+        let project = manifest.get("project");
+        let poetry = manifest.get("tool").and_then(|t| t.get("poetry"));
+        if let Some(name) = project
+            .or(poetry)
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+        {
+            self.add_package(&normalize_python_name(name), "python", path, relative);
+        }
+
+        // PEP 621 lists requirement strings, Poetry a table keyed by name
+        let requirements = project
+            .and_then(|p| p.get("dependencies"))
+            .into_iter()
+            .chain(
+                project
+                    .and_then(|p| p.get("optional-dependencies"))
+                    .and_then(|o| o.as_table())
+                    .into_iter()
+                    .flat_map(|o| o.values()),
+            )
+            .filter_map(|list| list.as_array())
+            .flatten()
+            .filter_map(|requirement| requirement.as_str());
+        for requirement in requirements {
+            self.add_dependency("python", requirement_name(requirement));
+        }
+        let poetry_sections = ["dependencies", "dev-dependencies"]
+            .into_iter()
+            .filter_map(|section| poetry.and_then(|p| p.get(section)));
+        for section in poetry_sections {
+            for name in section.as_table().into_iter().flat_map(|t| t.keys()) {
+                if name != "python" {
+                    self.add_dependency("python", name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read_requirements(&mut self, path: &Path) -> anyhow::Result<()> {
+        for line in std::fs::read_to_string(path)?.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            // Options such as `-r other.txt` and `--index-url` aren't requirements
+            if line.is_empty() || line.starts_with('-') {
+                continue;
+            }
+            self.add_dependency("python", requirement_name(line));
+        }
+        Ok(())
+    }
+
+    fn read_go_mod(&mut self, path: &Path, relative: &Path) -> anyhow::Result<()> {
+        let content = std::fs::read_to_string(path)?;
+        let mut in_require_block = false;
+        for line in content.lines() {
+            let line = line.split("//").next().unwrap_or_default().trim();
+            if let Some(module) = line.strip_prefix("module ") {
+                self.add_package(module.trim().trim_matches('"'), "go", path, relative);
+            } else if line == "require (" {
+                in_require_block = true;
+            } else if in_require_block && line == ")" {
+                in_require_block = false;
+            } else if let Some(requirement) = line.strip_prefix("require ") {
+                self.add_go_requirement(requirement);
+            } else if in_require_block {
+                self.add_go_requirement(line);
+            }
+        }
+        Ok(())
+    }
+
+    fn add_go_requirement(&mut self, requirement: &str) {
+        if let Some(module) = requirement.split_whitespace().next() {
+            self.add_dependency("go", module.trim_matches('"'));
+        }
+    }
+}
+
+fn is_requirements_file(name: &str) -> bool {
+    name.starts_with("requirements") && name.ends_with(".txt")
+}
+
+/// The distribution name at the start of a PEP 508 requirement such as
+/// `requests[socks]>=2.0; python_version > "3.8"`
+fn requirement_name(requirement: &str) -> &str {
+    let requirement = requirement.trim();
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    &requirement[..end]
+}
+
+/// PEP 503 normalization: lowercase, with runs of `-`, `_` and `.` as one `-`
+pub fn normalize_python_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.trim().chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
@@ -0,0 +1,208 @@
+// src/core/redact.rs - Strip proprietary detail from a matrix before sharing it
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::matrix::{ImportType, ProjectMatrix};
+use crate::utils::config::RedactionConfig;
+use crate::utils::file_utils::write_atomic;
+
+/// Where `cached_salt` keeps the generated salt, under the cache directory
+pub const SALT_FILE: &str = "redaction-salt";
+
+/// What redaction removed, for reporting
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedactionSummary {
+    pub summaries: usize,
+    pub signatures: usize,
+    pub metadata: usize,
+    pub hashed_paths: usize,
+}
+
+/// Maps project paths to stable, salted hashes. The extension is kept so languages stay
+/// recognizable; paths matching a `keep_paths` glob are left as they are.
+struct PathHasher {
+    salt: String,
+    keep: Vec<glob::Pattern>,
+    hashed: HashMap<PathBuf, PathBuf>,
+}
+
+impl PathHasher {
+    fn new(rules: &RedactionConfig) -> Result<Self> {
+        let keep = rules
+            .keep_paths
+            .iter()
+            .map(|pattern| {
+                glob::Pattern::new(pattern)
+                    .with_context(|| format!("Invalid redaction keep_paths glob: {pattern}"))
+            })
+            .collect::<Result<_>>()?;
+        // Unsalted hashes of guessable paths like src/main.rs can be recomputed by anyone
+        let salt = rules
+            .salt
+            .clone()
+            .filter(|salt| !salt.is_empty())
+            .context("Hashing paths needs a redaction salt")?;
+        Ok(Self {
+            salt,
+            keep,
+            hashed: HashMap::new(),
+        })
+    }
+
+    fn hash(&mut self, path: &Path) -> PathBuf {
+        if let Some(hashed) = self.hashed.get(path) {
+            return hashed.clone();
+        }
+        let hashed = if self.keep.iter().any(|pattern| pattern.matches_path(path)) {
+            path.to_path_buf()
+        } else {
+            let digest = Sha256::digest(format!("{}{}", self.salt, path.display()));
+            let mut name = format!("{digest:x}")[..16].to_string();
+            if let Some(extension) = path.extension() {
+                name.push('.');
+                name.push_str(&extension.to_string_lossy());
+            }
+            PathBuf::from(name)
+        };
+        self.hashed.insert(path.to_path_buf(), hashed.clone());
+        hashed
+    }
+
+    fn hash_str(&mut self, value: &str) -> String {
+        self.hash(Path::new(value)).to_string_lossy().to_string()
+    }
+}
+
+/// The salt stored in `cache_dir`, generated at random on first use, for projects that
+/// hash paths without configuring one. Reusing it keeps hashes stable across exports.
+pub async fn cached_salt(cache_dir: &Path) -> Result<String> {
+    let path = cache_dir.join(SALT_FILE);
+    match tokio::fs::read_to_string(&path).await {
+        Ok(salt) if !salt.trim().is_empty() => return Ok(salt.trim().to_string()),
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+    let salt = uuid::Uuid::new_v4().simple().to_string();
+    tokio::fs::create_dir_all(cache_dir)
+        .await
+        .with_context(|| format!("Failed to create {}", cache_dir.display()))?;
+    write_atomic(&path, &salt).await?;
+    Ok(salt)
+}
+
+/// Apply redaction rules in place. With `hash_paths`, every reference to a project file
+/// (keys, relationships, entrypoints, components, glossary terms, dependency sources) is
+/// rewritten to the same hash of its relative path so the dependency structure stays
+/// intact.
+pub fn redact_matrix(
+    matrix: &mut ProjectMatrix,
+    rules: &RedactionConfig,
+) -> Result<RedactionSummary> {
+    let mut summary = RedactionSummary::default();
+
+    for file in matrix.files.values_mut() {
+        if rules.strip_summaries {
+            summary.summaries += usize::from(file.file_summary.take().is_some());
+        }
+        if rules.strip_metadata {
+            if !file.metadata.is_null() {
+                file.metadata = serde_json::Value::Null;
+                summary.metadata += 1;
+            }
+            file.hash.clear();
+        }
+        for element in &mut file.elements {
+            if rules.strip_summaries {
+                summary.summaries += usize::from(element.summary.take().is_some());
+            }
+            if rules.strip_signatures {
+                summary.signatures += usize::from(element.signature.take().is_some());
+            }
+            if rules.strip_metadata && !element.metadata.is_null() {
+                element.metadata = serde_json::Value::Null;
+                summary.metadata += 1;
+            }
+        }
+    }
+    if rules.strip_metadata {
+        for relationship in &mut matrix.relationships {
+            relationship.details.clear();
+        }
+    }
+    // Glossary terms are drawn from summaries
+    if rules.strip_summaries {
+        summary.summaries += matrix.project_info.glossary_candidates.len();
+        matrix.project_info.glossary_candidates.clear();
+    }
+
+    if rules.hash_paths {
+        summary.hashed_paths = hash_paths(matrix, rules)?;
+    }
+    matrix.invalidate_graph();
+
+    Ok(summary)
+}
+
+fn hash_paths(matrix: &mut ProjectMatrix, rules: &RedactionConfig) -> Result<usize> {
+    let mut hasher = PathHasher::new(rules)?;
+
+    // Files may be keyed by absolute path; everything else is hashed by relative path
+    let mut by_key: HashMap<PathBuf, PathBuf> = HashMap::new();
+    let files = std::mem::take(&mut matrix.files);
+    for (key, mut file) in files {
+        let hashed = hasher.hash(&file.relative_path);
+        by_key.insert(key, hashed.clone());
+        by_key.insert(file.path.clone(), hashed.clone());
+        file.path = hashed.clone();
+        file.relative_path = hashed.clone();
+        for import in &mut file.imports {
+            if matches!(import.import_type, ImportType::Local | ImportType::Relative) {
+                import.module = hasher.hash_str(&import.module).into();
+            }
+        }
+        matrix.files.insert(hashed, file);
+    }
+    let mut rewrite = |path: &mut PathBuf| {
+        *path = match by_key.get(path.as_path()) {
+            Some(hashed) => hashed.clone(),
+            None => hasher.hash(path),
+        };
+    };
+
+    for relationship in &mut matrix.relationships {
+        rewrite(&mut relationship.from_file);
+        rewrite(&mut relationship.to_file);
+    }
+    for dependency in &mut matrix.external_dependencies {
+        rewrite(&mut dependency.source_file);
+    }
+    for entrypoint in &mut matrix.project_info.entrypoints {
+        rewrite(&mut entrypoint.file_path);
+        entrypoint.reason.clear();
+    }
+    for component in &mut matrix.project_info.components {
+        component.files.iter_mut().for_each(&mut rewrite);
+    }
+    for term in &mut matrix.project_info.glossary_candidates {
+        term.files.iter_mut().for_each(&mut rewrite);
+    }
+    if let Some(ref mut largest) = matrix.project_info.token_summary.largest_file_path {
+        rewrite(largest);
+    }
+    for table in &mut matrix.data_model.tables {
+        table.defined_in.iter_mut().for_each(&mut rewrite);
+    }
+    for access in &mut matrix.data_model.accesses {
+        rewrite(&mut access.file);
+    }
+    matrix.metadata.project_root = PathBuf::from("redacted");
+    for root in &mut matrix.metadata.roots {
+        root.name = hasher.hash_str(&root.name);
+        root.path = PathBuf::from(&root.name);
+    }
+
+    Ok(hasher.hashed.len())
+}
@@ -0,0 +1,234 @@
+// src/core/rules.rs - Rule plugins: custom quality checks run by `csd quality` next to
+// the built-in ones
+use anyhow::Result;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::core::matrix::{ProjectMatrix, SubsetOptions};
+use crate::core::suggestions::print_suggestions;
+use crate::core::suppressions::{QualityCheck, Suppressed, Suppressions};
+use crate::plugins::communication::RulePluginCommunicator;
+use crate::plugins::interface::{
+    plugin_script_path, FindingSeverity, OutputScope, PluginType, RuleFinding, RulePluginInput,
+    RulePluginInterface,
+};
+use crate::utils::cache::CacheRun;
+use crate::utils::config::Config;
+
+/// What the rule plugins found, less what pragmas and the suppressions file silence
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleReport {
+    // By path, then line. `rule` is qualified as `<plugin>/<rule>`, the name that
+    // pragmas and suppressions use.
+    pub findings: Vec<RuleFinding>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suppressed: Vec<Suppressed>,
+    // Plugins that couldn't be run or returned an error
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<String>,
+}
+
+impl RuleReport {
+    pub fn count(&self, severity: FindingSeverity) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == severity)
+            .count()
+    }
+
+    /// Findings of the plugins that ran, minus the suppressed ones. Files that aren't in
+    /// the matrix are kept: a rule may report on files the scan skips.
+    pub fn collect(
+        matrix: &ProjectMatrix,
+        results: Vec<(String, Vec<RuleFinding>)>,
+        suppressions: &Suppressions,
+    ) -> Self {
+        let mut report = Self::default();
+        for (plugin, findings) in results {
+            for mut finding in findings {
+                finding.rule = format!("{plugin}/{}", finding.rule);
+                match suppressed_by(matrix, suppressions, &finding) {
+                    Some(suppressed) => report.suppressed.push(suppressed),
+                    None => report.findings.push(finding),
+                }
+            }
+        }
+        report
+            .findings
+            .sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+        report
+            .suppressed
+            .sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+        report
+    }
+
+    pub fn print_summary(&self) {
+        if self.findings.is_empty() && self.suppressed.is_empty() && self.failed.is_empty() {
+            return;
+        }
+        println!("\n🧩 Rule Plugins:");
+        for plugin in &self.failed {
+            println!("   ❌ {plugin} failed; see the log for details");
+        }
+        if !self.suppressed.is_empty() {
+            println!("   🔕 {} findings suppressed", self.suppressed.len());
+        }
+        if self.findings.is_empty() {
+            println!("   ✅ No findings");
+            return;
+        }
+        println!(
+            "   {} errors, {} warnings, {} notes",
+            self.count(FindingSeverity::Error),
+            self.count(FindingSeverity::Warning),
+            self.count(FindingSeverity::Info)
+        );
+        for finding in &self.findings {
+            let location = match (finding.line, &finding.element) {
+                (Some(line), _) => format!("{}:{line}", finding.file.display()),
+                (None, Some(element)) => format!("{} ({element})", finding.file.display()),
+                (None, None) => finding.file.display().to_string(),
+            };
+            println!(
+                "     {:<7} {location}: {} [{}]",
+                finding.severity, finding.message, finding.rule
+            );
+            print_suggestions(&finding.suggestions, 7);
+        }
+    }
+}
+
+// Element findings are matched by the element's lines when the matrix has it, line
+// findings by their line, the rest as file findings
+fn suppressed_by(
+    matrix: &ProjectMatrix,
+    suppressions: &Suppressions,
+    finding: &RuleFinding,
+) -> Option<Suppressed> {
+    let check = QualityCheck::Rule(finding.rule.clone());
+    let file_suppressions = suppressions.for_file(&finding.file);
+    let element = finding.element.as_deref().and_then(|name| {
+        matrix
+            .files
+            .values()
+            .find(|file| file.relative_path == finding.file)?
+            .elements
+            .iter()
+            .find(|element| element.matches_name(name))
+    });
+    let by = match (element, finding.line) {
+        (Some(element), _) => file_suppressions.element(
+            element.qualified_name(),
+            (element.line_start, element.line_end),
+            &check,
+        ),
+        (None, Some(line)) => file_suppressions.element(
+            finding.element.as_deref().unwrap_or_default(),
+            (line, line),
+            &check,
+        ),
+        (None, None) => file_suppressions.file(&check),
+    }?;
+    Some(Suppressed {
+        path: finding.file.clone(),
+        name: finding.element.clone(),
+        line: finding.line,
+        checks: vec![check],
+        by,
+    })
+}
+
+/// Run every enabled rule plugin over the matrix at `matrix_path`. A plugin with a
+/// `scope` gets a matrix of just those files, written to its run directory. A failing
+/// plugin is logged and listed in the report rather than failing the run.
+pub async fn run_rules(
+    matrix: &ProjectMatrix,
+    matrix_path: &Path,
+    project_root: &Path,
+    config: &Config,
+    suppressions: &Suppressions,
+) -> Result<RuleReport> {
+    let mut results = Vec::new();
+    let mut failed = Vec::new();
+
+    for (name, plugin_config) in config.get_enabled_rule_plugins() {
+        let Some(plugin_path) = plugin_script_path(PluginType::Rule, &plugin_config.source) else {
+            warn!(
+                "Rule plugin '{name}' has an unsupported source: {:?}",
+                plugin_config.source
+            );
+            failed.push(name.clone());
+            continue;
+        };
+        if !plugin_path.exists() {
+            warn!("Rule plugin file not found: {}", plugin_path.display());
+            failed.push(name.clone());
+            continue;
+        }
+
+        let cache_dir = config.resolve_cache_dir(project_root);
+        let run = CacheRun::create(&cache_dir, Uuid::new_v4())?;
+        let input_matrix = if plugin_config.scope.is_empty() {
+            matrix_path.to_path_buf()
+        } else {
+            let files = OutputScope::from_patterns(plugin_config.scope.clone()).select(matrix)?;
+            if files.is_empty() {
+                info!("Rule plugin '{name}' skipped: no files in its scope");
+                continue;
+            }
+            let options = SubsetOptions {
+                dependency_depth: 0,
+                dependent_depth: 0,
+                max_tokens: None,
+            };
+            let path = run.dir().join("matrix.json");
+            matrix.subset(&files, &options).save(&path).await?;
+            path
+        };
+        let input = RulePluginInput {
+            matrix_path: input_matrix,
+            project_root: project_root.to_path_buf(),
+            cache_dir: cache_dir.to_string_lossy().to_string(),
+            plugin_config: plugin_config
+                .config
+                .as_ref()
+                .map(|v| serde_json::to_value(v).unwrap_or(serde_json::Value::Null)),
+        };
+
+        let mut communicator = RulePluginCommunicator::new(plugin_path)
+            .with_cache_dir(cache_dir)
+            .with_run_dir(run.dir().to_path_buf())
+            .with_logs(config.plugin_logs.clone())
+            .with_offline(config.offline)
+            .with_runtime(plugin_config.runtime);
+        if let Some(ref node_exe) = config.node_executable {
+            communicator = communicator.with_node_executable(node_exe.clone());
+        }
+        if let Some(ref wasm_exe) = config.wasm_executable {
+            communicator = communicator.with_wasm_executable(wasm_exe.clone());
+        }
+        if let Some(ref python_exe) = config.python_executable {
+            communicator = communicator.with_python_executable(python_exe.clone());
+        } else {
+            communicator = communicator.with_python_auto_detect();
+        }
+
+        match communicator.check(input).await {
+            Ok(findings) => {
+                info!("Rule plugin '{name}' reported {} findings", findings.len());
+                results.push((name.clone(), findings));
+            }
+            Err(e) => {
+                warn!("Rule plugin '{name}' failed: {e}");
+                failed.push(name.clone());
+            }
+        }
+    }
+
+    Ok(RuleReport {
+        failed,
+        ..RuleReport::collect(matrix, results, suppressions)
+    })
+}
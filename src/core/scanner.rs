@@ -1,12 +1,40 @@
 // src/core/scanner.rs - Enhanced scanner with token counting
-use crate::core::matrix::{estimate_code_tokens, estimate_tokens, ProjectMatrix, TokenInfo};
-use crate::plugins::interface::{InputPluginInterface, PluginInput};
-use crate::utils::config::Config;
+use crate::core::bench::{target_id, BenchResult};
+use crate::core::categories::FileClassifier;
+use crate::core::comments::syntax_for;
+use crate::core::complexity::{fill_missing_complexity, needs_estimate};
+use crate::core::contracts::link_contracts;
+use crate::core::data_model::detect_data_model;
+use crate::core::deployments::detect_deployments;
+use crate::core::glossary::extract_glossary;
+use crate::core::matrix::{estimate_tokens, ProjectMatrix, ScanRoot, ScanStats, TokenInfo};
+use crate::core::pipeline::{ByteBudget, BytePermit, READ_AHEAD_BATCHES};
+use crate::core::project::ProjectContext;
+use crate::core::structure::{extract_structure, link_imports};
+use crate::core::token_cache::{TokenCache, TokenCounts};
+use crate::plugins::communication::{PluginPool, Transport};
+use crate::plugins::interface::{
+    plugin_script_path, ContentPreview, InputPluginInterface, PluginInfo, PluginInput,
+    PluginInterface, PluginType, CAPABILITY_ANALYZE_BATCH, CAPABILITY_CONTENT_PATH,
+    CAPABILITY_PERSISTENT, CAPABILITY_PROJECT_CONTEXT, CAPABILITY_SOCKET,
+};
+use crate::plugins::logs::prune_plugin_logs;
+use crate::utils::cache::{prune_stale_runs, run_dir, CacheRun};
+use crate::utils::config::{
+    Config, FilePatterns, InputPluginConfig, ScanLevel, TruncationStrategy,
+};
+use crate::utils::file_utils::normalize_lexically;
+use crate::utils::intern::IStr;
+use crate::utils::profile::{self, Stage};
 use anyhow::Result;
 use ignore::WalkBuilder;
 use log::{debug, info, warn};
-use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use uuid::Uuid;
 
 #[derive(Debug, Clone)]
 pub struct FileInfo {
@@ -17,18 +45,262 @@ pub struct FileInfo {
     pub is_text: bool,
     pub plugin_name: Option<String>,
     pub content_hash: String,
+    // Set when several plugins matched and `can_analyze` picked this one
+    pub plugin_confidence: Option<f32>,
+}
+
+/// Result of probing one enabled input plugin before a scan
+#[derive(Debug, Clone)]
+pub struct PluginProbe {
+    pub name: String,
+    pub plugin_path: Option<PathBuf>,
+    pub info: Option<PluginInfo>,
+    pub warnings: Vec<String>,
+    pub error: Option<String>, // Why the plugin can't be used; its files are scanned unanalyzed
+}
+
+impl PluginProbe {
+    pub fn is_ready(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Configured extensions the plugin doesn't claim to support. A plugin that lists
+/// no extensions makes no claim either way.
+pub fn unclaimed_patterns(patterns: &FilePatterns, info: &PluginInfo) -> Vec<String> {
+    if info.supported_extensions.is_empty() {
+        return Vec::new();
+    }
+    patterns
+        .extensions
+        .iter()
+        .filter(|ext| {
+            !info
+                .supported_extensions
+                .iter()
+                .any(|claimed| claimed.eq_ignore_ascii_case(ext))
+        })
+        .map(|ext| {
+            format!(
+                "configured for {ext} files but only claims {}",
+                info.supported_extensions.join(", ")
+            )
+        })
+        .collect()
+}
+
+/// Why `scan` leaves a file out of the matrix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Exclusion {
+    Missing,
+    Directory,
+    OutsideRoots,
+    Hidden {
+        component: String,
+    },
+    // A .gitignore, .ignore, .git/info/exclude or global gitignore rule
+    IgnoreFile {
+        pattern: String,
+        source: Option<PathBuf>,
+    },
+    CacheDir,
+    IgnorePattern {
+        pattern: String,
+    },
+    TooLarge {
+        size_bytes: u64,
+        limit_bytes: u64,
+    },
+}
+
+impl std::fmt::Display for Exclusion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Exclusion::Missing => write!(f, "file does not exist"),
+            Exclusion::Directory => write!(f, "it is a directory; only files are scanned"),
+            Exclusion::OutsideRoots => write!(f, "it is outside the scanned root(s)"),
+            Exclusion::Hidden { component } => write!(
+                f,
+                "hidden path component '{component}' (set scanning.include_hidden)"
+            ),
+            Exclusion::IgnoreFile {
+                pattern,
+                source: Some(source),
+            } => write!(f, "ignore rule '{pattern}' in {}", source.display()),
+            Exclusion::IgnoreFile {
+                pattern,
+                source: None,
+            } => write!(f, "ignore rule '{pattern}'"),
+            Exclusion::CacheDir => write!(f, "it is inside the csd cache directory"),
+            Exclusion::IgnorePattern { pattern } => {
+                write!(f, "scanning.ignore_patterns entry '{pattern}'")
+            }
+            Exclusion::TooLarge {
+                size_bytes,
+                limit_bytes,
+            } => write!(
+                f,
+                "{size_bytes} bytes exceeds scanning.max_file_size_mb ({limit_bytes} bytes)"
+            ),
+        }
+    }
+}
+
+/// What a scan would do with one file, from `ProjectScanner::explain`
+#[derive(Debug, Clone)]
+pub struct FileExplanation {
+    pub path: PathBuf,
+    pub relative_path: Option<PathBuf>,
+    pub exclusion: Option<Exclusion>,
+    // Enabled input plugins whose patterns match, sorted by name
+    pub candidates: Vec<String>,
+    // The plugin picked by pattern order; `can_analyze` decides when there are several
+    pub plugin: Option<String>,
+    pub is_text: bool,
+}
+
+impl FileExplanation {
+    pub fn is_scanned(&self) -> bool {
+        self.exclusion.is_none()
+    }
+}
+
+/// Cut `content` down to roughly `max_tokens` (by `estimate_tokens`), or None when it
+/// already fits. `Sample` keeps equal slices from the start, middle and end, joined by
+/// newlines, so generated or minified files still show their overall shape.
+pub fn truncate_to_tokens(
+    content: &str,
+    max_tokens: u64,
+    strategy: TruncationStrategy,
+) -> Option<String> {
+    if estimate_tokens(content) <= max_tokens {
+        return None;
+    }
+    let budget = (max_tokens as usize).saturating_mul(4);
+    // Slice at char boundaries at or before `index`
+    let floor = |index: usize| {
+        let mut index = index.min(content.len());
+        while !content.is_char_boundary(index) {
+            index -= 1;
+        }
+        index
+    };
+
+    Some(match strategy {
+        TruncationStrategy::Head => content[..floor(budget)].to_string(),
+        TruncationStrategy::Sample => {
+            let slice = budget.saturating_sub(2) / 3;
+            let middle = floor(content.len() / 2 - slice / 2);
+            let tail = floor(content.len() - slice);
+            [
+                &content[..floor(slice)],
+                &content[middle..floor(middle + slice)],
+                &content[tail..],
+            ]
+            .join("\n")
+        }
+    })
+}
+
+/// Token info of a file no plugin analyzed: everything outside comments is code
+fn basic_token_info(counts: TokenCounts) -> TokenInfo {
+    let comments = counts.documentation_tokens + counts.comment_tokens;
+    TokenInfo {
+        total_tokens: counts.code_tokens,
+        code_tokens: counts.code_tokens.saturating_sub(comments),
+        documentation_tokens: counts.documentation_tokens,
+        comment_tokens: counts.comment_tokens,
+    }
+}
+
+/// The path the walker was refused access to, when `error` is a permission error
+fn permission_denied_path(error: &ignore::Error) -> Option<&Path> {
+    match error {
+        ignore::Error::WithPath { path, err } => {
+            let denied = err
+                .io_error()
+                .is_some_and(|e| e.kind() == std::io::ErrorKind::PermissionDenied);
+            denied.then_some(path.as_path())
+        }
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            permission_denied_path(err)
+        }
+        _ => None,
+    }
+}
+
+/// A batch of one plugin's files on its way from the read stage to analysis
+enum ReadBatch<'a> {
+    Read {
+        plugin_name: &'a str,
+        files: Vec<ReadFile<'a>>,
+    },
+    // The scan was out of time before it was read
+    Expired(&'a [FileInfo]),
+}
+
+impl<'a> ReadBatch<'a> {
+    fn infos(&self) -> Vec<&'a FileInfo> {
+        match self {
+            ReadBatch::Read { files, .. } => files.iter().map(|f| f.info).collect(),
+            ReadBatch::Expired(files) => files.iter().collect(),
+        }
+    }
+}
+
+/// A file's content, cut down to `scanning.max_file_tokens`, with its share of the
+/// in-flight byte budget
+struct ReadFile<'a> {
+    info: &'a FileInfo,
+    content: std::io::Result<String>,
+    truncated: bool,
+    budget: BytePermit,
+}
+
+// Files that couldn't be hashed share the "error" hash, so their counts aren't cached
+fn has_content_hash(file_info: &FileInfo) -> bool {
+    !file_info.content_hash.is_empty() && file_info.content_hash != "error"
+}
+
+/// FileNode metadata recorded by the scanner itself
+fn file_metadata(file_info: &FileInfo) -> serde_json::Value {
+    match file_info.plugin_confidence {
+        Some(confidence) => serde_json::json!({ "plugin_confidence": confidence }),
+        None => serde_json::Value::Null,
+    }
 }
 
 pub struct ProjectScanner {
     config: Config,
     project_root: PathBuf,
+    // Directories scanned instead of `project_root` when the code lives in several trees
+    roots: Vec<PathBuf>,
+    // Capabilities each plugin advertised, asked once per plugin per scan
+    plugin_capabilities: std::sync::Mutex<HashMap<PathBuf, Vec<String>>>,
+    // Manifest digests of the scan roots, read the first time a plugin asks for one
+    project_contexts: std::sync::Mutex<HashMap<PathBuf, Arc<ProjectContext>>>,
+    // Names this scanner's scratch directory under `<cache>/runs`
+    run_id: Uuid,
+    // Token estimates of unchanged files, carried over from the previous scan
+    token_cache: TokenCache,
+    // Running processes of persistent plugins, shut down when the scan finishes
+    pool: Option<Arc<PluginPool>>,
 }
 
 impl ProjectScanner {
     pub fn new(config: Config) -> Self {
         Self {
-            config,
             project_root: PathBuf::from("."),
+            roots: Vec::new(),
+            plugin_capabilities: Default::default(),
+            project_contexts: Default::default(),
+            run_id: Uuid::new_v4(),
+            token_cache: TokenCache::default(),
+            pool: config
+                .plugin_pool
+                .enabled
+                .then(|| Arc::new(PluginPool::new(config.plugin_pool.clone()))),
+            config,
         }
     }
 
@@ -37,63 +309,305 @@ impl ProjectScanner {
         self
     }
 
+    /// Scan several sibling directories into one matrix. Relative paths are prefixed
+    /// with each root's name; the project root still holds the cache and config.
+    pub fn with_roots(mut self, roots: Vec<PathBuf>) -> Self {
+        self.roots = roots;
+        self
+    }
+
+    /// Named roots recorded in the matrix, empty for a single-root scan
+    pub fn scan_roots(&self) -> Vec<ScanRoot> {
+        ScanRoot::named(&self.roots)
+    }
+
+    /// Root a scanned file came from, which plugins see as their project root
+    fn root_of(&self, path: &Path) -> &Path {
+        self.roots
+            .iter()
+            .find(|root| path.starts_with(root))
+            .unwrap_or(&self.project_root)
+    }
+
     pub async fn scan_to_matrix(&self) -> Result<ProjectMatrix> {
         debug!(
             "Starting file scan and matrix creation in: {}",
             self.project_root.display()
         );
 
+        let started = Instant::now();
+        let deadline = self
+            .config
+            .scanning
+            .max_duration()
+            .map(|limit| started + limit);
+        let classifier = FileClassifier::new(&self.config.categories)?;
+        let cache_dir = self.config.resolve_cache_dir(&self.project_root);
+        if let Err(e) = prune_plugin_logs(&cache_dir, &self.config.plugin_logs).await {
+            warn!("Failed to prune plugin logs: {e}");
+        }
+        if let Err(e) = prune_stale_runs(&cache_dir) {
+            warn!("Failed to remove stale run directories: {e}");
+        }
+        // Plugin scratch files live here until the scan finishes
+        let _run = CacheRun::create(&cache_dir, self.run_id)?;
+        let mut stats = ScanStats::default();
         let mut matrix = ProjectMatrix::new(self.project_root.clone());
-        let files = self.scan().await?;
+        matrix.metadata.roots = self.scan_roots();
+        matrix.metadata.hash_algorithm = self.config.scanning.hash_algorithm;
+        let mut files = self.walk(&mut stats).await?;
+        self.hash_files(&mut files, &mut stats);
+        if !stats.skipped_permission.is_empty() && self.config.scanning.fail_on_permission_denied {
+            let paths: Vec<String> = stats
+                .skipped_permission
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            return Err(anyhow::anyhow!(
+                "Permission denied for {} paths: {}",
+                paths.len(),
+                paths.join(", ")
+            ));
+        }
+        self.token_cache.load(&cache_dir).await;
+
+        // Probe every plugin up front instead of discovering breakage file by file; a
+        // structure scan starts none, so every file gets a basic node
+        let level = self.config.scanning.level;
+        matrix.metadata.scan_level = level;
+        let probes = match level {
+            ScanLevel::Full => self.warm_up_plugins().await,
+            ScanLevel::Structure => Vec::new(),
+        };
+        let unready: Vec<&PluginProbe> = probes.iter().filter(|p| !p.is_ready()).collect();
+        if !unready.is_empty() && self.config.scanning.fail_on_unready_plugins {
+            let reasons: Vec<String> = unready
+                .iter()
+                .map(|p| format!("{}: {}", p.name, p.error.as_deref().unwrap_or_default()))
+                .collect();
+            return Err(anyhow::anyhow!(
+                "Input plugins failed to start: {}",
+                reasons.join("; ")
+            ));
+        }
+        let ready: HashSet<&str> = probes
+            .iter()
+            .filter(|p| p.is_ready())
+            .map(|p| p.name.as_str())
+            .collect();
+
+        for file_info in files
+            .iter_mut()
+            .filter(|f| f.is_text && f.plugin_name.is_some())
+        {
+            self.route_file(file_info, &ready).await;
+        }
 
         debug!("Found {} files, analyzing with plugins...", files.len());
 
-        // Convert files to matrix nodes with plugin analysis
+        // Group plugin-handled files so each plugin process can take a whole batch
+        let mut by_plugin: BTreeMap<String, Vec<FileInfo>> = BTreeMap::new();
         for file_info in files {
-            debug!(
-                "🔍 Processing file: {} (is_text: {}, plugin: {:?})",
-                file_info.path.display(),
-                file_info.is_text,
-                file_info.plugin_name
-            );
+            match &file_info.plugin_name {
+                Some(plugin_name) if file_info.is_text && ready.contains(plugin_name.as_str()) => {
+                    by_plugin
+                        .entry(plugin_name.clone())
+                        .or_default()
+                        .push(file_info);
+                }
+                _ => {
+                    debug!(
+                        "❌ Skipping plugin for: {} (is_text: {}, plugin: {:?})",
+                        file_info.path.display(),
+                        file_info.is_text,
+                        file_info.plugin_name
+                    );
+                    // Create basic file node without plugin analysis
+                    let file_node = self.create_basic_file_node(&file_info).await?;
+                    matrix.add_file(file_node);
+                }
+            }
+        }
 
-            let file_node = if file_info.is_text && file_info.plugin_name.is_some() {
-                debug!("✅ Calling plugin for: {}", file_info.path.display());
-                // Analyze with plugin
-                self.analyze_file_with_plugin(&file_info, &mut matrix)
-                    .await?
-            } else {
+        // Files are read while plugins analyze earlier batches, and only as far ahead as
+        // the channel and the in-flight byte budget allow
+        let budget = ByteBudget::new(self.config.scanning.in_flight_bytes());
+        let (sender, mut receiver) = mpsc::channel(READ_AHEAD_BATCHES);
+        let read = self.read_batches(&by_plugin, deadline, &budget, sender);
+        let analyze = async {
+            while let Some(batch) = receiver.recv().await {
+                let (plugin_name, files) = match batch {
+                    ReadBatch::Read { plugin_name, files }
+                        if deadline.is_none_or(|deadline| Instant::now() < deadline) =>
+                    {
+                        (plugin_name, files)
+                    }
+                    expired => {
+                        let infos = expired.infos();
+                        stats.skipped_deadline += infos.len();
+                        for file_node in self.create_basic_file_nodes(infos).await? {
+                            matrix.add_file(file_node);
+                        }
+                        continue;
+                    }
+                };
                 debug!(
-                    "❌ Skipping plugin for: {} (is_text: {}, plugin: {:?})",
-                    file_info.path.display(),
-                    file_info.is_text,
-                    file_info.plugin_name
+                    "✅ Calling plugin {plugin_name} for {} file(s)",
+                    files.len()
                 );
-                // Create basic file node without plugin analysis
-                self.create_basic_file_node(&file_info).await?
-            };
+                let file_nodes = self
+                    .analyze_files_with_plugin(
+                        plugin_name,
+                        files,
+                        deadline,
+                        &mut matrix,
+                        &mut stats,
+                    )
+                    .await?;
+                for file_node in file_nodes {
+                    matrix.add_file(file_node);
+                }
+            }
+            Ok::<_, anyhow::Error>(())
+        };
+        // An analysis error drops the receiver, which stops the read stage
+        let ((), analyzed) = tokio::join!(read, analyze);
+        analyzed?;
+        if let Some(pool) = &self.pool {
+            pool.shutdown().await;
+            self.record_pool_stats(pool, &mut stats);
+        }
 
-            matrix.add_file(file_node);
+        for file_node in matrix.files.values_mut() {
+            file_node.category = Some(classifier.classify(&file_node.relative_path));
+        }
+        // Results from different plugin versions aren't comparable, so record which ran
+        for probe in &probes {
+            if let (Some(info), Some(plugin_config)) =
+                (&probe.info, self.config.get_input_plugin(&probe.name))
+            {
+                matrix.metadata.pin_plugin(
+                    &probe.name,
+                    &info.version,
+                    plugin_config.source.describe(),
+                );
+            }
+        }
+
+        if level == ScanLevel::Structure {
+            link_imports(&mut matrix);
         }
 
         // Finalize the matrix to detect entrypoints and calculate summaries
         matrix.finalize();
+        matrix.project_info.deployments = detect_deployments(&matrix);
+        matrix.data_model = detect_data_model(&matrix);
+        link_contracts(&mut matrix);
+        if self.config.glossary.enabled {
+            matrix.project_info.glossary_candidates =
+                extract_glossary(&matrix, &self.config.glossary);
+        }
+        if let Err(e) = self.token_cache.save(&cache_dir).await {
+            warn!("Failed to save token cache: {e}");
+        }
+        (stats.token_cache_hits, stats.token_cache_misses) = self.token_cache.stats();
+        if stats.skipped_deadline > 0 {
+            warn!(
+                "Scan reached its {} minute limit; {} files were kept without analysis",
+                self.config
+                    .scanning
+                    .max_duration_minutes
+                    .unwrap_or_default(),
+                stats.skipped_deadline
+            );
+        }
+        stats.duration_ms = started.elapsed().as_millis() as u64;
+        matrix.metadata.scan_stats = stats;
 
         debug!("Matrix created with {} files", matrix.files.len());
         Ok(matrix)
     }
 
-    async fn analyze_file_with_plugin(
+    /// Read stage: the content of each plugin's files in batches of
+    /// `scanning.plugin_batch_size`, reserving each file's size from `budget` first. A
+    /// batch is sent early rather than wait on the budget while holding part of it.
+    async fn read_batches<'a>(
         &self,
-        file_info: &FileInfo,
-        matrix: &mut ProjectMatrix,
-    ) -> Result<crate::core::matrix::FileNode> {
-        info!("🚀 Starting analysis for: {}", file_info.path.display());
+        by_plugin: &'a BTreeMap<String, Vec<FileInfo>>,
+        deadline: Option<Instant>,
+        budget: &ByteBudget,
+        sender: mpsc::Sender<ReadBatch<'a>>,
+    ) {
+        let batch_size = self.config.scanning.plugin_batch_size.max(1);
+        for (plugin_name, plugin_files) in by_plugin {
+            for batch in plugin_files.chunks(batch_size) {
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    if sender.send(ReadBatch::Expired(batch)).await.is_err() {
+                        return;
+                    }
+                    continue;
+                }
+                let mut files = Vec::with_capacity(batch.len());
+                for info in batch {
+                    let permit = match budget.try_reserve(info.size_bytes) {
+                        Some(permit) => permit,
+                        None => {
+                            if !files.is_empty() {
+                                let partial = ReadBatch::Read {
+                                    plugin_name,
+                                    files: std::mem::take(&mut files),
+                                };
+                                if sender.send(partial).await.is_err() {
+                                    return;
+                                }
+                            }
+                            budget.reserve(info.size_bytes).await
+                        }
+                    };
+                    files.push(self.read_file(info, permit).await);
+                }
+                let batch = ReadBatch::Read { plugin_name, files };
+                if sender.send(batch).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
 
-        use crate::plugins::communication::InputPluginCommunicator;
+    async fn read_file<'a>(&self, info: &'a FileInfo, budget: BytePermit) -> ReadFile<'a> {
+        let (content, truncated) = match tokio::fs::read_to_string(&info.path).await {
+            Ok(content) => match self.truncate(&content) {
+                Some(shortened) => {
+                    debug!(
+                        "Truncated {} to scanning.max_file_tokens",
+                        info.relative_path.display()
+                    );
+                    (Ok(shortened), true)
+                }
+                None => (Ok(content), false),
+            },
+            Err(e) => (Err(e), false),
+        };
+        ReadFile {
+            info,
+            content,
+            truncated,
+            budget,
+        }
+    }
 
-        let plugin_name = file_info.plugin_name.as_ref().unwrap();
-        debug!("📝 Plugin name: {plugin_name}");
+    async fn analyze_files_with_plugin(
+        &self,
+        plugin_name: &str,
+        files: Vec<ReadFile<'_>>,
+        deadline: Option<Instant>,
+        matrix: &mut ProjectMatrix,
+        stats: &mut ScanStats,
+    ) -> Result<Vec<crate::core::matrix::FileNode>> {
+        info!(
+            "🚀 Starting analysis of {} file(s) with plugin: {plugin_name}",
+            files.len()
+        );
 
         // Get input plugin configuration from new structure
         let plugin_config = self
@@ -103,16 +617,13 @@ impl ProjectScanner {
 
         debug!("⚙️ Got input plugin config for: {plugin_name}");
 
-        // Resolve plugin path with the new plugin_type structure
-        let plugin_path = match &plugin_config.source {
-            crate::utils::config::PluginSource::Builtin { name, plugin_type } => {
-                PathBuf::from(format!("plugins/input/{plugin_type}/{name}.py"))
-            }
-            crate::utils::config::PluginSource::Local { path } => PathBuf::from(path),
-            _ => {
-                // TODO: Handle other plugin sources (GitHub, Git)
-                return self.create_basic_file_node(file_info).await;
-            }
+        let plugin_stats = stats.plugins.entry(plugin_name.to_string()).or_default();
+        plugin_stats.files += files.len();
+
+        let Some(plugin_path) = plugin_script_path(PluginType::Input, &plugin_config.source) else {
+            return self
+                .create_basic_file_nodes(files.iter().map(|f| f.info))
+                .await;
         };
 
         debug!("📂 Plugin path resolved to: {}", plugin_path.display());
@@ -120,74 +631,412 @@ impl ProjectScanner {
         // Check if plugin file exists
         if !plugin_path.exists() {
             warn!("Plugin file not found: {}", plugin_path.display());
-            return self.create_basic_file_node(file_info).await;
+            plugin_stats.failed += files.len();
+            stats.failed_files += files.len();
+            return self
+                .create_basic_file_nodes(files.iter().map(|f| f.info))
+                .await;
         }
 
-        debug!("✅ Plugin file exists");
+        // Plugins write their results into this scan's run directory
+        let run_dir = self.run_dir();
+        let plugin_settings = plugin_config.config.as_ref().map(|v| {
+            // Convert serde_yaml::Value to serde_json::Value
+            serde_json::to_value(v).unwrap_or(serde_json::Value::Null)
+        });
+
+        let mut file_nodes = Vec::with_capacity(files.len());
+        let mut analyzed = Vec::with_capacity(files.len());
+        let mut truncated = Vec::with_capacity(files.len());
+        let mut inputs = Vec::with_capacity(files.len());
+        // The content stays counted against the budget until its results are merged
+        let mut permits = Vec::with_capacity(files.len());
+        for file in files {
+            let file_info = file.info;
+            let content = match file.content {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Could not read file {}: {}", file_info.path.display(), e);
+                    plugin_stats.failed += 1;
+                    stats.failed_files += 1;
+                    file_nodes.push(self.create_basic_file_node(file_info).await?);
+                    continue;
+                }
+            };
+            let was_truncated = file.truncated;
+            permits.push(file.budget);
+
+            inputs.push(PluginInput {
+                file_path: file_info.path.clone(),
+                relative_path: file_info.relative_path.clone(),
+                content,
+                project_root: self.root_of(&file_info.path).to_path_buf(),
+                cache_dir: run_dir.to_string_lossy().to_string(),
+                plugin_config: plugin_settings.clone(),
+                content_path: None,
+                truncated: was_truncated,
+                project: None,
+            });
+            analyzed.push(file_info);
+            truncated.push(was_truncated);
+        }
 
-        // Read file content
-        debug!("📖 Reading file content...");
-        let content = match tokio::fs::read_to_string(&file_info.path).await {
-            Ok(content) => {
-                debug!("✅ File content read ({} bytes)", content.len());
-                content
+        if inputs.is_empty() {
+            return Ok(file_nodes);
+        }
+
+        debug!("📡 Creating plugin communicator...");
+        let communicator = self.input_communicator(&plugin_path, plugin_config);
+
+        // Plugins that read files themselves don't need the content inlined, and
+        // batch-capable plugins get one process per batch rather than per file
+        let capabilities = self.plugin_capabilities(&plugin_path, &communicator).await;
+        let supports = |capability: &str| capabilities.iter().any(|c| c == capability);
+        let mut communicator = communicator
+            .with_content_path(supports(CAPABILITY_CONTENT_PATH))
+            .with_batch(supports(CAPABILITY_ANALYZE_BATCH));
+        if supports(CAPABILITY_PROJECT_CONTEXT) {
+            for input in &mut inputs {
+                let context = self.project_context(&input.project_root);
+                input.project = Some(context.as_ref().clone());
             }
+        }
+        // Socket plugins are persistent too, and get the socket when they can have it
+        if let Some(pool) = &self.pool {
+            if supports(CAPABILITY_SOCKET) {
+                communicator = communicator.with_pool(pool.clone(), Transport::Socket);
+            } else if supports(CAPABILITY_PERSISTENT) {
+                communicator = communicator.with_pool(pool.clone(), Transport::Stdio);
+            }
+        }
+        if let Some(timeout) = self.config.scanning.file_timeout() {
+            communicator = communicator.with_file_timeout(timeout);
+        }
+        if let Some(deadline) = deadline {
+            communicator = communicator.with_deadline(deadline);
+        }
+        // Failures once the scan is out of time are the limit's doing, not the plugin's
+        let out_of_time = || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+
+        debug!("🔄 Starting plugin communication...");
+        let started = Instant::now();
+        let outputs = communicator.analyze_batch(inputs).await;
+        plugin_stats.time_ms += started.elapsed().as_millis() as u64;
+        let outputs = match outputs {
+            Ok(outputs) => outputs,
             Err(e) => {
-                warn!("Could not read file {}: {}", file_info.path.display(), e);
-                return self.create_basic_file_node(file_info).await;
+                warn!(
+                    "❌ Plugin {plugin_name} failed for {} file(s): {e}",
+                    analyzed.len()
+                );
+                if out_of_time() {
+                    stats.skipped_deadline += analyzed.len();
+                } else {
+                    plugin_stats.failed += analyzed.len();
+                    stats.failed_files += analyzed.len();
+                }
+                file_nodes.extend(self.create_basic_file_nodes(analyzed).await?);
+                return Ok(file_nodes);
             }
         };
 
-        // Set up cache directory
-        let cache_dir = self.project_root.join(".csd_cache");
+        for ((file_info, output), was_truncated) in analyzed.into_iter().zip(outputs).zip(truncated)
+        {
+            match output {
+                Ok(plugin_output) => {
+                    stats.cache_misses += 1;
+                    info!(
+                        "✅ Analysis successful for: {} with {} elements",
+                        file_info.path.display(),
+                        plugin_output.elements.len()
+                    );
+
+                    // Convert plugin output to matrix data
+                    let mut file_node = self
+                        .convert_plugin_output_to_file_node(file_info, plugin_output, matrix)
+                        .await?;
+                    file_node.truncated = was_truncated;
+                    file_nodes.push(file_node);
+                }
+                Err(e) => {
+                    warn!(
+                        "❌ Plugin analysis failed for {}: {}",
+                        file_info.path.display(),
+                        e
+                    );
+                    if out_of_time() {
+                        stats.skipped_deadline += 1;
+                    } else {
+                        plugin_stats.failed += 1;
+                        stats.failed_files += 1;
+                    }
+                    file_nodes.push(self.create_basic_file_node(file_info).await?);
+                }
+            }
+        }
 
-        debug!("🔧 Creating plugin input...");
-        // Create plugin input
-        let plugin_input = PluginInput {
-            file_path: file_info.path.clone(),
-            relative_path: file_info.relative_path.clone(),
-            content,
-            project_root: self.project_root.clone(),
-            cache_dir: cache_dir.to_string_lossy().to_string(),
-            plugin_config: plugin_config.config.as_ref().map(|v| {
-                // Convert serde_yaml::Value to serde_json::Value
-                serde_json::to_value(v).unwrap_or(serde_json::Value::Null)
-            }),
-        };
+        Ok(file_nodes)
+    }
 
-        debug!("📡 Creating plugin communicator...");
-        // Communicate with plugin using the new InputPluginCommunicator
-        let mut communicator = InputPluginCommunicator::new(plugin_path).with_cache_dir(cache_dir);
+    /// Call `get_info` once on every enabled input plugin, checking that it responds,
+    /// is an input plugin, and claims the extensions its config routes to it
+    pub async fn warm_up_plugins(&self) -> Vec<PluginProbe> {
+        let mut plugins = self.config.get_enabled_input_plugins();
+        plugins.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut probes = Vec::with_capacity(plugins.len());
+        for (name, plugin_config) in plugins {
+            let mut probe = PluginProbe {
+                name: name.clone(),
+                plugin_path: plugin_script_path(PluginType::Input, &plugin_config.source),
+                info: None,
+                warnings: Vec::new(),
+                error: None,
+            };
 
-        // Use configured Python executable or auto-detect
-        if let Some(ref python_exe) = self.config.python_executable {
-            communicator = communicator.with_python_executable(python_exe.clone());
-        } else {
-            communicator = communicator.with_python_auto_detect();
+            match &probe.plugin_path {
+                None => probe.error = Some("plugin source is not supported yet".to_string()),
+                Some(path) if !path.exists() => {
+                    probe.error = Some(format!("plugin file not found: {}", path.display()))
+                }
+                Some(path) => {
+                    let communicator = self.input_communicator(path, plugin_config);
+                    match communicator.get_info().await {
+                        Ok(info) if !info.is_input_plugin() => {
+                            probe.error = Some(format!(
+                                "{} reports itself as an {:?} plugin",
+                                info.name, info.plugin_type
+                            ));
+                        }
+                        Ok(info) => {
+                            // Caught here rather than by the plugin failing on every file
+                            let violations = info.check_config(plugin_config.config.as_ref());
+                            if violations.is_empty() {
+                                probe.warnings =
+                                    unclaimed_patterns(&plugin_config.file_patterns, &info);
+                                self.plugin_capabilities
+                                    .lock()
+                                    .unwrap()
+                                    .insert(path.clone(), info.capabilities.clone());
+                                probe.info = Some(info);
+                            } else {
+                                probe.error = Some(format!(
+                                    "its config doesn't match the plugin's schema: {}",
+                                    violations
+                                        .iter()
+                                        .map(ToString::to_string)
+                                        .collect::<Vec<_>>()
+                                        .join("; ")
+                                ));
+                            }
+                        }
+                        Err(e) => probe.error = Some(format!("did not respond to get_info: {e}")),
+                    }
+                }
+            }
+
+            match &probe.error {
+                Some(error) => warn!(
+                    "Input plugin '{}' is not ready, its files won't be analyzed: {error}",
+                    probe.name
+                ),
+                None => {
+                    debug!("Input plugin '{}' is ready", probe.name);
+                    for warning in &probe.warnings {
+                        warn!("Input plugin '{}': {warning}", probe.name);
+                    }
+                }
+            }
+            probes.push(probe);
         }
 
-        debug!("🔄 Starting plugin communication...");
-        match communicator.analyze(plugin_input).await {
-            Ok(plugin_output) => {
-                info!(
-                    "✅ Analysis successful for: {} with {} elements",
-                    file_info.path.display(),
-                    plugin_output.elements.len()
-                );
+        probes
+    }
 
-                // Convert plugin output to matrix data
-                self.convert_plugin_output_to_file_node(file_info, plugin_output, matrix)
-                    .await
+    /// When several ready plugins match a file, ask each through `can_analyze` and keep
+    /// the most confident one. Otherwise the pattern match stands.
+    async fn route_file(&self, file_info: &mut FileInfo, ready: &HashSet<&str>) {
+        let candidates: Vec<String> = self
+            .config
+            .find_input_plugins_for_file(&file_info.path)
+            .into_iter()
+            .filter(|name| ready.contains(name.as_str()))
+            .collect();
+        if candidates.len() < 2 {
+            return;
+        }
+
+        let preview =
+            match ContentPreview::read(&file_info.path, self.config.scanning.preview_bytes).await {
+                Ok(preview) => preview,
+                Err(_) => return,
+            };
+
+        let mut best: Option<(String, f32)> = None;
+        for name in candidates {
+            let Some(plugin_config) = self.config.get_input_plugin(&name) else {
+                continue;
+            };
+            let Some(plugin_path) = plugin_script_path(PluginType::Input, &plugin_config.source)
+            else {
+                continue;
+            };
+
+            match self
+                .input_communicator(&plugin_path, plugin_config)
+                .can_analyze_with_confidence(&file_info.path, &preview)
+                .await
+            {
+                Ok((true, confidence))
+                    if best.as_ref().is_none_or(|(_, top)| confidence > *top) =>
+                {
+                    best = Some((name, confidence));
+                }
+                Ok(_) => {}
+                Err(e) => debug!(
+                    "can_analyze failed for {} with {name}: {e}",
+                    file_info.path.display()
+                ),
             }
+        }
+
+        if let Some((name, confidence)) = best {
+            debug!(
+                "Routed {} to {name} (confidence {confidence:.2})",
+                file_info.relative_path.display()
+            );
+            file_info.plugin_name = Some(name);
+            file_info.plugin_confidence = Some(confidence);
+        }
+    }
+
+    /// `content` cut down per `scanning.max_file_tokens`, or None when it fits
+    fn truncate(&self, content: &str) -> Option<String> {
+        let max_tokens = self.config.scanning.max_file_tokens?;
+        truncate_to_tokens(content, max_tokens, self.config.scanning.truncation)
+    }
+
+    /// Whether content of `tokens` (by `estimate_tokens`) is kept whole
+    fn fits(&self, tokens: u64) -> bool {
+        self.config
+            .scanning
+            .max_file_tokens
+            .is_none_or(|max_tokens| tokens <= max_tokens)
+    }
+
+    /// Token estimates cached for the file's content hash
+    fn cached_tokens(&self, file_info: &FileInfo) -> Option<TokenCounts> {
+        has_content_hash(file_info)
+            .then(|| self.token_cache.get(&self.token_cache_key(file_info)))
+            .flatten()
+    }
+
+    fn cache_tokens(&self, file_info: &FileInfo, counts: TokenCounts) {
+        if has_content_hash(file_info) {
+            self.token_cache
+                .insert(&self.token_cache_key(file_info), counts);
+        }
+    }
+
+    // Scans with different hash algorithms share the cache directory
+    fn token_cache_key(&self, file_info: &FileInfo) -> String {
+        self.config
+            .scanning
+            .hash_algorithm
+            .cache_key(&file_info.content_hash)
+    }
+
+    /// `estimate_tokens` of the whole file, reading it only when the cache has no entry
+    fn file_tokens(&self, file_info: &FileInfo) -> u64 {
+        if let Some(counts) = self.cached_tokens(file_info) {
+            return counts.tokens;
+        }
+        let content = std::fs::read_to_string(&file_info.path).unwrap_or_default();
+        let counts = TokenCounts::of(&content, syntax_for(&file_info.path));
+        self.cache_tokens(file_info, counts);
+        counts.tokens
+    }
+
+    fn record_pool_stats(&self, pool: &PluginPool, stats: &mut ScanStats) {
+        let by_path = pool.stats();
+        for (name, plugin_stats) in stats.plugins.iter_mut() {
+            plugin_stats.pool = self
+                .config
+                .get_input_plugin(name)
+                .and_then(|plugin_config| {
+                    plugin_script_path(PluginType::Input, &plugin_config.source)
+                })
+                .and_then(|path| by_path.get(&path).cloned());
+        }
+    }
+
+    /// Where an input plugin's script lives, or None for sources not handled yet
+    fn run_dir(&self) -> PathBuf {
+        run_dir(
+            &self.config.resolve_cache_dir(&self.project_root),
+            self.run_id,
+        )
+    }
+
+    fn input_communicator(
+        &self,
+        plugin_path: &Path,
+        plugin_config: &InputPluginConfig,
+    ) -> crate::plugins::communication::InputPluginCommunicator {
+        let mut communicator =
+            crate::plugins::communication::InputPluginCommunicator::new(plugin_path.to_path_buf())
+                .with_cache_dir(self.config.resolve_cache_dir(&self.project_root))
+                .with_run_dir(self.run_dir())
+                .with_logs(self.config.plugin_logs.clone())
+                .with_offline(self.config.offline)
+                .with_runtime(plugin_config.runtime);
+        if let Some(ref node_exe) = self.config.node_executable {
+            communicator = communicator.with_node_executable(node_exe.clone());
+        }
+        if let Some(ref wasm_exe) = self.config.wasm_executable {
+            communicator = communicator.with_wasm_executable(wasm_exe.clone());
+        }
+
+        // Use configured Python executable or auto-detect
+        match self.config.python_executable {
+            Some(ref python_exe) => communicator.with_python_executable(python_exe.clone()),
+            None => communicator.with_python_auto_detect(),
+        }
+    }
+
+    async fn plugin_capabilities(
+        &self,
+        plugin_path: &Path,
+        communicator: &crate::plugins::communication::InputPluginCommunicator,
+    ) -> Vec<String> {
+        if let Some(capabilities) = self.plugin_capabilities.lock().unwrap().get(plugin_path) {
+            return capabilities.clone();
+        }
+
+        let capabilities = match communicator.get_info().await {
+            Ok(info) => info.capabilities,
             Err(e) => {
-                warn!(
-                    "❌ Plugin analysis failed for {}: {}",
-                    file_info.path.display(),
-                    e
+                debug!(
+                    "Could not query capabilities of {}: {e}",
+                    plugin_path.display()
                 );
-                self.create_basic_file_node(file_info).await
+                Vec::new()
             }
-        }
+        };
+        self.plugin_capabilities
+            .lock()
+            .unwrap()
+            .insert(plugin_path.to_path_buf(), capabilities.clone());
+        capabilities
+    }
+
+    fn project_context(&self, root: &Path) -> Arc<ProjectContext> {
+        self.project_contexts
+            .lock()
+            .unwrap()
+            .entry(root.to_path_buf())
+            .or_insert_with(|| Arc::new(ProjectContext::detect(root)))
+            .clone()
     }
 
     async fn convert_plugin_output_to_file_node(
@@ -199,7 +1048,7 @@ impl ProjectScanner {
         use crate::core::matrix::{ExternalDependency, Relationship};
 
         // Convert plugin CodeElements to matrix CodeElements
-        let elements: Vec<crate::core::matrix::CodeElement> = plugin_output
+        let mut elements: Vec<crate::core::matrix::CodeElement> = plugin_output
             .elements
             .into_iter()
             .map(|e| {
@@ -226,6 +1075,8 @@ impl ProjectScanner {
                         _ => crate::core::matrix::ElementType::Function, // Default fallback
                     },
                     name: e.name,
+                    qualified_name: e.qualified_name,
+                    parent: e.parent,
                     signature: e.signature,
                     line_start: e.line_start,
                     line_end: e.line_end,
@@ -234,16 +1085,31 @@ impl ProjectScanner {
                     calls: e.calls,
                     metadata: e.metadata,
                     tokens: e.tokens.unwrap_or(0),
+                    element_id: String::new(),
+                    coverage: None,
                 }
             })
             .collect();
 
+        // Keep quality metrics from going blank for plugins that don't score complexity
+        if elements.iter().any(needs_estimate) {
+            match std::fs::read_to_string(&file_info.path) {
+                Ok(content) => {
+                    fill_missing_complexity(&mut elements, &content);
+                }
+                Err(e) => debug!(
+                    "Could not read {} to estimate complexity: {e}",
+                    file_info.path.display()
+                ),
+            }
+        }
+
         // Convert plugin Imports to matrix Imports
         let imports: Vec<crate::core::matrix::Import> = plugin_output
             .imports
             .into_iter()
             .map(|i| crate::core::matrix::Import {
-                module: i.module,
+                module: i.module.into(),
                 items: i.items,
                 alias: i.alias,
                 line_number: i.line_number,
@@ -258,25 +1124,10 @@ impl ProjectScanner {
             .collect();
 
         // Add relationships to the matrix
+        let detected_at = chrono::Utc::now();
         for rel in plugin_output.relationships {
-            let relationship = Relationship {
-                from_file: PathBuf::from(rel.from_file),
-                to_file: PathBuf::from(rel.to_file),
-                relationship_type: match rel.relationship_type.as_str() {
-                    "import" => crate::core::matrix::RelationshipType::Import,
-                    "call" => crate::core::matrix::RelationshipType::Call,
-                    "inheritance" => crate::core::matrix::RelationshipType::Inheritance,
-                    "configuration" => crate::core::matrix::RelationshipType::Configuration,
-                    "test" => crate::core::matrix::RelationshipType::Test,
-                    "documentation" => crate::core::matrix::RelationshipType::Documentation,
-                    "build" => crate::core::matrix::RelationshipType::Build,
-                    _ => crate::core::matrix::RelationshipType::Import,
-                },
-                details: rel.details,
-                line_number: rel.line_number,
-                strength: rel.strength,
-            };
-            matrix.add_relationship(relationship);
+            let source_plugin = file_info.plugin_name.as_ref().map(IStr::from);
+            matrix.add_relationship(Relationship::from_plugin(rel, source_plugin, detected_at));
         }
 
         // Add external dependencies to the matrix
@@ -284,7 +1135,7 @@ impl ProjectScanner {
             let dependency = ExternalDependency {
                 name: dep.name,
                 version: dep.version,
-                ecosystem: dep.ecosystem,
+                ecosystem: dep.ecosystem.into(),
                 dependency_type: match dep.dependency_type.as_str() {
                     "runtime" => crate::core::matrix::DependencyType::Runtime,
                     "development" => crate::core::matrix::DependencyType::Development,
@@ -293,6 +1144,8 @@ impl ProjectScanner {
                     _ => crate::core::matrix::DependencyType::Runtime,
                 },
                 source_file: PathBuf::from(dep.source_file),
+                source_plugin: file_info.plugin_name.as_ref().map(IStr::from),
+                detected_at: Some(detected_at),
             };
             matrix.add_external_dependency(dependency);
         }
@@ -314,8 +1167,7 @@ impl ProjectScanner {
                 }
             } else {
                 // Fallback: estimate tokens from file size
-                let estimated_tokens =
-                    estimate_tokens(&std::fs::read_to_string(&file_info.path).unwrap_or_default());
+                let estimated_tokens = self.file_tokens(file_info);
                 TokenInfo {
                     total_tokens: estimated_tokens,
                     code_tokens: estimated_tokens,
@@ -325,8 +1177,7 @@ impl ProjectScanner {
             }
         } else {
             // Fallback: estimate tokens from file size
-            let estimated_tokens =
-                estimate_tokens(&std::fs::read_to_string(&file_info.path).unwrap_or_default());
+            let estimated_tokens = self.file_tokens(file_info);
             TokenInfo {
                 total_tokens: estimated_tokens,
                 code_tokens: estimated_tokens,
@@ -344,40 +1195,71 @@ impl ProjectScanner {
             plugin: file_info
                 .plugin_name
                 .clone()
-                .unwrap_or_else(|| "unknown".to_string()),
-            language: self.config.find_input_plugin_for_file(&file_info.path),
+                .unwrap_or_else(|| "unknown".to_string())
+                .into(),
+            language: file_info.plugin_name.as_ref().map(IStr::from),
             is_text: file_info.is_text,
             elements,
             imports,
             exports: plugin_output.exports,
             file_summary: plugin_output.file_summary,
             token_info,
+            metadata: file_metadata(file_info),
+            truncated: false,
+            category: None,
+            coverage: None,
         })
     }
 
+    async fn create_basic_file_nodes<'a>(
+        &self,
+        files: impl IntoIterator<Item = &'a FileInfo>,
+    ) -> Result<Vec<crate::core::matrix::FileNode>> {
+        let mut file_nodes = Vec::new();
+        for file_info in files {
+            file_nodes.push(self.create_basic_file_node(file_info).await?);
+        }
+        Ok(file_nodes)
+    }
+
     async fn create_basic_file_node(
         &self,
         file_info: &FileInfo,
     ) -> Result<crate::core::matrix::FileNode> {
         // For non-analyzed files, estimate tokens from file content if it's text
-        let token_info = if file_info.is_text {
-            match tokio::fs::read_to_string(&file_info.path).await {
-                Ok(content) => {
-                    let total_tokens = estimate_code_tokens(&content);
-                    TokenInfo {
-                        total_tokens,
-                        code_tokens: total_tokens,
-                        documentation_tokens: 0,
-                        comment_tokens: 0,
-                    }
+        let mut truncated = false;
+        let cached = file_info
+            .is_text
+            .then(|| self.cached_tokens(file_info))
+            .flatten()
+            .filter(|counts| self.fits(counts.tokens));
+        // Structure scans read every text file for its imports and exports
+        let structural = file_info.is_text && self.config.scanning.level == ScanLevel::Structure;
+        let content = if structural || (file_info.is_text && cached.is_none()) {
+            tokio::fs::read_to_string(&file_info.path).await.ok()
+        } else {
+            None
+        };
+        let (imports, exports) = match &content {
+            Some(content) if structural => extract_structure(&file_info.path, content),
+            _ => (Vec::new(), Vec::new()),
+        };
+        let token_info = if let Some(counts) = cached {
+            basic_token_info(counts)
+        } else if let Some(content) = &content {
+            let syntax = syntax_for(&file_info.path);
+            let counts = match self.truncate(content) {
+                Some(shortened) => {
+                    truncated = true;
+                    TokenCounts::of(&shortened, syntax)
                 }
-                Err(_) => TokenInfo {
-                    total_tokens: 0,
-                    code_tokens: 0,
-                    documentation_tokens: 0,
-                    comment_tokens: 0,
-                },
-            }
+                None => {
+                    let counts = TokenCounts::of(content, syntax);
+                    self.cache_tokens(file_info, counts);
+                    counts
+                }
+            };
+            basic_token_info(counts)
         } else {
             TokenInfo {
                 total_tokens: 0,
@@ -395,26 +1277,90 @@ impl ProjectScanner {
             plugin: file_info
                 .plugin_name
                 .clone()
-                .unwrap_or_else(|| "unknown".to_string()),
-            language: self.config.find_input_plugin_for_file(&file_info.path),
+                .unwrap_or_else(|| "unknown".to_string())
+                .into(),
+            language: file_info.plugin_name.as_ref().map(IStr::from),
             is_text: file_info.is_text,
             elements: Vec::new(),
-            imports: Vec::new(),
-            exports: Vec::new(),
+            imports,
+            exports,
             file_summary: None,
             token_info,
+            metadata: file_metadata(file_info),
+            truncated,
+            category: None,
+            coverage: None,
         })
     }
 
     pub async fn scan(&self) -> Result<Vec<FileInfo>> {
+        let mut stats = ScanStats::default();
+        let mut files = self.walk(&mut stats).await?;
+        self.hash_files(&mut files, &mut stats);
+        Ok(files)
+    }
+
+    /// Time the stages of a scan that don't involve plugins: discovery, hashing and
+    /// token estimation. No plugin is started.
+    pub async fn bench(&self) -> Result<BenchResult> {
+        let mut stats = ScanStats::default();
+        let started = Instant::now();
+        let mut files = self.walk(&mut stats).await?;
+        let discovery = started.elapsed();
+
+        let started = Instant::now();
+        self.hash_files(&mut files, &mut stats);
+        let hashing = started.elapsed();
+
+        let started = Instant::now();
+        let tokens = files
+            .iter()
+            .filter(|file| file.is_text)
+            .filter_map(|file| std::fs::read(&file.path).ok())
+            .map(|content| estimate_tokens(&String::from_utf8_lossy(&content)))
+            .sum();
+        let tokenize = started.elapsed();
+
+        let as_ms = |elapsed: std::time::Duration| elapsed.as_secs_f64() * 1000.0;
+        Ok(BenchResult {
+            timestamp: chrono::Utc::now(),
+            csd_version: env!("CARGO_PKG_VERSION").to_string(),
+            target: target_id(&self.project_root),
+            files: files.len(),
+            skipped_files: stats.skipped_files,
+            bytes: files.iter().map(|file| file.size_bytes).sum(),
+            tokens,
+            discovery_ms: as_ms(discovery),
+            hashing_ms: as_ms(hashing),
+            tokenize_ms: as_ms(tokenize),
+        })
+    }
+
+    /// Walk the project tree, counting excluded files in `stats`
+    async fn walk(&self, stats: &mut ScanStats) -> Result<Vec<FileInfo>> {
         debug!("Starting file scan in: {}", self.project_root.display());
+        let _span = profile::span(Stage::Walk);
 
         let mut files = Vec::new();
         let mut _total_files = 0;
         let mut skipped_files = 0;
+        let cache_dir = self.config.resolve_cache_dir(&self.project_root);
+
+        let roots: Vec<(PathBuf, Option<String>)> = if self.roots.is_empty() {
+            vec![(self.project_root.clone(), None)]
+        } else {
+            self.scan_roots()
+                .into_iter()
+                .map(|root| (root.path, Some(root.name)))
+                .collect()
+        };
 
         // Use the `ignore` crate to respect .gitignore, .ignore files
-        let walker = WalkBuilder::new(&self.project_root)
+        let mut builder = WalkBuilder::new(&roots[0].0);
+        for (root, _) in &roots[1..] {
+            builder.add(root);
+        }
+        let walker = builder
             .hidden(!self.config.scanning.include_hidden)
             .git_ignore(true)
             .git_exclude(true)
@@ -425,6 +1371,9 @@ impl ProjectScanner {
                 Ok(entry) => entry,
                 Err(e) => {
                     warn!("Error reading directory entry: {e}");
+                    if let Some(path) = permission_denied_path(&e) {
+                        stats.skipped_permission.push(self.report_path(path));
+                    }
                     continue;
                 }
             };
@@ -438,6 +1387,12 @@ impl ProjectScanner {
 
             let path = entry.path();
 
+            // Never scan our own cache, wherever it has been relocated to
+            if path.starts_with(&cache_dir) {
+                skipped_files += 1;
+                continue;
+            }
+
             // Check if file matches our ignore patterns
             if self.should_ignore_file(path) {
                 debug!("Ignoring file: {}", path.display());
@@ -450,6 +1405,9 @@ impl ProjectScanner {
                 Ok(metadata) => metadata,
                 Err(e) => {
                     warn!("Could not read metadata for {}: {}", path.display(), e);
+                    if e.kind() == std::io::ErrorKind::PermissionDenied {
+                        stats.skipped_permission.push(self.report_path(path));
+                    }
                     skipped_files += 1;
                     continue;
                 }
@@ -468,10 +1426,14 @@ impl ProjectScanner {
                 continue;
             }
 
-            // Create relative path
-            let relative_path = match path.strip_prefix(&self.project_root) {
-                Ok(rel) => rel.to_path_buf(),
-                Err(_) => path.to_path_buf(),
+            // Create relative path, namespaced by root name when scanning several roots
+            let relative_path = match roots
+                .iter()
+                .find_map(|(root, name)| Some((path.strip_prefix(root).ok()?, name)))
+            {
+                Some((rel, Some(name))) => Path::new(name).join(rel),
+                Some((rel, None)) => rel.to_path_buf(),
+                None => path.to_path_buf(),
             };
 
             // Detect file info
@@ -483,11 +1445,6 @@ impl ProjectScanner {
             let is_text = self.is_text_file(path, &extension);
             let plugin_name = self.config.find_input_plugin_for_file(path);
 
-            // Calculate content hash
-            let content_hash = self
-                .calculate_file_hash(path)
-                .unwrap_or_else(|_| "error".to_string());
-
             let file_info = FileInfo {
                 path: path.to_path_buf(),
                 relative_path,
@@ -495,7 +1452,9 @@ impl ProjectScanner {
                 size_bytes,
                 is_text,
                 plugin_name,
-                content_hash,
+                // Filled in by `hash_files`
+                content_hash: String::new(),
+                plugin_confidence: None,
             };
 
             debug!("Found file: {file_info:?}");
@@ -507,42 +1466,164 @@ impl ProjectScanner {
             files.len(),
             skipped_files
         );
+        stats.skipped_files += skipped_files;
 
         Ok(files)
     }
 
-    fn calculate_file_hash(&self, path: &Path) -> Result<String> {
-        let content = std::fs::read(path)?;
-        let mut hasher = Sha256::new();
-        hasher.update(&content);
-        let hash = hasher.finalize();
-        Ok(format!("{hash:x}"))
+    /// Fill in content hashes. Files that may not be read are dropped and recorded in
+    /// `stats`; other read errors leave the "error" hash.
+    fn hash_files(&self, files: &mut Vec<FileInfo>, stats: &mut ScanStats) {
+        let _span = profile::span(Stage::Hash);
+        files.retain_mut(|file| match self.calculate_file_hash(&file.path) {
+            Ok(hash) => {
+                file.content_hash = hash;
+                true
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                warn!("Permission denied reading {}", file.path.display());
+                stats.skipped_permission.push(self.report_path(&file.path));
+                stats.skipped_files += 1;
+                false
+            }
+            Err(_) => {
+                file.content_hash = "error".to_string();
+                true
+            }
+        });
+    }
+
+    /// `path` relative to the project root for reports, or as is when outside it
+    fn report_path(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.project_root)
+            .unwrap_or(path)
+            .to_path_buf()
+    }
+
+    fn calculate_file_hash(&self, path: &Path) -> std::io::Result<String> {
+        self.config.scanning.hash_algorithm.hash_file(path)
     }
 
     fn should_ignore_file(&self, path: &Path) -> bool {
+        self.matching_ignore_pattern(path).is_some()
+    }
+
+    /// The first `scanning.ignore_patterns` entry that excludes `path`
+    fn matching_ignore_pattern(&self, path: &Path) -> Option<&str> {
         let path_str = path.to_string_lossy();
 
-        for pattern in &self.config.scanning.ignore_patterns {
-            // Simple glob-like matching
-            if pattern.ends_with('/') {
-                // Directory pattern
-                let dir_pattern = &pattern[..pattern.len() - 1];
-                if path_str.contains(dir_pattern) {
-                    return true;
-                }
-            } else if pattern.starts_with("*.") {
-                // Extension pattern
-                let ext = &pattern[1..]; // Remove the *
-                if path_str.ends_with(ext) {
-                    return true;
+        self.config
+            .scanning
+            .ignore_patterns
+            .iter()
+            .find(|pattern| {
+                // Simple glob-like matching
+                if let Some(dir_pattern) = pattern.strip_suffix('/') {
+                    // Directory pattern
+                    path_str.contains(dir_pattern)
+                } else if pattern.starts_with("*.") {
+                    // Extension pattern
+                    path_str.ends_with(&pattern[1..]) // Remove the *
+                } else {
+                    // Simple substring match
+                    path_str.contains(pattern.as_str())
                 }
-            } else if path_str.contains(pattern) {
-                // Simple substring match
-                return true;
+            })
+            .map(String::as_str)
+    }
+
+    /// Report whether `path` would be scanned, the first rule that excludes it
+    /// otherwise, and which input plugins would claim it. Checks run in the same order
+    /// as `scan`.
+    pub fn explain(&self, path: &Path) -> FileExplanation {
+        let candidates = self.config.find_input_plugins_for_file(path);
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| format!(".{}", ext.to_lowercase()));
+        let mut explanation = FileExplanation {
+            path: path.to_path_buf(),
+            relative_path: None,
+            exclusion: None,
+            plugin: candidates.first().cloned(),
+            candidates,
+            is_text: self.is_text_file(path, &extension),
+        };
+        explanation.exclusion = self.find_exclusion(path, &mut explanation.relative_path);
+        explanation
+    }
+
+    fn find_exclusion(
+        &self,
+        path: &Path,
+        relative_path: &mut Option<PathBuf>,
+    ) -> Option<Exclusion> {
+        let absolute = |path: &Path| {
+            normalize_lexically(&std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()))
+        };
+        let target = absolute(path);
+
+        let roots = if self.roots.is_empty() {
+            vec![ScanRoot {
+                name: String::new(),
+                path: self.project_root.clone(),
+            }]
+        } else {
+            self.scan_roots()
+        };
+        let Some((root, rel)) = roots.iter().find_map(|root| {
+            let rel = target.strip_prefix(absolute(&root.path)).ok()?;
+            Some((root, rel.to_path_buf()))
+        }) else {
+            return Some(Exclusion::OutsideRoots);
+        };
+        *relative_path = Some(Path::new(&root.name).join(&rel));
+
+        let metadata = match std::fs::metadata(&target) {
+            Ok(metadata) => metadata,
+            Err(_) => return Some(Exclusion::Missing),
+        };
+        if metadata.is_dir() {
+            return Some(Exclusion::Directory);
+        }
+
+        if !self.config.scanning.include_hidden {
+            let hidden = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .find(|name| name.starts_with('.'));
+            if let Some(component) = hidden {
+                return Some(Exclusion::Hidden {
+                    component: component.to_string(),
+                });
             }
         }
 
-        false
+        if let Some(exclusion) = ignore_file_rule(&target) {
+            return Some(exclusion);
+        }
+
+        // The walker reports paths under the root as given, so match the same form
+        let walked = root.path.join(&rel);
+        let cache_dir = absolute(&self.config.resolve_cache_dir(&self.project_root));
+        if target.starts_with(&cache_dir) {
+            return Some(Exclusion::CacheDir);
+        }
+        if let Some(pattern) = self.matching_ignore_pattern(&walked) {
+            return Some(Exclusion::IgnorePattern {
+                pattern: pattern.to_string(),
+            });
+        }
+
+        let limit_bytes = self.config.scanning.max_file_size_mb * 1024 * 1024;
+        if metadata.len() > limit_bytes {
+            return Some(Exclusion::TooLarge {
+                size_bytes: metadata.len(),
+                limit_bytes,
+            });
+        }
+
+        None
     }
 
     fn is_text_file(&self, path: &Path, extension: &Option<String>) -> bool {
@@ -661,3 +1742,49 @@ impl ProjectScanner {
         );
     }
 }
+
+/// The gitignore-style rule that hides `path` (absolute) from the walker, if any.
+/// Mirrors the `ignore` crate's precedence: deeper directories win over shallower ones,
+/// `.ignore` over `.gitignore`, and both over `.git/info/exclude` and the global file.
+/// Git rules only apply inside a repository.
+fn ignore_file_rule(path: &Path) -> Option<Exclusion> {
+    use ignore::gitignore::{Gitignore, GitignoreBuilder};
+    use ignore::Match;
+
+    // Rules in `file` apply to paths under `root`
+    let rules = |root: &Path, file: PathBuf| {
+        let mut builder = GitignoreBuilder::new(root);
+        builder.add(file);
+        builder.build().unwrap_or_else(|_| Gitignore::empty())
+    };
+
+    let dirs: Vec<&Path> = path.ancestors().skip(1).collect();
+    let repo = dirs.iter().find(|dir| dir.join(".git").exists());
+
+    let mut matchers = Vec::new();
+    if let Some(repo) = repo {
+        matchers.push(GitignoreBuilder::new(repo).build_global().0);
+        matchers.push(rules(repo, repo.join(".git/info/exclude")));
+    }
+    for dir in dirs.iter().rev() {
+        if repo.is_some() {
+            matchers.push(rules(dir, dir.join(".gitignore")));
+        }
+        matchers.push(rules(dir, dir.join(".ignore")));
+    }
+
+    let mut rule = None;
+    for matcher in &matchers {
+        match matcher.matched_path_or_any_parents(path, false) {
+            Match::Ignore(glob) => {
+                rule = Some(Exclusion::IgnoreFile {
+                    pattern: glob.original().to_string(),
+                    source: glob.from().map(Path::to_path_buf),
+                })
+            }
+            Match::Whitelist(_) => rule = None,
+            Match::None => {}
+        }
+    }
+    rule
+}
@@ -0,0 +1,523 @@
+// src/core/structure.rs - Imports and exports read line by line instead of by plugins, for
+// structure-level scans (`csd scan --level structure`)
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::core::matrix::{
+    Export, Import, ImportType, ProjectMatrix, Relationship, RelationshipType,
+};
+use crate::utils::file_utils::normalize_lexically;
+
+/// Recorded as the `source_plugin` of relationships linked here
+pub const STRUCTURE_SOURCE: &str = "structure";
+
+// Tried in order when a JavaScript or TypeScript specifier names no extension
+const JS_RESOLUTIONS: &[&str] = &[
+    "",
+    ".ts",
+    ".tsx",
+    ".js",
+    ".jsx",
+    ".mjs",
+    ".cjs",
+    "/index.ts",
+    "/index.tsx",
+    "/index.js",
+];
+
+/// Imports and exports of a Python, JavaScript/TypeScript, Rust or Go file; other files
+/// have none. Only statements starting a line are read and nothing is resolved, so
+/// imports are `Relative`, `Standard` or `ThirdParty` until `link_imports` finds them.
+pub fn extract_structure(path: &Path, content: &str) -> (Vec<Import>, Vec<Export>) {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "py" | "pyi" => python(content),
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => javascript(content),
+        "rs" => rust(content),
+        "go" => go(content),
+        _ => (Vec::new(), Vec::new()),
+    }
+}
+
+/// Add an import relationship for each import that names a file in the matrix, and
+/// mark those imports local
+pub fn link_imports(matrix: &mut ProjectMatrix) {
+    let known: HashSet<PathBuf> = matrix
+        .files
+        .values()
+        .map(|f| f.relative_path.clone())
+        .collect();
+    let mut relationships = Vec::new();
+    for file in matrix.files.values_mut() {
+        let from = file.relative_path.clone();
+        for import in &mut file.imports {
+            let Some(target) = candidates(&from, import)
+                .into_iter()
+                .map(|candidate| normalize_lexically(&candidate))
+                .find(|candidate| *candidate != from && known.contains(candidate))
+            else {
+                continue;
+            };
+            if import.import_type != ImportType::Relative {
+                import.import_type = ImportType::Local;
+            }
+            relationships.push(Relationship {
+                from_file: from.clone(),
+                to_file: target,
+                relationship_type: RelationshipType::Import,
+                details: format!("import {}", import.module),
+                line_number: Some(import.line_number),
+                strength: 0.8,
+                source_plugin: Some(STRUCTURE_SOURCE.into()),
+                detected_at: Some(chrono::Utc::now()),
+                from_element: None,
+                to_element: None,
+                runtime_calls: None,
+            });
+        }
+    }
+    relationships.sort_by(|a, b| (&a.from_file, a.line_number).cmp(&(&b.from_file, b.line_number)));
+    for relationship in relationships {
+        matrix.add_relationship(relationship);
+    }
+}
+
+// Project-relative paths `import` may refer to, most likely first
+fn candidates(from: &Path, import: &Import) -> Vec<PathBuf> {
+    let dir = from.parent().unwrap_or(Path::new(""));
+    let module: &str = &import.module;
+    let extension = from
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    match extension {
+        "py" | "pyi" => {
+            let dots = module.chars().take_while(|&c| c == '.').count();
+            let path = module[dots..].replace('.', "/");
+            let bases: Vec<PathBuf> = if dots > 0 {
+                // One dot is the file's own package, each further dot a parent
+                let mut base = dir.to_path_buf();
+                for _ in 1..dots {
+                    base.push("..");
+                }
+                vec![base]
+            } else {
+                vec![PathBuf::new(), PathBuf::from("src"), dir.to_path_buf()]
+            };
+            bases
+                .iter()
+                .flat_map(|base| {
+                    let module_path = base.join(&path);
+                    [
+                        module_path.with_extension("py"),
+                        module_path.join("__init__.py"),
+                    ]
+                })
+                .collect()
+        }
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" if module.starts_with('.') => JS_RESOLUTIONS
+            .iter()
+            .map(|suffix| PathBuf::from(format!("{}{suffix}", dir.join(module).display())))
+            .collect(),
+        // `mod name;` declarations; `use` paths would need the crate layout
+        "rs" if import.import_type == ImportType::Local && !module.contains("::") => {
+            let stem = from
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+            let base = if matches!(stem, "mod" | "lib" | "main") {
+                dir.to_path_buf()
+            } else {
+                dir.join(stem)
+            };
+            vec![
+                base.join(format!("{module}.rs")),
+                base.join(module).join("mod.rs"),
+            ]
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn import(
+    module: &str,
+    items: Vec<String>,
+    alias: Option<String>,
+    line: usize,
+    kind: ImportType,
+) -> Import {
+    Import {
+        module: module.into(),
+        items,
+        alias,
+        line_number: line as u32 + 1,
+        import_type: kind,
+    }
+}
+
+fn export(name: &str, kind: &str) -> Export {
+    Export {
+        kind: Some(kind.to_string()),
+        ..Export::named(name)
+    }
+}
+
+// The identifier `text` starts with
+fn identifier(text: &str) -> Option<&str> {
+    let end = text
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(text.len());
+    (end > 0).then(|| &text[..end])
+}
+
+// `a as b` → ("a", Some("b"))
+fn split_alias(name: &str) -> (&str, Option<String>) {
+    match name.split_once(" as ") {
+        Some((name, alias)) => (name.trim(), Some(alias.trim().to_string())),
+        None => (name.trim(), None),
+    }
+}
+
+// A statement continued over the following lines until `done` holds, as one line
+fn joined(lines: &[&str], start: usize, done: impl Fn(&str) -> bool) -> (String, usize) {
+    let mut statement = lines[start].to_string();
+    let mut end = start;
+    while !done(&statement) && end + 1 < lines.len() && end - start < 50 {
+        end += 1;
+        statement.push(' ');
+        statement.push_str(lines[end].trim());
+    }
+    (statement, end)
+}
+
+fn python(content: &str) -> (Vec<Import>, Vec<Export>) {
+    let lines: Vec<&str> = content.lines().collect();
+    let (mut imports, mut exports) = (Vec::new(), Vec::new());
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim_start();
+        let top_level = trimmed.len() == line.len();
+        if let Some(rest) = trimmed.strip_prefix("import ") {
+            for module in rest.split('#').next().unwrap_or_default().split(',') {
+                let (module, alias) = split_alias(module);
+                if !module.is_empty() {
+                    imports.push(import(
+                        module,
+                        Vec::new(),
+                        alias,
+                        index,
+                        python_kind(module),
+                    ));
+                }
+            }
+        } else if trimmed.starts_with("from ") {
+            let (statement, end) = joined(&lines, index, |s| !s.contains('(') || s.contains(')'));
+            let statement = statement.trim_start();
+            if let Some((module, names)) = statement["from ".len()..].split_once(" import ") {
+                let names = names.split('#').next().unwrap_or_default();
+                let items = names
+                    .split(',')
+                    .map(|name| split_alias(name.trim_matches(|c: char| "()\\ ".contains(c))).0)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                let module = module.trim();
+                imports.push(import(module, items, None, index, python_kind(module)));
+            }
+            index = end;
+        } else if top_level {
+            let definition = trimmed
+                .strip_prefix("async def ")
+                .or_else(|| trimmed.strip_prefix("def "))
+                .map(|rest| (rest, "function"))
+                .or_else(|| trimmed.strip_prefix("class ").map(|rest| (rest, "class")));
+            match definition {
+                Some((rest, kind)) => {
+                    if let Some(name) = identifier(rest).filter(|n| !n.starts_with('_')) {
+                        exports.push(export(name, kind));
+                    }
+                }
+                None => {
+                    if let Some(name) = identifier(trimmed).filter(|n| !n.starts_with('_')) {
+                        let after = trimmed[name.len()..].trim_start();
+                        let assigned = after.starts_with('=') && !after.starts_with("==");
+                        if assigned || after.starts_with(':') {
+                            exports.push(export(name, "variable"));
+                        }
+                    }
+                }
+            }
+        }
+        index += 1;
+    }
+    (imports, exports)
+}
+
+fn python_kind(module: &str) -> ImportType {
+    if module.starts_with('.') {
+        ImportType::Relative
+    } else {
+        ImportType::ThirdParty
+    }
+}
+
+// The quoted string in `text`, e.g. the specifier of `from "./a"`
+fn quoted(text: &str) -> Option<&str> {
+    let start = text.find(['"', '\'', '`'])?;
+    let quote = text[start..].chars().next()?;
+    let rest = &text[start + 1..];
+    Some(&rest[..rest.find(quote)?])
+}
+
+fn javascript(content: &str) -> (Vec<Import>, Vec<Export>) {
+    let lines: Vec<&str> = content.lines().collect();
+    let (mut imports, mut exports) = (Vec::new(), Vec::new());
+    let mut index = 0;
+    while index < lines.len() {
+        let trimmed = lines[index].trim_start();
+        let is_import = trimmed.starts_with("import ") || trimmed.starts_with("import{");
+        // `export { a } from "./b"`, `export type { C }` and `export * from "./d"`, not
+        // declarations, whose bodies may have braces too
+        let is_export = trimmed
+            .strip_prefix("export ")
+            .map(|rest| rest.trim_start_matches("type ").trim_start())
+            .is_some_and(|rest| rest.starts_with('{') || rest.starts_with('*'));
+        if is_import || is_export {
+            // Brace lists may run over several lines until the closing brace
+            let (statement, end) = joined(&lines, index, |s| !s.contains('{') || s.contains('}'));
+            let names = braced_names(&statement);
+            let from = statement
+                .rsplit_once(" from ")
+                .and_then(|(_, specifier)| quoted(specifier))
+                .or_else(|| is_import.then(|| quoted(&statement)).flatten());
+            match from {
+                Some(specifier) => {
+                    let mut items: Vec<String> =
+                        names.iter().map(|(name, _)| name.clone()).collect();
+                    // `import React, { useState } from "react"`
+                    if let Some(default) = statement
+                        .strip_prefix("import ")
+                        .and_then(|rest| identifier(rest.trim_start()))
+                        .filter(|name| *name != "type")
+                    {
+                        items.insert(0, default.to_string());
+                    }
+                    imports.push(import(specifier, items, None, index, js_kind(specifier)));
+                    if is_export {
+                        exports.extend(names.into_iter().map(|(name, alias)| Export {
+                            kind: Some("import".to_string()),
+                            alias,
+                            ..Export::named(&name)
+                        }));
+                    }
+                }
+                None if is_export => {
+                    exports.extend(names.into_iter().map(|(name, alias)| Export {
+                        alias,
+                        ..Export::named(&name)
+                    }));
+                }
+                None => {}
+            }
+            index = end;
+        } else if let Some(rest) = trimmed.strip_prefix("export ") {
+            if let Some(export) = js_declaration(rest) {
+                exports.push(export);
+            }
+        } else if let Some(position) = trimmed.find("require(") {
+            if let Some(specifier) = quoted(&trimmed[position..]) {
+                imports.push(import(
+                    specifier,
+                    Vec::new(),
+                    None,
+                    index,
+                    js_kind(specifier),
+                ));
+            }
+        }
+        index += 1;
+    }
+    (imports, exports)
+}
+
+// `{ a, b as c, type D }` → [("a", None), ("b", Some("c")), ("D", None)]
+fn braced_names(statement: &str) -> Vec<(String, Option<String>)> {
+    let Some((_, rest)) = statement.split_once('{') else {
+        return Vec::new();
+    };
+    let inner = rest.split('}').next().unwrap_or_default();
+    inner
+        .split(',')
+        .map(|name| name.trim().trim_start_matches("type ").trim())
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let (name, alias) = split_alias(name);
+            (name.to_string(), alias)
+        })
+        .collect()
+}
+
+fn js_declaration(rest: &str) -> Option<Export> {
+    let rest = rest.trim_start();
+    if rest.starts_with("default") {
+        return Some(export("default", "default"));
+    }
+    let rest = rest.strip_prefix("declare ").unwrap_or(rest);
+    let rest = rest.strip_prefix("async ").unwrap_or(rest);
+    let rest = rest.strip_prefix("abstract ").unwrap_or(rest);
+    [
+        ("function* ", "function"),
+        ("function ", "function"),
+        ("class ", "class"),
+        ("const ", "variable"),
+        ("let ", "variable"),
+        ("var ", "variable"),
+        ("interface ", "interface"),
+        ("type ", "type"),
+        ("enum ", "enum"),
+    ]
+    .into_iter()
+    .find_map(|(keyword, kind)| {
+        let name = identifier(rest.strip_prefix(keyword)?.trim_start())?;
+        Some(export(name, kind))
+    })
+}
+
+fn js_kind(specifier: &str) -> ImportType {
+    if specifier.starts_with('.') {
+        ImportType::Relative
+    } else if specifier.starts_with("node:") {
+        ImportType::Standard
+    } else {
+        ImportType::ThirdParty
+    }
+}
+
+fn rust(content: &str) -> (Vec<Import>, Vec<Export>) {
+    let lines: Vec<&str> = content.lines().collect();
+    let (mut imports, mut exports) = (Vec::new(), Vec::new());
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim_start();
+        let top_level = trimmed.len() == line.len();
+        let public = trimmed.starts_with("pub ");
+        let item = trimmed.strip_prefix("pub ").unwrap_or(trimmed);
+        if let Some(rest) = item.strip_prefix("use ") {
+            let (statement, end) = joined(&lines, index, |s| s.contains(';'));
+            let path = statement[statement.find("use ").unwrap_or(0) + 4..]
+                .split(';')
+                .next()
+                .unwrap_or(rest)
+                .trim();
+            let (module, items) = match path.split_once("::{") {
+                Some((module, list)) => (
+                    module,
+                    list.trim_end_matches('}')
+                        .split(',')
+                        .map(|item| split_alias(item).0.to_string())
+                        .filter(|item| !item.is_empty())
+                        .collect(),
+                ),
+                None => (split_alias(path).0, Vec::new()),
+            };
+            imports.push(import(module, items, None, index, rust_kind(module)));
+            if public && top_level {
+                let (name, alias) = split_alias(path.rsplit("::").next().unwrap_or(path));
+                if !path.contains('{') && name != "*" {
+                    exports.push(Export {
+                        kind: Some("import".to_string()),
+                        alias,
+                        ..Export::named(name)
+                    });
+                }
+            }
+            index = end;
+        } else if let Some(rest) = item.strip_prefix("mod ") {
+            // Only declarations of modules in other files, not inline `mod tests { ... }`
+            if let Some(name) = identifier(rest).filter(|_| rest.trim_end().ends_with(';')) {
+                imports.push(import(name, Vec::new(), None, index, ImportType::Local));
+                if public && top_level {
+                    exports.push(export(name, "module"));
+                }
+            }
+        } else if public && top_level {
+            let item = item.strip_prefix("async ").unwrap_or(item);
+            let item = item.strip_prefix("unsafe ").unwrap_or(item);
+            let declaration = [
+                ("fn ", "function"),
+                ("struct ", "struct"),
+                ("enum ", "enum"),
+                ("trait ", "trait"),
+                ("type ", "type"),
+                ("const ", "constant"),
+                ("static ", "constant"),
+            ]
+            .into_iter()
+            .find_map(|(keyword, kind)| Some((identifier(item.strip_prefix(keyword)?)?, kind)));
+            if let Some((name, kind)) = declaration {
+                exports.push(export(name, kind));
+            }
+        }
+        index += 1;
+    }
+    (imports, exports)
+}
+
+fn rust_kind(module: &str) -> ImportType {
+    match module.split("::").next().unwrap_or_default() {
+        "std" | "core" | "alloc" => ImportType::Standard,
+        "crate" => ImportType::Local,
+        "self" | "super" => ImportType::Relative,
+        _ => ImportType::ThirdParty,
+    }
+}
+
+fn go(content: &str) -> (Vec<Import>, Vec<Export>) {
+    let (mut imports, mut exports) = (Vec::new(), Vec::new());
+    let mut in_block = false;
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed == "import (" {
+            in_block = true;
+        } else if in_block && trimmed == ")" {
+            in_block = false;
+        } else if in_block || trimmed.starts_with("import ") {
+            let spec = trimmed.strip_prefix("import ").unwrap_or(trimmed);
+            if let Some(path) = quoted(spec) {
+                let alias = identifier(spec).map(str::to_string);
+                imports.push(import(path, Vec::new(), alias, index, go_kind(path)));
+            }
+        } else if trimmed.len() == line.len() {
+            let declaration = [
+                ("func ", "function"),
+                ("type ", "type"),
+                ("var ", "variable"),
+                ("const ", "constant"),
+            ]
+            .into_iter()
+            .find_map(|(keyword, kind)| Some((line.strip_prefix(keyword)?, kind)));
+            // Methods (`func (r *T) Name`) belong to their type
+            if let Some((rest, kind)) = declaration.filter(|(rest, _)| !rest.starts_with('(')) {
+                if let Some(name) = identifier(rest).filter(|n| n.starts_with(char::is_uppercase)) {
+                    exports.push(export(name, kind));
+                }
+            }
+        }
+    }
+    (imports, exports)
+}
+
+// Standard library paths have no domain in their first element
+fn go_kind(path: &str) -> ImportType {
+    if path.split('/').next().unwrap_or_default().contains('.') {
+        ImportType::ThirdParty
+    } else {
+        ImportType::Standard
+    }
+}
@@ -0,0 +1,508 @@
+// src/core/suggestions.rs - Fix-it suggestions on quality findings: what to do, optionally
+// as an action a tool could apply, and the matrix facts it rests on. Rule plugins attach
+// their own; `csd quality --suggest` has the LLM draft them for the top findings.
+use anyhow::Result;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::core::annotations::path_key;
+use crate::core::hotspots::HotspotReport;
+use crate::core::matrix::{CodeElement, FileNode, ProjectMatrix};
+use crate::core::rules::RuleReport;
+use crate::llm::client::LlmClient;
+use crate::llm::prompts::{suggestion_prompt, SUGGESTION_SYSTEM_PROMPT};
+use crate::plugins::interface::FindingSeverity;
+
+// Most suggestions kept per finding
+const MAX_SUGGESTIONS: usize = 3;
+
+/// A refactoring precise enough to apply mechanically. Paths are project-relative.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SuggestedAction {
+    MoveFile {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    SplitFile {
+        file: PathBuf,
+        into: Vec<PathBuf>,
+    },
+    SplitFunction {
+        file: PathBuf,
+        function: String,
+        into: Vec<String>,
+    },
+    MoveElement {
+        file: PathBuf,
+        element: String,
+        to: PathBuf,
+    },
+}
+
+impl SuggestedAction {
+    /// One line for people, e.g. "move src/util.rs → src/text/util.rs"
+    pub fn describe(&self) -> String {
+        let join = |items: Vec<String>| items.join(", ");
+        match self {
+            SuggestedAction::MoveFile { from, to } => {
+                format!("move {} → {}", from.display(), to.display())
+            }
+            SuggestedAction::SplitFile { file, into } => format!(
+                "split {} into {}",
+                file.display(),
+                join(into.iter().map(|p| p.display().to_string()).collect())
+            ),
+            SuggestedAction::SplitFunction {
+                file,
+                function,
+                into,
+            } => format!(
+                "split {function} in {} into {}",
+                file.display(),
+                join(into.clone())
+            ),
+            SuggestedAction::MoveElement { file, element, to } => {
+                format!("move {element} from {} to {}", file.display(), to.display())
+            }
+        }
+    }
+
+    // Names existing files and elements, and doesn't move anything onto an existing file
+    fn is_valid(&self, matrix: &ProjectMatrix) -> bool {
+        match self {
+            SuggestedAction::MoveFile { from, to } => {
+                find_file(matrix, from).is_some() && find_file(matrix, to).is_none()
+            }
+            SuggestedAction::SplitFile { file, into } => {
+                find_file(matrix, file).is_some() && !into.is_empty()
+            }
+            SuggestedAction::SplitFunction {
+                file,
+                function,
+                into,
+            } => find_element(matrix, file, function).is_some() && into.len() >= 2,
+            SuggestedAction::MoveElement { file, element, to } => {
+                find_element(matrix, file, element).is_some() && path_key(file) != path_key(to)
+            }
+        }
+    }
+}
+
+/// A fact from the matrix a suggestion cites
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Evidence {
+    // "E1", "E2", ...: how the prompt and the answer refer to it
+    pub id: String,
+    pub file: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    pub fact: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<SuggestedAction>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evidence: Vec<Evidence>,
+    // Drafted by the LLM rather than reported by a check; worth a second look
+    #[serde(default)]
+    pub drafted: bool,
+}
+
+/// Prints suggestions under a finding, indented by `indent` spaces
+pub fn print_suggestions(suggestions: &[Suggestion], indent: usize) {
+    let pad = " ".repeat(indent);
+    for suggestion in suggestions {
+        println!("{pad}💡 {}", suggestion.summary);
+        if let Some(ref action) = suggestion.action {
+            println!("{pad}   → {}", action.describe());
+        }
+        for evidence in &suggestion.evidence {
+            let location = match evidence.line {
+                Some(line) => format!("{}:{line}", evidence.file.display()),
+                None => evidence.file.display().to_string(),
+            };
+            println!("{pad}   [{}] {location}: {}", evidence.id, evidence.fact);
+        }
+    }
+}
+
+/// A finding suggestions are drafted for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SuggestionTarget {
+    File(PathBuf),
+    Function {
+        file: PathBuf,
+        name: String,
+    },
+    Rule {
+        file: PathBuf,
+        element: Option<String>,
+        line: Option<u32>,
+        rule: String,
+        message: String,
+    },
+}
+
+impl SuggestionTarget {
+    pub fn describe(&self) -> String {
+        match self {
+            SuggestionTarget::File(file) => {
+                format!("{} is over the file size limit", file.display())
+            }
+            SuggestionTarget::Function { file, name } => format!(
+                "{name} in {} is over the function length or complexity limit",
+                file.display()
+            ),
+            SuggestionTarget::Rule {
+                file,
+                element,
+                rule,
+                message,
+                ..
+            } => match element {
+                Some(element) => format!("{rule} on {element} in {}: {message}", file.display()),
+                None => format!("{rule} on {}: {message}", file.display()),
+            },
+        }
+    }
+}
+
+/// Numbered facts about the finding's file and element: sizes, the largest elements,
+/// calls, and dependencies both ways
+pub fn evidence_for(matrix: &ProjectMatrix, target: &SuggestionTarget) -> Vec<Evidence> {
+    let mut facts: Vec<(Option<String>, Option<u32>, String)> = Vec::new();
+    let (file_path, element_name) = match target {
+        SuggestionTarget::File(file) => (file, None),
+        SuggestionTarget::Function { file, name } => (file, Some(name.as_str())),
+        SuggestionTarget::Rule {
+            file,
+            element,
+            line,
+            rule,
+            message,
+        } => {
+            facts.push((
+                element.clone(),
+                *line,
+                format!("rule {rule} reported: {message}"),
+            ));
+            (file, element.as_deref())
+        }
+    };
+    let Some(file) = find_file(matrix, file_path) else {
+        return number(file_path, facts);
+    };
+    let path = file.relative_path.display().to_string();
+
+    facts.push((
+        None,
+        None,
+        format!(
+            "{path} has {} tokens in {} elements",
+            file.token_info.total_tokens,
+            file.elements.len()
+        ),
+    ));
+    let element = element_name.and_then(|name| {
+        file.elements
+            .iter()
+            .find(|element| element.matches_name(name))
+    });
+    match element {
+        Some(element) => {
+            facts.push(element_fact(element));
+            if !element.calls.is_empty() {
+                facts.push((
+                    Some(element.qualified_name().to_string()),
+                    None,
+                    format!(
+                        "{} calls {}",
+                        element.qualified_name(),
+                        element
+                            .calls
+                            .iter()
+                            .take(10)
+                            .cloned()
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                ));
+            }
+        }
+        None => {
+            let mut largest: Vec<&CodeElement> = file.elements.iter().collect();
+            largest.sort_by_key(|element| {
+                std::cmp::Reverse(element.line_end.saturating_sub(element.line_start))
+            });
+            facts.extend(largest.into_iter().take(5).map(element_fact));
+        }
+    }
+
+    let relative = |key: &Path| -> String {
+        matrix
+            .files
+            .get(key)
+            .map(|node| node.relative_path.display().to_string())
+            .unwrap_or_else(|| key.display().to_string())
+    };
+    let mut dependencies: Vec<String> = Vec::new();
+    let mut dependents: Vec<String> = Vec::new();
+    for relationship in &matrix.relationships {
+        if relationship.from_file == file.path || relationship.from_file == file.relative_path {
+            dependencies.push(format!(
+                "{} ({})",
+                relative(&relationship.to_file),
+                format!("{:?}", relationship.relationship_type).to_lowercase()
+            ));
+        } else if relationship.to_file == file.path || relationship.to_file == file.relative_path {
+            dependents.push(relative(&relationship.from_file));
+        }
+    }
+    dependents.sort();
+    dependents.dedup();
+    if !dependencies.is_empty() {
+        facts.push((
+            None,
+            None,
+            format!(
+                "{path} depends on {} files: {}",
+                dependencies.len(),
+                dependencies
+                    .iter()
+                    .take(8)
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ));
+    }
+    if !dependents.is_empty() {
+        facts.push((
+            None,
+            None,
+            format!(
+                "{} files depend on {path}: {}",
+                dependents.len(),
+                dependents
+                    .iter()
+                    .take(8)
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ));
+    }
+    number(&file.relative_path, facts)
+}
+
+fn element_fact(element: &CodeElement) -> (Option<String>, Option<u32>, String) {
+    let lines = element.line_end.saturating_sub(element.line_start) + 1;
+    let complexity = element
+        .complexity_score
+        .map(|c| format!(", complexity {c}"))
+        .unwrap_or_default();
+    (
+        Some(element.qualified_name().to_string()),
+        Some(element.line_start),
+        format!(
+            "{} spans lines {}-{} ({lines} lines{complexity})",
+            element.qualified_name(),
+            element.line_start,
+            element.line_end
+        ),
+    )
+}
+
+fn number(file: &Path, facts: Vec<(Option<String>, Option<u32>, String)>) -> Vec<Evidence> {
+    facts
+        .into_iter()
+        .enumerate()
+        .map(|(i, (element, line, fact))| Evidence {
+            id: format!("E{}", i + 1),
+            file: file.to_path_buf(),
+            element,
+            line,
+            fact,
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct DraftAnswer {
+    #[serde(default)]
+    suggestions: Vec<DraftSuggestion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DraftSuggestion {
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    evidence: Vec<String>,
+    #[serde(default)]
+    action: Option<serde_json::Value>,
+}
+
+/// The suggestions in an LLM answer that cite at least one of `evidence`. Citations of
+/// facts that weren't given are dropped, and so are actions that don't fit the matrix,
+/// leaving the suggestion as advice.
+pub fn parse_suggestions(
+    answer: &str,
+    matrix: &ProjectMatrix,
+    evidence: &[Evidence],
+) -> Result<Vec<Suggestion>> {
+    // Models like to wrap JSON in a code fence even when asked not to
+    let json = match (answer.find('{'), answer.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &answer[start..=end],
+        _ => return Err(anyhow::anyhow!("The LLM answer holds no JSON object")),
+    };
+    let draft: DraftAnswer = serde_json::from_str(json)
+        .map_err(|e| anyhow::anyhow!("The LLM answer isn't in the requested form: {e}"))?;
+
+    Ok(draft
+        .suggestions
+        .into_iter()
+        .filter_map(|draft| {
+            let cited: Vec<Evidence> = evidence
+                .iter()
+                .filter(|fact| draft.evidence.iter().any(|id| id.trim() == fact.id))
+                .cloned()
+                .collect();
+            if draft.summary.trim().is_empty() || cited.is_empty() {
+                return None;
+            }
+            let action = draft
+                .action
+                .filter(|value| !value.is_null())
+                .and_then(|value| serde_json::from_value::<SuggestedAction>(value).ok())
+                .filter(|action| action.is_valid(matrix));
+            Some(Suggestion {
+                summary: draft.summary.trim().to_string(),
+                action,
+                evidence: cited,
+                drafted: true,
+            })
+        })
+        .take(MAX_SUGGESTIONS)
+        .collect())
+}
+
+/// How a `--suggest` run went
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DraftSummary {
+    pub findings: usize,
+    pub suggestions: usize,
+    pub failed: usize,
+}
+
+/// Ask the LLM for suggestions on the `top` most pressing findings: rule errors, then
+/// functions and files over the size limits (worst first), then rule warnings. The
+/// suggestions are attached to the findings; a finding the LLM can't help with is
+/// logged and left as it was.
+pub async fn draft_suggestions(
+    client: &LlmClient,
+    matrix: &ProjectMatrix,
+    hotspots: &mut HotspotReport,
+    rules: &mut RuleReport,
+    top: usize,
+) -> Result<DraftSummary> {
+    enum Slot {
+        Function(usize),
+        File(usize),
+        Rule(usize),
+    }
+    let rules_with = |severity: FindingSeverity| {
+        rules
+            .findings
+            .iter()
+            .enumerate()
+            .filter(move |(_, finding)| finding.severity == severity)
+            .map(|(i, _)| Slot::Rule(i))
+    };
+    let slots: Vec<Slot> = rules_with(FindingSeverity::Error)
+        .chain((0..hotspots.functions.len()).map(Slot::Function))
+        .chain((0..hotspots.files.len()).map(Slot::File))
+        .chain(rules_with(FindingSeverity::Warning))
+        .take(top)
+        .collect();
+
+    let mut summary = DraftSummary::default();
+    for slot in slots {
+        let target = match slot {
+            Slot::Function(i) => SuggestionTarget::Function {
+                file: hotspots.functions[i].path.clone(),
+                name: hotspots.functions[i].name.clone(),
+            },
+            Slot::File(i) => SuggestionTarget::File(hotspots.files[i].path.clone()),
+            Slot::Rule(i) => {
+                let finding = &rules.findings[i];
+                SuggestionTarget::Rule {
+                    file: finding.file.clone(),
+                    element: finding.element.clone(),
+                    line: finding.line,
+                    rule: finding.rule.clone(),
+                    message: finding.message.clone(),
+                }
+            }
+        };
+        let evidence = evidence_for(matrix, &target);
+        let prompt = suggestion_prompt(&target.describe(), &evidence);
+        summary.findings += 1;
+
+        let drafted = match client
+            .generate(SUGGESTION_SYSTEM_PROMPT, &prompt, true)
+            .await
+        {
+            Ok(answer) => parse_suggestions(&answer, matrix, &evidence),
+            Err(e) => Err(e),
+        };
+        let suggestions = match drafted {
+            Ok(suggestions) => suggestions,
+            Err(e) => {
+                warn!("No suggestions for {}: {e:#}", target.describe());
+                summary.failed += 1;
+                continue;
+            }
+        };
+        summary.suggestions += suggestions.len();
+        let attached = match slot {
+            Slot::Function(i) => &mut hotspots.functions[i].suggestions,
+            Slot::File(i) => &mut hotspots.files[i].suggestions,
+            Slot::Rule(i) => &mut rules.findings[i].suggestions,
+        };
+        attached.extend(suggestions);
+    }
+    info!(
+        "{} drafted {} suggestions for {} findings",
+        client.model(),
+        summary.suggestions,
+        summary.findings
+    );
+    Ok(summary)
+}
+
+fn find_file<'a>(matrix: &'a ProjectMatrix, relative_path: &Path) -> Option<&'a FileNode> {
+    let key = path_key(relative_path);
+    matrix
+        .files
+        .values()
+        .find(|file| path_key(&file.relative_path) == key)
+}
+
+fn find_element<'a>(
+    matrix: &'a ProjectMatrix,
+    relative_path: &Path,
+    name: &str,
+) -> Option<&'a CodeElement> {
+    find_file(matrix, relative_path)?
+        .elements
+        .iter()
+        .find(|element| element.matches_name(name))
+}
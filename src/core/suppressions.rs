@@ -0,0 +1,401 @@
+// src/core/suppressions.rs - Quality findings people chose to live with: `csd-ignore`
+// pragmas in code comments, and entries in a suppressions file that can expire, so gates
+// can be adopted before every existing offender is fixed
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::core::comments::{comments_in, syntax_for};
+
+/// Suppressions file, relative to the project root
+pub const SUPPRESSIONS_FILE: &str = ".csd_suppressions.yaml";
+
+/// Silences element checks on the element it's in or directly above, e.g.
+/// `// csd-ignore: complexity -- generated parser`
+pub const PRAGMA: &str = "csd-ignore";
+
+/// Silences checks across the whole file, e.g. `# csd-ignore-file: file-size`
+pub const FILE_PRAGMA: &str = "csd-ignore-file";
+
+/// The checks behind the hotspot report, and those of rule plugins
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum QualityCheck {
+    // File over `max_file_tokens`
+    FileSize,
+    // Function over `max_function_lines`
+    FunctionLength,
+    // Function over `max_function_complexity`
+    Complexity,
+    // A rule plugin's check, as `<plugin>/<rule>`
+    Rule(String),
+}
+
+impl QualityCheck {
+    pub fn as_str(&self) -> &str {
+        match self {
+            QualityCheck::FileSize => "file-size",
+            QualityCheck::FunctionLength => "function-length",
+            QualityCheck::Complexity => "complexity",
+            QualityCheck::Rule(name) => name,
+        }
+    }
+}
+
+impl std::fmt::Display for QualityCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for QualityCheck {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        match s.to_ascii_lowercase().as_str() {
+            "file-size" => Ok(QualityCheck::FileSize),
+            "function-length" => Ok(QualityCheck::FunctionLength),
+            "complexity" => Ok(QualityCheck::Complexity),
+            _ if s
+                .split_once('/')
+                .is_some_and(|(plugin, rule)| !plugin.is_empty() && !rule.is_empty()) =>
+            {
+                Ok(QualityCheck::Rule(s.to_string()))
+            }
+            _ => Err(anyhow::anyhow!(
+                "Unknown quality check '{s}' (expected file-size, function-length, complexity \
+                 or <plugin>/<rule>)"
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for QualityCheck {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<QualityCheck> for String {
+    fn from(check: QualityCheck) -> Self {
+        check.as_str().to_string()
+    }
+}
+
+/// An entry of the suppressions file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suppression {
+    // Project-relative path or glob
+    pub path: String,
+    // Only this element of the matching files (a bare or qualified name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element: Option<String>,
+    // Every check when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<QualityCheck>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    // Last day the suppression applies; after it the findings count again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<NaiveDate>,
+}
+
+impl Suppression {
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        self.expires.is_some_and(|expires| today > expires)
+    }
+
+    fn covers(&self, path: &Path, element: Option<&str>, check: &QualityCheck) -> bool {
+        let path = path.to_string_lossy().replace('\\', "/");
+        let own = self.path.trim_start_matches("./").trim_end_matches('/');
+        // A directory covers everything under it
+        let path_matches = path == own
+            || path.starts_with(&format!("{own}/"))
+            || glob::Pattern::new(&self.path).is_ok_and(|pattern| pattern.matches(&path));
+        let element_matches = match (&self.element, element) {
+            (None, _) => true,
+            (Some(wanted), Some(element)) => names_match(element, wanted),
+            (Some(_), None) => false,
+        };
+        path_matches && element_matches && (self.checks.is_empty() || self.checks.contains(check))
+    }
+}
+
+// `Parser::parse` is matched by `parse` and `Parser::parse`, in either `::` or `.` form
+fn names_match(qualified: &str, wanted: &str) -> bool {
+    let segments = |name: &str| -> Vec<String> {
+        name.split("::")
+            .flat_map(|part| part.split('.'))
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    segments(qualified).ends_with(&segments(wanted))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SuppressionsFile {
+    #[serde(default)]
+    suppressions: Vec<Suppression>,
+}
+
+/// A `csd-ignore` comment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pragma {
+    // 1-based
+    pub line: usize,
+    pub file_wide: bool,
+    // Every check when empty
+    pub checks: Vec<QualityCheck>,
+    // Text after ` -- `
+    pub reason: Option<String>,
+}
+
+impl Pragma {
+    fn names(&self, check: &QualityCheck) -> bool {
+        self.checks.is_empty() || self.checks.contains(check)
+    }
+}
+
+/// The pragmas in the comments of `content`, read from `path`; none for languages
+/// without a known comment syntax. Unknown check names are warned about and skipped; a
+/// pragma naming only unknown checks is dropped rather than silencing everything.
+pub fn pragmas_in(path: &Path, content: &str) -> Vec<Pragma> {
+    let Some(syntax) = syntax_for(path) else {
+        return Vec::new();
+    };
+    let mut pragmas = Vec::new();
+    for (line, comment) in comments_in(content, syntax) {
+        let (file_wide, rest) = if let Some(at) = comment.find(FILE_PRAGMA) {
+            (true, &comment[at + FILE_PRAGMA.len()..])
+        } else if let Some(at) = comment.find(PRAGMA) {
+            (false, &comment[at + PRAGMA.len()..])
+        } else {
+            continue;
+        };
+        // `csd-ignored` and the like aren't pragmas
+        if rest
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            continue;
+        }
+        let (list, reason) = match rest.split_once("--") {
+            Some((list, reason)) => (list, Some(reason)),
+            None => (rest, None),
+        };
+        // Block comment closers aren't part of the list
+        let list = list
+            .trim()
+            .trim_start_matches(':')
+            .trim_end_matches("*/")
+            .trim_end_matches("-->");
+        let names: Vec<&str> = list
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|name| !name.is_empty())
+            .collect();
+        let checks: Vec<QualityCheck> = names
+            .iter()
+            .filter_map(|name| match name.parse() {
+                Ok(check) => Some(check),
+                Err(e) => {
+                    warn!("{}:{line}: {e}", path.display());
+                    None
+                }
+            })
+            .collect();
+        if !names.is_empty() && checks.is_empty() {
+            continue;
+        }
+        let reason = reason
+            .map(|reason| {
+                reason
+                    .trim()
+                    .trim_end_matches("*/")
+                    .trim_end_matches("-->")
+                    .trim()
+                    .to_string()
+            })
+            .filter(|reason| !reason.is_empty());
+        pragmas.push(Pragma {
+            line,
+            file_wide,
+            checks,
+            reason,
+        });
+    }
+    pragmas
+}
+
+/// What silenced a finding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum SuppressedBy {
+    Pragma {
+        line: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    File {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expires: Option<NaiveDate>,
+    },
+}
+
+/// An offender left out of a report, and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suppressed {
+    pub path: PathBuf,
+    // The element, for element checks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    pub checks: Vec<QualityCheck>,
+    pub by: SuppressedBy,
+}
+
+/// The suppressions file and the project whose comments hold pragmas
+#[derive(Debug, Clone)]
+pub struct Suppressions {
+    // Pragmas are only read with a root
+    root: Option<PathBuf>,
+    entries: Vec<Suppression>,
+    today: NaiveDate,
+}
+
+impl Default for Suppressions {
+    fn default() -> Self {
+        Self {
+            root: None,
+            entries: Vec::new(),
+            today: chrono::Local::now().date_naive(),
+        }
+    }
+}
+
+impl Suppressions {
+    /// The project's suppressions file, if it has one, and its pragmas
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let path = project_root.join(SUPPRESSIONS_FILE);
+        let file: SuppressionsFile = if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_yaml::from_str(&content)
+                .with_context(|| format!("Invalid suppressions in {}", path.display()))?
+        } else {
+            SuppressionsFile::default()
+        };
+        Ok(Self {
+            root: Some(project_root.to_path_buf()),
+            entries: file.suppressions,
+            ..Self::default()
+        })
+    }
+
+    pub fn new(entries: Vec<Suppression>) -> Self {
+        Self {
+            entries,
+            ..Self::default()
+        }
+    }
+
+    /// Judge expiry as of `today` rather than the local date
+    pub fn with_today(mut self, today: NaiveDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Entries past their expiry date, which no longer suppress anything
+    pub fn expired(&self) -> Vec<&Suppression> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.is_expired(self.today))
+            .collect()
+    }
+
+    /// Suppressions for one file: the entries covering it and the pragmas in it. An
+    /// unreadable file just has no pragmas.
+    pub fn for_file(&self, relative_path: &Path) -> FileSuppressions<'_> {
+        let pragmas = match self.root {
+            Some(ref root) if syntax_for(relative_path).is_some() => {
+                std::fs::read_to_string(root.join(relative_path))
+                    .map(|content| pragmas_in(relative_path, &content))
+                    .unwrap_or_default()
+            }
+            _ => Vec::new(),
+        };
+        FileSuppressions {
+            path: relative_path.to_path_buf(),
+            entries: self
+                .entries
+                .iter()
+                .filter(|entry| !entry.is_expired(self.today))
+                .collect(),
+            pragmas,
+        }
+    }
+}
+
+pub struct FileSuppressions<'a> {
+    path: PathBuf,
+    entries: Vec<&'a Suppression>,
+    pragmas: Vec<Pragma>,
+}
+
+impl FileSuppressions<'_> {
+    /// Whether a file-level check is silenced, by a file-wide pragma, a pragma naming
+    /// the check, or an entry without an element
+    pub fn file(&self, check: &QualityCheck) -> Option<SuppressedBy> {
+        self.pragmas
+            .iter()
+            .find(|pragma| {
+                pragma.names(check) && (pragma.file_wide || pragma.checks.contains(check))
+            })
+            .map(|pragma| SuppressedBy::Pragma {
+                line: pragma.line,
+                reason: pragma.reason.clone(),
+            })
+            .or_else(|| self.entry(None, check))
+    }
+
+    /// Whether a check on the element spanning `lines` is silenced, by a file-wide
+    /// pragma, a pragma on the line above or inside it, or an entry
+    pub fn element(
+        &self,
+        name: &str,
+        lines: (u32, u32),
+        check: &QualityCheck,
+    ) -> Option<SuppressedBy> {
+        let (start, end) = (lines.0.saturating_sub(1) as usize, lines.1 as usize);
+        self.pragmas
+            .iter()
+            .find(|pragma| {
+                pragma.names(check) && (pragma.file_wide || (start..=end).contains(&pragma.line))
+            })
+            .map(|pragma| SuppressedBy::Pragma {
+                line: pragma.line,
+                reason: pragma.reason.clone(),
+            })
+            .or_else(|| self.entry(Some(name), check))
+    }
+
+    fn entry(&self, element: Option<&str>, check: &QualityCheck) -> Option<SuppressedBy> {
+        self.entries
+            .iter()
+            .find(|entry| entry.covers(&self.path, element, check))
+            .map(|entry| SuppressedBy::File {
+                reason: entry.reason.clone(),
+                expires: entry.expires,
+            })
+    }
+}
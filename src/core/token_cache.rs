@@ -0,0 +1,160 @@
+// src/core/token_cache.rs - Token estimates cached by content hash across scans
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::core::comments::{count_comment_tokens, CommentSyntax};
+use crate::core::matrix::{estimate_code_tokens, estimate_tokens};
+use crate::utils::cache::CacheLock;
+use crate::utils::file_utils::write_atomic;
+
+/// Bump when the token estimators change so counts cached by older versions are dropped
+pub const TOKENIZER_VERSION: u32 = 2;
+
+/// Token estimates for a file's full, untruncated content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenCounts {
+    // `estimate_tokens`, which also decides whether a file gets truncated
+    pub tokens: u64,
+    // `estimate_code_tokens`, comments included
+    pub code_tokens: u64,
+    // Shares of `code_tokens` in doc comments and other comments, when the
+    // language's comment syntax is known
+    pub documentation_tokens: u64,
+    pub comment_tokens: u64,
+}
+
+impl TokenCounts {
+    pub fn of(content: &str, syntax: Option<&CommentSyntax>) -> Self {
+        let comments = syntax
+            .map(|syntax| count_comment_tokens(content, syntax))
+            .unwrap_or_default();
+        Self {
+            tokens: estimate_tokens(content),
+            code_tokens: estimate_code_tokens(content),
+            documentation_tokens: comments.documentation,
+            comment_tokens: comments.comment,
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct TokenCacheFile {
+    version: u32,
+    entries: HashMap<String, TokenCounts>,
+}
+
+#[derive(Default)]
+struct Entries {
+    // Loaded from the previous scan
+    cached: HashMap<String, TokenCounts>,
+    // Looked up or computed during this scan; the only entries saved
+    current: HashMap<String, TokenCounts>,
+    hits: usize,
+    misses: usize,
+}
+
+/// Token counts keyed by content hash, stored in `<cache>/tokens.json` so unchanged
+/// files aren't re-tokenized on every scan. Safe to share between concurrent tasks.
+#[derive(Default)]
+pub struct TokenCache {
+    entries: Mutex<Entries>,
+}
+
+impl TokenCache {
+    pub fn path(cache_dir: &Path) -> PathBuf {
+        cache_dir.join("tokens.json")
+    }
+
+    /// Replace the cached counts with those saved in `cache_dir`. A missing, unreadable
+    /// or outdated cache just starts empty.
+    pub async fn load(&self, cache_dir: &Path) {
+        let path = Self::path(cache_dir);
+        let cached = match read_cache_file(&path).await {
+            Ok(Some(file)) if file.version == TOKENIZER_VERSION => file.entries,
+            Ok(_) => HashMap::new(),
+            Err(e) => {
+                log::debug!("Ignoring unreadable token cache {}: {e}", path.display());
+                HashMap::new()
+            }
+        };
+        *self.entries.lock().unwrap() = Entries {
+            cached,
+            ..Entries::default()
+        };
+    }
+
+    pub fn get(&self, hash: &str) -> Option<TokenCounts> {
+        let mut entries = self.entries.lock().unwrap();
+        let counts = entries
+            .current
+            .get(hash)
+            .or_else(|| entries.cached.get(hash))
+            .copied();
+        match counts {
+            Some(counts) => {
+                entries.hits += 1;
+                entries.current.insert(hash.to_string(), counts);
+            }
+            None => entries.misses += 1,
+        }
+        counts
+    }
+
+    pub fn insert(&self, hash: &str, counts: TokenCounts) {
+        self.entries
+            .lock()
+            .unwrap()
+            .current
+            .insert(hash.to_string(), counts);
+    }
+
+    /// Cached counts for `hash`, or those of `content` (read only on a miss), which
+    /// are then cached. Entries are keyed by content alone, so files with identical
+    /// content in different languages share their comment counts.
+    pub fn get_or_compute(
+        &self,
+        hash: &str,
+        syntax: Option<&CommentSyntax>,
+        content: impl FnOnce() -> Option<String>,
+    ) -> Option<TokenCounts> {
+        if let Some(counts) = self.get(hash) {
+            return Some(counts);
+        }
+        let counts = TokenCounts::of(&content()?, syntax);
+        self.insert(hash, counts);
+        Some(counts)
+    }
+
+    /// (hits, misses) since the last load
+    pub fn stats(&self) -> (usize, usize) {
+        let entries = self.entries.lock().unwrap();
+        (entries.hits, entries.misses)
+    }
+
+    /// Write the counts used by this scan; entries for files that are gone or changed
+    /// are dropped
+    pub async fn save(&self, cache_dir: &Path) -> Result<()> {
+        let file = TokenCacheFile {
+            version: TOKENIZER_VERSION,
+            entries: self.entries.lock().unwrap().current.clone(),
+        };
+        let path = Self::path(cache_dir);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let _lock = CacheLock::exclusive(&path).await?;
+        write_atomic(&path, serde_json::to_vec(&file)?).await
+    }
+}
+
+async fn read_cache_file(path: &Path) -> Result<Option<TokenCacheFile>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let _lock = CacheLock::shared(path).await.ok();
+    let content = tokio::fs::read(path).await?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
@@ -0,0 +1,331 @@
+// src/core/traces.rs - Runtime traces: calls seen in profiler stacks or test call graphs,
+// matched to the matrix's elements to tell the dependencies a program exercises from
+// imports it merely has
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use crate::core::coverage::ReportPaths;
+use crate::core::matrix::{CodeElement, ProjectMatrix, Relationship, RelationshipType};
+use crate::utils::intern::IStr;
+
+// `source_plugin` of the call edges an import adds; the next import replaces them
+pub const TRACE_SOURCE: &str = "trace";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum TraceFormat {
+    /// Collapsed stacks, one `outer;inner;innermost <count>` per line, as written by
+    /// stackcollapse-perf.pl, stackcollapse-go (pprof) or `py-spy record --format raw`
+    Folded,
+    /// `{"calls": [{"caller": <frame>, "callee": <frame>, "count": N}]}`, e.g. from a
+    /// pytest hook recording calls; a frame is `{"function", "file", "line"}`
+    Json,
+}
+
+impl TraceFormat {
+    pub fn detect(content: &str) -> Self {
+        match content.trim_start().chars().next() {
+            Some('{') | Some('[') => TraceFormat::Json,
+            _ => TraceFormat::Folded,
+        }
+    }
+}
+
+/// A function in a trace, with its location when the tracer knew it
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Frame {
+    pub function: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+impl Frame {
+    /// `name (path:line)` as py-spy writes frames, or a bare symbol; perf's `+0x1a`
+    /// offsets are dropped
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() || text == "[unknown]" {
+            return None;
+        }
+        if let Some((function, location)) = text
+            .strip_suffix(')')
+            .and_then(|rest| rest.rsplit_once(" ("))
+        {
+            let (file, line) = match location.rsplit_once(':') {
+                Some((file, line)) if line.parse::<u32>().is_ok() => (file, line.parse().ok()),
+                _ => (location, None),
+            };
+            return Some(Frame {
+                function: function.trim().to_string(),
+                file: Some(PathBuf::from(file)),
+                line,
+            });
+        }
+        let function = match text.rsplit_once("+0x") {
+            Some((symbol, offset)) if offset.chars().all(|c| c.is_ascii_hexdigit()) => symbol,
+            _ => text,
+        };
+        Some(Frame {
+            function: function.to_string(),
+            file: None,
+            line: None,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonTrace {
+    calls: Vec<JsonCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonCall {
+    caller: Frame,
+    callee: Frame,
+    #[serde(default = "one")]
+    count: u64,
+}
+
+fn one() -> u64 {
+    1
+}
+
+/// How often each caller called each callee
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceData {
+    pub calls: BTreeMap<(Frame, Frame), u64>,
+}
+
+impl TraceData {
+    pub fn parse(content: &str, format: TraceFormat) -> Result<Self> {
+        let data = match format {
+            TraceFormat::Folded => Self::parse_folded(content)?,
+            TraceFormat::Json => Self::parse_json(content)?,
+        };
+        if data.calls.is_empty() {
+            return Err(anyhow::anyhow!("The trace holds no calls"));
+        }
+        Ok(data)
+    }
+
+    /// Each stack counts as its sample count of calls between each pair of adjacent
+    /// frames. A line without a count is one sample.
+    pub fn parse_folded(content: &str) -> Result<Self> {
+        let mut data = Self::default();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (stack, count) = match line.rsplit_once(char::is_whitespace) {
+                Some((stack, count)) if count.chars().all(|c| c.is_ascii_digit()) => {
+                    let count = count
+                        .parse::<u64>()
+                        .with_context(|| format!("Trace line {}: bad count", number + 1))?;
+                    (stack, count)
+                }
+                _ => (line, 1),
+            };
+            let frames: Vec<Frame> = stack.split(';').filter_map(Frame::parse).collect();
+            for pair in frames.windows(2) {
+                if pair[0] != pair[1] {
+                    data.add(pair[0].clone(), pair[1].clone(), count);
+                }
+            }
+        }
+        Ok(data)
+    }
+
+    /// A `{"calls": [...]}` object or just the array of calls
+    pub fn parse_json(content: &str) -> Result<Self> {
+        let calls = match serde_json::from_str::<JsonTrace>(content) {
+            Ok(trace) => trace.calls,
+            Err(_) => serde_json::from_str::<Vec<JsonCall>>(content)
+                .context("Not a JSON trace: expected {\"calls\": [...]}")?,
+        };
+        let mut data = Self::default();
+        for call in calls {
+            data.add(call.caller, call.callee, call.count);
+        }
+        Ok(data)
+    }
+
+    fn add(&mut self, caller: Frame, callee: Frame, count: u64) {
+        *self.calls.entry((caller, callee)).or_insert(0) += count;
+    }
+}
+
+/// What an import changed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TraceImport {
+    // Calls between elements of different files
+    pub calls: u64,
+    pub added: usize,
+    pub strengthened: usize,
+    // Frames that match no element, or several
+    pub unmatched_frames: usize,
+    // Imports between files the traces never saw calling each other
+    pub unexercised_imports: usize,
+}
+
+type Endpoint = (PathBuf, String);
+
+/// Replace the matrix's runtime calls with the trace's. Calls between elements of
+/// different files set `runtime_calls` on the edges between them and raise their
+/// strength with how hot they are; calls no `Call` edge records yet get one. Strengths
+/// are only ever raised, until the next scan.
+pub fn apply_traces(matrix: &mut ProjectMatrix, data: &TraceData) -> TraceImport {
+    matrix
+        .relationships
+        .retain(|r| r.source_plugin.as_deref() != Some(TRACE_SOURCE));
+    for relationship in &mut matrix.relationships {
+        relationship.runtime_calls = None;
+    }
+
+    let paths = ReportPaths::new(matrix);
+    let mut import = TraceImport::default();
+    let mut resolved: HashMap<&Frame, Option<Endpoint>> = HashMap::new();
+    let mut calls: BTreeMap<(Endpoint, Endpoint), u64> = BTreeMap::new();
+    for ((caller, callee), count) in &data.calls {
+        let mut endpoint = |frame| {
+            resolved
+                .entry(frame)
+                .or_insert_with(|| resolve_frame(matrix, &paths, frame))
+                .clone()
+        };
+        let (Some(from), Some(to)) = (endpoint(caller), endpoint(callee)) else {
+            continue;
+        };
+        if from.0 != to.0 {
+            *calls.entry((from, to)).or_insert(0) += count;
+        }
+    }
+    import.unmatched_frames = resolved.values().filter(|e| e.is_none()).count();
+
+    let mut between_files: HashMap<(PathBuf, PathBuf), u64> = HashMap::new();
+    for ((from, to), count) in &calls {
+        *between_files
+            .entry((from.0.clone(), to.0.clone()))
+            .or_insert(0) += count;
+    }
+    let hottest = calls.values().copied().max().unwrap_or(1);
+    let hottest_files = between_files.values().copied().max().unwrap_or(1);
+
+    // Existing edges: element edges take their own count, file edges the files' total
+    for relationship in &mut matrix.relationships {
+        let files = (relationship.from_file.clone(), relationship.to_file.clone());
+        let count = match (&relationship.from_element, &relationship.to_element) {
+            (Some(from), Some(to)) => calls
+                .get(&(
+                    (files.0.clone(), from.clone()),
+                    (files.1.clone(), to.clone()),
+                ))
+                .map(|count| (*count, hottest)),
+            _ => between_files
+                .get(&files)
+                .map(|count| (*count, hottest_files)),
+        };
+        if let Some((count, max)) = count {
+            relationship.runtime_calls = Some(count);
+            relationship.strength = relationship.strength.max(heat(count, max));
+            import.strengthened += 1;
+        }
+    }
+
+    let detected_at = chrono::Utc::now();
+    for ((from, to), count) in calls {
+        import.calls += count;
+        let recorded = matrix.relationships.iter().any(|r| {
+            r.relationship_type == RelationshipType::Call
+                && r.from_file == from.0
+                && r.to_file == to.0
+                && r.from_element.as_ref() == Some(&from.1)
+                && r.to_element.as_ref() == Some(&to.1)
+        });
+        if recorded {
+            continue;
+        }
+        matrix.relationships.push(Relationship {
+            from_file: from.0,
+            to_file: to.0,
+            relationship_type: RelationshipType::Call,
+            details: format!("{count} calls at runtime"),
+            line_number: None,
+            strength: heat(count, hottest),
+            source_plugin: Some(IStr::from(TRACE_SOURCE)),
+            detected_at: Some(detected_at),
+            from_element: Some(from.1),
+            to_element: Some(to.1),
+            runtime_calls: Some(count),
+        });
+        import.added += 1;
+    }
+    matrix.invalidate_graph();
+
+    import.unexercised_imports = matrix
+        .relationships
+        .iter()
+        .filter(|r| r.relationship_type == RelationshipType::Import && r.runtime_calls.is_none())
+        .count();
+    import
+}
+
+// 0.5 for a single call up to 1.0 for the hottest, on a log scale since a few paths
+// usually take most of the calls
+fn heat(count: u64, hottest: u64) -> f32 {
+    let scale = ((hottest as f64).ln_1p()).max(f64::MIN_POSITIVE);
+    (0.5 + 0.5 * ((count as f64).ln_1p() / scale).min(1.0)) as f32
+}
+
+// The file key and qualified name of the element a frame ran in. With a location,
+// that's the innermost element around the line, or the one of that name in the file;
+// without, the only element in the project by that name, trying less qualified forms
+// of it in turn.
+fn resolve_frame(matrix: &ProjectMatrix, paths: &ReportPaths, frame: &Frame) -> Option<Endpoint> {
+    let endpoint = |key: &PathBuf, element: &CodeElement| {
+        Some((key.clone(), element.qualified_name().to_string()))
+    };
+    if let Some(ref file) = frame.file {
+        let key = paths.resolve(&[], file)?;
+        let node = matrix.files.get(&key)?;
+        let around = frame.line.and_then(|line| {
+            node.elements
+                .iter()
+                .filter(|e| e.line_start <= line && line <= e.line_end)
+                .min_by_key(|e| e.line_end - e.line_start)
+        });
+        let element = around.or_else(|| {
+            node.elements
+                .iter()
+                .find(|e| e.matches_name(&frame.function))
+        })?;
+        return endpoint(&key, element);
+    }
+
+    let segments: Vec<&str> = frame
+        .function
+        .split("::")
+        .flat_map(|part| part.split('.'))
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    for start in 0..segments.len() {
+        let name = segments[start..].join("::");
+        let mut found = matrix.files.iter().flat_map(|(key, node)| {
+            node.elements
+                .iter()
+                .filter(|e| e.matches_name(&name))
+                .map(move |e| (key, e))
+        });
+        match (found.next(), found.next()) {
+            (Some((key, element)), None) => return endpoint(key, element),
+            (Some(_), Some(_)) => return None,
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,209 @@
+// src/core/transform.rs - Transform plugins: matrix post-processing between scan and output
+use anyhow::Result;
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::matrix::{FileNode, ProjectMatrix, Relationship, RelationshipType};
+use crate::plugins::communication::TransformPluginCommunicator;
+use crate::plugins::interface::{
+    plugin_script_path, MatrixPatch, PluginType, TransformPluginInput, TransformPluginInterface,
+};
+use crate::utils::cache::CacheRun;
+use crate::utils::config::Config;
+use crate::utils::intern::IStr;
+use uuid::Uuid;
+
+/// What applying a patch changed. Updates that don't validate (naming files or elements
+/// that aren't in the matrix, empty tags or summaries, unknown relationship types or
+/// strengths outside 0.0 to 1.0) are counted as skipped rather than failing the whole
+/// patch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatchSummary {
+    pub relationships: usize,
+    pub tags: usize,
+    pub summaries: usize,
+    pub skipped: usize,
+}
+
+/// Validate a plugin's patch and apply the updates that pass. Tags are merged into each
+/// file's `metadata.tags`; added relationships carry the plugin as their source.
+pub fn apply_patch(matrix: &mut ProjectMatrix, patch: MatrixPatch, plugin: &str) -> PatchSummary {
+    let mut summary = PatchSummary::default();
+    let index: HashMap<PathBuf, PathBuf> = matrix
+        .files
+        .values()
+        .map(|file| (file.relative_path.clone(), file.path.clone()))
+        .collect();
+
+    for mut update in patch.tags {
+        update.tags.retain(|tag| !tag.trim().is_empty());
+        let node = match file_by_relative_path(matrix, &index, &update.file) {
+            Some(node) if !update.tags.is_empty() => node,
+            _ => {
+                debug!("{plugin}: skipping tags for {}", update.file);
+                summary.skipped += 1;
+                continue;
+            }
+        };
+        if !node.metadata.is_object() {
+            node.metadata = serde_json::json!({});
+        }
+        let mut tags: Vec<String> = node.metadata["tags"]
+            .as_array()
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for tag in update.tags {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        node.metadata["tags"] = serde_json::json!(tags);
+        summary.tags += 1;
+    }
+
+    for update in patch.summaries {
+        let node = match file_by_relative_path(matrix, &index, &update.file) {
+            Some(node) if !update.summary.trim().is_empty() => node,
+            _ => {
+                debug!("{plugin}: skipping summary for {}", update.file);
+                summary.skipped += 1;
+                continue;
+            }
+        };
+        match update.element {
+            None => node.file_summary = Some(update.summary),
+            Some(name) => match node
+                .elements
+                .iter_mut()
+                .find(|element| element.qualified_name() == name || element.name == name)
+            {
+                Some(element) => element.summary = Some(update.summary),
+                None => {
+                    summary.skipped += 1;
+                    continue;
+                }
+            },
+        }
+        summary.summaries += 1;
+    }
+
+    let detected_at = chrono::Utc::now();
+    for relationship in patch.relationships {
+        let known = |file: &str| {
+            index.contains_key(Path::new(file)) || matrix.files.contains_key(Path::new(file))
+        };
+        if !known(&relationship.from_file)
+            || !known(&relationship.to_file)
+            || relationship
+                .relationship_type
+                .parse::<RelationshipType>()
+                .is_err()
+            || !(0.0..=1.0).contains(&relationship.strength)
+        {
+            debug!(
+                "{plugin}: skipping relationship {} -> {}",
+                relationship.from_file, relationship.to_file
+            );
+            summary.skipped += 1;
+            continue;
+        }
+        matrix.add_relationship(Relationship::from_plugin(
+            relationship,
+            Some(IStr::from(plugin)),
+            detected_at,
+        ));
+        summary.relationships += 1;
+    }
+    if summary.relationships > 0 {
+        matrix.dedup_relationships();
+    }
+
+    summary
+}
+
+fn file_by_relative_path<'a>(
+    matrix: &'a mut ProjectMatrix,
+    index: &HashMap<PathBuf, PathBuf>,
+    file: &str,
+) -> Option<&'a mut FileNode> {
+    index
+        .get(Path::new(file))
+        .and_then(|key| matrix.files.get_mut(key))
+}
+
+/// Run every enabled transform plugin in configured order. Each plugin reads the matrix
+/// from `matrix_path`, which is rewritten after every patch so later transforms see
+/// earlier changes. A failing transform is logged and skipped.
+pub async fn run_transforms(
+    matrix: &mut ProjectMatrix,
+    matrix_path: &Path,
+    project_root: &Path,
+    config: &Config,
+) -> Result<Vec<(String, PatchSummary)>> {
+    let mut applied = Vec::new();
+
+    for (name, plugin_config) in config.get_enabled_transform_plugins() {
+        let Some(plugin_path) = plugin_script_path(PluginType::Transform, &plugin_config.source)
+        else {
+            warn!(
+                "Transform plugin '{name}' has an unsupported source: {:?}",
+                plugin_config.source
+            );
+            continue;
+        };
+        if !plugin_path.exists() {
+            warn!("Transform plugin file not found: {}", plugin_path.display());
+            continue;
+        }
+
+        let cache_dir = config.resolve_cache_dir(project_root);
+        let input = TransformPluginInput {
+            matrix_path: matrix_path.to_path_buf(),
+            project_root: project_root.to_path_buf(),
+            cache_dir: cache_dir.to_string_lossy().to_string(),
+            plugin_config: plugin_config
+                .config
+                .as_ref()
+                .map(|v| serde_json::to_value(v).unwrap_or(serde_json::Value::Null)),
+        };
+
+        let run = CacheRun::create(&cache_dir, Uuid::new_v4())?;
+        let mut communicator = TransformPluginCommunicator::new(plugin_path)
+            .with_cache_dir(cache_dir)
+            .with_run_dir(run.dir().to_path_buf())
+            .with_logs(config.plugin_logs.clone())
+            .with_offline(config.offline)
+            .with_runtime(plugin_config.runtime);
+        if let Some(ref node_exe) = config.node_executable {
+            communicator = communicator.with_node_executable(node_exe.clone());
+        }
+        if let Some(ref wasm_exe) = config.wasm_executable {
+            communicator = communicator.with_wasm_executable(wasm_exe.clone());
+        }
+        if let Some(ref python_exe) = config.python_executable {
+            communicator = communicator.with_python_executable(python_exe.clone());
+        } else {
+            communicator = communicator.with_python_auto_detect();
+        }
+
+        match communicator.transform(input).await {
+            Ok(patch) => {
+                let summary = apply_patch(matrix, patch, name);
+                info!(
+                    "Transform '{name}' added {} relationships, tagged {} files, updated {} summaries ({} skipped)",
+                    summary.relationships, summary.tags, summary.summaries, summary.skipped
+                );
+                matrix.save(matrix_path).await?;
+                applied.push((name.clone(), summary));
+            }
+            Err(e) => warn!("Transform plugin '{name}' failed: {e}"),
+        }
+    }
+
+    Ok(applied)
+}
@@ -0,0 +1,149 @@
+// src/core/validate.rs - Each enabled plugin's `config` checked against the JSON Schema
+// the plugin declares (`csd validate`)
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::plugins::communication::PluginCommunicator;
+use crate::plugins::config_schema::SchemaViolation;
+use crate::plugins::conformance::CheckStatus;
+use crate::plugins::manager::{PluginInfo, PluginManager};
+use crate::utils::cache::CacheRun;
+use crate::utils::config::Config;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginConfigCheck {
+    pub plugin_type: String,
+    pub name: String,
+    pub status: CheckStatus,
+    // Plugins that declare no schema pass without anything being checked
+    pub has_schema: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<SchemaViolation>,
+    // Why the plugin couldn't be asked for its schema
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    pub plugins: Vec<PluginConfigCheck>,
+}
+
+impl ValidationReport {
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.plugins.iter().filter(|p| p.status == status).count()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.count(CheckStatus::Fail) == 0
+    }
+
+    pub fn print_summary(&self) {
+        println!("🔎 csd validate");
+        for plugin in &self.plugins {
+            let key = format!("{}_plugins.{}", plugin.plugin_type, plugin.name);
+            match (plugin.status, &plugin.error) {
+                (CheckStatus::Fail, _) => {
+                    println!("   ❌ {key}");
+                    for violation in &plugin.violations {
+                        println!("        → {violation}");
+                    }
+                }
+                (_, Some(error)) => println!("   ⚠️  {key}: not checked, {error}"),
+                _ if plugin.has_schema => println!("   ✅ {key}"),
+                _ => println!("   ✅ {key} (no schema declared)"),
+            }
+        }
+        println!(
+            "\n   {} valid, {} not checked, {} invalid",
+            self.count(CheckStatus::Pass),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail)
+        );
+    }
+}
+
+/// Ask every enabled plugin for its `config_schema` and check its `config` against it.
+/// A plugin that can't be started is a warning here; `csd doctor` is for diagnosing that.
+pub async fn validate_plugin_configs(
+    config: &Config,
+    project_root: &Path,
+) -> Result<ValidationReport> {
+    let mut plugins = PluginManager::new(config.clone())
+        .discover_plugins()
+        .await?;
+    plugins.sort_by(|a, b| (&a.plugin_type, &a.name).cmp(&(&b.plugin_type, &b.name)));
+
+    let run = CacheRun::create(&config.resolve_cache_dir(project_root), Uuid::new_v4())?;
+    let mut report = ValidationReport::default();
+    for plugin in &plugins {
+        let mut check = PluginConfigCheck {
+            plugin_type: plugin.plugin_type.clone(),
+            name: plugin.name.clone(),
+            status: CheckStatus::Pass,
+            has_schema: false,
+            violations: Vec::new(),
+            error: None,
+        };
+        match communicator(config, project_root, run.dir(), plugin)
+            .get_info()
+            .await
+        {
+            Ok(info) => {
+                check.has_schema = info.config_schema.is_some();
+                check.violations = info.check_config(plugin_settings(config, plugin));
+                if !check.violations.is_empty() {
+                    check.status = CheckStatus::Fail;
+                }
+            }
+            Err(e) => {
+                check.status = CheckStatus::Warn;
+                check.error = Some(format!("{e:#}"));
+            }
+        }
+        report.plugins.push(check);
+    }
+    Ok(report)
+}
+
+fn communicator(
+    config: &Config,
+    project_root: &Path,
+    run_dir: &Path,
+    plugin: &PluginInfo,
+) -> PluginCommunicator {
+    let path = if plugin.path.is_absolute() {
+        plugin.path.clone()
+    } else {
+        project_root.join(&plugin.path)
+    };
+    let mut communicator = PluginCommunicator::new(path)
+        .with_cache_dir(config.resolve_cache_dir(project_root))
+        .with_run_dir(run_dir.to_path_buf())
+        .with_logs(config.plugin_logs.clone())
+        .with_offline(config.offline)
+        .with_runtime(plugin.runtime);
+    if let Some(ref node_exe) = config.node_executable {
+        communicator = communicator.with_node_executable(node_exe.clone());
+    }
+    if let Some(ref wasm_exe) = config.wasm_executable {
+        communicator = communicator.with_wasm_executable(wasm_exe.clone());
+    }
+    match config.python_executable {
+        Some(ref python_exe) => communicator.with_python_executable(python_exe.clone()),
+        None => communicator.with_python_auto_detect(),
+    }
+}
+
+// The plugin's own `config` section
+fn plugin_settings<'a>(config: &'a Config, plugin: &PluginInfo) -> Option<&'a serde_yaml::Value> {
+    match plugin.plugin_type.as_str() {
+        "input" => config.input_plugins.get(&plugin.name)?.config.as_ref(),
+        "output" => config.output_plugins.get(&plugin.name)?.config.as_ref(),
+        "transform" => config.transform_plugins.get(&plugin.name)?.config.as_ref(),
+        "rule" => config.rule_plugins.get(&plugin.name)?.config.as_ref(),
+        _ => None,
+    }
+}
@@ -0,0 +1,75 @@
+// src/core/view.rs - Tag-scoped views of a matrix, behind the global --tag and
+// --exclude-tag options
+use anyhow::Result;
+use log::info;
+
+use crate::core::matrix::{FileNode, ProjectMatrix};
+
+/// Which files commands see, by the tags in their `metadata.tags`. Empty shows the
+/// whole matrix.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatrixView {
+    // A file must carry at least one of these, when there are any
+    pub tags: Vec<String>,
+    // A file carrying any of these is left out
+    pub exclude_tags: Vec<String>,
+}
+
+impl MatrixView {
+    pub fn new(tags: Vec<String>, exclude_tags: Vec<String>) -> Self {
+        Self { tags, exclude_tags }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.exclude_tags.is_empty()
+    }
+
+    pub fn includes(&self, file: &FileNode) -> bool {
+        let tags = file_tags(file);
+        (self.tags.is_empty() || self.tags.iter().any(|t| tags.contains(&t.as_str())))
+            && !self.exclude_tags.iter().any(|t| tags.contains(&t.as_str()))
+    }
+
+    /// The matrix cut down to the files in view, the edges among them and their
+    /// external dependencies. An empty view hands it back untouched; one that leaves
+    /// no files is an error, since every command would report on nothing.
+    pub fn apply(&self, matrix: ProjectMatrix) -> Result<ProjectMatrix> {
+        if self.is_empty() {
+            return Ok(matrix);
+        }
+        let view = matrix.filter_files(|file| self.includes(file));
+        if view.files.is_empty() {
+            return Err(anyhow::anyhow!("No files in the matrix match {self}"));
+        }
+        info!(
+            "Viewing {} of {} files ({self})",
+            view.files.len(),
+            matrix.files.len()
+        );
+        Ok(view)
+    }
+}
+
+impl std::fmt::Display for MatrixView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let options: Vec<String> = self
+            .tags
+            .iter()
+            .map(|t| format!("--tag {t}"))
+            .chain(
+                self.exclude_tags
+                    .iter()
+                    .map(|t| format!("--exclude-tag {t}")),
+            )
+            .collect();
+        f.write_str(&options.join(" "))
+    }
+}
+
+/// Tags plugins, transforms and `csd adr`/`csd debt` attached to a file
+pub fn file_tags(file: &FileNode) -> Vec<&str> {
+    file.metadata["tags"]
+        .as_array()
+        .map(|tags| tags.iter().filter_map(|t| t.as_str()).collect())
+        .unwrap_or_default()
+}
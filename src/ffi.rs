@@ -0,0 +1,207 @@
+// src/ffi.rs - C ABI for editors and tools that read the matrix without spawning the CLI
+//
+// Every function clears the thread's last error on entry and sets it on failure, so a
+// NULL return can be told apart from "nothing to report" with `csd_last_error()`.
+// Strings returned by the library are owned by the caller and released with
+// `csd_string_free`. The declarations live in `include/csd.h`.
+use anyhow::{Context, Result};
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+use crate::core::matrix::{FileNode, ProjectMatrix};
+
+/// Bumped whenever a function signature or returned JSON shape changes
+pub const CSD_FFI_VERSION: u32 = 1;
+
+/// Opaque handle to a loaded matrix
+pub struct CsdMatrix {
+    matrix: ProjectMatrix,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Run `body`, recording an error or panic as the last error and returning `fallback`
+fn ffi_call<T>(fallback: T, body: impl FnOnce() -> Result<T>) -> T {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+    match catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(e)) => {
+            set_last_error(format!("{e:#}"));
+            fallback
+        }
+        Err(_) => {
+            set_last_error("csd panicked".to_string());
+            fallback
+        }
+    }
+}
+
+unsafe fn str_arg<'a>(value: *const c_char, name: &str) -> Result<&'a str> {
+    if value.is_null() {
+        return Err(anyhow::anyhow!("{name} is NULL"));
+    }
+    CStr::from_ptr(value)
+        .to_str()
+        .with_context(|| format!("{name} is not valid UTF-8"))
+}
+
+unsafe fn matrix_arg<'a>(matrix: *mut CsdMatrix) -> Result<&'a mut ProjectMatrix> {
+    matrix
+        .as_mut()
+        .map(|handle| &mut handle.matrix)
+        .ok_or_else(|| anyhow::anyhow!("matrix is NULL"))
+}
+
+fn into_c_string(value: String) -> Result<*mut c_char> {
+    Ok(CString::new(value)
+        .context("Result contains a NUL byte")?
+        .into_raw())
+}
+
+/// Matrix key for `file`, given either as stored or relative to the project root
+fn resolve_file(matrix: &ProjectMatrix, file: &str) -> Result<PathBuf> {
+    let file = Path::new(file);
+    if matrix.files.contains_key(file) {
+        return Ok(file.to_path_buf());
+    }
+    matrix
+        .files
+        .values()
+        .find(|node| node.relative_path == file)
+        .map(|node| node.path.clone())
+        .ok_or_else(|| anyhow::anyhow!("File not in matrix: {}", file.display()))
+}
+
+fn relative_paths_json(nodes: Vec<&FileNode>) -> Result<*mut c_char> {
+    let mut paths: Vec<String> = nodes
+        .into_iter()
+        .map(|node| node.relative_path.to_string_lossy().to_string())
+        .collect();
+    paths.sort();
+    paths.dedup();
+    into_c_string(serde_json::to_string(&paths)?)
+}
+
+/// ABI version of this library (`CSD_FFI_VERSION`)
+#[no_mangle]
+pub extern "C" fn csd_ffi_version() -> u32 {
+    CSD_FFI_VERSION
+}
+
+/// Message for the last failed call on this thread, or NULL. Valid until the next call.
+#[no_mangle]
+pub extern "C" fn csd_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Load a matrix written by `csd init`. Returns NULL on failure.
+///
+/// # Safety
+/// `path` must be NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn csd_matrix_load(path: *const c_char) -> *mut CsdMatrix {
+    ffi_call(ptr::null_mut(), || {
+        let path = str_arg(path, "path")?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let matrix = runtime.block_on(ProjectMatrix::load(Path::new(path)))?;
+        Ok(Box::into_raw(Box::new(CsdMatrix { matrix })))
+    })
+}
+
+/// Release a matrix returned by `csd_matrix_load`. NULL is ignored.
+///
+/// # Safety
+/// `matrix` must be NULL or a handle from `csd_matrix_load` that wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn csd_matrix_free(matrix: *mut CsdMatrix) {
+    if !matrix.is_null() {
+        drop(Box::from_raw(matrix));
+    }
+}
+
+/// Number of files in the matrix
+///
+/// # Safety
+/// `matrix` must be NULL or a live handle from `csd_matrix_load`.
+#[no_mangle]
+pub unsafe extern "C" fn csd_matrix_file_count(matrix: *mut CsdMatrix) -> usize {
+    ffi_call(0, || Ok(matrix_arg(matrix)?.files.len()))
+}
+
+/// JSON array of the project-relative paths `file` depends on. Returns NULL on failure.
+///
+/// # Safety
+/// `matrix` must be NULL or a live handle; `file` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn csd_matrix_dependencies(
+    matrix: *mut CsdMatrix,
+    file: *const c_char,
+) -> *mut c_char {
+    ffi_call(ptr::null_mut(), || {
+        let matrix = matrix_arg(matrix)?;
+        let key = resolve_file(matrix, str_arg(file, "file")?)?;
+        relative_paths_json(matrix.find_dependencies(&key))
+    })
+}
+
+/// JSON array of the project-relative paths that depend on `file`. Returns NULL on failure.
+///
+/// # Safety
+/// `matrix` must be NULL or a live handle; `file` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn csd_matrix_dependents(
+    matrix: *mut CsdMatrix,
+    file: *const c_char,
+) -> *mut c_char {
+    ffi_call(ptr::null_mut(), || {
+        let matrix = matrix_arg(matrix)?;
+        let key = resolve_file(matrix, str_arg(file, "file")?)?;
+        relative_paths_json(matrix.find_dependents(&key))
+    })
+}
+
+/// Summary of `file`, or NULL when it has none (with no error set) or on failure
+///
+/// # Safety
+/// `matrix` must be NULL or a live handle; `file` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn csd_matrix_file_summary(
+    matrix: *mut CsdMatrix,
+    file: *const c_char,
+) -> *mut c_char {
+    ffi_call(ptr::null_mut(), || {
+        let matrix = matrix_arg(matrix)?;
+        let key = resolve_file(matrix, str_arg(file, "file")?)?;
+        match matrix.files[&key].file_summary.clone() {
+            Some(summary) => into_c_string(summary),
+            None => Ok(ptr::null_mut()),
+        }
+    })
+}
+
+/// Release a string returned by this library. NULL is ignored.
+///
+/// # Safety
+/// `value` must be NULL or a string returned by a `csd_*` function that wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn csd_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
@@ -1,6 +1,18 @@
+//! Code scanning, matrix building and documentation generation for `csd`.
+//!
+//! [`Csd`] wraps the whole pipeline for tools that embed csd instead of shelling out
+//! to the binary. The lower-level modules stay public for callers that need finer
+//! control over scanning or plugin communication, and [`ffi`] exposes matrix queries
+//! over a C ABI.
+
+#[cfg(feature = "cli")]
 pub mod cli;
 pub mod core;
+pub mod ffi;
 pub mod llm;
 pub mod output;
 pub mod plugins;
+pub mod server;
 pub mod utils;
+
+pub use crate::core::facade::{ChangelogReport, Csd, DocsPass, DocsReport, ScanReport};
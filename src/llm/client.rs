@@ -1 +1,96 @@
-// TODO: Implement
+// src/llm/client.rs - Talks to the configured LLM provider; the Rust counterpart of the
+// plugin SDK's LLMClient, for core commands that draft text themselves
+use anyhow::{Context, Result};
+use log::debug;
+use std::time::{Duration, Instant};
+
+use crate::llm::models::{GenerateOptions, GenerateRequest, GenerateResponse};
+use crate::utils::config::{is_local_url, LlmConfig};
+
+// Answers are short structured drafts, not documentation pages
+const MAX_OUTPUT_TOKENS: u32 = 1500;
+
+pub struct LlmClient {
+    client: reqwest::Client,
+    config: LlmConfig,
+}
+
+impl LlmClient {
+    /// A client for `config`, which must name a supported provider. With `offline`, only
+    /// an endpoint on this machine is accepted.
+    pub fn new(config: &LlmConfig, offline: bool) -> Result<Self> {
+        if !config.provider.eq_ignore_ascii_case("ollama") {
+            return Err(anyhow::anyhow!(
+                "Unsupported LLM provider: {} (expected ollama)",
+                config.provider
+            ));
+        }
+        if offline && !is_local_url(&config.base_url) {
+            return Err(anyhow::anyhow!(
+                "Offline mode forbids network access: LLM endpoint {} is not a local provider",
+                config.base_url
+            ));
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds.max(1)))
+            .user_agent(concat!("csd/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            client,
+            config: config.clone(),
+        })
+    }
+
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    /// The model's answer to `prompt`. With `json`, the answer is constrained to a JSON
+    /// value, though still unvalidated.
+    pub async fn generate(&self, system: &str, prompt: &str, json: bool) -> Result<String> {
+        let url = format!(
+            "{}/api/generate",
+            self.config.base_url.trim_end_matches('/')
+        );
+        let body = GenerateRequest {
+            model: &self.config.model,
+            prompt,
+            system: Some(system),
+            stream: false,
+            format: json.then_some("json"),
+            options: GenerateOptions {
+                temperature: 0.2,
+                num_predict: MAX_OUTPUT_TOKENS,
+            },
+        };
+        let mut request = self.client.post(&url).json(&body);
+        if let Some(ref api_key) = self.config.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let started = Instant::now();
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("LLM request to {url} failed"))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "LLM answered HTTP {status}: {}",
+                text.trim()
+            ));
+        }
+        let answer: GenerateResponse = response
+            .json()
+            .await
+            .context("Unreadable answer from the LLM")?;
+        debug!(
+            "{} answered in {}ms ({} tokens)",
+            self.config.model,
+            started.elapsed().as_millis(),
+            answer.eval_count.unwrap_or(0)
+        );
+        Ok(answer.response)
+    }
+}
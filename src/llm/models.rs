@@ -1 +1,31 @@
-// TODO: Implement
+// src/llm/models.rs - Request and response bodies of the LLM providers' HTTP APIs
+use serde::{Deserialize, Serialize};
+
+/// Body of Ollama's `POST /api/generate`
+#[derive(Debug, Clone, Serialize)]
+pub struct GenerateRequest<'a> {
+    pub model: &'a str,
+    pub prompt: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<&'a str>,
+    pub stream: bool,
+    // "json" makes the model answer with a single JSON value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<&'a str>,
+    pub options: GenerateOptions,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GenerateOptions {
+    pub temperature: f32,
+    // Most tokens to generate
+    pub num_predict: u32,
+}
+
+/// Non-streamed answer to `/api/generate`
+#[derive(Debug, Clone, Deserialize)]
+pub struct GenerateResponse {
+    pub response: String,
+    #[serde(default)]
+    pub eval_count: Option<u64>,
+}
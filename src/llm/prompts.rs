@@ -1 +1,30 @@
-// TODO: Implement
+// src/llm/prompts.rs - Prompts for what the core asks the LLM itself
+use crate::core::suggestions::Evidence;
+
+pub const SUGGESTION_SYSTEM_PROMPT: &str = "You review the structure of a codebase for a \
+static analysis tool. Suggest concrete refactorings for the quality finding you are given. \
+Rest every suggestion only on the numbered facts and cite the ones it uses by id; never \
+invent files, functions or numbers. Answer with JSON only.";
+
+/// The finding, its facts as `[E1] ...` lines, and the answer format
+pub fn suggestion_prompt(finding: &str, evidence: &[Evidence]) -> String {
+    let facts = evidence
+        .iter()
+        .map(|fact| format!("[{}] {}", fact.id, fact.fact))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        r#"Finding: {finding}
+
+Facts:
+{facts}
+
+Answer with {{"suggestions": [{{"summary": "...", "evidence": ["E1"], "action": ACTION}}]}}
+and at most 3 suggestions. ACTION is null or one of:
+{{"kind": "move_file", "from": "PATH", "to": "PATH"}}
+{{"kind": "split_file", "file": "PATH", "into": ["PATH", ...]}}
+{{"kind": "split_function", "file": "PATH", "function": "NAME", "into": ["NAME", ...]}}
+{{"kind": "move_element", "file": "PATH", "element": "NAME", "to": "PATH"}}
+Paths are relative to the project root."#
+    )
+}
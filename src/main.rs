@@ -3,25 +3,32 @@ use log::{error, info};
 
 use csd::cli::args::Args;
 use csd::cli::commands;
+use csd::cli::exit::ExitCode;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // Usage errors exit with 1; clap's usual 2 means "finished with warnings" here
+    let args = Args::try_parse().unwrap_or_else(|e| {
+        let _ = e.print();
+        std::process::exit(if e.use_stderr() { 1 } else { 0 });
+    });
+
     // Initialize logging
     env_logger::Builder::from_default_env()
-        .filter_level(log::LevelFilter::Info)
+        .filter_level(args.log_level())
         .init();
 
-    let args = Args::parse();
     info!("Starting code-scan-docs v{}", env!("CARGO_PKG_VERSION"));
 
-    match commands::handle_command(args).await {
+    let result = commands::handle_command(args).await;
+    match &result {
         Ok(_) => {
             info!("Command completed successfully");
             Ok(())
         }
         Err(e) => {
             error!("Command failed: {e}");
-            std::process::exit(1);
+            std::process::exit(ExitCode::of(&result).code());
         }
     }
 }
@@ -0,0 +1,79 @@
+// src/output/manifest.rs - Record of the files a `csd docs` run generated
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::plugins::interface::GeneratedOutput;
+
+/// Written to the root of the docs output directory
+pub const DOCS_MANIFEST_FILE: &str = "csd_manifest.json";
+
+const DOCUMENTATION: &str = "documentation";
+
+fn default_output_type() -> String {
+    DOCUMENTATION.to_string()
+}
+
+/// What the last documentation run produced, one entry per generation pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocsManifest {
+    pub generated_at: DateTime<Utc>,
+    pub format: String,
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// Outputs of one pass. `language` is absent for a single pass in the default language.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    // `documentation`, or e.g. `changelog` for drafts recorded next to the docs
+    #[serde(default = "default_output_type")]
+    pub output_type: String,
+    pub plugin: String,
+    pub plugin_version: String,
+    pub output_dir: PathBuf,
+    pub outputs: Vec<GeneratedOutput>,
+}
+
+impl DocsManifest {
+    pub fn new(format: &str) -> Self {
+        Self {
+            generated_at: Utc::now(),
+            format: format.to_string(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Documentation entry for `language`, or the default-language entry for `None`
+    pub fn entry(&self, language: Option<&str>) -> Option<&ManifestEntry> {
+        self.entries.iter().find(|entry| {
+            entry.output_type == DOCUMENTATION && entry.language.as_deref() == language
+        })
+    }
+
+    /// Replace the entries of `entry`'s output type with it, keeping the others
+    pub fn replace_output_type(&mut self, entry: ManifestEntry) {
+        self.entries
+            .retain(|existing| existing.output_type != entry.output_type);
+        self.entries.push(entry);
+    }
+
+    pub async fn save(&self, output_dir: &Path) -> Result<PathBuf> {
+        let path = output_dir.join(DOCS_MANIFEST_FILE);
+        tokio::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .await
+            .with_context(|| format!("Failed to write docs manifest: {}", path.display()))?;
+        Ok(path)
+    }
+
+    pub async fn load(output_dir: &Path) -> Result<Self> {
+        let path = output_dir.join(DOCS_MANIFEST_FILE);
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read docs manifest: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid docs manifest: {}", path.display()))
+    }
+}
@@ -1,2 +1,3 @@
 pub mod formatters;
+pub mod manifest;
 pub mod templates;
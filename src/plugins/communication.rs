@@ -1,37 +1,129 @@
 use anyhow::{Context, Result};
+use chrono::Utc;
 use log::{debug, error, info, warn};
 use serde_json;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::fs;
-use tokio::process::Command;
-use tokio::time::{interval, timeout};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Semaphore;
+use tokio::time::{interval, sleep_until, Instant};
 use uuid::Uuid;
 
+use crate::core::matrix::PluginPoolStats;
 use crate::plugins::interface::{
-    InputPluginInterface, OutputPluginInput, OutputPluginInterface, OutputPluginResult, PluginInfo,
-    PluginInput, PluginInterface, PluginMessage, PluginResponse, PluginType,
+    ContentPreview, GeneratedOutput, InputPluginInterface, MatrixPatch, OutputPluginInput,
+    OutputPluginInterface, OutputPluginResult, PluginInfo, PluginInput, PluginInterface,
+    PluginMessage, PluginProgress, PluginResponse, PluginType, RuleFinding, RulePluginInput,
+    RulePluginInterface, TransformPluginInput, TransformPluginInterface, OFFLINE_ENV,
+    PERSISTENT_ENV, PROGRESS_FILE_ENV, SOCKET_ENV, SOCKET_TOKEN_ENV,
 };
+use crate::plugins::logs::Transcript;
+use crate::plugins::transport::{read_frame, write_frame, Duplex, PluginListener, CONNECT_TIMEOUT};
+use crate::utils::config::{PluginLogConfig, PluginPoolConfig, PluginRuntime, DEFAULT_CACHE_DIR};
+use crate::utils::file_utils::{resolve_inside, resolve_within, write_atomic};
+use crate::utils::profile::{self, Stage};
 
 /// Base plugin communicator with common functionality
 pub struct PluginCommunicator {
     plugin_path: PathBuf,
+    runtime: PluginRuntime,
     python_executable: String,
+    node_executable: String,
+    wasm_executable: String,
     cache_dir: PathBuf,
+    // Replaces the per-message (timeout, progress interval) defaults
+    timeouts: Option<(Duration, Duration)>,
+    // Time allowed per analyzed file, replacing the analyze timeouts
+    file_timeout: Option<Duration>,
+    // No message may run past this, however much progress it reports
+    deadline: Option<Instant>,
+    logs: PluginLogConfig,
+    // Per-run scratch directory for input, progress and result files; see `CacheRun`
+    run_dir: Option<PathBuf>,
+    offline: bool,
+    // Messages go to a pooled process instead of a new one; see `with_pool`
+    pool: Option<Arc<PluginPool>>,
+    transport: Transport,
 }
 
 impl PluginCommunicator {
     pub fn new(plugin_path: PathBuf) -> Self {
-        let cache_dir = PathBuf::from(".csd_cache");
+        let cache_dir = PathBuf::from(DEFAULT_CACHE_DIR);
 
         Self {
             plugin_path,
+            runtime: PluginRuntime::default(),
             python_executable: "python".to_string(),
+            node_executable: "node".to_string(),
+            wasm_executable: "wasmtime".to_string(),
             cache_dir,
+            timeouts: None,
+            file_timeout: None,
+            deadline: None,
+            logs: PluginLogConfig::default(),
+            run_dir: None,
+            offline: false,
+            pool: None,
+            transport: Transport::default(),
         }
     }
 
+    /// Send messages through `pool`, for plugins that advertise `CAPABILITY_PERSISTENT`.
+    /// Pooled plugins can't extend their timeout by reporting progress.
+    pub fn with_pool(mut self, pool: Arc<PluginPool>, transport: Transport) -> Self {
+        self.pool = Some(pool);
+        self.transport = transport;
+        self
+    }
+
+    /// Tell the plugin, through `CSD_OFFLINE`, that it must not use the network
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Keep this run's input, progress and result files in `run_dir` rather than the
+    /// shared cache root
+    pub fn with_run_dir(mut self, run_dir: PathBuf) -> Self {
+        self.run_dir = Some(run_dir);
+        self
+    }
+
+    /// Where the plugin's input and progress files and its results live
+    pub fn scratch_dir(&self) -> &Path {
+        self.run_dir.as_deref().unwrap_or(&self.cache_dir)
+    }
+
+    /// Which invocations leave a stdout/stderr transcript under `<cache>/logs`
+    pub fn with_logs(mut self, logs: PluginLogConfig) -> Self {
+        self.logs = logs;
+        self
+    }
+
+    /// Override the timeout (extended while the plugin reports progress) and how often
+    /// progress is checked, for every message type
+    pub fn with_timeouts(mut self, timeout: Duration, progress_interval: Duration) -> Self {
+        self.timeouts = Some((timeout, progress_interval));
+        self
+    }
+
+    /// Give `analyze` messages `timeout` and `analyze_batch` messages `timeout` per file
+    pub fn with_file_timeout(mut self, timeout: Duration) -> Self {
+        self.file_timeout = Some(timeout);
+        self
+    }
+
+    /// Stop any plugin still running at `deadline`
+    pub fn with_deadline(mut self, deadline: std::time::Instant) -> Self {
+        self.deadline = Some(Instant::from_std(deadline));
+        self
+    }
+
     pub fn with_cache_dir(mut self, cache_dir: PathBuf) -> Self {
         self.cache_dir = cache_dir;
         self
@@ -42,6 +134,37 @@ impl PluginCommunicator {
         self
     }
 
+    /// Run the plugin with Node, a WASI runner or directly rather than with Python
+    pub fn with_runtime(mut self, runtime: PluginRuntime) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    pub fn with_node_executable(mut self, executable: String) -> Self {
+        self.node_executable = executable;
+        self
+    }
+
+    pub fn with_wasm_executable(mut self, executable: String) -> Self {
+        self.wasm_executable = executable;
+        self
+    }
+
+    /// How the plugin is started, whether once per message or by the pool
+    fn launch(&self) -> PluginLaunch {
+        PluginLaunch {
+            runtime: self.runtime,
+            interpreter: match self.runtime {
+                PluginRuntime::Node => self.node_executable.clone(),
+                PluginRuntime::Wasm => self.wasm_executable.clone(),
+                PluginRuntime::Python | PluginRuntime::Binary => self.python_executable.clone(),
+            },
+            plugin_path: self.plugin_path.clone(),
+            offline: self.offline,
+            transport: self.transport,
+        }
+    }
+
     pub fn with_python_auto_detect(mut self) -> Self {
         let candidates = ["python", "python3"];
 
@@ -62,39 +185,70 @@ impl PluginCommunicator {
 
     /// Ensure cache directory exists
     async fn ensure_cache_dir(&self) -> Result<()> {
-        if !self.cache_dir.exists() {
-            fs::create_dir_all(&self.cache_dir)
+        let dir = self.scratch_dir();
+        if !dir.exists() {
+            fs::create_dir_all(dir)
                 .await
                 .context("Failed to create cache directory")?;
-            debug!("Created cache directory: {}", self.cache_dir.display());
+            debug!("Created cache directory: {}", dir.display());
         }
         Ok(())
     }
 
     /// Send a message to the plugin with progress indication
     pub async fn send_message(&self, message: PluginMessage) -> Result<PluginResponse> {
-        debug!("Sending message to plugin: {}", self.plugin_path.display());
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(anyhow::anyhow!(
+                "Plugin not started: the time limit was reached"
+            ));
+        }
+        let (response_line, log) = self.exchange_logged(&message).await?;
+
+        let mut response: PluginResponse = {
+            let _serde = profile::span(Stage::Serde);
+            serde_json::from_str(response_line.trim()).context(format!(
+                "Failed to parse plugin response JSON: {response_line}{}",
+                log_hint(log.as_deref())
+            ))?
+        };
 
-        self.ensure_cache_dir().await?;
+        // Point plugin-reported errors at the transcript as well
+        if let PluginResponse::Error {
+            ref mut message, ..
+        } = response
+        {
+            message.push_str(&log_hint(log.as_deref()));
+        }
 
-        let input_filename = format!("plugin_input_{}.json", Uuid::new_v4());
-        let input_file_path = self.cache_dir.join(&input_filename);
+        Ok(response)
+    }
 
-        let message_json =
-            serde_json::to_string_pretty(&message).context("Failed to serialize plugin message")?;
+    /// Send a message and return the plugin's JSON response line without parsing it
+    pub async fn exchange(&self, message: &PluginMessage) -> Result<String> {
+        Ok(self.exchange_logged(message).await?.0)
+    }
 
-        fs::write(&input_file_path, &message_json)
-            .await
-            .context("Failed to write plugin input file")?;
+    /// Send a message, returning the response line and the transcript written for the
+    /// invocation, if any. Failed invocations and ones that wrote to stderr are logged.
+    async fn exchange_logged(&self, message: &PluginMessage) -> Result<(String, Option<PathBuf>)> {
+        debug!("Sending message to plugin: {}", self.plugin_path.display());
 
-        debug!("Wrote plugin input to: {}", input_file_path.display());
+        self.ensure_cache_dir().await?;
 
         // Determine appropriate timeout and progress message based on message type
-        let (global_timeout_secs, progress_interval_secs, operation_name) = match &message {
+        let (global_timeout_secs, progress_interval_secs, operation_name) = match message {
             PluginMessage::Analyze { .. } => (300, 30, "Analyzing code"),
+            PluginMessage::AnalyzeBatch { inputs } => {
+                (300 + 30 * inputs.len() as u64, 30, "Analyzing code batch")
+            }
             PluginMessage::Generate { .. } => (600, 30, "Generating documentation"), // LLM operations take longer
             PluginMessage::CanAnalyze { .. } => (30, 10, "Checking file compatibility"),
             PluginMessage::CanGenerate { .. } => (30, 10, "Checking generation capability"),
+            PluginMessage::Transform { .. } => (300, 30, "Transforming matrix"),
+            PluginMessage::Check { .. } => (300, 30, "Checking quality rules"),
             PluginMessage::GetInfo => (30, 10, "Getting plugin info"),
         };
 
@@ -107,21 +261,72 @@ impl PluginCommunicator {
                 .to_string_lossy()
         );
 
-        let result = self
-            .run_with_progress_indicator(
-                input_file_path.clone(),
-                Duration::from_secs(global_timeout_secs),
-                Duration::from_secs(progress_interval_secs),
-                operation_name,
-            )
-            .await;
+        let (mut global_timeout, progress_interval) = self.timeouts.unwrap_or((
+            Duration::from_secs(global_timeout_secs),
+            Duration::from_secs(progress_interval_secs),
+        ));
+        if let Some(file_timeout) = self.file_timeout {
+            match message {
+                PluginMessage::Analyze { .. } => global_timeout = file_timeout,
+                PluginMessage::AnalyzeBatch { inputs } => {
+                    global_timeout = file_timeout * inputs.len() as u32
+                }
+                _ => {}
+            }
+        }
+        let started_at = Utc::now();
+        let started = std::time::Instant::now();
+        let result = match &self.pool {
+            Some(pool) => self.exchange_pooled(pool, message, global_timeout).await,
+            None => {
+                let input_filename = format!("plugin_input_{}.json", Uuid::new_v4());
+                let input_file_path = self.scratch_dir().join(&input_filename);
+
+                let message_json = {
+                    let _serde = profile::span(Stage::Serde);
+                    serde_json::to_string_pretty(message)
+                        .context("Failed to serialize plugin message")?
+                };
+
+                write_atomic(&input_file_path, &message_json)
+                    .await
+                    .context("Failed to write plugin input file")?;
+
+                debug!("Wrote plugin input to: {}", input_file_path.display());
+
+                let result = self
+                    .run_with_progress_indicator(
+                        input_file_path.clone(),
+                        global_timeout,
+                        progress_interval,
+                        operation_name,
+                    )
+                    .await;
 
-        // Clean up input file
-        let _ = fs::remove_file(&input_file_path).await;
+                // Clean up input file
+                let _ = fs::remove_file(&input_file_path).await;
+                result
+            }
+        };
+
+        let mut transcript = Transcript {
+            plugin_path: &self.plugin_path,
+            message,
+            started: started_at,
+            duration: started.elapsed(),
+            outcome: "ok",
+            stdout: "",
+            stderr: "",
+        };
 
         let output = match result {
             Ok(output) => output,
-            Err(e) => return Err(e),
+            Err(e) => {
+                let outcome = e.to_string();
+                transcript.outcome = &outcome;
+                let log = self.write_log(&transcript, true).await;
+                return Err(anyhow::anyhow!("{e}{}", log_hint(log.as_deref())));
+            }
         };
 
         let stdout_str = String::from_utf8_lossy(&output.stdout);
@@ -132,42 +337,100 @@ impl PluginCommunicator {
             debug!("Plugin stderr: {stderr_str}");
         }
 
-        if !output.status.success() {
-            return Err(anyhow::anyhow!(
+        let response_line = stdout_str
+            .lines()
+            .find(|line| !line.trim().is_empty() && line.trim().starts_with('{'));
+        let failure = if !output.status.success() {
+            Some(format!(
                 "Plugin exited with non-zero status: {}. Stdout: {}. Stderr: {}",
                 output.status,
                 stdout_str.trim(),
                 stderr_str.trim()
-            ));
-        }
-
-        if stdout_str.trim().is_empty() {
-            return Err(anyhow::anyhow!(
+            ))
+        } else if stdout_str.trim().is_empty() {
+            Some(format!(
                 "Plugin produced no output. Stderr: {}",
                 stderr_str.trim()
-            ));
-        }
+            ))
+        } else if response_line.is_none() {
+            Some(format!(
+                "No valid JSON response found in plugin output. Stdout: {}",
+                stdout_str.trim()
+            ))
+        } else {
+            None
+        };
 
-        let response_line = stdout_str
-            .lines()
-            .find(|line| !line.trim().is_empty() && line.trim().starts_with('{'))
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "No valid JSON response found in plugin output. Stdout: {}",
-                    stdout_str.trim()
-                )
-            })?;
+        let status = output.status.to_string();
+        let reported_error = response_line.is_some_and(is_error_response);
+        transcript.stdout = &stdout_str;
+        transcript.stderr = &stderr_str;
+        transcript.outcome = match (&failure, reported_error) {
+            (Some(_), _) => &status,
+            (None, true) => "plugin reported an error",
+            (None, false) => "ok",
+        };
+        let log = self
+            .write_log(
+                &transcript,
+                failure.is_some() || reported_error || !stderr_str.trim().is_empty(),
+            )
+            .await;
+
+        if let Some(failure) = failure {
+            return Err(anyhow::anyhow!("{failure}{}", log_hint(log.as_deref())));
+        }
+        let response_line = response_line.unwrap_or_default();
 
         debug!("Plugin JSON response: {response_line}");
 
-        let response: PluginResponse = serde_json::from_str(response_line.trim()).context(
-            format!("Failed to parse plugin response JSON: {response_line}"),
-        )?;
+        Ok((response_line.to_string(), log))
+    }
 
-        Ok(response)
+    /// `message` answered by a pooled process, shaped like a one-shot process's output.
+    /// Its stderr goes to the debug log rather than the transcript.
+    async fn exchange_pooled(
+        &self,
+        pool: &PluginPool,
+        message: &PluginMessage,
+        timeout: Duration,
+    ) -> Result<std::process::Output> {
+        let timeout = match self.deadline {
+            Some(deadline) => timeout.min(deadline.saturating_duration_since(Instant::now())),
+            None => timeout,
+        };
+        let message_json = {
+            let _serde = profile::span(Stage::Serde);
+            serde_json::to_string(message).context("Failed to serialize plugin message")?
+        };
+        let response = pool
+            .exchange(&self.launch(), &message_json, timeout)
+            .await?;
+        Ok(std::process::Output {
+            status: Default::default(),
+            stdout: response.into_bytes(),
+            stderr: Vec::new(),
+        })
     }
 
-    /// Run plugin process with progress indication
+    /// Write the invocation's transcript when it's notable or every invocation is logged.
+    /// Logging is best effort and never fails the exchange.
+    async fn write_log(&self, transcript: &Transcript<'_>, notable: bool) -> Option<PathBuf> {
+        if !self.logs.enabled || !(notable || self.logs.all_invocations) {
+            return None;
+        }
+        match transcript.write(&self.cache_dir).await {
+            Ok(path) => Some(path),
+            Err(e) => {
+                warn!("Could not write plugin log: {e}");
+                None
+            }
+        }
+    }
+
+    /// Run plugin process with progress indication. The plugin may report progress to
+    /// the file named by `CSD_PROGRESS_FILE`; while it keeps advancing, the timeout is
+    /// measured from the latest change instead of from the start.
     async fn run_with_progress_indicator(
         &self,
         input_file_path: PathBuf,
@@ -175,76 +438,88 @@ impl PluginCommunicator {
         progress_interval: Duration,
         operation_name: &str,
     ) -> Result<std::process::Output> {
+        let progress_path = self
+            .scratch_dir()
+            .join(format!("plugin_progress_{}.json", Uuid::new_v4()));
+
         // Start the plugin process
         let process_future = async {
             let input_file =
                 std::fs::File::open(&input_file_path).context("Failed to open input file")?;
 
-            let child = Command::new(&self.python_executable)
-                .arg(&self.plugin_path)
+            let launch = self.launch();
+            let mut command = launch.command();
+            let spawn = profile::span(Stage::PluginSpawn);
+            let child = command
+                .env(PROGRESS_FILE_ENV, &progress_path)
                 .stdin(Stdio::from(input_file))
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
                 .spawn()
                 .context(format!(
-                    "Failed to spawn plugin process: {} {}",
-                    self.python_executable,
-                    self.plugin_path.display()
+                    "Failed to spawn plugin process: {}",
+                    launch.describe()
                 ))?;
+            drop(spawn);
 
+            let _exec = profile::span(Stage::PluginExec);
             child
                 .wait_with_output()
                 .await
                 .context("Failed to wait for plugin process")
         };
+        tokio::pin!(process_future);
 
-        // Progress indicator task
-        let progress_future = async {
-            let mut interval_timer = interval(progress_interval);
-            let mut elapsed_secs = 0;
-
-            loop {
-                interval_timer.tick().await;
-                elapsed_secs += progress_interval.as_secs();
-
-                // Show progress with different messages to keep it interesting
-                let progress_msg = match elapsed_secs {
-                    30 => format!(
-                        "⏳ {operation_name} is taking longer than expected, still working..."
-                    ),
-                    60 => format!("🔄 {operation_name} in progress (1 minute elapsed)..."),
-                    120 => format!("⚙️  {operation_name} continuing (2 minutes elapsed)..."),
-                    180 => format!("🎯 {operation_name} almost there (3 minutes elapsed)..."),
-                    240 => format!("⏱️  {operation_name} taking a while (4 minutes elapsed)..."),
-                    300 => format!("🚀 {operation_name} final stretch (5 minutes elapsed)..."),
-                    _ => {
-                        let minutes = elapsed_secs / 60;
-                        format!("⌛ {operation_name} still running ({minutes} minutes elapsed)...")
+        let started = Instant::now();
+        let extend = |from: Instant| match self.deadline {
+            Some(hard) => (from + global_timeout).min(hard),
+            None => from + global_timeout,
+        };
+        let mut deadline = extend(started);
+        let mut last_progress: Option<PluginProgress> = None;
+        let mut interval_timer = interval(progress_interval);
+        interval_timer.tick().await;
+        let mut elapsed_secs = 0;
+
+        // Race the process against the deadline, with progress updates
+        let result = loop {
+            tokio::select! {
+                result = &mut process_future => break Ok(result),
+                _ = sleep_until(deadline) => break Err(started.elapsed()),
+                _ = interval_timer.tick() => {
+                    elapsed_secs += progress_interval.as_secs();
+                    let progress = read_progress(&progress_path).await;
+                    match progress {
+                        Some(progress) if last_progress.as_ref() != Some(&progress) => {
+                            info!("📈 {operation_name}: {progress}");
+                            deadline = extend(Instant::now());
+                            last_progress = Some(progress);
+                        }
+                        _ => info!("{}", elapsed_message(operation_name, elapsed_secs)),
                     }
-                };
-
-                info!("{progress_msg}");
+                }
             }
         };
+        let _ = fs::remove_file(&progress_path).await;
 
-        // Race the process against the global timeout, with progress updates
-        match timeout(global_timeout, async {
-            tokio::select! {
-                result = process_future => result,
-                _ = progress_future => unreachable!("Progress task should never complete"),
-            }
-        })
-        .await
-        {
+        match result {
             Ok(result) => {
                 info!("✅ {operation_name} completed successfully");
                 result
             }
-            Err(_) => {
-                let timeout_minutes = global_timeout.as_secs() / 60;
-                warn!("⏰ {operation_name} timed out after {timeout_minutes} minutes");
+            Err(elapsed) => {
+                // Per-file budgets are usually seconds, the defaults minutes
+                let waited = match elapsed.as_secs() {
+                    secs if secs < 60 => format!("{secs} seconds"),
+                    secs => format!("{} minutes", secs / 60),
+                };
+                let stalled = match last_progress {
+                    Some(progress) => format!(" (no progress since {progress})"),
+                    None => String::new(),
+                };
+                warn!("⏰ {operation_name} timed out after {waited}{stalled}");
                 Err(anyhow::anyhow!(
-                    "{operation_name} timed out after {timeout_minutes} minutes. This may indicate the operation is still running in the background."
+                    "{operation_name} timed out after {waited}{stalled}. This may indicate the operation is still running in the background."
                 ))
             }
         }
@@ -263,6 +538,8 @@ impl PluginCommunicator {
                 supported_filenames,
                 supported_output_types,
                 supported_formats,
+                capabilities,
+                config_schema,
             } => Ok(PluginInfo {
                 name,
                 version,
@@ -271,6 +548,8 @@ impl PluginCommunicator {
                 supported_filenames,
                 supported_output_types,
                 supported_formats,
+                capabilities,
+                config_schema,
             }),
             PluginResponse::Error { message, details } => Err(anyhow::anyhow!(
                 "Plugin info request failed: {} {:?}",
@@ -329,15 +608,33 @@ impl PluginInterface for PluginCommunicator {
 /// Specialized communicator for input plugins (code analyzers)
 pub struct InputPluginCommunicator {
     base: PluginCommunicator,
+    content_path: bool,
+    batch: bool,
 }
 
 impl InputPluginCommunicator {
     pub fn new(plugin_path: PathBuf) -> Self {
         Self {
             base: PluginCommunicator::new(plugin_path),
+            content_path: false,
+            batch: false,
         }
     }
 
+    /// Send `analyze_batch` instead of one process per file; only for plugins
+    /// advertising the `analyze_batch` capability
+    pub fn with_batch(mut self, enabled: bool) -> Self {
+        self.batch = enabled;
+        self
+    }
+
+    /// Pass file content by path rather than inline; only for plugins advertising
+    /// the `content_path` capability
+    pub fn with_content_path(mut self, enabled: bool) -> Self {
+        self.content_path = enabled;
+        self
+    }
+
     pub fn with_cache_dir(mut self, cache_dir: PathBuf) -> Self {
         self.base = self.base.with_cache_dir(cache_dir);
         self
@@ -352,44 +649,134 @@ impl InputPluginCommunicator {
         self.base = self.base.with_python_auto_detect();
         self
     }
-}
 
-#[async_trait::async_trait]
-impl PluginInterface for InputPluginCommunicator {
-    async fn get_info(&self) -> Result<PluginInfo> {
-        self.base.get_info().await
+    pub fn with_runtime(mut self, runtime: PluginRuntime) -> Self {
+        self.base = self.base.with_runtime(runtime);
+        self
     }
 
-    async fn get_plugin_type(&self) -> Result<PluginType> {
-        self.base.get_plugin_type().await
+    pub fn with_node_executable(mut self, executable: String) -> Self {
+        self.base = self.base.with_node_executable(executable);
+        self
     }
-}
 
-#[async_trait::async_trait]
-impl InputPluginInterface for InputPluginCommunicator {
-    async fn can_analyze(&self, file_path: &Path, content_preview: &str) -> Result<bool> {
+    pub fn with_wasm_executable(mut self, executable: String) -> Self {
+        self.base = self.base.with_wasm_executable(executable);
+        self
+    }
+
+    pub fn with_logs(mut self, logs: PluginLogConfig) -> Self {
+        self.base = self.base.with_logs(logs);
+        self
+    }
+
+    pub fn with_run_dir(mut self, run_dir: PathBuf) -> Self {
+        self.base = self.base.with_run_dir(run_dir);
+        self
+    }
+
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.base = self.base.with_offline(offline);
+        self
+    }
+
+    pub fn with_file_timeout(mut self, timeout: Duration) -> Self {
+        self.base = self.base.with_file_timeout(timeout);
+        self
+    }
+
+    pub fn with_pool(mut self, pool: Arc<PluginPool>, transport: Transport) -> Self {
+        self.base = self.base.with_pool(pool, transport);
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: std::time::Instant) -> Self {
+        self.base = self.base.with_deadline(deadline);
+        self
+    }
+
+    /// Ask whether the plugin handles a file, along with its confidence from 0 to 1
+    pub async fn can_analyze_with_confidence(
+        &self,
+        file_path: &Path,
+        preview: &ContentPreview,
+    ) -> Result<(bool, f32)> {
         let message = PluginMessage::CanAnalyze {
             file_path: file_path.to_path_buf(),
-            content_preview: content_preview.chars().take(500).collect(),
+            content_preview: preview.head.clone(),
+            content_sample: preview.sample.clone(),
+            file_size: preview.size_bytes,
+            extension: preview.extension.clone(),
         };
 
         match self.base.send_message(message).await? {
             PluginResponse::CanAnalyze {
                 can_analyze,
-                confidence: _,
-            } => Ok(can_analyze),
+                confidence,
+            } => Ok((can_analyze, confidence)),
             PluginResponse::Error { message, details } => {
                 error!("Plugin error during can_analyze: {message} {details:?}");
-                Ok(false)
+                Ok((false, 0.0))
             }
             _ => {
                 warn!("Plugin returned unexpected response to can_analyze");
-                Ok(false)
+                Ok((false, 0.0))
             }
         }
     }
 
-    async fn analyze(&self, input: PluginInput) -> Result<crate::plugins::interface::PluginOutput> {
+    /// Load a plugin result the plugin wrote into the cache directory. Names that would
+    /// resolve outside it are rejected rather than read.
+    async fn read_cached_output(
+        &self,
+        cache_file: &str,
+    ) -> Result<crate::plugins::interface::PluginOutput> {
+        let cache_file_path = resolve_within(self.base.scratch_dir(), Path::new(cache_file))
+            .context("Plugin returned a cache file outside the cache directory")?;
+
+        debug!(
+            "Reading analysis result from cache file: {}",
+            cache_file_path.display()
+        );
+
+        let cache_content = fs::read_to_string(&cache_file_path).await.context(format!(
+            "Failed to read cache file: {}",
+            cache_file_path.display()
+        ))?;
+
+        let _serde = profile::span(Stage::Serde);
+        serde_json::from_str(&cache_content).context("Failed to parse cached analysis result")
+    }
+}
+
+#[async_trait::async_trait]
+impl PluginInterface for InputPluginCommunicator {
+    async fn get_info(&self) -> Result<PluginInfo> {
+        self.base.get_info().await
+    }
+
+    async fn get_plugin_type(&self) -> Result<PluginType> {
+        self.base.get_plugin_type().await
+    }
+}
+
+#[async_trait::async_trait]
+impl InputPluginInterface for InputPluginCommunicator {
+    async fn can_analyze(&self, file_path: &Path, content_preview: &str) -> Result<bool> {
+        let preview = ContentPreview::from_text(file_path, content_preview, 500);
+        let (can_analyze, _) = self
+            .can_analyze_with_confidence(file_path, &preview)
+            .await?;
+        Ok(can_analyze)
+    }
+
+    async fn analyze(
+        &self,
+        mut input: PluginInput,
+    ) -> Result<crate::plugins::interface::PluginOutput> {
+        if self.content_path {
+            input.use_content_path();
+        }
         let message = PluginMessage::Analyze { input };
 
         match self.base.send_message(message).await? {
@@ -397,24 +784,8 @@ impl InputPluginInterface for InputPluginCommunicator {
                 cache_file,
                 processing_time_ms,
             } => {
-                let cache_file_path = self.base.cache_dir.join(&cache_file);
-
-                debug!(
-                    "Reading analysis result from cache file: {}",
-                    cache_file_path.display()
-                );
-
-                let cache_content = fs::read_to_string(&cache_file_path).await.context(format!(
-                    "Failed to read cache file: {}",
-                    cache_file_path.display()
-                ))?;
-
-                let plugin_output: crate::plugins::interface::PluginOutput =
-                    serde_json::from_str(&cache_content)
-                        .context("Failed to parse cached analysis result")?;
-
+                let plugin_output = self.read_cached_output(&cache_file).await?;
                 debug!("Successfully loaded analysis result from cache, processing time: {processing_time_ms}ms");
-
                 Ok(plugin_output)
             }
             PluginResponse::Error { message, details } => Err(anyhow::anyhow!(
@@ -427,6 +798,58 @@ impl InputPluginInterface for InputPluginCommunicator {
             )),
         }
     }
+
+    async fn analyze_batch(
+        &self,
+        mut inputs: Vec<PluginInput>,
+    ) -> Result<Vec<Result<crate::plugins::interface::PluginOutput>>> {
+        if !self.batch || inputs.len() < 2 {
+            let mut outputs = Vec::with_capacity(inputs.len());
+            for input in inputs {
+                outputs.push(self.analyze(input).await);
+            }
+            return Ok(outputs);
+        }
+
+        if self.content_path {
+            inputs.iter_mut().for_each(PluginInput::use_content_path);
+        }
+        let expected = inputs.len();
+        let message = PluginMessage::AnalyzeBatch { inputs };
+
+        match self.base.send_message(message).await? {
+            PluginResponse::BatchSuccess { results } => {
+                if results.len() != expected {
+                    return Err(anyhow::anyhow!(
+                        "Plugin returned {} batch results for {} inputs",
+                        results.len(),
+                        expected
+                    ));
+                }
+
+                let mut outputs = Vec::with_capacity(results.len());
+                for result in results {
+                    outputs.push(match (result.cache_file, result.error) {
+                        (Some(cache_file), None) => self.read_cached_output(&cache_file).await,
+                        (_, error) => Err(anyhow::anyhow!(
+                            "Plugin analysis failed for {}: {}",
+                            result.relative_path.display(),
+                            error.unwrap_or_else(|| "no cache file returned".to_string())
+                        )),
+                    });
+                }
+                Ok(outputs)
+            }
+            PluginResponse::Error { message, details } => Err(anyhow::anyhow!(
+                "Plugin batch analysis failed: {} {:?}",
+                message,
+                details
+            )),
+            _ => Err(anyhow::anyhow!(
+                "Plugin returned unexpected response to analyze_batch"
+            )),
+        }
+    }
 }
 
 /// Specialized communicator for output plugins (documentation generators, etc.)
@@ -455,6 +878,41 @@ impl OutputPluginCommunicator {
         self.base = self.base.with_python_auto_detect();
         self
     }
+
+    pub fn with_runtime(mut self, runtime: PluginRuntime) -> Self {
+        self.base = self.base.with_runtime(runtime);
+        self
+    }
+
+    pub fn with_node_executable(mut self, executable: String) -> Self {
+        self.base = self.base.with_node_executable(executable);
+        self
+    }
+
+    pub fn with_wasm_executable(mut self, executable: String) -> Self {
+        self.base = self.base.with_wasm_executable(executable);
+        self
+    }
+
+    pub fn with_logs(mut self, logs: PluginLogConfig) -> Self {
+        self.base = self.base.with_logs(logs);
+        self
+    }
+
+    pub fn with_run_dir(mut self, run_dir: PathBuf) -> Self {
+        self.base = self.base.with_run_dir(run_dir);
+        self
+    }
+
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.base = self.base.with_offline(offline);
+        self
+    }
+
+    pub fn with_timeouts(mut self, timeout: Duration, progress_interval: Duration) -> Self {
+        self.base = self.base.with_timeouts(timeout, progress_interval);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -493,14 +951,26 @@ impl OutputPluginInterface for OutputPluginCommunicator {
     }
 
     async fn generate(&self, input: OutputPluginInput) -> Result<OutputPluginResult> {
+        let output_dir = input.output_dir.clone();
         let message = PluginMessage::Generate { input };
 
         match self.base.send_message(message).await? {
-            PluginResponse::OutputSuccess { result } => {
+            PluginResponse::OutputSuccess { mut result } => {
                 debug!(
                     "Output plugin generation successful: {} outputs",
                     result.outputs.len()
                 );
+                // Plugins write files themselves; don't trust paths outside the output dir
+                for output in &mut result.outputs {
+                    let path =
+                        resolve_inside(&output_dir, &output.output_path).with_context(|| {
+                            format!(
+                                "Output plugin reported a file outside the output directory: {}",
+                                output.output_path.display()
+                            )
+                        })?;
+                    verify_output(output, &path).await;
+                }
                 Ok(result)
             }
             PluginResponse::Error { message, details } => Err(anyhow::anyhow!(
@@ -525,13 +995,631 @@ impl OutputPluginInterface for OutputPluginCommunicator {
     }
 }
 
+/// Processes kept running for plugins that advertise `CAPABILITY_PERSISTENT`, so a scan
+/// doesn't start an interpreter per message. At most `max_processes` run per plugin;
+/// idle ones beyond `min_processes` are shut down after `idle_timeout_secs`, and ones
+/// that exited or failed a message are replaced.
+pub struct PluginPool {
+    config: PluginPoolConfig,
+    plugins: std::sync::Mutex<HashMap<PathBuf, PooledPlugin>>,
+}
+
+struct PooledPlugin {
+    idle: Vec<PooledProcess>,
+    // One permit per process that may run
+    slots: Arc<Semaphore>,
+    running: usize,
+    stats: PluginPoolStats,
+}
+
+/// How a pooled process exchanges messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Transport {
+    // One JSON line each way over stdin and stdout
+    #[default]
+    Stdio,
+    // Frames over a socket the plugin connects to, for ones advertising
+    // `CAPABILITY_SOCKET`; see `plugins::transport`
+    Socket,
+}
+
+/// What the pool starts for a plugin
+#[derive(Debug, Clone)]
+pub struct PluginLaunch {
+    pub runtime: PluginRuntime,
+    // Python, Node or WASI runner executable; unused for `PluginRuntime::Binary`
+    pub interpreter: String,
+    pub plugin_path: PathBuf,
+    pub offline: bool,
+    pub transport: Transport,
+}
+
+impl PluginLaunch {
+    /// The plugin under its interpreter, or on its own when it's a binary. WASI modules
+    /// see only the working directory and the `CSD_*` variables passed through to them.
+    pub fn command(&self) -> Command {
+        let mut command = match self.runtime {
+            PluginRuntime::Binary => Command::new(&self.plugin_path),
+            PluginRuntime::Python | PluginRuntime::Node => {
+                let mut command = Command::new(&self.interpreter);
+                command.arg(&self.plugin_path);
+                command
+            }
+            PluginRuntime::Wasm => {
+                let mut command = Command::new(&self.interpreter);
+                command.args(["run", "--dir=."]);
+                for name in [OFFLINE_ENV, PROGRESS_FILE_ENV, PERSISTENT_ENV] {
+                    command.arg(format!("--env={name}"));
+                }
+                command.arg(&self.plugin_path);
+                command
+            }
+        };
+        if self.offline {
+            command.env(OFFLINE_ENV, "1");
+        }
+        command
+    }
+
+    /// The command line, for errors
+    pub fn describe(&self) -> String {
+        match self.runtime {
+            PluginRuntime::Binary => self.plugin_path.display().to_string(),
+            PluginRuntime::Python | PluginRuntime::Node => {
+                format!("{} {}", self.interpreter, self.plugin_path.display())
+            }
+            PluginRuntime::Wasm => {
+                format!("{} run {}", self.interpreter, self.plugin_path.display())
+            }
+        }
+    }
+}
+
+struct PooledProcess {
+    // Killed when dropped
+    child: Child,
+    channel: Channel,
+    idle_since: Instant,
+}
+
+enum Channel {
+    Stdio {
+        stdin: ChildStdin,
+        stdout: BufReader<ChildStdout>,
+    },
+    Socket(Box<dyn Duplex>),
+}
+
+impl PooledProcess {
+    async fn spawn(launch: &PluginLaunch, tcp_sockets: bool) -> Result<Self> {
+        let mut command = launch.command();
+        let listener = match launch.transport {
+            Transport::Stdio => None,
+            Transport::Socket => {
+                let listener = PluginListener::bind(tcp_sockets).await?;
+                command
+                    .env(SOCKET_ENV, listener.address()?)
+                    .env(SOCKET_TOKEN_ENV, &listener.token)
+                    .stdin(Stdio::null());
+                Some(listener)
+            }
+        };
+        if listener.is_none() {
+            command.stdin(Stdio::piped());
+        }
+        let spawn = profile::span(Stage::PluginSpawn);
+        let mut child = command
+            .env(PERSISTENT_ENV, "1")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context(format!(
+                "Failed to spawn plugin process: {}",
+                launch.describe()
+            ))?;
+
+        // Drained so a chatty plugin can't block on a full pipe
+        let name = launch.plugin_path.display().to_string();
+        log_lines(&name, child.stderr.take());
+        let channel = match listener {
+            None => Channel::Stdio {
+                stdin: child.stdin.take().context("Plugin stdin was not piped")?,
+                stdout: BufReader::new(child.stdout.take().context("Plugin stdout was not piped")?),
+            },
+            Some(listener) => {
+                log_lines(&name, child.stdout.take());
+                let stream = tokio::select! {
+                    accepted = tokio::time::timeout(CONNECT_TIMEOUT, listener.accept()) => {
+                        accepted.map_err(|_| anyhow::anyhow!(
+                            "Plugin didn't connect to its socket within {} seconds",
+                            CONNECT_TIMEOUT.as_secs()
+                        ))??
+                    }
+                    status = child.wait() => {
+                        return Err(anyhow::anyhow!(
+                            "Plugin exited before connecting to its socket: {}",
+                            status?
+                        ));
+                    }
+                };
+                Channel::Socket(stream)
+            }
+        };
+        drop(spawn);
+        Ok(Self {
+            child,
+            channel,
+            idle_since: Instant::now(),
+        })
+    }
+
+    fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Send `message` and wait for its response. Over stdio that's the next line that
+    /// looks like JSON, so stray output is skipped.
+    async fn exchange(&mut self, message: &str) -> Result<String> {
+        let _exec = profile::span(Stage::PluginExec);
+        match &mut self.channel {
+            Channel::Stdio { stdin, stdout } => {
+                stdin.write_all(message.as_bytes()).await?;
+                stdin.write_all(b"\n").await?;
+                stdin.flush().await?;
+                let mut line = String::new();
+                loop {
+                    line.clear();
+                    if stdout.read_line(&mut line).await? == 0 {
+                        return Err(anyhow::anyhow!("Plugin process exited without responding"));
+                    }
+                    if line.trim_start().starts_with('{') {
+                        return Ok(line.trim().to_string());
+                    }
+                }
+            }
+            Channel::Socket(stream) => {
+                write_frame(stream, message.as_bytes()).await?;
+                let frame = read_frame(stream).await?.ok_or_else(|| {
+                    anyhow::anyhow!("Plugin closed its socket without responding")
+                })?;
+                String::from_utf8(frame).context("Plugin response frame is not UTF-8")
+            }
+        }
+    }
+
+    /// Close stdin or the socket so the plugin exits on its own, killing it if it
+    /// doesn't soon
+    async fn shut_down(mut self) {
+        drop(self.channel);
+        let _ = tokio::time::timeout(Duration::from_secs(2), self.child.wait()).await;
+    }
+}
+
+fn log_lines(name: &str, output: Option<impl AsyncRead + Unpin + Send + 'static>) {
+    let Some(output) = output else {
+        return;
+    };
+    let name = name.to_string();
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(output).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            debug!("{name}: {line}");
+        }
+    });
+}
+
+impl PluginPool {
+    pub fn new(config: PluginPoolConfig) -> Self {
+        Self {
+            config,
+            plugins: Default::default(),
+        }
+    }
+
+    /// Send `message` (JSON on a single line) to a process running the plugin, starting
+    /// one if none is idle and the plugin has fewer than `max_processes`. A process that
+    /// fails or takes longer than `timeout` is killed, not reused.
+    pub async fn exchange(
+        &self,
+        launch: &PluginLaunch,
+        message: &str,
+        timeout: Duration,
+    ) -> Result<String> {
+        let plugin_path = launch.plugin_path.as_path();
+        let retired = self.reap_idle();
+        for process in retired {
+            process.shut_down().await;
+        }
+
+        let slots = self.with_plugin(plugin_path, |plugin| plugin.slots.clone());
+        let _slot = slots.acquire_owned().await?;
+        let mut process = match self.checkout(plugin_path) {
+            Some(process) => process,
+            None => {
+                let process = PooledProcess::spawn(launch, self.config.tcp_sockets).await?;
+                self.with_plugin(plugin_path, |plugin| {
+                    plugin.stats.spawned += 1;
+                    plugin.running += 1;
+                    plugin.stats.peak = plugin.stats.peak.max(plugin.running);
+                });
+                process
+            }
+        };
+
+        let result = match tokio::time::timeout(timeout, process.exchange(message)).await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow::anyhow!(
+                "Plugin timed out after {} seconds",
+                timeout.as_secs()
+            )),
+        };
+        self.with_plugin(plugin_path, |plugin| match result {
+            Ok(_) => {
+                process.idle_since = Instant::now();
+                plugin.idle.push(process);
+            }
+            Err(_) => {
+                plugin.running -= 1;
+                plugin.stats.unhealthy += 1;
+            }
+        });
+        result
+    }
+
+    /// How each plugin's processes were used, by plugin path
+    pub fn stats(&self) -> HashMap<PathBuf, PluginPoolStats> {
+        self.plugins
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(path, plugin)| (path.clone(), plugin.stats.clone()))
+            .collect()
+    }
+
+    /// Stop every idle process; ones still answering a message stop when dropped
+    pub async fn shutdown(&self) {
+        let idle: Vec<PooledProcess> = {
+            let mut plugins = self.plugins.lock().unwrap();
+            plugins
+                .values_mut()
+                .flat_map(|plugin| {
+                    plugin.running -= plugin.idle.len();
+                    std::mem::take(&mut plugin.idle)
+                })
+                .collect()
+        };
+        for process in idle {
+            process.shut_down().await;
+        }
+    }
+
+    fn with_plugin<T>(&self, plugin_path: &Path, f: impl FnOnce(&mut PooledPlugin) -> T) -> T {
+        let mut plugins = self.plugins.lock().unwrap();
+        let plugin = plugins
+            .entry(plugin_path.to_path_buf())
+            .or_insert_with(|| PooledPlugin {
+                idle: Vec::new(),
+                slots: Arc::new(Semaphore::new(self.config.max_processes())),
+                running: 0,
+                stats: PluginPoolStats::default(),
+            });
+        f(plugin)
+    }
+
+    // The most recently used idle process that's still running
+    fn checkout(&self, plugin_path: &Path) -> Option<PooledProcess> {
+        self.with_plugin(plugin_path, |plugin| {
+            while let Some(mut process) = plugin.idle.pop() {
+                if process.is_alive() {
+                    plugin.stats.reused += 1;
+                    return Some(process);
+                }
+                plugin.running -= 1;
+                plugin.stats.unhealthy += 1;
+            }
+            None
+        })
+    }
+
+    // Take the processes idle past the timeout, keeping `min_processes` per plugin
+    fn reap_idle(&self) -> Vec<PooledProcess> {
+        let timeout = self.config.idle_timeout();
+        let mut retired = Vec::new();
+        for plugin in self.plugins.lock().unwrap().values_mut() {
+            // Oldest first; `checkout` takes from the end
+            plugin.idle.sort_by_key(|process| process.idle_since);
+            while plugin.running > self.config.min_processes
+                && plugin
+                    .idle
+                    .first()
+                    .is_some_and(|process| process.idle_since.elapsed() >= timeout)
+            {
+                retired.push(plugin.idle.remove(0));
+                plugin.running -= 1;
+                plugin.stats.retired_idle += 1;
+            }
+        }
+        retired
+    }
+}
+
+/// Suffix pointing an error message at the invocation's transcript
+fn log_hint(log: Option<&Path>) -> String {
+    log.map(|path| format!(" (log: {})", path.display()))
+        .unwrap_or_default()
+}
+
+fn is_error_response(line: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(line.trim())
+        .is_ok_and(|value| value.get("status").and_then(|s| s.as_str()) == Some("error"))
+}
+
+/// Latest progress a plugin wrote, if any. Partial or malformed writes are ignored.
+async fn read_progress(path: &Path) -> Option<PluginProgress> {
+    let content = fs::read_to_string(path).await.ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Generic status line for plugins that don't report progress
+fn elapsed_message(operation_name: &str, elapsed_secs: u64) -> String {
+    // Show progress with different messages to keep it interesting
+    match elapsed_secs {
+        30 => format!("⏳ {operation_name} is taking longer than expected, still working..."),
+        60 => format!("🔄 {operation_name} in progress (1 minute elapsed)..."),
+        120 => format!("⚙️  {operation_name} continuing (2 minutes elapsed)..."),
+        180 => format!("🎯 {operation_name} almost there (3 minutes elapsed)..."),
+        240 => format!("⏱️  {operation_name} taking a while (4 minutes elapsed)..."),
+        300 => format!("🚀 {operation_name} final stretch (5 minutes elapsed)..."),
+        _ => {
+            let minutes = elapsed_secs / 60;
+            format!("⌛ {operation_name} still running ({minutes} minutes elapsed)...")
+        }
+    }
+}
+
+/// Check a reported output against the file on disk, recording mismatches in
+/// `output.issues`. A blank checksum (or the SDK's "error" placeholder) is filled in
+/// rather than flagged.
+pub(crate) async fn verify_output(output: &mut GeneratedOutput, path: &Path) {
+    use sha2::{Digest, Sha256};
+
+    let content = match fs::read(path).await {
+        Ok(content) => content,
+        Err(e) => {
+            output.issues.push(format!("file not readable: {e}"));
+            warn!(
+                "Output plugin reported {} but it could not be read: {e}",
+                output.output_path.display()
+            );
+            return;
+        }
+    };
+
+    let actual_size = content.len() as u64;
+    if output.size_bytes != actual_size {
+        output.issues.push(format!(
+            "size mismatch: reported {} bytes, found {actual_size}",
+            output.size_bytes
+        ));
+    }
+
+    let actual_checksum = format!("{:x}", Sha256::digest(&content));
+    let reported = output.checksum.trim();
+    let reported = reported.strip_prefix("sha256:").unwrap_or(reported);
+    if reported.is_empty() || reported == "error" {
+        output.checksum = actual_checksum;
+    } else if !reported.eq_ignore_ascii_case(&actual_checksum) {
+        output.issues.push(format!(
+            "checksum mismatch: reported {reported}, found {actual_checksum}"
+        ));
+    }
+
+    for issue in &output.issues {
+        warn!("Output {}: {issue}", output.output_path.display());
+    }
+}
+
+/// Specialized communicator for transform plugins (matrix post-processors)
+pub struct TransformPluginCommunicator {
+    base: PluginCommunicator,
+}
+
+impl TransformPluginCommunicator {
+    pub fn new(plugin_path: PathBuf) -> Self {
+        Self {
+            base: PluginCommunicator::new(plugin_path),
+        }
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: PathBuf) -> Self {
+        self.base = self.base.with_cache_dir(cache_dir);
+        self
+    }
+
+    pub fn with_python_executable(mut self, executable: String) -> Self {
+        self.base = self.base.with_python_executable(executable);
+        self
+    }
+
+    pub fn with_python_auto_detect(mut self) -> Self {
+        self.base = self.base.with_python_auto_detect();
+        self
+    }
+
+    pub fn with_runtime(mut self, runtime: PluginRuntime) -> Self {
+        self.base = self.base.with_runtime(runtime);
+        self
+    }
+
+    pub fn with_node_executable(mut self, executable: String) -> Self {
+        self.base = self.base.with_node_executable(executable);
+        self
+    }
+
+    pub fn with_wasm_executable(mut self, executable: String) -> Self {
+        self.base = self.base.with_wasm_executable(executable);
+        self
+    }
+
+    pub fn with_logs(mut self, logs: PluginLogConfig) -> Self {
+        self.base = self.base.with_logs(logs);
+        self
+    }
+
+    pub fn with_run_dir(mut self, run_dir: PathBuf) -> Self {
+        self.base = self.base.with_run_dir(run_dir);
+        self
+    }
+
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.base = self.base.with_offline(offline);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl PluginInterface for TransformPluginCommunicator {
+    async fn get_info(&self) -> Result<PluginInfo> {
+        self.base.get_info().await
+    }
+
+    async fn get_plugin_type(&self) -> Result<PluginType> {
+        self.base.get_plugin_type().await
+    }
+}
+
+#[async_trait::async_trait]
+impl TransformPluginInterface for TransformPluginCommunicator {
+    async fn transform(&self, input: TransformPluginInput) -> Result<MatrixPatch> {
+        let message = PluginMessage::Transform { input };
+
+        match self.base.send_message(message).await? {
+            PluginResponse::TransformSuccess {
+                patch,
+                processing_time_ms,
+            } => {
+                debug!("Transform plugin returned its patch in {processing_time_ms}ms");
+                Ok(patch)
+            }
+            PluginResponse::Error { message, details } => Err(anyhow::anyhow!(
+                "Plugin transform failed: {} {:?}",
+                message,
+                details
+            )),
+            _ => Err(anyhow::anyhow!(
+                "Plugin returned unexpected response to transform"
+            )),
+        }
+    }
+}
+
+/// Specialized communicator for rule plugins (custom quality checks)
+pub struct RulePluginCommunicator {
+    base: PluginCommunicator,
+}
+
+impl RulePluginCommunicator {
+    pub fn new(plugin_path: PathBuf) -> Self {
+        Self {
+            base: PluginCommunicator::new(plugin_path),
+        }
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: PathBuf) -> Self {
+        self.base = self.base.with_cache_dir(cache_dir);
+        self
+    }
+
+    pub fn with_python_executable(mut self, executable: String) -> Self {
+        self.base = self.base.with_python_executable(executable);
+        self
+    }
+
+    pub fn with_python_auto_detect(mut self) -> Self {
+        self.base = self.base.with_python_auto_detect();
+        self
+    }
+
+    pub fn with_runtime(mut self, runtime: PluginRuntime) -> Self {
+        self.base = self.base.with_runtime(runtime);
+        self
+    }
+
+    pub fn with_node_executable(mut self, executable: String) -> Self {
+        self.base = self.base.with_node_executable(executable);
+        self
+    }
+
+    pub fn with_wasm_executable(mut self, executable: String) -> Self {
+        self.base = self.base.with_wasm_executable(executable);
+        self
+    }
+
+    pub fn with_logs(mut self, logs: PluginLogConfig) -> Self {
+        self.base = self.base.with_logs(logs);
+        self
+    }
+
+    pub fn with_run_dir(mut self, run_dir: PathBuf) -> Self {
+        self.base = self.base.with_run_dir(run_dir);
+        self
+    }
+
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.base = self.base.with_offline(offline);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl PluginInterface for RulePluginCommunicator {
+    async fn get_info(&self) -> Result<PluginInfo> {
+        self.base.get_info().await
+    }
+
+    async fn get_plugin_type(&self) -> Result<PluginType> {
+        self.base.get_plugin_type().await
+    }
+}
+
+#[async_trait::async_trait]
+impl RulePluginInterface for RulePluginCommunicator {
+    async fn check(&self, input: RulePluginInput) -> Result<Vec<RuleFinding>> {
+        let message = PluginMessage::Check { input };
+
+        match self.base.send_message(message).await? {
+            PluginResponse::CheckSuccess {
+                findings,
+                processing_time_ms,
+            } => {
+                debug!(
+                    "Rule plugin returned {} findings in {processing_time_ms}ms",
+                    findings.len()
+                );
+                Ok(findings)
+            }
+            PluginResponse::Error { message, details } => Err(anyhow::anyhow!(
+                "Plugin check failed: {} {:?}",
+                message,
+                details
+            )),
+            _ => Err(anyhow::anyhow!(
+                "Plugin returned unexpected response to check"
+            )),
+        }
+    }
+}
+
 // Legacy compatibility - maintain the original PluginCommunicator for existing code
 impl PluginCommunicator {
     /// Legacy method for backward compatibility
     pub async fn can_analyze(&self, file_path: &Path, content_preview: &str) -> Result<bool> {
         let input_comm = InputPluginCommunicator::new(self.plugin_path.clone())
             .with_cache_dir(self.cache_dir.clone())
-            .with_python_executable(self.python_executable.clone());
+            .with_python_executable(self.python_executable.clone())
+            .with_runtime(self.runtime)
+            .with_node_executable(self.node_executable.clone());
 
         input_comm.can_analyze(file_path, content_preview).await
     }
@@ -543,7 +1631,9 @@ impl PluginCommunicator {
     ) -> Result<crate::plugins::interface::PluginOutput> {
         let input_comm = InputPluginCommunicator::new(self.plugin_path.clone())
             .with_cache_dir(self.cache_dir.clone())
-            .with_python_executable(self.python_executable.clone());
+            .with_python_executable(self.python_executable.clone())
+            .with_runtime(self.runtime)
+            .with_node_executable(self.node_executable.clone());
 
         input_comm.analyze(input).await
     }
@@ -0,0 +1,260 @@
+// src/plugins/config_schema.rs - Checks a plugin's `config` against the JSON Schema it
+// declares in its `get_info` response (`config_schema`)
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// One way the config breaks the schema
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaViolation {
+    // Dotted path into the config, e.g. `rules[2].severity`; empty for the config itself
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "config: {}", self.message)
+        } else {
+            write!(f, "config.{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Check `config` against `schema`. A plugin without a `config` is checked as `{}`, so
+/// `required` properties are still reported.
+///
+/// Supports the keywords plugin configs need: `type`, `enum`, `const`, `properties`,
+/// `required`, `additionalProperties`, `items`, `minimum`, `maximum`, `minLength`,
+/// `maxLength`, `minItems`, `maxItems`, `anyOf` and `oneOf`. Others, `$ref` and
+/// `pattern` among them, are ignored rather than guessed at.
+pub fn validate_config(schema: &Value, config: Option<&Value>) -> Vec<SchemaViolation> {
+    let empty = Value::Object(Default::default());
+    let mut violations = Vec::new();
+    check(schema, config.unwrap_or(&empty), "", &mut violations);
+    violations
+}
+
+/// `validate_config` for a config as it appears in `.csdrc.yaml`
+pub fn validate_yaml_config(
+    schema: &Value,
+    config: Option<&serde_yaml::Value>,
+) -> Vec<SchemaViolation> {
+    match config.map(serde_json::to_value).transpose() {
+        Ok(config) => validate_config(schema, config.as_ref()),
+        Err(e) => vec![SchemaViolation {
+            path: String::new(),
+            message: format!("can't be represented as JSON: {e}"),
+        }],
+    }
+}
+
+fn check(schema: &Value, value: &Value, path: &str, out: &mut Vec<SchemaViolation>) {
+    // `true`, `{}` and anything else that isn't an object accept every value
+    let Some(schema) = schema.as_object() else {
+        if schema == &Value::Bool(false) {
+            violate(out, path, "is not allowed");
+        }
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            violate(
+                out,
+                path,
+                format!("expected {}, got {}", types.join(" or "), type_name(value)),
+            );
+            // The remaining keywords would only restate the mismatch
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            let choices: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            violate(
+                out,
+                path,
+                format!("{value} is not one of {}", choices.join(", ")),
+            );
+        }
+    }
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            violate(out, path, format!("must be {constant}"));
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(name) {
+                        violate(out, &join(path, name), "is required");
+                    }
+                }
+            }
+            for (name, item) in map {
+                let item_path = join(path, name);
+                match properties.and_then(|p| p.get(name)) {
+                    Some(property) => check(property, item, &item_path, out),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            let known: Vec<&str> = properties
+                                .map(|p| p.keys().map(String::as_str).collect())
+                                .unwrap_or_default();
+                            let hint = if known.is_empty() {
+                                String::new()
+                            } else {
+                                format!(" (expected one of {})", known.join(", "))
+                            };
+                            violate(out, &item_path, format!("is not a known setting{hint}"));
+                        }
+                        Some(additional) => check(additional, item, &item_path, out),
+                        None => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            bounds(
+                schema,
+                "minItems",
+                "maxItems",
+                items.len(),
+                "items",
+                path,
+                out,
+            );
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    check(item_schema, item, &format!("{path}[{index}]"), out);
+                }
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count();
+            bounds(
+                schema,
+                "minLength",
+                "maxLength",
+                length,
+                "characters",
+                path,
+                out,
+            );
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+                if number < minimum {
+                    violate(
+                        out,
+                        path,
+                        format!("{number} is below the minimum of {minimum}"),
+                    );
+                }
+            }
+            if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+                if number > maximum {
+                    violate(
+                        out,
+                        path,
+                        format!("{number} is above the maximum of {maximum}"),
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+
+    for (keyword, exactly_one) in [("anyOf", false), ("oneOf", true)] {
+        let Some(options) = schema.get(keyword).and_then(Value::as_array) else {
+            continue;
+        };
+        let matching = options
+            .iter()
+            .filter(|option| {
+                let mut scratch = Vec::new();
+                check(option, value, path, &mut scratch);
+                scratch.is_empty()
+            })
+            .count();
+        if matching == 0 {
+            violate(out, path, "matches none of the allowed forms");
+        } else if exactly_one && matching > 1 {
+            violate(out, path, "matches more than one of the allowed forms");
+        }
+    }
+}
+
+fn bounds(
+    schema: &serde_json::Map<String, Value>,
+    min_keyword: &str,
+    max_keyword: &str,
+    count: usize,
+    unit: &str,
+    path: &str,
+    out: &mut Vec<SchemaViolation>,
+) {
+    if let Some(min) = schema.get(min_keyword).and_then(Value::as_u64) {
+        if (count as u64) < min {
+            violate(out, path, format!("has {count} {unit}, fewer than {min}"));
+        }
+    }
+    if let Some(max) = schema.get(max_keyword).and_then(Value::as_u64) {
+        if count as u64 > max {
+            violate(out, path, format!("has {count} {unit}, more than {max}"));
+        }
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        // 2.0 is an integer to JSON Schema, and YAML configs may well spell it that way
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        // Unknown type names don't reject anything
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{path}.{name}")
+    }
+}
+
+fn violate(out: &mut Vec<SchemaViolation>, path: &str, message: impl Into<String>) {
+    out.push(SchemaViolation {
+        path: path.to_string(),
+        message: message.into(),
+    });
+}
@@ -0,0 +1,910 @@
+// src/plugins/conformance.rs - Drive a plugin through the protocol and check its responses
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::core::matrix::{
+    CodeElement, ElementType, Export, FileNode, ProjectMatrix, Relationship, RelationshipType,
+    TokenInfo,
+};
+use crate::core::transform::apply_patch;
+use crate::plugins::communication::{verify_output, PluginCommunicator};
+use crate::plugins::interface::{
+    BatchResult, OutputPluginInput, PluginInfo, PluginInput, PluginMessage, PluginOutput,
+    PluginResponse, PluginType, RulePluginInput, TransformPluginInput, CAPABILITY_ANALYZE_BATCH,
+};
+use crate::utils::config::PluginRuntime;
+use crate::utils::file_utils::{resolve_inside, resolve_within};
+
+/// get_info, can_analyze and can_generate run once per file or plugin, so they should
+/// answer quickly
+const QUICK_MESSAGE_LIMIT: Duration = Duration::from_secs(5);
+/// Slower than this per file makes a full scan impractical
+const ANALYZE_LIMIT: Duration = Duration::from_secs(30);
+
+/// Canned content for fixture files generated from the advertised extensions
+const SAMPLE_SOURCE: &str = "import helpers\n\nfunction greet(name)\n  return name\nend\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConformanceCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issues: Vec<String>,
+    // Wall-clock time of the exchange, including process startup
+    pub elapsed_ms: u64,
+}
+
+/// Outcome of every exchange with the plugin. A failed check means the core would
+/// reject or misread the plugin's response; a warning means it works but breaks a
+/// convention (timing, line ranges, unclaimed fixtures).
+#[derive(Debug, Clone, Serialize)]
+pub struct ConformanceReport {
+    pub plugin_path: PathBuf,
+    pub plugin_name: Option<String>,
+    pub plugin_type: Option<PluginType>,
+    pub checks: Vec<ConformanceCheck>,
+}
+
+impl ConformanceReport {
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.count(CheckStatus::Fail) == 0
+    }
+
+    pub fn print_summary(&self) {
+        let kind = self
+            .plugin_type
+            .as_ref()
+            .map_or("unknown type", PluginType::as_str);
+        println!(
+            "🔌 Plugin conformance: {} ({kind}, {})",
+            self.plugin_name.as_deref().unwrap_or("?"),
+            self.plugin_path.display()
+        );
+        for check in &self.checks {
+            let icon = match check.status {
+                CheckStatus::Pass => "✅",
+                CheckStatus::Warn => "⚠️ ",
+                CheckStatus::Fail => "❌",
+            };
+            println!("   {icon} {} ({} ms)", check.name, check.elapsed_ms);
+            for issue in &check.issues {
+                println!("        {issue}");
+            }
+        }
+        println!(
+            "\n   {} passed, {} warnings, {} failed",
+            self.count(CheckStatus::Pass),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail)
+        );
+    }
+}
+
+/// Issues found in one exchange; errors fail the check, warnings don't
+#[derive(Default)]
+struct Findings {
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+impl Findings {
+    fn error(&mut self, issue: impl Into<String>) {
+        self.errors.push(issue.into());
+    }
+
+    fn warn(&mut self, issue: impl Into<String>) {
+        self.warnings.push(issue.into());
+    }
+
+    fn into_check(self, name: String, elapsed: Duration) -> ConformanceCheck {
+        let status = if !self.errors.is_empty() {
+            CheckStatus::Fail
+        } else if !self.warnings.is_empty() {
+            CheckStatus::Warn
+        } else {
+            CheckStatus::Pass
+        };
+        ConformanceCheck {
+            name,
+            status,
+            issues: self.errors.into_iter().chain(self.warnings).collect(),
+            elapsed_ms: elapsed.as_millis() as u64,
+        }
+    }
+}
+
+/// Runs a plugin through the messages the core sends it, using fixture files for input
+/// plugins and a small canned matrix for output, transform and rule plugins. Everything the
+/// plugin writes goes under `work_dir`, which is removed afterwards.
+pub struct ConformanceRunner {
+    plugin_path: PathBuf,
+    work_dir: PathBuf,
+    runtime: PluginRuntime,
+    python_executable: Option<String>,
+    node_executable: Option<String>,
+    wasm_executable: Option<String>,
+    expected_type: Option<PluginType>,
+    fixtures: Vec<PathBuf>,
+    matrix_path: Option<PathBuf>,
+    timeout: Duration,
+}
+
+impl ConformanceRunner {
+    pub fn new(plugin_path: PathBuf, work_dir: PathBuf) -> Self {
+        Self {
+            runtime: PluginRuntime::for_path(&plugin_path),
+            plugin_path,
+            // Plugins run in the caller's directory; absolute paths keep outputs in place
+            work_dir: std::path::absolute(&work_dir).unwrap_or(work_dir),
+            python_executable: None,
+            node_executable: None,
+            wasm_executable: None,
+            expected_type: None,
+            fixtures: Vec::new(),
+            matrix_path: None,
+            timeout: Duration::from_secs(300),
+        }
+    }
+
+    pub fn with_python_executable(mut self, executable: Option<String>) -> Self {
+        self.python_executable = executable;
+        self
+    }
+
+    /// Run the plugin with this rather than what its extension implies
+    pub fn with_runtime(mut self, runtime: PluginRuntime) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    pub fn with_node_executable(mut self, executable: Option<String>) -> Self {
+        self.node_executable = executable;
+        self
+    }
+
+    pub fn with_wasm_executable(mut self, executable: Option<String>) -> Self {
+        self.wasm_executable = executable;
+        self
+    }
+
+    /// Fail when the plugin reports a different type than it is registered as
+    pub fn with_expected_type(mut self, plugin_type: Option<PluginType>) -> Self {
+        self.expected_type = plugin_type;
+        self
+    }
+
+    /// Files to analyze instead of samples generated from the advertised extensions
+    pub fn with_fixtures(mut self, fixtures: Vec<PathBuf>) -> Self {
+        self.fixtures = fixtures;
+        self
+    }
+
+    /// Matrix for output, transform and rule plugins instead of the canned one
+    pub fn with_matrix(mut self, matrix_path: Option<PathBuf>) -> Self {
+        self.matrix_path = matrix_path;
+        self
+    }
+
+    /// Hard limit for any single exchange
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub async fn run(&self) -> Result<ConformanceReport> {
+        let result = self.run_checks().await;
+        let _ = tokio::fs::remove_dir_all(&self.work_dir).await;
+        result
+    }
+
+    async fn run_checks(&self) -> Result<ConformanceReport> {
+        if !self.plugin_path.exists() {
+            return Err(anyhow::anyhow!(
+                "Plugin file not found: {}",
+                self.plugin_path.display()
+            ));
+        }
+        for dir in ["project", "cache", "output"] {
+            tokio::fs::create_dir_all(self.work_dir.join(dir))
+                .await
+                .with_context(|| format!("Failed to create {}", self.work_dir.display()))?;
+        }
+
+        let mut report = ConformanceReport {
+            plugin_path: self.plugin_path.clone(),
+            plugin_name: None,
+            plugin_type: None,
+            checks: Vec::new(),
+        };
+
+        let Some(info) = self.check_info(&mut report).await else {
+            return Ok(report);
+        };
+        report.plugin_name = Some(info.name.clone());
+        report.plugin_type = Some(info.plugin_type.clone());
+
+        match info.plugin_type {
+            PluginType::Input => self.check_input(&info, &mut report).await?,
+            PluginType::Output => self.check_output(&info, &mut report).await?,
+            PluginType::Transform => self.check_transform(&mut report).await?,
+            PluginType::Rule => self.check_rule(&mut report).await?,
+        }
+        Ok(report)
+    }
+
+    fn communicator(&self) -> PluginCommunicator {
+        let mut communicator = PluginCommunicator::new(self.plugin_path.clone())
+            .with_cache_dir(self.cache_dir())
+            .with_timeouts(self.timeout, Duration::from_secs(10))
+            .with_runtime(self.runtime);
+        if let Some(ref node_exe) = self.node_executable {
+            communicator = communicator.with_node_executable(node_exe.clone());
+        }
+        if let Some(ref wasm_exe) = self.wasm_executable {
+            communicator = communicator.with_wasm_executable(wasm_exe.clone());
+        }
+        match self.python_executable {
+            Some(ref python_exe) => communicator.with_python_executable(python_exe.clone()),
+            None => communicator.with_python_auto_detect(),
+        }
+    }
+
+    fn cache_dir(&self) -> PathBuf {
+        self.work_dir.join("cache")
+    }
+
+    fn project_root(&self) -> PathBuf {
+        self.work_dir.join("project")
+    }
+
+    /// Send one message and check the response line against the protocol types. Returns
+    /// the parsed response when it matched one of `expected` statuses.
+    async fn exchange(
+        &self,
+        message: &PluginMessage,
+        expected: &[&str],
+        findings: &mut Findings,
+    ) -> (Option<PluginResponse>, Duration) {
+        let started = Instant::now();
+        let line = self.communicator().exchange(message).await;
+        let elapsed = started.elapsed();
+
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                findings.error(format!("exchange failed: {e:#}"));
+                return (None, elapsed);
+            }
+        };
+        let value: serde_json::Value = match serde_json::from_str(line.trim()) {
+            Ok(value) => value,
+            Err(e) => {
+                findings.error(format!("response is not valid JSON: {e}"));
+                return (None, elapsed);
+            }
+        };
+        let Some(status) = value
+            .get("status")
+            .and_then(|s| s.as_str())
+            .map(str::to_string)
+        else {
+            findings.error("response has no \"status\" field");
+            return (None, elapsed);
+        };
+        if status == "error" {
+            let message = value.get("message").and_then(|m| m.as_str());
+            findings.error(format!(
+                "plugin returned an error: {}",
+                message.unwrap_or("(no message)")
+            ));
+            return (None, elapsed);
+        }
+        if !expected.contains(&status.as_str()) {
+            findings.error(format!(
+                "expected status {}, got \"{status}\"",
+                expected.join(" or ")
+            ));
+            return (None, elapsed);
+        }
+        match serde_json::from_value::<PluginResponse>(value) {
+            Ok(response) => (Some(response), elapsed),
+            Err(e) => {
+                findings.error(format!(
+                    "\"{status}\" response doesn't match the protocol: {e}"
+                ));
+                (None, elapsed)
+            }
+        }
+    }
+
+    async fn check_info(&self, report: &mut ConformanceReport) -> Option<PluginInfo> {
+        let mut findings = Findings::default();
+        let (response, elapsed) = self
+            .exchange(&PluginMessage::GetInfo, &["info"], &mut findings)
+            .await;
+        check_quick(&mut findings, elapsed);
+
+        let info = match response {
+            Some(PluginResponse::Info {
+                name,
+                version,
+                plugin_type,
+                supported_extensions,
+                supported_filenames,
+                supported_output_types,
+                supported_formats,
+                capabilities,
+                config_schema,
+            }) => Some(PluginInfo {
+                name,
+                version,
+                plugin_type,
+                supported_extensions,
+                supported_filenames,
+                supported_output_types,
+                supported_formats,
+                capabilities,
+                config_schema,
+            }),
+            _ => None,
+        };
+        if let Some(ref info) = info {
+            if info.name.trim().is_empty() {
+                findings.error("name is empty");
+            }
+            if info.version.trim().is_empty() {
+                findings.warn("version is empty");
+            }
+            if let Some(ref expected) = self.expected_type {
+                if &info.plugin_type != expected {
+                    findings.error(format!(
+                        "reports plugin_type {:?} but is registered as {expected:?}",
+                        info.plugin_type
+                    ));
+                }
+            }
+            if let Some(ext) = info
+                .supported_extensions
+                .iter()
+                .find(|ext| !ext.starts_with('.'))
+            {
+                findings.warn(format!("extension \"{ext}\" should start with a dot"));
+            }
+        }
+
+        report
+            .checks
+            .push(findings.into_check("get_info".to_string(), elapsed));
+        info
+    }
+
+    async fn check_input(&self, info: &PluginInfo, report: &mut ConformanceReport) -> Result<()> {
+        let fixtures = self.input_fixtures(info).await?;
+        if fixtures.is_empty() {
+            let mut findings = Findings::default();
+            findings.error("no fixtures: the plugin advertises no extensions or filenames");
+            report
+                .checks
+                .push(findings.into_check("fixtures".to_string(), Duration::ZERO));
+            return Ok(());
+        }
+
+        let mut inputs = Vec::new();
+        for fixture in &fixtures {
+            let relative = fixture
+                .strip_prefix(self.project_root())
+                .unwrap_or(fixture)
+                .to_path_buf();
+            let content = tokio::fs::read_to_string(fixture)
+                .await
+                .with_context(|| format!("Failed to read fixture {}", fixture.display()))?;
+
+            // can_analyze
+            let mut findings = Findings::default();
+            let message = PluginMessage::CanAnalyze {
+                file_path: fixture.clone(),
+                content_preview: content.chars().take(500).collect(),
+                content_sample: None,
+                file_size: Some(content.len() as u64),
+                extension: fixture
+                    .extension()
+                    .map(|ext| format!(".{}", ext.to_string_lossy())),
+            };
+            let (response, elapsed) = self
+                .exchange(&message, &["can_analyze"], &mut findings)
+                .await;
+            check_quick(&mut findings, elapsed);
+            if let Some(PluginResponse::CanAnalyze {
+                can_analyze,
+                confidence,
+            }) = response
+            {
+                if !(0.0..=1.0).contains(&confidence) {
+                    findings.error(format!("confidence {confidence} is outside 0..=1"));
+                }
+                if !can_analyze {
+                    findings.warn("the plugin doesn't claim this fixture");
+                }
+            }
+            report
+                .checks
+                .push(findings.into_check(format!("can_analyze {}", relative.display()), elapsed));
+
+            // analyze
+            let input = PluginInput {
+                file_path: fixture.clone(),
+                relative_path: relative.clone(),
+                content: content.clone(),
+                project_root: self.project_root(),
+                cache_dir: self.cache_dir().to_string_lossy().to_string(),
+                plugin_config: None,
+                content_path: None,
+                truncated: false,
+                project: None,
+            };
+            let mut findings = Findings::default();
+            let (response, elapsed) = self
+                .exchange(
+                    &PluginMessage::Analyze {
+                        input: input.clone(),
+                    },
+                    &["success"],
+                    &mut findings,
+                )
+                .await;
+            if elapsed > ANALYZE_LIMIT {
+                findings.warn(format!(
+                    "took {:.1}s; scans call analyze once per file",
+                    elapsed.as_secs_f64()
+                ));
+            }
+            if let Some(PluginResponse::Success {
+                cache_file,
+                processing_time_ms,
+            }) = response
+            {
+                check_processing_time(&mut findings, processing_time_ms, elapsed);
+                self.check_cache_file(&cache_file, &input, &mut findings)
+                    .await;
+            }
+            report
+                .checks
+                .push(findings.into_check(format!("analyze {}", relative.display()), elapsed));
+            inputs.push(input);
+        }
+
+        if info.supports(CAPABILITY_ANALYZE_BATCH) {
+            self.check_batch(inputs, report).await;
+        }
+        Ok(())
+    }
+
+    /// Configured fixtures, or one sample per advertised extension and filename
+    async fn input_fixtures(&self, info: &PluginInfo) -> Result<Vec<PathBuf>> {
+        let project_root = self.project_root();
+        if !self.fixtures.is_empty() {
+            let mut fixtures = Vec::new();
+            for fixture in &self.fixtures {
+                let name = fixture
+                    .file_name()
+                    .with_context(|| format!("Fixture is not a file: {}", fixture.display()))?;
+                let target = project_root.join(name);
+                tokio::fs::copy(fixture, &target)
+                    .await
+                    .with_context(|| format!("Failed to read fixture {}", fixture.display()))?;
+                fixtures.push(target);
+            }
+            return Ok(fixtures);
+        }
+
+        let names = info
+            .supported_extensions
+            .iter()
+            .map(|ext| format!("sample.{}", ext.trim_start_matches('.')))
+            .chain(info.supported_filenames.iter().cloned());
+        let mut fixtures = Vec::new();
+        for name in names {
+            let target = project_root.join("src").join(name);
+            if let Some(parent) = target.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&target, SAMPLE_SOURCE).await?;
+            fixtures.push(target);
+        }
+        Ok(fixtures)
+    }
+
+    /// The cache file must stay inside the cache directory and hold a PluginOutput that
+    /// describes the analyzed file
+    async fn check_cache_file(
+        &self,
+        cache_file: &str,
+        input: &PluginInput,
+        findings: &mut Findings,
+    ) {
+        let path = match resolve_within(&self.cache_dir(), Path::new(cache_file)) {
+            Ok(path) => path,
+            Err(_) => {
+                findings.error(format!(
+                    "cache file {cache_file} is outside the cache directory"
+                ));
+                return;
+            }
+        };
+        let content = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => content,
+            Err(e) => {
+                findings.error(format!("cache file {cache_file} not readable: {e}"));
+                return;
+            }
+        };
+        let output: PluginOutput = match serde_json::from_str(&content) {
+            Ok(output) => output,
+            Err(e) => {
+                findings.error(format!(
+                    "cache file doesn't match the PluginOutput schema: {e}"
+                ));
+                return;
+            }
+        };
+
+        if output.file_path != input.file_path {
+            findings.warn(format!(
+                "file_path is {}, expected {}",
+                output.file_path.display(),
+                input.file_path.display()
+            ));
+        }
+        let line_count = input.content.lines().count().max(1) as u32;
+        for element in &output.elements {
+            if element.name.trim().is_empty() {
+                findings.error(format!("{} element has no name", element.element_type));
+            }
+            if element.line_start == 0
+                || element.line_end < element.line_start
+                || element.line_end > line_count
+            {
+                findings.warn(format!(
+                    "{} has line range {}-{} in a {line_count}-line file",
+                    element.name, element.line_start, element.line_end
+                ));
+            }
+        }
+        if output.token_info.is_none() {
+            findings.warn("no token_info; token budgets will treat the file as empty");
+        }
+    }
+
+    async fn check_batch(&self, inputs: Vec<PluginInput>, report: &mut ConformanceReport) {
+        let mut findings = Findings::default();
+        let expected: Vec<PluginInput> = inputs.clone();
+        let (response, elapsed) = self
+            .exchange(
+                &PluginMessage::AnalyzeBatch { inputs },
+                &["batch_success"],
+                &mut findings,
+            )
+            .await;
+        if let Some(PluginResponse::BatchSuccess { results }) = response {
+            if results.len() != expected.len() {
+                findings.error(format!(
+                    "returned {} results for {} inputs",
+                    results.len(),
+                    expected.len()
+                ));
+            }
+            for (result, input) in results.iter().zip(&expected) {
+                self.check_batch_result(result, input, &mut findings).await;
+            }
+        }
+        report
+            .checks
+            .push(findings.into_check("analyze_batch".to_string(), elapsed));
+    }
+
+    async fn check_batch_result(
+        &self,
+        result: &BatchResult,
+        input: &PluginInput,
+        findings: &mut Findings,
+    ) {
+        if result.relative_path != input.relative_path {
+            findings.error(format!(
+                "result for {} is out of order (expected {})",
+                result.relative_path.display(),
+                input.relative_path.display()
+            ));
+        }
+        match (&result.cache_file, &result.error) {
+            (Some(cache_file), None) => self.check_cache_file(cache_file, input, findings).await,
+            (_, Some(error)) => findings.error(format!(
+                "{} failed: {error}",
+                result.relative_path.display()
+            )),
+            (None, None) => findings.error(format!(
+                "{} has neither cache_file nor error",
+                result.relative_path.display()
+            )),
+        }
+    }
+
+    async fn check_output(&self, info: &PluginInfo, report: &mut ConformanceReport) -> Result<()> {
+        let output_types = info.supported_output_types.clone().unwrap_or_default();
+        let formats = info.supported_formats.clone().unwrap_or_default();
+        if output_types.is_empty() || formats.is_empty() {
+            let mut findings = Findings::default();
+            findings.warn("get_info lists no supported_output_types or supported_formats");
+            report
+                .checks
+                .push(findings.into_check("capabilities".to_string(), Duration::ZERO));
+        }
+
+        for output_type in &output_types {
+            for format in &formats {
+                let mut findings = Findings::default();
+                let message = PluginMessage::CanGenerate {
+                    output_type: output_type.clone(),
+                    format: format.clone(),
+                };
+                let (response, elapsed) = self
+                    .exchange(&message, &["can_generate"], &mut findings)
+                    .await;
+                check_quick(&mut findings, elapsed);
+                if let Some(PluginResponse::CanGenerate {
+                    can_generate,
+                    confidence,
+                }) = response
+                {
+                    if !(0.0..=1.0).contains(&confidence) {
+                        findings.error(format!("confidence {confidence} is outside 0..=1"));
+                    }
+                    if !can_generate {
+                        findings.warn("advertised but declined");
+                    }
+                }
+                report.checks.push(
+                    findings.into_check(format!("can_generate {output_type}/{format}"), elapsed),
+                );
+            }
+        }
+
+        let output_dir = self.work_dir.join("output");
+        let input = OutputPluginInput {
+            matrix_path: self.matrix().await?.0,
+            project_root: self.project_root(),
+            output_dir: output_dir.clone(),
+            cache_dir: self.cache_dir().to_string_lossy().to_string(),
+            plugin_config: None,
+            format_options: serde_json::json!({
+                "format": formats.first().map_or("markdown", String::as_str),
+                "output_type": output_types.first().map_or("documentation", String::as_str),
+            }),
+            section_cache: None,
+            scope: None,
+        };
+        let mut findings = Findings::default();
+        let (response, elapsed) = self
+            .exchange(
+                &PluginMessage::Generate { input },
+                &["output_success"],
+                &mut findings,
+            )
+            .await;
+        if let Some(PluginResponse::OutputSuccess { mut result }) = response {
+            check_processing_time(&mut findings, result.processing_time_ms, elapsed);
+            if result.outputs.is_empty() {
+                findings.warn("generated no outputs");
+            }
+            for output in &mut result.outputs {
+                match resolve_inside(&output_dir, &output.output_path) {
+                    Ok(path) => {
+                        verify_output(output, &path).await;
+                        for issue in &output.issues {
+                            findings.error(format!("{}: {issue}", output.output_path.display()));
+                        }
+                    }
+                    Err(_) => findings.error(format!(
+                        "{} is outside the output directory",
+                        output.output_path.display()
+                    )),
+                }
+            }
+        }
+        report
+            .checks
+            .push(findings.into_check("generate".to_string(), elapsed));
+        Ok(())
+    }
+
+    async fn check_transform(&self, report: &mut ConformanceReport) -> Result<()> {
+        let (matrix_path, matrix) = self.matrix().await?;
+        let input = TransformPluginInput {
+            matrix_path,
+            project_root: self.project_root(),
+            cache_dir: self.cache_dir().to_string_lossy().to_string(),
+            plugin_config: None,
+        };
+
+        let mut findings = Findings::default();
+        let (response, elapsed) = self
+            .exchange(
+                &PluginMessage::Transform { input },
+                &["transform_success"],
+                &mut findings,
+            )
+            .await;
+        if let Some(PluginResponse::TransformSuccess {
+            patch,
+            processing_time_ms,
+        }) = response
+        {
+            check_processing_time(&mut findings, processing_time_ms, elapsed);
+            let summary = apply_patch(&mut matrix.clone(), patch, "conformance");
+            if summary.skipped > 0 {
+                findings.warn(format!(
+                    "{} updates name files or elements that aren't in the matrix",
+                    summary.skipped
+                ));
+            }
+        }
+        report
+            .checks
+            .push(findings.into_check("transform".to_string(), elapsed));
+        Ok(())
+    }
+
+    async fn check_rule(&self, report: &mut ConformanceReport) -> Result<()> {
+        let (matrix_path, matrix) = self.matrix().await?;
+        let input = RulePluginInput {
+            matrix_path,
+            project_root: self.project_root(),
+            cache_dir: self.cache_dir().to_string_lossy().to_string(),
+            plugin_config: None,
+        };
+
+        let mut findings = Findings::default();
+        let (response, elapsed) = self
+            .exchange(
+                &PluginMessage::Check { input },
+                &["check_success"],
+                &mut findings,
+            )
+            .await;
+        if let Some(PluginResponse::CheckSuccess {
+            findings: reported,
+            processing_time_ms,
+        }) = response
+        {
+            check_processing_time(&mut findings, processing_time_ms, elapsed);
+            let unknown = reported
+                .iter()
+                .filter(|finding| !matrix.files.contains_key(&finding.file))
+                .count();
+            if unknown > 0 {
+                findings.warn(format!(
+                    "{unknown} findings name files that aren't in the matrix"
+                ));
+            }
+            if reported
+                .iter()
+                .any(|finding| finding.rule.trim().is_empty())
+            {
+                findings.error("findings must name their rule");
+            }
+        }
+        report
+            .checks
+            .push(findings.into_check("check".to_string(), elapsed));
+        Ok(())
+    }
+
+    /// The configured matrix, or the canned one written into the work directory
+    async fn matrix(&self) -> Result<(PathBuf, ProjectMatrix)> {
+        if let Some(ref path) = self.matrix_path {
+            let matrix = ProjectMatrix::load(path)
+                .await
+                .with_context(|| format!("Failed to load matrix {}", path.display()))?;
+            return Ok((path.clone(), matrix));
+        }
+
+        let path = self.work_dir.join("matrix.json");
+        let matrix = sample_matrix(&self.project_root());
+        matrix.save(&path).await?;
+        Ok((path, matrix))
+    }
+}
+
+fn check_quick(findings: &mut Findings, elapsed: Duration) {
+    if elapsed > QUICK_MESSAGE_LIMIT {
+        findings.warn(format!(
+            "took {:.1}s (limit {}s)",
+            elapsed.as_secs_f64(),
+            QUICK_MESSAGE_LIMIT.as_secs()
+        ));
+    }
+}
+
+/// processing_time_ms is measured inside the plugin, so it can't exceed the exchange
+fn check_processing_time(findings: &mut Findings, processing_time_ms: u64, elapsed: Duration) {
+    if processing_time_ms > elapsed.as_millis() as u64 {
+        findings.warn(format!(
+            "reports processing_time_ms {processing_time_ms} but the exchange took {} ms",
+            elapsed.as_millis()
+        ));
+    }
+}
+
+/// Two files with a function each and an import between them
+fn sample_matrix(project_root: &Path) -> ProjectMatrix {
+    let file = |relative: &str, function: &str, tokens: u64| FileNode {
+        path: project_root.join(relative),
+        relative_path: PathBuf::from(relative),
+        hash: String::new(),
+        size_bytes: tokens * 4,
+        plugin: "python".into(),
+        language: Some("python".into()),
+        is_text: true,
+        elements: vec![CodeElement {
+            element_type: ElementType::Function,
+            name: function.to_string(),
+            qualified_name: None,
+            parent: None,
+            signature: Some(format!("def {function}()")),
+            line_start: 1,
+            line_end: 5,
+            summary: None,
+            complexity_score: Some(2),
+            calls: Vec::new(),
+            metadata: serde_json::Value::Null,
+            tokens: tokens / 2,
+            element_id: String::new(),
+            coverage: None,
+        }],
+        imports: Vec::new(),
+        exports: vec![Export::named(function)],
+        file_summary: None,
+        token_info: TokenInfo {
+            total_tokens: tokens,
+            code_tokens: tokens,
+            documentation_tokens: 0,
+            comment_tokens: 0,
+        },
+        metadata: serde_json::Value::Null,
+        truncated: false,
+        category: None,
+        coverage: None,
+    };
+
+    let mut matrix = ProjectMatrix::new(project_root.to_path_buf());
+    matrix.add_file(file("src/main.py", "main", 120));
+    matrix.add_file(file("src/util.py", "helper", 80));
+    matrix.add_relationship(Relationship {
+        from_file: PathBuf::from("src/main.py"),
+        to_file: PathBuf::from("src/util.py"),
+        relationship_type: RelationshipType::Import,
+        details: "import util".to_string(),
+        line_number: Some(1),
+        strength: 1.0,
+        source_plugin: None,
+        detected_at: None,
+        from_element: None,
+        to_element: None,
+        runtime_calls: None,
+    });
+    matrix.finalize();
+    matrix
+}
@@ -2,12 +2,45 @@
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
+use crate::core::matrix::ProjectMatrix;
+use crate::core::project::ProjectContext;
+use crate::plugins::config_schema::{validate_yaml_config, SchemaViolation};
+use crate::utils::config::PluginSource;
+
 /// Plugin type enumeration to distinguish between input and output plugins
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum PluginType {
-    Input,  // Code analyzers (existing functionality)
-    Output, // Documentation generators, quality scanners, etc.
+    Input,     // Code analyzers (existing functionality)
+    Output,    // Documentation generators, quality scanners, etc.
+    Transform, // Matrix post-processors run between scanning and output
+    Rule,      // Custom quality checks reporting findings on the matrix
+}
+
+impl PluginType {
+    /// Category name used in config sections and plugin directories
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PluginType::Input => "input",
+            PluginType::Output => "output",
+            PluginType::Transform => "transform",
+            PluginType::Rule => "rule",
+        }
+    }
+}
+
+/// The script of a built-in or local plugin of type `kind`. Plugins fetched from GitHub
+/// or Git have none until the plugin manager installs them.
+pub fn plugin_script_path(kind: PluginType, source: &PluginSource) -> Option<PathBuf> {
+    match source {
+        PluginSource::Builtin { name, plugin_type } => Some(PathBuf::from(format!(
+            "plugins/{}/{plugin_type}/{name}.py",
+            kind.as_str()
+        ))),
+        PluginSource::Local { path } => Some(PathBuf::from(path)),
+        // TODO: Handle other plugin sources (GitHub, Git)
+        _ => None,
+    }
 }
 
 /// Standard output format that all input plugins must produce
@@ -17,7 +50,8 @@ pub struct PluginOutput {
     pub file_hash: String,
     pub elements: Vec<CodeElement>,
     pub imports: Vec<Import>,
-    pub exports: Vec<String>,
+    // Bare names or export records
+    pub exports: Vec<crate::core::matrix::Export>,
     pub relationships: Vec<Relationship>,
     pub external_dependencies: Vec<ExternalDependency>,
     pub file_summary: Option<String>,
@@ -40,6 +74,10 @@ pub struct OutputPluginResult {
     pub outputs: Vec<GeneratedOutput>,
     pub processing_time_ms: u64,
     pub metadata: serde_json::Value,
+    // Matrix changes worked out while generating, e.g. summaries the plugin wrote, for
+    // the core to validate and keep in the matrix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<MatrixPatch>,
 }
 
 /// Individual output file or result generated by an output plugin
@@ -50,6 +88,17 @@ pub struct GeneratedOutput {
     pub size_bytes: u64,
     pub checksum: String,
     pub metadata: serde_json::Value,
+    // Mismatches between what the plugin reported and what is on disk, filled in by
+    // `OutputPluginCommunicator::generate`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issues: Vec<String>,
+}
+
+impl GeneratedOutput {
+    /// Whether the reported file was found on disk with the reported size and checksum
+    pub fn is_verified(&self) -> bool {
+        self.issues.is_empty()
+    }
 }
 
 /// Code element structure for plugin communication (uses strings, not enums)
@@ -57,6 +106,11 @@ pub struct GeneratedOutput {
 pub struct CodeElement {
     pub element_type: String, // "function", "class", "method", etc.
     pub name: String,
+    // e.g. "Config::load" for a method, and "Config" as its parent
+    #[serde(default)]
+    pub qualified_name: Option<String>,
+    #[serde(default)]
+    pub parent: Option<String>,
     pub signature: Option<String>,
     pub line_start: u32,
     pub line_end: u32,
@@ -88,6 +142,10 @@ pub struct Relationship {
     pub details: String,
     pub line_number: Option<u32>,
     pub strength: f32,
+    #[serde(default)]
+    pub from_element: Option<String>,
+    #[serde(default)]
+    pub to_element: Option<String>,
 }
 
 /// External dependency structure for plugin communication
@@ -100,15 +158,61 @@ pub struct ExternalDependency {
     pub source_file: String,
 }
 
+/// Capability: the plugin reads file content from `PluginInput.content_path` itself
+pub const CAPABILITY_CONTENT_PATH: &str = "content_path";
+
+/// Capability: the plugin accepts `analyze_batch` with many files per invocation
+pub const CAPABILITY_ANALYZE_BATCH: &str = "analyze_batch";
+
+/// Capability: with `PERSISTENT_ENV` set the plugin keeps running, answering one message
+/// per stdin line with one response line, so the core can pool its processes
+pub const CAPABILITY_PERSISTENT: &str = "persistent";
+
+/// Capability: with `SOCKET_ENV` set the plugin connects there and exchanges
+/// length-prefixed JSON frames instead of stdin/stdout lines, leaving stdout for its
+/// own logging; see `plugins::transport`. Implies `CAPABILITY_PERSISTENT`.
+pub const CAPABILITY_SOCKET: &str = "socket";
+
+/// Capability: the plugin wants `PluginInput.project`, the digest of the project's
+/// package manifests
+pub const CAPABILITY_PROJECT_CONTEXT: &str = "project_context";
+
 /// Input sent to input plugins for code analysis
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginInput {
     pub file_path: PathBuf,
     pub relative_path: PathBuf,
+    #[serde(default)]
     pub content: String,
     pub project_root: PathBuf,
+    // The scan's run directory under `<cache>/runs`, removed once the scan finishes
     pub cache_dir: String,
     pub plugin_config: Option<serde_json::Value>,
+
+    // When set, `content` is empty and the plugin reads the file from here instead;
+    // only sent to plugins advertising CAPABILITY_CONTENT_PATH
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_path: Option<PathBuf>,
+
+    // `content` was cut down to `scanning.max_file_tokens`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+
+    // Only sent to plugins advertising CAPABILITY_PROJECT_CONTEXT
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<ProjectContext>,
+}
+
+impl PluginInput {
+    /// Replace the embedded content with a reference to the file on disk. Truncated
+    /// content stays inline since the file on disk is the full version.
+    pub fn use_content_path(&mut self) {
+        if self.truncated {
+            return;
+        }
+        self.content_path = Some(self.file_path.clone());
+        self.content = String::new();
+    }
 }
 
 /// Input sent to output plugins for generating documentation, reports, etc.
@@ -120,6 +224,309 @@ pub struct OutputPluginInput {
     pub cache_dir: String,
     pub plugin_config: Option<serde_json::Value>,
     pub format_options: serde_json::Value, // Plugin-specific formatting options
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section_cache: Option<SectionCache>,
+    // Set when the matrix at `matrix_path` was cut down to part of the project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<OutputScope>,
+}
+
+/// Part of the project an output plugin is run for, e.g. one package of a monorepo.
+/// A file is in scope when it is at or under one of `paths`, matches one of `globs`
+/// or carries one of `tags`; the matrix handed to the plugin also keeps `depth`
+/// levels of those files' dependencies so cross-package links still resolve.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputScope {
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    #[serde(default)]
+    pub globs: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default = "default_scope_depth")]
+    pub depth: usize,
+}
+
+fn default_scope_depth() -> usize {
+    1
+}
+
+impl Default for OutputScope {
+    fn default() -> Self {
+        Self {
+            paths: Vec::new(),
+            globs: Vec::new(),
+            tags: Vec::new(),
+            depth: default_scope_depth(),
+        }
+    }
+}
+
+impl OutputScope {
+    /// Patterns with glob metacharacters become globs, the rest directory prefixes
+    pub fn from_patterns<I: IntoIterator<Item = String>>(patterns: I) -> Self {
+        let (globs, paths): (Vec<String>, Vec<String>) = patterns
+            .into_iter()
+            .partition(|pattern| pattern.contains(['*', '?', '[']));
+        Self {
+            paths: paths.into_iter().map(PathBuf::from).collect(),
+            globs,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.globs.is_empty() && self.tags.is_empty()
+    }
+
+    /// Matrix keys of the files in scope, sorted
+    pub fn select(&self, matrix: &ProjectMatrix) -> anyhow::Result<Vec<PathBuf>> {
+        // "./packages/api/" names the same files as "packages/api"
+        let paths: Vec<PathBuf> = self
+            .paths
+            .iter()
+            .map(|path| {
+                path.components()
+                    .filter(|c| !matches!(c, std::path::Component::CurDir))
+                    .collect()
+            })
+            .collect();
+        let globs = self
+            .globs
+            .iter()
+            .map(|glob| {
+                glob::Pattern::new(glob)
+                    .map_err(|e| anyhow::anyhow!("Invalid scope glob '{glob}': {e}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut files: Vec<PathBuf> = matrix
+            .files
+            .iter()
+            .filter(|(path, node)| {
+                paths
+                    .iter()
+                    .any(|p| path.starts_with(p) || node.relative_path.starts_with(p))
+                    || globs.iter().any(|g| {
+                        g.matches_path(&node.relative_path) || g.matches_path(path.as_path())
+                    })
+                    || node.metadata["tags"].as_array().is_some_and(|tags| {
+                        tags.iter()
+                            .filter_map(|tag| tag.as_str())
+                            .any(|tag| self.tags.iter().any(|t| t == tag))
+                    })
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// Where an output plugin keeps finished sections so an interrupted run can resume.
+/// The plugin writes `<dir>/<section>.md` and then an empty `<dir>/<section>.done`
+/// marker; only sections with a marker count as complete. Section names are reduced
+/// to letters, digits, `-` and `_` to form file names.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionCache {
+    pub dir: PathBuf,
+    #[serde(default)]
+    pub resume: bool,
+    // Sections that had a marker when the run started; empty unless resuming
+    #[serde(default)]
+    pub completed: Vec<String>,
+}
+
+impl SectionCache {
+    pub const MARKER_EXTENSION: &'static str = "done";
+
+    /// Sections with a completion marker in `dir`, sorted
+    pub fn completed_sections(dir: &Path) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut completed: Vec<String> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == Self::MARKER_EXTENSION)
+            })
+            .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+            .collect();
+        completed.sort();
+        completed
+    }
+}
+
+/// What `can_analyze` sees of a file: its head (without a UTF-8 BOM), a sample from
+/// the middle of larger files, and its size and extension
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContentPreview {
+    pub head: String,
+    pub sample: Option<String>,
+    pub size_bytes: Option<u64>,
+    pub extension: Option<String>,
+}
+
+impl ContentPreview {
+    const UTF8_BOM: &'static [u8] = b"\xEF\xBB\xBF";
+
+    /// Most bytes read for the head or the sample, whatever `scanning.preview_bytes` asks
+    pub const MAX_BYTES: usize = 64 * 1024;
+
+    /// Read up to `max_bytes` (at most `MAX_BYTES`) from the start of the file and, when
+    /// the file is longer, as many from its middle
+    pub async fn read(path: &Path, max_bytes: usize) -> std::io::Result<Self> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let max_bytes = max_bytes.min(Self::MAX_BYTES);
+        let mut file = tokio::fs::File::open(path).await?;
+        let size = file.metadata().await?.len();
+        let max = max_bytes as u64;
+
+        let mut head = Vec::new();
+        (&mut file)
+            .take(max + Self::UTF8_BOM.len() as u64)
+            .read_to_end(&mut head)
+            .await?;
+        let head = head.strip_prefix(Self::UTF8_BOM).unwrap_or(&head);
+        let head = &head[..head.len().min(max_bytes)];
+
+        let sample = if size > max {
+            let start = (size / 2).saturating_sub(max / 2).max(max);
+            file.seek(std::io::SeekFrom::Start(start)).await?;
+            let mut chunk = Vec::new();
+            (&mut file).take(max).read_to_end(&mut chunk).await?;
+            Some(decode_chunk(&chunk, true)).filter(|sample| !sample.is_empty())
+        } else {
+            None
+        };
+
+        Ok(Self {
+            head: decode_chunk(head, false),
+            sample,
+            size_bytes: Some(size),
+            extension: extension_of(path),
+        })
+    }
+
+    /// Preview built from text already in memory, truncated to `max_chars`
+    pub fn from_text(path: &Path, text: &str, max_chars: usize) -> Self {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        Self {
+            head: text.chars().take(max_chars).collect(),
+            sample: None,
+            size_bytes: None,
+            extension: extension_of(path),
+        }
+    }
+}
+
+fn extension_of(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+}
+
+// Chunks are cut at byte offsets, so drop characters split at the edges
+fn decode_chunk(bytes: &[u8], trim_start: bool) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_end_matches('\u{fffd}');
+    if trim_start {
+        text.trim_start_matches('\u{fffd}').to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Input sent to transform plugins, which read the matrix and return a patch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformPluginInput {
+    pub matrix_path: PathBuf,
+    pub project_root: PathBuf,
+    pub cache_dir: String,
+    pub plugin_config: Option<serde_json::Value>,
+}
+
+/// Modifications a transform plugin, or an output plugin alongside its outputs, asks
+/// the core to apply to the matrix
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MatrixPatch {
+    #[serde(default)]
+    pub relationships: Vec<Relationship>,
+    #[serde(default)]
+    pub tags: Vec<TagUpdate>,
+    #[serde(default)]
+    pub summaries: Vec<SummaryUpdate>,
+}
+
+impl MatrixPatch {
+    pub fn is_empty(&self) -> bool {
+        self.relationships.is_empty() && self.tags.is_empty() && self.summaries.is_empty()
+    }
+}
+
+/// Tags to add to a file, by project-relative path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagUpdate {
+    pub file: String,
+    pub tags: Vec<String>,
+}
+
+/// Replacement summary for a file, or for one of its elements when `element` is set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryUpdate {
+    pub file: String,
+    #[serde(default)]
+    pub element: Option<String>,
+    pub summary: String,
+}
+
+/// Input sent to rule plugins: the matrix to check, which is only the plugin's scope
+/// when it has one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulePluginInput {
+    pub matrix_path: PathBuf,
+    pub project_root: PathBuf,
+    pub cache_dir: String,
+    pub plugin_config: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingSeverity {
+    Info,
+    #[default]
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for FindingSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FindingSeverity::Info => "info",
+            FindingSeverity::Warning => "warning",
+            FindingSeverity::Error => "error",
+        })
+    }
+}
+
+/// A problem a rule plugin found, on a file or one of its elements
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleFinding {
+    // The plugin's own name for the check, e.g. "no-print"
+    pub rule: String,
+    #[serde(default)]
+    pub severity: FindingSeverity,
+    pub message: String,
+    // Project-relative path
+    pub file: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    // Fixes the plugin proposes, ideally with an action a tool could apply
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<crate::core::suggestions::Suggestion>,
 }
 
 /// Plugin communication protocol - now supports both input and output plugins
@@ -129,6 +536,10 @@ pub enum PluginMessage {
     #[serde(rename = "analyze")]
     Analyze { input: PluginInput },
 
+    // Only sent to plugins advertising CAPABILITY_ANALYZE_BATCH
+    #[serde(rename = "analyze_batch")]
+    AnalyzeBatch { inputs: Vec<PluginInput> },
+
     #[serde(rename = "generate")]
     Generate { input: OutputPluginInput },
 
@@ -136,11 +547,24 @@ pub enum PluginMessage {
     CanAnalyze {
         file_path: PathBuf,
         content_preview: String,
+        // A chunk from the middle of files larger than the preview
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content_sample: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file_size: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        extension: Option<String>,
     },
 
     #[serde(rename = "can_generate")]
     CanGenerate { output_type: String, format: String },
 
+    #[serde(rename = "transform")]
+    Transform { input: TransformPluginInput },
+
+    #[serde(rename = "check")]
+    Check { input: RulePluginInput },
+
     #[serde(rename = "get_info")]
     GetInfo,
 }
@@ -155,9 +579,24 @@ pub enum PluginResponse {
         processing_time_ms: u64,
     },
 
+    #[serde(rename = "batch_success")]
+    BatchSuccess { results: Vec<BatchResult> },
+
     #[serde(rename = "output_success")]
     OutputSuccess { result: OutputPluginResult },
 
+    #[serde(rename = "transform_success")]
+    TransformSuccess {
+        patch: MatrixPatch,
+        processing_time_ms: u64,
+    },
+
+    #[serde(rename = "check_success")]
+    CheckSuccess {
+        findings: Vec<RuleFinding>,
+        processing_time_ms: u64,
+    },
+
     #[serde(rename = "can_analyze")]
     CanAnalyze { can_analyze: bool, confidence: f32 },
 
@@ -173,6 +612,11 @@ pub enum PluginResponse {
         supported_filenames: Vec<String>,
         supported_output_types: Option<Vec<String>>, // For output plugins
         supported_formats: Option<Vec<String>>,      // For output plugins
+        #[serde(default)]
+        capabilities: Vec<String>, // Optional protocol features, e.g. "content_path"
+        // JSON Schema for the plugin's `config` in `.csdrc.yaml`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        config_schema: Option<serde_json::Value>,
     },
 
     #[serde(rename = "error")]
@@ -182,6 +626,60 @@ pub enum PluginResponse {
     },
 }
 
+/// Outcome for one file of an `analyze_batch` request; a failed file carries `error`
+/// instead of `cache_file` so the rest of the batch still counts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub relative_path: PathBuf,
+    #[serde(default)]
+    pub cache_file: Option<String>,
+    #[serde(default)]
+    pub processing_time_ms: u64,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Environment variable naming the file a plugin may write `PluginProgress` JSON to
+pub const PROGRESS_FILE_ENV: &str = "CSD_PROGRESS_FILE";
+
+/// Environment variable set to `1` for plugins and hooks when csd runs in offline mode;
+/// they must not reach anything beyond this machine
+pub const OFFLINE_ENV: &str = "CSD_OFFLINE";
+
+/// Environment variable set to `1` for plugins started by the plugin pool
+pub const PERSISTENT_ENV: &str = "CSD_PERSISTENT";
+
+/// Environment variable telling a pooled `CAPABILITY_SOCKET` plugin where to connect:
+/// `unix:<path>` or `tcp:<host>:<port>`
+pub const SOCKET_ENV: &str = "CSD_SOCKET";
+
+/// Environment variable holding the token a socket plugin sends as its first frame
+pub const SOCKET_TOKEN_ENV: &str = "CSD_SOCKET_TOKEN";
+
+/// Progress a long-running plugin reports through its progress file, e.g. sections
+/// completed out of the total. Each change extends the communicator's timeout.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginProgress {
+    pub completed: u64,
+    #[serde(default)]
+    pub total: Option<u64>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl std::fmt::Display for PluginProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.total {
+            Some(total) => write!(f, "{}/{}", self.completed, total)?,
+            None => write!(f, "{}", self.completed)?,
+        }
+        if let Some(ref message) = self.message {
+            write!(f, " - {message}")?;
+        }
+        Ok(())
+    }
+}
+
 /// Trait for implementing plugin communication - now generic over plugin type
 #[async_trait::async_trait]
 pub trait PluginInterface {
@@ -194,6 +692,12 @@ pub trait PluginInterface {
 pub trait InputPluginInterface: PluginInterface {
     async fn can_analyze(&self, file_path: &Path, content_preview: &str) -> anyhow::Result<bool>;
     async fn analyze(&self, input: PluginInput) -> anyhow::Result<PluginOutput>;
+
+    /// Analyze several files, returning one result per input in the same order
+    async fn analyze_batch(
+        &self,
+        inputs: Vec<PluginInput>,
+    ) -> anyhow::Result<Vec<anyhow::Result<PluginOutput>>>;
 }
 
 /// Trait specifically for output plugins (documentation generators, etc.)
@@ -205,6 +709,18 @@ pub trait OutputPluginInterface: PluginInterface {
     async fn get_supported_formats(&self) -> anyhow::Result<Vec<String>>;
 }
 
+/// Trait specifically for transform plugins (matrix post-processors)
+#[async_trait::async_trait]
+pub trait TransformPluginInterface: PluginInterface {
+    async fn transform(&self, input: TransformPluginInput) -> anyhow::Result<MatrixPatch>;
+}
+
+/// Trait specifically for rule plugins (custom quality checks)
+#[async_trait::async_trait]
+pub trait RulePluginInterface: PluginInterface {
+    async fn check(&self, input: RulePluginInput) -> anyhow::Result<Vec<RuleFinding>>;
+}
+
 /// Enhanced plugin info structure with type identification
 #[derive(Debug, Clone)]
 pub struct PluginInfo {
@@ -215,9 +731,24 @@ pub struct PluginInfo {
     pub supported_filenames: Vec<String>,
     pub supported_output_types: Option<Vec<String>>, // For output plugins
     pub supported_formats: Option<Vec<String>>,      // For output plugins
+    pub capabilities: Vec<String>,
+    pub config_schema: Option<serde_json::Value>,
 }
 
 impl PluginInfo {
+    /// Check whether the plugin advertised a protocol capability
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+
+    /// How `config` breaks the plugin's declared schema; nothing when it declares none
+    pub fn check_config(&self, config: Option<&serde_yaml::Value>) -> Vec<SchemaViolation> {
+        match &self.config_schema {
+            Some(schema) => validate_yaml_config(schema, config),
+            None => Vec::new(),
+        }
+    }
+
     /// Check if this is an input plugin
     pub fn is_input_plugin(&self) -> bool {
         self.plugin_type == PluginType::Input
@@ -228,6 +759,16 @@ impl PluginInfo {
         self.plugin_type == PluginType::Output
     }
 
+    /// Check if this is a transform plugin
+    pub fn is_transform_plugin(&self) -> bool {
+        self.plugin_type == PluginType::Transform
+    }
+
+    /// Check if this is a rule plugin
+    pub fn is_rule_plugin(&self) -> bool {
+        self.plugin_type == PluginType::Rule
+    }
+
     /// Get capabilities description for display
     pub fn get_capabilities_description(&self) -> String {
         match self.plugin_type {
@@ -254,6 +795,8 @@ impl PluginInfo {
                 }
                 caps.join(" | ")
             }
+            PluginType::Transform => "Matrix transform".to_string(),
+            PluginType::Rule => "Quality rules".to_string(),
         }
     }
 }
@@ -284,6 +827,12 @@ impl PluginFactory {
             PluginType::Output => Ok(Box::new(
                 crate::plugins::communication::OutputPluginCommunicator::new(plugin_path),
             )),
+            PluginType::Transform => Ok(Box::new(
+                crate::plugins::communication::TransformPluginCommunicator::new(plugin_path),
+            )),
+            PluginType::Rule => Ok(Box::new(
+                crate::plugins::communication::RulePluginCommunicator::new(plugin_path),
+            )),
         }
     }
 }
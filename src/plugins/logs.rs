@@ -0,0 +1,146 @@
+// src/plugins/logs.rs - Per-invocation transcripts of plugin stdout/stderr
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::plugins::interface::PluginMessage;
+use crate::utils::config::PluginLogConfig;
+use crate::utils::file_utils::write_atomic;
+
+/// Subdirectory of the cache holding one directory of logs per plugin
+pub const LOGS_DIR: &str = "logs";
+
+/// What one plugin invocation did, as written to its log
+pub struct Transcript<'a> {
+    pub plugin_path: &'a Path,
+    pub message: &'a PluginMessage,
+    pub started: DateTime<Utc>,
+    pub duration: Duration,
+    // "ok", the exit status or the reason the exchange failed
+    pub outcome: &'a str,
+    pub stdout: &'a str,
+    pub stderr: &'a str,
+}
+
+/// `<cache>/logs/<plugin>/<key>.log`. File messages are keyed by a hash of the file's
+/// path, so a re-run replaces that file's previous log instead of piling up new ones.
+pub fn log_path(cache_dir: &Path, plugin_path: &Path, message: &PluginMessage) -> PathBuf {
+    let plugin = plugin_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "plugin".to_string());
+    let key = match message {
+        PluginMessage::Analyze { input } => path_hash(&input.file_path),
+        PluginMessage::CanAnalyze { file_path, .. } => {
+            format!("{}.can_analyze", path_hash(file_path))
+        }
+        PluginMessage::AnalyzeBatch { inputs } => {
+            let mut hasher = Sha256::new();
+            for input in inputs {
+                hasher.update(input.file_path.to_string_lossy().as_bytes());
+                hasher.update(b"\n");
+            }
+            format!("batch-{}", &format!("{:x}", hasher.finalize())[..16])
+        }
+        PluginMessage::Generate { .. } => "generate".to_string(),
+        PluginMessage::CanGenerate { .. } => "can_generate".to_string(),
+        PluginMessage::Transform { .. } => "transform".to_string(),
+        PluginMessage::Check { .. } => "check".to_string(),
+        PluginMessage::GetInfo => "get_info".to_string(),
+    };
+    cache_dir
+        .join(LOGS_DIR)
+        .join(plugin)
+        .join(format!("{key}.log"))
+}
+
+fn path_hash(path: &Path) -> String {
+    format!("{:x}", Sha256::digest(path.to_string_lossy().as_bytes()))[..16].to_string()
+}
+
+impl Transcript<'_> {
+    pub fn render(&self) -> String {
+        let (kind, files) = match self.message {
+            PluginMessage::Analyze { input } => ("analyze", vec![&input.file_path]),
+            PluginMessage::CanAnalyze { file_path, .. } => ("can_analyze", vec![file_path]),
+            PluginMessage::AnalyzeBatch { inputs } => (
+                "analyze_batch",
+                inputs.iter().map(|input| &input.file_path).collect(),
+            ),
+            PluginMessage::Generate { .. } => ("generate", Vec::new()),
+            PluginMessage::CanGenerate { .. } => ("can_generate", Vec::new()),
+            PluginMessage::Transform { .. } => ("transform", Vec::new()),
+            PluginMessage::Check { .. } => ("check", Vec::new()),
+            PluginMessage::GetInfo => ("get_info", Vec::new()),
+        };
+
+        let mut log = format!("plugin: {}\nmessage: {kind}\n", self.plugin_path.display());
+        for file in files {
+            log.push_str(&format!("file: {}\n", file.display()));
+        }
+        log.push_str(&format!(
+            "started: {}\nduration_ms: {}\noutcome: {}\n\n--- stdout ---\n{}\n--- stderr ---\n{}\n",
+            self.started.to_rfc3339(),
+            self.duration.as_millis(),
+            self.outcome,
+            self.stdout.trim_end(),
+            self.stderr.trim_end()
+        ));
+        log
+    }
+
+    pub async fn write(&self, cache_dir: &Path) -> Result<PathBuf> {
+        let path = log_path(cache_dir, self.plugin_path, self.message);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        write_atomic(&path, self.render()).await?;
+        Ok(path)
+    }
+}
+
+/// Remove logs past `max_age_days`, then the oldest beyond `max_files_per_plugin` in
+/// each plugin's directory. Returns how many were removed.
+pub async fn prune_plugin_logs(cache_dir: &Path, config: &PluginLogConfig) -> Result<usize> {
+    let logs_dir = cache_dir.join(LOGS_DIR);
+    if !logs_dir.is_dir() {
+        return Ok(0);
+    }
+    let cutoff = (config.max_age_days > 0)
+        .then(|| SystemTime::now() - Duration::from_secs(config.max_age_days * 24 * 3600));
+
+    let mut removed = 0;
+    let mut plugins = tokio::fs::read_dir(&logs_dir).await?;
+    while let Some(plugin) = plugins.next_entry().await? {
+        if !plugin.file_type().await?.is_dir() {
+            continue;
+        }
+
+        let mut logs: Vec<(SystemTime, PathBuf)> = Vec::new();
+        let mut entries = tokio::fs::read_dir(plugin.path()).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("log") {
+                continue;
+            }
+            let modified = entry.metadata().await?.modified()?;
+            logs.push((modified, path));
+        }
+
+        // Newest first, so whatever is past the limit is the oldest
+        logs.sort_by_key(|log| std::cmp::Reverse(log.0));
+        for (index, (modified, path)) in logs.into_iter().enumerate() {
+            let too_old = cutoff.is_some_and(|cutoff| modified < cutoff);
+            let over_limit =
+                config.max_files_per_plugin > 0 && index >= config.max_files_per_plugin;
+            if (too_old || over_limit) && tokio::fs::remove_file(&path).await.is_ok() {
+                removed += 1;
+            }
+        }
+    }
+    Ok(removed)
+}
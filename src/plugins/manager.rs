@@ -1,4 +1,4 @@
-use crate::utils::config::{Config, PluginSource};
+use crate::utils::config::{Config, PluginRuntime, PluginSource};
 use anyhow::Result;
 use std::path::PathBuf;
 
@@ -6,12 +6,13 @@ use std::path::PathBuf;
 pub struct PluginInfo {
     pub name: String,
     pub path: PathBuf,
-    pub plugin_type: String,       // "input" or "output"
+    pub plugin_type: String,       // "input", "output", "transform" or "rule"
     pub extensions: Vec<String>,   // For input plugins
     pub filenames: Vec<String>,    // For input plugins
     pub output_types: Vec<String>, // For output plugins
     pub formats: Vec<String>,      // For output plugins
     pub source: PluginSource,
+    pub runtime: PluginRuntime,
     pub enabled: bool,
 }
 
@@ -46,6 +47,7 @@ impl PluginManager {
                 output_types: vec![], // Input plugins don't have output types
                 formats: vec![],      // Input plugins don't have formats
                 source: plugin_config.source.clone(),
+                runtime: plugin_config.runtime,
                 enabled: plugin_config.enabled,
             });
         }
@@ -69,6 +71,47 @@ impl PluginManager {
                 output_types: plugin_config.output_types.clone(),
                 formats: plugin_config.formats.clone(),
                 source: plugin_config.source.clone(),
+                runtime: plugin_config.runtime,
+                enabled: plugin_config.enabled,
+            });
+        }
+
+        // Discover transform plugins, in the order they run
+        for (name, plugin_config) in self.config.get_enabled_transform_plugins() {
+            let path = self
+                .resolve_plugin_path(name, &plugin_config.source, "transform")
+                .await?;
+
+            plugins.push(PluginInfo {
+                name: name.clone(),
+                path,
+                plugin_type: "transform".to_string(),
+                extensions: vec![],
+                filenames: vec![],
+                output_types: vec![],
+                formats: vec![],
+                source: plugin_config.source.clone(),
+                runtime: plugin_config.runtime,
+                enabled: plugin_config.enabled,
+            });
+        }
+
+        // Discover rule plugins
+        for (name, plugin_config) in self.config.get_enabled_rule_plugins() {
+            let path = self
+                .resolve_plugin_path(name, &plugin_config.source, "rule")
+                .await?;
+
+            plugins.push(PluginInfo {
+                name: name.clone(),
+                path,
+                plugin_type: "rule".to_string(),
+                extensions: vec![],
+                filenames: vec![],
+                output_types: vec![],
+                formats: vec![],
+                source: plugin_config.source.clone(),
+                runtime: plugin_config.runtime,
                 enabled: plugin_config.enabled,
             });
         }
@@ -240,9 +283,15 @@ impl PluginManager {
         &self,
         _name: &str,
         source: &PluginSource,
-        plugin_category: &str, // "input" or "output"
+        plugin_category: &str, // "input", "output", "transform" or "rule"
     ) -> Result<PathBuf> {
         match source {
+            PluginSource::GitHub { repo, .. } if self.config.offline => Err(anyhow::anyhow!(
+                "Plugin '{_name}' is fetched from GitHub ({repo}), which offline mode forbids"
+            )),
+            PluginSource::Git { url, .. } if self.config.offline => Err(anyhow::anyhow!(
+                "Plugin '{_name}' is fetched from Git ({url}), which offline mode forbids"
+            )),
             PluginSource::Local { path } => Ok(PathBuf::from(path)),
             PluginSource::Builtin {
                 name: plugin_name,
@@ -256,6 +305,12 @@ impl PluginManager {
                     "output" => Ok(PathBuf::from(format!(
                         "plugins/output/{plugin_type}/{plugin_name}.py"
                     ))),
+                    "transform" => Ok(PathBuf::from(format!(
+                        "plugins/transform/{plugin_type}/{plugin_name}.py"
+                    ))),
+                    "rule" => Ok(PathBuf::from(format!(
+                        "plugins/rule/{plugin_type}/{plugin_name}.py"
+                    ))),
                     _ => Err(anyhow::anyhow!(
                         "Unknown plugin category: {plugin_category}"
                     )),
@@ -264,17 +319,21 @@ impl PluginManager {
             PluginSource::GitHub { repo, version } => {
                 // TODO: Implement GitHub plugin downloading
                 let version_str = version.as_deref().unwrap_or("latest");
-                Ok(PathBuf::from(format!(
-                    ".csd_cache/github/{repo}/{version_str}/{_name}.py"
-                )))
+                Ok(self
+                    .config
+                    .resolve_cache_dir(std::path::Path::new("."))
+                    .join(format!("github/{repo}/{version_str}/{_name}.py")))
             }
             PluginSource::Git { url, branch } => {
                 // TODO: Implement Git plugin cloning
                 let branch_str = branch.as_deref().unwrap_or("main");
-                Ok(PathBuf::from(format!(
-                    ".csd_cache/git/{}/{branch_str}/{_name}.py",
-                    url.replace('/', "_")
-                )))
+                Ok(self
+                    .config
+                    .resolve_cache_dir(std::path::Path::new("."))
+                    .join(format!(
+                        "git/{}/{branch_str}/{_name}.py",
+                        url.replace('/', "_")
+                    )))
             }
         }
     }
@@ -288,6 +347,11 @@ impl PluginManager {
     ) -> Result<()> {
         // TODO: Implement plugin installation
         // This would download/clone the plugin and add it to configuration
+        if self.config.offline {
+            return Err(anyhow::anyhow!(
+                "Cannot install plugin '{name}': offline mode forbids network access"
+            ));
+        }
 
         match plugin_type.as_str() {
             "input" => {
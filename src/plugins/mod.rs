@@ -1,3 +1,8 @@
 pub mod communication;
+pub mod config_schema;
+pub mod conformance;
 pub mod interface;
+pub mod logs;
 pub mod manager;
+pub mod scaffold;
+pub mod transport;
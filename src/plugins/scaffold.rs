@@ -0,0 +1,835 @@
+// src/plugins/scaffold.rs - Skeleton Python plugins for `csd plugin new`
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::plugins::interface::PluginType;
+use crate::utils::config::{
+    FilePatterns, InputPluginConfig, OutputPluginConfig, PluginRuntime, PluginSource,
+    RulePluginConfig, TransformPluginConfig,
+};
+
+/// A new plugin's name, type and claimed extensions, rendered into a plugin script built
+/// on the SDK base classes plus a pytest harness that feeds it canned inputs
+#[derive(Debug, Clone)]
+pub struct PluginScaffold {
+    pub name: String,
+    pub plugin_type: PluginType,
+    // Extensions an input plugin claims, with the leading dot
+    pub extensions: Vec<String>,
+}
+
+impl PluginScaffold {
+    /// The name becomes the Python module name, so it must be a valid identifier
+    pub fn new(name: &str, plugin_type: PluginType) -> Result<Self> {
+        let valid = name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid {
+            return Err(anyhow::anyhow!(
+                "Invalid plugin name '{name}': use lowercase letters, digits and underscores, \
+                 starting with a letter (e.g. my_lang)"
+            ));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            extensions: vec![format!(".{name}")],
+            plugin_type,
+        })
+    }
+
+    /// Extensions for an input plugin to claim, instead of `.<name>`
+    pub fn with_extensions(mut self, extensions: &[String]) -> Self {
+        if !extensions.is_empty() {
+            self.extensions = extensions
+                .iter()
+                .map(|ext| format!(".{}", ext.trim_start_matches('.')))
+                .collect();
+        }
+        self
+    }
+
+    /// `my_lang` becomes `MyLangAnalyzer`, `MyLangOutput`, `MyLangTransform` or
+    /// `MyLangRules`
+    pub fn class_name(&self) -> String {
+        let mut class: String = self
+            .name
+            .split('_')
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let mut chars = part.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect();
+        class.push_str(match self.plugin_type {
+            PluginType::Input => "Analyzer",
+            PluginType::Output => "Output",
+            PluginType::Transform => "Transform",
+            PluginType::Rule => "Rules",
+        });
+        class
+    }
+
+    pub fn category(&self) -> &'static str {
+        self.plugin_type.as_str()
+    }
+
+    /// Directory the plugin goes in, following the `plugins/<category>/<group>/` layout
+    pub fn plugin_dir(&self, plugins_root: &Path) -> PathBuf {
+        plugins_root.join(self.category()).join(&self.name)
+    }
+
+    /// File names and contents, relative to the plugin directory
+    pub fn files(&self) -> Vec<(String, String)> {
+        let (plugin, test) = match self.plugin_type {
+            PluginType::Input => (INPUT_PLUGIN_TEMPLATE, INPUT_TEST_TEMPLATE),
+            PluginType::Output => (OUTPUT_PLUGIN_TEMPLATE, OUTPUT_TEST_TEMPLATE),
+            PluginType::Transform => (TRANSFORM_PLUGIN_TEMPLATE, TRANSFORM_TEST_TEMPLATE),
+            PluginType::Rule => (RULE_PLUGIN_TEMPLATE, RULE_TEST_TEMPLATE),
+        };
+        vec![
+            (format!("{}.py", self.name), self.render(plugin)),
+            (format!("test_{}.py", self.name), self.render(test)),
+        ]
+    }
+
+    /// Write the plugin and its tests, refusing to replace existing files unless `force`
+    pub fn write(&self, plugins_root: &Path, force: bool) -> Result<Vec<PathBuf>> {
+        let dir = self.plugin_dir(plugins_root);
+        let files = self.files();
+        if !force {
+            if let Some((existing, _)) = files.iter().find(|(name, _)| dir.join(name).exists()) {
+                return Err(anyhow::anyhow!(
+                    "{} already exists. Use --force to overwrite",
+                    dir.join(existing).display()
+                ));
+            }
+        }
+
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let mut written = Vec::with_capacity(files.len());
+        for (name, content) in files {
+            let path = dir.join(name);
+            std::fs::write(&path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    /// Config key the plugin is registered under, e.g. `input_plugins.my_lang`
+    pub fn config_key(&self) -> String {
+        format!("{}_plugins.{}", self.category(), self.name)
+    }
+
+    /// Local plugin entry pointing at the generated script
+    pub fn config_value(&self, script: &Path) -> Result<serde_yaml::Value> {
+        let source = PluginSource::Local {
+            path: script.to_string_lossy().to_string(),
+        };
+        let value = match self.plugin_type {
+            PluginType::Input => serde_yaml::to_value(InputPluginConfig {
+                source,
+                file_patterns: FilePatterns {
+                    extensions: self.extensions.clone(),
+                    filenames: Vec::new(),
+                    glob_patterns: None,
+                },
+                enabled: true,
+                config: None,
+                runtime: PluginRuntime::Python,
+            })?,
+            PluginType::Output => serde_yaml::to_value(OutputPluginConfig {
+                source,
+                output_types: vec!["documentation".to_string()],
+                formats: vec!["markdown".to_string()],
+                enabled: true,
+                config: None,
+                runtime: PluginRuntime::Python,
+            })?,
+            PluginType::Transform => serde_yaml::to_value(TransformPluginConfig {
+                source,
+                enabled: true,
+                order: 0,
+                config: None,
+                runtime: PluginRuntime::Python,
+            })?,
+            PluginType::Rule => serde_yaml::to_value(RulePluginConfig {
+                source,
+                enabled: true,
+                scope: Vec::new(),
+                config: None,
+                runtime: PluginRuntime::Python,
+            })?,
+        };
+        Ok(value)
+    }
+
+    fn render(&self, template: &str) -> String {
+        let extensions = self
+            .extensions
+            .iter()
+            .map(|ext| format!("\"{ext}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        template
+            .replace("{{name}}", &self.name)
+            .replace("{{class}}", &self.class_name())
+            .replace("{{extensions}}", &format!("[{extensions}]"))
+            .replace("{{extension}}", &self.extensions[0])
+    }
+}
+
+const INPUT_PLUGIN_TEMPLATE: &str = r##"#!/usr/bin/env python3
+"""
+{{name}} analyzer plugin for CSD.
+
+BaseAnalyzer handles the message protocol (get_info, can_analyze, analyze and
+analyze_batch as JSON on stdin/stdout); this class only decides which files it
+claims and what it extracts from them. Run test_{{name}}.py with pytest.
+"""
+
+import re
+from pathlib import Path
+from typing import Dict, List, Tuple
+
+from csd_plugin_sdk import (
+    BaseAnalyzer,
+    CodeElement,
+    Import,
+    PluginInput,
+    PluginOutput,
+    calculate_complexity,
+)
+
+# Placeholder patterns; replace them with a real parser for the language
+FUNCTION_PATTERN = re.compile(r"^\s*(?:def|fn|func|function)\s+(\w+)")
+IMPORT_PATTERN = re.compile(r"^\s*(?:import|use|require|include)\s+([\w./:]+)")
+
+
+class {{class}}(BaseAnalyzer):
+    """Analyzer for {{name}} files."""
+
+    def __init__(self):
+        """Initialize the {{class}} instance."""
+        super().__init__()
+        self.name = "{{name}}"
+        self.version = "0.1.0"
+        self.supported_extensions = {{extensions}}
+        self.supported_filenames = []
+
+    def can_analyze(self, file_path: str, content_preview: str) -> Tuple[bool, float]:
+        """Check if this plugin can analyze the given file."""
+        path = Path(file_path)
+
+        if path.suffix in self.supported_extensions:
+            return True, 1.0
+
+        if path.name in self.supported_filenames:
+            return True, 0.9
+
+        return False, 0.0
+
+    def analyze(self, input_data: PluginInput) -> PluginOutput:
+        """Extract elements and imports from one file."""
+        content = input_data.content
+        lines = content.split("\n")
+        elements: List[CodeElement] = []
+        imports: List[Import] = []
+
+        for line_number, line in enumerate(lines, start=1):
+            function = FUNCTION_PATTERN.match(line)
+            if function:
+                elements.append(
+                    CodeElement(
+                        element_type="function",
+                        name=function.group(1),
+                        signature=line.strip(),
+                        line_start=line_number,
+                        line_end=line_number,
+                        complexity_score=calculate_complexity(
+                            content, line_number, line_number
+                        ),
+                    )
+                )
+                continue
+
+            imported = IMPORT_PATTERN.match(line)
+            if imported:
+                imports.append(
+                    Import(
+                        module=imported.group(1),
+                        line_number=line_number,
+                        import_type="relative"
+                        if imported.group(1).startswith(".")
+                        else "third_party",
+                    )
+                )
+
+        return PluginOutput(
+            file_path=input_data.file_path,
+            file_hash="",
+            elements=elements,
+            imports=imports,
+            exports=[element.name for element in elements],
+            relationships=[],
+            external_dependencies=[],
+            file_summary=None,
+            token_info=self._calculate_token_info(content),
+        )
+
+    def _calculate_token_info(self, content: str) -> Dict[str, int]:
+        """Rough token counts (~4 characters per token)."""
+        total_tokens = max(1, len(content) // 4) if content else 0
+        return {
+            "total_tokens": total_tokens,
+            "code_tokens": total_tokens,
+            "documentation_tokens": 0,
+            "comment_tokens": 0,
+        }
+
+
+if __name__ == "__main__":
+    {{class}}().run()
+"##;
+
+const INPUT_TEST_TEMPLATE: &str = r##"#!/usr/bin/env python3
+"""
+Tests for the {{name}} analyzer plugin.
+
+Requires the CSD plugin SDK (`pip install -e plugins/shared/python`). The canned
+PluginInputs exercise `analyze` directly; the protocol test runs the plugin the way
+the core does, one JSON message per process.
+"""
+
+import json
+import os
+import subprocess
+import sys
+from pathlib import Path
+
+import pytest
+
+PLUGIN_DIR = Path(__file__).parent
+sys.path.insert(0, str(PLUGIN_DIR))
+
+from csd_plugin_sdk import PluginInput  # noqa: E402
+from {{name}} import {{class}}  # noqa: E402
+
+SAMPLE = """import .helpers
+import collections
+
+function greet(name)
+  return "hello " + name
+end
+"""
+
+
+@pytest.fixture
+def analyzer():
+    return {{class}}()
+
+
+def make_input(tmp_path, content, relative_path="src/sample{{extension}}"):
+    file_path = tmp_path / relative_path
+    return PluginInput(
+        file_path=str(file_path),
+        relative_path=relative_path,
+        content=content,
+        project_root=str(tmp_path),
+        cache_dir=str(tmp_path / "cache"),
+    )
+
+
+def run_plugin(message):
+    env = dict(os.environ, PYTHONPATH=os.pathsep.join(sys.path))
+    completed = subprocess.run(
+        [sys.executable, str(PLUGIN_DIR / "{{name}}.py")],
+        input=json.dumps(message),
+        capture_output=True,
+        text=True,
+        env=env,
+        timeout=30,
+    )
+    return json.loads(completed.stdout.strip().splitlines()[-1])
+
+
+def test_can_analyze_supported_extension(analyzer):
+    can_analyze, confidence = analyzer.can_analyze("src/sample{{extension}}", SAMPLE)
+    assert can_analyze
+    assert confidence > 0.5
+
+
+def test_can_analyze_rejects_other_files(analyzer):
+    can_analyze, _ = analyzer.can_analyze("README.unrelated", "# Title")
+    assert not can_analyze
+
+
+def test_analyze_sample(analyzer, tmp_path):
+    result = analyzer.analyze(make_input(tmp_path, SAMPLE))
+
+    assert [element.name for element in result.elements] == ["greet"]
+    assert result.elements[0].line_start == 4
+    assert [imported.module for imported in result.imports] == [".helpers", "collections"]
+    assert result.imports[0].import_type == "relative"
+    assert result.token_info["total_tokens"] > 0
+
+
+def test_analyze_empty_file(analyzer, tmp_path):
+    result = analyzer.analyze(make_input(tmp_path, ""))
+
+    assert result.elements == []
+    assert result.imports == []
+    assert result.token_info["total_tokens"] == 0
+
+
+def test_protocol_roundtrip(tmp_path):
+    info = run_plugin({"type": "get_info"})
+    assert info["status"] == "info"
+    assert info["name"] == "{{name}}"
+
+    claim = run_plugin(
+        {
+            "type": "can_analyze",
+            "file_path": "src/sample{{extension}}",
+            "content_preview": SAMPLE,
+        }
+    )
+    assert claim["status"] == "can_analyze"
+    assert claim["can_analyze"]
+
+    plugin_input = make_input(tmp_path, SAMPLE)
+    response = run_plugin({"type": "analyze", "input": plugin_input.__dict__})
+    assert response["status"] == "success", response
+    cached = json.loads(
+        (Path(plugin_input.cache_dir) / response["cache_file"]).read_text()
+    )
+    assert cached["elements"][0]["name"] == "greet"
+"##;
+
+const OUTPUT_PLUGIN_TEMPLATE: &str = r##"#!/usr/bin/env python3
+"""
+{{name}} output plugin for CSD.
+
+BaseOutputPlugin handles the message protocol (get_info, can_generate and generate
+as JSON on stdin/stdout); this class turns the project matrix into output files.
+Run test_{{name}}.py with pytest.
+"""
+
+from pathlib import Path
+from typing import Tuple
+
+from csd_plugin_sdk import (
+    BaseOutputPlugin,
+    OutputPluginInput,
+    OutputPluginResult,
+)
+
+
+class {{class}}(BaseOutputPlugin):
+    """Output plugin that writes a summary of the project matrix."""
+
+    def __init__(self):
+        """Initialize the {{class}} instance."""
+        super().__init__()
+        self.name = "{{name}}"
+        self.version = "0.1.0"
+        self.supported_output_types = ["documentation"]
+        self.supported_formats = ["markdown"]
+
+    def can_generate(self, output_type: str, format: str) -> Tuple[bool, float]:
+        """Check if this plugin can generate the given output type and format."""
+        if (
+            output_type in self.supported_output_types
+            and format in self.supported_formats
+        ):
+            return True, 1.0
+        return False, 0.0
+
+    def generate(self, input_data: OutputPluginInput) -> OutputPluginResult:
+        """Write one markdown file listing the project's files."""
+        matrix = self._load_matrix_from_file(input_data.matrix_path)
+        files = matrix.get("files", {})
+
+        lines = ["# Project Files", ""]
+        for file in sorted(files.values(), key=lambda f: f["relative_path"]):
+            tokens = file.get("token_info", {}).get("total_tokens", 0)
+            lines.append(f"- `{file['relative_path']}` ({tokens} tokens)")
+
+        self._ensure_output_directory(input_data.output_dir)
+        output_path = str(Path(input_data.output_dir) / "{{name}}.md")
+        Path(output_path).write_text("\n".join(lines) + "\n", encoding="utf-8")
+
+        return OutputPluginResult(
+            plugin_name=self.name,
+            plugin_version=self.version,
+            output_type="documentation",
+            outputs=[self._create_generated_output(output_path, "markdown")],
+        )
+
+
+if __name__ == "__main__":
+    {{class}}().run()
+"##;
+
+const OUTPUT_TEST_TEMPLATE: &str = r##"#!/usr/bin/env python3
+"""
+Tests for the {{name}} output plugin.
+
+Requires the CSD plugin SDK (`pip install -e plugins/shared/python`). The canned
+matrix exercises `generate` directly; the protocol test runs the plugin the way the
+core does, one JSON message per process.
+"""
+
+import json
+import os
+import subprocess
+import sys
+from pathlib import Path
+
+import pytest
+
+PLUGIN_DIR = Path(__file__).parent
+sys.path.insert(0, str(PLUGIN_DIR))
+
+from csd_plugin_sdk import OutputPluginInput  # noqa: E402
+from {{name}} import {{class}}  # noqa: E402
+
+MATRIX = {
+    "files": {
+        "src/main.py": {
+            "relative_path": "src/main.py",
+            "token_info": {"total_tokens": 120},
+        },
+        "README.md": {"relative_path": "README.md", "token_info": {"total_tokens": 40}},
+    },
+    "relationships": [],
+}
+
+
+@pytest.fixture
+def plugin():
+    return {{class}}()
+
+
+def make_input(tmp_path):
+    matrix_path = tmp_path / "matrix.json"
+    matrix_path.write_text(json.dumps(MATRIX))
+    return OutputPluginInput(
+        matrix_path=str(matrix_path),
+        project_root=str(tmp_path),
+        output_dir=str(tmp_path / "out"),
+        cache_dir=str(tmp_path / "cache"),
+    )
+
+
+def run_plugin(message):
+    env = dict(os.environ, PYTHONPATH=os.pathsep.join(sys.path))
+    completed = subprocess.run(
+        [sys.executable, str(PLUGIN_DIR / "{{name}}.py")],
+        input=json.dumps(message),
+        capture_output=True,
+        text=True,
+        env=env,
+        timeout=30,
+    )
+    return json.loads(completed.stdout.strip().splitlines()[-1])
+
+
+def test_can_generate(plugin):
+    assert plugin.can_generate("documentation", "markdown")[0]
+    assert not plugin.can_generate("quality_report", "pdf")[0]
+
+
+def test_generate_lists_files(plugin, tmp_path):
+    result = plugin.generate(make_input(tmp_path))
+
+    assert len(result.outputs) == 1
+    content = Path(result.outputs[0].output_path).read_text()
+    assert content.index("README.md") < content.index("src/main.py")
+    assert "(120 tokens)" in content
+
+
+def test_protocol_roundtrip(tmp_path):
+    info = run_plugin({"type": "get_info"})
+    assert info["status"] == "info"
+    assert info["name"] == "{{name}}"
+
+    response = run_plugin({"type": "generate", "input": make_input(tmp_path).__dict__})
+    assert response["status"] == "output_success", response
+    assert response["result"]["outputs"][0]["size_bytes"] > 0
+"##;
+
+const TRANSFORM_PLUGIN_TEMPLATE: &str = r##"#!/usr/bin/env python3
+"""
+{{name}} transform plugin for CSD.
+
+BaseTransformPlugin handles the message protocol (get_info and transform as JSON on
+stdin/stdout); this class reads the matrix after a scan and returns a patch of
+relationships, tags and summaries for the core to apply. Run test_{{name}}.py with
+pytest.
+"""
+
+from typing import Any, Dict
+
+from csd_plugin_sdk import (
+    BaseTransformPlugin,
+    MatrixPatch,
+    TagUpdate,
+    TransformPluginInput,
+)
+
+DEFAULT_LARGE_FILE_TOKENS = 4000
+
+
+class {{class}}(BaseTransformPlugin):
+    """Transform plugin that tags large files."""
+
+    def __init__(self):
+        """Initialize the {{class}} instance."""
+        super().__init__()
+        self.name = "{{name}}"
+        self.version = "0.1.0"
+
+    def transform(
+        self, input_data: TransformPluginInput, matrix: Dict[str, Any]
+    ) -> MatrixPatch:
+        """Tag files over the `large_file_tokens` setting as "large"."""
+        config = input_data.plugin_config or {}
+        limit = config.get("large_file_tokens", DEFAULT_LARGE_FILE_TOKENS)
+
+        patch = MatrixPatch()
+        for file in matrix.get("files", {}).values():
+            tokens = file.get("token_info", {}).get("total_tokens", 0)
+            if tokens > limit:
+                patch.tags.append(TagUpdate(file=file["relative_path"], tags=["large"]))
+        return patch
+
+
+if __name__ == "__main__":
+    {{class}}().run()
+"##;
+
+const TRANSFORM_TEST_TEMPLATE: &str = r##"#!/usr/bin/env python3
+"""
+Tests for the {{name}} transform plugin.
+
+Requires the CSD plugin SDK (`pip install -e plugins/shared/python`). The canned
+matrix exercises `transform` directly; the protocol test runs the plugin the way the
+core does, one JSON message per process.
+"""
+
+import json
+import os
+import subprocess
+import sys
+from pathlib import Path
+
+import pytest
+
+PLUGIN_DIR = Path(__file__).parent
+sys.path.insert(0, str(PLUGIN_DIR))
+
+from csd_plugin_sdk import TransformPluginInput  # noqa: E402
+from {{name}} import {{class}}  # noqa: E402
+
+MATRIX = {
+    "files": {
+        "src/big.py": {"relative_path": "src/big.py", "token_info": {"total_tokens": 9000}},
+        "src/small.py": {
+            "relative_path": "src/small.py",
+            "token_info": {"total_tokens": 100},
+        },
+    },
+    "relationships": [],
+}
+
+
+@pytest.fixture
+def plugin():
+    return {{class}}()
+
+
+def make_input(tmp_path, plugin_config=None):
+    matrix_path = tmp_path / "matrix.json"
+    matrix_path.write_text(json.dumps(MATRIX))
+    return TransformPluginInput(
+        matrix_path=str(matrix_path),
+        project_root=str(tmp_path),
+        cache_dir=str(tmp_path / "cache"),
+        plugin_config=plugin_config,
+    )
+
+
+def run_plugin(message):
+    env = dict(os.environ, PYTHONPATH=os.pathsep.join(sys.path))
+    completed = subprocess.run(
+        [sys.executable, str(PLUGIN_DIR / "{{name}}.py")],
+        input=json.dumps(message),
+        capture_output=True,
+        text=True,
+        env=env,
+        timeout=30,
+    )
+    return json.loads(completed.stdout.strip().splitlines()[-1])
+
+
+def test_tags_large_files(plugin, tmp_path):
+    patch = plugin.transform(make_input(tmp_path), MATRIX)
+
+    assert [tag.file for tag in patch.tags] == ["src/big.py"]
+
+
+def test_limit_comes_from_plugin_config(plugin, tmp_path):
+    patch = plugin.transform(make_input(tmp_path, {"large_file_tokens": 50}), MATRIX)
+
+    assert sorted(tag.file for tag in patch.tags) == ["src/big.py", "src/small.py"]
+
+
+def test_protocol_roundtrip(tmp_path):
+    info = run_plugin({"type": "get_info"})
+    assert info["status"] == "info"
+    assert info["name"] == "{{name}}"
+
+    response = run_plugin({"type": "transform", "input": make_input(tmp_path).__dict__})
+    assert response["status"] == "transform_success", response
+    assert response["patch"]["tags"] == [{"file": "src/big.py", "tags": ["large"]}]
+"##;
+
+const RULE_PLUGIN_TEMPLATE: &str = r##"#!/usr/bin/env python3
+"""
+{{name}} rule plugin for CSD.
+
+BaseRulePlugin handles the message protocol (get_info and check as JSON on
+stdin/stdout); this class reads the matrix and returns findings that `csd quality`
+reports next to its own checks. Run test_{{name}}.py with pytest.
+"""
+
+from typing import Any, Dict, List
+
+from csd_plugin_sdk import BaseRulePlugin, RuleFinding, RulePluginInput
+
+DEFAULT_MAX_ELEMENTS = 20
+
+
+class {{class}}(BaseRulePlugin):
+    """Rule plugin that flags files defining too many elements."""
+
+    def __init__(self):
+        """Initialize the {{class}} instance."""
+        super().__init__()
+        self.name = "{{name}}"
+        self.version = "0.1.0"
+
+    def check(
+        self, input_data: RulePluginInput, matrix: Dict[str, Any]
+    ) -> List[RuleFinding]:
+        """Report files with more elements than the `max_elements` setting."""
+        config = input_data.plugin_config or {}
+        limit = config.get("max_elements", DEFAULT_MAX_ELEMENTS)
+
+        findings = []
+        for file in matrix.get("files", {}).values():
+            count = len(file.get("elements", []))
+            if count > limit:
+                findings.append(
+                    RuleFinding(
+                        rule="too-many-elements",
+                        message=f"{count} elements (limit {limit})",
+                        file=file["relative_path"],
+                    )
+                )
+        return findings
+
+
+if __name__ == "__main__":
+    {{class}}().run()
+"##;
+
+const RULE_TEST_TEMPLATE: &str = r##"#!/usr/bin/env python3
+"""
+Tests for the {{name}} rule plugin.
+
+Requires the CSD plugin SDK (`pip install -e plugins/shared/python`). The canned
+matrix exercises `check` directly; the protocol test runs the plugin the way the
+core does, one JSON message per process.
+"""
+
+import json
+import os
+import subprocess
+import sys
+from pathlib import Path
+
+import pytest
+
+PLUGIN_DIR = Path(__file__).parent
+sys.path.insert(0, str(PLUGIN_DIR))
+
+from csd_plugin_sdk import RulePluginInput  # noqa: E402
+from {{name}} import {{class}}  # noqa: E402
+
+MATRIX = {
+    "files": {
+        "src/big.py": {"relative_path": "src/big.py", "elements": [{}] * 30},
+        "src/small.py": {"relative_path": "src/small.py", "elements": [{}] * 3},
+    },
+    "relationships": [],
+}
+
+
+@pytest.fixture
+def plugin():
+    return {{class}}()
+
+
+def make_input(tmp_path, plugin_config=None):
+    matrix_path = tmp_path / "matrix.json"
+    matrix_path.write_text(json.dumps(MATRIX))
+    return RulePluginInput(
+        matrix_path=str(matrix_path),
+        project_root=str(tmp_path),
+        cache_dir=str(tmp_path / "cache"),
+        plugin_config=plugin_config,
+    )
+
+
+def run_plugin(message):
+    env = dict(os.environ, PYTHONPATH=os.pathsep.join(sys.path))
+    completed = subprocess.run(
+        [sys.executable, str(PLUGIN_DIR / "{{name}}.py")],
+        input=json.dumps(message),
+        capture_output=True,
+        text=True,
+        env=env,
+        timeout=30,
+    )
+    return json.loads(completed.stdout.strip().splitlines()[-1])
+
+
+def test_flags_files_with_many_elements(plugin, tmp_path):
+    findings = plugin.check(make_input(tmp_path), MATRIX)
+
+    assert [finding.file for finding in findings] == ["src/big.py"]
+
+
+def test_limit_comes_from_plugin_config(plugin, tmp_path):
+    findings = plugin.check(make_input(tmp_path, {"max_elements": 2}), MATRIX)
+
+    assert sorted(finding.file for finding in findings) == ["src/big.py", "src/small.py"]
+
+
+def test_protocol_roundtrip(tmp_path):
+    info = run_plugin({"type": "get_info"})
+    assert info["status"] == "info"
+    assert info["plugin_type"] == "rule"
+
+    response = run_plugin({"type": "check", "input": make_input(tmp_path).__dict__})
+    assert response["status"] == "check_success", response
+    assert [finding["file"] for finding in response["findings"]] == ["src/big.py"]
+"##;
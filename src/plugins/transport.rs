@@ -0,0 +1,147 @@
+// src/plugins/transport.rs - Length-prefixed JSON frames over a Unix socket or localhost
+// TCP, for pooled plugins advertising CAPABILITY_SOCKET
+use anyhow::{Context, Result};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+use crate::utils::secrets::constant_time_eq;
+
+/// Frames above this are refused rather than allocated
+pub const MAX_FRAME_BYTES: usize = 256 * 1024 * 1024;
+
+// The `{"token": ...}` hello is read before the peer is trusted, so it gets far less
+const MAX_HELLO_BYTES: usize = 1024;
+
+/// How long a started plugin has to connect and send its token
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A connected plugin's socket
+pub trait Duplex: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Duplex for T {}
+
+/// Write `json` as one frame: its length as a big-endian u32, then the bytes
+pub async fn write_frame(stream: &mut (impl AsyncWrite + Unpin), json: &[u8]) -> Result<()> {
+    if json.len() > MAX_FRAME_BYTES {
+        return Err(anyhow::anyhow!(
+            "Message of {} bytes is too large for a frame",
+            json.len()
+        ));
+    }
+    stream.write_all(&(json.len() as u32).to_be_bytes()).await?;
+    stream.write_all(json).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// The next frame, or None when the other end closed the connection between frames
+pub async fn read_frame(stream: &mut (impl AsyncRead + Unpin)) -> Result<Option<Vec<u8>>> {
+    read_frame_within(stream, MAX_FRAME_BYTES).await
+}
+
+async fn read_frame_within(
+    stream: &mut (impl AsyncRead + Unpin),
+    limit: usize,
+) -> Result<Option<Vec<u8>>> {
+    let mut len = [0; 4];
+    match stream.read_exact(&mut len).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    let len = u32::from_be_bytes(len) as usize;
+    if len > limit {
+        return Err(anyhow::anyhow!(
+            "Frame of {len} bytes is over the {limit} byte limit"
+        ));
+    }
+    let mut frame = vec![0; len];
+    stream.read_exact(&mut frame).await?;
+    Ok(Some(frame))
+}
+
+/// Where a plugin process connects back to. Only a connection whose first frame is
+/// `{"token": ...}` with `token` is accepted, since anything on the machine can connect.
+pub struct PluginListener {
+    inner: Inner,
+    pub token: String,
+}
+
+enum Inner {
+    #[cfg(unix)]
+    Unix(tokio::net::UnixListener, std::path::PathBuf),
+    Tcp(TcpListener),
+}
+
+impl PluginListener {
+    /// A Unix socket in the temp directory, or a localhost TCP port when `tcp` is set or
+    /// there are no Unix sockets
+    pub async fn bind(tcp: bool) -> Result<Self> {
+        let token = Uuid::new_v4().simple().to_string();
+        #[cfg(unix)]
+        if !tcp {
+            // Socket paths are limited to about 100 bytes, so not under the cache dir
+            let path = std::env::temp_dir().join(format!("csd-{}.sock", &token[..12]));
+            let listener = tokio::net::UnixListener::bind(&path)
+                .context(format!("Failed to bind {}", path.display()))?;
+            return Ok(Self {
+                inner: Inner::Unix(listener, path),
+                token,
+            });
+        }
+        let _ = tcp;
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .context("Failed to bind a localhost port")?;
+        Ok(Self {
+            inner: Inner::Tcp(listener),
+            token,
+        })
+    }
+
+    /// `CSD_SOCKET` for the plugin: `unix:<path>` or `tcp:127.0.0.1:<port>`
+    pub fn address(&self) -> Result<String> {
+        Ok(match &self.inner {
+            #[cfg(unix)]
+            Inner::Unix(_, path) => format!("unix:{}", path.display()),
+            Inner::Tcp(listener) => format!("tcp:{}", listener.local_addr()?),
+        })
+    }
+
+    /// The first connection that presents the token
+    pub async fn accept(&self) -> Result<Box<dyn Duplex>> {
+        loop {
+            let mut stream: Box<dyn Duplex> = match &self.inner {
+                #[cfg(unix)]
+                Inner::Unix(listener, _) => Box::new(listener.accept().await?.0),
+                Inner::Tcp(listener) => Box::new(listener.accept().await?.0),
+            };
+            let hello = tokio::time::timeout(
+                CONNECT_TIMEOUT,
+                read_frame_within(&mut stream, MAX_HELLO_BYTES),
+            )
+            .await;
+            let presented = match hello {
+                Ok(Ok(Some(frame))) => serde_json::from_slice::<serde_json::Value>(&frame)
+                    .ok()
+                    .and_then(|hello| hello["token"].as_str().map(str::to_string)),
+                _ => None,
+            };
+            if presented.is_some_and(|p| constant_time_eq(p.as_bytes(), self.token.as_bytes())) {
+                return Ok(stream);
+            }
+            log::warn!("Refused a plugin socket connection without the expected token");
+        }
+    }
+}
+
+impl Drop for PluginListener {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Inner::Unix(_, path) = &self.inner {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
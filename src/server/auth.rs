@@ -0,0 +1,114 @@
+// src/server/auth.rs - Bearer tokens and the roles they grant on the HTTP API
+use log::warn;
+
+use crate::utils::config::{ApiConfig, ApiRole};
+use crate::utils::secrets::constant_time_eq;
+
+/// Token with the `write` role, for setups that keep tokens out of config files entirely
+pub const API_TOKEN_ENV: &str = "CSD_API_TOKEN";
+
+/// Token with the `read` role
+pub const API_READ_TOKEN_ENV: &str = "CSD_API_READ_TOKEN";
+
+/// Why a request was turned away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthError {
+    // No token, or one nobody configured (401)
+    Unauthenticated,
+    // A valid token without the role or project the request needs (403)
+    Forbidden,
+}
+
+#[derive(Debug, Clone)]
+struct ApiToken {
+    name: String,
+    secret: String,
+    role: ApiRole,
+    projects: Vec<String>,
+}
+
+impl ApiToken {
+    fn reaches(&self, project: &str) -> bool {
+        self.projects.is_empty() || self.projects.iter().any(|id| id == project)
+    }
+}
+
+/// Checks requests against the configured tokens. With none configured every request
+/// is allowed, which is only safe on a loopback address.
+#[derive(Debug, Clone, Default)]
+pub struct Authenticator {
+    tokens: Vec<ApiToken>,
+}
+
+impl Authenticator {
+    /// Tokens from `api.tokens` plus `CSD_API_TOKEN` and `CSD_API_READ_TOKEN`. Empty
+    /// tokens, e.g. from an unset `${VAR}`, are skipped.
+    pub fn from_config(config: &ApiConfig) -> Self {
+        let mut tokens: Vec<ApiToken> = Vec::new();
+        for token in &config.tokens {
+            if token.token.trim().is_empty() {
+                warn!(
+                    "API token '{}' is empty and will not be accepted",
+                    token.name
+                );
+                continue;
+            }
+            tokens.push(ApiToken {
+                name: token.name.clone(),
+                secret: token.token.trim().to_string(),
+                role: token.role,
+                projects: token.projects.clone(),
+            });
+        }
+        for (env, role) in [
+            (API_TOKEN_ENV, ApiRole::Write),
+            (API_READ_TOKEN_ENV, ApiRole::Read),
+        ] {
+            if let Some(secret) = std::env::var(env).ok().filter(|s| !s.trim().is_empty()) {
+                tokens.push(ApiToken {
+                    name: env.to_string(),
+                    secret: secret.trim().to_string(),
+                    role,
+                    projects: Vec::new(),
+                });
+            }
+        }
+        Self { tokens }
+    }
+
+    /// Whether any token is required at all
+    pub fn is_open(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Check an `Authorization` header for `role` on `project` (`None` for requests
+    /// that span projects). Returns the token's name for the logs.
+    pub fn authorize(
+        &self,
+        header: Option<&str>,
+        role: ApiRole,
+        project: Option<&str>,
+    ) -> Result<Option<&str>, AuthError> {
+        if self.is_open() {
+            return Ok(None);
+        }
+        let presented = header
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .ok_or(AuthError::Unauthenticated)?;
+        let token = self
+            .tokens
+            .iter()
+            .find(|token| constant_time_eq(token.secret.as_bytes(), presented.as_bytes()))
+            .ok_or(AuthError::Unauthenticated)?;
+        if token.role < role || project.is_some_and(|id| !token.reaches(id)) {
+            return Err(AuthError::Forbidden);
+        }
+        Ok(Some(&token.name))
+    }
+
+    /// Whether the token in `header` may see `project`; used to filter listings
+    pub fn can_see(&self, header: Option<&str>, project: &str) -> bool {
+        self.authorize(header, ApiRole::Read, Some(project)).is_ok()
+    }
+}
@@ -0,0 +1,1462 @@
+// src/server/graphql.rs - A read-only GraphQL schema over a project's matrix, so dashboards
+// can fetch the nested files, elements and edges they need in one request. Queries are
+// parsed and executed here; mutations, subscriptions and introspection beyond
+// `__typename` aren't supported, `GET /graphql` serves the schema instead.
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::core::annotations::{Annotation, Annotations, Note};
+use crate::core::categories::FileCategory;
+use crate::core::matrix::{
+    CategoryRollup, CodeElement, ExternalDependency, FileNode, ProjectMatrix, ProjectMetrics,
+    Relationship, RelationshipType,
+};
+
+/// The schema, in SDL
+pub const SCHEMA: &str = r#"type Query {
+  project: Project!
+  metrics: Metrics!
+  # `path` is a glob over project-relative paths
+  files(path: String, language: String, plugin: String, category: String, first: Int, offset: Int): [File!]!
+  file(path: String!): File
+  elements(name: String, type: String, first: Int, offset: Int): [Element!]!
+  # `from` and `to` are globs over project-relative paths
+  relationships(from: String, to: String, type: String, first: Int, offset: Int): [Relationship!]!
+  dependencies(name: String, ecosystem: String, type: String): [Dependency!]!
+}
+
+type Project {
+  root: String!
+  scannedAt: String!
+  csdVersion: String!
+  totalFiles: Int!
+  totalSizeBytes: Int!
+  totalTokens: Int!
+  plugins: [String!]!
+}
+
+type Metrics {
+  totalFiles: Int!
+  totalRelationships: Int!
+  totalTokens: Int!
+  languages: [String!]!
+  highlyCoupled: [CoupledFile!]!
+  categories: [CategoryRollup!]!
+}
+
+type CoupledFile {
+  path: String!
+  dependents: Int!
+  file: File
+}
+
+type CategoryRollup {
+  category: String!
+  files: Int!
+  sizeBytes: Int!
+  tokens: Int!
+  averageComplexity: Float
+}
+
+type File {
+  path: String!
+  plugin: String!
+  language: String
+  category: String
+  sizeBytes: Int!
+  tokens: Int!
+  summary: String
+  truncated: Boolean!
+  imports: [String!]!
+  exports: [String!]!
+  elements(name: String, type: String, first: Int, offset: Int): [Element!]!
+  outgoing(type: String): [Relationship!]!
+  incoming(type: String): [Relationship!]!
+  annotation: Annotation
+}
+
+type Element {
+  id: String!
+  name: String!
+  qualifiedName: String
+  type: String!
+  parent: String
+  signature: String
+  startLine: Int!
+  endLine: Int!
+  summary: String
+  complexity: Int
+  tokens: Int!
+  calls: [String!]!
+  file: File!
+  annotation: Annotation
+}
+
+# Attached with `csd annotate`; kept outside the matrix
+type Annotation {
+  owner: String
+  # needs-review, changes-requested or approved
+  review: String
+  notes: [Note!]!
+}
+
+type Note {
+  text: String!
+  author: String
+  date: String!
+}
+
+type Relationship {
+  fromPath: String!
+  toPath: String!
+  # Null when the endpoint isn't a scanned file
+  from: File
+  to: File
+  type: String!
+  details: String!
+  line: Int
+  strength: Float!
+  fromElement: String
+  toElement: String
+  sourcePlugin: String
+}
+
+type Dependency {
+  name: String!
+  version: String
+  ecosystem: String!
+  type: String!
+  sourcePath: String!
+  file: File
+}
+"#;
+
+// Deeper queries are almost certainly walking the graph in circles
+const MAX_DEPTH: usize = 12;
+
+// Nesting multiplies: `outgoing { to { outgoing { ... } } }` or a few hundred aliased
+// copies of `files` stay within MAX_DEPTH and still resolve the graph many times over.
+// Queries are refused up front past the field caps, and abandoned once they resolve more
+// nodes or output than the budget allows.
+const MAX_ROOT_FIELDS: usize = 16;
+const MAX_ALIASES: usize = 32;
+const MAX_NODES: usize = 100_000;
+const MAX_OUTPUT_BYTES: usize = 32 * 1024 * 1024;
+
+/// How long a query may run before it's abandoned
+pub const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Body of `POST /graphql`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQLRequest {
+    pub query: String,
+    #[serde(default)]
+    pub variables: Option<Map<String, Value>>,
+    #[serde(default)]
+    pub operation_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphQLError {
+    pub message: String,
+    // Response keys leading to the field that failed
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub path: Vec<Value>,
+}
+
+/// `data` is absent when the query couldn't be run at all
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphQLResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphQLError>,
+}
+
+impl GraphQLResponse {
+    /// A response for a request that couldn't be run at all
+    pub fn failed(message: String) -> Self {
+        Self {
+            data: None,
+            errors: vec![GraphQLError {
+                message,
+                path: Vec::new(),
+            }],
+        }
+    }
+}
+
+/// Run a query against a matrix, the metrics calculated from it and the project's
+/// annotations
+pub fn execute(
+    matrix: &ProjectMatrix,
+    metrics: &ProjectMetrics,
+    annotations: &Annotations,
+    request: &GraphQLRequest,
+) -> GraphQLResponse {
+    let document = match Parser::new(&request.query).and_then(|mut p| p.document()) {
+        Ok(document) => document,
+        Err(e) => return GraphQLResponse::failed(format!("Syntax error: {e}")),
+    };
+    let operation = match document.operation(request.operation_name.as_deref()) {
+        Ok(operation) => operation,
+        Err(e) => return GraphQLResponse::failed(e.to_string()),
+    };
+    let variables = match operation.variables(request.variables.as_ref()) {
+        Ok(variables) => variables,
+        Err(e) => return GraphQLResponse::failed(e.to_string()),
+    };
+    let executor = Executor::new(matrix, metrics, annotations, &document.fragments, variables);
+    if let Err(e) = executor.validate("Query", &operation.selection, 0) {
+        return GraphQLResponse::failed(e.to_string());
+    }
+
+    let mut errors = Vec::new();
+    let data = executor.select(
+        Node::Query,
+        &operation.selection,
+        &mut Vec::new(),
+        &mut errors,
+    );
+    if let Some(reason) = executor.exhausted.take() {
+        return GraphQLResponse::failed(reason);
+    }
+    GraphQLResponse {
+        data: Some(data),
+        errors,
+    }
+}
+
+// --- Parsing ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Punct(char),
+    Spread,
+    Str(String),
+    Int(i64),
+    Float(f64),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            // Commas are insignificant in GraphQL
+            c if c.is_whitespace() || c == ',' || c == '\u{feff}' => i += 1,
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '{' | '}' | '(' | ')' | '[' | ']' | ':' | '$' | '!' | '=' | '@' | '|' | '&' => {
+                tokens.push(Token::Punct(c));
+                i += 1;
+            }
+            '.' => {
+                if chars.get(i + 1) != Some(&'.') || chars.get(i + 2) != Some(&'.') {
+                    return Err(anyhow!("Unexpected '.'"));
+                }
+                tokens.push(Token::Spread);
+                i += 3;
+            }
+            '"' => {
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None | Some('\n') => return Err(anyhow!("Unterminated string")),
+                        Some('"') => break,
+                        Some('\\') => {
+                            let escaped = match chars.get(i + 1) {
+                                Some('n') => '\n',
+                                Some('t') => '\t',
+                                Some('r') => '\r',
+                                Some('b') => '\u{8}',
+                                Some('f') => '\u{c}',
+                                Some('u') => {
+                                    let hex: String = chars.iter().skip(i + 2).take(4).collect();
+                                    i += 4;
+                                    u32::from_str_radix(&hex, 16)
+                                        .ok()
+                                        .and_then(char::from_u32)
+                                        .ok_or_else(|| anyhow!("Invalid escape \\u{hex}"))?
+                                }
+                                Some(&c @ ('"' | '\\' | '/')) => c,
+                                _ => return Err(anyhow!("Invalid escape in string")),
+                            };
+                            value.push(escaped);
+                            i += 2;
+                        }
+                        Some(&c) => {
+                            value.push(c);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::Str(value));
+                i += 1;
+            }
+            c if c == '-' || c.is_ascii_digit() => {
+                let start = i;
+                i += 1;
+                let mut float = false;
+                while let Some(&c) = chars.get(i) {
+                    let exponent_sign = (c == '+' || c == '-') && matches!(chars[i - 1], 'e' | 'E');
+                    if c == '.' || c == 'e' || c == 'E' {
+                        float = true;
+                    } else if !c.is_ascii_digit() && !exponent_sign {
+                        break;
+                    }
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let invalid = || anyhow!("Invalid number {text}");
+                tokens.push(if float {
+                    Token::Float(text.parse().map_err(|_| invalid())?)
+                } else {
+                    Token::Int(text.parse().map_err(|_| invalid())?)
+                });
+            }
+            c if c == '_' || c.is_ascii_alphabetic() => {
+                let start = i;
+                while chars
+                    .get(i)
+                    .is_some_and(|c| *c == '_' || c.is_ascii_alphanumeric())
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Name(chars[start..i].iter().collect()));
+            }
+            c => return Err(anyhow!("Unexpected character '{c}'")),
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum InputValue {
+    Variable(String),
+    Const(Value),
+    List(Vec<InputValue>),
+    Object(Vec<(String, InputValue)>),
+}
+
+impl InputValue {
+    fn resolve(&self, variables: &Map<String, Value>) -> Value {
+        match self {
+            InputValue::Variable(name) => variables.get(name).cloned().unwrap_or(Value::Null),
+            InputValue::Const(value) => value.clone(),
+            InputValue::List(items) => {
+                Value::Array(items.iter().map(|item| item.resolve(variables)).collect())
+            }
+            InputValue::Object(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.resolve(variables)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Directive {
+    name: String,
+    arguments: Vec<(String, InputValue)>,
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    alias: Option<String>,
+    name: String,
+    arguments: Vec<(String, InputValue)>,
+    directives: Vec<Directive>,
+    selection: Vec<Selection>,
+}
+
+impl Field {
+    fn response_key(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Selection {
+    Field(Field),
+    Spread(String, Vec<Directive>),
+    // Type conditions are accepted but never narrow: the schema has no interfaces or
+    // unions, so a fragment can only apply to the type it's spread in
+    Inline(Vec<Directive>, Vec<Selection>),
+}
+
+#[derive(Debug, Clone)]
+struct VariableDefinition {
+    name: String,
+    // Declared with a `!` type
+    required: bool,
+    default: Option<InputValue>,
+}
+
+#[derive(Debug, Clone)]
+struct Operation {
+    name: Option<String>,
+    variables: Vec<VariableDefinition>,
+    selection: Vec<Selection>,
+}
+
+impl Operation {
+    fn variables(&self, provided: Option<&Map<String, Value>>) -> Result<Map<String, Value>> {
+        let mut variables = Map::new();
+        for definition in &self.variables {
+            let name = &definition.name;
+            match provided.and_then(|provided| provided.get(name)) {
+                Some(value) if !value.is_null() || !definition.required => {
+                    variables.insert(name.clone(), value.clone());
+                }
+                _ => match definition.default {
+                    Some(ref default) => {
+                        variables.insert(name.clone(), default.resolve(&Map::new()));
+                    }
+                    None if definition.required => {
+                        return Err(anyhow!("Variable ${name} is required"));
+                    }
+                    None => {}
+                },
+            }
+        }
+        Ok(variables)
+    }
+}
+
+struct Document {
+    operations: Vec<Operation>,
+    fragments: HashMap<String, Vec<Selection>>,
+}
+
+impl Document {
+    fn operation(&self, name: Option<&str>) -> Result<&Operation> {
+        match name {
+            Some(name) => self
+                .operations
+                .iter()
+                .find(|operation| operation.name.as_deref() == Some(name))
+                .ok_or_else(|| anyhow!("No operation named '{name}'")),
+            None => match self.operations.as_slice() {
+                [operation] => Ok(operation),
+                [] => Err(anyhow!("The document has no query")),
+                _ => Err(anyhow!(
+                    "The document has several operations; choose one with operationName"
+                )),
+            },
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Result<Self> {
+        Ok(Self {
+            tokens: tokenize(source)?,
+            pos: 0,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unexpected end of document"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn at(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punct(c))
+    }
+
+    fn at_name(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Name(n)) if n == name)
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        match self.next()? {
+            Token::Punct(p) if p == c => Ok(()),
+            other => Err(anyhow!("Expected '{c}', found {}", describe(&other))),
+        }
+    }
+
+    fn name(&mut self) -> Result<String> {
+        match self.next()? {
+            Token::Name(name) => Ok(name),
+            other => Err(anyhow!("Expected a name, found {}", describe(&other))),
+        }
+    }
+
+    fn document(&mut self) -> Result<Document> {
+        let mut document = Document {
+            operations: Vec::new(),
+            fragments: HashMap::new(),
+        };
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Punct('{') => document.operations.push(Operation {
+                    name: None,
+                    variables: Vec::new(),
+                    selection: self.selection_set(0)?,
+                }),
+                Token::Name(keyword) if keyword == "query" => {
+                    self.pos += 1;
+                    document.operations.push(self.operation()?);
+                }
+                Token::Name(keyword) if keyword == "mutation" || keyword == "subscription" => {
+                    return Err(anyhow!("Only queries are supported; the API is read-only"))
+                }
+                Token::Name(keyword) if keyword == "fragment" => {
+                    self.pos += 1;
+                    let name = self.name()?;
+                    if !self.at_name("on") {
+                        return Err(anyhow!("Expected 'on' after fragment {name}"));
+                    }
+                    self.pos += 1;
+                    self.name()?;
+                    self.directives()?;
+                    let selection = self.selection_set(0)?;
+                    if document.fragments.insert(name.clone(), selection).is_some() {
+                        return Err(anyhow!("Fragment {name} is defined twice"));
+                    }
+                }
+                other => return Err(anyhow!("Unexpected {}", describe(other))),
+            }
+        }
+        Ok(document)
+    }
+
+    fn operation(&mut self) -> Result<Operation> {
+        let name = match self.peek() {
+            Some(Token::Name(_)) => Some(self.name()?),
+            _ => None,
+        };
+        let mut variables = Vec::new();
+        if self.at('(') {
+            self.pos += 1;
+            while !self.at(')') {
+                self.expect('$')?;
+                let name = self.name()?;
+                self.expect(':')?;
+                let required = self.type_reference(0)?;
+                let default = if self.at('=') {
+                    self.pos += 1;
+                    Some(self.value(true, 0)?)
+                } else {
+                    None
+                };
+                variables.push(VariableDefinition {
+                    name,
+                    required,
+                    default,
+                });
+            }
+            self.pos += 1;
+        }
+        self.directives()?;
+        Ok(Operation {
+            name,
+            variables,
+            selection: self.selection_set(0)?,
+        })
+    }
+
+    // Whether the type is non-null. Variable types aren't checked otherwise; arguments
+    // are checked where they're used.
+    fn type_reference(&mut self, depth: usize) -> Result<bool> {
+        if self.at('[') {
+            self.pos += 1;
+            self.type_reference(nested(depth)?)?;
+            self.expect(']')?;
+        } else {
+            self.name()?;
+        }
+        if self.at('!') {
+            self.pos += 1;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    // The depth guards recursion here, before the executor's own checks ever see the
+    // document: a few kilobytes of brackets would otherwise overflow the stack
+    fn selection_set(&mut self, depth: usize) -> Result<Vec<Selection>> {
+        self.expect('{')?;
+        let mut selection = Vec::new();
+        while !self.at('}') {
+            if self.peek() == Some(&Token::Spread) {
+                self.pos += 1;
+                if self.at_name("on") || self.at('{') || self.at('@') {
+                    if self.at_name("on") {
+                        self.pos += 1;
+                        self.name()?;
+                    }
+                    let directives = self.directives()?;
+                    selection.push(Selection::Inline(
+                        directives,
+                        self.selection_set(nested(depth)?)?,
+                    ));
+                } else {
+                    let name = self.name()?;
+                    selection.push(Selection::Spread(name, self.directives()?));
+                }
+                continue;
+            }
+            let mut name = self.name()?;
+            let mut alias = None;
+            if self.at(':') {
+                self.pos += 1;
+                alias = Some(name);
+                name = self.name()?;
+            }
+            let arguments = self.arguments()?;
+            let directives = self.directives()?;
+            let sub_selection = if self.at('{') {
+                self.selection_set(nested(depth)?)?
+            } else {
+                Vec::new()
+            };
+            selection.push(Selection::Field(Field {
+                alias,
+                name,
+                arguments,
+                directives,
+                selection: sub_selection,
+            }));
+        }
+        self.pos += 1;
+        if selection.is_empty() {
+            return Err(anyhow!("Empty selection set"));
+        }
+        Ok(selection)
+    }
+
+    fn arguments(&mut self) -> Result<Vec<(String, InputValue)>> {
+        let mut arguments = Vec::new();
+        if self.at('(') {
+            self.pos += 1;
+            while !self.at(')') {
+                let name = self.name()?;
+                self.expect(':')?;
+                arguments.push((name, self.value(false, 0)?));
+            }
+            self.pos += 1;
+        }
+        Ok(arguments)
+    }
+
+    fn directives(&mut self) -> Result<Vec<Directive>> {
+        let mut directives = Vec::new();
+        while self.at('@') {
+            self.pos += 1;
+            directives.push(Directive {
+                name: self.name()?,
+                arguments: self.arguments()?,
+            });
+        }
+        Ok(directives)
+    }
+
+    fn value(&mut self, constant: bool, depth: usize) -> Result<InputValue> {
+        Ok(match self.next()? {
+            Token::Punct('$') if !constant => InputValue::Variable(self.name()?),
+            Token::Int(i) => InputValue::Const(Value::from(i)),
+            Token::Float(f) => InputValue::Const(Value::from(f)),
+            Token::Str(s) => InputValue::Const(Value::String(s)),
+            Token::Name(name) => InputValue::Const(match name.as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" => Value::Null,
+                // Enum values; the schema takes them wherever it takes a type name
+                _ => Value::String(name),
+            }),
+            Token::Punct('[') => {
+                let mut items = Vec::new();
+                while !self.at(']') {
+                    items.push(self.value(constant, nested(depth)?)?);
+                }
+                self.pos += 1;
+                InputValue::List(items)
+            }
+            Token::Punct('{') => {
+                let mut fields = Vec::new();
+                while !self.at('}') {
+                    let name = self.name()?;
+                    self.expect(':')?;
+                    fields.push((name, self.value(constant, nested(depth)?)?));
+                }
+                self.pos += 1;
+                InputValue::Object(fields)
+            }
+            other => return Err(anyhow!("Expected a value, found {}", describe(&other))),
+        })
+    }
+}
+
+fn nested(depth: usize) -> Result<usize> {
+    if depth >= MAX_DEPTH {
+        return Err(anyhow!("The query is nested more than {MAX_DEPTH} levels"));
+    }
+    Ok(depth + 1)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Name(name) => format!("'{name}'"),
+        Token::Punct(c) => format!("'{c}'"),
+        Token::Spread => "'...'".to_string(),
+        Token::Str(s) => format!("\"{s}\""),
+        Token::Int(i) => i.to_string(),
+        Token::Float(f) => f.to_string(),
+    }
+}
+
+// --- Execution ---
+
+// The output type of each field; object types need a selection, scalars can't have one
+fn field_type(parent: &str, field: &str) -> Option<&'static str> {
+    Some(match (parent, field) {
+        (_, "__typename") => "String",
+        ("Query", "project") => "Project",
+        ("Query", "metrics") => "Metrics",
+        ("Query", "files" | "file") => "File",
+        ("Query", "elements") => "Element",
+        ("Query", "relationships") => "Relationship",
+        ("Query", "dependencies") => "Dependency",
+        (
+            "Project",
+            "root" | "scannedAt" | "csdVersion" | "totalFiles" | "totalSizeBytes" | "totalTokens"
+            | "plugins",
+        ) => "String",
+        ("Metrics", "totalFiles" | "totalRelationships" | "totalTokens" | "languages") => "String",
+        ("Metrics", "highlyCoupled") => "CoupledFile",
+        ("Metrics", "categories") => "CategoryRollup",
+        ("CoupledFile", "path" | "dependents") => "String",
+        ("CoupledFile", "file") => "File",
+        ("CategoryRollup", "category" | "files" | "sizeBytes" | "tokens" | "averageComplexity") => {
+            "String"
+        }
+        (
+            "File",
+            "path" | "plugin" | "language" | "category" | "sizeBytes" | "tokens" | "summary"
+            | "truncated" | "imports" | "exports",
+        ) => "String",
+        ("File", "elements") => "Element",
+        ("File", "outgoing" | "incoming") => "Relationship",
+        (
+            "Element",
+            "id" | "name" | "qualifiedName" | "type" | "parent" | "signature" | "startLine"
+            | "endLine" | "summary" | "complexity" | "tokens" | "calls",
+        ) => "String",
+        ("Element", "file") => "File",
+        ("File" | "Element", "annotation") => "Annotation",
+        ("Annotation", "owner" | "review") => "String",
+        ("Annotation", "notes") => "Note",
+        ("Note", "text" | "author" | "date") => "String",
+        (
+            "Relationship",
+            "fromPath" | "toPath" | "type" | "details" | "line" | "strength" | "fromElement"
+            | "toElement" | "sourcePlugin",
+        ) => "String",
+        ("Relationship", "from" | "to") => "File",
+        ("Dependency", "name" | "version" | "ecosystem" | "type" | "sourcePath") => "String",
+        ("Dependency", "file") => "File",
+        _ => return None,
+    })
+}
+
+#[derive(Clone, Copy)]
+enum Node<'a> {
+    Query,
+    Project,
+    Metrics,
+    CoupledFile(&'a Path, usize),
+    Category(FileCategory, &'a CategoryRollup),
+    File(&'a FileNode),
+    Element(&'a FileNode, &'a CodeElement),
+    Relationship(&'a Relationship),
+    Dependency(&'a ExternalDependency),
+    Annotation(&'a Annotation),
+    Note(&'a Note),
+}
+
+impl Node<'_> {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Node::Query => "Query",
+            Node::Project => "Project",
+            Node::Metrics => "Metrics",
+            Node::CoupledFile(..) => "CoupledFile",
+            Node::Category(..) => "CategoryRollup",
+            Node::File(_) => "File",
+            Node::Element(..) => "Element",
+            Node::Relationship(_) => "Relationship",
+            Node::Dependency(_) => "Dependency",
+            Node::Annotation(_) => "Annotation",
+            Node::Note(_) => "Note",
+        }
+    }
+}
+
+enum Resolved<'a> {
+    Value(Value),
+    Node(Option<Node<'a>>),
+    Nodes(Vec<Node<'a>>),
+}
+
+impl From<Value> for Resolved<'_> {
+    fn from(value: Value) -> Self {
+        Resolved::Value(value)
+    }
+}
+
+// A field's arguments, with variables substituted
+struct Args(Map<String, Value>);
+
+impl Args {
+    fn string(&self, name: &str) -> Result<Option<&str>> {
+        match self.0.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s)),
+            Some(other) => Err(anyhow!("Argument '{name}' must be a string, not {other}")),
+        }
+    }
+
+    fn int(&self, name: &str) -> Result<Option<usize>> {
+        match self.0.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => value
+                .as_u64()
+                .map(|n| Some(n as usize))
+                .ok_or_else(|| anyhow!("Argument '{name}' must be a non-negative integer")),
+        }
+    }
+
+    fn glob(&self, name: &str) -> Result<Option<glob::Pattern>> {
+        self.string(name)?
+            .map(|pattern| {
+                glob::Pattern::new(pattern)
+                    .map_err(|e| anyhow!("Argument '{name}' is not a valid glob: {e}"))
+            })
+            .transpose()
+    }
+
+    fn relationship_type(&self) -> Result<Option<RelationshipType>> {
+        self.string("type")?
+            .map(RelationshipType::from_str)
+            .transpose()
+    }
+
+    // `offset` then `first`, applied after filtering
+    fn page<'a>(&self, nodes: impl Iterator<Item = Node<'a>>) -> Result<Resolved<'a>> {
+        let offset = self.int("offset")?.unwrap_or(0);
+        let nodes = nodes.skip(offset);
+        Ok(Resolved::Nodes(match self.int("first")? {
+            Some(first) => nodes.take(first).collect(),
+            None => nodes.collect(),
+        }))
+    }
+}
+
+// Serialized name of a unit enum variant, e.g. `Function`
+fn variant_name(value: &impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+fn matches_name(actual: &Value, wanted: Option<&str>) -> bool {
+    wanted.is_none_or(|wanted| {
+        actual
+            .as_str()
+            .is_some_and(|actual| actual.eq_ignore_ascii_case(wanted))
+    })
+}
+
+struct Executor<'a> {
+    matrix: &'a ProjectMatrix,
+    metrics: &'a ProjectMetrics,
+    annotations: &'a Annotations,
+    fragments: &'a HashMap<String, Vec<Selection>>,
+    variables: Map<String, Value>,
+    // Files in path order, and the edges leaving and entering each
+    files: Vec<&'a FileNode>,
+    by_relative: HashMap<&'a Path, &'a FileNode>,
+    outgoing: HashMap<&'a Path, Vec<&'a Relationship>>,
+    incoming: HashMap<&'a Path, Vec<&'a Relationship>>,
+    // Counted by `validate`, against MAX_ALIASES
+    aliases: Cell<usize>,
+    // Spent by `select`: nodes resolved and bytes of output, roughly
+    nodes: Cell<usize>,
+    output_bytes: Cell<usize>,
+    deadline: Instant,
+    // Why `select` gave up, once it has
+    exhausted: RefCell<Option<String>>,
+}
+
+impl<'a> Executor<'a> {
+    fn new(
+        matrix: &'a ProjectMatrix,
+        metrics: &'a ProjectMetrics,
+        annotations: &'a Annotations,
+        fragments: &'a HashMap<String, Vec<Selection>>,
+        variables: Map<String, Value>,
+    ) -> Self {
+        let mut files: Vec<&FileNode> = matrix.files.values().collect();
+        files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+        let by_relative = files
+            .iter()
+            .map(|file| (file.relative_path.as_path(), *file))
+            .collect();
+        let mut outgoing: HashMap<&Path, Vec<&Relationship>> = HashMap::new();
+        let mut incoming: HashMap<&Path, Vec<&Relationship>> = HashMap::new();
+        for relationship in &matrix.relationships {
+            outgoing
+                .entry(relationship.from_file.as_path())
+                .or_default()
+                .push(relationship);
+            incoming
+                .entry(relationship.to_file.as_path())
+                .or_default()
+                .push(relationship);
+        }
+        Self {
+            matrix,
+            metrics,
+            annotations,
+            fragments,
+            variables,
+            files,
+            by_relative,
+            outgoing,
+            incoming,
+            aliases: Cell::new(0),
+            nodes: Cell::new(0),
+            output_bytes: Cell::new(0),
+            deadline: Instant::now() + QUERY_TIMEOUT,
+            exhausted: RefCell::new(None),
+        }
+    }
+
+    // Spend from the budget; false once the query is over it, which ends `select`
+    fn charge(&self, nodes: usize, output_bytes: usize) -> bool {
+        if self.exhausted.borrow().is_some() {
+            return false;
+        }
+        self.nodes.set(self.nodes.get() + nodes);
+        self.output_bytes
+            .set(self.output_bytes.get() + output_bytes);
+        let reason = if self.nodes.get() > MAX_NODES {
+            format!("The query resolves more than {MAX_NODES} nodes; page or narrow it")
+        } else if self.output_bytes.get() > MAX_OUTPUT_BYTES {
+            format!("The query's result is over {MAX_OUTPUT_BYTES} bytes; page or narrow it")
+        } else if Instant::now() > self.deadline {
+            format!(
+                "The query ran for more than {} seconds",
+                QUERY_TIMEOUT.as_secs()
+            )
+        } else {
+            return true;
+        };
+        *self.exhausted.borrow_mut() = Some(reason);
+        false
+    }
+
+    // Fields by response key, in first-seen order, with fragments expanded and
+    // `@skip`/`@include` applied. Fields sharing a key merge their selections.
+    fn collect<'s>(&'s self, selection: &'s [Selection]) -> Result<Vec<(&'s str, Vec<&'s Field>)>> {
+        let mut fields: Vec<(&str, Vec<&Field>)> = Vec::new();
+        self.collect_into(selection, &mut fields, 0)?;
+        Ok(fields)
+    }
+
+    fn collect_into<'s>(
+        &'s self,
+        selection: &'s [Selection],
+        fields: &mut Vec<(&'s str, Vec<&'s Field>)>,
+        depth: usize,
+    ) -> Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(anyhow!(
+                "Fragments are nested too deeply or spread in a cycle"
+            ));
+        }
+        for item in selection {
+            match item {
+                Selection::Field(field) => {
+                    if !self.included(&field.directives)? {
+                        continue;
+                    }
+                    let key = field.response_key();
+                    match fields.iter_mut().find(|(k, _)| *k == key) {
+                        Some((_, same)) => {
+                            if same[0].name != field.name {
+                                return Err(anyhow!(
+                                    "'{key}' selects both '{}' and '{}'; alias one of them",
+                                    same[0].name,
+                                    field.name
+                                ));
+                            }
+                            same.push(field);
+                        }
+                        None => fields.push((key, vec![field])),
+                    }
+                }
+                Selection::Spread(name, directives) => {
+                    if !self.included(directives)? {
+                        continue;
+                    }
+                    let fragment = self
+                        .fragments
+                        .get(name)
+                        .ok_or_else(|| anyhow!("Unknown fragment {name}"))?;
+                    self.collect_into(fragment, fields, depth + 1)?;
+                }
+                Selection::Inline(directives, selection) => {
+                    if self.included(directives)? {
+                        self.collect_into(selection, fields, depth + 1)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn included(&self, directives: &[Directive]) -> Result<bool> {
+        for directive in directives {
+            let condition = directive
+                .arguments
+                .iter()
+                .find(|(name, _)| name == "if")
+                .map(|(_, value)| value.resolve(&self.variables));
+            let condition = match condition {
+                Some(Value::Bool(condition)) => condition,
+                _ => return Err(anyhow!("@{} needs a Boolean 'if' argument", directive.name)),
+            };
+            match directive.name.as_str() {
+                "skip" if condition => return Ok(false),
+                "include" if !condition => return Ok(false),
+                "skip" | "include" => {}
+                other => return Err(anyhow!("Unknown directive @{other}")),
+            }
+        }
+        Ok(true)
+    }
+
+    // Check every field exists and has a selection exactly when it returns an object,
+    // before anything runs
+    fn validate(&self, parent: &str, selection: &[Selection], depth: usize) -> Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(anyhow!("The query is nested more than {MAX_DEPTH} levels"));
+        }
+        let collected = self.collect(selection)?;
+        if depth == 0 && collected.len() > MAX_ROOT_FIELDS {
+            return Err(anyhow!(
+                "The query selects more than {MAX_ROOT_FIELDS} root fields"
+            ));
+        }
+        for (key, fields) in collected {
+            let aliased = fields.iter().filter(|field| field.alias.is_some()).count();
+            self.aliases.set(self.aliases.get() + aliased);
+            if self.aliases.get() > MAX_ALIASES {
+                return Err(anyhow!("The query uses more than {MAX_ALIASES} aliases"));
+            }
+            let field = fields[0];
+            let output = field_type(parent, &field.name)
+                .ok_or_else(|| anyhow!("Cannot query field '{}' on type '{parent}'", field.name))?;
+            let sub_selection: Vec<Selection> = fields
+                .iter()
+                .flat_map(|field| field.selection.iter().cloned())
+                .collect();
+            match (output, sub_selection.is_empty()) {
+                ("String", false) => {
+                    return Err(anyhow!(
+                        "Field '{key}' is a scalar and can't have a selection"
+                    ))
+                }
+                ("String", true) => {}
+                (_, true) => {
+                    return Err(anyhow!(
+                        "Field '{key}' of type '{output}' needs a selection of subfields"
+                    ))
+                }
+                (_, false) => self.validate(output, &sub_selection, depth + 1)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn select(
+        &self,
+        node: Node<'a>,
+        selection: &[Selection],
+        path: &mut Vec<Value>,
+        errors: &mut Vec<GraphQLError>,
+    ) -> Value {
+        let mut object = Map::new();
+        if self.exhausted.borrow().is_some() {
+            return Value::Null;
+        }
+        // `validate` already collected this selection without errors
+        for (key, fields) in self.collect(selection).unwrap_or_default() {
+            path.push(Value::String(key.to_string()));
+            let field = fields[0];
+            let args = Args(
+                field
+                    .arguments
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.resolve(&self.variables)))
+                    .collect(),
+            );
+            let sub_selection: Vec<Selection> = fields
+                .iter()
+                .flat_map(|field| field.selection.iter().cloned())
+                .collect();
+            let value = match self.resolve(node, &field.name, &args) {
+                Ok(Resolved::Value(value)) => {
+                    // Key, quotes and separators aside, as it will be serialized
+                    let size = key.len() + value.to_string().len();
+                    if !self.charge(0, size) {
+                        break;
+                    }
+                    value
+                }
+                Ok(Resolved::Node(None)) => Value::Null,
+                Ok(Resolved::Node(Some(child))) => {
+                    if !self.charge(1, key.len()) {
+                        break;
+                    }
+                    self.select(child, &sub_selection, path, errors)
+                }
+                Ok(Resolved::Nodes(children)) => {
+                    if !self.charge(children.len(), key.len()) {
+                        break;
+                    }
+                    let mut items = Vec::with_capacity(children.len());
+                    for (index, child) in children.into_iter().enumerate() {
+                        if self.exhausted.borrow().is_some() {
+                            break;
+                        }
+                        path.push(Value::from(index));
+                        items.push(self.select(child, &sub_selection, path, errors));
+                        path.pop();
+                    }
+                    Value::Array(items)
+                }
+                Err(e) => {
+                    errors.push(GraphQLError {
+                        message: e.to_string(),
+                        path: path.clone(),
+                    });
+                    Value::Null
+                }
+            };
+            path.pop();
+            object.insert(key.to_string(), value);
+        }
+        Value::Object(object)
+    }
+
+    fn file(&self, path: &Path) -> Option<Node<'a>> {
+        self.matrix
+            .files
+            .get(path)
+            .or_else(|| self.by_relative.get(path).copied())
+            .map(Node::File)
+    }
+
+    // Project-relative form of a path from the matrix's edges or dependencies
+    fn relative(&self, path: &Path) -> Value {
+        let path = self
+            .matrix
+            .files
+            .get(path)
+            .map(|file| file.relative_path.as_path())
+            .unwrap_or(path);
+        Value::String(path.to_string_lossy().replace('\\', "/"))
+    }
+
+    fn elements(&self, files: &[&'a FileNode], args: &Args) -> Result<Resolved<'a>> {
+        let name = args.string("name")?;
+        let element_type = args.string("type")?;
+        args.page(files.iter().copied().flat_map(|file| {
+            file.elements
+                .iter()
+                .filter(move |element| {
+                    name.is_none_or(|name| {
+                        element.name == name || element.qualified_name.as_deref() == Some(name)
+                    }) && matches_name(&variant_name(&element.element_type), element_type)
+                })
+                .map(move |element| Node::Element(file, element))
+        }))
+    }
+
+    fn edges(&self, edges: Option<&Vec<&'a Relationship>>, args: &Args) -> Result<Resolved<'a>> {
+        let wanted = args.relationship_type()?;
+        Ok(Resolved::Nodes(
+            edges
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|r| wanted.as_ref().is_none_or(|t| r.relationship_type == *t))
+                .map(Node::Relationship)
+                .collect(),
+        ))
+    }
+
+    fn resolve(&self, node: Node<'a>, field: &str, args: &Args) -> Result<Resolved<'a>> {
+        if field == "__typename" {
+            return Ok(Value::from(node.type_name()).into());
+        }
+        let matrix = self.matrix;
+        let metadata = &matrix.metadata;
+        Ok(match (node, field) {
+            (Node::Query, "project") => Resolved::Node(Some(Node::Project)),
+            (Node::Query, "metrics") => Resolved::Node(Some(Node::Metrics)),
+            (Node::Query, "files") => {
+                let path = args.glob("path")?;
+                let language = args.string("language")?;
+                let plugin = args.string("plugin")?;
+                let category = args.string("category")?;
+                return args.page(
+                    self.files
+                        .iter()
+                        .copied()
+                        .filter(|file| {
+                            path.as_ref()
+                                .is_none_or(|pattern| pattern.matches_path(&file.relative_path))
+                                && language.is_none_or(|language| {
+                                    file.language
+                                        .as_deref()
+                                        .is_some_and(|l| l.eq_ignore_ascii_case(language))
+                                })
+                                && plugin.is_none_or(|plugin| &*file.plugin == plugin)
+                                && category.is_none_or(|category| {
+                                    file.category
+                                        .is_some_and(|c| c.as_str().eq_ignore_ascii_case(category))
+                                })
+                        })
+                        .map(Node::File),
+                );
+            }
+            (Node::Query, "file") => {
+                let path = args
+                    .string("path")?
+                    .ok_or_else(|| anyhow!("Argument 'path' is required"))?;
+                Resolved::Node(
+                    self.by_relative
+                        .get(Path::new(path))
+                        .copied()
+                        .map(Node::File),
+                )
+            }
+            (Node::Query, "elements") => return self.elements(&self.files, args),
+            (Node::Query, "relationships") => {
+                let from = args.glob("from")?;
+                let to = args.glob("to")?;
+                let wanted = args.relationship_type()?;
+                let matches = |pattern: &Option<glob::Pattern>, path: &Path| {
+                    pattern.as_ref().is_none_or(|pattern| {
+                        pattern.matches(self.relative(path).as_str().unwrap_or_default())
+                    })
+                };
+                return args.page(
+                    matrix
+                        .relationships
+                        .iter()
+                        .filter(|r| {
+                            matches(&from, &r.from_file)
+                                && matches(&to, &r.to_file)
+                                && wanted.as_ref().is_none_or(|t| r.relationship_type == *t)
+                        })
+                        .map(Node::Relationship),
+                );
+            }
+            (Node::Query, "dependencies") => {
+                let name = args.string("name")?;
+                let ecosystem = args.string("ecosystem")?;
+                let dependency_type = args.string("type")?;
+                Resolved::Nodes(
+                    matrix
+                        .external_dependencies
+                        .iter()
+                        .filter(|d| {
+                            name.is_none_or(|name| d.name == name)
+                                && ecosystem.is_none_or(|e| d.ecosystem.eq_ignore_ascii_case(e))
+                                && matches_name(&variant_name(&d.dependency_type), dependency_type)
+                        })
+                        .map(Node::Dependency)
+                        .collect(),
+                )
+            }
+
+            (Node::Project, "root") => {
+                Value::from(metadata.project_root.to_string_lossy().to_string()).into()
+            }
+            (Node::Project, "scannedAt") => {
+                Value::from(metadata.scan_timestamp.to_rfc3339()).into()
+            }
+            (Node::Project, "csdVersion") => Value::from(metadata.csd_version.clone()).into(),
+            (Node::Project, "totalFiles") => Value::from(metadata.total_files).into(),
+            (Node::Project, "totalSizeBytes") => Value::from(metadata.total_size_bytes).into(),
+            (Node::Project, "totalTokens") => Value::from(metadata.total_tokens).into(),
+            (Node::Project, "plugins") => Value::from(metadata.plugin_names()).into(),
+
+            (Node::Metrics, "totalFiles") => Value::from(self.metrics.total_files).into(),
+            (Node::Metrics, "totalRelationships") => {
+                Value::from(self.metrics.total_relationships).into()
+            }
+            (Node::Metrics, "totalTokens") => Value::from(self.metrics.total_tokens).into(),
+            (Node::Metrics, "languages") => Value::from(self.metrics.languages.clone()).into(),
+            (Node::Metrics, "highlyCoupled") => Resolved::Nodes(
+                self.metrics
+                    .highly_coupled_files
+                    .iter()
+                    .map(|(path, dependents)| Node::CoupledFile(path, *dependents))
+                    .collect(),
+            ),
+            (Node::Metrics, "categories") => Resolved::Nodes(
+                self.metrics
+                    .categories
+                    .iter()
+                    .map(|(category, rollup)| Node::Category(*category, rollup))
+                    .collect(),
+            ),
+
+            (Node::CoupledFile(path, _), "path") => self.relative(path).into(),
+            (Node::CoupledFile(_, dependents), "dependents") => Value::from(dependents).into(),
+            (Node::CoupledFile(path, _), "file") => Resolved::Node(self.file(path)),
+
+            (Node::Category(category, _), "category") => Value::from(category.as_str()).into(),
+            (Node::Category(_, rollup), "files") => Value::from(rollup.files).into(),
+            (Node::Category(_, rollup), "sizeBytes") => Value::from(rollup.size_bytes).into(),
+            (Node::Category(_, rollup), "tokens") => Value::from(rollup.tokens).into(),
+            (Node::Category(_, rollup), "averageComplexity") => {
+                Value::from(rollup.average_complexity).into()
+            }
+
+            (Node::File(file), "path") => self.relative(&file.path).into(),
+            (Node::File(file), "plugin") => Value::from(&*file.plugin).into(),
+            (Node::File(file), "language") => Value::from(file.language.as_deref()).into(),
+            (Node::File(file), "category") => Value::from(file.category.map(|c| c.as_str())).into(),
+            (Node::File(file), "sizeBytes") => Value::from(file.size_bytes).into(),
+            (Node::File(file), "tokens") => Value::from(file.token_info.total_tokens).into(),
+            (Node::File(file), "summary") => Value::from(file.file_summary.clone()).into(),
+            (Node::File(file), "truncated") => Value::from(file.truncated).into(),
+            (Node::File(file), "imports") => Value::from(
+                file.imports
+                    .iter()
+                    .map(|import| import.module.to_string())
+                    .collect::<Vec<_>>(),
+            )
+            .into(),
+            (Node::File(file), "exports") => Value::from(
+                file.exports
+                    .iter()
+                    .map(|export| export.exported_name().to_string())
+                    .collect::<Vec<_>>(),
+            )
+            .into(),
+            (Node::File(file), "elements") => return self.elements(&[file], args),
+            (Node::File(file), "outgoing") => {
+                return self.edges(self.outgoing.get(file.path.as_path()), args)
+            }
+            (Node::File(file), "incoming") => {
+                return self.edges(self.incoming.get(file.path.as_path()), args)
+            }
+            (Node::File(file), "annotation") => Resolved::Node(
+                self.annotations
+                    .file(&file.relative_path)
+                    .map(Node::Annotation),
+            ),
+
+            (Node::Element(_, element), "id") => Value::from(element.element_id.clone()).into(),
+            (Node::Element(_, element), "name") => Value::from(element.name.clone()).into(),
+            (Node::Element(_, element), "qualifiedName") => {
+                Value::from(element.qualified_name.clone()).into()
+            }
+            (Node::Element(_, element), "type") => variant_name(&element.element_type).into(),
+            (Node::Element(_, element), "parent") => Value::from(element.parent.clone()).into(),
+            (Node::Element(_, element), "signature") => {
+                Value::from(element.signature.clone()).into()
+            }
+            (Node::Element(_, element), "startLine") => Value::from(element.line_start).into(),
+            (Node::Element(_, element), "endLine") => Value::from(element.line_end).into(),
+            (Node::Element(_, element), "summary") => Value::from(element.summary.clone()).into(),
+            (Node::Element(_, element), "complexity") => {
+                Value::from(element.complexity_score).into()
+            }
+            (Node::Element(_, element), "tokens") => Value::from(element.tokens).into(),
+            (Node::Element(_, element), "calls") => Value::from(element.calls.clone()).into(),
+            (Node::Element(file, _), "file") => Resolved::Node(Some(Node::File(file))),
+            (Node::Element(_, element), "annotation") => Resolved::Node(
+                self.annotations
+                    .element(&element.element_id)
+                    .map(|element| Node::Annotation(&element.annotation)),
+            ),
+
+            (Node::Annotation(a), "owner") => Value::from(a.owner.clone()).into(),
+            (Node::Annotation(a), "review") => {
+                Value::from(a.review.map(|review| review.to_string())).into()
+            }
+            (Node::Annotation(a), "notes") => {
+                Resolved::Nodes(a.notes.iter().map(Node::Note).collect())
+            }
+            (Node::Note(note), "text") => Value::from(note.text.clone()).into(),
+            (Node::Note(note), "author") => Value::from(note.author.clone()).into(),
+            (Node::Note(note), "date") => Value::from(note.date.to_rfc3339()).into(),
+
+            (Node::Relationship(r), "fromPath") => self.relative(&r.from_file).into(),
+            (Node::Relationship(r), "toPath") => self.relative(&r.to_file).into(),
+            (Node::Relationship(r), "from") => Resolved::Node(self.file(&r.from_file)),
+            (Node::Relationship(r), "to") => Resolved::Node(self.file(&r.to_file)),
+            (Node::Relationship(r), "type") => variant_name(&r.relationship_type).into(),
+            (Node::Relationship(r), "details") => Value::from(r.details.clone()).into(),
+            (Node::Relationship(r), "line") => Value::from(r.line_number).into(),
+            (Node::Relationship(r), "strength") => Value::from(r.strength).into(),
+            (Node::Relationship(r), "fromElement") => Value::from(r.from_element.clone()).into(),
+            (Node::Relationship(r), "toElement") => Value::from(r.to_element.clone()).into(),
+            (Node::Relationship(r), "sourcePlugin") => {
+                Value::from(r.source_plugin.as_deref()).into()
+            }
+
+            (Node::Dependency(d), "name") => Value::from(d.name.clone()).into(),
+            (Node::Dependency(d), "version") => Value::from(d.version.clone()).into(),
+            (Node::Dependency(d), "ecosystem") => Value::from(&*d.ecosystem).into(),
+            (Node::Dependency(d), "type") => variant_name(&d.dependency_type).into(),
+            (Node::Dependency(d), "sourcePath") => self.relative(&d.source_file).into(),
+            (Node::Dependency(d), "file") => Resolved::Node(self.file(&d.source_file)),
+
+            (node, field) => {
+                return Err(anyhow!(
+                    "Cannot query field '{field}' on type '{}'",
+                    node.type_name()
+                ))
+            }
+        })
+    }
+}
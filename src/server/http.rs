@@ -0,0 +1,464 @@
+// src/server/http.rs - JSON endpoints over each project's matrix, its history, the
+// changes found between scans, its annotations and the daemon's scan status, a GraphQL
+// endpoint over the matrix, plus a write endpoint to request a scan
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use hyper::body::HttpBody;
+use hyper::header::{HeaderValue, AUTHORIZATION, WWW_AUTHENTICATE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::{Mutex, Notify, RwLock};
+
+use crate::core::annotations::Annotations;
+use crate::core::events::EventLog;
+use crate::core::history::MetricsHistory;
+use crate::core::matrix::{ProjectMatrix, ProjectMetrics};
+use crate::server::auth::{AuthError, Authenticator};
+use crate::server::graphql::{self, GraphQLRequest, GraphQLResponse};
+use crate::utils::config::ApiRole;
+
+/// One scan the daemon ran
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanRun {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    // Whether the scan and its --fail-on gate passed
+    pub success: bool,
+    // "passed", or why the scan or gate failed
+    pub gate: String,
+    // HEAD when the scan ran, in a git checkout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+/// What `GET /status` reports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub started_at: DateTime<Utc>,
+    pub scanning: bool,
+    pub scans: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_scan: Option<ScanRun>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_scan: Option<DateTime<Utc>>,
+}
+
+impl Default for DaemonStatus {
+    fn default() -> Self {
+        Self {
+            started_at: Utc::now(),
+            scanning: false,
+            scans: 0,
+            last_scan: None,
+            next_scan: None,
+        }
+    }
+}
+
+/// What one project's endpoints read from; the daemon updates `status` as it scans
+pub struct ProjectState {
+    pub id: String,
+    pub matrix_path: PathBuf,
+    pub cache_dir: PathBuf,
+    // Where the annotations file lives; without it projects have no annotations
+    pub root: Option<PathBuf>,
+    pub status: RwLock<DaemonStatus>,
+    // Set by `POST .../scan`; the daemon scans and clears it
+    pub rescan: AtomicBool,
+    // Parsed for GraphQL queries, until the scan rewrites the file
+    loaded: Mutex<Option<LoadedMatrix>>,
+}
+
+struct LoadedMatrix {
+    modified: SystemTime,
+    matrix: Arc<ProjectMatrix>,
+    metrics: Arc<ProjectMetrics>,
+}
+
+impl ProjectState {
+    pub fn new(id: &str, matrix_path: PathBuf, cache_dir: PathBuf) -> Self {
+        Self {
+            id: id.to_string(),
+            matrix_path,
+            cache_dir,
+            root: None,
+            status: RwLock::new(DaemonStatus::default()),
+            rescan: AtomicBool::new(false),
+            loaded: Mutex::new(None),
+        }
+    }
+
+    pub fn with_root(mut self, root: PathBuf) -> Self {
+        self.root = Some(root);
+        self
+    }
+
+    /// Annotations are read on every request: `csd annotate` edits them between scans
+    fn annotations(&self) -> Result<Annotations> {
+        match self.root {
+            Some(ref root) => Annotations::load(root),
+            None => Ok(Annotations::default()),
+        }
+    }
+
+    /// The matrix and its metrics, parsed again only when the file changed
+    async fn matrix(&self) -> Result<(Arc<ProjectMatrix>, Arc<ProjectMetrics>)> {
+        let modified = tokio::fs::metadata(&self.matrix_path).await?.modified()?;
+        let mut loaded = self.loaded.lock().await;
+        if let Some(ref current) = *loaded {
+            if current.modified == modified {
+                return Ok((current.matrix.clone(), current.metrics.clone()));
+            }
+        }
+        let mut matrix = ProjectMatrix::load(&self.matrix_path).await?;
+        let metrics = Arc::new(matrix.calculate_metrics());
+        let matrix = Arc::new(matrix);
+        *loaded = Some(LoadedMatrix {
+            modified,
+            matrix: matrix.clone(),
+            metrics: metrics.clone(),
+        });
+        Ok((matrix, metrics))
+    }
+}
+
+// Largest GraphQL request body accepted
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// The projects served. Each answers under `/projects/{id}/`; a lone project also
+/// answers at the top level.
+pub struct ServerState {
+    pub projects: Vec<Arc<ProjectState>>,
+    pub auth: Authenticator,
+    // Notified when a scan is requested, so the daemon needn't wait out its sleep
+    pub wake: Notify,
+}
+
+impl ServerState {
+    /// Open to every caller until `with_auth`
+    pub fn new(projects: Vec<Arc<ProjectState>>) -> Self {
+        Self {
+            projects,
+            auth: Authenticator::default(),
+            wake: Notify::new(),
+        }
+    }
+
+    pub fn with_auth(mut self, auth: Authenticator) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    fn project(&self, id: &str) -> Option<&ProjectState> {
+        self.projects
+            .iter()
+            .find(|project| project.id == id)
+            .map(|project| project.as_ref())
+    }
+}
+
+/// A bound listener, ready to serve
+pub struct ApiServer {
+    listener: std::net::TcpListener,
+    state: Arc<ServerState>,
+}
+
+impl ApiServer {
+    /// Bind `addr`; port 0 picks a free port, see `local_addr`
+    pub fn bind(addr: SocketAddr, state: Arc<ServerState>) -> Result<Self> {
+        let listener = std::net::TcpListener::bind(addr)
+            .with_context(|| format!("Failed to listen on {addr}"))?;
+        listener.set_nonblocking(true)?;
+        Ok(Self { listener, state })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serve requests until the task is dropped
+    pub async fn run(self) -> Result<()> {
+        let state = self.state;
+        let make_service = make_service_fn(move |_| {
+            let state = state.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let state = state.clone();
+                    async move { Ok::<_, Infallible>(route(&state, request).await) }
+                }))
+            }
+        });
+        hyper::Server::from_tcp(self.listener)?
+            .serve(make_service)
+            .await
+            .context("HTTP API stopped")
+    }
+}
+
+async fn route(state: &ServerState, request: Request<Body>) -> Response<Body> {
+    let (request, body) = request.into_parts();
+    let path = request.uri.path().trim_end_matches('/');
+    let method = &request.method;
+    // Load balancers probe this without a token
+    if path == "/health" {
+        if method != Method::GET {
+            return error(StatusCode::METHOD_NOT_ALLOWED, "Use GET");
+        }
+        return json(
+            StatusCode::OK,
+            &serde_json::json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }),
+        );
+    }
+
+    // Authenticate before anything else, so unknown routes and project ids reveal
+    // nothing to callers without a token
+    let authorization = request
+        .headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    if let Err(e) = state.auth.authorize(authorization, ApiRole::Read, None) {
+        return denied(e);
+    }
+
+    if path == "/projects" {
+        if method != Method::GET {
+            return error(StatusCode::METHOD_NOT_ALLOWED, "Use GET");
+        }
+        let mut projects = Vec::new();
+        for project in &state.projects {
+            if state.auth.can_see(authorization, &project.id) {
+                projects.push(serde_json::json!({
+                    "id": project.id,
+                    "status": *project.status.read().await,
+                }));
+            }
+        }
+        return json(StatusCode::OK, &projects);
+    }
+    let (id, endpoint) = match path.strip_prefix("/projects/") {
+        Some(rest) => rest.split_once('/').unwrap_or((rest, "")),
+        None => match state.projects.as_slice() {
+            [project] => (project.id.as_str(), path.trim_start_matches('/')),
+            _ => {
+                return error(
+                    StatusCode::NOT_FOUND,
+                    "Unknown endpoint; projects answer under /projects/{id}/",
+                )
+            }
+        },
+    };
+
+    let role = match (method, endpoint) {
+        (&Method::GET, "status" | "matrix" | "history" | "events" | "annotations" | "graphql") => {
+            ApiRole::Read
+        }
+        // GraphQL queries only read, whatever the method
+        (&Method::POST, "graphql") => ApiRole::Read,
+        (&Method::POST, "scan") => ApiRole::Write,
+        (_, "graphql") => return error(StatusCode::METHOD_NOT_ALLOWED, "Use POST"),
+        (_, "scan") => return error(StatusCode::METHOD_NOT_ALLOWED, "Use POST"),
+        (_, "status" | "matrix" | "history" | "events" | "annotations") => {
+            return error(StatusCode::METHOD_NOT_ALLOWED, "Use GET")
+        }
+        _ => return error(StatusCode::NOT_FOUND, "Unknown endpoint"),
+    };
+    // Authorized against the requested id before looking it up, so a token scoped to one
+    // project can't tell which other ids exist
+    match state.auth.authorize(authorization, role, Some(id)) {
+        Ok(token) => {
+            if role == ApiRole::Write {
+                info!(
+                    "{} {path} by {}",
+                    method,
+                    token.unwrap_or("an unauthenticated caller")
+                );
+            }
+        }
+        Err(e) => return denied(e),
+    }
+    let Some(project) = state.project(id) else {
+        return error(StatusCode::NOT_FOUND, &format!("No project '{id}'"));
+    };
+    let query = request.uri.query().unwrap_or_default();
+    project_route(state, project, method, endpoint, query, body).await
+}
+
+async fn project_route(
+    state: &ServerState,
+    project: &ProjectState,
+    method: &Method,
+    endpoint: &str,
+    query: &str,
+    body: Body,
+) -> Response<Body> {
+    match endpoint {
+        // The schema, for clients and code generators
+        "graphql" if method == Method::GET => Response::builder()
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(Body::from(graphql::SCHEMA))
+            .unwrap_or_default(),
+        "graphql" => graphql_route(project, body).await,
+        "status" => json(StatusCode::OK, &*project.status.read().await),
+        // Served as stored rather than parsed and re-encoded: matrices can be large
+        "matrix" => match tokio::fs::read(&project.matrix_path).await {
+            Ok(bytes) => Response::builder()
+                .header("Content-Type", "application/json")
+                .body(Body::from(bytes))
+                .unwrap_or_default(),
+            Err(_) => error(
+                StatusCode::NOT_FOUND,
+                "No matrix yet; the first scan is running",
+            ),
+        },
+        "history" => match MetricsHistory::new(&project.cache_dir).load().await {
+            Ok(snapshots) => json(StatusCode::OK, &snapshots),
+            Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &format!("{e:#}")),
+        },
+        // `?since=<RFC 3339 time>` for pollers that already have the older events
+        "events" => {
+            let since = query_param(query, "since")
+                .map(|since| {
+                    DateTime::parse_from_rfc3339(&since).map(|since| since.with_timezone(&Utc))
+                })
+                .transpose();
+            match since {
+                Ok(since) => match EventLog::new(&project.cache_dir).load(since).await {
+                    Ok(events) => json(StatusCode::OK, &events),
+                    Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &format!("{e:#}")),
+                },
+                Err(e) => error(
+                    StatusCode::BAD_REQUEST,
+                    &format!("'since' must be an RFC 3339 time: {e}"),
+                ),
+            }
+        }
+        "annotations" => match project.annotations() {
+            Ok(annotations) => json(StatusCode::OK, &annotations),
+            Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &format!("{e:#}")),
+        },
+        "scan" => {
+            project.rescan.store(true, Ordering::SeqCst);
+            state.wake.notify_one();
+            json(
+                StatusCode::ACCEPTED,
+                &serde_json::json!({ "status": "queued" }),
+            )
+        }
+        _ => error(StatusCode::NOT_FOUND, "Unknown endpoint"),
+    }
+}
+
+async fn graphql_route(project: &ProjectState, body: Body) -> Response<Body> {
+    let Some(bytes) = read_body(body).await else {
+        return error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            &format!("GraphQL requests are limited to {MAX_BODY_BYTES} bytes"),
+        );
+    };
+    let request: GraphQLRequest = match serde_json::from_slice(&bytes) {
+        Ok(request) => request,
+        Err(e) => {
+            let response = GraphQLResponse::failed(format!("Invalid request body: {e}"));
+            return json(StatusCode::BAD_REQUEST, &response);
+        }
+    };
+    let (matrix, metrics) = match project.matrix().await {
+        Ok(loaded) => loaded,
+        Err(_) => {
+            return error(
+                StatusCode::NOT_FOUND,
+                "No matrix yet; the first scan is running",
+            )
+        }
+    };
+    let annotations = match project.annotations() {
+        Ok(annotations) => annotations,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, &format!("{e:#}")),
+    };
+    // Deep queries over large matrices take a while; keep them off the I/O threads. The
+    // executor stops itself at the same deadline, so the thread isn't left running.
+    let response = tokio::time::timeout(
+        graphql::QUERY_TIMEOUT,
+        tokio::task::spawn_blocking(move || {
+            graphql::execute(&matrix, &metrics, &annotations, &request)
+        }),
+    )
+    .await;
+    match response {
+        Ok(Ok(response)) if response.data.is_some() => json(StatusCode::OK, &response),
+        Ok(Ok(response)) => json(StatusCode::BAD_REQUEST, &response),
+        Ok(Err(e)) => error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+        Err(_) => error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &format!(
+                "The query ran for more than {} seconds",
+                graphql::QUERY_TIMEOUT.as_secs()
+            ),
+        ),
+    }
+}
+
+// The whole body, or None once it exceeds MAX_BODY_BYTES
+async fn read_body(mut body: Body) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.ok()?;
+        if bytes.len() + chunk.len() > MAX_BODY_BYTES {
+            return None;
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Some(bytes)
+}
+
+// A percent-decoded query string parameter
+fn query_param(query: &str, name: &str) -> Option<String> {
+    let url = reqwest::Url::parse(&format!("http://localhost/?{query}")).ok()?;
+    url.query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+fn denied(e: AuthError) -> Response<Body> {
+    match e {
+        AuthError::Unauthenticated => {
+            let mut response = error(StatusCode::UNAUTHORIZED, "A valid bearer token is required");
+            response
+                .headers_mut()
+                .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            response
+        }
+        AuthError::Forbidden => error(
+            StatusCode::FORBIDDEN,
+            "The token's role or projects don't allow this",
+        ),
+    }
+}
+
+fn json(status: StatusCode, body: &impl Serialize) -> Response<Body> {
+    match serde_json::to_vec(body) {
+        Ok(bytes) => Response::builder()
+            .status(status)
+            .header("Content-Type", "application/json")
+            .body(Body::from(bytes))
+            .unwrap_or_default(),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> Response<Body> {
+    let body = serde_json::json!({ "error": message }).to_string();
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body))
+        .unwrap_or_default()
+}
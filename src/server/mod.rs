@@ -0,0 +1,5 @@
+// src/server/mod.rs - The HTTP API `csd daemon` serves over the matrices it maintains
+pub mod auth;
+pub mod graphql;
+pub mod http;
+pub mod registry;
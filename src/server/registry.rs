@@ -0,0 +1,173 @@
+// src/server/registry.rs - Projects one csd service scans and serves: local directories,
+// or git repositories it keeps checked out next to the registry file
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::utils::config::{find_config_file, find_global_config_file, global_config_dir, Config};
+use crate::utils::file_utils::write_atomic_sync;
+use crate::utils::git::{clone, fast_forward};
+
+/// Registry file in the user configuration directory
+pub const REGISTRY_FILE: &str = "registry.yaml";
+
+// Checkouts of git projects, next to the registry file
+const CHECKOUTS_DIR: &str = "checkouts";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisteredProject {
+    // Names the project in `/projects/{id}/...` routes
+    pub id: String,
+    // An absolute directory, or a git URL
+    pub source: String,
+    // Branch to check out of a git source; the remote's default when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    // Config file relative to the project root; a `.csdrc.*` there when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<PathBuf>,
+}
+
+impl RegisteredProject {
+    /// Whether the source is a git URL rather than a directory
+    pub fn is_remote(&self) -> bool {
+        self.source.contains("://") || self.source.starts_with("git@")
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectRegistry {
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(default)]
+    pub projects: Vec<RegisteredProject>,
+}
+
+impl ProjectRegistry {
+    /// `<user config dir>/registry.yaml`
+    pub fn default_path() -> Option<PathBuf> {
+        global_config_dir().map(|dir| dir.join(REGISTRY_FILE))
+    }
+
+    /// The registry at `path`; empty when the file doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut registry: Self = if path.exists() {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            serde_yaml::from_str(&content)
+                .with_context(|| format!("Invalid registry {}", path.display()))?
+        } else {
+            Self::default()
+        };
+        registry.path = path.to_path_buf();
+        Ok(registry)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        write_atomic_sync(&self.path, serde_yaml::to_string(self)?.as_bytes())
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self, id: &str) -> Option<&RegisteredProject> {
+        self.projects.iter().find(|project| project.id == id)
+    }
+
+    /// Register a project. Ids must be unique and URL-safe; a directory source must
+    /// exist and is stored as an absolute path.
+    pub fn add(&mut self, mut project: RegisteredProject) -> Result<()> {
+        let url_safe = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+        if project.id.is_empty() || !project.id.chars().all(url_safe) {
+            return Err(anyhow::anyhow!(
+                "Project id '{}' may only use letters, digits, '-' and '_'",
+                project.id
+            ));
+        }
+        if self.get(&project.id).is_some() {
+            return Err(anyhow::anyhow!(
+                "A project '{}' is already registered",
+                project.id
+            ));
+        }
+        if !project.is_remote() {
+            let dir = Path::new(&project.source);
+            project.source = dir
+                .canonicalize()
+                .with_context(|| format!("No project directory {}", dir.display()))?
+                .to_string_lossy()
+                .to_string();
+        }
+        self.projects.push(project);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<RegisteredProject> {
+        let index = self
+            .projects
+            .iter()
+            .position(|project| project.id == id)
+            .with_context(|| format!("No project '{id}' is registered"))?;
+        Ok(self.projects.remove(index))
+    }
+
+    /// Where the project's files are: its directory, or its checkout
+    pub fn root(&self, project: &RegisteredProject) -> PathBuf {
+        if project.is_remote() {
+            self.path
+                .parent()
+                .unwrap_or(Path::new("."))
+                .join(CHECKOUTS_DIR)
+                .join(&project.id)
+        } else {
+            PathBuf::from(&project.source)
+        }
+    }
+
+    /// Bring a git project's checkout up to date, cloning it the first time. Directory
+    /// projects are left as they are.
+    pub fn update(&self, project: &RegisteredProject, offline: bool) -> Result<PathBuf> {
+        let root = self.root(project);
+        if !project.is_remote() {
+            return Ok(root);
+        }
+        if offline {
+            if !root.exists() {
+                return Err(anyhow::anyhow!(
+                    "Offline mode forbids cloning project '{}'",
+                    project.id
+                ));
+            }
+            return Ok(root);
+        }
+        if root.exists() {
+            fast_forward(&root)?;
+        } else {
+            if let Some(parent) = root.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            clone(&project.source, &root, project.branch.as_deref())?;
+        }
+        Ok(root)
+    }
+}
+
+/// The project's own configuration over the user's global one, as `csd` run in the
+/// project's directory would load it
+pub async fn project_config(project: &RegisteredProject, root: &Path) -> Result<Config> {
+    let project_path = match project.config {
+        Some(ref path) => Some(root.join(path)),
+        None => find_config_file(root),
+    };
+    Config::load_layered(
+        find_global_config_file().as_deref(),
+        project_path.as_deref(),
+    )
+    .await
+    .with_context(|| format!("Failed to load the configuration of '{}'", project.id))
+}
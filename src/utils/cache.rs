@@ -0,0 +1,147 @@
+// src/utils/cache.rs - Per-run scratch directories and locks for the shared cache
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Subdirectory of the cache holding one scratch directory per run
+pub const RUNS_DIR: &str = "runs";
+
+// Held by a run for its whole lifetime; a run directory whose lock can be taken is stale
+const RUN_LOCK: &str = ".lock";
+
+// Run directories without a lock file are only removed once they're this old, since a
+// run may be between creating its directory and locking it
+const UNLOCKED_RUN_GRACE: Duration = Duration::from_secs(3600);
+
+/// `<cache>/runs/<run id>`
+pub fn run_dir(cache_dir: &Path, run_id: Uuid) -> PathBuf {
+    cache_dir.join(RUNS_DIR).join(run_id.to_string())
+}
+
+/// Scratch directory for one scan or docs run. Plugin input, progress and result files
+/// go here, so runs sharing a cache can neither collide on file names nor clean up each
+/// other's files. The directory stays locked while the run is alive and is removed when
+/// the run is dropped.
+pub struct CacheRun {
+    id: Uuid,
+    dir: PathBuf,
+    _lock: File,
+}
+
+impl CacheRun {
+    pub fn create(cache_dir: &Path, id: Uuid) -> Result<Self> {
+        let dir = run_dir(cache_dir, id);
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create run directory {}", dir.display()))?;
+        let lock = open_lock_file(&dir.join(RUN_LOCK))?;
+        lock.try_lock()
+            .with_context(|| format!("Run directory {} is already in use", dir.display()))?;
+        Ok(Self {
+            id,
+            dir,
+            _lock: lock,
+        })
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Drop for CacheRun {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+            log::debug!("Could not remove run directory {}: {e}", self.dir.display());
+        }
+    }
+}
+
+/// Remove run directories left behind by runs that crashed or were killed. Live runs
+/// hold their directory's lock and are left alone. Returns how many were removed.
+pub fn prune_stale_runs(cache_dir: &Path) -> Result<usize> {
+    let runs_dir = cache_dir.join(RUNS_DIR);
+    if !runs_dir.is_dir() {
+        return Ok(0);
+    }
+
+    let mut removed = 0;
+    for entry in std::fs::read_dir(&runs_dir)? {
+        let dir = entry?.path();
+        if !dir.is_dir() {
+            continue;
+        }
+
+        let lock_path = dir.join(RUN_LOCK);
+        let stale = if lock_path.exists() {
+            // Dropped once the check is done, before the directory goes away
+            let lock = open_lock_file(&lock_path)?;
+            lock.try_lock().is_ok()
+        } else {
+            std::fs::metadata(&dir)?
+                .modified()?
+                .elapsed()
+                .is_ok_and(|age| age > UNLOCKED_RUN_GRACE)
+        };
+        if stale && std::fs::remove_dir_all(&dir).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Advisory lock guarding a cache file that several runs read and write, held until
+/// dropped. The lock lives on a `.<name>.lock` sibling so the file itself can still be
+/// replaced atomically. Writers lock exclusively and readers shared, so a reader never
+/// sees a backup being swapped in or an append in progress.
+pub struct CacheLock {
+    _file: File,
+}
+
+impl CacheLock {
+    pub async fn exclusive(path: &Path) -> Result<Self> {
+        Self::acquire(path, true).await
+    }
+
+    pub async fn shared(path: &Path) -> Result<Self> {
+        Self::acquire(path, false).await
+    }
+
+    async fn acquire(path: &Path, exclusive: bool) -> Result<Self> {
+        let lock_path = lock_path(path);
+        tokio::task::spawn_blocking(move || {
+            if let Some(parent) = lock_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let file = open_lock_file(&lock_path)?;
+            if exclusive {
+                file.lock()?;
+            } else {
+                file.lock_shared()?;
+            }
+            Ok(Self { _file: file })
+        })
+        .await
+        .context("Cache lock task panicked")?
+    }
+}
+
+/// Sibling lock file of a cache file, e.g. `.matrix.json.lock`
+pub fn lock_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{name}.lock"))
+}
+
+fn open_lock_file(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+        .with_context(|| format!("Failed to open lock file {}", path.display()))
+}
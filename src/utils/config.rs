@@ -1,25 +1,113 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use log::{debug, warn};
+
+use crate::core::categories::FileCategory;
+use crate::utils::file_utils::write_atomic;
+use crate::utils::hashing::HashAlgorithm;
+use crate::utils::secrets::{interpolate_config_values, REDACTED};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Cache directory used when neither `CSD_CACHE_DIR` nor `cache_dir` is set
+pub const DEFAULT_CACHE_DIR: &str = ".csd_cache";
+
+/// Environment variable overriding the configured cache directory
+pub const CACHE_DIR_ENV: &str = "CSD_CACHE_DIR";
+
+/// Configuration file names searched in order when no `--config` is given
+pub const CONFIG_FILE_NAMES: &[&str] = &[".csdrc.yaml", ".csdrc.yml", ".csdrc.toml", ".csdrc.json"];
+
+/// Top-level keys that are still accepted but should be rewritten by `csd config migrate`
+pub const DEPRECATED_KEYS: &[(&str, &str)] =
+    &[("plugins", "split into `input_plugins` and `output_plugins`")];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub output_dir: String,
     pub llm: LlmConfig,
     pub scanning: ScanConfig,
+    #[serde(default)]
     pub input_plugins: HashMap<String, InputPluginConfig>, // NEW: Separated plugin types
+    #[serde(default)]
     pub output_plugins: HashMap<String, OutputPluginConfig>, // NEW: Output plugins
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub transform_plugins: HashMap<String, TransformPluginConfig>,
+    // Custom quality checks run by `csd quality` alongside the built-in ones
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub rule_plugins: HashMap<String, RulePluginConfig>,
     pub python_executable: Option<String>,
+    // Interpreter for plugins with `runtime: node`; "node" when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_executable: Option<String>,
+    // WASI runner for plugins with `runtime: wasm`; "wasmtime" when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wasm_executable: Option<String>,
+
+    // Forbid network access: remote plugin sources and LLM endpoints off this machine
+    // are errors instead of being reached; `--offline` turns this on too
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub offline: bool,
+
+    // Commands run at pipeline stages; see `utils::hooks`
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+
+    #[serde(default, skip_serializing_if = "DocsConfig::is_empty")]
+    pub docs: DocsConfig,
+
+    // What `csd export --redact` strips or hashes
+    #[serde(default, skip_serializing_if = "RedactionConfig::is_default")]
+    pub redaction: RedactionConfig,
+
+    // Size limits for the hotspot check in `csd quality` and the docs
+    #[serde(default, skip_serializing_if = "QualityConfig::is_default")]
+    pub quality: QualityConfig,
+
+    // Domain terms collected into the matrix for the docs glossary; see `core::glossary`
+    #[serde(default, skip_serializing_if = "GlossaryConfig::is_default")]
+    pub glossary: GlossaryConfig,
+
+    // Where `csd debt --verify` looks up issues referenced by TODO markers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issue_trackers: Vec<IssueTrackerConfig>,
+
+    // Who may use the HTTP API of `csd daemon`; see `server::auth`
+    #[serde(default, skip_serializing_if = "ApiConfig::is_empty")]
+    pub api: ApiConfig,
+
+    // Webhooks told when `scan` and `docs` finish; see `utils::notify`
+    #[serde(default, skip_serializing_if = "NotificationsConfig::is_empty")]
+    pub notifications: NotificationsConfig,
+
+    // Transcripts of plugin stdout/stderr under `<cache>/logs`; see `plugins::logs`
+    #[serde(default, skip_serializing_if = "PluginLogConfig::is_default")]
+    pub plugin_logs: PluginLogConfig,
+
+    // Processes kept running for plugins that can answer many messages; see `PluginPool`
+    #[serde(default, skip_serializing_if = "PluginPoolConfig::is_default")]
+    pub plugin_pool: PluginPoolConfig,
+
+    // Extra file category rules, checked before the built-in ones; see `core::categories`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<CategoryRule>,
+
+    // Cache root: a path (relative to the project root) or "xdg"; see `resolve_cache_dir`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<String>,
 
     // Legacy field for backward compatibility
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub plugins: Option<HashMap<String, LegacyPluginConfig>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InputPluginConfig {
     pub source: PluginSource,
+    #[serde(default, skip_serializing_if = "PluginRuntime::is_default")]
+    pub runtime: PluginRuntime,
     pub file_patterns: FilePatterns,
     pub enabled: bool,
     pub config: Option<serde_yaml::Value>, // Plugin-specific configuration
@@ -28,12 +116,496 @@ pub struct InputPluginConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OutputPluginConfig {
     pub source: PluginSource,
+    #[serde(default, skip_serializing_if = "PluginRuntime::is_default")]
+    pub runtime: PluginRuntime,
     pub output_types: Vec<String>, // e.g., ["documentation", "quality_report"]
     pub formats: Vec<String>,      // e.g., ["markdown", "html", "pdf"]
     pub enabled: bool,
     pub config: Option<serde_yaml::Value>, // Plugin-specific configuration
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformPluginConfig {
+    pub source: PluginSource,
+    #[serde(default, skip_serializing_if = "PluginRuntime::is_default")]
+    pub runtime: PluginRuntime,
+    pub enabled: bool,
+    // Transforms run in ascending order, ties broken by name
+    #[serde(default)]
+    pub order: i32,
+    pub config: Option<serde_yaml::Value>, // Plugin-specific configuration
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulePluginConfig {
+    pub source: PluginSource,
+    #[serde(default, skip_serializing_if = "PluginRuntime::is_default")]
+    pub runtime: PluginRuntime,
+    pub enabled: bool,
+    // Paths or globs; the plugin gets a matrix of just these files. The whole matrix
+    // when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scope: Vec<String>,
+    pub config: Option<serde_yaml::Value>, // Plugin-specific configuration
+}
+
+/// Documentation generation settings shared by all output plugins
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocsConfig {
+    // Language codes (e.g. "en", "de", "ja"); each gets its own generation pass and
+    // output subdirectory. Empty means a single pass in the plugin's default language.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+    // What to do with files already in the output directory before generating
+    #[serde(default, skip_serializing_if = "OverwritePolicy::is_default")]
+    pub overwrite: OverwritePolicy,
+}
+
+impl DocsConfig {
+    pub fn is_empty(&self) -> bool {
+        self.languages.is_empty() && self.overwrite.is_default()
+    }
+}
+
+/// Handling of existing files in the docs output directory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverwritePolicy {
+    // Refuse to generate into a non-empty output directory
+    Fail,
+    #[default]
+    Overwrite,
+    // Copy existing files under `.csd_backup/<timestamp>/` first
+    Backup,
+}
+
+impl OverwritePolicy {
+    pub fn is_default(&self) -> bool {
+        *self == OverwritePolicy::default()
+    }
+}
+
+/// Redaction rules for matrices shared outside the organization
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactionConfig {
+    // File and element summaries
+    #[serde(default = "default_true")]
+    pub strip_summaries: bool,
+    #[serde(default = "default_true")]
+    pub strip_signatures: bool,
+    // Plugin metadata on files and elements, content hashes and relationship details
+    #[serde(default = "default_true")]
+    pub strip_metadata: bool,
+    // Replace project paths (and local import modules) with salted hashes
+    #[serde(default)]
+    pub hash_paths: bool,
+    // Without one, `csd export` generates a salt and keeps it in the cache directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+    // Globs (relative to the project root) left readable when hashing paths
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keep_paths: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            strip_summaries: true,
+            strip_signatures: true,
+            strip_metadata: true,
+            hash_paths: false,
+            salt: None,
+            keep_paths: Vec::new(),
+        }
+    }
+}
+
+impl RedactionConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Thresholds for the oversized files and functions report
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QualityConfig {
+    pub max_file_tokens: u64,
+    pub max_function_lines: u32,
+    pub max_function_complexity: u32,
+    // Offenders listed per kind; counts always cover all of them
+    pub top: usize,
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self {
+            max_file_tokens: 8000,
+            max_function_lines: 80,
+            max_function_complexity: 15,
+            top: 10,
+        }
+    }
+}
+
+impl QualityConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Which domain terms make it into the glossary candidates
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GlossaryConfig {
+    pub enabled: bool,
+    pub max_terms: usize,
+    // Terms found in fewer files are left out as noise
+    pub min_files: usize,
+    // Terms always kept when they occur, whatever their score
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    // Terms never kept
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+
+impl Default for GlossaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_terms: 40,
+            min_files: 2,
+            allow: Vec::new(),
+            deny: Vec::new(),
+        }
+    }
+}
+
+impl GlossaryConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// An issue tracker that issue keys in code comments can be checked against
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssueTrackerConfig {
+    pub kind: TrackerKind,
+    // API base URL; GitHub and Linear default to their public APIs, Jira has none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    // `owner/repo` that `#123` keys refer to (GitHub)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    // Key prefixes this tracker owns, e.g. `["PAY", "ENG"]` (Jira, Linear); empty
+    // means every `ABC-123` style key
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<String>,
+    // Environment variable holding the API token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackerKind {
+    #[serde(rename = "github")]
+    GitHub,
+    Jira,
+    Linear,
+}
+
+/// Bearer tokens accepted by the HTTP API. They belong in the user configuration or
+/// come from `${ENV}` and `${keyring:NAME}` references, never a committed project config.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tokens: Vec<ApiTokenConfig>,
+}
+
+impl ApiConfig {
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiTokenConfig {
+    // Shown in logs instead of the token
+    pub name: String,
+    pub token: String,
+    #[serde(default)]
+    pub role: ApiRole,
+    // Registered project ids the token may reach; empty means all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<String>,
+}
+
+/// What a token may do. `write` includes `read`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiRole {
+    // Read the matrix, history and status
+    #[default]
+    Read,
+    // Also trigger scans
+    Write,
+}
+
+impl std::fmt::Display for ApiRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ApiRole::Read => "read",
+            ApiRole::Write => "write",
+        })
+    }
+}
+
+/// Where run summaries are posted
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+}
+
+impl NotificationsConfig {
+    pub fn is_empty(&self) -> bool {
+        self.webhooks.is_empty()
+    }
+
+    /// The webhooks subscribed to `command`, leaving out failure-only ones on success.
+    /// For `events`, success means no change was a warning.
+    pub fn webhooks_for(
+        &self,
+        command: NotifyCommand,
+        success: bool,
+    ) -> impl Iterator<Item = &WebhookConfig> {
+        self.webhooks.iter().filter(move |webhook| {
+            webhook.commands.contains(&command) && !(success && webhook.only_failures)
+        })
+    }
+}
+
+/// A webhook taking a Slack-compatible JSON body
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    // Environment variable holding the URL, which keeps the secret out of the config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_env: Option<String>,
+    #[serde(default = "default_notify_commands")]
+    pub commands: Vec<NotifyCommand>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub only_failures: bool,
+    // Artifacts are linked as `<link_base>/<path>`, e.g. a CI artifact browser
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_base: Option<String>,
+}
+
+fn default_notify_commands() -> Vec<NotifyCommand> {
+    vec![NotifyCommand::Scan, NotifyCommand::Docs]
+}
+
+impl WebhookConfig {
+    /// The URL, from the config or else from `url_env`
+    pub fn resolve_url(&self) -> Result<String> {
+        if let Some(ref url) = self.url {
+            return Ok(url.clone());
+        }
+        let name = self
+            .url_env
+            .as_deref()
+            .context("Webhook needs a url or url_env")?;
+        std::env::var(name).with_context(|| format!("Webhook URL variable {name} is not set"))
+    }
+
+    /// How to name this webhook in messages without revealing its URL
+    pub fn describe(&self) -> String {
+        match self.url_env {
+            Some(ref name) => format!("${name}"),
+            None => "webhook".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyCommand {
+    Scan,
+    Docs,
+    // Architectural changes `csd daemon` finds between scans
+    Events,
+}
+
+impl std::fmt::Display for NotifyCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            NotifyCommand::Scan => "scan",
+            NotifyCommand::Docs => "docs",
+            NotifyCommand::Events => "events",
+        })
+    }
+}
+
+/// Which plugin invocations leave a transcript, and how long transcripts are kept
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PluginLogConfig {
+    pub enabled: bool,
+    // Also log invocations that succeeded without writing to stderr
+    pub all_invocations: bool,
+    // Logs older than this are pruned; 0 keeps them regardless of age
+    pub max_age_days: u64,
+    // Newest logs kept per plugin; 0 means no limit
+    pub max_files_per_plugin: usize,
+}
+
+impl Default for PluginLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            all_invocations: false,
+            max_age_days: 7,
+            max_files_per_plugin: 500,
+        }
+    }
+}
+
+impl PluginLogConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// How many processes each persistent plugin may keep running during a scan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PluginPoolConfig {
+    // Off starts a process per message, as for plugins that aren't persistent
+    pub enabled: bool,
+    // Processes kept however long they sit idle
+    pub min_processes: usize,
+    // Processes running at once; 0 means one per CPU
+    pub max_processes: usize,
+    // Idle processes beyond `min_processes` are shut down after this long
+    pub idle_timeout_secs: u64,
+    // Socket plugins connect over localhost TCP rather than a Unix socket
+    pub tcp_sockets: bool,
+}
+
+impl Default for PluginPoolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_processes: 0,
+            max_processes: 0,
+            idle_timeout_secs: 60,
+            tcp_sockets: false,
+        }
+    }
+}
+
+impl PluginPoolConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn max_processes(&self) -> usize {
+        match self.max_processes {
+            0 => std::thread::available_parallelism().map_or(4, |n| n.get()),
+            max => max,
+        }
+    }
+
+    pub fn idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.idle_timeout_secs)
+    }
+}
+
+/// Globs assigning files to a category, e.g. `{ category: generated, patterns: ["gen/**"] }`.
+/// `vendored` marks third-party code that's scanned but not measured or documented.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryRule {
+    pub category: FileCategory,
+    pub patterns: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Commands registered per pipeline stage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HooksConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_scan: Vec<HookConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_scan: Vec<HookConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_docs: Vec<HookConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_docs: Vec<HookConfig>,
+}
+
+impl HooksConfig {
+    pub fn is_empty(&self) -> bool {
+        self.pre_scan.is_empty()
+            && self.post_scan.is_empty()
+            && self.pre_docs.is_empty()
+            && self.post_docs.is_empty()
+    }
+}
+
+/// One hook: a shell command, or a Python script run with the configured interpreter.
+/// A bare string is shorthand for `{ command: "..." }`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "HookEntry")]
+pub struct HookConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+    // By default a failing hook aborts the command that triggered it
+    #[serde(default)]
+    pub allow_failure: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HookEntry {
+    Command(String),
+    Full {
+        #[serde(default)]
+        command: Option<String>,
+        #[serde(default)]
+        plugin: Option<String>,
+        #[serde(default)]
+        allow_failure: bool,
+    },
+}
+
+impl From<HookEntry> for HookConfig {
+    fn from(entry: HookEntry) -> Self {
+        match entry {
+            HookEntry::Command(command) => Self {
+                command: Some(command),
+                ..Default::default()
+            },
+            HookEntry::Full {
+                command,
+                plugin,
+                allow_failure,
+            } => Self {
+                command,
+                plugin,
+                allow_failure,
+            },
+        }
+    }
+}
+
 // Legacy plugin config for backward compatibility
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LegacyPluginConfig {
@@ -71,12 +643,94 @@ pub enum PluginSource {
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl PluginSource {
+    /// Where the plugin comes from as one string, e.g. `builtin:python/python_analyzer`
+    /// or `github:owner/repo@v1.2.0`, recorded in matrices next to the plugin version
+    pub fn describe(&self) -> String {
+        match self {
+            PluginSource::Local { path } => format!("local:{path}"),
+            PluginSource::GitHub { repo, version } => match version {
+                Some(version) => format!("github:{repo}@{version}"),
+                None => format!("github:{repo}"),
+            },
+            PluginSource::Git { url, branch } => match branch {
+                Some(branch) => format!("git:{url}#{branch}"),
+                None => format!("git:{url}"),
+            },
+            PluginSource::Builtin { name, plugin_type } => format!("builtin:{plugin_type}/{name}"),
+        }
+    }
+}
+
+/// What a plugin's script is run with. Built-in plugins are all Python; `binary`
+/// plugins are native executables (or scripts with a shebang) started directly; `wasm`
+/// plugins are WASI modules run by `wasm_executable` with the working directory as
+/// their only filesystem access.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginRuntime {
+    #[default]
+    Python,
+    Node,
+    Binary,
+    Wasm,
+}
+
+impl PluginRuntime {
+    pub fn is_default(&self) -> bool {
+        *self == PluginRuntime::default()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PluginRuntime::Python => "python",
+            PluginRuntime::Node => "node",
+            PluginRuntime::Binary => "binary",
+            PluginRuntime::Wasm => "wasm",
+        }
+    }
+
+    /// The runtime a script path implies, for plugins run by path rather than from the
+    /// configuration: `.js`, `.mjs` and `.cjs` are Node, `.wasm` WASI, anything else but
+    /// `.py` a binary
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("py") => PluginRuntime::Python,
+            Some("js" | "mjs" | "cjs") => PluginRuntime::Node,
+            Some("wasm") => PluginRuntime::Wasm,
+            _ => PluginRuntime::Binary,
+        }
+    }
+}
+
+impl std::fmt::Display for PluginRuntime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct LlmConfig {
     pub provider: String,
     pub base_url: String,
     pub model: String,
     pub timeout_seconds: u64,
+    // Usually `${ENV_VAR}` or a keyring reference from `csd config set-secret`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
+
+// Manual impl so API keys never end up in logs
+impl std::fmt::Debug for LlmConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmConfig")
+            .field("provider", &self.provider)
+            .field("base_url", &self.base_url)
+            .field("model", &self.model)
+            .field("timeout_seconds", &self.timeout_seconds)
+            .field("api_key", &self.api_key.as_ref().map(|_| REDACTED))
+            .finish()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,6 +738,99 @@ pub struct ScanConfig {
     pub ignore_patterns: Vec<String>,
     pub include_hidden: bool,
     pub max_file_size_mb: u64,
+    // Files sent per plugin invocation to plugins that support `analyze_batch`
+    #[serde(default = "default_plugin_batch_size")]
+    pub plugin_batch_size: usize,
+    // Abort the scan when an enabled input plugin fails its start-up probe,
+    // rather than scanning its files without analysis
+    #[serde(default)]
+    pub fail_on_unready_plugins: bool,
+    // Abort the scan when a file or directory can't be read for lack of permission,
+    // rather than leaving it out (`csd init --strict`)
+    #[serde(default)]
+    pub fail_on_permission_denied: bool,
+    // Bytes of the file head (and of a sample from the middle) sent to `can_analyze`
+    // when several plugins match a file; at most 64 KiB are read
+    #[serde(default = "default_preview_bytes")]
+    pub preview_bytes: usize,
+    // Estimated tokens above which a file's content is cut down before analysis
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "TruncationStrategy::is_default")]
+    pub truncation: TruncationStrategy,
+    // Wall-clock limit for a scan; files not analyzed by then are kept without analysis
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_duration_minutes: Option<u64>,
+    // Seconds a plugin may spend per file (per file in a batch) before it's stopped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_timeout_secs: Option<u64>,
+    // Content hash for change detection: sha256, or blake3/xxh3 for speed
+    #[serde(default, skip_serializing_if = "HashAlgorithm::is_default")]
+    pub hash_algorithm: HashAlgorithm,
+    // Megabytes of file content read ahead of analysis; 256 when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_in_flight_mb: Option<u64>,
+    // `structure` skips plugins and transforms for a quick matrix (`csd scan --level`)
+    #[serde(default, skip_serializing_if = "ScanLevel::is_default")]
+    pub level: ScanLevel,
+}
+
+impl ScanConfig {
+    pub fn max_duration(&self) -> Option<Duration> {
+        self.max_duration_minutes
+            .map(|minutes| Duration::from_secs(minutes * 60))
+    }
+
+    pub fn file_timeout(&self) -> Option<Duration> {
+        self.file_timeout_secs.map(Duration::from_secs)
+    }
+
+    pub fn in_flight_bytes(&self) -> u64 {
+        self.max_in_flight_mb.unwrap_or(256) * 1024 * 1024
+    }
+}
+
+/// How files over `scanning.max_file_tokens` are cut down
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TruncationStrategy {
+    // Keep the start of the file
+    #[default]
+    Head,
+    // Keep equal slices from the start, middle and end
+    Sample,
+}
+
+impl TruncationStrategy {
+    pub fn is_default(&self) -> bool {
+        *self == TruncationStrategy::default()
+    }
+}
+
+/// How deep a scan goes. A structure scan records files, hashes, tokens and the imports
+/// and exports line patterns find, in seconds; a full scan adds what plugins and
+/// transforms report (elements, relationships, summaries).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum ScanLevel {
+    Structure,
+    #[default]
+    Full,
+}
+
+impl ScanLevel {
+    pub fn is_default(&self) -> bool {
+        *self == ScanLevel::default()
+    }
+}
+
+fn default_plugin_batch_size() -> usize {
+    32
+}
+
+fn default_preview_bytes() -> usize {
+    1024
 }
 
 impl Default for Config {
@@ -117,6 +864,7 @@ impl Default for Config {
                 },
                 enabled: true,
                 config: None,
+                runtime: PluginRuntime::Python,
             },
         );
 
@@ -143,6 +891,7 @@ impl Default for Config {
                 },
                 enabled: true,
                 config: None,
+                runtime: PluginRuntime::Python,
             },
         );
 
@@ -154,10 +903,11 @@ impl Default for Config {
                     name: "markdown_docs".to_string(),
                     plugin_type: "docs".to_string(),
                 },
-                output_types: vec!["documentation".to_string()],
+                output_types: vec!["documentation".to_string(), "changelog".to_string()],
                 formats: vec!["markdown".to_string()],
                 enabled: true,
                 config: None,
+                runtime: PluginRuntime::Python,
             },
         );
 
@@ -168,6 +918,7 @@ impl Default for Config {
                 base_url: "http://localhost:11434".to_string(),
                 model: "deepseek-coder".to_string(),
                 timeout_seconds: 30,
+                api_key: None,
             },
             scanning: ScanConfig {
                 ignore_patterns: vec![
@@ -179,34 +930,245 @@ impl Default for Config {
                 ],
                 include_hidden: false,
                 max_file_size_mb: 10,
+                plugin_batch_size: default_plugin_batch_size(),
+                fail_on_unready_plugins: false,
+                fail_on_permission_denied: false,
+                preview_bytes: default_preview_bytes(),
+                max_file_tokens: None,
+                truncation: TruncationStrategy::default(),
+                max_duration_minutes: None,
+                file_timeout_secs: None,
+                hash_algorithm: HashAlgorithm::default(),
+                max_in_flight_mb: None,
+                level: ScanLevel::default(),
             },
             input_plugins,
             output_plugins,
+            transform_plugins: HashMap::new(),
+            rule_plugins: HashMap::new(),
             python_executable: None,
+            node_executable: None,
+            wasm_executable: None,
+            offline: false,
+            hooks: HooksConfig::default(),
+            docs: DocsConfig::default(),
+            redaction: RedactionConfig::default(),
+            quality: QualityConfig::default(),
+            glossary: GlossaryConfig::default(),
+            issue_trackers: Vec::new(),
+            api: ApiConfig::default(),
+            notifications: NotificationsConfig::default(),
+            plugin_logs: PluginLogConfig::default(),
+            plugin_pool: PluginPoolConfig::default(),
+            categories: Vec::new(),
+            cache_dir: None,
             plugins: None, // Legacy field
         }
     }
 }
 
+/// On-disk configuration format, detected from the file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// Detect the format from the extension, defaulting to YAML
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .as_deref()
+        {
+            Some("toml") => ConfigFormat::Toml,
+            Some("json") => ConfigFormat::Json,
+            _ => ConfigFormat::Yaml,
+        }
+    }
+
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> Result<T> {
+        Ok(match self {
+            ConfigFormat::Yaml => serde_yaml::from_str(content)?,
+            ConfigFormat::Toml => toml::from_str(content)?,
+            ConfigFormat::Json => serde_json::from_str(content)?,
+        })
+    }
+
+    pub fn render<T: Serialize>(&self, value: &T) -> Result<String> {
+        Ok(match self {
+            ConfigFormat::Yaml => serde_yaml::to_string(value)?,
+            ConfigFormat::Toml => {
+                // TOML has no null, so unset optional values are dropped
+                let mut tree = serde_yaml::to_value(value)?;
+                strip_nulls(&mut tree);
+                toml::to_string_pretty(&tree)?
+            }
+            ConfigFormat::Json => serde_json::to_string_pretty(value)? + "\n",
+        })
+    }
+}
+
+fn strip_nulls(value: &mut serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        serde_yaml::Value::Sequence(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+/// Find the first configuration file from [`CONFIG_FILE_NAMES`] in a directory
+pub fn find_config_file(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+}
+
+/// Directory holding the user-level config (`$XDG_CONFIG_HOME/csd` or `~/.config/csd`)
+pub fn global_config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("csd"))
+}
+
+/// Existing user-level config file, if any (`config.yaml`, `config.toml`, ...)
+pub fn find_global_config_file() -> Option<PathBuf> {
+    let dir = global_config_dir()?;
+    ["config.yaml", "config.yml", "config.toml", "config.json"]
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+}
+
+/// Out-of-tree cache directory for a project, keyed by its name and a hash of its
+/// canonical path so different checkouts never share a cache
+fn xdg_project_cache_dir(project_root: &Path) -> Option<PathBuf> {
+    use sha2::{Digest, Sha256};
+
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+
+    let canonical = project_root
+        .canonicalize()
+        .unwrap_or_else(|_| project_root.to_path_buf());
+    let name = canonical
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("project");
+    let hash = format!(
+        "{:x}",
+        Sha256::digest(canonical.to_string_lossy().as_bytes())
+    );
+
+    Some(base.join("csd").join(format!("{name}-{}", &hash[..12])))
+}
+
+/// Recursively merge `overlay` into `base`; mappings merge key by key, anything else
+/// replaces. A null in the overlay means "not set" and keeps the lower layer's value.
+pub fn merge_config_values(base: &mut serde_yaml::Value, overlay: serde_yaml::Value) {
+    match (base, overlay) {
+        (_, serde_yaml::Value::Null) => {}
+        (serde_yaml::Value::Mapping(base_map), serde_yaml::Value::Mapping(overlay_map)) => {
+            for (key, value) in overlay_map {
+                match base_map.get_mut(&key) {
+                    Some(existing) => merge_config_values(existing, value),
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
 impl Config {
     pub async fn load(path: &Path) -> Result<Self> {
         let content = tokio::fs::read_to_string(path).await?;
-        let mut config: Config = serde_yaml::from_str(&content)?;
+        let mut tree: serde_yaml::Value = ConfigFormat::from_path(path).parse(&content)?;
+        interpolate_config_values(&mut tree)?;
+        let mut config: Config = serde_yaml::from_value(tree)?;
 
         // Handle legacy configuration migration
-        config.migrate_legacy_plugins();
+        if config.plugins.is_some() {
+            warn!(
+                "{} uses the deprecated `plugins:` section; run `csd config migrate` to update it",
+                path.display()
+            );
+        }
+        let migration = config.migrate_legacy_plugins();
+        for name in &migration.skipped {
+            warn!("Legacy plugin '{name}' has neither file patterns nor output types; ignoring it");
+        }
+
+        Ok(config)
+    }
+
+    /// Load the effective configuration: built-in defaults, then the user-level file,
+    /// then the project file. Plugin tables from the defaults only apply when neither
+    /// file configures plugins, so a project's plugin list is never silently extended.
+    pub async fn load_layered(global: Option<&Path>, project: Option<&Path>) -> Result<Self> {
+        let mut layers = Vec::new();
+        for path in [global, project].into_iter().flatten() {
+            debug!("Loading configuration layer: {}", path.display());
+            let content = tokio::fs::read_to_string(path).await?;
+            let layer: serde_yaml::Value = ConfigFormat::from_path(path).parse(&content)?;
+            if layer.get("plugins").is_some_and(|v| !v.is_null()) {
+                warn!(
+                    "{} uses the deprecated `plugins:` section; run `csd config migrate` to update it",
+                    path.display()
+                );
+            }
+            layers.push(layer);
+        }
+
+        let mut tree = serde_yaml::to_value(Config::default())?;
+        if let serde_yaml::Value::Mapping(defaults) = &mut tree {
+            let configures_plugins = layers.iter().any(|layer| {
+                ["input_plugins", "output_plugins", "plugins"]
+                    .iter()
+                    .any(|key| layer.get(key).is_some())
+            });
+            if configures_plugins {
+                defaults.remove("input_plugins");
+                defaults.remove("output_plugins");
+            }
+        }
+        for layer in layers {
+            merge_config_values(&mut tree, layer);
+        }
+        interpolate_config_values(&mut tree)?;
+
+        let mut config: Config = serde_yaml::from_value(tree)?;
+        let migration = config.migrate_legacy_plugins();
+        for name in &migration.skipped {
+            warn!("Legacy plugin '{name}' has neither file patterns nor output types; ignoring it");
+        }
 
         Ok(config)
     }
 
     pub async fn save(&self, path: &Path) -> Result<()> {
-        let content = serde_yaml::to_string(self)?;
-        tokio::fs::write(path, content).await?;
+        let content = ConfigFormat::from_path(path).render(self)?;
+        write_atomic(path, content).await?;
         Ok(())
     }
 
     /// Migrate legacy plugin configuration to new typed structure
-    fn migrate_legacy_plugins(&mut self) {
+    pub fn migrate_legacy_plugins(&mut self) -> LegacyMigration {
+        let mut migration = LegacyMigration::default();
+
         if let Some(legacy_plugins) = &self.plugins {
             for (name, legacy_config) in legacy_plugins {
                 // Try to determine if it's an input or output plugin based on configuration
@@ -223,8 +1185,10 @@ impl Config {
                         }),
                         enabled: legacy_config.enabled,
                         config: legacy_config.config.clone(),
+                        runtime: PluginRuntime::default(),
                     };
                     self.input_plugins.insert(name.clone(), input_config);
+                    migration.input_plugins.push(name.clone());
                 } else if legacy_config.output_types.is_some() || legacy_config.formats.is_some() {
                     // Has output types or formats, likely an output plugin
                     let output_config = OutputPluginConfig {
@@ -233,18 +1197,55 @@ impl Config {
                         formats: legacy_config.formats.clone().unwrap_or_default(),
                         enabled: legacy_config.enabled,
                         config: legacy_config.config.clone(),
+                        runtime: PluginRuntime::default(),
                     };
                     self.output_plugins.insert(name.clone(), output_config);
+                    migration.output_plugins.push(name.clone());
+                } else {
+                    migration.skipped.push(name.clone());
                 }
             }
 
             // Clear legacy plugins after migration
             self.plugins = None;
         }
+
+        migration.input_plugins.sort();
+        migration.output_plugins.sort();
+        migration.skipped.sort();
+        migration
+    }
+
+    /// Resolve the cache root for a project. `CSD_CACHE_DIR` wins over `cache_dir`;
+    /// relative paths are taken from the project root, and the special value `xdg`
+    /// selects a per-project directory under `$XDG_CACHE_HOME/csd` (or `~/.cache/csd`).
+    pub fn resolve_cache_dir(&self, project_root: &Path) -> PathBuf {
+        let configured = std::env::var(CACHE_DIR_ENV)
+            .ok()
+            .filter(|dir| !dir.is_empty())
+            .or_else(|| self.cache_dir.clone())
+            .unwrap_or_else(|| DEFAULT_CACHE_DIR.to_string());
+
+        if configured.eq_ignore_ascii_case("xdg") {
+            if let Some(dir) = xdg_project_cache_dir(project_root) {
+                return dir;
+            }
+            warn!("Could not determine the XDG cache directory, using {DEFAULT_CACHE_DIR}");
+            return project_root.join(DEFAULT_CACHE_DIR);
+        }
+
+        project_root.join(configured)
     }
 
     /// Find which input plugin should handle a given file
     pub fn find_input_plugin_for_file(&self, file_path: &Path) -> Option<String> {
+        self.find_input_plugins_for_file(file_path)
+            .into_iter()
+            .next()
+    }
+
+    /// Find every enabled input plugin whose patterns match a file, sorted by name
+    pub fn find_input_plugins_for_file(&self, file_path: &Path) -> Vec<String> {
         let filename = file_path
             .file_name()
             .and_then(|name| name.to_str())
@@ -255,32 +1256,32 @@ impl Config {
             .and_then(|ext| ext.to_str())
             .map(|ext| format!(".{}", ext.to_lowercase()));
 
+        let mut matching_plugins = Vec::new();
         for (plugin_name, plugin_config) in &self.input_plugins {
             if !plugin_config.enabled {
                 continue;
             }
 
             // Check extensions
-            if let Some(ref ext) = extension {
-                if plugin_config.file_patterns.extensions.contains(ext) {
-                    return Some(plugin_name.clone());
-                }
-            }
+            let extension_match = extension
+                .as_ref()
+                .is_some_and(|ext| plugin_config.file_patterns.extensions.contains(ext));
 
             // Check exact filenames
-            if plugin_config
+            let filename_match = plugin_config
                 .file_patterns
                 .filenames
                 .iter()
-                .any(|pattern| pattern.to_lowercase() == filename.to_lowercase())
-            {
-                return Some(plugin_name.clone());
-            }
+                .any(|pattern| pattern.to_lowercase() == filename.to_lowercase());
 
             // TODO: Check glob patterns if needed
+            if extension_match || filename_match {
+                matching_plugins.push(plugin_name.clone());
+            }
         }
 
-        None
+        matching_plugins.sort();
+        matching_plugins
     }
 
     /// Find output plugins that can generate the specified output type and format
@@ -326,6 +1327,105 @@ impl Config {
             .collect()
     }
 
+    /// What running the configured pipeline would reach over the network: enabled
+    /// plugins fetched from GitHub or Git, and LLM endpoints that aren't on this machine
+    pub fn network_requirements(&self) -> Vec<String> {
+        let mut plugins: Vec<(&str, &String, &PluginSource)> = Vec::new();
+        plugins.extend(
+            self.get_enabled_input_plugins()
+                .into_iter()
+                .map(|(name, plugin)| ("input", name, &plugin.source)),
+        );
+        plugins.extend(
+            self.get_enabled_output_plugins()
+                .into_iter()
+                .map(|(name, plugin)| ("output", name, &plugin.source)),
+        );
+        plugins.extend(
+            self.get_enabled_transform_plugins()
+                .into_iter()
+                .map(|(name, plugin)| ("transform", name, &plugin.source)),
+        );
+        plugins.extend(
+            self.get_enabled_rule_plugins()
+                .into_iter()
+                .map(|(name, plugin)| ("rule", name, &plugin.source)),
+        );
+        plugins.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+
+        let mut requirements = Vec::new();
+        for (kind, name, source) in plugins {
+            match source {
+                PluginSource::GitHub { repo, .. } => requirements.push(format!(
+                    "{kind} plugin '{name}' is fetched from GitHub ({repo})"
+                )),
+                PluginSource::Git { url, .. } => requirements.push(format!(
+                    "{kind} plugin '{name}' is fetched from Git ({url})"
+                )),
+                _ => {}
+            }
+        }
+
+        if !is_local_url(&self.llm.base_url) {
+            requirements.push(format!(
+                "llm.base_url {} is not a local provider",
+                self.llm.base_url
+            ));
+        }
+        for (name, plugin) in self.get_enabled_output_plugins() {
+            let base_url = plugin
+                .config
+                .as_ref()
+                .and_then(|config| config.get("llm"))
+                .and_then(|llm| llm.get("base_url"))
+                .and_then(|url| url.as_str());
+            if let Some(url) = base_url.filter(|url| !is_local_url(url)) {
+                requirements.push(format!(
+                    "output plugin '{name}' uses LLM endpoint {url}, which is not a local provider"
+                ));
+            }
+        }
+        requirements.sort();
+        requirements
+    }
+
+    /// In offline mode, fail up front when the configuration needs the network
+    pub fn ensure_offline_ready(&self) -> Result<()> {
+        if !self.offline {
+            return Ok(());
+        }
+        let requirements = self.network_requirements();
+        if requirements.is_empty() {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "Offline mode forbids network access, but:\n  - {}\nDisable these or run without --offline",
+            requirements.join("\n  - ")
+        ))
+    }
+
+    /// Get enabled transform plugins in the order they should run
+    pub fn get_enabled_transform_plugins(&self) -> Vec<(&String, &TransformPluginConfig)> {
+        let mut plugins: Vec<_> = self
+            .transform_plugins
+            .iter()
+            .filter(|(_, config)| config.enabled)
+            .collect();
+        plugins.sort_by(|a, b| (a.1.order, a.0).cmp(&(b.1.order, b.0)));
+        plugins
+    }
+
+    /// Get enabled rule plugins, by name
+    pub fn get_enabled_rule_plugins(&self) -> Vec<(&String, &RulePluginConfig)> {
+        let mut plugins: Vec<_> = self
+            .rule_plugins
+            .iter()
+            .filter(|(_, config)| config.enabled)
+            .collect();
+        plugins.sort_by(|a, b| a.0.cmp(b.0));
+        plugins
+    }
+
     /// Get input plugin configuration by name
     pub fn get_input_plugin(&self, name: &str) -> Option<&InputPluginConfig> {
         self.input_plugins.get(name)
@@ -383,3 +1483,284 @@ pub struct PluginSummary {
     pub input_plugin_names: Vec<String>,
     pub output_plugin_names: Vec<String>,
 }
+
+/// Names of legacy plugins moved by [`Config::migrate_legacy_plugins`]
+#[derive(Debug, Clone, Default)]
+pub struct LegacyMigration {
+    pub input_plugins: Vec<String>,
+    pub output_plugins: Vec<String>,
+    pub skipped: Vec<String>, // Neither file patterns nor output types/formats
+}
+
+/// Result of rewriting a configuration file with [`migrate_config_text`]
+#[derive(Debug, Clone)]
+pub struct ConfigMigration {
+    pub content: String,
+    pub migration: LegacyMigration,
+    pub deprecated_keys: Vec<String>,
+}
+
+impl ConfigMigration {
+    /// Whether the rewritten content differs from the original
+    pub fn changed(&self) -> bool {
+        !self.deprecated_keys.is_empty()
+    }
+}
+
+/// Rewrite legacy `plugins:` configuration text into the `input_plugins`/`output_plugins`
+/// structure. For YAML only the plugin sections are regenerated and every other top-level
+/// block, including its comments, is kept verbatim; TOML and JSON files are re-serialized.
+pub fn migrate_config_text(content: &str, format: ConfigFormat) -> Result<ConfigMigration> {
+    let raw: serde_yaml::Value = format.parse(content)?;
+    let deprecated_keys: Vec<String> = DEPRECATED_KEYS
+        .iter()
+        .filter(|(key, _)| raw.get(key).is_some_and(|v| !v.is_null()))
+        .map(|(key, _)| key.to_string())
+        .collect();
+
+    if deprecated_keys.is_empty() {
+        return Ok(ConfigMigration {
+            content: content.to_string(),
+            migration: LegacyMigration::default(),
+            deprecated_keys,
+        });
+    }
+
+    let mut config: Config = format.parse(content)?;
+    let migration = config.migrate_legacy_plugins();
+
+    if format != ConfigFormat::Yaml {
+        return Ok(ConfigMigration {
+            content: format.render(&config)?,
+            migration,
+            deprecated_keys,
+        });
+    }
+
+    // Sorted maps keep the generated sections stable between runs
+    #[derive(Serialize)]
+    struct PluginSections<'a> {
+        input_plugins: BTreeMap<&'a String, &'a InputPluginConfig>,
+        output_plugins: BTreeMap<&'a String, &'a OutputPluginConfig>,
+    }
+    let sections = serde_yaml::to_string(&PluginSections {
+        input_plugins: config.input_plugins.iter().collect(),
+        output_plugins: config.output_plugins.iter().collect(),
+    })?;
+
+    let lines: Vec<&str> = content.lines().collect();
+    let replaced: Vec<(usize, usize)> = top_level_blocks(&lines)
+        .into_iter()
+        .filter(|(key, _, _)| {
+            key == "input_plugins" || key == "output_plugins" || deprecated_keys.contains(key)
+        })
+        .map(|(_, start, end)| (start, end))
+        .collect();
+
+    let insert_at = replaced
+        .first()
+        .map(|(start, _)| *start)
+        .unwrap_or(lines.len());
+    let mut output = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i == insert_at {
+            output.push_str(&sections);
+        }
+        if replaced.iter().any(|(start, end)| i >= *start && i < *end) {
+            continue;
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+    if insert_at >= lines.len() {
+        output.push_str(&sections);
+    }
+
+    Ok(ConfigMigration {
+        content: output,
+        migration,
+        deprecated_keys,
+    })
+}
+
+/// Find top-level YAML keys as `(key, start_line, end_line)`. Comments and blank lines
+/// directly above a key belong to that key's block, not the one before it.
+fn top_level_blocks(lines: &[&str]) -> Vec<(String, usize, usize)> {
+    let starts: Vec<(String, usize)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            if line.starts_with(|c: char| c.is_whitespace() || c == '#' || c == '-') {
+                return None;
+            }
+            let (key, _) = line.split_once(':')?;
+            let key = key.trim().trim_matches(|c| c == '"' || c == '\'');
+            (!key.is_empty()).then(|| (key.to_string(), i))
+        })
+        .collect();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(n, (key, start))| {
+            let mut end = starts.get(n + 1).map(|(_, s)| *s).unwrap_or(lines.len());
+            while end > start + 1 {
+                let previous = lines[end - 1];
+                if previous.trim().is_empty() || previous.starts_with('#') {
+                    end -= 1;
+                } else {
+                    break;
+                }
+            }
+            (key.clone(), *start, end)
+        })
+        .collect()
+}
+
+/// Whether a URL points at this machine: `localhost` (and `*.localhost`), loopback
+/// addresses, or a Unix socket
+pub fn is_local_url(url: &str) -> bool {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return false;
+    };
+    if scheme.eq_ignore_ascii_case("unix") || scheme.to_ascii_lowercase().ends_with("+unix") {
+        return true;
+    }
+
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = match host_port.strip_prefix('[') {
+        // Bracketed IPv6, e.g. `[::1]:8080`
+        Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+        None => host_port.split(':').next().unwrap_or_default(),
+    };
+    let host = host.to_ascii_lowercase();
+
+    if host == "localhost" || host.ends_with(".localhost") {
+        return true;
+    }
+    host.parse::<std::net::IpAddr>()
+        .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Look up a value by dot-separated path (e.g. `llm.model`)
+pub fn get_config_value<'a>(
+    root: &'a serde_yaml::Value,
+    key: &str,
+) -> Option<&'a serde_yaml::Value> {
+    key.split('.').try_fold(root, |value, segment| match value {
+        serde_yaml::Value::Sequence(items) => items.get(segment.parse::<usize>().ok()?),
+        _ => value.get(segment),
+    })
+}
+
+/// Set a value by dot-separated path, creating intermediate mappings as needed
+pub fn set_config_value(
+    root: &mut serde_yaml::Value,
+    key: &str,
+    new_value: serde_yaml::Value,
+) -> Result<()> {
+    let mut current = root;
+    for segment in key.split('.') {
+        if segment.is_empty() {
+            return Err(anyhow::anyhow!("Invalid configuration key: '{key}'"));
+        }
+        if current.is_null() {
+            *current = serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+        }
+        current = match current {
+            serde_yaml::Value::Mapping(map) => map
+                .entry(serde_yaml::Value::String(segment.to_string()))
+                .or_insert(serde_yaml::Value::Null),
+            serde_yaml::Value::Sequence(items) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get_mut(index))
+                .ok_or_else(|| anyhow::anyhow!("No list item '{segment}' in '{key}'"))?,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Cannot set '{key}': '{segment}' is inside a scalar value"
+                ))
+            }
+        };
+    }
+    *current = new_value;
+    Ok(())
+}
+
+/// Remove a value by dot-separated path, returning it if it existed
+pub fn unset_config_value(root: &mut serde_yaml::Value, key: &str) -> Option<serde_yaml::Value> {
+    let (parent_key, last) = match key.rsplit_once('.') {
+        Some((parent, last)) => (Some(parent), last),
+        None => (None, key),
+    };
+    let parent = match parent_key {
+        Some(parent_key) => {
+            let mut current = root;
+            for segment in parent_key.split('.') {
+                current = match current {
+                    serde_yaml::Value::Sequence(items) => {
+                        items.get_mut(segment.parse::<usize>().ok()?)?
+                    }
+                    _ => current.get_mut(segment)?,
+                };
+            }
+            current
+        }
+        None => root,
+    };
+    match parent {
+        serde_yaml::Value::Mapping(map) => map.remove(last),
+        serde_yaml::Value::Sequence(items) => {
+            let index = last.parse::<usize>().ok()?;
+            (index < items.len()).then(|| items.remove(index))
+        }
+        _ => None,
+    }
+}
+
+/// Flatten a configuration tree into sorted `(dot.path, value)` pairs for listing
+pub fn list_config_values(root: &serde_yaml::Value) -> Vec<(String, String)> {
+    fn walk(prefix: &str, value: &serde_yaml::Value, out: &mut Vec<(String, String)>) {
+        match value {
+            serde_yaml::Value::Mapping(map) if !map.is_empty() => {
+                for (k, v) in map {
+                    let name = match k {
+                        serde_yaml::Value::String(s) => s.clone(),
+                        other => format_config_value(other),
+                    };
+                    let path = if prefix.is_empty() {
+                        name
+                    } else {
+                        format!("{prefix}.{name}")
+                    };
+                    walk(&path, v, out);
+                }
+            }
+            _ => out.push((prefix.to_string(), format_config_value(value))),
+        }
+    }
+
+    let mut values = Vec::new();
+    walk("", root, &mut values);
+    values.sort();
+    values
+}
+
+/// Parse a command-line value as YAML so numbers, booleans and lists keep their types
+pub fn parse_config_value(raw: &str) -> serde_yaml::Value {
+    serde_yaml::from_str(raw).unwrap_or_else(|_| serde_yaml::Value::String(raw.to_string()))
+}
+
+/// Render a configuration value on one line (scalars bare, collections as flow YAML)
+pub fn format_config_value(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::Null => "null".to_string(),
+        serde_yaml::Value::Bool(b) => b.to_string(),
+        serde_yaml::Value::Number(n) => n.to_string(),
+        serde_yaml::Value::String(s) => s.clone(),
+        other => serde_json::to_string(other).unwrap_or_default(),
+    }
+}
@@ -1 +1,119 @@
-// TODO: Implement
+// src/utils/file_utils.rs - Shared file helpers
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Write a file atomically: the content goes to a temporary sibling which is synced
+/// and then renamed over the target, so readers never observe a partial file.
+pub async fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let path = path.to_path_buf();
+    let contents = contents.as_ref().to_vec();
+
+    tokio::task::spawn_blocking(move || write_atomic_sync(&path, &contents))
+        .await
+        .context("Atomic write task panicked")?
+}
+
+/// Blocking version of [`write_atomic`]
+pub fn write_atomic_sync(path: &Path, contents: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let temp_path = temp_sibling(path);
+    let result = (|| -> Result<()> {
+        let mut file = std::fs::File::create(&temp_path)
+            .with_context(|| format!("Failed to create {}", temp_path.display()))?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        std::fs::rename(&temp_path, path)
+            .with_context(|| format!("Failed to move temp file into {}", path.display()))?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Path of the backup kept next to a file, e.g. `matrix.json.bak`
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Resolve `path` (relative paths are taken from `base`) and check that it stays inside
+/// `base` once `..` components and symlinks are resolved. `base` must exist; `path`
+/// need not.
+pub fn resolve_within(base: &Path, path: &Path) -> Result<PathBuf> {
+    let canonical_base = base
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", base.display()))?;
+    contain(&canonical_base, &canonical_base.join(path), path)
+}
+
+/// Like [`resolve_within`], but relative paths are taken from the working directory, as
+/// a plugin reporting a file it wrote under `base` would give it
+pub fn resolve_inside(base: &Path, path: &Path) -> Result<PathBuf> {
+    let canonical_base = base
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", base.display()))?;
+    let absolute = std::path::absolute(path)
+        .with_context(|| format!("Failed to resolve {}", path.display()))?;
+    contain(&canonical_base, &absolute, path)
+}
+
+// `joined`, with symlinks resolved, as long as it stays inside `base`
+fn contain(base: &Path, joined: &Path, path: &Path) -> Result<PathBuf> {
+    let joined = normalize_lexically(joined);
+
+    // Canonicalize the deepest existing ancestor so symlinks inside `base` can't escape
+    let mut existing = joined.as_path();
+    let mut rest = Vec::new();
+    while !existing.exists() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => break,
+        }
+    }
+    let mut resolved = existing
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", existing.display()))?;
+    resolved.extend(rest.iter().rev());
+
+    if !resolved.starts_with(base) {
+        return Err(anyhow::anyhow!(
+            "{} is outside {}",
+            path.display(),
+            base.display()
+        ));
+    }
+    Ok(resolved)
+}
+
+/// Drop `.` components and apply `..` without touching the filesystem
+pub fn normalize_lexically(path: &Path) -> PathBuf {
+    use std::path::Component;
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+fn temp_sibling(path: &Path) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(format!(".{}.tmp", Uuid::new_v4().simple()));
+    path.with_file_name(name)
+}
@@ -0,0 +1,109 @@
+// src/utils/hashing.rs - Content hashes for change detection (scanning.hash_algorithm)
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+
+// Files are hashed this much at a time, so memory stays flat however large they are
+const CHUNK_BYTES: usize = 64 * 1024;
+
+/// Algorithm behind `FileNode.hash`. SHA-256 is the default, for provenance; BLAKE3
+/// and XXH3 trade that for speed on large trees, XXH3 being non-cryptographic.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Blake3,
+    Xxh3,
+}
+
+impl HashAlgorithm {
+    pub fn is_default(&self) -> bool {
+        *self == HashAlgorithm::default()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+            HashAlgorithm::Xxh3 => "xxh3",
+        }
+    }
+
+    /// Lowercase hex digest of `content`: 64 digits, or 32 for XXH3's 128 bits
+    pub fn hash(&self, content: &[u8]) -> String {
+        let mut hasher = Hasher::new(*self);
+        hasher.update(content);
+        hasher.finish()
+    }
+
+    /// `hash` of everything `reader` yields, read in fixed-size chunks
+    pub fn hash_reader(&self, mut reader: impl Read) -> std::io::Result<String> {
+        let mut hasher = Hasher::new(*self);
+        let mut chunk = vec![0; CHUNK_BYTES];
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(0) => return Ok(hasher.finish()),
+                Ok(n) => hasher.update(&chunk[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub fn hash_file(&self, path: &Path) -> std::io::Result<String> {
+        self.hash_reader(std::fs::File::open(path)?)
+    }
+
+    /// `hash` qualified with the algorithm for caches shared by scans that may use
+    /// different ones; SHA-256 hashes stay bare, as they were before the choice existed
+    pub fn cache_key(&self, hash: &str) -> String {
+        match self {
+            HashAlgorithm::Sha256 => hash.to_string(),
+            other => format!("{other}:{hash}"),
+        }
+    }
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    // Boxed: blake3's state is much larger than the others
+    Blake3(Box<blake3::Hasher>),
+    Xxh3(Box<xxhash_rust::xxh3::Xxh3>),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::default()),
+            HashAlgorithm::Xxh3 => Hasher::Xxh3(Box::default()),
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(bytes),
+            Hasher::Blake3(hasher) => {
+                hasher.update(bytes);
+            }
+            Hasher::Xxh3(hasher) => hasher.update(bytes),
+        }
+    }
+
+    fn finish(self) -> String {
+        match self {
+            Hasher::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+            Hasher::Xxh3(hasher) => format!("{:032x}", hasher.digest128()),
+        }
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,151 @@
+// src/utils/hooks.rs - User commands run at pipeline stages (pre/post scan and docs)
+use anyhow::{Context, Result};
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+
+use crate::plugins::interface::OFFLINE_ENV;
+use crate::utils::config::{Config, HookConfig};
+
+/// Pipeline stage a hook is registered for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    PreScan,
+    PostScan,
+    PreDocs,
+    PostDocs,
+}
+
+impl HookStage {
+    /// Name used in config keys and `CSD_HOOK_STAGE`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookStage::PreScan => "pre_scan",
+            HookStage::PostScan => "post_scan",
+            HookStage::PreDocs => "pre_docs",
+            HookStage::PostDocs => "post_docs",
+        }
+    }
+
+    fn hooks<'a>(&self, config: &'a Config) -> &'a [HookConfig] {
+        match self {
+            HookStage::PreScan => &config.hooks.pre_scan,
+            HookStage::PostScan => &config.hooks.post_scan,
+            HookStage::PreDocs => &config.hooks.pre_docs,
+            HookStage::PostDocs => &config.hooks.post_docs,
+        }
+    }
+}
+
+/// Paths exposed to hooks as `CSD_*` environment variables
+#[derive(Debug, Clone, Default)]
+pub struct HookContext {
+    pub project_root: PathBuf,
+    pub cache_dir: PathBuf,
+    pub matrix_path: Option<PathBuf>,
+    pub output_dir: Option<PathBuf>,
+}
+
+impl HookContext {
+    // Paths are made absolute since hooks run from the project root, not our cwd
+    fn env(&self, stage: HookStage) -> Vec<(&'static str, String)> {
+        let absolute = |path: &Path| {
+            std::path::absolute(path)
+                .unwrap_or_else(|_| path.to_path_buf())
+                .display()
+                .to_string()
+        };
+        let mut env = vec![
+            ("CSD_HOOK_STAGE", stage.as_str().to_string()),
+            ("CSD_PROJECT_ROOT", absolute(&self.project_root)),
+            ("CSD_CACHE_DIR", absolute(&self.cache_dir)),
+        ];
+        if let Some(ref matrix_path) = self.matrix_path {
+            env.push(("CSD_MATRIX_PATH", absolute(matrix_path)));
+        }
+        if let Some(ref output_dir) = self.output_dir {
+            env.push(("CSD_OUTPUT_DIR", absolute(output_dir)));
+        }
+        env
+    }
+}
+
+/// Run the hooks registered for a stage in order, from the project root. A failing hook
+/// stops the pipeline unless it sets `allow_failure`.
+pub async fn run_hooks(config: &Config, stage: HookStage, context: &HookContext) -> Result<()> {
+    for hook in stage.hooks(config) {
+        let mut command = match (&hook.command, &hook.plugin) {
+            (Some(shell_command), _) => shell(shell_command),
+            (None, Some(plugin)) => {
+                let python = config.python_executable.as_deref().unwrap_or("python");
+                let mut command = Command::new(python);
+                command.arg(plugin);
+                command
+            }
+            (None, None) => {
+                warn!(
+                    "Ignoring {} hook with neither command nor plugin",
+                    stage.as_str()
+                );
+                continue;
+            }
+        };
+        let description = hook
+            .command
+            .as_deref()
+            .or(hook.plugin.as_deref())
+            .unwrap_or_default();
+
+        if config.offline {
+            command.env(OFFLINE_ENV, "1");
+        }
+
+        info!("Running {} hook: {description}", stage.as_str());
+        let status = command
+            .current_dir(working_dir(&context.project_root))
+            .envs(context.env(stage))
+            .stdin(Stdio::null())
+            .status()
+            .await
+            .with_context(|| format!("Failed to start {} hook: {description}", stage.as_str()))?;
+
+        if !status.success() {
+            if hook.allow_failure {
+                warn!(
+                    "{} hook failed ({status}), continuing: {description}",
+                    stage.as_str()
+                );
+            } else {
+                return Err(anyhow::anyhow!(
+                    "{} hook failed ({status}): {description}",
+                    stage.as_str()
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn shell(command_line: &str) -> Command {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    command.arg(command_line);
+    command
+}
+
+fn working_dir(project_root: &Path) -> &Path {
+    if project_root.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        project_root
+    }
+}
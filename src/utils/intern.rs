@@ -0,0 +1,164 @@
+// src/utils/intern.rs - Interned strings for values repeated across many matrix records
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{Arc, LazyLock, Mutex};
+
+static INTERNER: LazyLock<Mutex<Interner>> = LazyLock::new(Default::default);
+
+/// Smallest table size at which released values are pruned
+const MIN_PRUNE_AT: usize = 1024;
+
+/// Interned values, pruned of those no record holds any more whenever the table doubles
+/// in size, so a long-running process keeps only the values of matrices still loaded
+#[derive(Default)]
+struct Interner {
+    values: HashSet<Arc<str>>,
+    prune_at: usize,
+}
+
+impl Interner {
+    fn prune(&mut self) {
+        // A count of one is the table's own reference; no IStr is left to clone it
+        self.values.retain(|value| Arc::strong_count(value) > 1);
+        self.prune_at = (self.values.len() * 2).max(MIN_PRUNE_AT);
+    }
+}
+
+/// An immutable string shared between all records holding the same value. Plugin names,
+/// languages, ecosystems and module names repeat across thousands of files; interning
+/// keeps one allocation per distinct value. Serializes as a plain JSON string.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IStr(Arc<str>);
+
+impl IStr {
+    pub fn new(value: &str) -> Self {
+        let mut interner = INTERNER.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(existing) = interner.values.get(value) {
+            return Self(existing.clone());
+        }
+        let interned: Arc<str> = Arc::from(value);
+        interner.values.insert(interned.clone());
+        if interner.values.len() >= interner.prune_at.max(MIN_PRUNE_AT) {
+            interner.prune();
+        }
+        Self(interned)
+    }
+
+    /// Whether `value` is still held by the interner
+    pub fn is_interned(value: &str) -> bool {
+        let interner = INTERNER.lock().unwrap_or_else(|e| e.into_inner());
+        interner.values.contains(value)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether two values share the same allocation
+    pub fn ptr_eq(&self, other: &IStr) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for IStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for IStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::borrow::Borrow<str> for IStr {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for IStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Debug for IStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl From<&str> for IStr {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for IStr {
+    fn from(value: String) -> Self {
+        Self::new(&value)
+    }
+}
+
+impl From<&String> for IStr {
+    fn from(value: &String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<IStr> for String {
+    fn from(value: IStr) -> Self {
+        value.0.to_string()
+    }
+}
+
+impl PartialEq<str> for IStr {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for IStr {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for IStr {
+    fn eq(&self, other: &String) -> bool {
+        &*self.0 == other.as_str()
+    }
+}
+
+impl Serialize for IStr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for IStr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct IStrVisitor;
+
+        impl serde::de::Visitor<'_> for IStrVisitor {
+            type Value = IStr;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            // Values already interned don't allocate at all
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<IStr, E> {
+                Ok(IStr::new(value))
+            }
+        }
+
+        deserializer.deserialize_str(IStrVisitor)
+    }
+}
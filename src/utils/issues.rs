@@ -0,0 +1,202 @@
+// src/utils/issues.rs - Issue keys in free text and their status in GitHub, Jira or Linear
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::utils::config::{is_local_url, IssueTrackerConfig, TrackerKind};
+
+const GITHUB_API: &str = "https://api.github.com";
+const LINEAR_API: &str = "https://api.linear.app/graphql";
+
+// Prefixes of `ABC-123` shaped names that are standards and algorithms, not projects
+const NOT_PROJECTS: &[&str] = &[
+    "AES", "CVE", "HTTP", "ISO", "MD", "RFC", "RSA", "SHA", "SSL", "TLS", "UTF",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueStatus {
+    Open,
+    Closed,
+    // No tracker claims the key, the lookup failed, or it wasn't attempted
+    Unverified,
+}
+
+/// `#456` and `PAY-123` style keys in `text`, in order of appearance, without repeats
+pub fn issue_keys(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut keys: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let starts_word = i == 0 || !(chars[i - 1].is_alphanumeric() || chars[i - 1] == '&');
+        let key = if !starts_word {
+            None
+        } else if chars[i] == '#' {
+            let digits = count_while(&chars[i + 1..], |c| c.is_ascii_digit());
+            (digits > 0 && !ends_word(&chars, i + 1 + digits))
+                .then(|| chars[i..i + 1 + digits].iter().collect::<String>())
+        } else if chars[i].is_ascii_uppercase() {
+            let prefix = count_while(&chars[i..], |c| {
+                c.is_ascii_uppercase() || c.is_ascii_digit()
+            });
+            let dash = i + prefix;
+            let digits = if chars.get(dash) == Some(&'-') {
+                count_while(&chars[dash + 1..], |c| c.is_ascii_digit())
+            } else {
+                0
+            };
+            let project: String = chars[i..dash].iter().collect();
+            (prefix >= 2
+                && digits > 0
+                && !ends_word(&chars, dash + 1 + digits)
+                && !NOT_PROJECTS.contains(&project.as_str()))
+            .then(|| chars[i..dash + 1 + digits].iter().collect::<String>())
+        } else {
+            None
+        };
+        match key {
+            Some(key) => {
+                i += key.chars().count();
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+            None => i += 1,
+        }
+    }
+    keys
+}
+
+fn count_while(chars: &[char], predicate: impl Fn(char) -> bool) -> usize {
+    chars.iter().take_while(|c| predicate(**c)).count()
+}
+
+// A key followed by more word characters (`#12abc`, `PAY-12x`) is something else
+fn ends_word(chars: &[char], end: usize) -> bool {
+    chars
+        .get(end)
+        .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+}
+
+/// The tracker responsible for `key`: the GitHub tracker for `#123`, otherwise the first
+/// Jira or Linear tracker listing the key's project, or listing none
+pub fn tracker_for<'a>(
+    trackers: &'a [IssueTrackerConfig],
+    key: &str,
+) -> Option<&'a IssueTrackerConfig> {
+    if key.starts_with('#') {
+        return trackers.iter().find(|t| t.kind == TrackerKind::GitHub);
+    }
+    let project = key.split('-').next().unwrap_or_default();
+    trackers.iter().find(|t| {
+        t.kind != TrackerKind::GitHub
+            && (t.projects.is_empty() || t.projects.iter().any(|p| p == project))
+    })
+}
+
+/// Looks issues up over each tracker's HTTP API
+pub struct IssueClient {
+    client: reqwest::Client,
+    offline: bool,
+}
+
+impl IssueClient {
+    /// With `offline`, only trackers at local URLs are contacted
+    pub fn new(offline: bool) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(15))
+            .user_agent(concat!("csd/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self { client, offline })
+    }
+
+    /// Whether `key` is open or closed in `tracker`
+    pub async fn status(&self, tracker: &IssueTrackerConfig, key: &str) -> Result<IssueStatus> {
+        let url = match (tracker.kind, tracker.url.as_deref()) {
+            (_, Some(url)) => url.trim_end_matches('/'),
+            (TrackerKind::GitHub, None) => GITHUB_API,
+            (TrackerKind::Linear, None) => LINEAR_API,
+            (TrackerKind::Jira, None) => {
+                return Err(anyhow::anyhow!("Jira tracker needs a url"));
+            }
+        };
+        if self.offline && !is_local_url(url) {
+            return Err(anyhow::anyhow!(
+                "Offline mode forbids contacting {url} for {key}"
+            ));
+        }
+        let token = tracker
+            .token_env
+            .as_deref()
+            .and_then(|name| std::env::var(name).ok());
+
+        let request = match tracker.kind {
+            TrackerKind::GitHub => {
+                let repo = tracker
+                    .repo
+                    .as_deref()
+                    .context("GitHub tracker needs a repo")?;
+                let number = key.trim_start_matches('#');
+                let request = self
+                    .client
+                    .get(format!("{url}/repos/{repo}/issues/{number}"))
+                    .header("Accept", "application/vnd.github+json");
+                match token {
+                    Some(token) => request.bearer_auth(token),
+                    None => request,
+                }
+            }
+            TrackerKind::Jira => {
+                let request = self
+                    .client
+                    .get(format!("{url}/rest/api/2/issue/{key}"))
+                    .query(&[("fields", "status")]);
+                match token {
+                    Some(token) => request.bearer_auth(token),
+                    None => request,
+                }
+            }
+            TrackerKind::Linear => {
+                let request = self.client.post(url).json(&serde_json::json!({
+                    "query": "query($id: String!) { issue(id: $id) { state { type } } }",
+                    "variables": { "id": key }
+                }));
+                // Linear API keys go in the header as they are
+                match token {
+                    Some(token) => request.header("Authorization", token),
+                    None => request,
+                }
+            }
+        };
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to look up {key}"))?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Looking up {key} failed: HTTP {}",
+                response.status()
+            ));
+        }
+        let body: serde_json::Value = response.json().await?;
+        if tracker.kind == TrackerKind::Linear && body["data"]["issue"].is_null() {
+            return Err(anyhow::anyhow!("Linear has no issue {key}"));
+        }
+
+        let closed = match tracker.kind {
+            TrackerKind::GitHub => body["state"].as_str() == Some("closed"),
+            TrackerKind::Jira => {
+                body["fields"]["status"]["statusCategory"]["key"].as_str() == Some("done")
+            }
+            TrackerKind::Linear => matches!(
+                body["data"]["issue"]["state"]["type"].as_str(),
+                Some("completed" | "canceled")
+            ),
+        };
+        Ok(if closed {
+            IssueStatus::Closed
+        } else {
+            IssueStatus::Open
+        })
+    }
+}
@@ -1,2 +1,11 @@
+pub mod cache;
 pub mod config;
 pub mod file_utils;
+pub mod git;
+pub mod hashing;
+pub mod hooks;
+pub mod intern;
+pub mod issues;
+pub mod notify;
+pub mod profile;
+pub mod secrets;
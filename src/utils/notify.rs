@@ -0,0 +1,218 @@
+// src/utils/notify.rs - Summaries posted to webhooks when long-running commands finish,
+// and the architectural changes the daemon finds between scans
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::core::events::{EventSeverity, MatrixEvent};
+use crate::utils::config::{is_local_url, NotificationsConfig, NotifyCommand, WebhookConfig};
+
+// Slack rejects section blocks with more fields than this
+const MAX_FIELDS: usize = 10;
+
+// Changes listed in one message; the rest are counted
+const MAX_LISTED_EVENTS: usize = 20;
+
+/// What happened in one command run
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub command: NotifyCommand,
+    pub project: String,
+    // Whether the command and its --fail-on gate passed
+    pub success: bool,
+    // "passed", or why the command or gate failed
+    pub gate: String,
+    pub duration: Duration,
+    // (label, value) pairs such as ("Files", "120 (+3)")
+    pub facts: Vec<(String, String)>,
+    // (label, path relative to the project root) of artifacts the run produced
+    pub artifacts: Vec<(String, String)>,
+}
+
+impl Notification {
+    /// Slack-compatible body: a `text` fallback plus blocks. Artifacts become links
+    /// under `link_base` when one is given, plain paths otherwise.
+    pub fn slack_payload(&self, link_base: Option<&str>) -> Value {
+        let icon = if self.success { "✅" } else { "❌" };
+        let headline = format!(
+            "{icon} csd {} {} for {} in {}",
+            self.command,
+            if self.success { "finished" } else { "failed" },
+            self.project,
+            format_duration(self.duration)
+        );
+
+        let mut fields: Vec<Value> = vec![mrkdwn(&format!("*Quality gate*\n{}", self.gate))];
+        fields.extend(
+            self.facts
+                .iter()
+                .map(|(label, value)| mrkdwn(&format!("*{label}*\n{value}"))),
+        );
+        fields.truncate(MAX_FIELDS);
+
+        let mut blocks = vec![
+            json!({ "type": "section", "text": mrkdwn(&headline) }),
+            json!({ "type": "section", "fields": fields }),
+        ];
+        if !self.artifacts.is_empty() {
+            let links: Vec<String> = self
+                .artifacts
+                .iter()
+                .map(|(label, path)| match link_base {
+                    Some(base) => format!("<{}/{path}|{label}>", base.trim_end_matches('/')),
+                    None => format!("{label}: `{path}`"),
+                })
+                .collect();
+            blocks.push(json!({
+                "type": "context",
+                "elements": [mrkdwn(&links.join(" · "))]
+            }));
+        }
+        json!({ "text": headline, "blocks": blocks })
+    }
+}
+
+/// Slack-compatible body listing the changes one scan of `project` found, with the
+/// events themselves under `events` for receivers that aren't Slack
+pub fn events_payload(project: &str, events: &[MatrixEvent]) -> Value {
+    let warnings = events
+        .iter()
+        .filter(|event| event.severity == EventSeverity::Warning)
+        .count();
+    let headline = format!(
+        "{} csd found {} architectural change{} in {project}",
+        if warnings > 0 { "⚠️" } else { "ℹ️" },
+        events.len(),
+        if events.len() == 1 { "" } else { "s" }
+    );
+    let mut lines: Vec<String> = events
+        .iter()
+        .take(MAX_LISTED_EVENTS)
+        .map(|event| match event.severity {
+            EventSeverity::Warning => format!("• *{}*", event.summary),
+            EventSeverity::Info => format!("• {}", event.summary),
+        })
+        .collect();
+    if events.len() > MAX_LISTED_EVENTS {
+        lines.push(format!("…and {} more", events.len() - MAX_LISTED_EVENTS));
+    }
+    json!({
+        "text": headline,
+        "blocks": [
+            { "type": "section", "text": mrkdwn(&headline) },
+            { "type": "section", "text": mrkdwn(&lines.join("\n")) },
+        ],
+        "events": events,
+    })
+}
+
+fn mrkdwn(text: &str) -> Value {
+    json!({ "type": "mrkdwn", "text": text })
+}
+
+/// `42s`, `3m 12s` or `1h 05m`
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// `120 (+3)`, `120 (-2)`, or just `120` with nothing to compare against or no change
+pub fn with_delta(current: u64, previous: Option<u64>) -> String {
+    match previous {
+        Some(previous) if previous != current => {
+            let delta = current as i64 - previous as i64;
+            format!("{current} ({delta:+})")
+        }
+        _ => current.to_string(),
+    }
+}
+
+/// Posts notifications to the configured webhooks
+pub struct Notifier {
+    client: reqwest::Client,
+    offline: bool,
+}
+
+impl Notifier {
+    /// With `offline`, only webhooks at local URLs are contacted
+    pub fn new(offline: bool) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(15))
+            .user_agent(concat!("csd/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self { client, offline })
+    }
+
+    /// Post to every webhook that wants this notification. A webhook that can't be
+    /// reached is only a warning: the command's own result stands.
+    pub async fn notify_all(&self, config: &NotificationsConfig, notification: &Notification) {
+        for webhook in config.webhooks_for(notification.command, notification.success) {
+            if let Err(e) = self.send(webhook, notification).await {
+                warn!("Notification not sent: {e:#}");
+            }
+        }
+    }
+
+    pub async fn send(&self, webhook: &WebhookConfig, notification: &Notification) -> Result<()> {
+        let payload = notification.slack_payload(webhook.link_base.as_deref());
+        let host = self.post(webhook, &payload).await?;
+        info!("Sent {} notification to {host}", notification.command);
+        Ok(())
+    }
+
+    /// Post the changes a scan found to every webhook subscribed to `events`; as with
+    /// run summaries, failures are only warnings
+    pub async fn notify_events(
+        &self,
+        config: &NotificationsConfig,
+        project: &str,
+        events: &[MatrixEvent],
+    ) {
+        if events.is_empty() {
+            return;
+        }
+        let alerting = events
+            .iter()
+            .any(|event| event.severity == EventSeverity::Warning);
+        let payload = events_payload(project, events);
+        for webhook in config.webhooks_for(NotifyCommand::Events, !alerting) {
+            match self.post(webhook, &payload).await {
+                Ok(host) => info!("Sent {} {project} events to {host}", events.len()),
+                Err(e) => warn!("Events not sent: {e:#}"),
+            }
+        }
+    }
+
+    // Returns the host, the only part of the URL that may be shown
+    async fn post(&self, webhook: &WebhookConfig, payload: &Value) -> Result<String> {
+        let url = webhook.resolve_url()?;
+        // Webhook URLs embed their secret, so only the host is ever shown
+        let host = reqwest::Url::parse(&url)
+            .with_context(|| format!("Invalid webhook URL for {}", webhook.describe()))?
+            .host_str()
+            .unwrap_or_default()
+            .to_string();
+        if self.offline && !is_local_url(&url) {
+            return Err(anyhow::anyhow!("Offline mode forbids posting to {host}"));
+        }
+        let response = self
+            .client
+            .post(&url)
+            .json(payload)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to post to {host}: {}", e.without_url()))?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Webhook at {host} answered HTTP {}",
+                response.status()
+            ));
+        }
+        Ok(host)
+    }
+}
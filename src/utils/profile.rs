@@ -0,0 +1,160 @@
+// src/utils/profile.rs - Opt-in timing of where a command spends its time (--profile-perf)
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static PROFILE: LazyLock<Mutex<Profile>> = LazyLock::new(Default::default);
+
+/// Part of a command's work that's timed separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    // Walking the tree, applying ignore rules and matching plugins
+    Walk,
+    Hash,
+    // Starting plugin processes
+    PluginSpawn,
+    // Plugin processes running, from spawn to exit
+    PluginExec,
+    // JSON encoding and decoding of matrices and plugin messages
+    Serde,
+    // Reading matrices from disk
+    Load,
+    // Writing matrices to disk
+    Save,
+}
+
+impl Stage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stage::Walk => "walk",
+            Stage::Hash => "hash",
+            Stage::PluginSpawn => "plugin spawn",
+            Stage::PluginExec => "plugin execution",
+            Stage::Serde => "serde",
+            Stage::Load => "load",
+            Stage::Save => "save",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Profile {
+    started: Option<Instant>,
+    stages: BTreeMap<Stage, (usize, Duration)>,
+}
+
+/// Start recording. Until then spans cost an atomic load and record nothing.
+pub fn enable() {
+    *PROFILE.lock().unwrap_or_else(|e| e.into_inner()) = Profile {
+        started: Some(Instant::now()),
+        stages: BTreeMap::new(),
+    };
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn record(stage: Stage, elapsed: Duration) {
+    if !is_enabled() {
+        return;
+    }
+    let mut profile = PROFILE.lock().unwrap_or_else(|e| e.into_inner());
+    let (calls, total) = profile.stages.entry(stage).or_default();
+    *calls += 1;
+    *total += elapsed;
+}
+
+/// Time `stage` until the returned guard is dropped
+pub fn span(stage: Stage) -> Span {
+    Span {
+        stage,
+        started: is_enabled().then(Instant::now),
+    }
+}
+
+pub struct Span {
+    stage: Stage,
+    started: Option<Instant>,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(started) = self.started {
+            record(self.stage, started.elapsed());
+        }
+    }
+}
+
+/// Time spent in one stage. Plugins run concurrently, so stage totals can add up to
+/// more than the command's wall time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageTiming {
+    pub stage: Stage,
+    pub calls: usize,
+    pub total_ms: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileReport {
+    pub command: String,
+    pub wall_ms: f64,
+    pub stages: Vec<StageTiming>,
+}
+
+impl ProfileReport {
+    pub fn print_table(&self) {
+        eprintln!(
+            "\n⏱️  Profile: csd {} ({:.1} ms)",
+            self.command, self.wall_ms
+        );
+        eprintln!(
+            "{:<18} {:>7} {:>12} {:>7}",
+            "stage", "calls", "total ms", "% wall"
+        );
+        for timing in &self.stages {
+            let share = if self.wall_ms > 0.0 {
+                timing.total_ms / self.wall_ms * 100.0
+            } else {
+                0.0
+            };
+            eprintln!(
+                "{:<18} {:>7} {:>12.1} {:>6.1}%",
+                timing.stage.as_str(),
+                timing.calls,
+                timing.total_ms,
+                share
+            );
+        }
+    }
+}
+
+/// Stop recording and return what was recorded for `command`, or None when profiling
+/// was never enabled
+pub fn finish(command: &str) -> Option<ProfileReport> {
+    if !ENABLED.swap(false, Ordering::Relaxed) {
+        return None;
+    }
+    let profile = std::mem::take(&mut *PROFILE.lock().unwrap_or_else(|e| e.into_inner()));
+    let as_ms = |elapsed: Duration| elapsed.as_secs_f64() * 1000.0;
+    Some(ProfileReport {
+        command: command.to_string(),
+        wall_ms: profile
+            .started
+            .map_or(0.0, |started| as_ms(started.elapsed())),
+        stages: profile
+            .stages
+            .into_iter()
+            .map(|(stage, (calls, total))| StageTiming {
+                stage,
+                calls,
+                total_ms: as_ms(total),
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,131 @@
+// src/utils/secrets.rs - Secret interpolation, keyring storage and redaction
+use anyhow::{Context, Result};
+use log::warn;
+
+/// Compare without returning early, so response times don't reveal how much of a
+/// guessed token was right
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Keyring service name under which `csd config set-secret` stores values
+pub const KEYRING_SERVICE: &str = "csd";
+
+/// Placeholder shown instead of secret values
+pub const REDACTED: &str = "********";
+
+/// Key names (last path segment) whose values are treated as secrets
+const SECRET_KEY_SUFFIXES: &[&str] = &["api_key", "apikey", "token", "secret", "password"];
+
+/// Whether a config key holds a secret, e.g. `llm.api_key` or `github_token`
+pub fn is_secret_key(key: &str) -> bool {
+    let last = key.rsplit('.').next().unwrap_or(key).to_lowercase();
+    SECRET_KEY_SUFFIXES
+        .iter()
+        .any(|suffix| last == *suffix || last.ends_with(&format!("_{suffix}")))
+}
+
+/// Reference stored in the config file for a keyring-held secret
+pub fn keyring_reference(name: &str) -> String {
+    format!("${{keyring:{name}}}")
+}
+
+/// Store a secret in the OS keyring
+pub fn store_secret(name: &str, value: &str) -> Result<()> {
+    keyring::Entry::new(KEYRING_SERVICE, name)
+        .and_then(|entry| entry.set_password(value))
+        .with_context(|| format!("Failed to store secret '{name}' in the OS keyring"))
+}
+
+/// Read a secret from the OS keyring
+pub fn load_secret(name: &str) -> Result<String> {
+    keyring::Entry::new(KEYRING_SERVICE, name)
+        .and_then(|entry| entry.get_password())
+        .with_context(|| format!("Failed to read secret '{name}' from the OS keyring"))
+}
+
+/// Replace `${VAR}`, `${VAR:-default}` and `${keyring:NAME}` references in every string
+/// of a configuration tree. Unset variables without a default become empty strings.
+pub fn interpolate_config_values(value: &mut serde_yaml::Value) -> Result<()> {
+    match value {
+        serde_yaml::Value::String(s) if s.contains("${") => *s = interpolate_str(s)?,
+        serde_yaml::Value::Mapping(map) => {
+            for v in map.values_mut() {
+                interpolate_config_values(v)?;
+            }
+        }
+        serde_yaml::Value::Sequence(items) => {
+            for v in items {
+                interpolate_config_values(v)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Interpolate a single string; `$${` escapes a literal `${`
+pub fn interpolate_str(input: &str) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(pos) = rest.find('$') {
+        output.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        if let Some(escaped) = rest.strip_prefix("$${") {
+            output.push_str("${");
+            rest = escaped;
+            continue;
+        }
+        let Some(body) = rest.strip_prefix("${") else {
+            output.push('$');
+            rest = &rest[1..];
+            continue;
+        };
+        let end = body
+            .find('}')
+            .ok_or_else(|| anyhow::anyhow!("Unterminated '${{' in config value"))?;
+        let reference = &body[..end];
+        rest = &body[end + 1..];
+
+        if let Some(name) = reference.strip_prefix("keyring:") {
+            output.push_str(&load_secret(name)?);
+            continue;
+        }
+
+        let (name, default) = match reference.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (reference, None),
+        };
+        match (std::env::var(name), default) {
+            (Ok(value), _) if !value.is_empty() => output.push_str(&value),
+            (_, Some(default)) => output.push_str(default),
+            _ => warn!("Environment variable {name} referenced in config is not set"),
+        }
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Replace secret values in a configuration tree with [`REDACTED`] before display
+pub fn redact_config_values(value: &mut serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            for (k, v) in map.iter_mut() {
+                let is_secret = k.as_str().is_some_and(is_secret_key);
+                if is_secret && !v.is_null() {
+                    *v = serde_yaml::Value::String(REDACTED.to_string());
+                } else {
+                    redact_config_values(v);
+                }
+            }
+        }
+        serde_yaml::Value::Sequence(items) => items.iter_mut().for_each(redact_config_values),
+        _ => {}
+    }
+}
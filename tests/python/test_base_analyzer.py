@@ -353,6 +353,46 @@ def outer_function():
         )
         assert result == "third_party"
 
+    def test_detect_import_type_with_project_context(self):
+        """Source roots and the project's own packages count as local."""
+        project = {
+            "packages": [
+                {"name": "my-tools", "ecosystem": "python", "manifest": "pyproject.toml"}
+            ],
+            "dependencies": {"python": ["requests"]},
+            "source_roots": ["src"],
+        }
+
+        with patch(
+            "pathlib.Path.exists",
+            autospec=True,
+            side_effect=lambda path: str(path) == "/test/project/src/mymodule.py",
+        ):
+            assert (
+                detect_import_type(
+                    "mymodule", "/test/project", "/test/project/main.py", project
+                )
+                == "local"
+            )
+            assert (
+                detect_import_type(
+                    "mymodule", "/test/project", "/test/project/main.py"
+                )
+                == "third_party"
+            )
+            assert (
+                detect_import_type(
+                    "my_tools.cli", "/test/project", "/test/project/main.py", project
+                )
+                == "local"
+            )
+            assert (
+                detect_import_type(
+                    "requests", "/test/project", "/test/project/main.py", project
+                )
+                == "third_party"
+            )
+
 
 class TestBaseAnalyzer:
     """Test the BaseAnalyzer abstract class."""
@@ -443,10 +483,18 @@ class TestBaseAnalyzer:
             "supported_filenames": ["test.config"],
             "supported_formats": None,
             "supported_output_types": None,
+            "capabilities": ["content_path", "analyze_batch", "persistent", "socket"],
         }
 
         assert info == expected
 
+        # A declared config schema is advertised alongside
+        analyzer.config_schema = {
+            "type": "object",
+            "properties": {"depth": {"type": "integer"}},
+        }
+        assert analyzer.get_info()["config_schema"] == analyzer.config_schema
+
     def test_generate_cache_filename(self, sample_plugin_input):
         """Test cache filename generation using concrete implementation."""
 
@@ -718,6 +766,211 @@ class TestPluginCommunication:
         assert response["name"] == "test_plugin"
         assert response["version"] == "1.0.0"
 
+    def test_persistent_mode_answers_each_line(self, monkeypatch, capsys):
+        """Test that a pooled plugin answers every message line, bad ones included."""
+
+        class ConcreteAnalyzer(BaseAnalyzer):
+            def __init__(self):
+                super().__init__()
+                self.name = "test_plugin"
+
+            def can_analyze(
+                self, file_path: str, content_preview: str
+            ) -> Tuple[bool, float]:
+                return True, 0.5
+
+            def analyze(self, input_data):
+                raise NotImplementedError
+
+        from io import StringIO
+
+        lines = [
+            json.dumps({"type": "get_info"}),
+            "",
+            "not json",
+            json.dumps(
+                {
+                    "type": "can_analyze",
+                    "file_path": "a.test",
+                    "content_preview": "",
+                }
+            ),
+        ]
+        monkeypatch.setenv("CSD_PERSISTENT", "1")
+        monkeypatch.setattr(sys, "stdin", StringIO("\n".join(lines) + "\n"))
+        ConcreteAnalyzer().run()
+
+        responses = [
+            json.loads(line) for line in capsys.readouterr().out.strip().splitlines()
+        ]
+        assert [r["status"] for r in responses] == ["info", "error", "can_analyze"]
+        assert responses[0]["name"] == "test_plugin"
+        assert responses[2]["confidence"] == 0.5
+
+    def test_socket_mode_answers_frames(self, monkeypatch, capsys):
+        """Test that a socket plugin sends its token, then answers each frame."""
+        import socket
+        import struct
+        import threading
+
+        class ConcreteAnalyzer(BaseAnalyzer):
+            def __init__(self):
+                super().__init__()
+                self.name = "test_plugin"
+
+            def can_analyze(
+                self, file_path: str, content_preview: str
+            ) -> Tuple[bool, float]:
+                print("free to log to stdout")
+                return True, 0.5
+
+            def analyze(self, input_data):
+                raise NotImplementedError
+
+        def send(conn, payload):
+            data = payload.encode("utf-8")
+            conn.sendall(struct.pack(">I", len(data)) + data)
+
+        def receive(stream):
+            (length,) = struct.unpack(">I", stream.read(4))
+            return json.loads(stream.read(length))
+
+        core = socket.socket(socket.AF_INET, socket.SOCK_STREAM)
+        core.bind(("127.0.0.1", 0))
+        core.listen(1)
+        host, port = core.getsockname()
+        monkeypatch.setenv("CSD_SOCKET", f"tcp:{host}:{port}")
+        monkeypatch.setenv("CSD_SOCKET_TOKEN", "s3cret")
+
+        plugin = threading.Thread(target=ConcreteAnalyzer().run)
+        plugin.start()
+        conn, _ = core.accept()
+        stream = conn.makefile("rb")
+        assert receive(stream) == {"token": "s3cret"}
+
+        send(conn, json.dumps({"type": "get_info"}))
+        assert receive(stream)["name"] == "test_plugin"
+        send(conn, "not json")
+        assert receive(stream)["status"] == "error"
+        send(
+            conn,
+            json.dumps(
+                {"type": "can_analyze", "file_path": "a.test", "content_preview": ""}
+            ),
+        )
+        assert receive(stream)["confidence"] == 0.5
+
+        stream.close()
+        conn.close()
+        plugin.join(timeout=5)
+        core.close()
+        assert not plugin.is_alive()
+        assert capsys.readouterr().out.strip() == "free to log to stdout"
+
+    def test_analyze_reads_content_from_content_path(
+        self, monkeypatch, capsys, temp_project_dir
+    ):
+        """Test that content passed by path is read before analysis."""
+        seen = {}
+
+        class ConcreteAnalyzer(BaseAnalyzer):
+            def can_analyze(
+                self, file_path: str, content_preview: str
+            ) -> Tuple[bool, float]:
+                return True, 1.0
+
+            def analyze(self, input_data):
+                seen["content"] = input_data.content
+                return PluginOutput(
+                    file_path=input_data.file_path,
+                    file_hash="test",
+                    elements=[],
+                    imports=[],
+                    exports=[],
+                    relationships=[],
+                    external_dependencies=[],
+                )
+
+        source = temp_project_dir / "big.test"
+        source.write_text("line one\nline two\n", encoding="utf-8")
+        message = {
+            "type": "analyze",
+            "input": {
+                "file_path": str(source),
+                "relative_path": "big.test",
+                "content": "",
+                "content_path": str(source),
+                "project_root": str(temp_project_dir),
+                "cache_dir": str(temp_project_dir / ".csd_cache"),
+                "plugin_config": None,
+            },
+        }
+
+        from io import StringIO
+
+        monkeypatch.setattr(sys, "stdin", StringIO(json.dumps(message)))
+        ConcreteAnalyzer().run()
+
+        response = json.loads(capsys.readouterr().out.strip())
+        assert response["status"] == "success"
+        assert seen["content"] == "line one\nline two\n"
+
+    def test_analyze_batch_reports_each_file(
+        self, monkeypatch, capsys, temp_project_dir
+    ):
+        """Test that a batch returns one result per input and isolates failures."""
+
+        class ConcreteAnalyzer(BaseAnalyzer):
+            def can_analyze(
+                self, file_path: str, content_preview: str
+            ) -> Tuple[bool, float]:
+                return True, 1.0
+
+            def analyze(self, input_data):
+                if input_data.relative_path == "bad.test":
+                    raise ValueError("cannot parse")
+                return PluginOutput(
+                    file_path=input_data.file_path,
+                    file_hash="test",
+                    elements=[],
+                    imports=[],
+                    exports=[],
+                    relationships=[],
+                    external_dependencies=[],
+                )
+
+        def make_input(name):
+            return {
+                "file_path": str(temp_project_dir / name),
+                "relative_path": name,
+                "content": "x = 1\n",
+                "project_root": str(temp_project_dir),
+                "cache_dir": str(temp_project_dir / ".csd_cache"),
+                "plugin_config": None,
+            }
+
+        message = {
+            "type": "analyze_batch",
+            "inputs": [
+                make_input("a.test"),
+                make_input("bad.test"),
+                make_input("b.test"),
+            ],
+        }
+
+        from io import StringIO
+
+        monkeypatch.setattr(sys, "stdin", StringIO(json.dumps(message)))
+        ConcreteAnalyzer().run()
+
+        response = json.loads(capsys.readouterr().out.strip())
+        assert response["status"] == "batch_success"
+        results = response["results"]
+        assert [r["relative_path"] for r in results] == ["a.test", "bad.test", "b.test"]
+        assert "cache_file" in results[0] and "cache_file" in results[2]
+        assert results[1]["error"] == "cannot parse"
+        assert "cache_file" not in results[1]
+
     def test_plugin_communication_invalid_json(self, monkeypatch, capsys):
         """Test handling of invalid JSON input using concrete implementation."""
 
@@ -319,6 +319,35 @@ class Person:
         assert "dataclass" in person_class.metadata["decorators"]
         assert person_class.summary == "A person with dataclass decorator."
 
+    def test_analyze_methods_are_qualified_by_class(self):
+        """Test that methods and nested classes record their enclosing class."""
+        code = """
+class Config:
+    def load(self):
+        def helper():
+            pass
+        return helper()
+
+    class Loader:
+        async def fetch(self):
+            pass
+
+def load():
+    pass
+"""
+        input_data = self.create_plugin_input(code)
+        result = self.analyzer._analyze_python_code(input_data)
+
+        by_line = {e.line_start: e for e in result.elements}
+        assert by_line[3].qualified_name == "Config.load"
+        assert by_line[3].parent == "Config"
+        assert by_line[9].qualified_name == "Config.Loader.fetch"
+        assert by_line[8].parent == "Config"
+        # Local to a method, and at module level
+        assert by_line[4].qualified_name is None
+        assert by_line[12].qualified_name is None
+        assert by_line[2].parent is None
+
     def test_token_info_calculation(self):
         """Test that token_info is properly calculated."""
         code = '''#!/usr/bin/env python3
@@ -557,7 +586,7 @@ _PRIVATE_CONSTANT = "not exported"
         input_data = self.create_plugin_input(code)
         result = self.analyzer._analyze_python_code(input_data)
 
-        exports = result.exports
+        exports = [export.name for export in result.exports]
 
         # Public items should be exported
         assert "public_function" in exports
@@ -569,6 +598,34 @@ _PRIVATE_CONSTANT = "not exported"
         assert "_PrivateClass" not in exports
         assert "_PRIVATE_CONSTANT" not in exports
 
+    def test_analyze_package_reexports(self, tmp_path):
+        """Test that a package's __init__.py re-exports names from its modules."""
+        package = tmp_path / "pkg"
+        package.mkdir()
+        (package / "parser.py").write_text("class RawParser:\n    pass\n")
+        code = """
+from .parser import RawParser as Parser
+from .parser import _helper
+import json
+
+VERSION = "1.0"
+"""
+        (package / "__init__.py").write_text(code)
+        input_data = PluginInput(
+            file_path=str(package / "__init__.py"),
+            relative_path="pkg/__init__.py",
+            content=code,
+            project_root=str(tmp_path),
+            cache_dir=str(tmp_path / ".csd_cache"),
+        )
+        result = self.analyzer._analyze_python_code(input_data)
+
+        exports = {export.exported_name: export for export in result.exports}
+        assert set(exports) == {"Parser", "VERSION"}
+        assert exports["Parser"].name == "RawParser"
+        assert exports["Parser"].source == "pkg/parser.py"
+        assert exports["VERSION"].source is None
+
     def test_analyze_syntax_error(self):
         """Test handling of Python syntax errors."""
         # Code with syntax error
@@ -355,6 +355,39 @@ trait Clone {
         assert iterator_trait.metadata["is_public"] is True
         assert iterator_trait.summary == "Iterator trait for traversing collections."
 
+    def test_analyze_impl_methods_are_qualified_by_type(self):
+        """Test that functions in impl and trait blocks record their type."""
+        code = """
+pub struct Config {}
+
+impl Config {
+    pub fn load() -> Self {
+        Config {}
+    }
+}
+
+impl Display for Config {
+    fn fmt(&self) {}
+}
+
+pub trait Loader {
+    fn fetch(&self);
+}
+
+fn load() {}
+"""
+        input_data = self.create_plugin_input(code)
+        result = self.analyzer._analyze_rust_code(input_data)
+
+        functions = {
+            e.line_start: e for e in result.elements if e.element_type == "function"
+        }
+        assert functions[5].qualified_name == "Config::load"
+        assert functions[5].parent == "Config"
+        assert functions[11].qualified_name == "Config::fmt"
+        assert functions[15].qualified_name == "Loader::fetch"
+        assert functions[18].qualified_name is None
+
     def test_token_info_calculation(self):
         """Test that token_info is properly calculated."""
         code = """// Regular comment here
@@ -672,7 +705,7 @@ mod private_module {}
         input_data = self.create_plugin_input(code)
         result = self.analyzer._analyze_rust_code(input_data)
 
-        exports = result.exports
+        exports = [export.name for export in result.exports]
 
         # Public items should be exported
         assert "public_function" in exports
@@ -688,6 +721,33 @@ mod private_module {}
         assert "PRIVATE_CONST" not in exports
         assert "private_module" not in exports
 
+    def test_analyze_reexports(self, tmp_path):
+        """Test that `pub use` re-exports record their alias and source file."""
+        (tmp_path / "src").mkdir()
+        (tmp_path / "src" / "parser.rs").write_text("pub struct RawParser;\n")
+        code = """
+pub use crate::parser::{RawParser as Parser, Token};
+pub use serde::Serialize;
+pub use crate::lexer::*;
+use crate::parser::Hidden;
+"""
+        input_data = PluginInput(
+            file_path=str(tmp_path / "src" / "lib.rs"),
+            relative_path="src/lib.rs",
+            content=code,
+            project_root=str(tmp_path),
+            cache_dir=str(tmp_path / ".csd_cache"),
+        )
+        result = self.analyzer._analyze_rust_code(input_data)
+
+        exports = {export.exported_name: export for export in result.exports}
+        assert set(exports) == {"Parser", "Token", "Serialize"}
+        assert exports["Parser"].name == "RawParser"
+        assert exports["Parser"].source == "src/parser.rs"
+        assert exports["Token"].alias is None
+        # Not a file in this project
+        assert exports["Serialize"].source is None
+
 
 class TestRustEcosystemFiles:
     """Test analysis of Rust ecosystem files like Cargo.toml, Cargo.lock."""
@@ -1013,10 +1073,11 @@ mod tests {
 
         # Should have identified exports
         assert len(result.exports) > 0
-        assert "User" in result.exports
-        assert "DatabaseError" in result.exports
-        assert "Repository" in result.exports
-        assert "process_users" in result.exports
+        export_names = [export.name for export in result.exports]
+        assert "User" in export_names
+        assert "DatabaseError" in export_names
+        assert "Repository" in export_names
+        assert "process_users" in export_names
 
         # Check token info
         assert result.token_info is not None
@@ -1,5 +1,7 @@
 // CLI module tests
 
 pub mod test_args;
+pub mod test_exit;
+pub mod test_porcelain;
 // Future CLI test modules would go here:
 // pub mod test_commands;
@@ -1,6 +1,16 @@
 use clap::Parser;
-use csd::cli::args::{Args, Command, DocFormat, OutputFormat, QualityMetric};
+use csd::cli::args::{
+    parse_interval, AdrAction, ApiAction, Args, Command, ConfigAction, CoverageAction, DocFormat,
+    ExportFormat, FailOn, GraphFormat, MatrixAction, OutputFormat, PluginAction, PluginKind,
+    QualityMetric, RegistryAction, ServicesAction, TraceAction, TrendFormat,
+};
+use csd::core::annotations::ReviewStatus;
+use csd::core::coverage::CoverageFormat;
+use csd::core::interactions::InteractionSource;
+use csd::core::traces::TraceFormat;
+use csd::utils::config::ScanLevel;
 use std::path::PathBuf;
+use std::time::Duration;
 
 // Helper function to parse args from a string slice
 fn parse_args(args: &[&str]) -> Result<Args, clap::Error> {
@@ -23,16 +33,22 @@ mod basic_parsing_tests {
         match args.command {
             Command::Init {
                 path,
+                more_paths,
                 output,
                 output_file,
                 no_llm,
                 include_tests,
+                strict,
+                level,
             } => {
                 assert!(path.is_none()); // Default: no path specified
+                assert!(more_paths.is_empty());
                 assert!(matches!(output, OutputFormat::Json)); // Default output format
                 assert!(output_file.is_none()); // No output file specified
                 assert!(!no_llm); // Default: LLM enabled
                 assert!(!include_tests); // Default: tests not included
+                assert!(!strict); // Default: unreadable paths are skipped
+                assert!(level.is_none()); // Default: scanning.level decides
             }
             _ => panic!("Expected Init command"),
         }
@@ -42,6 +58,17 @@ mod basic_parsing_tests {
         assert!(args.project.is_none()); // No project specified
     }
 
+    #[test]
+    fn test_scan_is_init_with_level() {
+        let args = parse_args_success(&["csd", "scan", "--level", "structure"]);
+
+        match args.command {
+            Command::Init { level, .. } => assert_eq!(level, Some(ScanLevel::Structure)),
+            _ => panic!("Expected Init command"),
+        }
+        assert!(parse_args(&["csd", "scan", "--level", "deep"]).is_err());
+    }
+
     #[test]
     fn test_init_command_with_path() {
         let args = parse_args_success(&["csd", "init", "/path/to/project"]);
@@ -54,6 +81,23 @@ mod basic_parsing_tests {
         }
     }
 
+    #[test]
+    fn test_init_command_with_several_roots() {
+        let args = parse_args_success(&["csd", "init", "backend", "frontend", "infra"]);
+        match args.command {
+            Command::Init {
+                path, more_paths, ..
+            } => {
+                assert_eq!(path, Some(PathBuf::from("backend")));
+                assert_eq!(
+                    more_paths,
+                    vec![PathBuf::from("frontend"), PathBuf::from("infra")]
+                );
+            }
+            _ => panic!("Expected Init command"),
+        }
+    }
+
     #[test]
     fn test_init_command_with_all_options() {
         let args = parse_args_success(&[
@@ -66,21 +110,30 @@ mod basic_parsing_tests {
             "results.yaml",
             "--no-llm",
             "--include-tests",
+            "--strict",
+            "--level",
+            "structure",
         ]);
 
         match args.command {
             Command::Init {
                 path,
+                more_paths,
                 output,
                 output_file,
                 no_llm,
                 include_tests,
+                strict,
+                level,
             } => {
                 assert_eq!(path, Some(PathBuf::from("/project")));
+                assert!(more_paths.is_empty());
                 assert!(matches!(output, OutputFormat::Yaml));
                 assert_eq!(output_file, Some(PathBuf::from("results.yaml")));
                 assert!(no_llm);
                 assert!(include_tests);
+                assert!(strict);
+                assert_eq!(level, Some(ScanLevel::Structure));
             }
             _ => panic!("Expected Init command"),
         }
@@ -131,7 +184,9 @@ mod quality_command_tests {
         let args = parse_args_success(&["csd", "quality"]);
 
         match args.command {
-            Command::Quality { matrix, metrics } => {
+            Command::Quality {
+                matrix, metrics, ..
+            } => {
                 assert!(matrix.is_none()); // No matrix file specified
                 assert!(metrics.is_empty()); // No specific metrics specified
             }
@@ -153,7 +208,9 @@ mod quality_command_tests {
         ]);
 
         match args.command {
-            Command::Quality { matrix, metrics } => {
+            Command::Quality {
+                matrix, metrics, ..
+            } => {
                 assert_eq!(matrix, Some(PathBuf::from("/path/to/matrix.json")));
                 assert_eq!(metrics.len(), 2);
                 assert!(metrics
@@ -165,6 +222,20 @@ mod quality_command_tests {
         }
     }
 
+    #[test]
+    fn test_quality_command_suggest() {
+        for (argv, expected) in [
+            (vec!["csd", "quality"], None),
+            (vec!["csd", "quality", "--suggest"], Some(5)),
+            (vec!["csd", "quality", "--suggest=2"], Some(2)),
+        ] {
+            match parse_args_success(&argv).command {
+                Command::Quality { suggest, .. } => assert_eq!(suggest, expected, "{argv:?}"),
+                _ => panic!("Expected Quality command"),
+            }
+        }
+    }
+
     #[test]
     fn test_quality_metrics_all_types() {
         let args = parse_args_success(&[
@@ -181,12 +252,14 @@ mod quality_command_tests {
             "--metrics",
             "performance",
             "--metrics",
+            "contracts",
+            "--metrics",
             "all",
         ]);
 
         match args.command {
             Command::Quality { metrics, .. } => {
-                assert_eq!(metrics.len(), 6);
+                assert_eq!(metrics.len(), 7);
                 assert!(metrics
                     .iter()
                     .any(|m| matches!(m, QualityMetric::Complexity)));
@@ -198,6 +271,9 @@ mod quality_command_tests {
                 assert!(metrics
                     .iter()
                     .any(|m| matches!(m, QualityMetric::Performance)));
+                assert!(metrics
+                    .iter()
+                    .any(|m| matches!(m, QualityMetric::Contracts)));
                 assert!(metrics.iter().any(|m| matches!(m, QualityMetric::All)));
             }
             _ => panic!("Expected Quality command"),
@@ -218,10 +294,20 @@ mod docs_command_tests {
                 matrix,
                 format,
                 output_dir,
+                resume,
+                scope,
+                scope_depth,
+                stdout,
+                check_drift,
             } => {
                 assert!(matrix.is_none()); // No matrix file specified
                 assert!(matches!(format, DocFormat::Markdown)); // Default format
                 assert!(output_dir.is_none()); // No output directory specified
+                assert!(!resume);
+                assert!(scope.is_empty());
+                assert_eq!(scope_depth, 1);
+                assert!(!stdout);
+                assert!(!check_drift);
             }
             _ => panic!("Expected Docs command"),
         }
@@ -238,6 +324,7 @@ mod docs_command_tests {
             "html",
             "--output-dir",
             "/docs/output",
+            "--resume",
         ]);
 
         match args.command {
@@ -245,10 +332,13 @@ mod docs_command_tests {
                 matrix,
                 format,
                 output_dir,
+                resume,
+                ..
             } => {
                 assert_eq!(matrix, Some(PathBuf::from("matrix.json")));
                 assert!(matches!(format, DocFormat::Html));
                 assert_eq!(output_dir, Some(PathBuf::from("/docs/output")));
+                assert!(resume);
             }
             _ => panic!("Expected Docs command"),
         }
@@ -283,6 +373,86 @@ mod docs_command_tests {
             _ => panic!("Expected Docs command"),
         }
     }
+
+    #[test]
+    fn test_docs_command_stdout() {
+        let args = parse_args_success(&["csd", "docs", "--stdout"]);
+        match args.command {
+            Command::Docs { stdout, .. } => assert!(stdout),
+            _ => panic!("Expected Docs command"),
+        }
+
+        // There is no output directory when the document goes to stdout
+        assert!(parse_args(&["csd", "docs", "--stdout", "-o", "docs"]).is_err());
+    }
+
+    #[test]
+    fn test_docs_command_check_drift() {
+        let args = parse_args_success(&["csd", "docs", "--check-drift", "-m", "matrix.json"]);
+        match args.command {
+            Command::Docs {
+                check_drift,
+                matrix,
+                ..
+            } => {
+                assert!(check_drift);
+                assert_eq!(matrix, Some(PathBuf::from("matrix.json")));
+            }
+            _ => panic!("Expected Docs command"),
+        }
+
+        // Checking generates nothing
+        assert!(parse_args(&["csd", "docs", "--check-drift", "--stdout"]).is_err());
+    }
+
+    #[test]
+    fn test_changelog_command() {
+        let args = parse_args_success(&["csd", "changelog", "--since", "v1.2.0", "--polish"]);
+        match args.command {
+            Command::Changelog {
+                since,
+                matrix,
+                output_dir,
+                polish,
+            } => {
+                assert_eq!(since, "v1.2.0");
+                assert!(matrix.is_none());
+                assert!(output_dir.is_none());
+                assert!(polish);
+            }
+            _ => panic!("Expected Changelog command"),
+        }
+
+        // There's no default starting point
+        assert!(parse_args(&["csd", "changelog"]).is_err());
+    }
+
+    #[test]
+    fn test_docs_command_scope() {
+        let args = parse_args_success(&[
+            "csd",
+            "docs",
+            "--scope",
+            "packages/api",
+            "--scope",
+            "**/*.py",
+            "--tag",
+            "public",
+            "--scope-depth",
+            "2",
+        ]);
+        // --tag is global, scoping every command that reads the matrix
+        assert_eq!(args.tags, vec!["public"]);
+        match args.command {
+            Command::Docs {
+                scope, scope_depth, ..
+            } => {
+                assert_eq!(scope, vec!["packages/api", "**/*.py"]);
+                assert_eq!(scope_depth, 2);
+            }
+            _ => panic!("Expected Docs command"),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -312,6 +482,905 @@ mod other_commands_tests {
             _ => panic!("Expected Plugins command"),
         }
     }
+
+    #[test]
+    fn test_query_command() {
+        let args = parse_args_success(&[
+            "csd",
+            "query",
+            "--source-plugin",
+            "rust",
+            "--type",
+            "import",
+            "--type",
+            "call",
+            "--from",
+            "src/main.rs",
+        ]);
+        match args.command {
+            Command::Query {
+                matrix,
+                from,
+                to,
+                relationship_types,
+                source_plugin,
+                min_strength,
+                exclude_tests,
+                output,
+            } => {
+                assert!(matrix.is_none());
+                assert_eq!(from, Some(PathBuf::from("src/main.rs")));
+                assert!(to.is_none());
+                assert_eq!(relationship_types, vec!["import", "call"]);
+                assert_eq!(source_plugin, Some("rust".to_string()));
+                assert!(min_strength.is_none());
+                assert!(!exclude_tests);
+                assert!(matches!(output, OutputFormat::Pretty));
+            }
+            _ => panic!("Expected Query command"),
+        }
+
+        let args = parse_args_success(&[
+            "csd",
+            "query",
+            "--types",
+            "import,call",
+            "--min-strength",
+            "0.5",
+            "--exclude-tests",
+        ]);
+        match args.command {
+            Command::Query {
+                relationship_types,
+                min_strength,
+                exclude_tests,
+                ..
+            } => {
+                assert_eq!(relationship_types, vec!["import", "call"]);
+                assert_eq!(min_strength, Some(0.5));
+                assert!(exclude_tests);
+            }
+            _ => panic!("Expected Query command"),
+        }
+    }
+
+    #[test]
+    fn test_trend_command() {
+        let args = parse_args_success(&["csd", "trend"]);
+        match args.command {
+            Command::Trend {
+                format,
+                limit,
+                output_file,
+            } => {
+                assert!(matches!(format, TrendFormat::Table));
+                assert!(limit.is_none());
+                assert!(output_file.is_none());
+            }
+            _ => panic!("Expected Trend command"),
+        }
+
+        let args = parse_args_success(&["csd", "trend", "--format", "csv", "-n", "5"]);
+        match args.command {
+            Command::Trend { format, limit, .. } => {
+                assert!(matches!(format, TrendFormat::Csv));
+                assert_eq!(limit, Some(5));
+            }
+            _ => panic!("Expected Trend command"),
+        }
+    }
+
+    #[test]
+    fn test_stats_command() {
+        let args = parse_args_success(&["csd", "stats", "--top", "10", "-o", "json"]);
+        match args.command {
+            Command::Stats {
+                matrix,
+                output,
+                top,
+            } => {
+                assert!(matrix.is_none());
+                assert!(matches!(output, OutputFormat::Json));
+                assert_eq!(top, 10);
+            }
+            _ => panic!("Expected Stats command"),
+        }
+    }
+
+    #[test]
+    fn test_api_commands() {
+        let args = parse_args_success(&["csd", "api", "extract", "-f", "api.json"]);
+        match args.command {
+            Command::Api {
+                action:
+                    ApiAction::Extract {
+                        matrix,
+                        output_file,
+                    },
+            } => {
+                assert!(matrix.is_none());
+                assert_eq!(output_file, Some(PathBuf::from("api.json")));
+            }
+            _ => panic!("Expected Api Extract command"),
+        }
+
+        let args = parse_args_success(&["csd", "api", "diff", "old.json", "--fail-on-breaking"]);
+        match args.command {
+            Command::Api {
+                action:
+                    ApiAction::Diff {
+                        old,
+                        new,
+                        output,
+                        fail_on_breaking,
+                    },
+            } => {
+                assert_eq!(old, PathBuf::from("old.json"));
+                assert!(new.is_none());
+                assert!(matches!(output, OutputFormat::Pretty));
+                assert!(fail_on_breaking);
+            }
+            _ => panic!("Expected Api Diff command"),
+        }
+
+        assert!(parse_args(&["csd", "api", "diff"]).is_err());
+    }
+
+    #[test]
+    fn test_debt_command() {
+        let args = parse_args_success(&["csd", "debt", "--verify", "-o", "json"]);
+        match args.command {
+            Command::Debt {
+                matrix,
+                verify,
+                output,
+            } => {
+                assert!(matrix.is_none());
+                assert!(verify);
+                assert!(matches!(output, OutputFormat::Json));
+            }
+            _ => panic!("Expected Debt command"),
+        }
+    }
+
+    #[test]
+    fn test_coverage_import_command() {
+        let args = parse_args_success(&[
+            "csd",
+            "coverage",
+            "import",
+            "coverage.xml",
+            "--format",
+            "cobertura",
+        ]);
+        match args.command {
+            Command::Coverage {
+                action:
+                    CoverageAction::Import {
+                        report,
+                        format,
+                        matrix,
+                    },
+            } => {
+                assert_eq!(report, PathBuf::from("coverage.xml"));
+                assert_eq!(format, Some(CoverageFormat::Cobertura));
+                assert!(matrix.is_none());
+            }
+            _ => panic!("Expected Coverage Import command"),
+        }
+
+        assert!(parse_args(&["csd", "coverage", "import"]).is_err());
+    }
+
+    #[test]
+    fn test_trace_import_command() {
+        let args = parse_args_success(&["csd", "trace", "import", "perf.folded", "-m", "m.json"]);
+        match args.command {
+            Command::Trace {
+                action:
+                    TraceAction::Import {
+                        trace,
+                        format,
+                        matrix,
+                    },
+            } => {
+                assert_eq!(trace, PathBuf::from("perf.folded"));
+                assert!(format.is_none());
+                assert_eq!(matrix, Some(PathBuf::from("m.json")));
+            }
+            _ => panic!("Expected Trace Import command"),
+        }
+
+        let args =
+            parse_args_success(&["csd", "trace", "import", "calls.json", "--format", "json"]);
+        assert!(matches!(
+            args.command,
+            Command::Trace {
+                action: TraceAction::Import {
+                    format: Some(TraceFormat::Json),
+                    ..
+                }
+            }
+        ));
+    }
+
+    #[test]
+    fn test_services_import_command() {
+        let args = parse_args_success(&["csd", "services", "import", "graph.prom"]);
+        match args.command {
+            Command::Services {
+                action:
+                    ServicesAction::Import {
+                        file,
+                        format,
+                        service,
+                        matrix,
+                    },
+            } => {
+                assert_eq!(file, PathBuf::from("graph.prom"));
+                assert!(format.is_none());
+                assert!(service.is_none());
+                assert!(matrix.is_none());
+            }
+            _ => panic!("Expected Services Import command"),
+        }
+
+        let args = parse_args_success(&[
+            "csd",
+            "services",
+            "import",
+            "envoy.log",
+            "--format",
+            "access-log",
+            "--service",
+            "shop",
+        ]);
+        assert!(matches!(
+            args.command,
+            Command::Services {
+                action: ServicesAction::Import {
+                    format: Some(InteractionSource::AccessLog),
+                    service: Some(ref service),
+                    ..
+                }
+            } if service == "shop"
+        ));
+    }
+
+    #[test]
+    fn test_daemon_command() {
+        let args = parse_args_success(&["csd", "daemon"]);
+        match args.command {
+            Command::Daemon {
+                interval,
+                on_commits,
+                poll,
+                listen,
+                no_api,
+                registry,
+            } => {
+                assert!(registry.is_none());
+                assert_eq!(interval, Duration::from_secs(6 * 3600));
+                assert!(!on_commits);
+                assert_eq!(poll, Duration::from_secs(300));
+                assert_eq!(listen.to_string(), "127.0.0.1:7878");
+                assert!(!no_api);
+            }
+            _ => panic!("Expected Daemon command"),
+        }
+
+        let args = parse_args_success(&[
+            "csd",
+            "daemon",
+            "--interval",
+            "1d",
+            "--on-commits",
+            "--poll",
+            "90",
+            "--no-api",
+            "--registry",
+        ]);
+        match args.command {
+            Command::Daemon {
+                interval,
+                on_commits,
+                poll,
+                no_api,
+                registry,
+                ..
+            } => {
+                assert_eq!(registry, Some(None));
+                assert_eq!(interval, Duration::from_secs(86400));
+                assert!(on_commits);
+                assert_eq!(poll, Duration::from_secs(90));
+                assert!(no_api);
+            }
+            _ => panic!("Expected Daemon command"),
+        }
+
+        assert!(parse_args(&["csd", "daemon", "--interval", "0h"]).is_err());
+        assert!(parse_args(&["csd", "daemon", "--interval", "6 weeks"]).is_err());
+        assert_eq!(parse_interval("30m"), Ok(Duration::from_secs(1800)));
+    }
+
+    #[test]
+    fn test_registry_commands() {
+        let args = parse_args_success(&[
+            "csd",
+            "registry",
+            "--file",
+            "services.yaml",
+            "add",
+            "shop",
+            "https://github.com/acme/shop.git",
+            "--branch",
+            "main",
+        ]);
+        match args.command {
+            Command::Registry {
+                file,
+                action:
+                    RegistryAction::Add {
+                        id,
+                        source,
+                        branch,
+                        config,
+                    },
+            } => {
+                assert_eq!(file, Some(PathBuf::from("services.yaml")));
+                assert_eq!(id, "shop");
+                assert_eq!(source, "https://github.com/acme/shop.git");
+                assert_eq!(branch.as_deref(), Some("main"));
+                assert!(config.is_none());
+            }
+            _ => panic!("Expected Registry add command"),
+        }
+
+        let args = parse_args_success(&["csd", "registry", "remove", "shop"]);
+        assert!(matches!(
+            args.command,
+            Command::Registry {
+                action: RegistryAction::Remove { .. },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_adr_commands() {
+        let args = parse_args_success(&[
+            "csd",
+            "adr",
+            "new",
+            "Use event bus",
+            "--affects",
+            "src/events",
+            "--affects",
+            "**/*.proto",
+        ]);
+        match args.command {
+            Command::Adr {
+                action: AdrAction::New { title, affects },
+            } => {
+                assert_eq!(title, "Use event bus");
+                assert_eq!(affects, vec!["src/events", "**/*.proto"]);
+            }
+            _ => panic!("Expected Adr New command"),
+        }
+
+        let args = parse_args_success(&["csd", "adr", "index"]);
+        match args.command {
+            Command::Adr {
+                action: AdrAction::Index { matrix },
+            } => assert!(matrix.is_none()),
+            _ => panic!("Expected Adr Index command"),
+        }
+
+        assert!(parse_args(&["csd", "adr", "new"]).is_err());
+    }
+
+    #[test]
+    fn test_annotate_command() {
+        let args = parse_args_success(&[
+            "csd",
+            "annotate",
+            "src/billing.rs",
+            "--element",
+            "charge",
+            "--note",
+            "Retries twice",
+            "--author",
+            "dana",
+            "--review",
+            "needs-review",
+        ]);
+        match args.command {
+            Command::Annotate {
+                path,
+                element,
+                note,
+                author,
+                owner,
+                review,
+                clear,
+                matrix,
+            } => {
+                assert_eq!(path, Some(PathBuf::from("src/billing.rs")));
+                assert_eq!(element.as_deref(), Some("charge"));
+                assert_eq!(note.as_deref(), Some("Retries twice"));
+                assert_eq!(author.as_deref(), Some("dana"));
+                assert!(owner.is_none());
+                assert_eq!(review, Some(ReviewStatus::NeedsReview));
+                assert!(!clear);
+                assert!(matrix.is_none());
+            }
+            _ => panic!("Expected Annotate command"),
+        }
+
+        // Listing takes no path; changes need one, and an author needs a note
+        assert!(parse_args(&["csd", "annotate"]).is_ok());
+        assert!(parse_args(&["csd", "annotate", "--owner", "payments"]).is_err());
+        assert!(parse_args(&["csd", "annotate", "src/lib.rs", "--author", "dana"]).is_err());
+        assert!(parse_args(&["csd", "annotate", "src/lib.rs", "--review", "maybe"]).is_err());
+    }
+
+    #[test]
+    fn test_explain_command() {
+        let args = parse_args_success(&["csd", "explain", "src/main.rs"]);
+        match args.command {
+            Command::Explain { path } => assert_eq!(path, PathBuf::from("src/main.rs")),
+            _ => panic!("Expected Explain command"),
+        }
+        assert!(parse_args(&["csd", "explain"]).is_err());
+    }
+
+    #[test]
+    fn test_export_command_destination() {
+        let args = parse_args_success(&["csd", "export", "-"]);
+        match args.command {
+            Command::Export {
+                output_file,
+                destination,
+                ..
+            } => {
+                assert!(output_file.is_none());
+                assert_eq!(destination, Some(PathBuf::from("-")));
+            }
+            _ => panic!("Expected Export command"),
+        }
+
+        assert!(parse_args(&["csd", "export", "out.json", "-f", "other.json"]).is_err());
+    }
+
+    #[test]
+    fn test_export_command() {
+        let args = parse_args_success(&["csd", "export", "--redact", "-f", "shared.json"]);
+        match args.command {
+            Command::Export {
+                matrix,
+                output_file,
+                destination,
+                output,
+                format,
+                directories,
+                redact,
+                hash_paths,
+                relationship_types,
+                min_strength,
+                exclude_tests,
+                language,
+            } => {
+                assert!(matrix.is_none());
+                assert_eq!(output_file, Some(PathBuf::from("shared.json")));
+                assert!(destination.is_none());
+                assert!(matches!(output, OutputFormat::Json));
+                assert_eq!(format, ExportFormat::Matrix);
+                assert!(!directories);
+                assert!(redact);
+                assert!(!hash_paths);
+                assert!(relationship_types.is_empty());
+                assert!(min_strength.is_none());
+                assert!(!exclude_tests);
+                assert!(language.is_none());
+            }
+            _ => panic!("Expected Export command"),
+        }
+
+        let args = parse_args_success(&[
+            "csd",
+            "export",
+            "--format",
+            "dsm-html",
+            "--types",
+            "import",
+            "--type",
+            "call",
+            "--min-strength",
+            "0.3",
+            "--exclude-tests",
+            "--language",
+            "python",
+        ]);
+        match args.command {
+            Command::Export {
+                relationship_types,
+                min_strength,
+                exclude_tests,
+                language,
+                ..
+            } => {
+                assert_eq!(relationship_types, vec!["import", "call"]);
+                assert_eq!(min_strength, Some(0.3));
+                assert!(exclude_tests);
+                assert_eq!(language.as_deref(), Some("python"));
+            }
+            _ => panic!("Expected Export command"),
+        }
+
+        let args = parse_args_success(&["csd", "export", "--hash-paths", "-o", "yaml"]);
+        match args.command {
+            Command::Export {
+                output,
+                redact,
+                hash_paths,
+                ..
+            } => {
+                assert!(matches!(output, OutputFormat::Yaml));
+                assert!(!redact);
+                assert!(hash_paths);
+            }
+            _ => panic!("Expected Export command"),
+        }
+
+        let args = parse_args_success(&[
+            "csd",
+            "export",
+            "--format",
+            "adjacency-csv",
+            "--directories",
+        ]);
+        match args.command {
+            Command::Export {
+                format,
+                directories,
+                ..
+            } => {
+                assert_eq!(format, ExportFormat::AdjacencyCsv);
+                assert!(directories);
+            }
+            _ => panic!("Expected Export command"),
+        }
+        assert!(parse_args(&["csd", "export", "--format", "xlsx"]).is_err());
+    }
+
+    #[test]
+    fn test_matrix_lint_command() {
+        let args = parse_args_success(&["csd", "matrix", "lint"]);
+        assert_eq!(args.command.name(), "matrix");
+        match args.command {
+            Command::Matrix {
+                action:
+                    MatrixAction::Lint {
+                        matrix,
+                        fix,
+                        output,
+                    },
+            } => {
+                assert!(matrix.is_none());
+                assert!(!fix);
+                assert!(matches!(output, OutputFormat::Pretty));
+            }
+            _ => panic!("Expected Matrix Lint command"),
+        }
+
+        let args = parse_args_success(&[
+            "csd",
+            "matrix",
+            "lint",
+            "-m",
+            "plugin.json",
+            "--fix",
+            "-o",
+            "json",
+        ]);
+        match args.command {
+            Command::Matrix {
+                action:
+                    MatrixAction::Lint {
+                        matrix,
+                        fix,
+                        output,
+                    },
+            } => {
+                assert_eq!(matrix, Some(PathBuf::from("plugin.json")));
+                assert!(fix);
+                assert!(matches!(output, OutputFormat::Json));
+            }
+            _ => panic!("Expected Matrix Lint command"),
+        }
+    }
+
+    #[test]
+    fn test_matrix_compact_command() {
+        let args = parse_args_success(&["csd", "matrix", "compact"]);
+        match args.command {
+            Command::Matrix {
+                action:
+                    MatrixAction::Compact {
+                        matrix,
+                        metadata,
+                        signatures,
+                        summaries,
+                        graph_only,
+                        output,
+                    },
+            } => {
+                assert!(matrix.is_none());
+                assert!(!metadata && !signatures && !summaries && !graph_only);
+                assert!(matches!(output, OutputFormat::Pretty));
+            }
+            _ => panic!("Expected Matrix Compact command"),
+        }
+
+        let args = parse_args_success(&["csd", "matrix", "compact", "--graph-only", "-o", "json"]);
+        match args.command {
+            Command::Matrix {
+                action:
+                    MatrixAction::Compact {
+                        graph_only,
+                        summaries,
+                        output,
+                        ..
+                    },
+            } => {
+                assert!(graph_only);
+                assert!(!summaries);
+                assert!(matches!(output, OutputFormat::Json));
+            }
+            _ => panic!("Expected Matrix Compact command"),
+        }
+    }
+
+    #[test]
+    fn test_graph_command() {
+        let args = parse_args_success(&["csd", "graph", "--focus", "src/core/"]);
+        match args.command {
+            Command::Graph {
+                matrix,
+                focus,
+                depth,
+                format,
+                output_file,
+                relationship_types,
+                min_strength,
+                exclude_tests,
+            } => {
+                assert!(matrix.is_none());
+                assert_eq!(focus, PathBuf::from("src/core/"));
+                assert_eq!(depth, 1);
+                assert_eq!(format, GraphFormat::Dot);
+                assert!(output_file.is_none());
+                assert!(relationship_types.is_empty());
+                assert!(min_strength.is_none());
+                assert!(!exclude_tests);
+            }
+            _ => panic!("Expected Graph command"),
+        }
+
+        let args = parse_args_success(&[
+            "csd",
+            "graph",
+            "--focus",
+            "src/main.rs",
+            "--depth",
+            "2",
+            "--format",
+            "matrix",
+            "-f",
+            "subset.json",
+            "--types",
+            "import,call",
+        ]);
+        match args.command {
+            Command::Graph {
+                depth,
+                format,
+                output_file,
+                relationship_types,
+                ..
+            } => {
+                assert_eq!(depth, 2);
+                assert_eq!(format, GraphFormat::Matrix);
+                assert_eq!(output_file, Some(PathBuf::from("subset.json")));
+                assert_eq!(relationship_types, vec!["import", "call"]);
+            }
+            _ => panic!("Expected Graph command"),
+        }
+        assert!(parse_args(&["csd", "graph"]).is_err());
+    }
+
+    #[test]
+    fn test_plugin_new_command() {
+        let args = parse_args_success(&["csd", "plugin", "new", "my_lang"]);
+        match args.command {
+            Command::Plugin {
+                action:
+                    PluginAction::New {
+                        name,
+                        plugin_type,
+                        dir,
+                        extensions,
+                        no_register,
+                        force,
+                    },
+            } => {
+                assert_eq!(name, "my_lang");
+                assert_eq!(plugin_type, PluginKind::Input);
+                assert_eq!(dir, PathBuf::from("plugins"));
+                assert!(extensions.is_empty());
+                assert!(!no_register);
+                assert!(!force);
+            }
+            _ => panic!("Expected Plugin new command"),
+        }
+
+        let args = parse_args_success(&[
+            "csd",
+            "plugin",
+            "new",
+            "reports",
+            "--type",
+            "output",
+            "-e",
+            "ml",
+            "-e",
+            ".mli",
+            "--no-register",
+        ]);
+        match args.command {
+            Command::Plugin {
+                action:
+                    PluginAction::New {
+                        plugin_type,
+                        extensions,
+                        no_register,
+                        ..
+                    },
+            } => {
+                assert_eq!(plugin_type, PluginKind::Output);
+                assert_eq!(extensions, vec!["ml", ".mli"]);
+                assert!(no_register);
+            }
+            _ => panic!("Expected Plugin new command"),
+        }
+
+        assert!(parse_args(&["csd", "plugin", "new", "x", "--type", "wasm"]).is_err());
+    }
+
+    #[test]
+    fn test_plugin_test_command() {
+        let args = parse_args_success(&[
+            "csd",
+            "plugin",
+            "test",
+            "my_lang",
+            "--fixture",
+            "a.ml",
+            "--fixture",
+            "b.ml",
+            "-o",
+            "json",
+        ]);
+        match args.command {
+            Command::Plugin {
+                action:
+                    PluginAction::Test {
+                        name,
+                        plugin_type,
+                        fixtures,
+                        matrix,
+                        timeout,
+                        output,
+                    },
+            } => {
+                assert_eq!(name, "my_lang");
+                assert!(plugin_type.is_none());
+                assert_eq!(fixtures, vec![PathBuf::from("a.ml"), PathBuf::from("b.ml")]);
+                assert!(matrix.is_none());
+                assert_eq!(timeout, 300);
+                assert!(matches!(output, OutputFormat::Json));
+            }
+            _ => panic!("Expected Plugin test command"),
+        }
+    }
+
+    #[test]
+    fn test_config_command_basic() {
+        let args = parse_args_success(&["csd", "config", "--force"]);
+
+        match args.command {
+            Command::Config {
+                force,
+                global,
+                action,
+            } => {
+                assert!(force);
+                assert!(!global);
+                assert!(action.is_none());
+            }
+            _ => panic!("Expected Config command"),
+        }
+    }
+
+    #[test]
+    fn test_config_migrate_command() {
+        let args = parse_args_success(&["csd", "config", "migrate", "--dry-run"]);
+
+        match args.command {
+            Command::Config {
+                action: Some(ConfigAction::Migrate { dry_run, no_backup }),
+                ..
+            } => {
+                assert!(dry_run);
+                assert!(!no_backup);
+            }
+            _ => panic!("Expected Config migrate command"),
+        }
+    }
+
+    #[test]
+    fn test_config_get_set_unset_list_commands() {
+        let args = parse_args_success(&["csd", "config", "set", "llm.model", "codellama"]);
+        match args.command {
+            Command::Config {
+                action: Some(ConfigAction::Set { key, value }),
+                ..
+            } => {
+                assert_eq!(key, "llm.model");
+                assert_eq!(value, "codellama");
+            }
+            _ => panic!("Expected Config set command"),
+        }
+
+        let args = parse_args_success(&["csd", "config", "get", "llm.model"]);
+        assert!(matches!(
+            args.command,
+            Command::Config {
+                action: Some(ConfigAction::Get { .. }),
+                ..
+            }
+        ));
+
+        let args = parse_args_success(&["csd", "config", "unset", "python_executable"]);
+        assert!(matches!(
+            args.command,
+            Command::Config {
+                action: Some(ConfigAction::Unset { .. }),
+                ..
+            }
+        ));
+
+        let args = parse_args_success(&["csd", "config", "list"]);
+        assert!(matches!(
+            args.command,
+            Command::Config {
+                action: Some(ConfigAction::List),
+                ..
+            }
+        ));
+
+        // `--global` is accepted before or after the action
+        for argv in [
+            ["csd", "config", "--global", "get", "llm.model"],
+            ["csd", "config", "get", "llm.model", "--global"],
+        ] {
+            let args = parse_args_success(&argv);
+            assert!(matches!(args.command, Command::Config { global: true, .. }));
+        }
+
+        // `set` requires a value
+        assert!(parse_args(&["csd", "config", "set", "llm.model"]).is_err());
+    }
 }
 
 #[cfg(test)]
@@ -349,6 +1418,127 @@ mod global_flags_tests {
         assert_eq!(args.project, Some(PathBuf::from("/root")));
     }
 
+    #[test]
+    fn test_bench_command() {
+        let args = parse_args_success(&["csd", "bench"]);
+        match args.command {
+            Command::Bench {
+                path,
+                runs,
+                no_record,
+                output,
+            } => {
+                assert!(path.is_none());
+                assert_eq!(runs, 1);
+                assert!(!no_record);
+                assert!(matches!(output, OutputFormat::Pretty));
+            }
+            _ => panic!("Expected Bench command"),
+        }
+
+        let args = parse_args_success(&[
+            "csd",
+            "bench",
+            "../other",
+            "--runs",
+            "3",
+            "--no-record",
+            "-o",
+            "json",
+        ]);
+        match args.command {
+            Command::Bench {
+                path,
+                runs,
+                no_record,
+                output,
+            } => {
+                assert_eq!(path, Some(PathBuf::from("../other")));
+                assert_eq!(runs, 3);
+                assert!(no_record);
+                assert!(matches!(output, OutputFormat::Json));
+            }
+            _ => panic!("Expected Bench command"),
+        }
+
+        assert!(parse_args(&["csd", "bench", "--runs", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_global_offline_flag() {
+        assert!(!parse_args_success(&["csd", "init"]).offline);
+        assert!(parse_args_success(&["csd", "--offline", "init"]).offline);
+        assert!(parse_args_success(&["csd", "docs", "--offline"]).offline);
+    }
+
+    #[test]
+    fn test_global_tag_flags() {
+        let args = parse_args_success(&["csd", "stats"]);
+        assert!(args.tags.is_empty() && args.exclude_tags.is_empty());
+
+        let args = parse_args_success(&["csd", "--tag", "layer:core", "stats"]);
+        assert_eq!(args.tags, vec!["layer:core"]);
+
+        let args = parse_args_success(&[
+            "csd",
+            "graph",
+            "--focus",
+            "src",
+            "--tag",
+            "layer:core",
+            "--tag",
+            "layer:api",
+            "--exclude-tag",
+            "generated",
+        ]);
+        assert_eq!(args.tags, vec!["layer:core", "layer:api"]);
+        assert_eq!(args.exclude_tags, vec!["generated"]);
+        assert_eq!(args.command.name(), "graph");
+    }
+
+    #[test]
+    fn test_profile_perf_flag() {
+        assert_eq!(parse_args_success(&["csd", "init"]).profile_perf, None);
+
+        // Without `=FILE` the next word is still the subcommand
+        let args = parse_args_success(&["csd", "--profile-perf", "stats"]);
+        assert_eq!(args.profile_perf, Some(None));
+        assert_eq!(args.command.name(), "stats");
+
+        let args = parse_args_success(&["csd", "init", "--profile-perf=profile.json"]);
+        assert_eq!(args.profile_perf, Some(Some(PathBuf::from("profile.json"))));
+        assert_eq!(args.command.name(), "init");
+    }
+
+    #[test]
+    fn test_quiet_and_porcelain_flags() {
+        let args = parse_args_success(&["csd", "stats"]);
+        assert!(!args.quiet && !args.porcelain);
+        assert_eq!(args.log_level(), log::LevelFilter::Info);
+
+        let args = parse_args_success(&["csd", "-q", "stats"]);
+        assert!(args.quiet);
+        assert_eq!(args.log_level(), log::LevelFilter::Error);
+
+        // Porcelain output is for scripts, so it silences logs too
+        let args = parse_args_success(&["csd", "query", "--porcelain"]);
+        assert!(args.porcelain && !args.quiet);
+        assert_eq!(args.log_level(), log::LevelFilter::Error);
+    }
+
+    #[test]
+    fn test_fail_on_flag() {
+        assert!(parse_args_success(&["csd", "init"]).fail_on.is_empty());
+
+        let args = parse_args_success(&["csd", "--fail-on", "warnings,drift", "init"]);
+        assert_eq!(args.fail_on, vec![FailOn::Warnings, FailOn::Drift]);
+
+        let args = parse_args_success(&["csd", "docs", "--fail-on", "plugin-errors"]);
+        assert_eq!(args.fail_on, vec![FailOn::PluginErrors]);
+
+        assert!(parse_args(&["csd", "--fail-on", "sometimes", "init"]).is_err());
+    }
+
     #[test]
     fn test_global_flags_combination() {
         let args = parse_args_success(&[
@@ -493,16 +1683,22 @@ mod comprehensive_tests {
         match args.command {
             Command::Init {
                 path,
+                more_paths,
                 output,
                 output_file,
                 no_llm,
                 include_tests,
+                strict,
+                level,
             } => {
                 assert!(path.is_none());
+                assert!(more_paths.is_empty());
                 assert!(matches!(output, OutputFormat::Json));
                 assert!(output_file.is_none());
                 assert!(!no_llm);
                 assert!(!include_tests);
+                assert!(!strict);
+                assert!(level.is_none());
             }
             _ => panic!("Expected Init command"),
         }
@@ -568,7 +1764,9 @@ mod comprehensive_tests {
         assert_eq!(args.config, Some(PathBuf::from("custom-config.yaml")));
 
         match args.command {
-            Command::Quality { matrix, metrics } => {
+            Command::Quality {
+                matrix, metrics, ..
+            } => {
                 assert_eq!(matrix, Some(PathBuf::from("analysis-matrix.json")));
                 assert_eq!(metrics.len(), 3);
                 assert!(metrics
@@ -0,0 +1,53 @@
+use anyhow::Context;
+
+use csd::cli::args::FailOn;
+use csd::cli::exit::{ExitCode, ExitError, Findings};
+
+#[test]
+fn test_exit_code_of_result() {
+    assert_eq!(ExitCode::of(&Ok(())), ExitCode::Success);
+    assert_eq!(
+        ExitCode::of(&Err(anyhow::anyhow!("anything else"))),
+        ExitCode::Error
+    );
+
+    // The code survives context added further up
+    let result: anyhow::Result<()> = Err(anyhow::Error::from(ExitError::new(
+        ExitCode::Config,
+        "bad config",
+    )))
+    .context("loading");
+    assert_eq!(ExitCode::of(&result), ExitCode::Config);
+
+    assert_eq!(ExitCode::Partial.code(), 2);
+    assert_eq!(ExitCode::QualityGate.code(), 3);
+    assert_eq!(ExitCode::Config.code(), 4);
+    assert_eq!(ExitCode::Plugin.code(), 5);
+}
+
+#[test]
+fn test_findings_only_fail_on_requested_policies() {
+    let findings = Findings {
+        warnings: 2,
+        plugin_errors: 1,
+        drift: 3,
+    };
+    assert!(findings.enforce(&[]).is_ok());
+    assert!(Findings::default()
+        .enforce(&[FailOn::Warnings, FailOn::PluginErrors, FailOn::Drift])
+        .is_ok());
+
+    let code = |fail_on: &[FailOn]| ExitCode::of(&findings.enforce(fail_on));
+    assert_eq!(code(&[FailOn::Warnings]), ExitCode::Partial);
+    assert_eq!(code(&[FailOn::Drift]), ExitCode::QualityGate);
+    assert_eq!(code(&[FailOn::PluginErrors]), ExitCode::Plugin);
+    // The most severe requested finding decides the code
+    assert_eq!(
+        code(&[FailOn::Warnings, FailOn::Drift]),
+        ExitCode::QualityGate
+    );
+    assert_eq!(
+        code(&[FailOn::Warnings, FailOn::PluginErrors, FailOn::Drift]),
+        ExitCode::Plugin
+    );
+}
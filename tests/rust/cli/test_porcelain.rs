@@ -0,0 +1,121 @@
+use std::path::PathBuf;
+
+use csd::cli::porcelain::{
+    api_diff_records, doc_drift_records, record, relationship_records, stats_records,
+};
+use csd::core::api::{ApiChange, ApiDiff, ApiItem, PluginVersionChange};
+use csd::core::doc_drift::{DocDriftReport, ReferenceKind, StaleReference};
+use csd::core::graph_analysis::GraphAnalysis;
+use csd::core::matrix::ProjectMatrix;
+
+use crate::rust::core::test_matrix::{create_test_file_node, create_test_relationship};
+
+fn item(name: &str, signature: &str) -> ApiItem {
+    ApiItem {
+        file: PathBuf::from("src/lib.rs"),
+        name: name.to_string(),
+        kind: "function".to_string(),
+        signature: Some(signature.to_string()),
+    }
+}
+
+#[test]
+fn test_record_escapes_separators() {
+    assert_eq!(record(["a", "b c", ""]), "a\tb c\t");
+    assert_eq!(
+        record(["tab\there", "line\nbreak", "back\\slash"]),
+        "tab\\there\tline\\nbreak\tback\\\\slash"
+    );
+}
+
+#[test]
+fn test_relationship_records() {
+    let mut relationship = create_test_relationship("src/main.rs", "src/lib.rs");
+    relationship.to_element = Some("parse".to_string());
+    relationship.source_plugin = Some("rust".into());
+    let plain = create_test_relationship("src/a.rs", "src/b.rs");
+
+    assert_eq!(
+        relationship_records(&[&relationship, &plain]),
+        vec![
+            "relationship\tsrc/main.rs\t\tsrc/lib.rs\tparse\timport\t0.80\trust",
+            "relationship\tsrc/a.rs\t\tsrc/b.rs\t\timport\t0.80\t",
+        ]
+    );
+}
+
+#[test]
+fn test_stats_records() {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    for file in ["src/a.rs", "src/b.rs", "src/c.rs"] {
+        matrix.add_file(create_test_file_node(file, "rust"));
+    }
+    matrix.add_relationship(create_test_relationship("src/a.rs", "src/b.rs"));
+    matrix.add_relationship(create_test_relationship("src/b.rs", "src/a.rs"));
+    let analysis = GraphAnalysis::analyze(&mut matrix);
+
+    let records = stats_records(&matrix, &analysis, 1);
+    assert_eq!(records[0], "files\t3");
+    assert_eq!(records[1], "relationships\t2");
+    assert!(records[2].starts_with("layers\t"));
+    assert!(records
+        .iter()
+        .any(|r| r.starts_with("category\tsource\t3\t")));
+    assert_eq!(
+        records
+            .iter()
+            .filter(|r| r.starts_with("pagerank\t"))
+            .count(),
+        1
+    );
+    assert!(records.contains(&"cycle\tsrc/a.rs\tsrc/b.rs".to_string()));
+    // Every record is a kind followed by tab-separated fields
+    assert!(records.iter().all(|r| r.split('\t').count() >= 2));
+}
+
+#[test]
+fn test_api_diff_records() {
+    let diff = ApiDiff {
+        added: vec![item("render", "pub fn render()")],
+        removed: vec![item("old", "pub fn old()")],
+        changed: vec![ApiChange {
+            before: item("parse", "pub fn parse()"),
+            after: item("parse", "pub fn parse(strict: bool)"),
+        }],
+        plugin_changes: vec![PluginVersionChange {
+            name: "rust".to_string(),
+            before: "1.0.0".to_string(),
+            after: "2.0.0".to_string(),
+        }],
+    };
+
+    assert_eq!(
+        api_diff_records(&diff),
+        vec![
+            "removed\tsrc/lib.rs\told\tfunction\tpub fn old()",
+            "changed\tsrc/lib.rs\tparse\tfunction\tpub fn parse(strict: bool)",
+            "added\tsrc/lib.rs\trender\tfunction\tpub fn render()",
+            "plugin\trust\t1.0.0\t2.0.0",
+            "bump\tmajor",
+        ]
+    );
+    assert_eq!(api_diff_records(&ApiDiff::default()), vec!["bump\tpatch"]);
+}
+
+#[test]
+fn test_doc_drift_records() {
+    let report = DocDriftReport {
+        docs_checked: 1,
+        references_checked: 3,
+        stale: vec![StaleReference {
+            doc: PathBuf::from("docs/guide.md"),
+            line: 12,
+            reference: "Scanner::walk".to_string(),
+            kind: ReferenceKind::Symbol,
+        }],
+    };
+    assert_eq!(
+        doc_drift_records(&report),
+        vec!["stale\tsymbol\tdocs/guide.md\t12\tScanner::walk"]
+    );
+}
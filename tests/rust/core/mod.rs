@@ -1,7 +1,44 @@
 // Core module tests
 
+pub mod test_adjacency;
+pub mod test_adr;
+pub mod test_annotations;
+pub mod test_api;
+pub mod test_bench;
+pub mod test_categories;
+pub mod test_changelog;
+pub mod test_comments;
+pub mod test_compact;
+pub mod test_complexity;
+pub mod test_contracts;
+pub mod test_coverage;
+pub mod test_data_model;
+pub mod test_debt;
+pub mod test_deployments;
+pub mod test_doc_drift;
+pub mod test_doctor;
+pub mod test_documentation;
+pub mod test_dsm;
+pub mod test_events;
+pub mod test_facade;
+pub mod test_focus;
+pub mod test_glossary;
+pub mod test_graph_analysis;
+pub mod test_history;
+pub mod test_hotspots;
+pub mod test_interactions;
+pub mod test_lint;
 pub mod test_matrix;
+pub mod test_pipeline;
+pub mod test_project;
+pub mod test_redact;
+pub mod test_rules;
 pub mod test_scanner;
-
-// Future core test modules:
-// pub mod test_project;
+pub mod test_structure;
+pub mod test_suggestions;
+pub mod test_suppressions;
+pub mod test_token_cache;
+pub mod test_traces;
+pub mod test_transform;
+pub mod test_validate;
+pub mod test_view;
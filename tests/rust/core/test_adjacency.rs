@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+use csd::core::adjacency::{AdjacencyLevel, AdjacencyMatrix};
+use csd::core::matrix::ProjectMatrix;
+
+use super::test_matrix::{create_test_file_node, create_test_relationship};
+
+fn sample_matrix() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    for path in [
+        "main.rs",
+        "src/lib.rs",
+        "src/util.rs",
+        "src/net/http.rs",
+        "docs/a,b.md",
+    ] {
+        matrix.add_file(create_test_file_node(path, "rust"));
+    }
+    for (from, to, strength) in [
+        ("main.rs", "src/lib.rs", 1.0),
+        ("src/lib.rs", "src/util.rs", 1.0),
+        ("src/lib.rs", "src/net/http.rs", 1.0),
+        ("src/net/http.rs", "src/util.rs", 0.5),
+        // Edges to files outside the matrix are dropped
+        ("src/lib.rs", "vendor/zlib.c", 1.0),
+    ] {
+        let mut relationship = create_test_relationship(from, to);
+        relationship.strength = strength;
+        matrix.add_relationship(relationship);
+    }
+    matrix
+}
+
+fn weight(adjacency: &AdjacencyMatrix, from: &str, to: &str) -> f32 {
+    let index = |label: &str| adjacency.labels.iter().position(|l| l == label).unwrap();
+    adjacency.weights[index(from)][index(to)]
+}
+
+#[test]
+fn test_file_adjacency() {
+    let adjacency = AdjacencyMatrix::from_matrix(&sample_matrix(), AdjacencyLevel::File);
+
+    assert_eq!(
+        adjacency.labels,
+        vec![
+            "docs/a,b.md",
+            "main.rs",
+            "src/lib.rs",
+            "src/net/http.rs",
+            "src/util.rs"
+        ]
+    );
+    assert_eq!(weight(&adjacency, "main.rs", "src/lib.rs"), 1.0);
+    assert_eq!(weight(&adjacency, "src/lib.rs", "main.rs"), 0.0);
+    let total: f32 = adjacency.weights.iter().flatten().sum();
+    assert_eq!(total, 3.5);
+}
+
+#[test]
+fn test_directory_adjacency_sums_edges() {
+    let adjacency = AdjacencyMatrix::from_matrix(&sample_matrix(), AdjacencyLevel::Directory);
+
+    assert_eq!(adjacency.labels, vec![".", "docs", "src", "src/net"]);
+    assert_eq!(weight(&adjacency, ".", "src"), 1.0);
+    // lib.rs -> util.rs stays inside src
+    assert_eq!(weight(&adjacency, "src", "src"), 1.0);
+    assert_eq!(weight(&adjacency, "src", "src/net"), 1.0);
+    assert_eq!(weight(&adjacency, "src/net", "src"), 0.5);
+}
+
+#[test]
+fn test_adjacency_csv() {
+    let adjacency = AdjacencyMatrix::from_matrix(&sample_matrix(), AdjacencyLevel::File);
+    let csv = adjacency.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(
+        lines[0],
+        "source,\"docs/a,b.md\",main.rs,src/lib.rs,src/net/http.rs,src/util.rs"
+    );
+    assert_eq!(lines[2], "main.rs,0,0,1,0,0");
+    assert_eq!(lines[3], "src/lib.rs,0,0,0,1,1");
+    assert_eq!(lines.len(), 6);
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn test_adjacency_parquet_roundtrip() {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("adjacency.parquet");
+    let adjacency = AdjacencyMatrix::from_matrix(&sample_matrix(), AdjacencyLevel::Directory);
+    adjacency.write_parquet(&path).unwrap();
+
+    let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+    let schema = reader.metadata().file_metadata().schema_descr_ptr();
+    let columns: Vec<&str> = schema.columns().iter().map(|c| c.name()).collect();
+    assert_eq!(columns, vec!["source", ".", "docs", "src", "src/net"]);
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 4);
+
+    let rows: Vec<String> = reader
+        .get_row_iter(None)
+        .unwrap()
+        .map(|row| row.unwrap().to_string())
+        .collect();
+    assert!(rows[0].starts_with("{source: \".\""));
+}
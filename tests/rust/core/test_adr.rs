@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use csd::core::adr::{create_adr, index_markdown, link_adrs, load_adrs, write_index, ADR_DIR};
+use csd::core::matrix::ProjectMatrix;
+
+use super::test_matrix::create_test_file_node;
+
+#[test]
+fn test_create_numbers_adrs_and_load_reads_them_back() {
+    let dir = TempDir::new().unwrap();
+    let first = create_adr(
+        dir.path(),
+        "Use event bus",
+        &["src/events".to_string()],
+        "2024-05-01",
+    )
+    .unwrap();
+    let second = create_adr(dir.path(), "Drop the cache!", &[], "2024-05-02").unwrap();
+
+    assert_eq!(first.number, 1);
+    assert_eq!(
+        first.path,
+        PathBuf::from(ADR_DIR).join("0001-use-event-bus.md")
+    );
+    assert_eq!(second.number, 2);
+    assert_eq!(
+        second.path,
+        PathBuf::from(ADR_DIR).join("0002-drop-the-cache.md")
+    );
+
+    // The index is not an ADR
+    write_index(dir.path(), &[first.clone(), second.clone()]).unwrap();
+    let adrs = load_adrs(dir.path()).unwrap();
+    assert_eq!(adrs, vec![first, second]);
+    assert_eq!(adrs[0].status, "Proposed");
+    assert_eq!(adrs[0].affects, vec!["src/events".to_string()]);
+
+    assert!(create_adr(dir.path(), "???", &[], "2024-05-03").is_err());
+}
+
+#[test]
+fn test_load_without_adr_directory_is_empty() {
+    let dir = TempDir::new().unwrap();
+    assert!(load_adrs(dir.path()).unwrap().is_empty());
+    assert!(index_markdown(&[]).contains("No decisions recorded yet"));
+}
+
+#[test]
+fn test_index_links_each_adr() {
+    let dir = TempDir::new().unwrap();
+    let adr = create_adr(
+        dir.path(),
+        "Use event bus",
+        &["src/events".to_string(), "**/*.proto".to_string()],
+        "2024-05-01",
+    )
+    .unwrap();
+
+    let index = index_markdown(&[adr]);
+    assert!(index.contains(
+        "| [0001](0001-use-event-bus.md) | Use event bus | Proposed | 2024-05-01 | `src/events`, `**/*.proto` |"
+    ));
+}
+
+#[test]
+fn test_link_tags_affected_files() {
+    let dir = TempDir::new().unwrap();
+    create_adr(
+        dir.path(),
+        "Use event bus",
+        &["src/events".to_string(), "**/*.proto".to_string()],
+        "2024-05-01",
+    )
+    .unwrap();
+    create_adr(dir.path(), "Undecided", &[], "2024-05-02").unwrap();
+
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    for path in ["src/events/bus.rs", "api/events.proto", "src/main.rs"] {
+        matrix.add_file(create_test_file_node(path, "rust"));
+    }
+
+    let mut adrs = load_adrs(dir.path()).unwrap();
+    let summary = link_adrs(&mut adrs, &mut matrix).unwrap();
+    assert_eq!(summary.tags, 2);
+    assert_eq!(
+        adrs[0].files,
+        vec![
+            PathBuf::from("api/events.proto"),
+            PathBuf::from("src/events/bus.rs")
+        ]
+    );
+    assert!(adrs[1].files.is_empty());
+
+    assert_eq!(adrs[0].tag(), "adr:0001");
+    assert_eq!(adrs[0].files_in(&matrix), adrs[0].files);
+    assert!(matrix.files[&PathBuf::from("src/main.rs")].metadata["tags"].is_null());
+}
@@ -0,0 +1,122 @@
+use serde_json::Value;
+use tempfile::TempDir;
+
+use csd::core::annotations::{
+    find_target, Annotation, Annotations, Note, ReviewStatus, ANNOTATIONS_FILE,
+};
+use csd::core::matrix::{CodeElement, ElementType, ProjectMatrix};
+
+use crate::rust::core::test_matrix::create_test_file_node;
+
+fn element(name: &str, parent: Option<&str>, line_start: u32) -> CodeElement {
+    CodeElement {
+        element_type: ElementType::Function,
+        name: name.to_string(),
+        qualified_name: parent.map(|parent| format!("{parent}::{name}")),
+        parent: parent.map(str::to_string),
+        signature: Some(format!("fn {name}()")),
+        line_start,
+        line_end: line_start + 3,
+        summary: None,
+        complexity_score: None,
+        calls: Vec::new(),
+        metadata: Value::Null,
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    }
+}
+
+fn sample_matrix(elements: Vec<CodeElement>) -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new("/project".into());
+    let mut file = create_test_file_node("src/billing.rs", "rust");
+    file.elements = elements;
+    matrix.add_file(file);
+    matrix.add_file(create_test_file_node("src/main.rs", "rust"));
+    matrix
+}
+
+fn note(text: &str) -> Note {
+    Note {
+        text: text.to_string(),
+        author: Some("dana".to_string()),
+        date: chrono::Utc::now(),
+    }
+}
+
+#[test]
+fn test_annotations_round_trip_and_drop_empty_entries() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let matrix = sample_matrix(vec![element("charge", None, 1)]);
+    let (file, charge) = find_target(&matrix, "./src/billing.rs".as_ref(), Some("charge")).unwrap();
+
+    let mut annotations = Annotations::load(temp_dir.path()).unwrap();
+    assert!(annotations.is_empty());
+    *annotations.file_mut("src/billing.rs".as_ref()) = Annotation {
+        owner: Some("payments".to_string()),
+        review: Some(ReviewStatus::Approved),
+        notes: vec![note("Talks to the card processor")],
+    };
+    annotations
+        .element_mut(file, charge.unwrap())
+        .notes
+        .push(note("Retries twice"));
+    // Nothing to say, so not written
+    annotations.file_mut("src/main.rs".as_ref());
+    annotations.save().unwrap();
+
+    let content = std::fs::read_to_string(temp_dir.path().join(ANNOTATIONS_FILE)).unwrap();
+    assert!(content.contains("review: approved"));
+    assert!(!content.contains("src/main.rs"));
+
+    let loaded = Annotations::load(temp_dir.path()).unwrap();
+    let billing = loaded.file("src/billing.rs".as_ref()).unwrap();
+    assert_eq!(
+        billing.describe(),
+        "owner: payments · review: approved · 1 note"
+    );
+    let charge = loaded.element(&charge.unwrap().element_id).unwrap();
+    assert_eq!(charge.name, "charge");
+    assert_eq!(charge.file, std::path::PathBuf::from("src/billing.rs"));
+    assert_eq!(charge.annotation.notes[0].text, "Retries twice");
+}
+
+#[test]
+fn test_annotations_outlive_rescans_and_report_orphans() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let before = sample_matrix(vec![element("charge", None, 1), element("refund", None, 9)]);
+    let mut annotations = Annotations::load(temp_dir.path()).unwrap();
+    for name in ["charge", "refund"] {
+        let (file, element) = find_target(&before, "src/billing.rs".as_ref(), Some(name)).unwrap();
+        annotations.element_mut(file, element.unwrap()).owner = Some("payments".to_string());
+    }
+
+    // Moving `charge` keeps its ID; renaming `refund` doesn't
+    let after = sample_matrix(vec![
+        element("charge", None, 20),
+        element("reverse", None, 9),
+    ]);
+    let current = annotations.in_matrix(&after);
+    assert_eq!(current.elements.len(), 1);
+    assert_eq!(current.elements.values().next().unwrap().name, "charge");
+
+    let orphans = annotations.orphans(&after);
+    assert_eq!(orphans.len(), 1);
+    assert_eq!(orphans[0].1.name, "refund");
+}
+
+#[test]
+fn test_find_target_needs_an_unambiguous_element() {
+    let matrix = sample_matrix(vec![
+        element("new", Some("Invoice"), 1),
+        element("new", Some("Receipt"), 9),
+    ]);
+
+    let error = find_target(&matrix, "src/billing.rs".as_ref(), Some("new")).unwrap_err();
+    assert!(error.to_string().contains("matches 2 elements"), "{error}");
+    let (_, found) = find_target(&matrix, "src/billing.rs".as_ref(), Some("Receipt::new")).unwrap();
+    assert_eq!(found.unwrap().line_start, 9);
+
+    assert!(find_target(&matrix, "src/billing.rs".as_ref(), Some("void")).is_err());
+    assert!(find_target(&matrix, "src/nope.rs".as_ref(), None).is_err());
+}
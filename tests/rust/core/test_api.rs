@@ -0,0 +1,186 @@
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use csd::core::api::{ApiSurface, SemverBump};
+use csd::core::matrix::{CodeElement, ElementType, Export, ProjectMatrix};
+
+use super::test_matrix::create_test_file_node;
+
+fn element(name: &str, signature: &str, metadata: serde_json::Value) -> CodeElement {
+    CodeElement {
+        element_type: ElementType::Function,
+        name: name.to_string(),
+        qualified_name: None,
+        parent: None,
+        signature: Some(signature.to_string()),
+        line_start: 1,
+        line_end: 5,
+        summary: None,
+        complexity_score: None,
+        calls: vec![],
+        metadata,
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    }
+}
+
+fn matrix_with(elements: Vec<CodeElement>, exports: &[&str]) -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    let mut file = create_test_file_node("src/lib.rs", "rust");
+    file.elements = elements;
+    file.exports = exports.iter().map(|e| Export::named(e)).collect();
+    matrix.add_file(file);
+    matrix
+}
+
+fn public(name: &str, signature: &str) -> CodeElement {
+    element(name, signature, serde_json::json!({"visibility": "pub"}))
+}
+
+#[test]
+fn test_extract_only_public_elements() {
+    let matrix = matrix_with(
+        vec![
+            public("parse", "pub fn parse(input: &str)"),
+            element(
+                "helper",
+                "fn helper()",
+                serde_json::json!({"is_public": false}),
+            ),
+            element(
+                "internal",
+                "pub(crate) fn internal()",
+                serde_json::json!({"visibility": "pub(crate)"}),
+            ),
+            element("exported", "def exported()", serde_json::json!({})),
+            element("hidden", "def hidden()", serde_json::json!({})),
+        ],
+        &["exported"],
+    );
+
+    let surface = ApiSurface::from_matrix(&matrix);
+    let names: Vec<&str> = surface.items.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(names, vec!["exported", "parse"]);
+    assert_eq!(surface.items[1].kind, "function");
+    assert_eq!(surface.items[1].file, PathBuf::from("src/lib.rs"));
+}
+
+#[test]
+fn test_aliased_exports_are_public_but_reexports_are_not() {
+    let mut matrix = matrix_with(
+        vec![
+            element("parse", "def parse()", serde_json::json!({})),
+            element("render", "def render()", serde_json::json!({})),
+        ],
+        &[],
+    );
+    let file = matrix.files.get_mut(Path::new("src/lib.rs")).unwrap();
+    file.exports = vec![
+        Export {
+            alias: Some("parse_all".to_string()),
+            ..Export::named("parse")
+        },
+        // Another file's render, passed through
+        Export {
+            source: Some(PathBuf::from("src/render.rs")),
+            ..Export::named("render")
+        },
+    ];
+
+    let surface = ApiSurface::from_matrix(&matrix);
+    let names: Vec<&str> = surface.items.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(names, vec!["parse"]);
+}
+
+#[test]
+fn test_diff_reports_breaking_changes() {
+    let old = ApiSurface::from_matrix(&matrix_with(
+        vec![
+            public("parse", "pub fn parse(input: &str)"),
+            public("render", "pub fn render()"),
+            public("legacy", "pub fn legacy()"),
+        ],
+        &[],
+    ));
+    let new = ApiSurface::from_matrix(&matrix_with(
+        vec![
+            // Whitespace-only change is not a signature change
+            public("parse", "pub fn parse(input:   &str)"),
+            public("render", "pub fn render(width: usize)"),
+            public("format", "pub fn format()"),
+        ],
+        &[],
+    ));
+
+    let diff = new.diff(&old);
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].name, "legacy");
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].after.name, "render");
+    assert_eq!(diff.added.len(), 1);
+    assert!(diff.is_breaking());
+    assert_eq!(diff.required_bump(), SemverBump::Major);
+}
+
+#[test]
+fn test_additions_only_need_minor_bump() {
+    let old = ApiSurface::from_matrix(&matrix_with(vec![public("parse", "pub fn parse()")], &[]));
+    let new = ApiSurface::from_matrix(&matrix_with(
+        vec![
+            public("parse", "pub fn parse()"),
+            public("lex", "pub fn lex()"),
+        ],
+        &[],
+    ));
+
+    assert!(!new.diff(&old).is_breaking());
+    assert_eq!(new.diff(&old).required_bump(), SemverBump::Minor);
+    assert_eq!(old.diff(&old).required_bump(), SemverBump::Patch);
+}
+
+#[test]
+fn test_diff_reports_plugin_version_changes() {
+    let mut old_matrix = matrix_with(vec![public("parse", "pub fn parse()")], &[]);
+    old_matrix
+        .metadata
+        .pin_plugin("rust", "1.0.0", "builtin:rust/rust".to_string());
+    let mut new_matrix = old_matrix.clone();
+    new_matrix
+        .metadata
+        .pin_plugin("rust", "2.0.0", "builtin:rust/rust".to_string());
+
+    let old = ApiSurface::from_matrix(&old_matrix);
+    let new = ApiSurface::from_matrix(&new_matrix);
+    let diff = new.diff(&old);
+    assert_eq!(diff.plugin_changes.len(), 1);
+    assert_eq!(diff.plugin_changes[0].name, "rust");
+    assert_eq!(diff.plugin_changes[0].before, "1.0.0");
+    assert_eq!(diff.plugin_changes[0].after, "2.0.0");
+    // Not an API change in itself
+    assert!(!diff.is_breaking());
+
+    assert!(new.diff(&new).plugin_changes.is_empty());
+    // Unknown versions can't be compared
+    let unpinned = ApiSurface::from_matrix(&matrix_with(vec![], &[]));
+    assert!(new.diff(&unpinned).plugin_changes.is_empty());
+}
+
+#[tokio::test]
+async fn test_load_accepts_surface_or_matrix() {
+    let temp_dir = TempDir::new().unwrap();
+    let matrix = matrix_with(vec![public("parse", "pub fn parse()")], &[]);
+    let surface = ApiSurface::from_matrix(&matrix);
+
+    let surface_path = temp_dir.path().join("api.json");
+    surface.save(&surface_path).await.unwrap();
+    let matrix_path = temp_dir.path().join("matrix.json");
+    matrix.save(&matrix_path).await.unwrap();
+
+    assert_eq!(ApiSurface::load(&surface_path).await.unwrap(), surface);
+    assert_eq!(ApiSurface::load(&matrix_path).await.unwrap(), surface);
+
+    let garbage = temp_dir.path().join("garbage.json");
+    tokio::fs::write(&garbage, "{}").await.unwrap();
+    assert!(ApiSurface::load(Path::new(&garbage)).await.is_err());
+}
@@ -0,0 +1,103 @@
+use chrono::{Duration, Utc};
+use tempfile::TempDir;
+
+use csd::core::bench::{target_id, BenchHistory, BenchReport, BenchResult};
+use csd::core::scanner::ProjectScanner;
+use csd::utils::config::Config;
+
+fn result(target: &str, files: usize, total_ms: f64, minutes_ago: i64) -> BenchResult {
+    BenchResult {
+        timestamp: Utc::now() - Duration::minutes(minutes_ago),
+        csd_version: "0.1.0".to_string(),
+        target: target.to_string(),
+        files,
+        skipped_files: 0,
+        bytes: files as u64 * 1024 * 1024,
+        tokens: files as u64 * 100,
+        discovery_ms: total_ms / 2.0,
+        hashing_ms: total_ms / 4.0,
+        tokenize_ms: total_ms / 4.0,
+    }
+}
+
+#[test]
+fn test_bench_throughput() {
+    let run = result("a", 50, 500.0, 0);
+    assert_eq!(run.total_ms(), 500.0);
+    assert_eq!(run.files_per_sec(), 100.0);
+    assert_eq!(run.mb_per_sec(), 100.0);
+
+    // An instant run doesn't divide by zero
+    assert_eq!(result("a", 50, 0.0, 0).files_per_sec(), 0.0);
+}
+
+#[test]
+fn test_bench_report_compares_same_target() {
+    let history = vec![
+        result("a", 100, 100.0, 30), // fastest
+        result("b", 100, 10.0, 20),  // other project
+        result("a", 100, 400.0, 10), // previous
+    ];
+
+    let report = BenchReport::new(result("a", 100, 200.0, 0), &history);
+    assert_eq!(report.previous, Some(history[2].clone()));
+    assert_eq!(report.best, Some(history[0].clone()));
+
+    // The previous run being the best isn't reported twice
+    let report = BenchReport::new(result("a", 100, 200.0, 0), &history[..1]);
+    assert_eq!(report.previous, Some(history[0].clone()));
+    assert_eq!(report.best, None);
+
+    let report = BenchReport::new(result("c", 100, 200.0, 0), &history);
+    assert_eq!(report.previous, None);
+    assert_eq!(report.best, None);
+}
+
+#[tokio::test]
+async fn test_bench_history_roundtrip() {
+    let temp_dir = TempDir::new().unwrap();
+    let history = BenchHistory::new(temp_dir.path());
+    assert!(history.load().await.unwrap().is_empty());
+
+    let newer = result("a", 10, 50.0, 0);
+    let older = result("a", 10, 60.0, 5);
+    history.append(&newer).await.unwrap();
+    history.append(&older).await.unwrap();
+
+    assert!(history.path().ends_with("history/bench.jsonl"));
+    assert_eq!(history.load().await.unwrap(), vec![older, newer]);
+}
+
+#[tokio::test]
+async fn test_bench_scans_without_plugins() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(
+        root.join("src/lib.rs"),
+        "pub fn add(a: i32, b: i32) -> i32 { a + b }\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("debug.log"), "ignored by default\n").unwrap();
+
+    // A plugin that would fail if it were ever started
+    let config = Config {
+        python_executable: Some("/nonexistent/python".to_string()),
+        ..Config::default()
+    };
+
+    let run = ProjectScanner::new(config)
+        .with_root(root)
+        .bench()
+        .await
+        .unwrap();
+
+    assert_eq!(run.files, 2);
+    assert_eq!(run.skipped_files, 1);
+    assert_eq!(run.bytes, 13 + 44);
+    assert!(run.tokens > 0);
+    assert_eq!(run.target, target_id(root));
+    assert_ne!(target_id(root), target_id(&root.join("src")));
+    assert!(!run.target.contains(&*root.to_string_lossy()));
+}
@@ -0,0 +1,174 @@
+use std::path::{Path, PathBuf};
+
+use csd::core::categories::{FileCategory, FileClassifier};
+use csd::core::matrix::{CodeElement, ElementType, ProjectMatrix};
+use csd::utils::config::CategoryRule;
+
+use super::test_matrix::create_test_file_node;
+
+fn element_with_complexity(score: u32) -> CodeElement {
+    CodeElement {
+        element_type: ElementType::Function,
+        name: format!("f{score}"),
+        qualified_name: None,
+        parent: None,
+        signature: None,
+        line_start: 1,
+        line_end: 2,
+        summary: None,
+        complexity_score: Some(score),
+        calls: vec![],
+        metadata: serde_json::Value::Null,
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    }
+}
+
+#[test]
+fn test_default_classification() {
+    let classifier = FileClassifier::default();
+    let cases = [
+        ("src/main.rs", FileCategory::Source),
+        ("tests/test_scanner.rs", FileCategory::Test),
+        ("src/utils/test_helpers.py", FileCategory::Test),
+        ("web/app.spec.ts", FileCategory::Test),
+        ("pkg/api/client_test.go", FileCategory::Test),
+        ("Cargo.lock", FileCategory::Build),
+        ("build.rs", FileCategory::Build),
+        (".github/workflows/ci.yml", FileCategory::Build),
+        ("README.md", FileCategory::Docs),
+        ("docs/guide/index.html", FileCategory::Docs),
+        ("Cargo.toml", FileCategory::Config),
+        ("config/settings.yaml", FileCategory::Config),
+        ("static/logo.svg", FileCategory::Asset),
+        ("web/dist/app.min.js", FileCategory::Generated),
+        ("proto/service_pb2.py", FileCategory::Generated),
+        ("vendor/lib/util.c", FileCategory::Vendored),
+        ("third_party/zlib/zlib.h", FileCategory::Vendored),
+        ("node_modules/react/index.js", FileCategory::Vendored),
+    ];
+    for (path, expected) in cases {
+        assert_eq!(classifier.classify(Path::new(path)), expected, "{path}");
+    }
+}
+
+#[test]
+fn test_custom_rules_take_precedence() {
+    let rules = vec![
+        CategoryRule {
+            category: FileCategory::Generated,
+            patterns: vec!["src/gen/**".to_string()],
+        },
+        CategoryRule {
+            category: FileCategory::Source,
+            patterns: vec!["*.json".to_string()],
+        },
+    ];
+    let classifier = FileClassifier::new(&rules).unwrap();
+
+    assert_eq!(
+        classifier.classify(Path::new("src/gen/models.rs")),
+        FileCategory::Generated
+    );
+    assert_eq!(
+        classifier.classify(Path::new("schema.json")),
+        FileCategory::Source
+    );
+    assert_eq!(
+        classifier.classify(Path::new("tests/it.rs")),
+        FileCategory::Test
+    );
+}
+
+#[test]
+fn test_invalid_rule_is_an_error() {
+    let rules = vec![CategoryRule {
+        category: FileCategory::Docs,
+        patterns: vec!["docs/[".to_string()],
+    }];
+    let error = FileClassifier::new(&rules).err().unwrap();
+    assert!(error.to_string().contains("docs/["));
+}
+
+#[test]
+fn test_category_rollups() {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+
+    let mut main = create_test_file_node("src/main.rs", "rust");
+    main.elements.push(element_with_complexity(2));
+    main.elements.push(element_with_complexity(4));
+    matrix.add_file(main);
+    matrix.add_file(create_test_file_node("src/lib.rs", "rust"));
+    let mut test = create_test_file_node("tests/it.rs", "rust");
+    test.elements.push(element_with_complexity(1));
+    matrix.add_file(test);
+    // A stored category wins over the path-based default
+    let mut generated = create_test_file_node("src/schema.rs", "rust");
+    generated.category = Some(FileCategory::Generated);
+    matrix.add_file(generated);
+
+    let metrics = matrix.calculate_metrics();
+    let categories = &metrics.categories;
+    assert_eq!(categories.len(), 3);
+
+    let source = &categories[&FileCategory::Source];
+    assert_eq!(source.files, 2);
+    assert_eq!(source.size_bytes, 2048);
+    assert_eq!(source.tokens, 512);
+    assert_eq!(source.average_complexity, Some(3.0));
+
+    assert_eq!(categories[&FileCategory::Test].files, 1);
+    assert_eq!(
+        categories[&FileCategory::Test].average_complexity,
+        Some(1.0)
+    );
+    assert_eq!(categories[&FileCategory::Generated].files, 1);
+    assert_eq!(
+        categories[&FileCategory::Generated].average_complexity,
+        None
+    );
+}
+
+#[test]
+fn test_maintained_files_leave_out_vendored_code() {
+    let rules = vec![CategoryRule {
+        category: FileCategory::Vendored,
+        patterns: vec!["extern/**".to_string()],
+    }];
+    let classifier = FileClassifier::new(&rules).unwrap();
+    assert_eq!(
+        classifier.classify(Path::new("extern/json/json.hpp")),
+        FileCategory::Vendored
+    );
+    assert!(!FileCategory::Vendored.is_maintained());
+    assert!(FileCategory::Generated.is_maintained());
+
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    matrix.add_file(create_test_file_node("src/main.rs", "rust"));
+    // Unclassified files fall back to the built-in rules
+    matrix.add_file(create_test_file_node(
+        "node_modules/left-pad/index.js",
+        "javascript",
+    ));
+    let mut copied = create_test_file_node("extern/json/json.hpp", "cpp");
+    copied.category = Some(classifier.classify(&copied.relative_path));
+    matrix.add_file(copied);
+
+    let maintained: Vec<&Path> = matrix
+        .maintained_files()
+        .map(|file| file.relative_path.as_path())
+        .collect();
+    assert_eq!(maintained, vec![Path::new("src/main.rs")]);
+}
+
+#[test]
+fn test_category_serializes_lowercase() {
+    let mut node = create_test_file_node("README.md", "markdown");
+    node.category = Some(FileCategory::Docs);
+    let json = serde_json::to_value(&node).unwrap();
+    assert_eq!(json["category"], "docs");
+
+    let untagged = serde_json::to_value(create_test_file_node("a.rs", "rust")).unwrap();
+    assert!(untagged.get("category").is_none());
+}
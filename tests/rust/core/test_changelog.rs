@@ -0,0 +1,125 @@
+use std::path::PathBuf;
+
+use csd::core::changelog::{component_of, Changelog};
+use csd::core::matrix::{CodeElement, ElementType, ProjectMatrix};
+use csd::utils::git::Commit;
+use csd::utils::hashing::HashAlgorithm;
+
+use super::test_matrix::create_test_matrix;
+
+fn public(name: &str, signature: &str) -> CodeElement {
+    CodeElement {
+        element_type: ElementType::Function,
+        name: name.to_string(),
+        qualified_name: None,
+        parent: None,
+        signature: Some(signature.to_string()),
+        line_start: 1,
+        line_end: 5,
+        summary: None,
+        complexity_score: None,
+        calls: vec![],
+        metadata: serde_json::json!({"visibility": "pub"}),
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    }
+}
+
+// Files with their content hashes and elements
+fn matrix(files: &[(&str, &str, Vec<CodeElement>)]) -> ProjectMatrix {
+    create_test_matrix("rust", files, |file, hash| file.hash = hash.to_string())
+}
+
+fn commit(hash: &str, subject: &str, files: &[&str]) -> Commit {
+    Commit {
+        hash: hash.to_string(),
+        subject: subject.to_string(),
+        files: files.iter().map(PathBuf::from).collect(),
+    }
+}
+
+#[test]
+fn test_component_of() {
+    assert_eq!(
+        component_of(&PathBuf::from("src/core/scanner.rs")),
+        "src/core"
+    );
+    assert_eq!(component_of(&PathBuf::from("README.md")), "(root)");
+}
+
+#[test]
+fn test_changes_grouped_by_component() {
+    let before = matrix(&[
+        (
+            "src/core/scan.rs",
+            "a1",
+            vec![public("scan", "pub fn scan()")],
+        ),
+        ("src/old.rs", "b1", vec![]),
+        ("README.md", "c1", vec![]),
+    ]);
+    let after = matrix(&[
+        (
+            "src/core/scan.rs",
+            "a2",
+            vec![
+                public("scan", "pub fn scan(depth: usize)"),
+                public("rescan", "pub fn rescan()"),
+            ],
+        ),
+        ("src/core/walk.rs", "d1", vec![]),
+        ("README.md", "c1", vec![]),
+    ]);
+    let commits = vec![
+        commit(
+            "abc1234",
+            "Add directory walking",
+            &["src/core/walk.rs", "src/core/scan.rs", "README.md"],
+        ),
+        commit("def5678", "Drop the old module", &["src/old.rs"]),
+    ];
+
+    let changelog = Changelog::build("v1.0.0", &before, &after, commits).unwrap();
+    let names: Vec<&str> = changelog
+        .components
+        .iter()
+        .map(|c| c.component.as_str())
+        .collect();
+    // README.md is unchanged, so the root has nothing to report
+    assert_eq!(names, vec!["src", "src/core"]);
+
+    let core = &changelog.components[1];
+    assert!(core.is_breaking());
+    assert_eq!(core.commits.len(), 1);
+    assert_eq!(core.commits[0].hash, "abc1234");
+    assert_eq!(core.api_added.len(), 1);
+    assert_eq!(core.api_changed.len(), 1);
+    assert_eq!(core.files_added, vec![PathBuf::from("src/core/walk.rs")]);
+    assert_eq!(core.files_modified, vec![PathBuf::from("src/core/scan.rs")]);
+
+    let src = &changelog.components[0];
+    assert!(!src.is_breaking());
+    assert_eq!(src.files_removed, vec![PathBuf::from("src/old.rs")]);
+    assert_eq!(src.commits[0].subject, "Drop the old module");
+
+    let md = changelog.to_markdown();
+    assert!(md.starts_with("# Changelog (draft, since v1.0.0)\n"));
+    assert!(md.contains("\n## src/core\n\n**Breaking:**"));
+    assert!(md.contains("- Add directory walking (abc1234)\n"));
+    assert!(md.contains("- Added `rescan` (function)"));
+    assert!(md.contains("- Changed `scan`: `pub fn scan()` → `pub fn scan(depth: usize)`"));
+    assert!(md.contains("- Removed `src/old.rs`"));
+}
+
+#[test]
+fn test_no_changes_and_hash_mismatch() {
+    let before = matrix(&[("src/lib.rs", "a1", vec![])]);
+    let changelog = Changelog::build("v1", &before, &before.clone(), vec![]).unwrap();
+    assert!(changelog.is_empty());
+    assert!(changelog.to_markdown().contains("No changes."));
+
+    let mut after = before.clone();
+    after.metadata.hash_algorithm = HashAlgorithm::Blake3;
+    assert!(Changelog::build("v1", &before, &after, vec![]).is_err());
+}
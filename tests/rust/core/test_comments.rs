@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use csd::core::comments::{count_comment_tokens, syntax_for};
+use csd::core::matrix::estimate_code_tokens;
+
+#[test]
+fn test_syntax_for_known_extensions() {
+    assert!(syntax_for(Path::new("src/lib.rs")).is_some());
+    assert!(syntax_for(Path::new("app/Main.JAVA")).is_some());
+    assert!(syntax_for(Path::new("script.py")).is_some());
+    assert_eq!(syntax_for(Path::new("notes.txt")), None);
+    assert_eq!(syntax_for(Path::new("Makefile")), None);
+}
+
+#[test]
+fn test_count_comment_tokens_c_like() {
+    let content = "\
+//! Crate docs
+/// Adds numbers
+fn add(a: i32, b: i32) -> i32 {
+    // plain comment
+    a + b // trailing comments count as code
+}
+/**
+ * Block docs
+ */
+/* block comment */
+";
+    let syntax = syntax_for(Path::new("lib.rs")).unwrap();
+    let tokens = count_comment_tokens(content, syntax);
+    assert_eq!(
+        tokens.documentation,
+        estimate_code_tokens("//! Crate docs\n/// Adds numbers\n/**\n* Block docs\n*/\n")
+    );
+    assert_eq!(
+        tokens.comment,
+        estimate_code_tokens("// plain comment\n/* block comment */\n")
+    );
+}
+
+#[test]
+fn test_count_comment_tokens_python_docstrings() {
+    let content = "\
+\"\"\"Module docs.\"\"\"
+# a comment
+def f():
+    '''
+    Function docs
+    '''
+    return 1
+";
+    let syntax = syntax_for(Path::new("mod.py")).unwrap();
+    let tokens = count_comment_tokens(content, syntax);
+    assert_eq!(
+        tokens.documentation,
+        estimate_code_tokens("\"\"\"Module docs.\"\"\"\n'''\nFunction docs\n'''\n")
+    );
+    assert_eq!(tokens.comment, estimate_code_tokens("# a comment\n"));
+}
+
+#[test]
+fn test_count_comment_tokens_without_comments() {
+    let syntax = syntax_for(Path::new("main.go")).unwrap();
+    let tokens = count_comment_tokens("package main\nfunc main() {}\n", syntax);
+    assert_eq!((tokens.documentation, tokens.comment), (0, 0));
+}
@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use csd::core::compact::{compact_matrix, CompactOptions};
+use csd::core::matrix::{CodeElement, ElementType, Import, ImportType, ProjectMatrix};
+
+use super::test_matrix::{create_test_file_node, create_test_relationship};
+
+fn sample_matrix() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test/project"));
+    let mut lib = create_test_file_node("src/lib.rs", "rust");
+    lib.metadata = serde_json::json!({ "tags": ["layer:core"] });
+    lib.elements.push(CodeElement {
+        element_type: ElementType::Function,
+        name: "load".to_string(),
+        qualified_name: None,
+        parent: None,
+        signature: Some("fn load(path: &Path) -> Config".to_string()),
+        line_start: 1,
+        line_end: 5,
+        summary: Some("Loads the configuration".to_string()),
+        complexity_score: Some(3),
+        calls: vec!["read".to_string()],
+        metadata: serde_json::json!({ "qualified_name": "Config::load", "ast": [1, 2, 3] }),
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    });
+    lib.imports.push(Import {
+        module: "src/util.rs".into(),
+        items: vec![],
+        alias: None,
+        line_number: 1,
+        import_type: ImportType::Local,
+    });
+    matrix.add_file(lib);
+    matrix.add_file(create_test_file_node("src/util.rs", "rust"));
+    let mut relationship = create_test_relationship("src/lib.rs", "src/util.rs");
+    relationship.from_element = Some("src/lib.rs::load".to_string());
+    matrix.add_relationship(relationship);
+    matrix
+}
+
+#[test]
+fn test_compact_strips_content_but_keeps_ids() {
+    let mut matrix = sample_matrix();
+    let id = matrix.files[&PathBuf::from("src/lib.rs")].elements[0]
+        .element_id
+        .clone();
+    let summary = compact_matrix(&mut matrix, &CompactOptions::all());
+
+    assert_eq!(summary.summaries, 3);
+    assert_eq!(summary.signatures, 1);
+    assert_eq!(summary.metadata, 1);
+    assert_eq!(summary.elements, 0);
+
+    let lib = &matrix.files[&PathBuf::from("src/lib.rs")];
+    let element = &lib.elements[0];
+    assert!(element.signature.is_none() && element.summary.is_none());
+    assert!(element.metadata.is_null());
+    assert_eq!(element.element_id, id);
+    // Promoted out of the metadata before it went
+    assert_eq!(element.qualified_name(), "Config::load");
+    // Tags live in file metadata
+    assert_eq!(lib.metadata["tags"][0], "layer:core");
+    assert_eq!(lib.imports.len(), 1);
+    assert!(matrix.relationships[0].from_element.is_some());
+}
+
+#[test]
+fn test_compact_graph_only() {
+    let mut matrix = sample_matrix();
+    let summary = compact_matrix(
+        &mut matrix,
+        &CompactOptions {
+            graph_only: true,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(summary.elements, 1);
+    assert_eq!(summary.imports, 1);
+    assert_eq!(summary.summaries, 0);
+    let lib = &matrix.files[&PathBuf::from("src/lib.rs")];
+    assert!(lib.elements.is_empty() && lib.imports.is_empty());
+    assert!(lib.file_summary.is_some());
+    assert_eq!(matrix.relationships.len(), 1);
+    assert!(matrix.relationships[0].from_element.is_none());
+}
+
+#[tokio::test]
+async fn test_compacted_matrix_is_smaller_and_loads() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("matrix.json");
+    let mut matrix = sample_matrix();
+    matrix.save(&path).await.unwrap();
+    let before = std::fs::metadata(&path).unwrap().len();
+
+    compact_matrix(&mut matrix, &CompactOptions::all());
+    matrix.save(&path).await.unwrap();
+    assert!(std::fs::metadata(&path).unwrap().len() < before);
+
+    let loaded = ProjectMatrix::load(&path).await.unwrap();
+    assert_eq!(loaded.files.len(), 2);
+    assert!(loaded.files[&PathBuf::from("src/lib.rs")].elements[0]
+        .signature
+        .is_none());
+}
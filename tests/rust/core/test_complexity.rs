@@ -0,0 +1,55 @@
+use csd::core::complexity::{estimate_complexity, fill_missing_complexity, ESTIMATED_FLAG};
+use csd::core::matrix::{CodeElement, ElementType};
+
+fn element(element_type: ElementType, lines: (u32, u32), score: Option<u32>) -> CodeElement {
+    CodeElement {
+        element_type,
+        name: "f".to_string(),
+        qualified_name: None,
+        parent: None,
+        signature: None,
+        line_start: lines.0,
+        line_end: lines.1,
+        summary: None,
+        complexity_score: score,
+        calls: Vec::new(),
+        metadata: serde_json::Value::Null,
+        tokens: 0,
+        element_id: String::new(),
+        coverage: None,
+    }
+}
+
+#[test]
+fn test_estimate_complexity_counts_branches() {
+    assert_eq!(estimate_complexity("return 1;"), 1);
+
+    let rust = "if a && b {\n    x\n} else if c {\n    for i in v {}\n}";
+    assert_eq!(estimate_complexity(rust), 5);
+
+    let python = "# if this were code it would count\nwhile x:\n    try:\n        f()\n    except E:\n        pass";
+    assert_eq!(estimate_complexity(python), 3);
+
+    // Identifiers merely containing keywords don't count
+    assert_eq!(estimate_complexity("let iffy = format_for(x);"), 1);
+}
+
+#[test]
+fn test_fill_missing_complexity_only_scores_unscored_callables() {
+    let content = "fn a() {\n    if x { y }\n}\nfn b() {\n    while z {}\n}\nstruct S;";
+    let mut elements = vec![
+        element(ElementType::Function, (1, 3), None),
+        element(ElementType::Method, (4, 6), Some(9)),
+        element(ElementType::Struct, (7, 7), None),
+        element(ElementType::Function, (20, 30), None),
+    ];
+
+    assert_eq!(fill_missing_complexity(&mut elements, content), 1);
+    assert_eq!(elements[0].complexity_score, Some(2));
+    assert_eq!(elements[0].metadata[ESTIMATED_FLAG], true);
+    assert_eq!(elements[1].complexity_score, Some(9));
+    assert!(elements[1].metadata.get(ESTIMATED_FLAG).is_none());
+    assert_eq!(elements[2].complexity_score, None);
+    // Lines past the end of the file are left alone
+    assert_eq!(elements[3].complexity_score, None);
+}
@@ -0,0 +1,358 @@
+use std::path::{Path, PathBuf};
+
+use csd::core::contracts::{
+    extract_routes, link_contracts, normalize_path, parse_openapi, parse_proto, ContractDrift,
+    ContractKind, CONTRACTS_SOURCE,
+};
+use csd::core::matrix::RelationshipType;
+
+use super::test_matrix::create_test_project;
+
+const OPENAPI: &str = r##"openapi: 3.0.3
+info:
+  title: Pet Store
+  version: 1.2.0
+servers:
+  - url: https://api.example.com/v1
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: "#/components/schemas/Pet"
+    post:
+      summary: Add a pet
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/NewPet"
+      responses:
+        "201":
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Pet"
+  /pets/{petId}:
+    delete:
+      operationId: deletePet
+components:
+  schemas:
+    Pet:
+      properties:
+        id:
+          type: integer
+        owner:
+          $ref: "#/components/schemas/Owner"
+"##;
+
+const PROTO: &str = r#"syntax = "proto3";
+
+package billing.v1;
+
+// Charges customers
+service Billing {
+  rpc Charge (ChargeRequest) returns (ChargeReply);
+  rpc Watch (stream WatchRequest) returns (stream Event) {
+    option (google.api.http) = { get: "/v1/watch" };
+  }
+}
+
+message ChargeRequest {
+  string customer_id = 1;
+  repeated LineItem items = 2 [packed = true];
+  map<string, string> metadata = 3;
+  oneof payment {
+    string card = 4;
+    string iban = 5;
+  }
+  message LineItem {
+    int64 cents = 1;
+  }
+  enum Currency {
+    EUR = 0;
+  }
+  reserved 9;
+}
+"#;
+
+#[test]
+fn test_openapi_operations_and_schemas() {
+    let contract = parse_openapi(Path::new("api/openapi.yaml"), OPENAPI).unwrap();
+    assert_eq!(contract.kind, ContractKind::OpenApi);
+    assert_eq!(contract.name, "Pet Store");
+    assert_eq!(contract.version.as_deref(), Some("1.2.0"));
+    assert_eq!(contract.base_path.as_deref(), Some("/v1"));
+
+    let names: Vec<&str> = contract
+        .operations
+        .iter()
+        .map(|o| o.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["GET /pets", "POST /pets", "DELETE /pets/{petId}"]
+    );
+    let list = &contract.operations[0];
+    assert_eq!(list.description.as_deref(), Some("listPets"));
+    assert_eq!(list.output.as_deref(), Some("Pet"));
+    let create = &contract.operations[1];
+    assert_eq!(create.description.as_deref(), Some("Add a pet"));
+    assert_eq!(create.input.as_deref(), Some("NewPet"));
+    assert_eq!(create.output.as_deref(), Some("Pet"));
+
+    assert_eq!(contract.messages.len(), 1);
+    let fields: Vec<(&str, Option<&str>)> = contract.messages[0]
+        .fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.field_type.as_deref()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![("id", Some("integer")), ("owner", Some("Owner"))]
+    );
+
+    // Swagger 2 keeps the prefix in basePath and the body among the parameters
+    let swagger = r##"{"swagger": "2.0", "basePath": "/api", "paths": {"/orders": {"post": {
+        "parameters": [{"in": "body", "name": "order", "schema": {"$ref": "#/definitions/Order"}}],
+        "responses": {"200": {"schema": {"$ref": "#/definitions/Receipt"}}}}}},
+        "definitions": {"Order": {"properties": {"sku": {"type": "string"}}}}}"##;
+    let contract = parse_openapi(Path::new("swagger.json"), swagger).unwrap();
+    assert_eq!(contract.name, "swagger");
+    assert_eq!(contract.base_path.as_deref(), Some("/api"));
+    assert_eq!(contract.operations[0].input.as_deref(), Some("Order"));
+    assert_eq!(contract.operations[0].output.as_deref(), Some("Receipt"));
+    assert_eq!(contract.messages[0].name, "Order");
+
+    // Other YAML isn't a contract
+    assert!(parse_openapi(Path::new("config.yaml"), "swagger_ui: true\nport: 80\n").is_none());
+}
+
+#[test]
+fn test_proto_services_and_messages() {
+    let contract = parse_proto(Path::new("proto/billing.proto"), PROTO);
+    assert_eq!(contract.kind, ContractKind::Protobuf);
+    assert_eq!(contract.name, "billing.v1");
+
+    let operations: Vec<(&str, Option<&str>, Option<&str>)> = contract
+        .operations
+        .iter()
+        .map(|o| (o.name.as_str(), o.input.as_deref(), o.output.as_deref()))
+        .collect();
+    assert_eq!(
+        operations,
+        vec![
+            ("Billing.Charge", Some("ChargeRequest"), Some("ChargeReply")),
+            ("Billing.Watch", Some("WatchRequest"), Some("Event")),
+        ]
+    );
+
+    let messages: Vec<&str> = contract.messages.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(messages, vec!["ChargeRequest", "ChargeRequest.LineItem"]);
+    let fields: Vec<(&str, Option<&str>)> = contract.messages[0]
+        .fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.field_type.as_deref()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("customer_id", Some("string")),
+            ("items", Some("repeated LineItem")),
+            ("metadata", Some("map<string, string>")),
+            ("card", Some("string")),
+            ("iban", Some("string")),
+        ]
+    );
+    assert_eq!(contract.messages[1].fields[0].name, "cents");
+}
+
+#[test]
+fn test_routes_across_frameworks() {
+    let routes = |file: &str, content: &str| -> Vec<(String, String)> {
+        extract_routes(Path::new(file), content)
+            .into_iter()
+            .map(|r| (r.method, r.path))
+            .collect()
+    };
+    let pair = |method: &str, path: &str| (method.to_string(), path.to_string());
+
+    assert_eq!(
+        routes(
+            "app.py",
+            r#"router = APIRouter(prefix="/items")
+
+@router.get("/{item_id}")
+def read_item(item_id: int):
+    token = os.environ.get("TOKEN")
+    return requests.get("/upstream")
+
+@app.route("/login", methods=["GET", "POST"])
+def login():
+    pass
+"#
+        ),
+        vec![
+            pair("GET", "/items/{item_id}"),
+            pair("GET", "/items/login"),
+            pair("POST", "/items/login"),
+        ]
+    );
+    assert_eq!(
+        routes(
+            "server.js",
+            "app.get('/users/:id', show);\n// app.get('/old', legacy);\naxios.get('/api/users');\napp.route('/book').get(list).post(add);\n"
+        ),
+        vec![
+            pair("GET", "/users/:id"),
+            pair("GET", "/book"),
+            pair("POST", "/book"),
+        ]
+    );
+    assert_eq!(
+        routes(
+            "UserController.java",
+            "@RestController\n@RequestMapping(\"/api/users\")\npublic class UserController {\n    @GetMapping(\"/{id}\")\n    public User get() {}\n    @RequestMapping(value = \"/search\", method = RequestMethod.POST)\n    public List<User> search() {}\n}\n"
+        ),
+        vec![pair("GET", "/api/users/{id}"), pair("POST", "/api/users/search")]
+    );
+    assert_eq!(
+        routes(
+            "users.controller.ts",
+            "@Controller('users')\nexport class UsersController {\n  @Get()\n  findAll() {}\n  @Post(':id/ban')\n  ban() {}\n}\n"
+        ),
+        vec![pair("GET", "/users"), pair("POST", "/users/:id/ban")]
+    );
+    assert_eq!(
+        routes(
+            "main.rs",
+            "#[get(\"/health\")]\nasync fn health() {}\nlet app = Router::new().route(\"/todos\", get(list).post(create));\n"
+        ),
+        vec![
+            pair("GET", "/health"),
+            pair("GET", "/todos"),
+            pair("POST", "/todos"),
+        ]
+    );
+    assert_eq!(
+        routes(
+            "main.go",
+            "mux.HandleFunc(\"GET /items/{id}\", getItem)\nr.HandleFunc(\"/orders\", orders).Methods(\"PUT\")\nr.GET(\"/ping\", ping)\n"
+        ),
+        vec![
+            pair("GET", "/items/{id}"),
+            pair("PUT", "/orders"),
+            pair("GET", "/ping"),
+        ]
+    );
+
+    assert_eq!(normalize_path("/users/<int:id>/"), "/users/{}");
+    assert_eq!(normalize_path("users/:id"), normalize_path("/users/{id}"));
+}
+
+#[test]
+fn test_link_contracts_and_check_drift() {
+    let (_temp_dir, mut matrix) = create_test_project(&[
+        ("api/openapi.yaml", OPENAPI),
+        ("proto/billing.proto", PROTO),
+        (
+            "src/pets.py",
+            "@app.get(\"/v1/pets\")\ndef list_pets():\n    pass\n\n@app.post(\"/pets\")\ndef add_pet():\n    pass\n\n@app.get(\"/v1/admin/stats\")\ndef stats():\n    pass\n",
+        ),
+        (
+            "src/billing.py",
+            "class BillingServicer(billing_pb2_grpc.BillingServicer):\n    def Charge(self, request, context):\n        pass\n",
+        ),
+        (
+            "src/client.py",
+            "stub = billing_pb2_grpc.BillingStub(channel)\nstub.Watch(request)\n",
+        ),
+        // Tests aren't handlers
+        ("tests/test_pets.py", "@app.delete(\"/pets/{pet_id}\")\ndef fake():\n    pass\n"),
+    ]);
+    link_contracts(&mut matrix);
+
+    let contracts = &matrix.project_info.contracts;
+    assert_eq!(contracts.len(), 2);
+    let openapi = &contracts[0];
+    assert_eq!(
+        openapi.operations[0].handlers,
+        vec![PathBuf::from("src/pets.py")]
+    );
+    assert_eq!(
+        openapi.operations[1].handlers,
+        vec![PathBuf::from("src/pets.py")]
+    );
+    assert!(openapi.operations[2].handlers.is_empty());
+    let proto = &contracts[1];
+    assert_eq!(
+        proto.operations[0].handlers,
+        vec![PathBuf::from("src/billing.py")]
+    );
+    assert!(proto.operations[1].handlers.is_empty());
+
+    let edges: Vec<(&str, &str, &str)> = matrix
+        .relationships
+        .iter()
+        .filter(|r| r.relationship_type == RelationshipType::Contract)
+        .map(|r| {
+            assert_eq!(r.source_plugin.as_deref(), Some(CONTRACTS_SOURCE));
+            (
+                r.from_file.to_str().unwrap(),
+                r.to_file.to_str().unwrap(),
+                r.details.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        edges,
+        vec![
+            ("src/pets.py", "api/openapi.yaml", "implements GET /pets"),
+            ("src/pets.py", "api/openapi.yaml", "implements POST /pets"),
+            (
+                "src/billing.py",
+                "proto/billing.proto",
+                "implements Billing.Charge"
+            ),
+        ]
+    );
+
+    let drift = ContractDrift::check(&matrix);
+    assert_eq!(drift.contracts, 2);
+    assert_eq!(drift.operations, 5);
+    assert_eq!(
+        drift.unimplemented,
+        vec![
+            (
+                PathBuf::from("api/openapi.yaml"),
+                "DELETE /pets/{petId}".to_string()
+            ),
+            (
+                PathBuf::from("proto/billing.proto"),
+                "Billing.Watch".to_string()
+            ),
+        ]
+    );
+    let undocumented: Vec<&str> = drift.undocumented.iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(undocumented, vec!["/v1/admin/stats"]);
+    assert_eq!(drift.undocumented[0].line, 9);
+    assert_eq!(drift.count(), 3);
+}
+
+#[test]
+fn test_no_contracts_means_no_drift() {
+    let (_temp_dir, mut matrix) =
+        create_test_project(&[("src/app.py", "@app.get(\"/x\")\ndef x():\n    pass\n")]);
+    link_contracts(&mut matrix);
+
+    assert!(matrix.project_info.contracts.is_empty());
+    assert_eq!(matrix.project_info.routes.len(), 1);
+    // Routes only count as undocumented against a spec
+    assert_eq!(ContractDrift::check(&matrix).count(), 0);
+}
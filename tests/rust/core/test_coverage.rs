@@ -0,0 +1,219 @@
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use csd::core::coverage::{apply_coverage, Coverage, CoverageData, CoverageFormat, RiskReport};
+use csd::core::facade::Csd;
+use csd::core::matrix::{CodeElement, ElementType, FileNode, ProjectMatrix};
+use csd::utils::config::Config;
+
+use super::test_matrix::{create_test_file_node, create_test_relationship};
+
+fn function(name: &str, lines: (u32, u32), complexity: u32) -> CodeElement {
+    CodeElement {
+        element_type: ElementType::Function,
+        name: name.to_string(),
+        qualified_name: None,
+        parent: None,
+        signature: None,
+        line_start: lines.0,
+        line_end: lines.1,
+        summary: None,
+        complexity_score: Some(complexity),
+        calls: vec![],
+        metadata: serde_json::Value::Null,
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    }
+}
+
+fn file(root: &Path, relative: &str, elements: Vec<CodeElement>) -> FileNode {
+    let mut node = create_test_file_node(relative, "rust");
+    node.path = root.join(relative);
+    node.elements = elements;
+    node
+}
+
+fn sample_matrix(root: &Path) -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(root.to_path_buf());
+    matrix.add_file(file(
+        root,
+        "src/parser.rs",
+        vec![function("parse", (1, 4), 12), function("peek", (6, 7), 1)],
+    ));
+    matrix.add_file(file(root, "src/util.rs", vec![function("trim", (1, 3), 2)]));
+    matrix.add_file(file(root, "src/main.rs", vec![function("main", (1, 2), 1)]));
+    matrix.add_relationship(create_test_relationship(
+        root.join("src/main.rs").to_str().unwrap(),
+        root.join("src/parser.rs").to_str().unwrap(),
+    ));
+    matrix.add_relationship(create_test_relationship(
+        root.join("src/parser.rs").to_str().unwrap(),
+        root.join("src/util.rs").to_str().unwrap(),
+    ));
+    matrix
+}
+
+const LCOV: &str = "TN:
+SF:/project/src/parser.rs
+DA:1,1
+DA:2,0
+DA:3,0,abc123
+DA:4,0
+DA:6,3
+LF:5
+LH:2
+end_of_record
+SF:src/util.rs
+DA:1,5
+DA:2,5
+end_of_record
+SF:/builds/ci/checkout/src/main.rs
+DA:1,0
+end_of_record
+SF:/project/target/generated.rs
+DA:1,1
+end_of_record
+";
+
+#[test]
+fn test_parse_lcov() {
+    let data = CoverageData::parse(LCOV, CoverageFormat::detect(LCOV)).unwrap();
+    assert_eq!(data.files.len(), 4);
+    let parser = &data.files[Path::new("/project/src/parser.rs")];
+    assert_eq!(parser.len(), 5);
+    assert_eq!(parser[&6], 3);
+    assert_eq!(parser[&3], 0);
+
+    let err = CoverageData::parse_lcov("DA:1,1\n").unwrap_err();
+    assert!(err.to_string().contains("outside a file record"), "{err}");
+    assert!(CoverageData::parse("TN:\n", CoverageFormat::Lcov).is_err());
+}
+
+#[test]
+fn test_parse_cobertura() {
+    let xml = r#"<?xml version="1.0" ?>
+<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">
+<coverage line-rate="0.5" version="7.4">
+  <sources>
+    <source>/project/src</source>
+  </sources>
+  <packages><package name="app"><classes>
+    <class name="parser" filename="parser.rs" line-rate="0.5">
+      <methods><method name="parse"><lines>
+        <line number="1" hits="2"/>
+      </lines></method></methods>
+      <lines>
+        <line number="1" hits="2"/>
+        <line number="2" hits="0" branch="true" condition-coverage="50% (1/2)"/>
+      </lines>
+    </class>
+    <class name='parser_tests' filename='parser.rs'>
+      <lines><line hits="1" number="9"/></lines>
+    </class>
+    <class name="odd &amp; end" filename="a&amp;b.rs"><lines/></class>
+  </classes></package></packages>
+</coverage>"#;
+
+    assert_eq!(CoverageFormat::detect(xml), CoverageFormat::Cobertura);
+    let data = CoverageData::parse(xml, CoverageFormat::Cobertura).unwrap();
+    assert_eq!(data.sources, vec![PathBuf::from("/project/src")]);
+    let parser = &data.files[Path::new("parser.rs")];
+    assert_eq!(
+        parser.iter().map(|(l, h)| (*l, *h)).collect::<Vec<_>>(),
+        vec![(1, 2), (2, 0), (9, 1)]
+    );
+    assert!(data.files.contains_key(Path::new("a&b.rs")));
+}
+
+#[test]
+fn test_apply_coverage_maps_files_and_elements() {
+    let root = Path::new("/project");
+    let mut matrix = sample_matrix(root);
+    let data = CoverageData::parse(LCOV, CoverageFormat::Lcov).unwrap();
+
+    let import = apply_coverage(&mut matrix, &data);
+    assert_eq!(import.files, 3);
+    assert_eq!(
+        import.unmatched,
+        vec![PathBuf::from("/project/target/generated.rs")]
+    );
+    assert_eq!(import.total, Coverage::new(8, 4));
+    assert_eq!(import.total.percent, 50.0);
+
+    let parser = &matrix.files[&root.join("src/parser.rs")];
+    assert_eq!(parser.coverage, Some(Coverage::new(5, 2)));
+    assert_eq!(parser.elements[0].coverage.unwrap().percent, 25.0);
+    assert_eq!(parser.elements[1].coverage.unwrap().percent, 100.0);
+    // Found by its path's ending
+    assert_eq!(
+        matrix.files[&root.join("src/main.rs")].coverage,
+        Some(Coverage::new(1, 0))
+    );
+    let util = &matrix.files[&root.join("src/util.rs")];
+    assert_eq!(util.coverage.unwrap().percent, 100.0);
+    assert_eq!(import.elements, 4);
+
+    // Another import replaces this one
+    let data = CoverageData::parse("SF:src/util.rs\nDA:1,0\n", CoverageFormat::Lcov).unwrap();
+    apply_coverage(&mut matrix, &data);
+    assert!(matrix.files[&root.join("src/parser.rs")].coverage.is_none());
+    assert!(matrix.files[&root.join("src/parser.rs")].elements[0]
+        .coverage
+        .is_none());
+}
+
+#[test]
+fn test_risk_ranks_complex_coupled_uncovered_files() {
+    let root = Path::new("/project");
+    let mut matrix = sample_matrix(root);
+    let data = CoverageData::parse(LCOV, CoverageFormat::Lcov).unwrap();
+    apply_coverage(&mut matrix, &data);
+
+    let report = RiskReport::from_matrix(&matrix, 10);
+    assert_eq!(report.covered_files, 3);
+    let ranked: Vec<(&str, f64)> = report
+        .files
+        .iter()
+        .map(|risk| (risk.path.to_str().unwrap(), risk.score))
+        .collect();
+    // parser.rs: 60% uncovered, the most complex and coupled; main.rs: all uncovered
+    // but simple; util.rs is fully covered so carries no risk
+    assert_eq!(ranked, vec![("src/parser.rs", 0.6), ("src/main.rs", 0.29)]);
+    assert_eq!(report.files[0].complexity, 13);
+    assert_eq!(report.files[0].coupling, 2);
+
+    assert_eq!(RiskReport::from_matrix(&matrix, 1).files.len(), 1);
+    let unscanned = RiskReport::from_matrix(&sample_matrix(root), 10);
+    assert_eq!(unscanned.covered_files, 0);
+    assert!(unscanned.files.is_empty());
+}
+
+#[tokio::test]
+async fn test_import_coverage_saves_matrix() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    let matrix_path = root.join("matrix.json");
+    sample_matrix(root).save(&matrix_path).await.unwrap();
+    let report = root.join("coverage.lcov");
+    std::fs::write(
+        &report,
+        format!(
+            "SF:{}\nDA:1,1\nDA:2,0\n",
+            root.join("src/util.rs").display()
+        ),
+    )
+    .unwrap();
+
+    let csd = Csd::new(Config::default())
+        .with_root(root)
+        .with_matrix_path(&matrix_path);
+    let import = csd.import_coverage(&report, None).await.unwrap();
+    assert_eq!(import.files, 1);
+
+    let matrix = ProjectMatrix::load(&matrix_path).await.unwrap();
+    let util = &matrix.files[&root.join("src/util.rs")];
+    assert_eq!(util.coverage, Some(Coverage::new(2, 1)));
+    assert_eq!(util.elements[0].coverage, Some(Coverage::new(2, 1)));
+    assert_eq!(csd.coverage_risk().await.unwrap().covered_files, 1);
+}
@@ -0,0 +1,387 @@
+use std::path::{Path, PathBuf};
+
+use csd::core::data_model::{
+    detect_data_model, parse_alembic, parse_diesel, parse_django, parse_prisma, parse_sql,
+    parse_sqlalchemy, AccessKind, Column, SchemaChange,
+};
+use csd::core::matrix::{Import, ImportType, ProjectMatrix};
+
+use super::test_matrix::{create_test_project, create_test_relationship};
+
+fn column_names(columns: &[Column]) -> Vec<&str> {
+    columns.iter().map(|c| c.name.as_str()).collect()
+}
+
+#[test]
+fn test_sql_create_alter_and_drop() {
+    let changes = parse_sql(
+        r#"-- V1: initial schema
+CREATE TABLE IF NOT EXISTS "public"."users" (
+    id SERIAL PRIMARY KEY,
+    email VARCHAR(255) NOT NULL, -- login
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT now(),
+    CONSTRAINT users_email_key UNIQUE (email)
+);
+/* composite key */
+CREATE TABLE memberships (user_id INT, team_id INT, PRIMARY KEY (user_id, team_id));
+ALTER TABLE users ADD COLUMN name TEXT, DROP COLUMN created_at, ADD CONSTRAINT c CHECK (1 = 1);
+ALTER TABLE users RENAME COLUMN name TO full_name;
+ALTER TABLE memberships RENAME TO team_members;
+DROP TABLE IF EXISTS legacy, old_users CASCADE;
+"#,
+    );
+
+    let SchemaChange::CreateTable { table, columns } = &changes[0] else {
+        panic!("expected CREATE TABLE, got {:?}", changes[0]);
+    };
+    assert_eq!(table, "users");
+    assert_eq!(column_names(columns), vec!["id", "email", "created_at"]);
+    assert!(columns[0].primary_key && columns[0].not_null);
+    assert_eq!(columns[1].data_type.as_deref(), Some("VARCHAR(255)"));
+    assert!(columns[1].not_null);
+    assert_eq!(
+        columns[2].data_type.as_deref(),
+        Some("TIMESTAMP WITH TIME ZONE")
+    );
+    assert!(!columns[2].not_null);
+
+    let SchemaChange::CreateTable { columns, .. } = &changes[1] else {
+        panic!("expected CREATE TABLE, got {:?}", changes[1]);
+    };
+    assert!(columns.iter().all(|c| c.primary_key));
+
+    assert!(matches!(
+        &changes[2],
+        SchemaChange::AddColumn { table, column } if table == "users" && column.name == "name"
+    ));
+    assert_eq!(
+        changes[3],
+        SchemaChange::DropColumn {
+            table: "users".to_string(),
+            column: "created_at".to_string()
+        }
+    );
+    assert_eq!(
+        changes[4],
+        SchemaChange::RenameColumn {
+            table: "users".to_string(),
+            from: "name".to_string(),
+            to: "full_name".to_string()
+        }
+    );
+    assert_eq!(
+        changes[5],
+        SchemaChange::RenameTable {
+            from: "memberships".to_string(),
+            to: "team_members".to_string()
+        }
+    );
+    assert_eq!(
+        changes[6..],
+        [
+            SchemaChange::DropTable {
+                table: "legacy".to_string()
+            },
+            SchemaChange::DropTable {
+                table: "old_users".to_string()
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_alembic_upgrade_only() {
+    let changes = parse_alembic(
+        r#"from alembic import op
+import sqlalchemy as sa
+
+revision = "1a2b"
+down_revision = None
+
+
+def upgrade():
+    op.create_table(
+        "orders",
+        sa.Column("id", sa.Integer(), nullable=False),
+        sa.Column("user_id", sa.Integer(), sa.ForeignKey("users.id")),
+        sa.Column("total", sa.Numeric(10, 2)),
+        sa.PrimaryKeyConstraint("id"),
+    )
+    op.create_index(op.f("ix_orders_user_id"), "orders", ["user_id"])
+    with op.batch_alter_table("orders") as batch_op:
+        batch_op.add_column(sa.Column("status", sa.String(20)))
+        batch_op.drop_column("total")
+
+
+def downgrade():
+    op.drop_table("orders")
+"#,
+    );
+
+    let SchemaChange::CreateTable { table, columns } = &changes[0] else {
+        panic!("expected create_table, got {:?}", changes[0]);
+    };
+    assert_eq!(table, "orders");
+    assert_eq!(column_names(columns), vec!["id", "user_id", "total"]);
+    assert!(columns[0].primary_key && columns[0].not_null);
+    assert_eq!(columns[1].data_type.as_deref(), Some("Integer"));
+    assert_eq!(columns[2].data_type.as_deref(), Some("Numeric"));
+    assert!(matches!(
+        &changes[1],
+        SchemaChange::AddColumn { table, column } if table == "orders" && column.name == "status"
+    ));
+    assert_eq!(
+        changes[2],
+        SchemaChange::DropColumn {
+            table: "orders".to_string(),
+            column: "total".to_string()
+        }
+    );
+    // The downgrade's drop_table is left out
+    assert_eq!(changes.len(), 3);
+}
+
+#[test]
+fn test_sqlalchemy_and_django_models() {
+    let models = parse_sqlalchemy(
+        r#"class User(Base):
+    __tablename__ = "users"
+
+    id = Column(Integer, primary_key=True)
+    email = db.Column(
+        db.String(120), unique=True, nullable=False
+    )
+    nickname: Mapped[Optional[str]] = mapped_column()
+    team_id = Column("team", ForeignKey("teams.id"))
+
+    def display_name(self):
+        return self.email
+
+
+class Helper:
+    pass
+"#,
+    );
+    assert_eq!(models.len(), 1);
+    assert_eq!(models[0].class.as_deref(), Some("User"));
+    assert_eq!(models[0].table, "users");
+    let columns = &models[0].columns;
+    assert_eq!(
+        column_names(columns),
+        vec!["id", "email", "nickname", "team"]
+    );
+    assert!(columns[0].primary_key);
+    assert_eq!(columns[1].data_type.as_deref(), Some("String"));
+    assert!(columns[1].not_null);
+    assert_eq!(columns[2].data_type.as_deref(), Some("str"));
+    assert!(!columns[2].not_null);
+    assert_eq!(columns[3].data_type, None);
+
+    let models = parse_django(
+        r#"from django.db import models
+
+
+class Timestamped(models.Model):
+    created = models.DateTimeField(auto_now_add=True)
+
+    class Meta:
+        abstract = True
+
+
+class Article(models.Model):
+    title = models.CharField(max_length=200)
+    author = models.ForeignKey(User, on_delete=models.CASCADE)
+    tags = models.ManyToManyField(Tag)
+    summary = models.TextField(null=True, db_column="abstract")
+
+
+class Comment(models.Model):
+    body = models.TextField()
+
+    class Meta:
+        db_table = "blog_comments"
+"#,
+        "blog",
+    );
+    let tables: Vec<&str> = models.iter().map(|m| m.table.as_str()).collect();
+    assert_eq!(tables, vec!["blog_article", "blog_comments"]);
+    assert_eq!(
+        column_names(&models[0].columns),
+        vec!["id", "title", "author_id", "abstract"]
+    );
+    assert!(models[0].columns[0].primary_key);
+    assert!(models[0].columns[1].not_null);
+    assert!(!models[0].columns[3].not_null);
+}
+
+#[test]
+fn test_prisma_and_diesel_schemas() {
+    let models = parse_prisma(
+        r#"datasource db {
+  provider = "postgresql"
+  url      = env("DATABASE_URL")
+}
+
+enum Role {
+  USER
+  ADMIN
+}
+
+model User {
+  id      Int     @id @default(autoincrement())
+  email   String  @unique @map("email_address")
+  role    Role    @default(USER)
+  bio     String?
+  posts   Post[]
+
+  @@map("users")
+}
+
+model Post {
+  id       Int  @id
+  author   User @relation(fields: [authorId], references: [id])
+  authorId Int
+}
+"#,
+    );
+    assert_eq!(models.len(), 2);
+    assert_eq!(models[0].table, "users");
+    assert_eq!(
+        column_names(&models[0].columns),
+        vec!["id", "email_address", "role", "bio"]
+    );
+    assert!(models[0].columns[0].primary_key);
+    assert!(!models[0].columns[3].not_null);
+    assert_eq!(models[1].table, "Post");
+    assert_eq!(column_names(&models[1].columns), vec!["id", "authorId"]);
+
+    let models = parse_diesel(
+        r#"// @generated automatically by Diesel CLI.
+
+diesel::table! {
+    posts (id) {
+        id -> Int4,
+        #[max_length = 255]
+        title -> Varchar,
+        body -> Nullable<Text>,
+    }
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::posts)]
+pub struct Post {
+    pub id: i32,
+}
+"#,
+    );
+    assert_eq!(models.len(), 2);
+    assert_eq!(models[0].class, None);
+    assert_eq!(models[0].table, "posts");
+    assert_eq!(
+        column_names(&models[0].columns),
+        vec!["id", "title", "body"]
+    );
+    assert!(models[0].columns[0].primary_key);
+    assert_eq!(models[0].columns[2].data_type.as_deref(), Some("Text"));
+    assert!(!models[0].columns[2].not_null);
+    assert_eq!(models[1].class.as_deref(), Some("Post"));
+    assert_eq!(models[1].table, "posts");
+}
+
+#[test]
+fn test_detect_data_model_links_files_to_tables() {
+    let (_temp_dir, mut matrix) = create_test_project(&[
+        (
+            "db/migration/V2__add_name.sql",
+            "ALTER TABLE users ADD COLUMN name TEXT;",
+        ),
+        (
+            "db/migration/V10__drop_name.sql",
+            "ALTER TABLE users DROP COLUMN name;",
+        ),
+        (
+            "db/migration/V1__init.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY);\nCREATE TABLE audit_log (id INT);",
+        ),
+        (
+            "db/migration/U2__undo.sql",
+            "ALTER TABLE users DROP COLUMN id;",
+        ),
+        (
+            "app/models.py",
+            "class User(Base):\n    __tablename__ = \"users\"\n    email = Column(String)\n",
+        ),
+        (
+            "app/views.py",
+            "from app.models import User\n\ndef profile(id):\n    return User.get(id)\n",
+        ),
+        (
+            "app/reports.py",
+            "from audit_log import write\n\nQUERY = \"SELECT * FROM audit_log JOIN users ON true\"\n",
+        ),
+        ("app/unrelated.py", "class Account:\n    User = None\n"),
+    ]);
+    matrix.add_relationship(create_test_relationship("app/views.py", "app/models.py"));
+    if let Some(reports) = matrix.files.get_mut(Path::new("app/reports.py")) {
+        reports.imports.push(Import {
+            module: "audit_log".into(),
+            items: vec!["write".to_string()],
+            alias: None,
+            line_number: 1,
+            import_type: ImportType::Local,
+        });
+    }
+
+    let data_model = detect_data_model(&matrix);
+    let names: Vec<&str> = data_model.tables.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!["audit_log", "users"]);
+
+    // V10 runs after V2 and drops what it added; the undo script is ignored
+    let users = data_model.table("users").unwrap();
+    assert_eq!(column_names(&users.columns), vec!["id", "email"]);
+    assert_eq!(users.model.as_deref(), Some("User"));
+    assert_eq!(
+        users.defined_in,
+        vec![
+            PathBuf::from("app/models.py"),
+            PathBuf::from("db/migration/V1__init.sql"),
+            PathBuf::from("db/migration/V2__add_name.sql"),
+            PathBuf::from("db/migration/V10__drop_name.sql"),
+        ]
+    );
+
+    let accesses: Vec<(&str, &str, AccessKind)> = data_model
+        .accesses
+        .iter()
+        .map(|a| (a.file.to_str().unwrap(), a.table.as_str(), a.kind))
+        .collect();
+    assert_eq!(
+        accesses,
+        vec![
+            ("app/reports.py", "audit_log", AccessKind::Sql),
+            ("app/reports.py", "users", AccessKind::Sql),
+            ("app/views.py", "users", AccessKind::Orm),
+        ]
+    );
+    assert_eq!(
+        data_model.files_touching("USERS"),
+        vec![Path::new("app/reports.py"), Path::new("app/views.py")]
+    );
+}
+
+#[test]
+fn test_data_model_round_trips_through_the_matrix() {
+    let (temp_dir, mut matrix) = create_test_project(&[("schema.sql", "CREATE TABLE t (id INT);")]);
+    matrix.data_model = detect_data_model(&matrix);
+
+    let json = serde_json::to_string(&matrix).unwrap();
+    let path = temp_dir.path().join("matrix.json");
+    std::fs::write(&path, json).unwrap();
+    let handle = tokio_test::block_on(ProjectMatrix::open(&path)).unwrap();
+    assert_eq!(handle.data_model, matrix.data_model);
+    assert_eq!(handle.data_model.tables[0].name, "t");
+
+    // Matrices without tables leave the section out
+    let empty = serde_json::to_value(ProjectMatrix::new(temp_dir.path().to_path_buf())).unwrap();
+    assert!(empty.get("data_model").is_none());
+}
@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use csd::core::comments::syntax_for;
+use csd::core::debt::{markers_in, DebtReport};
+use csd::core::matrix::ProjectMatrix;
+use csd::utils::issues::IssueStatus;
+
+use super::test_matrix::create_test_file_node;
+
+#[test]
+fn test_markers_only_in_comments() {
+    let source = "fn main() {\n\
+                  \x20   let todo = \"TODO in a string\"; // FIXME: PAY-7 handle errors\n\
+                  \x20   /* first line\n\
+                  \x20    * HACK - works around #12\n\
+                  \x20    */\n\
+                  \x20   // TODOS aren't markers\n\
+                  }\n";
+    let markers = markers_in(source, syntax_for(Path::new("main.rs")).unwrap());
+    assert_eq!(
+        markers,
+        vec![
+            (2, "FIXME".to_string(), "PAY-7 handle errors".to_string()),
+            (4, "HACK".to_string(), "works around #12".to_string()),
+        ]
+    );
+
+    let python = "def f():\n    \"\"\"Docs.\n\n    TODO: split this up\n    \"\"\"\n    # XXX #3\n";
+    let markers = markers_in(python, syntax_for(Path::new("f.py")).unwrap());
+    assert_eq!(
+        markers,
+        vec![
+            (4, "TODO".to_string(), "split this up".to_string()),
+            (6, "XXX".to_string(), "#3".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_harvest_links_open_issues_and_flags_closed_ones() {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir_all(dir.path().join("src")).unwrap();
+    std::fs::write(
+        dir.path().join("src/pay.py"),
+        "# TODO(PAY-1): retry\n# FIXME PAY-2 rounding\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("src/cart.py"), "x = 1  # TODO PAY-1\n").unwrap();
+
+    let mut matrix = ProjectMatrix::new(dir.path().to_path_buf());
+    for path in ["src/pay.py", "src/cart.py"] {
+        matrix.add_file(create_test_file_node(path, "python"));
+    }
+
+    let mut report = DebtReport::harvest(dir.path(), &matrix);
+    assert_eq!(report.markers.len(), 3);
+    assert_eq!(report.markers[0].file, PathBuf::from("src/cart.py"));
+    let keys: Vec<&str> = report.issues.iter().map(|i| i.key.as_str()).collect();
+    assert_eq!(keys, vec!["PAY-1", "PAY-2"]);
+    assert_eq!(
+        report.issues[0].files,
+        vec![PathBuf::from("src/cart.py"), PathBuf::from("src/pay.py")]
+    );
+    assert!(report.stale_markers().is_empty());
+
+    report.issues[1].status = IssueStatus::Closed;
+    let stale = report.stale_markers();
+    assert_eq!(stale.len(), 1);
+    assert_eq!(stale[0].text, "PAY-2 rounding");
+
+    let summary = report.link(&mut matrix);
+    assert_eq!(summary.tags, 2);
+    let tags = &matrix.files[Path::new("src/pay.py")].metadata["tags"];
+    assert_eq!(tags, &serde_json::json!(["issue:PAY-1"]));
+}
@@ -0,0 +1,267 @@
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use csd::core::deployments::{detect_deployments, Deployment, DeploymentKind, Dockerfile};
+use csd::core::scanner::ProjectScanner;
+use csd::utils::config::{Config, ScanLevel};
+
+use super::test_matrix::create_test_project;
+
+const DOCKERFILE: &str = r#"# syntax=docker/dockerfile:1
+FROM rust:1.80 AS build
+COPY . /src
+RUN cargo build --release
+
+FROM --platform=linux/amd64 debian:bookworm-slim
+COPY --from=build /src/target/release/api /usr/local/bin/api
+COPY --chown=app:app config/ \
+     /etc/api/
+COPY ["migrations", "/migrations"]
+ADD https://example.com/ca.pem /etc/ssl/
+CMD ["api"]
+"#;
+
+fn find<'a>(deployments: &'a [Deployment], name: &str) -> &'a Deployment {
+    deployments
+        .iter()
+        .find(|d| d.name == name)
+        .unwrap_or_else(|| panic!("no deployment named {name}"))
+}
+
+fn paths(files: &[PathBuf]) -> Vec<&str> {
+    files.iter().map(|f| f.to_str().unwrap()).collect()
+}
+
+#[test]
+fn test_dockerfile_sources_of_the_final_stage() {
+    let dockerfile = Dockerfile::parse(DOCKERFILE);
+    assert_eq!(
+        dockerfile.base_image.as_deref(),
+        Some("debian:bookworm-slim")
+    );
+    assert_eq!(dockerfile.sources, vec!["config/", "migrations"]);
+
+    let single = Dockerfile::parse("from python:3.12\ncopy src/*.py requirements.txt /app/\n");
+    assert_eq!(single.sources, vec!["src/*.py", "requirements.txt"]);
+}
+
+#[test]
+fn test_images_package_what_they_copy() {
+    let (_dir, matrix) = create_test_project(&[
+        ("api/Dockerfile", DOCKERFILE),
+        ("api/config/app.toml", ""),
+        ("api/config/local.toml", ""),
+        ("api/.dockerignore", "# not for the image\nconfig/local.*\n"),
+        ("api/migrations/001_init.sql", ""),
+        ("api/src/main.rs", ""),
+        ("web/src/app.py", ""),
+        ("web/src/app_test.py", ""),
+        ("web/src/assets/logo.png", ""),
+        (
+            "web/worker.Dockerfile",
+            "FROM python:3.12\nCOPY src/*.py /app/\n",
+        ),
+    ]);
+
+    let deployments = detect_deployments(&matrix);
+    assert_eq!(deployments.len(), 2);
+    let api = find(&deployments, "api");
+    assert_eq!(api.kind, DeploymentKind::Image);
+    assert_eq!(api.manifest, Path::new("api/Dockerfile"));
+    assert_eq!(api.image.as_deref(), Some("debian:bookworm-slim"));
+    assert_eq!(
+        paths(&api.files),
+        vec!["api/config/app.toml", "api/migrations/001_init.sql"]
+    );
+
+    // Named by its file; `*` stays within a directory
+    let worker = find(&deployments, "worker");
+    assert_eq!(
+        paths(&worker.files),
+        vec!["web/src/app.py", "web/src/app_test.py"]
+    );
+}
+
+#[test]
+fn test_compose_services_and_the_workloads_running_their_images() {
+    let compose = r#"
+services:
+  api:
+    build:
+      context: ./services/api
+      dockerfile: Dockerfile.prod
+    image: registry.example.com/shop/api:1.4
+    depends_on:
+      db:
+        condition: service_healthy
+  worker:
+    build: ./services/worker
+    depends_on: [api]
+  db:
+    image: postgres:16
+"#;
+    let kubernetes = r#"
+apiVersion: v1
+kind: Service
+metadata:
+  name: api
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: shop-api
+spec:
+  template:
+    spec:
+      containers:
+        - name: api
+          image: registry.example.com/shop/api:2.0
+---
+apiVersion: batch/v1
+kind: CronJob
+metadata:
+  name: cleanup
+spec:
+  jobTemplate:
+    spec:
+      template:
+        spec:
+          containers:
+            - image: busybox
+"#;
+    let (_dir, matrix) = create_test_project(&[
+        ("docker-compose.yml", compose),
+        ("deploy/k8s/api.yaml", kubernetes),
+        (
+            "deploy/chart/templates/job.yaml",
+            "{{- if .Values.job }}\nkind: Job\n",
+        ),
+        (
+            "services/api/Dockerfile.prod",
+            "FROM node:20\nCOPY src ./src\n",
+        ),
+        ("services/api/src/index.js", ""),
+        ("services/api/test/index.test.js", ""),
+        ("services/worker/run.py", ""),
+    ]);
+
+    let deployments = detect_deployments(&matrix);
+    let api = deployments
+        .iter()
+        .find(|d| d.kind == DeploymentKind::ComposeService && d.name == "api")
+        .unwrap();
+    assert_eq!(
+        api.dockerfile.as_deref(),
+        Some(Path::new("services/api/Dockerfile.prod"))
+    );
+    assert_eq!(api.depends_on, vec!["db"]);
+    assert_eq!(paths(&api.files), vec!["services/api/src/index.js"]);
+
+    // Without a Dockerfile in the project, the whole context may be copied in
+    let worker = find(&deployments, "worker");
+    assert!(worker.dockerfile.is_none());
+    assert_eq!(paths(&worker.files), vec!["services/worker/run.py"]);
+    assert_eq!(worker.depends_on, vec!["api"]);
+    assert!(find(&deployments, "db").files.is_empty());
+
+    // The cluster runs the image compose builds, by name whatever the tag
+    let workload = find(&deployments, "shop-api");
+    assert_eq!(workload.kind, DeploymentKind::Kubernetes);
+    assert_eq!(workload.resource_type.as_deref(), Some("Deployment"));
+    assert_eq!(workload.dockerfile, api.dockerfile);
+    assert_eq!(workload.files, api.files);
+    let cleanup = find(&deployments, "cleanup");
+    assert_eq!(cleanup.image.as_deref(), Some("busybox"));
+    assert!(cleanup.files.is_empty());
+
+    assert!(!deployments
+        .iter()
+        .any(|d| d.name == "api" && d.kind == DeploymentKind::Kubernetes));
+}
+
+#[test]
+fn test_terraform_resources_deploying_project_files() {
+    let terraform = r#"
+# Zipped for Lambda
+data "archive_file" "handler" {
+  type        = "zip"
+  source_dir  = "${path.module}/../../functions/handler"
+  output_path = "${path.module}/handler.zip"
+}
+
+resource "aws_lambda_function" "handler" {
+  function_name    = "handler"
+  filename         = data.archive_file.handler.output_path
+  source_code_hash = data.archive_file.handler.output_base64sha256
+  environment {
+    variables = {
+      source_dir = "ignored"
+    }
+  }
+}
+
+resource "aws_ecs_task_definition" "api" {
+  image = "registry.example.com/shop/api:latest"
+}
+
+resource "aws_s3_bucket" "assets" {
+  bucket = "shop-assets"
+}
+
+resource "aws_s3_object" "escape" {
+  source_dir = "../../../../etc"
+}
+"#;
+    let (_dir, matrix) = create_test_project(&[
+        ("infra/envs/main.tf", terraform),
+        ("functions/handler/index.py", ""),
+        ("functions/handler/lib/util.py", ""),
+        ("functions/other/index.py", ""),
+    ]);
+
+    let deployments = detect_deployments(&matrix);
+    let names: Vec<&str> = deployments.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["aws_ecs_task_definition.api", "aws_lambda_function.handler"]
+    );
+    let handler = find(&deployments, "aws_lambda_function.handler");
+    assert_eq!(handler.kind, DeploymentKind::Terraform);
+    assert_eq!(
+        handler.resource_type.as_deref(),
+        Some("aws_lambda_function")
+    );
+    assert_eq!(
+        paths(&handler.files),
+        vec![
+            "functions/handler/index.py",
+            "functions/handler/lib/util.py"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_scan_records_deployments() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(temp_dir.path().join("app.py"), "print('hi')\n").unwrap();
+    std::fs::write(
+        temp_dir.path().join("Dockerfile"),
+        "FROM python:3.12\nCOPY app.py /app/\n",
+    )
+    .unwrap();
+
+    let mut config = Config::default();
+    config.input_plugins.clear();
+    config.scanning.level = ScanLevel::Structure;
+    let scanner = ProjectScanner::new(config).with_root(temp_dir.path());
+    let matrix = scanner.scan_to_matrix().await.expect("Scan should succeed");
+
+    let deployments = &matrix.project_info.deployments;
+    assert_eq!(deployments.len(), 1);
+    // Named after the project directory when the Dockerfile sits at its root
+    assert_eq!(
+        deployments[0].name,
+        temp_dir.path().file_name().unwrap().to_str().unwrap()
+    );
+    assert_eq!(paths(&deployments[0].files), vec!["app.py"]);
+}
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use csd::core::doc_drift::{DocDriftReport, ReferenceKind};
+use csd::core::matrix::{CodeElement, ElementType, ProjectMatrix};
+
+use super::test_matrix::create_test_file_node;
+
+fn element(name: &str, qualified_name: &str) -> CodeElement {
+    CodeElement {
+        element_type: ElementType::Function,
+        name: name.to_string(),
+        qualified_name: Some(qualified_name.to_string()),
+        parent: None,
+        signature: None,
+        line_start: 1,
+        line_end: 5,
+        summary: None,
+        complexity_score: None,
+        calls: vec![],
+        metadata: serde_json::Value::Null,
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    }
+}
+
+fn matrix() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    let mut scanner = create_test_file_node("src/scanner.rs", "rust");
+    scanner.elements = vec![
+        element("Scanner", "Scanner"),
+        element("scan", "Scanner::scan"),
+        element("load_config", "load_config"),
+    ];
+    matrix.add_file(scanner);
+    matrix.add_file(create_test_file_node("src/main.rs", "rust"));
+    matrix
+}
+
+fn check(docs: &[(&str, &str)]) -> DocDriftReport {
+    let dir = TempDir::new().unwrap();
+    for (path, content) in docs {
+        let path = dir.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    DocDriftReport::check(dir.path(), &matrix()).unwrap()
+}
+
+fn stale(report: &DocDriftReport) -> Vec<(String, usize, ReferenceKind)> {
+    report
+        .stale
+        .iter()
+        .map(|s| (s.reference.clone(), s.line, s.kind))
+        .collect()
+}
+
+#[test]
+fn test_stale_file_references() {
+    let report = check(&[(
+        "docs/guide.md",
+        "See [the scanner](../src/scanner.rs#L10) and [old](../src/walker.rs).\n\
+         Entry point: `src/main.rs`, helpers in `src/helpers/`.\n\
+         [ref]: ../src/legacy.rs \"Legacy\"\n\
+         Links out: [docs](https://example.com/a.rs), [top](#usage).\n",
+    )]);
+    assert_eq!(report.docs_checked, 1);
+    assert_eq!(
+        stale(&report),
+        vec![
+            ("../src/walker.rs".to_string(), 1, ReferenceKind::File),
+            ("src/helpers/".to_string(), 2, ReferenceKind::File),
+            ("../src/legacy.rs".to_string(), 3, ReferenceKind::File),
+        ]
+    );
+}
+
+#[test]
+fn test_stale_symbol_references() {
+    let report = check(&[(
+        "README.md",
+        "Call `Scanner::scan()` after `load_config()`.\n\
+         `Scanner::walk` is gone, and so is `scanner::discover`.\n\
+         Third-party names like `serde_json::to_value` and `config.yaml` are skipped.\n",
+    )]);
+    assert_eq!(
+        stale(&report),
+        vec![
+            ("Scanner::walk".to_string(), 2, ReferenceKind::Symbol),
+            ("scanner::discover".to_string(), 2, ReferenceKind::Symbol),
+        ]
+    );
+}
+
+#[test]
+fn test_fenced_code_is_skipped() {
+    let report = check(&[(
+        "README.md",
+        "```rust\nlet x = `Scanner::walk`;\n[a](missing.rs)\n```\n~~~\n`gone.rs`\n~~~\n",
+    )]);
+    assert!(report.stale.is_empty());
+    assert_eq!(report.references_checked, 0);
+}
@@ -0,0 +1,113 @@
+use tempfile::TempDir;
+
+use csd::core::doctor::{parse_df_available, parse_python_version, Doctor, DoctorReport};
+use csd::plugins::conformance::CheckStatus;
+use csd::utils::config::{Config, PluginRuntime};
+
+fn check<'a>(report: &'a DoctorReport, name: &str) -> &'a csd::core::doctor::DoctorCheck {
+    report
+        .checks
+        .iter()
+        .find(|c| c.name == name)
+        .unwrap_or_else(|| panic!("no {name} check"))
+}
+
+// Offline so the LLM check doesn't touch the network
+fn offline_config() -> Config {
+    Config {
+        offline: true,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn test_parse_python_version() {
+    assert_eq!(parse_python_version("Python 3.11.4\n"), Some((3, 11, 4)));
+    assert_eq!(parse_python_version("Python 3.13.0rc1"), Some((3, 13, 0)));
+    assert_eq!(parse_python_version("Python 2.7"), Some((2, 7, 0)));
+    assert_eq!(parse_python_version("command not found"), None);
+}
+
+#[test]
+fn test_parse_df_available() {
+    let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+                  /dev/sda1        102400000  51200000  51200000      50% /\n";
+    assert_eq!(parse_df_available(output), Some(51_200_000 * 1024));
+    assert_eq!(parse_df_available("Filesystem\n"), None);
+}
+
+#[tokio::test]
+async fn test_doctor_reports_every_check() {
+    let temp_dir = TempDir::new().unwrap();
+    let report = Doctor::new(Ok(offline_config()), temp_dir.path())
+        .run()
+        .await;
+
+    for name in ["config", "python", "cache", "git", "llm", "disk space"] {
+        check(&report, name);
+    }
+    assert_eq!(check(&report, "config").status, CheckStatus::Pass);
+    assert_eq!(check(&report, "cache").status, CheckStatus::Pass);
+    assert!(check(&report, "llm").detail.contains("offline"));
+    // The built-in plugin paths are relative to the project root, which has none
+    let plugin = check(&report, "input plugin python");
+    assert_eq!(plugin.status, CheckStatus::Fail);
+    assert!(plugin
+        .hint
+        .as_deref()
+        .unwrap()
+        .contains("input_plugins.python"));
+    assert!(!report.passed());
+    // No plugin runs under Node
+    assert!(report.checks.iter().all(|c| c.name != "node"));
+}
+
+#[tokio::test]
+async fn test_doctor_checks_node_for_node_plugins() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = Config {
+        node_executable: Some("/nonexistent/node".to_string()),
+        ..offline_config()
+    };
+    config.input_plugins.get_mut("rust").unwrap().runtime = PluginRuntime::Node;
+    let report = Doctor::new(Ok(config), temp_dir.path()).run().await;
+
+    let node = check(&report, "node");
+    assert_eq!(node.status, CheckStatus::Fail);
+    assert!(node.detail.contains("/nonexistent/node"));
+}
+
+#[tokio::test]
+async fn test_doctor_reports_config_error_and_carries_on() {
+    let temp_dir = TempDir::new().unwrap();
+    let report = Doctor::new(
+        Err(anyhow::anyhow!("invalid type: expected a map")),
+        temp_dir.path(),
+    )
+    .run()
+    .await;
+
+    let config = check(&report, "config");
+    assert_eq!(config.status, CheckStatus::Fail);
+    assert!(config.detail.contains("expected a map"));
+    assert!(config.hint.is_some());
+    // The rest ran against the defaults
+    assert_eq!(check(&report, "cache").status, CheckStatus::Pass);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_doctor_flags_unwritable_cache() {
+    let temp_dir = TempDir::new().unwrap();
+    // A file where the cache directory should be
+    std::fs::write(temp_dir.path().join("blocked"), "").unwrap();
+    let config = Config {
+        cache_dir: Some("blocked/cache".to_string()),
+        ..offline_config()
+    };
+    let report = Doctor::new(Ok(config), temp_dir.path()).run().await;
+
+    let cache = check(&report, "cache");
+    assert_eq!(cache.status, CheckStatus::Fail);
+    assert!(cache.detail.contains("not writable"));
+}
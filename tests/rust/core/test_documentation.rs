@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use csd::core::categories::FileCategory;
+use csd::core::documentation::DocumentationReport;
+use csd::core::matrix::ProjectMatrix;
+
+use super::test_matrix::create_test_file_node;
+
+fn undocumented(path: &str, plugin: &str, tokens: u64) -> csd::core::matrix::FileNode {
+    let mut file = create_test_file_node(path, plugin);
+    file.file_summary = None;
+    file.token_info.total_tokens = tokens;
+    file.token_info.documentation_tokens = 0;
+    file.token_info.comment_tokens = 0;
+    file
+}
+
+#[test]
+fn test_documentation_report_lists_largest_undocumented_modules() {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    // 256 tokens, 40 of them documentation and 16 comments
+    matrix.add_file(create_test_file_node("src/documented.rs", "rust"));
+    matrix.add_file(undocumented("src/small.rs", "rust", 100));
+    matrix.add_file(undocumented("src/large.rs", "rust", 400));
+    matrix.add_file(undocumented("src/medium.rs", "rust", 244));
+    // Not source, or in a language whose docs can't be told apart
+    let mut test = undocumented("tests/test_x.rs", "rust", 500);
+    test.category = Some(FileCategory::Test);
+    matrix.add_file(test);
+    matrix.add_file(undocumented("src/data.txt", "unknown", 500));
+
+    let report = DocumentationReport::from_matrix(&matrix, 2);
+    assert_eq!(report.source_files, 4);
+    assert_eq!(report.documented_files, 1);
+    assert_eq!(report.total_tokens, 1000);
+    assert!((report.documentation_density() - 0.04).abs() < 1e-9);
+    assert!((report.comment_density() - 0.016).abs() < 1e-9);
+    assert_eq!(report.undocumented_files, 3);
+    let paths: Vec<&str> = report
+        .undocumented
+        .iter()
+        .map(|m| m.path.to_str().unwrap())
+        .collect();
+    assert_eq!(paths, vec!["src/large.rs", "src/medium.rs"]);
+}
+
+#[test]
+fn test_documentation_report_of_empty_matrix() {
+    let report = DocumentationReport::from_matrix(&ProjectMatrix::new(PathBuf::from("/test")), 5);
+    assert_eq!(report, DocumentationReport::default());
+    assert_eq!(report.documentation_density(), 0.0);
+}
@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+use csd::core::adjacency::{AdjacencyLevel, AdjacencyMatrix};
+use csd::core::dsm::{render_dsm_html, Dsm};
+use csd::core::matrix::ProjectMatrix;
+
+use super::test_matrix::{create_test_file_node, create_test_relationship};
+
+/// bin/app -> service -> {repo, util}; repo <-> model form a cycle; model -> util
+fn layered_matrix() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/work/shop"));
+    for path in [
+        "bin/app.rs",
+        "core/service.rs",
+        "data/model.rs",
+        "data/repo.rs",
+        "util.rs",
+    ] {
+        matrix.add_file(create_test_file_node(path, "rust"));
+    }
+    for (from, to) in [
+        ("bin/app.rs", "core/service.rs"),
+        ("core/service.rs", "data/repo.rs"),
+        ("core/service.rs", "util.rs"),
+        ("data/repo.rs", "data/model.rs"),
+        ("data/model.rs", "data/repo.rs"),
+        ("data/model.rs", "util.rs"),
+    ] {
+        matrix.add_relationship(create_test_relationship(from, to));
+    }
+    matrix
+}
+
+#[test]
+fn test_dsm_orders_providers_first() {
+    let adjacency = AdjacencyMatrix::from_matrix(&layered_matrix(), AdjacencyLevel::File);
+    let dsm = Dsm::from_adjacency(&adjacency);
+
+    assert_eq!(
+        dsm.labels,
+        vec![
+            "util.rs",
+            "data/model.rs",
+            "data/repo.rs",
+            "core/service.rs",
+            "bin/app.rs"
+        ]
+    );
+    assert_eq!(dsm.layers, vec![0, 1, 1, 2, 3]);
+    assert_eq!(dsm.cycles, vec![None, Some(0), Some(0), None, None]);
+
+    // Only the cycle puts a mark above the diagonal
+    assert_eq!(dsm.upward_dependencies(), 1);
+    assert!(dsm.weights[1][2] > 0.0);
+    assert!(dsm.weights[4][3] > 0.0);
+}
+
+#[test]
+fn test_directory_dsm_ignores_internal_dependencies() {
+    let adjacency = AdjacencyMatrix::from_matrix(&layered_matrix(), AdjacencyLevel::Directory);
+    let dsm = Dsm::from_adjacency(&adjacency);
+
+    // The repo <-> model cycle stays inside data, so directories layer cleanly
+    assert_eq!(dsm.labels, vec![".", "data", "core", "bin"]);
+    assert!(dsm.cycles.iter().all(Option::is_none));
+    assert_eq!(dsm.upward_dependencies(), 0);
+    assert!(dsm.weights[1][1] > 0.0);
+}
+
+#[test]
+fn test_dsm_csv_and_html() {
+    let adjacency = AdjacencyMatrix::from_matrix(&layered_matrix(), AdjacencyLevel::File);
+    let dsm = Dsm::from_adjacency(&adjacency);
+
+    let csv = dsm.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "source,layer,cycle,util.rs,data/model.rs,data/repo.rs,core/service.rs,bin/app.rs"
+    );
+    assert_eq!(lines[1], "util.rs,0,,0,0,0,0,0");
+    assert!(lines[2].starts_with("data/model.rs,1,0,"));
+
+    let html = render_dsm_html("<shop>", &[("files", &dsm)]).unwrap();
+    assert!(html.contains("<h1>&lt;shop&gt;</h1>"));
+    assert!(html.contains(r#"["files"]"#));
+    assert!(html.contains("\"data/repo.rs\""));
+}
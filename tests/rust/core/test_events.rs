@@ -0,0 +1,154 @@
+use chrono::{Duration, Utc};
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use csd::core::events::{detect_changes, EventLog, EventSeverity, MatrixChange, MatrixEvent};
+use csd::core::matrix::{DependencyType, EntrypointInfo, ExternalDependency, ProjectMatrix};
+
+use crate::rust::core::test_matrix::{create_test_file_node, create_test_relationship};
+
+fn dependency(name: &str, version: &str) -> ExternalDependency {
+    ExternalDependency {
+        name: name.to_string(),
+        version: Some(version.to_string()),
+        ecosystem: "cargo".into(),
+        dependency_type: DependencyType::Runtime,
+        source_file: PathBuf::from("Cargo.toml"),
+        source_plugin: None,
+        detected_at: None,
+    }
+}
+
+fn entrypoint(path: &str, kind: &str) -> EntrypointInfo {
+    EntrypointInfo {
+        file_path: PathBuf::from(path),
+        entrypoint_type: kind.to_string(),
+        confidence: 0.9,
+        reason: "test".to_string(),
+    }
+}
+
+fn matrix() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new("/project".into());
+    for file in ["src/main.rs", "src/a.rs", "src/b.rs"] {
+        matrix.add_file(create_test_file_node(file, "rust"));
+    }
+    matrix.add_relationship(create_test_relationship("src/main.rs", "src/a.rs"));
+    matrix.add_relationship(create_test_relationship("src/a.rs", "src/b.rs"));
+    matrix.external_dependencies = vec![dependency("serde", "1.0"), dependency("log", "0.4")];
+    matrix.project_info.entrypoints = vec![entrypoint("src/main.rs", "main")];
+    matrix
+}
+
+#[test]
+fn test_detect_changes() {
+    let mut previous = matrix();
+    let mut current = matrix();
+    assert!(detect_changes(&mut previous, &mut current).is_empty());
+
+    current.add_relationship(create_test_relationship("src/b.rs", "src/a.rs"));
+    current.external_dependencies = vec![
+        dependency("serde", "1.1"),
+        dependency("tokio", "1"),
+        // Declared again in another manifest: still one dependency
+        dependency("tokio", "1"),
+    ];
+    current.project_info.entrypoints = vec![entrypoint("src/lib.rs", "lib")];
+
+    let changes = detect_changes(&mut previous, &mut current);
+    assert_eq!(
+        changes,
+        vec![
+            MatrixChange::CycleIntroduced {
+                files: vec![PathBuf::from("src/a.rs"), PathBuf::from("src/b.rs")],
+            },
+            MatrixChange::DependencyVersionChanged {
+                ecosystem: "cargo".to_string(),
+                name: "serde".to_string(),
+                from: Some("1.0".to_string()),
+                to: Some("1.1".to_string()),
+            },
+            MatrixChange::DependencyAdded {
+                ecosystem: "cargo".to_string(),
+                name: "tokio".to_string(),
+                version: Some("1".to_string()),
+            },
+            MatrixChange::DependencyRemoved {
+                ecosystem: "cargo".to_string(),
+                name: "log".to_string(),
+                version: Some("0.4".to_string()),
+            },
+            MatrixChange::EntrypointAdded {
+                file: PathBuf::from("src/lib.rs"),
+                kind: "lib".to_string(),
+            },
+            MatrixChange::EntrypointRemoved {
+                file: PathBuf::from("src/main.rs"),
+                kind: "main".to_string(),
+            },
+        ]
+    );
+    assert_eq!(changes[0].severity(), EventSeverity::Warning);
+    assert_eq!(
+        changes[0].summary(),
+        "New dependency cycle: src/a.rs → src/b.rs"
+    );
+    assert_eq!(changes[1].severity(), EventSeverity::Info);
+
+    // Breaking the cycle again resolves it
+    let resolved = detect_changes(&mut current, &mut previous);
+    assert!(matches!(resolved[0], MatrixChange::CycleResolved { .. }));
+}
+
+#[test]
+fn test_dependency_versions_compare_by_semver() {
+    let mut previous = matrix();
+    previous.external_dependencies = vec![dependency("serde", "1.9")];
+    let mut current = matrix();
+    // The highest of several manifests' versions counts, numerically
+    current.external_dependencies = vec![dependency("serde", "^1.10"), dependency("serde", "1.9")];
+
+    let changes = detect_changes(&mut previous, &mut current);
+    assert_eq!(
+        changes,
+        vec![MatrixChange::DependencyVersionChanged {
+            ecosystem: "cargo".to_string(),
+            name: "serde".to_string(),
+            from: Some("1.9".to_string()),
+            to: Some("^1.10".to_string()),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_event_log() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let log = EventLog::new(temp_dir.path());
+    assert!(log.load(None).await.unwrap().is_empty());
+
+    let earlier = Utc::now() - Duration::hours(1);
+    let added = |name: &str| MatrixChange::DependencyAdded {
+        ecosystem: "npm".to_string(),
+        name: name.to_string(),
+        version: None,
+    };
+    log.append(&[MatrixEvent::new(earlier, "shop", added("left-pad"))])
+        .await
+        .unwrap();
+    log.append(&[MatrixEvent::new(Utc::now(), "shop", added("react"))])
+        .await
+        .unwrap();
+
+    let events = log.load(None).await.unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].summary, "New npm dependency left-pad");
+    let recent = log.load(Some(earlier)).await.unwrap();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0].change, added("react"));
+
+    // The change is flattened next to its metadata
+    let line = std::fs::read_to_string(log.path()).unwrap();
+    let first: serde_json::Value = serde_json::from_str(line.lines().next().unwrap()).unwrap();
+    assert_eq!(first["type"], "dependency_added");
+    assert_eq!(first["severity"], "warning");
+}
@@ -0,0 +1,542 @@
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use csd::output::manifest::DocsManifest;
+use csd::plugins::interface::{OutputScope, SectionCache};
+use csd::utils::config::{
+    Config, OutputPluginConfig, OverwritePolicy, PluginRuntime, PluginSource,
+};
+use csd::Csd;
+
+use crate::rust::common::python_available;
+
+fn project_with_files(temp_dir: &TempDir) -> Csd {
+    let root = temp_dir.path();
+    fs::write(root.join("main.py"), "import util\n").unwrap();
+    fs::write(root.join("util.py"), "def helper():\n    pass\n").unwrap();
+
+    // No input plugins keeps the scan to basic file nodes
+    let mut config = Config::default();
+    config.input_plugins.clear();
+    config.transform_plugins.clear();
+    Csd::new(config).with_root(root)
+}
+
+#[test]
+fn test_default_paths_follow_project_root() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let csd = Csd::new(Config::default()).with_root(temp_dir.path());
+
+    assert_eq!(
+        csd.matrix_path(),
+        temp_dir.path().join(".csd_cache").join("matrix.json")
+    );
+    assert_eq!(csd.output_dir(), temp_dir.path().join("output"));
+
+    let csd = csd
+        .with_matrix_path("elsewhere/matrix.json")
+        .with_output_dir("site");
+    assert_eq!(
+        csd.matrix_path(),
+        std::path::Path::new("elsewhere/matrix.json")
+    );
+    assert_eq!(csd.output_dir(), std::path::Path::new("site"));
+}
+
+#[tokio::test]
+async fn test_scan_saves_matrix_for_later_loads() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let csd = project_with_files(&temp_dir);
+
+    let err = csd.load_matrix().await.unwrap_err();
+    assert!(err.to_string().contains("Run 'csd init' first"));
+
+    let report = csd.scan().await.expect("Scan failed");
+    assert_eq!(report.matrix_path, csd.matrix_path());
+    assert!(report.transforms.is_empty());
+    assert_eq!(report.matrix.files.len(), 2);
+
+    let loaded = csd.load_matrix().await.expect("Matrix should load");
+    assert_eq!(loaded.files.len(), 2);
+
+    let analysis = csd.quality().await.expect("Analysis failed");
+    assert_eq!(analysis.layers.iter().map(Vec::len).sum::<usize>(), 2);
+}
+
+#[tokio::test]
+async fn test_generate_docs_without_plugin_for_format() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let csd = project_with_files(&temp_dir);
+    csd.scan().await.expect("Scan failed");
+
+    let err = csd.generate_docs("docx").await.unwrap_err();
+    assert!(err.to_string().contains("No documentation plugins"));
+}
+
+/// Project whose only docs plugin writes `index.md` naming the requested language
+fn project_with_docs_plugin(temp_dir: &TempDir, languages: &[&str]) -> Csd {
+    project_with_reporting_docs_plugin(temp_dir, languages, None)
+}
+
+/// Like `project_with_docs_plugin`, but with `report_path` / `report_size` overriding
+/// what the plugin claims to have written
+fn project_with_reporting_docs_plugin(
+    temp_dir: &TempDir,
+    languages: &[&str],
+    plugin_config: Option<&str>,
+) -> Csd {
+    let script = r#"import json, os, sys
+msg = json.loads(sys.stdin.read())
+inp = msg["input"]
+options = inp.get("plugin_config") or {}
+cache = inp["section_cache"]
+generated = []
+for index, section in enumerate(["overview", "usage", "api"]):
+    if section in cache["completed"]:
+        continue
+    if index == options.get("crash_at"):
+        sys.exit(1)
+    open(os.path.join(cache["dir"], section + ".md"), "w").write(section)
+    open(os.path.join(cache["dir"], section + ".done"), "w").close()
+    generated.append(section)
+with open(os.path.join(inp["output_dir"], "sections.txt"), "w") as f:
+    f.write(",".join(generated))
+with open(os.path.join(inp["output_dir"], "hotspots.json"), "w") as f:
+    json.dump(inp["format_options"].get("hotspots"), f)
+with open(os.path.join(inp["output_dir"], "scope.json"), "w") as f:
+    files = sorted(json.load(open(inp["matrix_path"]))["files"])
+    json.dump({"files": files, "scope": inp.get("scope")}, f)
+language = inp["format_options"].get("language", "default")
+path = os.path.join(inp["output_dir"], "index.md")
+with open(path, "w") as f:
+    f.write(language)
+path = options.get("report_path", path)
+size = options.get("report_size", len(language))
+print(json.dumps({"status": "output_success", "result": {
+    "plugin_name": "fake_docs", "plugin_version": "0.1.0", "output_type": "documentation",
+    "outputs": [{"output_path": path, "content_type": "markdown", "size_bytes": size,
+                 "checksum": "", "metadata": {}}],
+    "processing_time_ms": 1, "metadata": {}, "patch": options.get("patch")}}))
+"#;
+    let plugin_path = temp_dir.path().join("fake_docs.py");
+    fs::write(&plugin_path, script).unwrap();
+
+    let csd = project_with_files(temp_dir);
+    let mut config = csd.config().clone();
+    config.output_plugins.clear();
+    config.output_plugins.insert(
+        "fake_docs".to_string(),
+        OutputPluginConfig {
+            source: PluginSource::Local {
+                path: plugin_path.to_string_lossy().to_string(),
+            },
+            output_types: vec!["documentation".to_string()],
+            formats: vec!["markdown".to_string()],
+            enabled: true,
+            config: plugin_config.map(|yaml| serde_yaml::from_str(yaml).unwrap()),
+            runtime: PluginRuntime::Python,
+        },
+    );
+    config.docs.languages = languages.iter().map(|l| l.to_string()).collect();
+    Csd::new(config).with_root(temp_dir.path())
+}
+
+#[tokio::test]
+async fn test_generate_docs_once_per_language() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake docs plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let csd = project_with_docs_plugin(&temp_dir, &["en", "de"]);
+    csd.scan().await.expect("Scan failed");
+
+    let report = csd.generate_docs("markdown").await.expect("Docs failed");
+    let languages: Vec<_> = report
+        .passes
+        .iter()
+        .map(|p| p.language.as_deref())
+        .collect();
+    assert_eq!(languages, vec![Some("en"), Some("de")]);
+
+    let output_dir = csd.output_dir();
+    for language in ["en", "de"] {
+        let written = fs::read_to_string(output_dir.join(language).join("index.md")).unwrap();
+        assert_eq!(written, language);
+    }
+
+    let manifest = DocsManifest::load(&output_dir)
+        .await
+        .expect("Manifest missing");
+    assert_eq!(report.manifest_path, output_dir.join("csd_manifest.json"));
+    assert_eq!(manifest.format, "markdown");
+    let german = manifest.entry(Some("de")).expect("No de entry");
+    assert_eq!(german.output_dir, output_dir.join("de"));
+    assert_eq!(german.outputs.len(), 1);
+    assert!(manifest.entry(None).is_none());
+}
+
+#[tokio::test]
+async fn test_generate_docs_single_pass_without_languages() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake docs plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let csd = project_with_docs_plugin(&temp_dir, &[]);
+    csd.scan().await.expect("Scan failed");
+
+    let report = csd.generate_docs("markdown").await.expect("Docs failed");
+    assert_eq!(report.passes.len(), 1);
+    assert_eq!(report.passes[0].output_dir, csd.output_dir());
+    let written = fs::read_to_string(csd.output_dir().join("index.md")).unwrap();
+    assert_eq!(written, "default");
+
+    let manifest = DocsManifest::load(&csd.output_dir()).await.unwrap();
+    assert!(manifest.entry(None).is_some());
+}
+
+#[tokio::test]
+async fn test_generate_docs_passes_hotspots_to_plugin() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake docs plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let mut config = project_with_docs_plugin(&temp_dir, &[]).config().clone();
+    config.quality.max_file_tokens = 0;
+    config.quality.top = 1;
+    let csd = Csd::new(config).with_root(temp_dir.path());
+    // Without input plugins only known text files get a token estimate
+    fs::write(temp_dir.path().join("notes.md"), "word ".repeat(50)).unwrap();
+    fs::write(temp_dir.path().join("todo.md"), "word ".repeat(20)).unwrap();
+    csd.scan().await.expect("Scan failed");
+
+    csd.generate_docs("markdown").await.expect("Docs failed");
+    let written = fs::read_to_string(csd.output_dir().join("hotspots.json")).unwrap();
+    let hotspots: serde_json::Value = serde_json::from_str(&written).unwrap();
+    assert_eq!(hotspots["files_over_limit"], 2);
+    assert_eq!(hotspots["files"][0]["path"], "notes.md");
+    assert_eq!(hotspots["files"].as_array().unwrap().len(), 1);
+    assert_eq!(hotspots["thresholds"]["max_file_tokens"], 0);
+}
+
+#[tokio::test]
+async fn test_generate_docs_hands_plugin_the_scoped_matrix() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake docs plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let csd = project_with_docs_plugin(&temp_dir, &[]);
+    csd.scan().await.expect("Scan failed");
+
+    let scoped = csd.clone().with_scope(OutputScope {
+        globs: vec!["util*".to_string()],
+        ..OutputScope::default()
+    });
+    scoped.generate_docs("markdown").await.expect("Docs failed");
+    let written = fs::read_to_string(csd.output_dir().join("scope.json")).unwrap();
+    let received: serde_json::Value = serde_json::from_str(&written).unwrap();
+    let files = received["files"].as_array().unwrap();
+    assert_eq!(files.len(), 1);
+    assert!(files[0].as_str().unwrap().ends_with("util.py"));
+    assert_eq!(received["scope"]["globs"], serde_json::json!(["util*"]));
+    // The full matrix is left alone
+    assert!(csd.load_matrix().await.unwrap().files.len() > 1);
+
+    let nothing = csd.clone().with_scope(OutputScope {
+        paths: vec!["missing".into()],
+        ..OutputScope::default()
+    });
+    assert!(nothing.generate_docs("markdown").await.is_err());
+}
+
+#[tokio::test]
+async fn test_generate_docs_writes_plugin_patches_back_to_the_matrix() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake docs plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let plugin_config = r#"
+patch:
+  summaries:
+    - {file: main.py, summary: Entry point that wires the helpers together}
+    - {file: gone.py, summary: Not in the matrix}
+  relationships:
+    - {from_file: main.py, to_file: util.py, relationship_type: call, details: helper(),
+       line_number: 1, strength: 0.7}
+"#;
+    let csd = project_with_reporting_docs_plugin(&temp_dir, &[], Some(plugin_config));
+    csd.scan().await.expect("Scan failed");
+
+    // Shown only util.py, the plugin's changes still land in the full matrix
+    let scoped = csd.clone().with_scope(OutputScope {
+        globs: vec!["util*".to_string()],
+        ..OutputScope::default()
+    });
+    let report = scoped.generate_docs("markdown").await.expect("Docs failed");
+    let summary = report.passes[0].write_back.as_ref().unwrap();
+    assert_eq!(
+        (summary.summaries, summary.relationships, summary.skipped),
+        (1, 1, 1)
+    );
+
+    let matrix = csd.load_matrix().await.unwrap();
+    let main = matrix
+        .files
+        .values()
+        .find(|f| f.relative_path.ends_with("main.py"))
+        .unwrap();
+    assert_eq!(
+        main.file_summary.as_deref(),
+        Some("Entry point that wires the helpers together")
+    );
+    assert!(matrix
+        .relationships
+        .iter()
+        .any(|r| r.source_plugin.as_deref() == Some("fake_docs")));
+}
+
+#[tokio::test]
+async fn test_generate_document_returns_content_without_leaving_files() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake docs plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let csd = project_with_docs_plugin(&temp_dir, &[]);
+    csd.scan().await.expect("Scan failed");
+
+    let (result, content) = csd
+        .generate_document("markdown")
+        .await
+        .expect("Docs failed");
+    assert_eq!(result.plugin_name, "fake_docs");
+    assert_eq!(content, b"default");
+    assert!(!csd.output_dir().exists());
+
+    let two_languages = project_with_docs_plugin(&temp_dir, &["en", "de"]);
+    assert!(two_languages.generate_document("markdown").await.is_err());
+}
+
+#[tokio::test]
+async fn test_changelog_since_matrix_is_recorded_in_manifest() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let csd = project_with_files(&temp_dir);
+    csd.scan().await.expect("Scan failed");
+    let since = temp_dir.path().join("since.json");
+    fs::copy(csd.matrix_path(), &since).unwrap();
+
+    fs::write(
+        temp_dir.path().join("util.py"),
+        "def helper(x):\n    pass\n",
+    )
+    .unwrap();
+    fs::write(temp_dir.path().join("extra.py"), "x = 1\n").unwrap();
+    csd.scan().await.expect("Scan failed");
+
+    let report = csd
+        .generate_changelog(&since.to_string_lossy(), false)
+        .await
+        .expect("Changelog failed");
+    let root = &report.changelog.components[0];
+    assert_eq!(root.component, "(root)");
+    assert!(root.files_added.contains(&"extra.py".into()));
+    assert!(root.files_modified.contains(&"util.py".into()));
+
+    let draft = fs::read_to_string(csd.output_dir().join("CHANGELOG.draft.md")).unwrap();
+    assert!(draft.contains("- Added `extra.py`"));
+    let manifest = DocsManifest::load(&csd.output_dir()).await.unwrap();
+    assert_eq!(manifest.entries.len(), 1);
+    assert_eq!(manifest.entries[0].output_type, "changelog");
+    assert_eq!(manifest.entries[0].plugin, "csd");
+    // Changelog entries aren't documentation passes
+    assert!(manifest.entry(None).is_none());
+
+    // Without a plugin for the changelog type there's nothing to polish with
+    let mut config = csd.config().clone();
+    config.output_plugins.clear();
+    let unpolished = Csd::new(config).with_root(temp_dir.path());
+    assert!(unpolished
+        .generate_changelog(&since.to_string_lossy(), true)
+        .await
+        .is_err());
+    assert!(csd.generate_changelog("no-such-tag", false).await.is_err());
+}
+
+#[tokio::test]
+async fn test_changelog_since_tag_scans_the_tagged_checkout() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    let git = |args: &[&str]| {
+        std::process::Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args(["-c", "user.name=csd", "-c", "user.email=csd@example.com"])
+            .args(args)
+            .output()
+            .is_ok_and(|output| output.status.success())
+    };
+    if !git(&["init", "-q"]) {
+        eprintln!("Skipping: git is not available");
+        return;
+    }
+    let csd = project_with_files(&temp_dir);
+    fs::write(root.join(".gitignore"), ".csd_cache/\noutput/\n").unwrap();
+    assert!(git(&["add", "-A"]) && git(&["commit", "-q", "-m", "Initial"]));
+    assert!(git(&["tag", "v1"]));
+    fs::write(root.join("util.py"), "def helper(x):\n    pass\n").unwrap();
+    assert!(git(&["commit", "-q", "-am", "Let helper take an argument"]));
+    csd.scan().await.expect("Scan failed");
+
+    let report = csd
+        .generate_changelog("v1", false)
+        .await
+        .expect("Changelog failed");
+    let root_changes = &report.changelog.components[0];
+    assert_eq!(root_changes.files_modified, vec![PathBuf::from("util.py")]);
+    assert!(root_changes.files_added.is_empty());
+    assert_eq!(root_changes.commits.len(), 1);
+    assert_eq!(
+        root_changes.commits[0].subject,
+        "Let helper take an argument"
+    );
+}
+
+#[tokio::test]
+async fn test_generate_docs_rejects_path_like_languages() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let csd = project_with_docs_plugin(&temp_dir, &["en", "../escape"]);
+    csd.scan().await.expect("Scan failed");
+
+    let err = csd.generate_docs("markdown").await.unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Invalid docs language '../escape'"));
+}
+
+#[tokio::test]
+async fn test_generate_docs_rejects_outputs_outside_output_dir() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake docs plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let csd =
+        project_with_reporting_docs_plugin(&temp_dir, &[], Some("report_path: ../../etc/passwd"));
+    csd.scan().await.expect("Scan failed");
+
+    let err = csd.generate_docs("markdown").await.unwrap_err();
+    assert!(format!("{err:#}").contains("outside the output directory"));
+}
+
+#[tokio::test]
+async fn test_overwrite_policy_fail_and_backup() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake docs plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let csd = project_with_docs_plugin(&temp_dir, &[]);
+    csd.scan().await.expect("Scan failed");
+    csd.generate_docs("markdown")
+        .await
+        .expect("First run failed");
+
+    let mut config = csd.config().clone();
+    config.docs.overwrite = OverwritePolicy::Fail;
+    let failing = Csd::new(config.clone()).with_root(temp_dir.path());
+    let err = failing.generate_docs("markdown").await.unwrap_err();
+    assert!(err.to_string().contains("docs.overwrite is 'fail'"));
+
+    config.docs.overwrite = OverwritePolicy::Backup;
+    let backing_up = Csd::new(config).with_root(temp_dir.path());
+    backing_up
+        .generate_docs("markdown")
+        .await
+        .expect("Backup run failed");
+
+    let backups: Vec<_> = fs::read_dir(csd.output_dir().join(".csd_backup"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(backups.len(), 1);
+    assert_eq!(
+        fs::read_to_string(backups[0].join("index.md")).unwrap(),
+        "default"
+    );
+    assert!(backups[0].join("csd_manifest.json").exists());
+}
+
+#[tokio::test]
+async fn test_generate_docs_verifies_reported_outputs() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake docs plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let csd = project_with_docs_plugin(&temp_dir, &[]);
+    csd.scan().await.expect("Scan failed");
+
+    // The fake plugin leaves the checksum blank, so it is filled in
+    let report = csd.generate_docs("markdown").await.expect("Docs failed");
+    let output = &report.passes[0].result.outputs[0];
+    assert!(
+        output.is_verified(),
+        "unexpected issues: {:?}",
+        output.issues
+    );
+    assert_eq!(
+        output.checksum,
+        // sha256("default")
+        "37a8eec1ce19687d132fe29051dca629d164e2c4958ba141d5f4133a33f0688f"
+    );
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let csd = project_with_reporting_docs_plugin(&temp_dir, &[], Some("report_size: 4096"));
+    csd.scan().await.expect("Scan failed");
+
+    let report = csd.generate_docs("markdown").await.expect("Docs failed");
+    let output = &report.passes[0].result.outputs[0];
+    assert_eq!(
+        output.issues,
+        vec!["size mismatch: reported 4096 bytes, found 7".to_string()]
+    );
+
+    // Flagged outputs are recorded as such in the manifest
+    let manifest = DocsManifest::load(&csd.output_dir()).await.unwrap();
+    assert!(!manifest.entries[0].outputs[0].is_verified());
+}
+
+#[tokio::test]
+async fn test_resume_reuses_sections_from_interrupted_run() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake docs plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let crashing = project_with_reporting_docs_plugin(&temp_dir, &[], Some("crash_at: 2"));
+    crashing.scan().await.expect("Scan failed");
+    assert!(crashing.generate_docs("markdown").await.is_err());
+
+    let section_dir = crashing.cache_dir().join("sections/fake_docs/default");
+    assert_eq!(
+        SectionCache::completed_sections(&section_dir),
+        vec!["overview".to_string(), "usage".to_string()]
+    );
+
+    let csd = project_with_docs_plugin(&temp_dir, &[]).with_resume(true);
+    csd.generate_docs("markdown").await.expect("Resume failed");
+    let generated = fs::read_to_string(csd.output_dir().join("sections.txt")).unwrap();
+    assert_eq!(generated, "api");
+
+    // A finished run leaves nothing to resume, and a fresh run regenerates everything
+    assert!(!section_dir.exists());
+    let csd = project_with_docs_plugin(&temp_dir, &[]);
+    csd.generate_docs("markdown").await.expect("Docs failed");
+    let generated = fs::read_to_string(csd.output_dir().join("sections.txt")).unwrap();
+    assert_eq!(generated, "overview,usage,api");
+}
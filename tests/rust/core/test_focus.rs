@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+
+use csd::core::focus::FocusGraph;
+use csd::core::matrix::ProjectMatrix;
+use csd::plugins::interface::OutputScope;
+
+use super::test_matrix::{create_test_file_node, create_test_relationship};
+
+// main -> core/scanner -> core/matrix -> utils/fs, and tests/scan -> core/scanner
+fn project() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    for file in [
+        "src/main.rs",
+        "src/core/scanner.rs",
+        "src/core/matrix.rs",
+        "src/utils/fs.rs",
+        "tests/scan.rs",
+    ] {
+        matrix.add_file(create_test_file_node(file, "rust"));
+    }
+    for (from, to) in [
+        ("src/main.rs", "src/core/scanner.rs"),
+        ("src/core/scanner.rs", "src/core/matrix.rs"),
+        ("src/core/matrix.rs", "src/utils/fs.rs"),
+        ("tests/scan.rs", "src/core/scanner.rs"),
+    ] {
+        matrix.add_relationship(create_test_relationship(from, to));
+    }
+    matrix
+}
+
+fn files(graph: &FocusGraph) -> Vec<&Path> {
+    let mut files: Vec<&Path> = graph.matrix.files.keys().map(|p| p.as_path()).collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn test_focus_on_a_file_expands_both_ways() {
+    let graph = FocusGraph::new(&project(), Path::new("src/core/matrix.rs"), 1).unwrap();
+    assert_eq!(graph.focus, vec![PathBuf::from("src/core/matrix.rs")]);
+    assert_eq!(
+        files(&graph),
+        vec![
+            Path::new("src/core/matrix.rs"),
+            Path::new("src/core/scanner.rs"),
+            Path::new("src/utils/fs.rs"),
+        ]
+    );
+    assert_eq!(graph.matrix.relationships.len(), 2);
+
+    let wider = FocusGraph::new(&project(), Path::new("src/core/matrix.rs"), 2).unwrap();
+    assert_eq!(wider.matrix.files.len(), 5);
+}
+
+#[test]
+fn test_focus_on_a_directory() {
+    let graph = FocusGraph::new(&project(), Path::new("./src/core/"), 0).unwrap();
+    assert_eq!(
+        graph.focus,
+        vec![
+            PathBuf::from("src/core/matrix.rs"),
+            PathBuf::from("src/core/scanner.rs"),
+        ]
+    );
+    assert_eq!(graph.matrix.files.len(), 2);
+    assert_eq!(graph.matrix.relationships.len(), 1);
+
+    assert!(FocusGraph::new(&project(), Path::new("src/missing"), 1).is_err());
+    // A prefix of a name isn't a directory
+    assert!(FocusGraph::new(&project(), Path::new("src/co"), 1).is_err());
+}
+
+#[test]
+fn test_dot_highlights_focus_and_merges_edges() {
+    let mut matrix = project();
+    let mut call = create_test_relationship("src/main.rs", "src/core/scanner.rs");
+    call.relationship_type = "call".parse().unwrap();
+    call.strength = 1.0;
+    matrix.add_relationship(call);
+
+    let dot = FocusGraph::new(&matrix, Path::new("src/main.rs"), 1)
+        .unwrap()
+        .to_dot();
+    assert!(dot.starts_with("digraph focus {\n"));
+    assert!(dot.contains("  \"src/main.rs\" [style=filled, fillcolor=\"#ffe08a\"];\n"));
+    assert!(dot.contains("  \"src/core/scanner.rs\";\n"));
+    assert!(dot.contains(
+        "  \"src/main.rs\" -> \"src/core/scanner.rs\" [label=\"call, import\", penwidth=3.0];\n"
+    ));
+    assert_eq!(dot.matches(" -> ").count(), 1);
+    assert!(dot.ends_with("}\n"));
+}
+
+#[test]
+fn test_output_scope_selects_paths_globs_and_tags() {
+    let mut matrix = project();
+    matrix
+        .files
+        .get_mut(Path::new("tests/scan.rs"))
+        .unwrap()
+        .metadata["tags"] = serde_json::json!(["integration"]);
+
+    let by_path = OutputScope {
+        paths: vec![PathBuf::from("./src/core/")],
+        ..OutputScope::default()
+    };
+    assert_eq!(
+        by_path.select(&matrix).unwrap(),
+        vec![
+            PathBuf::from("src/core/matrix.rs"),
+            PathBuf::from("src/core/scanner.rs"),
+        ]
+    );
+
+    let by_glob_and_tag = OutputScope {
+        globs: vec!["src/*/fs.rs".to_string()],
+        tags: vec!["integration".to_string()],
+        ..OutputScope::default()
+    };
+    assert_eq!(
+        by_glob_and_tag.select(&matrix).unwrap(),
+        vec![
+            PathBuf::from("src/utils/fs.rs"),
+            PathBuf::from("tests/scan.rs")
+        ]
+    );
+
+    assert!(OutputScope::default().is_empty());
+    let invalid = OutputScope {
+        globs: vec!["[".to_string()],
+        ..OutputScope::default()
+    };
+    assert!(invalid.select(&matrix).is_err());
+}
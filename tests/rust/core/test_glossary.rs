@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+
+use csd::core::glossary::{extract_glossary, split_words};
+use csd::core::matrix::{CodeElement, ElementType, ProjectMatrix};
+use csd::utils::config::GlossaryConfig;
+
+use super::test_matrix::create_test_matrix;
+
+fn element(name: &str, summary: Option<&str>) -> CodeElement {
+    CodeElement {
+        element_type: ElementType::Function,
+        name: name.to_string(),
+        qualified_name: None,
+        parent: None,
+        signature: None,
+        line_start: 1,
+        line_end: 5,
+        summary: summary.map(str::to_string),
+        complexity_score: None,
+        calls: vec![],
+        metadata: serde_json::Value::Null,
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    }
+}
+
+// Files with their summaries and elements
+fn matrix(files: &[(&str, &str, Vec<CodeElement>)]) -> ProjectMatrix {
+    create_test_matrix("python", files, |file, summary| {
+        file.file_summary = Some(summary.to_string())
+    })
+}
+
+fn terms(matrix: &ProjectMatrix, config: &GlossaryConfig) -> Vec<String> {
+    extract_glossary(matrix, config)
+        .into_iter()
+        .map(|term| term.term)
+        .collect()
+}
+
+#[test]
+fn test_split_words() {
+    assert_eq!(
+        split_words("parseHTTPRequest"),
+        vec!["parse", "http", "request"]
+    );
+    assert_eq!(
+        split_words("ledger_entry2 is an Invoice"),
+        vec!["ledger", "entry", "invoice"]
+    );
+}
+
+#[test]
+fn test_terms_ranked_by_use_and_distinctiveness() {
+    let matrix = matrix(&[
+        (
+            "billing/ledger.py",
+            "Ledger of invoice postings",
+            vec![
+                element("post_invoice", Some("Post an invoice to the ledger")),
+                element("void_invoice", None),
+            ],
+        ),
+        (
+            "billing/invoice.py",
+            "Invoice model",
+            vec![element(
+                "Invoice",
+                Some("An invoice with a ledger reference"),
+            )],
+        ),
+        ("app.py", "Entry point", vec![element("run_app", None)]),
+    ]);
+
+    let candidates = extract_glossary(&matrix, &GlossaryConfig::default());
+    let names: Vec<&str> = candidates.iter().map(|t| t.term.as_str()).collect();
+    // Stopwords and terms of a single file are left out
+    assert_eq!(names, vec!["invoice", "ledger"]);
+    assert_eq!(candidates[0].occurrences, 8);
+    // Ties in uses are listed by path
+    assert_eq!(
+        candidates[0].files,
+        vec![
+            PathBuf::from("billing/invoice.py"),
+            PathBuf::from("billing/ledger.py")
+        ]
+    );
+    assert!(candidates[0].score > candidates[1].score);
+}
+
+#[test]
+fn test_allow_and_deny_lists() {
+    let matrix = matrix(&[
+        ("a.py", "Ledger settlement", vec![]),
+        ("b.py", "Ledger", vec![]),
+    ]);
+    let config = GlossaryConfig {
+        allow: vec!["Settlement".to_string()],
+        deny: vec!["ledger".to_string()],
+        ..GlossaryConfig::default()
+    };
+    assert_eq!(terms(&matrix, &config), vec!["settlement"]);
+
+    let config = GlossaryConfig {
+        max_terms: 0,
+        allow: vec!["settlement".to_string()],
+        ..GlossaryConfig::default()
+    };
+    assert_eq!(terms(&matrix, &config), vec!["settlement"]);
+}
+
+#[test]
+fn test_glossary_config_defaults() {
+    let config: GlossaryConfig = serde_yaml::from_str("deny: [widget]").unwrap();
+    assert!(config.enabled);
+    assert_eq!(config.min_files, GlossaryConfig::default().min_files);
+    assert!(!config.is_default());
+}
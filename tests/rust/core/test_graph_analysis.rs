@@ -0,0 +1,159 @@
+use std::path::PathBuf;
+
+use csd::core::graph_analysis::{detect_components, label_propagation, pagerank, GraphAnalysis};
+use csd::core::matrix::{ComponentSource, ProjectMatrix};
+
+use super::test_matrix::{create_test_file_node, create_test_relationship};
+
+/// Matrix whose files are the given names and whose edges are `(dependent, dependency)`
+fn matrix_with(files: &[&str], edges: &[(&str, &str)]) -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    for file in files {
+        matrix.add_file(create_test_file_node(file, "rust"));
+    }
+    for (from, to) in edges {
+        matrix.add_relationship(create_test_relationship(from, to));
+    }
+    matrix
+}
+
+fn paths(names: &[&str]) -> Vec<PathBuf> {
+    names.iter().map(PathBuf::from).collect()
+}
+
+#[test]
+fn test_chain_layers_centrality_and_articulation() {
+    let mut matrix = matrix_with(
+        &["a.rs", "b.rs", "c.rs"],
+        &[("a.rs", "b.rs"), ("b.rs", "c.rs")],
+    );
+    let analysis = GraphAnalysis::analyze(&mut matrix);
+
+    assert_eq!(
+        analysis.layers,
+        vec![paths(&["c.rs"]), paths(&["b.rs"]), paths(&["a.rs"])]
+    );
+    assert!(analysis.cycles.is_empty());
+    assert_eq!(analysis.betweenness[0].0, PathBuf::from("b.rs"));
+    assert!(analysis.betweenness[0].1 > 0.0);
+    assert_eq!(analysis.betweenness[1].1, 0.0);
+    assert_eq!(analysis.pagerank[0].0, PathBuf::from("c.rs"));
+    assert_eq!(analysis.articulation_points, paths(&["b.rs"]));
+}
+
+#[test]
+fn test_cycles_share_a_layer() {
+    let mut matrix = matrix_with(
+        &["a.rs", "b.rs", "c.rs"],
+        &[("a.rs", "b.rs"), ("b.rs", "a.rs"), ("c.rs", "a.rs")],
+    );
+    let analysis = GraphAnalysis::analyze(&mut matrix);
+
+    assert_eq!(analysis.cycles, vec![paths(&["a.rs", "b.rs"])]);
+    assert_eq!(
+        analysis.layers,
+        vec![paths(&["a.rs", "b.rs"]), paths(&["c.rs"])]
+    );
+}
+
+#[test]
+fn test_hub_is_single_point_of_failure() {
+    let mut matrix = matrix_with(
+        &["hub.rs", "x.rs", "y.rs", "z.rs", "w.rs"],
+        &[
+            ("x.rs", "hub.rs"),
+            ("y.rs", "hub.rs"),
+            ("z.rs", "hub.rs"),
+            // x and y also know each other, so only the hub splits the graph
+            ("x.rs", "y.rs"),
+            ("w.rs", "z.rs"),
+        ],
+    );
+    let analysis = GraphAnalysis::analyze(&mut matrix);
+
+    assert_eq!(analysis.articulation_points, paths(&["hub.rs", "z.rs"]));
+    assert_eq!(analysis.pagerank[0].0, PathBuf::from("hub.rs"));
+}
+
+#[test]
+fn test_pagerank_is_a_distribution() {
+    let mut matrix = matrix_with(
+        &["a.rs", "b.rs", "c.rs", "d.rs"],
+        &[("a.rs", "b.rs"), ("a.rs", "c.rs"), ("b.rs", "c.rs")],
+    );
+    let total: f64 = pagerank(matrix.graph()).values().sum();
+    assert!((total - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_empty_matrix() {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    let analysis = GraphAnalysis::analyze(&mut matrix);
+
+    assert!(analysis.layers.is_empty());
+    assert!(analysis.betweenness.is_empty());
+    assert!(analysis.pagerank.is_empty());
+    assert!(analysis.articulation_points.is_empty());
+}
+
+#[test]
+fn test_label_propagation_finds_clusters() {
+    let mut matrix = matrix_with(
+        &[
+            "parser/lexer.rs",
+            "parser/ast.rs",
+            "parser/mod.rs",
+            "render/html.rs",
+            "render/mod.rs",
+            "render/theme.rs",
+            "lonely.rs",
+        ],
+        &[
+            ("parser/mod.rs", "parser/lexer.rs"),
+            ("parser/mod.rs", "parser/ast.rs"),
+            ("parser/lexer.rs", "parser/ast.rs"),
+            ("render/mod.rs", "render/html.rs"),
+            ("render/mod.rs", "render/theme.rs"),
+            ("render/html.rs", "render/theme.rs"),
+        ],
+    );
+    assert_eq!(label_propagation(matrix.graph()).len(), 2);
+
+    let components = detect_components(&mut matrix);
+    let names: Vec<&str> = components.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["parser", "render"]);
+    assert_eq!(components[0].files.len(), 3);
+    assert_eq!(components[0].source, ComponentSource::Detected);
+}
+
+#[test]
+fn test_components_follow_package_manifests() {
+    let mut matrix = matrix_with(
+        &[
+            "Cargo.toml",
+            "src/main.rs",
+            "crates/core/Cargo.toml",
+            "crates/core/src/lib.rs",
+        ],
+        &[("src/main.rs", "crates/core/src/lib.rs")],
+    );
+
+    let components = detect_components(&mut matrix);
+    assert_eq!(components.len(), 2);
+    assert_eq!(components[0].name, "crates/core");
+    assert_eq!(
+        components[0].files,
+        paths(&["crates/core/Cargo.toml", "crates/core/src/lib.rs"])
+    );
+    assert_eq!(components[1].name, "root");
+    assert_eq!(components[1].source, ComponentSource::Package);
+}
+
+#[test]
+fn test_finalize_stores_components() {
+    let mut matrix = matrix_with(&["a/x.rs", "a/y.rs"], &[("a/x.rs", "a/y.rs")]);
+    matrix.finalize();
+
+    assert_eq!(matrix.project_info.components.len(), 1);
+    assert_eq!(matrix.project_info.components[0].name, "a");
+}
@@ -0,0 +1,69 @@
+use chrono::{Duration, Utc};
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use csd::core::history::{snapshots_to_csv, MetricsHistory, MetricsSnapshot};
+use csd::core::matrix::ProjectMatrix;
+
+use super::test_matrix::{create_test_file_node, create_test_relationship};
+
+fn snapshot(files: usize, minutes_ago: i64) -> MetricsSnapshot {
+    MetricsSnapshot {
+        timestamp: Utc::now() - Duration::minutes(minutes_ago),
+        csd_version: "0.1.0".to_string(),
+        total_files: files,
+        total_tokens: files as u64 * 100,
+        total_relationships: files / 2,
+        external_dependencies: 3,
+        max_coupling: 1,
+        average_complexity: Some(2.5),
+    }
+}
+
+#[test]
+fn test_snapshot_from_matrix() {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    matrix.add_file(create_test_file_node("src/main.rs", "rust"));
+    matrix.add_file(create_test_file_node("src/lib.rs", "rust"));
+    matrix.add_relationship(create_test_relationship("src/main.rs", "src/lib.rs"));
+
+    let snapshot = MetricsSnapshot::from_matrix(&mut matrix);
+
+    assert_eq!(snapshot.total_files, 2);
+    assert_eq!(snapshot.total_tokens, 512);
+    assert_eq!(snapshot.total_relationships, 1);
+    assert_eq!(snapshot.max_coupling, 1);
+    assert_eq!(snapshot.average_complexity, None); // No elements with scores
+}
+
+#[tokio::test]
+async fn test_history_append_and_load_in_order() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let history = MetricsHistory::new(temp_dir.path());
+
+    assert!(history.load().await.unwrap().is_empty());
+
+    history.append(&snapshot(10, 5)).await.unwrap();
+    history.append(&snapshot(5, 10)).await.unwrap();
+
+    // A torn line is skipped rather than failing the whole log
+    let mut content = tokio::fs::read_to_string(history.path()).await.unwrap();
+    content.push_str("{\"timestamp\":");
+    tokio::fs::write(history.path(), content).await.unwrap();
+
+    let loaded = history.load().await.unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded[0].total_files, 5); // Oldest first
+    assert_eq!(loaded[1].total_files, 10);
+    assert!(history.path().ends_with("history/metrics.jsonl"));
+}
+
+#[test]
+fn test_snapshots_to_csv() {
+    let csv = snapshots_to_csv(&[snapshot(4, 1)]);
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("timestamp,csd_version,total_files"));
+    assert!(lines[1].contains(",0.1.0,4,400,2,3,1,2.50"));
+}
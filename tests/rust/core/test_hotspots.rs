@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+
+use csd::core::hotspots::HotspotReport;
+use csd::core::matrix::{CodeElement, ElementType, ProjectMatrix};
+use csd::utils::config::QualityConfig;
+
+use super::test_matrix::create_test_file_node;
+
+fn element(
+    element_type: ElementType,
+    name: &str,
+    lines: (u32, u32),
+    complexity: Option<u32>,
+) -> CodeElement {
+    CodeElement {
+        element_type,
+        name: name.to_string(),
+        qualified_name: None,
+        parent: None,
+        signature: None,
+        line_start: lines.0,
+        line_end: lines.1,
+        summary: None,
+        complexity_score: complexity,
+        calls: vec![],
+        metadata: serde_json::Value::Null,
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    }
+}
+
+fn thresholds() -> QualityConfig {
+    QualityConfig {
+        max_file_tokens: 300,
+        max_function_lines: 50,
+        max_function_complexity: 10,
+        top: 10,
+    }
+}
+
+fn sample_matrix() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+
+    let mut big = create_test_file_node("src/big.rs", "rust");
+    big.token_info.total_tokens = 900;
+    big.elements
+        .push(element(ElementType::Function, "long", (1, 120), Some(3)));
+    big.elements.push(element(
+        ElementType::Method,
+        "tangled",
+        (130, 150),
+        Some(30),
+    ));
+    big.elements
+        .push(element(ElementType::Function, "fine", (160, 170), Some(2)));
+    // Only functions and methods are measured
+    big.elements
+        .push(element(ElementType::Struct, "Huge", (200, 900), None));
+    matrix.add_file(big);
+
+    let mut bigger = create_test_file_node("src/bigger.rs", "rust");
+    bigger.token_info.total_tokens = 1500;
+    bigger
+        .elements
+        .push(element(ElementType::Function, "edge", (1, 51), None));
+    matrix.add_file(bigger);
+
+    matrix.add_file(create_test_file_node("src/small.rs", "rust"));
+    matrix
+}
+
+#[test]
+fn test_hotspots_flag_oversized_files_and_functions() {
+    let report = HotspotReport::from_matrix(&sample_matrix(), &thresholds());
+
+    assert_eq!(report.files_over_limit, 2);
+    let files: Vec<_> = report.files.iter().map(|f| f.path.clone()).collect();
+    assert_eq!(
+        files,
+        vec![PathBuf::from("src/bigger.rs"), PathBuf::from("src/big.rs")]
+    );
+
+    // Ranked by how far past its worst limit each function is
+    assert_eq!(report.functions_over_limit, 3);
+    let names: Vec<_> = report.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["tangled", "long", "edge"]);
+    assert_eq!(report.functions[1].lines, 120);
+    assert_eq!(report.functions[0].complexity, Some(30));
+}
+
+#[test]
+fn test_hotspots_top_limits_lists_not_counts() {
+    let mut limits = thresholds();
+    limits.top = 1;
+    let report = HotspotReport::from_matrix(&sample_matrix(), &limits);
+
+    assert_eq!(report.files_over_limit, 2);
+    assert_eq!(report.functions_over_limit, 3);
+    assert_eq!(report.files.len(), 1);
+    assert_eq!(report.functions.len(), 1);
+}
+
+#[test]
+fn test_hotspots_skip_vendored_files() {
+    let mut matrix = sample_matrix();
+    let mut library = create_test_file_node("vendor/sqlite/sqlite3.c", "c");
+    library.token_info.total_tokens = 90_000;
+    library.elements.push(element(
+        ElementType::Function,
+        "sqlite3_step",
+        (1, 800),
+        Some(120),
+    ));
+    matrix.add_file(library);
+
+    let report = HotspotReport::from_matrix(&matrix, &thresholds());
+    assert_eq!(report.files_over_limit, 2);
+    assert_eq!(report.functions_over_limit, 3);
+    assert!(report.files.iter().all(|f| !f.path.starts_with("vendor")));
+}
+
+#[test]
+fn test_hotspots_empty_under_defaults() {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    matrix.add_file(create_test_file_node("src/lib.rs", "rust"));
+
+    let report = HotspotReport::from_matrix(&matrix, &QualityConfig::default());
+    assert!(report.is_empty());
+    assert!(report.files.is_empty() && report.functions.is_empty());
+}
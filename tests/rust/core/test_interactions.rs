@@ -0,0 +1,164 @@
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use csd::core::facade::Csd;
+use csd::core::interactions::{
+    merge_interactions, parse_access_log, parse_service_graph, InteractionDirection,
+    InteractionSource,
+};
+use csd::core::matrix::ProjectMatrix;
+use csd::utils::config::Config;
+
+const PROMETHEUS: &str = r#"
+# HELP traces_service_graph_request_total Total count of requests between two nodes
+# TYPE traces_service_graph_request_total counter
+traces_service_graph_request_total{client="shop",server="billing",connection_type=""} 120
+traces_service_graph_request_total{client="web",server="shop"} 300
+traces_service_graph_request_total{client="web",server="search"} 80
+traces_service_graph_request_failed_total{client="shop",server="billing"} 4
+traces_service_graph_request_server_seconds_count{client="shop",server="billing"} 120
+"#;
+
+const ACCESS_LOG: &str = r#"{"peer.service": "billing", "http.request.method": "GET", "url.path": "/invoices/1042", "http.response.status_code": 200, "duration_ms": 12.0}
+{"peer.service": "billing", "http.request.method": "GET", "url.path": "/invoices/77?expand=lines", "http.response.status_code": 503, "duration_ms": 30.0}
+{"peer.service": "billing", "http.request.method": "POST", "url.path": "/invoices", "http.response.status_code": "201"}
+{"upstream_cluster": "postgres", "duration_ms": 2}
+{"message": "started"}
+not json
+"#;
+
+#[test]
+fn test_service_graph_from_prometheus_metrics() {
+    assert_eq!(
+        InteractionSource::detect(PROMETHEUS),
+        InteractionSource::ServiceGraph
+    );
+    let (interactions, skipped) = parse_service_graph(PROMETHEUS, "shop").unwrap();
+    assert_eq!(skipped, 1);
+    let summary: Vec<(&str, InteractionDirection, u64, u64)> = interactions
+        .iter()
+        .map(|i| (i.service.as_str(), i.direction, i.calls, i.errors))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("billing", InteractionDirection::Outgoing, 120, 4),
+            ("web", InteractionDirection::Incoming, 300, 0),
+        ]
+    );
+    assert!(interactions
+        .iter()
+        .all(|i| i.source == InteractionSource::ServiceGraph));
+}
+
+#[test]
+fn test_service_graph_from_jaeger_dependencies() {
+    let json = r#"{"data": [
+        {"parent": "shop", "child": "redis", "callCount": 9},
+        {"parent": "shop", "child": "shop", "callCount": 2}
+    ]}"#;
+    assert_eq!(
+        InteractionSource::detect(json),
+        InteractionSource::ServiceGraph
+    );
+    let (interactions, skipped) = parse_service_graph(json, "shop").unwrap();
+    assert_eq!(skipped, 1);
+    assert_eq!(interactions.len(), 1);
+    assert_eq!(interactions[0].service, "redis");
+    assert_eq!(interactions[0].calls, 9);
+
+    assert!(parse_service_graph("# nothing here\n", "shop").is_err());
+}
+
+#[test]
+fn test_access_log_tallies_calls_per_service() {
+    assert_eq!(
+        InteractionSource::detect(ACCESS_LOG),
+        InteractionSource::AccessLog
+    );
+    let (interactions, skipped) = parse_access_log(ACCESS_LOG).unwrap();
+    assert_eq!(skipped, 2);
+    assert_eq!(interactions.len(), 2);
+
+    let billing = &interactions[0];
+    assert_eq!(billing.service, "billing");
+    assert_eq!(billing.direction, InteractionDirection::Outgoing);
+    assert_eq!((billing.calls, billing.errors), (3, 1));
+    assert_eq!(billing.avg_latency_ms, Some(21.0));
+    // Ids and query strings are folded into one operation
+    assert_eq!(
+        billing.operations,
+        vec!["GET /invoices/{id}", "POST /invoices"]
+    );
+
+    let postgres = &interactions[1];
+    assert_eq!(postgres.service, "postgres");
+    assert!(postgres.operations.is_empty());
+
+    assert!(parse_access_log("{\"message\": \"hi\"}\n").is_err());
+}
+
+#[test]
+fn test_merge_replaces_interactions_from_the_same_source() {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/project"));
+    let (graph, _) = parse_service_graph(PROMETHEUS, "shop").unwrap();
+    let (log, _) = parse_access_log(ACCESS_LOG).unwrap();
+    merge_interactions(&mut matrix, InteractionSource::ServiceGraph, graph);
+    merge_interactions(&mut matrix, InteractionSource::AccessLog, log);
+
+    let recorded = |matrix: &ProjectMatrix| -> Vec<(String, InteractionSource)> {
+        matrix
+            .project_info
+            .external_interactions
+            .iter()
+            .map(|i| (i.service.clone(), i.source))
+            .collect()
+    };
+    // Outgoing before incoming, then by service; busiest first
+    assert_eq!(
+        recorded(&matrix),
+        vec![
+            ("billing".to_string(), InteractionSource::ServiceGraph),
+            ("billing".to_string(), InteractionSource::AccessLog),
+            ("postgres".to_string(), InteractionSource::AccessLog),
+            ("web".to_string(), InteractionSource::ServiceGraph),
+        ]
+    );
+
+    let (log, _) = parse_access_log("{\"peer.service\": \"kafka\"}\n").unwrap();
+    merge_interactions(&mut matrix, InteractionSource::AccessLog, log);
+    assert_eq!(recorded(&matrix).len(), 3);
+}
+
+#[tokio::test]
+async fn test_import_interactions_saves_matrix() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path().join("shop");
+    std::fs::create_dir(&root).unwrap();
+    let matrix_path = root.join("matrix.json");
+    ProjectMatrix::new(root.clone())
+        .save(&matrix_path)
+        .await
+        .unwrap();
+    let metrics = root.join("service-graph.prom");
+    std::fs::write(&metrics, PROMETHEUS).unwrap();
+
+    // The project directory's name identifies it in the graph
+    let csd = Csd::new(Config::default())
+        .with_root(&root)
+        .with_matrix_path(&matrix_path);
+    let import = csd.import_interactions(&metrics, None, None).await.unwrap();
+    assert_eq!(import.downstream, vec!["billing"]);
+    assert_eq!(import.upstream, vec!["web"]);
+    assert_eq!(import.calls, 420);
+
+    let matrix = ProjectMatrix::load(&matrix_path).await.unwrap();
+    assert_eq!(matrix.project_info.external_interactions.len(), 2);
+
+    let import = csd
+        .import_interactions(&metrics, None, Some("search"))
+        .await
+        .unwrap();
+    assert_eq!(import.upstream, vec!["web"]);
+    assert!(import.downstream.is_empty());
+}
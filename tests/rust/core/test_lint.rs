@@ -0,0 +1,158 @@
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use csd::core::lint::{fix_matrix, lint_matrix, normalize_path, LintKind};
+use csd::core::matrix::ProjectMatrix;
+
+use super::test_matrix::{create_test_file_node, create_test_relationship};
+
+fn project() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    for file in ["src/main.rs", "src/lib.rs"] {
+        matrix.add_file(create_test_file_node(file, "rust"));
+    }
+    matrix.add_relationship(create_test_relationship("src/main.rs", "src/lib.rs"));
+    matrix
+}
+
+// What a plugin with sloppy output leaves behind
+fn broken_project() -> ProjectMatrix {
+    let mut matrix = project();
+    // The same file under a second spelling, which the totals don't count
+    matrix.files.insert(
+        PathBuf::from("./src/lib.rs"),
+        create_test_file_node("./src/lib.rs", "rust"),
+    );
+    let main = matrix.files.get_mut(Path::new("src/main.rs")).unwrap();
+    main.hash.clear();
+    main.token_info.total_tokens = 0;
+    matrix.metadata.total_tokens -= 256;
+
+    matrix.add_relationship(create_test_relationship("src/main.rs", "src/gone.rs"));
+    let mut strong = create_test_relationship("./src/main.rs", "src/lib.rs");
+    strong.strength = 1.5;
+    matrix.add_relationship(strong);
+    matrix
+}
+
+fn kinds(matrix: &ProjectMatrix) -> Vec<(LintKind, bool)> {
+    lint_matrix(matrix)
+        .issues
+        .iter()
+        .map(|i| (i.kind, i.fixable))
+        .collect()
+}
+
+#[test]
+fn test_consistent_matrix_is_clean() {
+    let report = lint_matrix(&project());
+    assert!(report.is_clean(), "{:?}", report.issues);
+}
+
+#[test]
+fn test_lint_reports_each_problem() {
+    let report = lint_matrix(&broken_project());
+    assert_eq!(
+        kinds(&broken_project()),
+        vec![
+            (LintKind::DanglingRelationship, true),
+            (LintKind::DuplicatePath, true),
+            // /test/src/main.rs doesn't exist to hash
+            (LintKind::MissingHash, false),
+            (LintKind::ZeroTokens, false),
+            // Strength 1.5, and the three totals
+            (LintKind::Schema, true),
+            (LintKind::Schema, true),
+            (LintKind::Schema, true),
+            (LintKind::Schema, true),
+        ]
+    );
+    assert_eq!(report.fixable(), 6);
+    assert_eq!(
+        report.issues[1].path.as_deref(),
+        Some(Path::new("./src/lib.rs"))
+    );
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["issues"][0]["kind"], "dangling_relationship");
+}
+
+#[test]
+fn test_fix_repairs_what_it_can() {
+    let mut matrix = broken_project();
+    let report = fix_matrix(&mut matrix);
+
+    assert_eq!(report.fixed, 6);
+    assert_eq!(
+        kinds(&matrix),
+        vec![
+            (LintKind::MissingHash, false),
+            (LintKind::ZeroTokens, false)
+        ]
+    );
+    assert_eq!(report.issues.len(), 2);
+
+    let mut files: Vec<&PathBuf> = matrix.files.keys().collect();
+    files.sort();
+    assert_eq!(
+        files,
+        vec![Path::new("src/lib.rs"), Path::new("src/main.rs")]
+    );
+    // The dangling edge is dropped; ./src/main.rs is another spelling, not a dangling one
+    assert_eq!(matrix.relationships.len(), 2);
+    assert!(matrix.relationships.iter().all(|r| r.strength <= 1.0));
+    assert_eq!(matrix.metadata.total_files, 2);
+    assert_eq!(matrix.metadata.total_size_bytes, 2048);
+
+    // Edges to a merged duplicate move to the file it was merged into
+    let mut matrix = broken_project();
+    matrix.add_relationship(create_test_relationship("src/main.rs", "./src/lib.rs"));
+    fix_matrix(&mut matrix);
+    assert!(matrix
+        .relationships
+        .iter()
+        .all(|r| r.to_file == Path::new("src/lib.rs")));
+    assert_eq!(matrix.relationships.len(), 2);
+}
+
+#[test]
+fn test_fix_hashes_files_on_disk() {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+
+    let mut matrix = ProjectMatrix::new(dir.path().to_path_buf());
+    let mut main = create_test_file_node("src/main.rs", "rust");
+    main.hash.clear();
+    matrix.add_file(main);
+    assert_eq!(kinds(&matrix), vec![(LintKind::MissingHash, true)]);
+
+    let report = fix_matrix(&mut matrix);
+    assert!(report.is_clean());
+    assert_eq!(
+        matrix.files[Path::new("src/main.rs")].hash,
+        format!("{:x}", Sha256::digest(b"fn main() {}\n"))
+    );
+}
+
+#[test]
+fn test_normalize_path() {
+    assert_eq!(
+        normalize_path(Path::new("./src/lib.rs")),
+        Path::new("src/lib.rs")
+    );
+    assert_eq!(
+        normalize_path(Path::new("src/core/../lib.rs")),
+        Path::new("src/lib.rs")
+    );
+    assert_eq!(
+        normalize_path(Path::new("src\\lib.rs")),
+        Path::new("src/lib.rs")
+    );
+    assert_eq!(
+        normalize_path(Path::new("../lib.rs")),
+        Path::new("../lib.rs")
+    );
+    assert_eq!(normalize_path(Path::new("/abs/./x")), Path::new("/abs/x"));
+}
@@ -1,11 +1,13 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tempfile::TempDir;
 
 // Import the modules we're testing
+use csd::core::categories::FileCategory;
 use csd::core::matrix::{
-    estimate_code_tokens, estimate_tokens, CodeElement, DependencyType, ElementType,
-    EntrypointInfo, ExternalDependency, FileNode, Import, ImportType, ProjectMatrix, ProjectType,
-    Relationship, RelationshipType, TokenInfo,
+    element_id, estimate_code_tokens, estimate_tokens, CodeElement, DependencyType, ElementRef,
+    ElementType, EntrypointInfo, Export, ExternalDependency, FileNode, Import, ImportType,
+    PluginUsage, ProjectMatrix, ProjectType, Relationship, RelationshipFilter, RelationshipType,
+    SubsetOptions, TokenInfo,
 };
 
 // Helper function to create a test FileNode with token information
@@ -15,8 +17,8 @@ pub fn create_test_file_node(path: &str, plugin: &str) -> FileNode {
         relative_path: PathBuf::from(path),
         hash: "test_hash_123".to_string(),
         size_bytes: 1024,
-        plugin: plugin.to_string(),
-        language: Some(plugin.to_string()),
+        plugin: plugin.into(),
+        language: Some(plugin.into()),
         is_text: true,
         elements: vec![],
         imports: vec![],
@@ -28,9 +30,45 @@ pub fn create_test_file_node(path: &str, plugin: &str) -> FileNode {
             documentation_tokens: 40,
             comment_tokens: 16,
         },
+        metadata: serde_json::Value::Null,
+        truncated: false,
+        category: None,
+        coverage: None,
     }
 }
 
+// Helper function to create a matrix of test FileNodes holding `elements`; `set` applies
+// each file's remaining field, e.g. its summary or hash
+pub fn create_test_matrix(
+    plugin: &str,
+    files: &[(&str, &str, Vec<CodeElement>)],
+    set: impl Fn(&mut FileNode, &str),
+) -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    for (path, value, elements) in files {
+        let mut file = create_test_file_node(path, plugin);
+        set(&mut file, value);
+        file.elements = elements.clone();
+        matrix.add_file(file);
+    }
+    matrix
+}
+
+// Files on disk, and a matrix holding them as a scan would
+pub fn create_test_project(files: &[(&str, &str)]) -> (TempDir, ProjectMatrix) {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let mut matrix = ProjectMatrix::new(temp_dir.path().to_path_buf());
+    for (relative, content) in files {
+        let path = temp_dir.path().join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        let mut node = create_test_file_node(relative, "unknown");
+        node.path = path;
+        matrix.add_file(node);
+    }
+    (temp_dir, matrix)
+}
+
 // Helper function to create a test Relationship
 pub fn create_test_relationship(from: &str, to: &str) -> Relationship {
     Relationship {
@@ -40,6 +78,11 @@ pub fn create_test_relationship(from: &str, to: &str) -> Relationship {
         details: "test import".to_string(),
         line_number: Some(10),
         strength: 0.8,
+        source_plugin: None,
+        detected_at: None,
+        from_element: None,
+        to_element: None,
+        runtime_calls: None,
     }
 }
 
@@ -81,7 +124,7 @@ mod matrix_creation_tests {
         assert_eq!(matrix.metadata.total_files, 1);
         assert_eq!(matrix.metadata.total_size_bytes, 1024);
         assert_eq!(matrix.metadata.total_tokens, 256);
-        assert!(matrix.metadata.plugins_used.contains(&"rust".to_string()));
+        assert_eq!(matrix.metadata.plugin_names(), vec!["rust"]);
         assert!(matrix.files.contains_key(&PathBuf::from("src/main.rs")));
 
         // Check token tracking
@@ -103,8 +146,39 @@ mod matrix_creation_tests {
         assert_eq!(matrix.metadata.total_size_bytes, 2048);
         assert_eq!(matrix.metadata.total_tokens, 512);
         assert_eq!(matrix.metadata.plugins_used.len(), 2);
-        assert!(matrix.metadata.plugins_used.contains(&"rust".to_string()));
-        assert!(matrix.metadata.plugins_used.contains(&"python".to_string()));
+        // Sorted by name, whatever order files arrive in
+        assert_eq!(matrix.metadata.plugin_names(), vec!["python", "rust"]);
+    }
+
+    #[test]
+    fn test_plugins_used_records_versions() {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        matrix.add_file(create_test_file_node("src/main.rs", "rust"));
+        matrix
+            .metadata
+            .pin_plugin("rust", "1.2.0", "local:plugins/rust.py".to_string());
+        // Plugins without files in the matrix aren't added
+        matrix
+            .metadata
+            .pin_plugin("python", "0.9.0", "builtin:python/python".to_string());
+
+        let json = serde_json::to_value(&matrix.metadata.plugins_used).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([{
+                "name": "rust",
+                "version": "1.2.0",
+                "source": "local:plugins/rust.py"
+            }])
+        );
+
+        // Older matrices list bare names
+        let legacy: Vec<PluginUsage> =
+            serde_json::from_value(serde_json::json!(["rust", {"name": "python"}])).unwrap();
+        assert_eq!(
+            legacy,
+            vec![PluginUsage::named("rust"), PluginUsage::named("python")]
+        );
     }
 
     #[test]
@@ -134,7 +208,7 @@ mod matrix_creation_tests {
             .project_info
             .entrypoints
             .iter()
-            .find(|e| e.file_path == PathBuf::from("src/main.rs"))
+            .find(|e| e.file_path == Path::new("src/main.rs"))
             .expect("main.rs should be detected as entrypoint");
         assert_eq!(main_entry.entrypoint_type, "cli");
         assert_eq!(main_entry.confidence, 1.0);
@@ -144,7 +218,7 @@ mod matrix_creation_tests {
             .project_info
             .entrypoints
             .iter()
-            .find(|e| e.file_path == PathBuf::from("src/lib.rs"))
+            .find(|e| e.file_path == Path::new("src/lib.rs"))
             .expect("lib.rs should be detected as entrypoint");
         assert_eq!(lib_entry.entrypoint_type, "lib");
         assert_eq!(lib_entry.confidence, 1.0);
@@ -177,9 +251,11 @@ mod matrix_creation_tests {
         let dependency = ExternalDependency {
             name: "serde".to_string(),
             version: Some("1.0.0".to_string()),
-            ecosystem: "cargo".to_string(),
+            ecosystem: "cargo".into(),
             dependency_type: DependencyType::Runtime,
             source_file: PathBuf::from("Cargo.toml"),
+            source_plugin: None,
+            detected_at: None,
         };
 
         matrix.add_external_dependency(dependency.clone());
@@ -277,6 +353,30 @@ mod token_management_tests {
             .included_files
             .contains(&PathBuf::from("file2.rs")));
     }
+
+    #[test]
+    fn test_token_budget_skips_vendored_files() {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        let mut own = create_test_file_node("src/lib.rs", "rust");
+        own.token_info.total_tokens = 300;
+        matrix.add_file(own);
+        // Vendored by the built-in rules, and by a stored category
+        let mut library = create_test_file_node("vendor/zlib/zlib.c", "c");
+        library.token_info.total_tokens = 100;
+        matrix.add_file(library);
+        let mut copied = create_test_file_node("extern/json.hpp", "cpp");
+        copied.token_info.total_tokens = 100;
+        copied.category = Some(csd::core::categories::FileCategory::Vendored);
+        matrix.add_file(copied);
+
+        let budget_info = matrix.get_token_budget_info(1000);
+        assert_eq!(budget_info.used_tokens, 300);
+        assert_eq!(
+            budget_info.included_files,
+            vec![PathBuf::from("src/lib.rs")]
+        );
+        assert_eq!(budget_info.excluded_files.len(), 2);
+    }
 }
 
 #[cfg(test)]
@@ -518,6 +618,45 @@ mod matrix_persistence_tests {
             PathBuf::from("src/lib.rs")
         );
     }
+
+    #[tokio::test]
+    async fn test_scan_stats_round_trip_and_default() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let matrix_path = temp_dir.path().join("test_matrix.json");
+
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test/project"));
+        matrix.metadata.scan_stats.duration_ms = 1234;
+        matrix.metadata.scan_stats.failed_files = 2;
+        matrix
+            .metadata
+            .scan_stats
+            .plugins
+            .entry("rust".to_string())
+            .or_default()
+            .files = 5;
+        matrix
+            .save(&matrix_path)
+            .await
+            .expect("Failed to save matrix");
+
+        let loaded = ProjectMatrix::load(&matrix_path)
+            .await
+            .expect("Failed to load matrix");
+        assert_eq!(loaded.metadata.scan_stats, matrix.metadata.scan_stats);
+
+        // Matrices written before scan stats existed still load
+        let mut json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&matrix_path).unwrap()).unwrap();
+        json["metadata"]
+            .as_object_mut()
+            .unwrap()
+            .remove("scan_stats");
+        std::fs::write(&matrix_path, json.to_string()).unwrap();
+        let loaded = ProjectMatrix::load(&matrix_path)
+            .await
+            .expect("Failed to load legacy matrix");
+        assert_eq!(loaded.metadata.scan_stats, Default::default());
+    }
 }
 
 #[cfg(test)]
@@ -546,9 +685,11 @@ mod matrix_summary_tests {
         let dependency = ExternalDependency {
             name: "serde".to_string(),
             version: Some("1.0.0".to_string()),
-            ecosystem: "cargo".to_string(),
+            ecosystem: "cargo".into(),
             dependency_type: DependencyType::Runtime,
             source_file: PathBuf::from("Cargo.toml"),
+            source_plugin: None,
+            detected_at: None,
         };
         matrix.add_external_dependency(dependency);
 
@@ -569,6 +710,8 @@ mod data_structure_tests {
         let element = CodeElement {
             element_type: ElementType::Function,
             name: "test_function".to_string(),
+            qualified_name: None,
+            parent: None,
             signature: Some("fn test_function() -> bool".to_string()),
             line_start: 10,
             line_end: 20,
@@ -580,6 +723,8 @@ mod data_structure_tests {
                 "visibility": "public"
             }),
             tokens: 150,
+            element_id: String::new(),
+            coverage: None,
         };
 
         assert_eq!(element.name, "test_function");
@@ -594,7 +739,7 @@ mod data_structure_tests {
     #[test]
     fn test_import_creation() {
         let import = Import {
-            module: "std::collections".to_string(),
+            module: "std::collections".into(),
             items: vec!["HashMap".to_string(), "HashSet".to_string()],
             alias: Some("collections".to_string()),
             line_number: 5,
@@ -619,6 +764,11 @@ mod data_structure_tests {
             details: "imports lib module".to_string(),
             line_number: Some(15),
             strength: 0.8,
+            source_plugin: None,
+            detected_at: None,
+            from_element: None,
+            to_element: None,
+            runtime_calls: None,
         };
 
         assert_eq!(relationship.from_file, PathBuf::from("src/main.rs"));
@@ -637,9 +787,11 @@ mod data_structure_tests {
         let dependency = ExternalDependency {
             name: "tokio".to_string(),
             version: Some("1.0.0".to_string()),
-            ecosystem: "cargo".to_string(),
+            ecosystem: "cargo".into(),
             dependency_type: DependencyType::Runtime,
             source_file: PathBuf::from("Cargo.toml"),
+            source_plugin: None,
+            detected_at: None,
         };
 
         assert_eq!(dependency.name, "tokio");
@@ -659,12 +811,12 @@ mod data_structure_tests {
             relative_path: PathBuf::from("src/main.rs"),
             hash: "abc123def456".to_string(),
             size_bytes: 2048,
-            plugin: "rust".to_string(),
-            language: Some("rust".to_string()),
+            plugin: "rust".into(),
+            language: Some("rust".into()),
             is_text: true,
             elements: vec![],
             imports: vec![],
-            exports: vec!["main".to_string()],
+            exports: vec![Export::named("main")],
             file_summary: Some("Main application file".to_string()),
             token_info: TokenInfo {
                 total_tokens: 512,
@@ -672,6 +824,10 @@ mod data_structure_tests {
                 documentation_tokens: 80,
                 comment_tokens: 32,
             },
+            metadata: serde_json::Value::Null,
+            truncated: false,
+            category: None,
+            coverage: None,
         };
 
         assert_eq!(file_node.path, PathBuf::from("/project/src/main.rs"));
@@ -679,9 +835,9 @@ mod data_structure_tests {
         assert_eq!(file_node.hash, "abc123def456");
         assert_eq!(file_node.size_bytes, 2048);
         assert_eq!(file_node.plugin, "rust");
-        assert_eq!(file_node.language, Some("rust".to_string()));
+        assert_eq!(file_node.language, Some("rust".into()));
         assert!(file_node.is_text);
-        assert_eq!(file_node.exports, vec!["main".to_string()]);
+        assert_eq!(file_node.exports, vec![Export::named("main")]);
         assert_eq!(
             file_node.file_summary,
             Some("Main application file".to_string())
@@ -808,3 +964,943 @@ mod enum_variant_tests {
         assert!(matches!(variants[4], ProjectType::Unknown));
     }
 }
+
+#[cfg(test)]
+mod matrix_recovery_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_save_keeps_backup_and_leaves_no_temp_files() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let matrix_path = temp_dir.path().join("matrix.json");
+
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        matrix.add_file(create_test_file_node("src/main.rs", "rust"));
+        matrix.save(&matrix_path).await.expect("First save failed");
+        assert!(!temp_dir.path().join("matrix.json.bak").exists());
+
+        matrix.add_file(create_test_file_node("src/lib.rs", "rust"));
+        matrix.save(&matrix_path).await.expect("Second save failed");
+
+        let backup = ProjectMatrix::load(&temp_dir.path().join("matrix.json.bak"))
+            .await
+            .expect("Backup should load");
+        assert_eq!(backup.files.len(), 1);
+
+        let entries: Vec<_> = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert!(!entries.iter().any(|name| name.ends_with(".tmp")));
+    }
+
+    #[tokio::test]
+    async fn test_load_falls_back_to_backup_on_corruption() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let matrix_path = temp_dir.path().join("matrix.json");
+
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        matrix.add_file(create_test_file_node("src/main.rs", "rust"));
+        matrix.save(&matrix_path).await.unwrap();
+        matrix.save(&matrix_path).await.unwrap();
+
+        // Simulate an interrupted write
+        let json = std::fs::read_to_string(&matrix_path).unwrap();
+        std::fs::write(&matrix_path, &json[..json.len() / 2]).unwrap();
+
+        let recovered = ProjectMatrix::load(&matrix_path)
+            .await
+            .expect("Should recover from backup");
+        assert_eq!(recovered.files.len(), 1);
+
+        // Without a backup the error surfaces
+        std::fs::remove_file(temp_dir.path().join("matrix.json.bak")).unwrap();
+        assert!(ProjectMatrix::load(&matrix_path).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_save_over_corrupt_matrix_keeps_good_backup() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let matrix_path = temp_dir.path().join("matrix.json");
+
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        matrix.add_file(create_test_file_node("src/main.rs", "rust"));
+        matrix.save(&matrix_path).await.unwrap();
+        matrix.add_file(create_test_file_node("src/lib.rs", "rust"));
+        matrix.save(&matrix_path).await.unwrap();
+
+        let json = std::fs::read_to_string(&matrix_path).unwrap();
+        std::fs::write(&matrix_path, &json[..json.len() / 2]).unwrap();
+        matrix.add_file(create_test_file_node("src/util.rs", "rust"));
+        matrix.save(&matrix_path).await.unwrap();
+
+        // The truncated file never became the backup
+        let backup = ProjectMatrix::load(&temp_dir.path().join("matrix.json.bak"))
+            .await
+            .expect("Backup should load");
+        assert_eq!(backup.files.len(), 1);
+        let saved = ProjectMatrix::load(&matrix_path).await.unwrap();
+        assert_eq!(saved.files.len(), 3);
+    }
+}
+
+#[cfg(test)]
+mod provenance_tests {
+    use super::*;
+
+    fn relationship_from(plugin: Option<&str>, strength: f32) -> Relationship {
+        Relationship {
+            strength,
+            source_plugin: plugin.map(|p| p.into()),
+            detected_at: plugin.map(|_| chrono::Utc::now()),
+            ..create_test_relationship("src/main.rs", "src/lib.rs")
+        }
+    }
+
+    #[test]
+    fn test_dedup_prefers_strongest_then_known_source() {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        matrix.add_relationship(relationship_from(None, 0.5));
+        matrix.add_relationship(relationship_from(Some("rust"), 0.5));
+        matrix.add_relationship(relationship_from(Some("regex"), 0.3));
+        matrix.add_relationship(Relationship {
+            relationship_type: RelationshipType::Call,
+            ..relationship_from(Some("rust"), 0.1)
+        });
+
+        let removed = matrix.dedup_relationships();
+
+        assert_eq!(removed, 2);
+        assert_eq!(matrix.relationships.len(), 2);
+        let import = matrix
+            .relationships
+            .iter()
+            .find(|r| r.relationship_type == RelationshipType::Import)
+            .unwrap();
+        assert_eq!(import.source_plugin.as_deref(), Some("rust"));
+        assert!(import.detected_at.is_some());
+    }
+
+    #[test]
+    fn test_query_relationships_by_provenance_and_type() {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        matrix.add_relationship(relationship_from(Some("rust"), 0.8));
+        matrix.add_relationship(Relationship {
+            relationship_type: RelationshipType::Test,
+            ..relationship_from(Some("python"), 0.8)
+        });
+
+        let by_plugin = matrix.query_relationships(&RelationshipFilter {
+            source_plugin: Some("python".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(by_plugin.len(), 1);
+        assert_eq!(by_plugin[0].relationship_type, RelationshipType::Test);
+
+        let by_type = matrix.query_relationships(&RelationshipFilter {
+            relationship_types: vec!["import".parse().unwrap()],
+            from_file: Some(PathBuf::from("src/main.rs")),
+            ..Default::default()
+        });
+        assert_eq!(by_type.len(), 1);
+        assert!("bogus".parse::<RelationshipType>().is_err());
+
+        assert_eq!(
+            matrix
+                .query_relationships(&RelationshipFilter::default())
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_filter_by_strength_and_tests() {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        for path in ["src/main.rs", "src/lib.rs", "tests/parse.rs"] {
+            matrix.add_file(create_test_file_node(path, "rust"));
+        }
+        // Categorized by the scan, whatever its path says
+        let mut fixture = create_test_file_node("src/fixture.rs", "rust");
+        fixture.category = Some(FileCategory::Test);
+        matrix.add_file(fixture);
+
+        matrix.add_relationship(relationship_from(Some("rust"), 0.8));
+        matrix.add_relationship(relationship_from(Some("rust"), 0.2));
+        matrix.add_relationship(create_test_relationship("tests/parse.rs", "src/lib.rs"));
+        matrix.add_relationship(create_test_relationship("src/lib.rs", "src/fixture.rs"));
+        matrix.add_relationship(Relationship {
+            relationship_type: RelationshipType::Test,
+            ..relationship_from(Some("rust"), 0.9)
+        });
+
+        let strong = RelationshipFilter {
+            min_strength: Some(0.5),
+            ..Default::default()
+        };
+        assert_eq!(matrix.query_relationships(&strong).len(), 4);
+
+        let no_tests = RelationshipFilter {
+            exclude_tests: true,
+            ..Default::default()
+        };
+        let kept = matrix.query_relationships(&no_tests);
+        assert_eq!(kept.len(), 2);
+        assert!(kept.iter().all(|r| r.from_file == Path::new("src/main.rs")
+            && r.relationship_type == RelationshipType::Import));
+
+        let both = RelationshipFilter {
+            min_strength: Some(0.5),
+            exclude_tests: true,
+            ..Default::default()
+        };
+        assert_eq!(matrix.retain_relationships(&both), 4);
+        assert_eq!(matrix.relationships.len(), 1);
+        assert_eq!(matrix.relationships[0].strength, 0.8);
+        // The graph is rebuilt from what's left
+        assert_eq!(matrix.find_dependents(Path::new("src/lib.rs")).len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod element_graph_tests {
+    use super::*;
+
+    fn call(from: (&str, &str), to: (&str, &str)) -> Relationship {
+        Relationship {
+            relationship_type: RelationshipType::Call,
+            from_element: Some(from.1.to_string()),
+            to_element: Some(to.1.to_string()),
+            runtime_calls: None,
+            ..create_test_relationship(from.0, to.0)
+        }
+    }
+
+    fn element(file: &str, name: &str) -> ElementRef {
+        ElementRef {
+            file: PathBuf::from(file),
+            name: name.to_string(),
+        }
+    }
+
+    fn call_matrix() -> ProjectMatrix {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        matrix.add_relationship(call(("src/main.rs", "main"), ("src/cli.rs", "run")));
+        matrix.add_relationship(call(("src/cli.rs", "run"), ("src/lib.rs", "parse")));
+        matrix.add_relationship(call(("src/cli.rs", "run"), ("src/lib.rs", "render")));
+        matrix.add_relationship(call(("src/test.rs", "check"), ("src/lib.rs", "parse")));
+        // File-level edges stay out of the element subgraph
+        matrix.add_relationship(create_test_relationship("src/main.rs", "src/lib.rs"));
+        matrix
+    }
+
+    #[test]
+    fn test_element_graph_ignores_file_level_edges() {
+        let graph = call_matrix().element_graph();
+        assert_eq!(graph.element_count(), 5);
+        assert_eq!(graph.edge_count(), 4);
+    }
+
+    #[test]
+    fn test_callers_and_callees() {
+        let graph = call_matrix().element_graph();
+        let parse = element("src/lib.rs", "parse");
+
+        let callers = graph.callers(&parse);
+        assert_eq!(callers.len(), 2);
+        assert!(callers.contains(&&element("src/cli.rs", "run")));
+        assert_eq!(graph.callees(&element("src/cli.rs", "run")).len(), 2);
+        assert!(graph.callers(&element("src/nowhere.rs", "x")).is_empty());
+    }
+
+    #[test]
+    fn test_impact_and_call_path() {
+        let graph = call_matrix().element_graph();
+        let parse = element("src/lib.rs", "parse");
+
+        let impacted = graph.impacted_by(&parse);
+        assert_eq!(impacted.len(), 3);
+        assert!(impacted.contains(&&element("src/main.rs", "main")));
+
+        let path = graph
+            .call_path(&element("src/main.rs", "main"), &parse)
+            .expect("path exists");
+        let names: Vec<&str> = path.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["main", "run", "parse"]);
+        assert!(graph
+            .call_path(&parse, &element("src/main.rs", "main"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_dedup_keeps_distinct_element_edges() {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        matrix.add_relationship(call(("a.rs", "f"), ("b.rs", "g")));
+        matrix.add_relationship(call(("a.rs", "f"), ("b.rs", "h")));
+        matrix.add_relationship(call(("a.rs", "f"), ("b.rs", "g")));
+
+        assert_eq!(matrix.dedup_relationships(), 1);
+        assert_eq!(matrix.relationships.len(), 2);
+    }
+
+    #[test]
+    fn test_element_fields_are_optional_in_json() {
+        let json = serde_json::to_string(&create_test_relationship("a.rs", "b.rs")).unwrap();
+        assert!(!json.contains("from_element"));
+
+        let relationship: Relationship = serde_json::from_str(
+            &serde_json::to_string(&call(("a.rs", "f"), ("b.rs", "g"))).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(relationship.to_element.as_deref(), Some("g"));
+    }
+}
+
+#[cfg(test)]
+mod element_id_tests {
+    use super::*;
+
+    fn element(name: &str, signature: &str, line_start: u32) -> CodeElement {
+        CodeElement {
+            element_type: ElementType::Function,
+            name: name.to_string(),
+            qualified_name: None,
+            parent: None,
+            signature: Some(signature.to_string()),
+            line_start,
+            line_end: line_start + 5,
+            summary: None,
+            complexity_score: None,
+            calls: vec![],
+            metadata: serde_json::json!({}),
+            tokens: 10,
+            element_id: String::new(),
+            coverage: None,
+        }
+    }
+
+    fn matrix_with(elements: Vec<CodeElement>) -> ProjectMatrix {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        let mut file = create_test_file_node("src/lib.rs", "rust");
+        file.elements = elements;
+        matrix.add_file(file);
+        matrix
+    }
+
+    #[test]
+    fn test_element_id_is_deterministic() {
+        let id = element_id(Path::new("src/lib.rs"), "parse", Some("fn parse()"));
+        assert_eq!(id.len(), 16);
+        assert_eq!(
+            id,
+            element_id(Path::new("src/lib.rs"), "parse", Some("fn parse()"))
+        );
+        assert_ne!(
+            id,
+            element_id(Path::new("src/lib.rs"), "parse", Some("fn parse(x: u8)"))
+        );
+        assert_ne!(
+            id,
+            element_id(Path::new("src/main.rs"), "parse", Some("fn parse()"))
+        );
+    }
+
+    #[test]
+    fn test_add_file_assigns_ids_using_qualified_name() {
+        let mut method = element("run", "def run(self)", 1);
+        method.metadata = serde_json::json!({"qualified_name": "App.run"});
+        let matrix = matrix_with(vec![method, element("run", "def run(self)", 20)]);
+
+        let elements = &matrix.files[Path::new("src/lib.rs")].elements;
+        assert_eq!(
+            elements[0].element_id,
+            element_id(Path::new("src/lib.rs"), "App.run", Some("def run(self)"))
+        );
+        assert_ne!(elements[0].element_id, elements[1].element_id);
+
+        let (file, found) = matrix.find_element(&elements[1].element_id).unwrap();
+        assert_eq!(file.relative_path, PathBuf::from("src/lib.rs"));
+        assert_eq!(found.line_start, 20);
+    }
+
+    fn method(parent: &str, name: &str, line_start: u32) -> CodeElement {
+        CodeElement {
+            element_type: ElementType::Method,
+            qualified_name: Some(format!("{parent}::{name}")),
+            parent: Some(parent.to_string()),
+            ..element(name, &format!("fn {name}()"), line_start)
+        }
+    }
+
+    #[test]
+    fn test_qualified_names_match_either_separator() {
+        let load = method("Config", "load", 1);
+        assert_eq!(load.qualified_name(), "Config::load");
+        assert!(load.matches_name("load"));
+        assert!(load.matches_name("Config::load"));
+        assert!(load.matches_name("Config.load"));
+        assert!(!load.matches_name("Cache::load"));
+        assert!(!load.matches_name("Config"));
+
+        // The field wins over the older metadata key
+        let mut legacy = method("Config", "load", 1);
+        legacy.metadata = serde_json::json!({"qualified_name": "Old.load"});
+        assert_eq!(legacy.qualified_name(), "Config::load");
+
+        let json = serde_json::to_value(element("parse", "fn parse()", 1)).unwrap();
+        assert!(json.get("qualified_name").is_none());
+        assert!(json.get("parent").is_none());
+    }
+
+    #[test]
+    fn test_resolve_element_prefers_unambiguous_matches() {
+        let mut matrix = matrix_with(vec![
+            method("Config", "load", 1),
+            element("load", "fn load()", 20),
+        ]);
+        let mut cache = create_test_file_node("src/cache.rs", "rust");
+        cache.elements = vec![method("Cache", "load", 1)];
+        matrix.add_file(cache);
+
+        let found = matrix.find_elements("load");
+        assert_eq!(found.len(), 3);
+        assert_eq!(matrix.find_elements("Config::load").len(), 1);
+
+        let resolved = matrix
+            .resolve_element("Cache.load", Path::new("src/lib.rs"))
+            .unwrap();
+        assert_eq!(resolved.file, PathBuf::from("src/cache.rs"));
+        assert_eq!(resolved.name, "Cache::load");
+
+        // Two `load`s in src/lib.rs, and a third elsewhere
+        assert!(matrix
+            .resolve_element("load", Path::new("src/lib.rs"))
+            .is_none());
+        assert!(matrix
+            .resolve_element("load", Path::new("src/main.rs"))
+            .is_none());
+        let only_local = matrix
+            .resolve_element("load", Path::new("src/cache.rs"))
+            .unwrap();
+        assert_eq!(only_local.name, "Cache::load");
+        assert!(matrix
+            .resolve_element("save", Path::new("src/lib.rs"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_diff_elements_survives_line_shifts() {
+        let previous = matrix_with(vec![
+            element("parse", "fn parse()", 1),
+            element("render", "fn render()", 10),
+            element("old", "fn old()", 20),
+        ]);
+        let current = matrix_with(vec![
+            element("parse", "fn parse()", 1),
+            element("render", "fn render()", 14),
+            element("new", "fn new()", 30),
+        ]);
+
+        let diff = current.diff_elements(&previous);
+        let id = |name: &str, sig: &str| element_id(Path::new("src/lib.rs"), name, Some(sig));
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.moved, vec![id("render", "fn render()")]);
+        assert_eq!(diff.added, vec![id("new", "fn new()")]);
+        assert_eq!(diff.removed, vec![id("old", "fn old()")]);
+    }
+
+    #[tokio::test]
+    async fn test_load_backfills_missing_ids() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("matrix.json");
+        let mut matrix = matrix_with(vec![element("parse", "fn parse()", 1)]);
+        matrix
+            .files
+            .get_mut(Path::new("src/lib.rs"))
+            .unwrap()
+            .elements[0]
+            .element_id
+            .clear();
+        matrix.save(&path).await.unwrap();
+
+        let loaded = ProjectMatrix::load(&path).await.unwrap();
+        assert_eq!(
+            loaded.files[Path::new("src/lib.rs")].elements[0].element_id,
+            element_id(Path::new("src/lib.rs"), "parse", Some("fn parse()"))
+        );
+    }
+}
+
+#[cfg(test)]
+mod subset_tests {
+    use super::*;
+
+    // a -> b -> c -> d, and e -> b
+    fn chain_matrix() -> ProjectMatrix {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        for name in ["a.rs", "b.rs", "c.rs", "d.rs", "e.rs"] {
+            matrix.add_file(create_test_file_node(name, "rust"));
+        }
+        for (from, to) in [
+            ("a.rs", "b.rs"),
+            ("b.rs", "c.rs"),
+            ("c.rs", "d.rs"),
+            ("e.rs", "b.rs"),
+        ] {
+            matrix.add_relationship(create_test_relationship(from, to));
+        }
+        matrix
+    }
+
+    fn names(matrix: &ProjectMatrix) -> Vec<String> {
+        let mut names: Vec<String> = matrix
+            .files
+            .keys()
+            .map(|p| p.display().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_subset_expands_dependencies_by_depth() {
+        let matrix = chain_matrix();
+        let subset = matrix.subset(
+            &[PathBuf::from("a.rs")],
+            &SubsetOptions {
+                dependency_depth: 2,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(names(&subset), vec!["a.rs", "b.rs", "c.rs"]);
+        assert_eq!(subset.relationships.len(), 2);
+    }
+
+    #[test]
+    fn test_subset_expands_dependents() {
+        let matrix = chain_matrix();
+        let subset = matrix.subset(
+            &[PathBuf::from("b.rs")],
+            &SubsetOptions {
+                dependent_depth: 1,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(names(&subset), vec!["a.rs", "b.rs", "e.rs"]);
+        assert_eq!(subset.relationships.len(), 2);
+    }
+
+    #[test]
+    fn test_subset_walks_each_direction_separately() {
+        // Diamond: top.rs -> left.rs, right.rs -> bottom.rs
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        for name in ["top.rs", "left.rs", "right.rs", "bottom.rs"] {
+            matrix.add_file(create_test_file_node(name, "rust"));
+        }
+        for (from, to) in [
+            ("top.rs", "left.rs"),
+            ("top.rs", "right.rs"),
+            ("left.rs", "bottom.rs"),
+            ("right.rs", "bottom.rs"),
+        ] {
+            matrix.add_relationship(create_test_relationship(from, to));
+        }
+
+        // right.rs is a dependency of a dependent and a dependent of a dependency,
+        // but neither a dependency nor a dependent of left.rs
+        for (dependency_depth, dependent_depth) in [(2, 1), (1, 2), (2, 2)] {
+            let subset = matrix.subset(
+                &[PathBuf::from("left.rs")],
+                &SubsetOptions {
+                    dependency_depth,
+                    dependent_depth,
+                    max_tokens: None,
+                },
+            );
+            assert_eq!(
+                names(&subset),
+                vec!["bottom.rs", "left.rs", "top.rs"],
+                "{dependency_depth}/{dependent_depth}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_subset_respects_token_budget() {
+        let matrix = chain_matrix();
+        // Each test file is 256 tokens; the budget leaves room for one extra file
+        let subset = matrix.subset(
+            &[PathBuf::from("b.rs")],
+            &SubsetOptions {
+                dependency_depth: 3,
+                dependent_depth: 3,
+                max_tokens: Some(512),
+            },
+        );
+
+        assert_eq!(subset.files.len(), 2);
+        assert!(subset.files.contains_key(&PathBuf::from("b.rs")));
+    }
+
+    #[test]
+    fn test_subset_default_matches_plain_selection() {
+        let matrix = chain_matrix();
+        let subset = matrix.subset(
+            &[PathBuf::from("a.rs"), PathBuf::from("missing.rs")],
+            &SubsetOptions::default(),
+        );
+
+        assert_eq!(names(&subset), vec!["a.rs"]);
+        assert!(subset.relationships.is_empty());
+    }
+
+    #[test]
+    fn test_language_subset() {
+        let mut matrix = chain_matrix();
+        for name in ["app.py", "models.py"] {
+            matrix.add_file(create_test_file_node(name, "python"));
+        }
+        matrix.add_relationship(create_test_relationship("app.py", "models.py"));
+        matrix.add_relationship(create_test_relationship("app.py", "a.rs"));
+        for (name, ecosystem, source) in [
+            ("serde", "cargo", "Cargo.toml"),
+            ("requests", "pip", "requirements.txt"),
+            ("attrs", "unknown", "models.py"),
+        ] {
+            matrix.add_external_dependency(ExternalDependency {
+                name: name.to_string(),
+                version: None,
+                ecosystem: ecosystem.into(),
+                dependency_type: DependencyType::Runtime,
+                source_file: PathBuf::from(source),
+                source_plugin: None,
+                detected_at: None,
+            });
+        }
+
+        let subset = matrix.language_subset("Python");
+        assert_eq!(names(&subset), vec!["app.py", "models.py"]);
+        // The edge into Rust crosses the boundary
+        assert_eq!(subset.relationships.len(), 1);
+        let mut deps: Vec<&str> = subset
+            .external_dependencies
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect();
+        deps.sort();
+        assert_eq!(deps, vec!["attrs", "requests"]);
+        assert_eq!(subset.metadata.total_files, 2);
+        assert_eq!(subset.metadata.total_tokens, 512);
+        assert_eq!(subset.metadata.plugin_names(), vec!["python"]);
+        assert_eq!(subset.project_info.main_language, "python");
+
+        assert_eq!(matrix.languages(), vec!["python", "rust"]);
+        assert!(matrix.language_subset("cobol").files.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_load_subset_with_options() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("matrix.json");
+        chain_matrix().save(&path).await.unwrap();
+
+        let subset = ProjectMatrix::load_subset_with(
+            &path,
+            &[PathBuf::from("c.rs")],
+            &SubsetOptions {
+                dependency_depth: 1,
+                dependent_depth: 1,
+                max_tokens: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(names(&subset), vec!["b.rs", "c.rs", "d.rs"]);
+    }
+}
+
+#[cfg(test)]
+mod lazy_loading_tests {
+    use super::*;
+
+    async fn saved_matrix(temp_dir: &TempDir) -> PathBuf {
+        let path = temp_dir.path().join("matrix.json");
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        matrix.add_file(create_test_file_node("src/main.rs", "rust"));
+        matrix.add_file(create_test_file_node("src/lib.rs", "rust"));
+        matrix.add_relationship(create_test_relationship("src/main.rs", "src/lib.rs"));
+        matrix.finalize();
+        matrix.save(&path).await.unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_open_parses_files_on_demand() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = saved_matrix(&temp_dir).await;
+
+        let mut handle = ProjectMatrix::open(&path).await.unwrap();
+        assert_eq!(handle.file_count(), 2);
+        assert_eq!(handle.relationships.len(), 1);
+        assert_eq!(handle.metadata.total_files, 2);
+        assert_eq!(handle.loaded_count(), 0);
+
+        let file = handle.file(Path::new("src/lib.rs")).unwrap().unwrap();
+        assert_eq!(file.token_info.total_tokens, 256);
+        assert_eq!(handle.loaded_count(), 1);
+        assert!(handle.file(Path::new("src/missing.rs")).unwrap().is_none());
+        assert!(handle.contains_file(Path::new("src/main.rs")));
+
+        let filter = RelationshipFilter {
+            from_file: Some(PathBuf::from("src/main.rs")),
+            ..Default::default()
+        };
+        assert_eq!(handle.query_relationships(&filter).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_into_matrix_matches_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = saved_matrix(&temp_dir).await;
+
+        let mut handle = ProjectMatrix::open(&path).await.unwrap();
+        handle.file(Path::new("src/main.rs")).unwrap();
+        let mut lazy = handle.into_matrix().unwrap();
+        let mut eager = ProjectMatrix::load(&path).await.unwrap();
+
+        assert_eq!(lazy.files.len(), eager.files.len());
+        assert_eq!(
+            lazy.find_dependents(Path::new("src/lib.rs")).len(),
+            eager.find_dependents(Path::new("src/lib.rs")).len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_open_falls_back_to_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = saved_matrix(&temp_dir).await;
+        // A second save leaves the first as the backup
+        ProjectMatrix::load(&path)
+            .await
+            .unwrap()
+            .save(&path)
+            .await
+            .unwrap();
+        tokio::fs::write(&path, "{ truncated").await.unwrap();
+
+        let handle = ProjectMatrix::open(&path).await.unwrap();
+        assert_eq!(handle.file_count(), 2);
+    }
+}
+
+#[cfg(test)]
+mod traversal_order_tests {
+    use super::*;
+
+    fn relationship(from: &str, to: &str, strength: f32) -> Relationship {
+        Relationship {
+            strength,
+            ..create_test_relationship(from, to)
+        }
+    }
+
+    fn order(matrix: &ProjectMatrix) -> Vec<String> {
+        matrix
+            .traversal_order()
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_traversal_order_walks_out_from_entrypoints() {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        for path in [
+            "src/main.rs",
+            "src/cli.rs",
+            "src/config.rs",
+            "src/util.rs",
+            "src/orphan.rs",
+            "src/script.rs",
+        ] {
+            matrix.add_file(create_test_file_node(path, "rust"));
+        }
+        matrix.add_relationship(relationship("src/main.rs", "src/config.rs", 0.3));
+        matrix.add_relationship(relationship("src/main.rs", "src/cli.rs", 0.9));
+        matrix.add_relationship(relationship("src/cli.rs", "src/util.rs", 0.5));
+        matrix.add_relationship(relationship("src/config.rs", "src/util.rs", 0.5));
+        matrix.add_relationship(relationship("src/script.rs", "src/orphan.rs", 1.0));
+        matrix.finalize();
+
+        assert_eq!(
+            order(&matrix),
+            vec![
+                "src/main.rs",
+                "src/cli.rs",
+                "src/config.rs",
+                "src/util.rs",
+                // Unreachable from main.rs: the file depending on others goes first
+                "src/script.rs",
+                "src/orphan.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_traversal_order_covers_every_file_once() {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        for path in ["b.py", "a.py", "c.py"] {
+            matrix.add_file(create_test_file_node(path, "python"));
+        }
+        // Cycles and self-references don't repeat files
+        matrix.add_relationship(relationship("a.py", "b.py", 0.5));
+        matrix.add_relationship(relationship("b.py", "a.py", 0.5));
+        matrix.add_relationship(relationship("c.py", "c.py", 0.5));
+
+        assert_eq!(order(&matrix), vec!["a.py", "b.py", "c.py"]);
+    }
+
+    #[test]
+    fn test_traversal_order_skips_vendored_files() {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        for path in ["src/main.rs", "src/net.rs", "third_party/http/lib.rs"] {
+            matrix.add_file(create_test_file_node(path, "rust"));
+        }
+        matrix.add_relationship(relationship("src/main.rs", "third_party/http/lib.rs", 0.9));
+        matrix.add_relationship(relationship("src/main.rs", "src/net.rs", 0.5));
+        matrix.finalize();
+
+        assert_eq!(order(&matrix), vec!["src/main.rs", "src/net.rs"]);
+    }
+}
+
+#[cfg(test)]
+mod reexport_tests {
+    use super::*;
+
+    fn reexport(name: &str, alias: Option<&str>, source: &str) -> Export {
+        Export {
+            alias: alias.map(str::to_string),
+            source: Some(PathBuf::from(source)),
+            ..Export::named(name)
+        }
+    }
+
+    fn import(items: &[&str], line_number: u32) -> Import {
+        Import {
+            module: "crate::prelude".into(),
+            items: items.iter().map(|i| i.to_string()).collect(),
+            alias: None,
+            line_number,
+            import_type: ImportType::Local,
+        }
+    }
+
+    fn edge(from: &str, to: &str, line: u32, to_element: Option<&str>) -> Relationship {
+        Relationship {
+            line_number: Some(line),
+            to_element: to_element.map(str::to_string),
+            runtime_calls: None,
+            ..create_test_relationship(from, to)
+        }
+    }
+
+    // src/prelude.rs re-exports Parser from src/api.rs, which re-exports it from
+    // src/parser.rs as Parser (defined there as RawParser), plus Token from src/lexer.rs
+    fn project() -> ProjectMatrix {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        let mut prelude = create_test_file_node("src/prelude.rs", "rust");
+        prelude.exports = vec![
+            reexport("Parser", None, "src/api.rs"),
+            reexport("Token", None, "src/lexer.rs"),
+            Export::named("VERSION"),
+        ];
+        let mut api = create_test_file_node("src/api.rs", "rust");
+        api.exports = vec![reexport("RawParser", Some("Parser"), "src/parser.rs")];
+        let mut main = create_test_file_node("src/main.rs", "rust");
+        main.imports = vec![import(&["Parser", "VERSION"], 1), import(&["*"], 2)];
+
+        for file in [prelude, api, main] {
+            matrix.add_file(file);
+        }
+        for file in ["src/parser.rs", "src/lexer.rs"] {
+            matrix.add_file(create_test_file_node(file, "rust"));
+        }
+        matrix
+    }
+
+    #[test]
+    fn test_export_records_accept_bare_names() {
+        let exports: Vec<Export> = serde_json::from_str(
+            r#"["main", {"name": "Raw", "kind": "struct", "alias": "Parsed", "source": "src/raw.rs"}]"#,
+        )
+        .unwrap();
+        assert_eq!(exports[0], Export::named("main"));
+        assert_eq!(exports[1].exported_name(), "Parsed");
+        assert_eq!(exports[1].kind.as_deref(), Some("struct"));
+        assert_eq!(exports[1].source, Some(PathBuf::from("src/raw.rs")));
+
+        let json = serde_json::to_value(&exports[0]).unwrap();
+        assert_eq!(json, serde_json::json!({"name": "main"}));
+    }
+
+    #[test]
+    fn test_element_edges_follow_reexport_chains() {
+        let mut matrix = project();
+        matrix.add_relationship(edge("src/main.rs", "src/prelude.rs", 10, Some("Parser")));
+        matrix.add_relationship(edge("src/main.rs", "src/prelude.rs", 11, Some("VERSION")));
+
+        assert_eq!(matrix.resolve_reexports(), 1);
+        let parser = &matrix.relationships[0];
+        assert_eq!(parser.to_file, PathBuf::from("src/parser.rs"));
+        assert_eq!(parser.to_element.as_deref(), Some("RawParser"));
+        // Defined where it's exported, so left alone
+        assert_eq!(
+            matrix.relationships[1].to_file,
+            PathBuf::from("src/prelude.rs")
+        );
+    }
+
+    #[test]
+    fn test_imports_of_reexported_names_gain_edges() {
+        let mut matrix = project();
+        matrix.add_relationship(edge("src/main.rs", "src/prelude.rs", 1, None));
+        matrix.add_relationship(edge("src/main.rs", "src/prelude.rs", 2, None));
+        matrix.finalize();
+
+        let targets: std::collections::BTreeSet<(&Path, Option<&str>)> = matrix
+            .relationships
+            .iter()
+            .map(|r| (r.to_file.as_path(), r.to_element.as_deref()))
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                (Path::new("src/prelude.rs"), None),
+                (Path::new("src/parser.rs"), Some("RawParser")),
+                (Path::new("src/lexer.rs"), Some("Token")),
+            ]
+            .into_iter()
+            .collect()
+        );
+        // The glob import's edge to the parser duplicates the named import's
+        assert_eq!(matrix.find_dependencies(Path::new("src/main.rs")).len(), 3);
+    }
+
+    #[test]
+    fn test_reexport_cycles_terminate() {
+        let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+        let mut a = create_test_file_node("src/a.rs", "rust");
+        a.exports = vec![reexport("Thing", None, "src/b.rs")];
+        let mut b = create_test_file_node("src/b.rs", "rust");
+        b.exports = vec![reexport("Thing", None, "src/a.rs")];
+        matrix.add_file(a);
+        matrix.add_file(b);
+        matrix.add_relationship(edge("src/main.rs", "src/a.rs", 1, Some("Thing")));
+
+        matrix.resolve_reexports();
+        assert_eq!(matrix.relationships.len(), 1);
+    }
+}
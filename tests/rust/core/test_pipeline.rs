@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use csd::core::pipeline::ByteBudget;
+
+#[tokio::test]
+async fn test_budget_holds_bytes_until_released() {
+    let budget = ByteBudget::new(10 * 1024);
+    assert_eq!(budget.capacity(), 10 * 1024);
+
+    let first = budget.reserve(6 * 1024).await;
+    // Partial KiB round up
+    let second = budget.try_reserve(3 * 1024 + 1).expect("fits");
+    assert_eq!(budget.available(), 0);
+    assert!(budget.try_reserve(1).is_none());
+
+    drop(second);
+    assert_eq!(budget.available(), 4 * 1024);
+    assert!(
+        tokio::time::timeout(Duration::from_millis(50), budget.reserve(5 * 1024))
+            .await
+            .is_err(),
+        "5 KiB shouldn't fit while 6 are held"
+    );
+    drop(first);
+    budget.reserve(5 * 1024).await;
+    assert_eq!(budget.available(), 10 * 1024);
+}
+
+#[tokio::test]
+async fn test_oversized_reservations_take_the_whole_budget() {
+    let budget = ByteBudget::new(4096);
+    let small = budget.reserve(0).await;
+    assert_eq!(budget.available(), 3 * 1024);
+
+    let waiting = {
+        let budget = budget.clone();
+        tokio::spawn(async move {
+            let _all = budget.reserve(1 << 30).await;
+            budget.available()
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(!waiting.is_finished());
+    drop(small);
+    assert_eq!(waiting.await.unwrap(), 0);
+    assert_eq!(budget.available(), 4096);
+}
@@ -0,0 +1,124 @@
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use csd::core::project::{normalize_python_name, ProjectContext};
+
+fn write(root: &Path, relative: &str, content: &str) {
+    let path = root.join(relative);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn test_detect_reads_manifests_of_each_ecosystem() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        "[package]\nname = \"core\"\n\n[dependencies]\nserde = \"1\"\n\n[dev-dependencies]\ntempfile = \"3\"\n",
+    );
+    write(root, "src/lib.rs", "");
+    write(
+        root,
+        "web/package.json",
+        r#"{"name": "web", "dependencies": {"react": "^18"}, "devDependencies": {"vite": "^5"}}"#,
+    );
+    write(
+        root,
+        "tools/pyproject.toml",
+        "[project]\nname = \"My_Tools\"\ndependencies = [\"Requests[socks]>=2.0\", \"click; python_version > '3.8'\"]\n",
+    );
+    write(
+        root,
+        "tools/requirements-dev.txt",
+        "# tooling\n-r base.txt\npytest==8.0\n",
+    );
+    write(
+        root,
+        "svc/go.mod",
+        "module example.com/svc\n\ngo 1.22\n\nrequire (\n\tgithub.com/gorilla/mux v1.8.0 // indirect\n)\nrequire golang.org/x/sync v0.6.0\n",
+    );
+
+    let context = ProjectContext::detect(root);
+
+    let packages: Vec<(&str, &str)> = context
+        .packages
+        .iter()
+        .map(|p| (p.ecosystem.as_str(), p.name.as_str()))
+        .collect();
+    assert_eq!(
+        packages,
+        vec![
+            ("cargo", "core"),
+            ("go", "example.com/svc"),
+            ("python", "my-tools"),
+            ("npm", "web"),
+        ]
+    );
+    assert_eq!(
+        context.dependencies_of("cargo").collect::<Vec<_>>(),
+        vec!["serde", "tempfile"]
+    );
+    assert_eq!(
+        context.dependencies_of("npm").collect::<Vec<_>>(),
+        vec!["react", "vite"]
+    );
+    assert_eq!(
+        context.dependencies_of("python").collect::<Vec<_>>(),
+        vec!["click", "pytest", "requests"]
+    );
+    assert_eq!(
+        context.dependencies_of("go").collect::<Vec<_>>(),
+        vec!["github.com/gorilla/mux", "golang.org/x/sync"]
+    );
+    // The Rust package keeps its code under src/, the others beside their manifests
+    assert_eq!(
+        context.source_roots,
+        vec![
+            PathBuf::from("src"),
+            PathBuf::from("svc"),
+            PathBuf::from("tools"),
+            PathBuf::from("web"),
+        ]
+    );
+}
+
+#[test]
+fn test_detect_skips_dependency_trees_and_broken_manifests() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write(root, "package.json", r#"{"name": "app"}"#);
+    write(
+        root,
+        "node_modules/left-pad/package.json",
+        r#"{"name": "left-pad"}"#,
+    );
+    write(root, "broken/Cargo.toml", "[package\nname = ");
+
+    let context = ProjectContext::detect(root);
+
+    assert_eq!(context.packages.len(), 1);
+    assert_eq!(context.packages[0].name, "app");
+    assert_eq!(context.packages[0].manifest, PathBuf::from("package.json"));
+}
+
+#[test]
+fn test_detect_without_manifests_is_empty() {
+    let temp_dir = TempDir::new().unwrap();
+    write(temp_dir.path(), "main.py", "print('hi')\n");
+
+    assert_eq!(
+        ProjectContext::detect(temp_dir.path()),
+        ProjectContext::default()
+    );
+}
+
+#[test]
+fn test_normalize_python_name() {
+    assert_eq!(
+        normalize_python_name("Django_REST.framework"),
+        "django-rest-framework"
+    );
+    assert_eq!(normalize_python_name("zope__interface"), "zope-interface");
+}
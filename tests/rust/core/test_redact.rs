@@ -0,0 +1,224 @@
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use csd::core::data_model::{AccessKind, DataModel, Table, TableAccess};
+use csd::core::glossary::GlossaryTerm;
+use csd::core::matrix::{CodeElement, ElementType, Import, ImportType, ProjectMatrix};
+use csd::core::redact::{cached_salt, redact_matrix, SALT_FILE};
+use csd::utils::config::RedactionConfig;
+
+use super::test_matrix::{create_test_file_node, create_test_relationship};
+
+fn sample_matrix() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/work/secret-project"));
+    let mut lib = create_test_file_node("src/billing/invoice.rs", "rust");
+    lib.metadata = serde_json::json!({ "crate": "billing" });
+    lib.elements.push(CodeElement {
+        element_type: ElementType::Function,
+        name: "charge".to_string(),
+        qualified_name: None,
+        parent: None,
+        signature: Some("fn charge(card: &Card) -> Receipt".to_string()),
+        line_start: 1,
+        line_end: 5,
+        summary: Some("Charges the customer's card".to_string()),
+        complexity_score: None,
+        calls: vec![],
+        metadata: serde_json::json!({ "visibility": "pub" }),
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    });
+    lib.imports.push(Import {
+        module: "src/billing/tax.rs".into(),
+        items: vec![],
+        alias: None,
+        line_number: 1,
+        import_type: ImportType::Local,
+    });
+    lib.imports.push(Import {
+        module: "serde".into(),
+        items: vec![],
+        alias: None,
+        line_number: 2,
+        import_type: ImportType::ThirdParty,
+    });
+    matrix.add_file(lib);
+    matrix.add_file(create_test_file_node("src/billing/tax.rs", "rust"));
+    matrix.add_file(create_test_file_node("README.md", "markdown"));
+    matrix.add_relationship(create_test_relationship(
+        "src/billing/invoice.rs",
+        "src/billing/tax.rs",
+    ));
+    matrix
+}
+
+#[test]
+fn test_default_rules_strip_content_but_keep_paths() {
+    let mut matrix = sample_matrix();
+    let summary = redact_matrix(&mut matrix, &RedactionConfig::default()).unwrap();
+
+    assert_eq!(summary.summaries, 4);
+    assert_eq!(summary.signatures, 1);
+    assert_eq!(summary.metadata, 2);
+    assert_eq!(summary.hashed_paths, 0);
+
+    let lib = &matrix.files[Path::new("src/billing/invoice.rs")];
+    assert!(lib.file_summary.is_none());
+    assert!(lib.metadata.is_null());
+    assert!(lib.hash.is_empty());
+    assert!(lib.elements[0].signature.is_none());
+    assert!(lib.elements[0].summary.is_none());
+    assert_eq!(lib.elements[0].name, "charge");
+    assert!(matrix.relationships[0].details.is_empty());
+}
+
+#[test]
+fn test_hash_paths_keeps_structure() {
+    let mut matrix = sample_matrix();
+    let rules = RedactionConfig {
+        hash_paths: true,
+        salt: Some("pepper".to_string()),
+        keep_paths: vec!["*.md".to_string()],
+        ..RedactionConfig::default()
+    };
+    let summary = redact_matrix(&mut matrix, &rules).unwrap();
+    assert_eq!(summary.hashed_paths, 3);
+
+    assert!(matrix.files.contains_key(Path::new("README.md")));
+    assert_eq!(matrix.metadata.project_root, PathBuf::from("redacted"));
+    for (key, file) in &matrix.files {
+        assert!(!key.to_string_lossy().contains("billing"));
+        assert_eq!(key, &file.relative_path);
+    }
+
+    let relationship = &matrix.relationships[0];
+    assert!(matrix.files.contains_key(&relationship.from_file));
+    assert!(matrix.files.contains_key(&relationship.to_file));
+    assert_eq!(relationship.to_file.extension().unwrap(), "rs");
+
+    // Local imports point at the same hash as the file they import
+    let importer = &matrix.files[&relationship.from_file];
+    assert_eq!(
+        importer.imports[0].module,
+        relationship.to_file.to_string_lossy().to_string()
+    );
+    assert_eq!(importer.imports[1].module, "serde");
+
+    // The graph is rebuilt from the rewritten keys
+    let dependencies = matrix.find_dependencies(&relationship.from_file.clone());
+    assert_eq!(dependencies.len(), 1);
+}
+
+#[test]
+fn test_hash_paths_rewrites_data_model() {
+    let mut matrix = sample_matrix();
+    matrix.data_model = DataModel {
+        tables: vec![Table {
+            name: "invoices".to_string(),
+            columns: Vec::new(),
+            model: None,
+            defined_in: vec![PathBuf::from("src/billing/invoice.rs")],
+        }],
+        accesses: vec![TableAccess {
+            file: PathBuf::from("src/billing/tax.rs"),
+            table: "invoices".to_string(),
+            kind: AccessKind::Sql,
+        }],
+    };
+    let rules = RedactionConfig {
+        hash_paths: true,
+        salt: Some("pepper".to_string()),
+        ..RedactionConfig::default()
+    };
+    redact_matrix(&mut matrix, &rules).unwrap();
+
+    let defined_in = &matrix.data_model.tables[0].defined_in[0];
+    let access = &matrix.data_model.accesses[0].file;
+    assert!(matrix.files.contains_key(defined_in));
+    assert!(matrix.files.contains_key(access));
+    assert!(!access.to_string_lossy().contains("billing"));
+}
+
+#[test]
+fn test_hash_depends_on_salt() {
+    let hashed = |salt: &str| {
+        let mut matrix = sample_matrix();
+        let rules = RedactionConfig {
+            hash_paths: true,
+            salt: Some(salt.to_string()),
+            ..RedactionConfig::default()
+        };
+        redact_matrix(&mut matrix, &rules).unwrap();
+        let mut keys: Vec<PathBuf> = matrix.files.into_keys().collect();
+        keys.sort();
+        keys
+    };
+
+    assert_eq!(hashed("a"), hashed("a"));
+    assert_ne!(hashed("a"), hashed("b"));
+}
+
+#[test]
+fn test_hash_paths_needs_a_salt() {
+    let mut matrix = sample_matrix();
+    let rules = RedactionConfig {
+        hash_paths: true,
+        ..RedactionConfig::default()
+    };
+    let error = redact_matrix(&mut matrix, &rules).unwrap_err();
+    assert!(error.to_string().contains("salt"));
+    assert!(matrix
+        .files
+        .contains_key(Path::new("src/billing/invoice.rs")));
+}
+
+#[tokio::test]
+async fn test_cached_salt_is_generated_once() {
+    let temp_dir = TempDir::new().unwrap();
+    let cache_dir = temp_dir.path().join(".csd");
+    let salt = cached_salt(&cache_dir).await.unwrap();
+    assert_eq!(salt.len(), 32);
+    assert!(cache_dir.join(SALT_FILE).exists());
+    assert_eq!(cached_salt(&cache_dir).await.unwrap(), salt);
+
+    let other = TempDir::new().unwrap();
+    assert_ne!(cached_salt(other.path()).await.unwrap(), salt);
+}
+
+#[test]
+fn test_invalid_keep_paths_glob_is_an_error() {
+    let mut matrix = sample_matrix();
+    let rules = RedactionConfig {
+        hash_paths: true,
+        keep_paths: vec!["[".to_string()],
+        ..RedactionConfig::default()
+    };
+    let error = redact_matrix(&mut matrix, &rules).unwrap_err();
+    assert!(error.to_string().contains("keep_paths"));
+}
+
+#[test]
+fn test_glossary_follows_summary_and_path_rules() {
+    let mut matrix = sample_matrix();
+    matrix.project_info.glossary_candidates = vec![GlossaryTerm {
+        term: "invoice".to_string(),
+        score: 2.0,
+        occurrences: 3,
+        files: vec![PathBuf::from("src/billing/invoice.rs")],
+    }];
+
+    let rules = RedactionConfig {
+        strip_summaries: false,
+        hash_paths: true,
+        salt: Some("pepper".to_string()),
+        ..RedactionConfig::default()
+    };
+    let mut hashed = matrix.clone();
+    redact_matrix(&mut hashed, &rules).unwrap();
+    let term = &hashed.project_info.glossary_candidates[0];
+    assert!(hashed.files.contains_key(&term.files[0]));
+
+    redact_matrix(&mut matrix, &RedactionConfig::default()).unwrap();
+    assert!(matrix.project_info.glossary_candidates.is_empty());
+}
@@ -0,0 +1,175 @@
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use csd::core::matrix::{CodeElement, ElementType, ProjectMatrix};
+use csd::core::rules::{run_rules, RuleReport};
+use csd::core::suppressions::{QualityCheck, SuppressedBy, Suppressions, SUPPRESSIONS_FILE};
+use csd::plugins::interface::{FindingSeverity, RuleFinding};
+use csd::utils::config::{Config, PluginRuntime, PluginSource, RulePluginConfig};
+
+use super::test_matrix::create_test_file_node;
+
+fn finding(rule: &str, file: &str, element: Option<&str>, line: Option<u32>) -> RuleFinding {
+    RuleFinding {
+        rule: rule.to_string(),
+        severity: FindingSeverity::Warning,
+        message: format!("{rule} in {file}"),
+        file: PathBuf::from(file),
+        element: element.map(str::to_string),
+        line,
+        suggestions: Vec::new(),
+    }
+}
+
+fn rule_plugin(path: &str, scope: &[&str]) -> RulePluginConfig {
+    RulePluginConfig {
+        source: PluginSource::Local {
+            path: path.to_string(),
+        },
+        runtime: PluginRuntime::Python,
+        enabled: true,
+        scope: scope.iter().map(|s| s.to_string()).collect(),
+        config: None,
+    }
+}
+
+#[test]
+fn test_findings_are_qualified_and_suppressed() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("src/app.py"),
+        "import sys\n# csd-ignore: lint/no-print -- debugging aid\ndef debug():\n    print(sys.argv)\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join(SUPPRESSIONS_FILE),
+        "suppressions:\n  - path: scripts/\n    checks: [lint/todo]\n",
+    )
+    .unwrap();
+
+    let mut matrix = ProjectMatrix::new(root.to_path_buf());
+    let mut app = create_test_file_node("src/app.py", "python");
+    app.elements.push(CodeElement {
+        element_type: ElementType::Function,
+        name: "debug".to_string(),
+        qualified_name: None,
+        parent: None,
+        signature: None,
+        line_start: 3,
+        line_end: 4,
+        summary: None,
+        complexity_score: None,
+        calls: vec![],
+        metadata: serde_json::Value::Null,
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    });
+    matrix.add_file(app);
+
+    let mut error = finding("no-print", "src/app.py", None, Some(10));
+    error.severity = FindingSeverity::Error;
+    let report = RuleReport::collect(
+        &matrix,
+        vec![
+            (
+                "lint".to_string(),
+                vec![
+                    error,
+                    finding("no-print", "src/app.py", Some("debug"), None),
+                    finding("todo", "scripts/release.py", None, Some(1)),
+                ],
+            ),
+            (
+                "style".to_string(),
+                vec![finding("no-print", "src/app.py", Some("debug"), None)],
+            ),
+        ],
+        &Suppressions::load(root).unwrap(),
+    );
+
+    // The pragma only names lint's rule, and only covers the element below it
+    let kept: Vec<(&str, Option<u32>)> = report
+        .findings
+        .iter()
+        .map(|f| (f.rule.as_str(), f.line))
+        .collect();
+    assert_eq!(
+        kept,
+        vec![("style/no-print", None), ("lint/no-print", Some(10))]
+    );
+    assert_eq!(report.count(FindingSeverity::Error), 1);
+    assert_eq!(report.count(FindingSeverity::Warning), 1);
+
+    assert_eq!(report.suppressed.len(), 2);
+    assert_eq!(
+        report.suppressed[0].checks,
+        vec![QualityCheck::Rule("lint/todo".to_string())]
+    );
+    assert!(matches!(report.suppressed[0].by, SuppressedBy::File { .. }));
+    assert_eq!(
+        report.suppressed[1].by,
+        SuppressedBy::Pragma {
+            line: 2,
+            reason: Some("debugging aid".to_string())
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_run_rules_scopes_the_matrix() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let matrix_path = temp_dir.path().join("matrix.json");
+
+    // Reports every file it's given
+    let script = r#"import json, sys
+msg = json.loads(sys.stdin.read())
+if msg["type"] == "check":
+    matrix = json.load(open(msg["input"]["matrix_path"]))
+    findings = [{"rule": "seen", "message": "seen", "file": node["relative_path"]}
+        for node in matrix["files"].values()]
+    print(json.dumps({"status": "check_success", "processing_time_ms": 1,
+        "findings": findings}))
+else:
+    print(json.dumps({"status": "error", "message": "unsupported", "details": None}))
+"#;
+    let plugin_path = temp_dir.path().join("seen.py");
+    tokio::fs::write(&plugin_path, script).await.unwrap();
+
+    let mut config = Config::default();
+    config.rule_plugins.insert(
+        "scoped".to_string(),
+        rule_plugin(&plugin_path.to_string_lossy(), &["src/"]),
+    );
+    config.rule_plugins.insert(
+        "broken".to_string(),
+        rule_plugin(&temp_dir.path().join("missing.py").to_string_lossy(), &[]),
+    );
+
+    let mut matrix = ProjectMatrix::new(temp_dir.path().to_path_buf());
+    matrix.add_file(create_test_file_node("src/lib.rs", "rust"));
+    matrix.add_file(create_test_file_node("docs/guide.md", "markdown"));
+    matrix.save(&matrix_path).await.unwrap();
+
+    let report = run_rules(
+        &matrix,
+        &matrix_path,
+        temp_dir.path(),
+        &config,
+        &Suppressions::default(),
+    )
+    .await
+    .expect("Rules should run");
+    if report.failed.contains(&"scoped".to_string()) {
+        eprintln!("Skipping: python is not available to run the fake rule plugin");
+        return;
+    }
+
+    assert_eq!(report.failed, vec!["broken".to_string()]);
+    assert_eq!(report.findings.len(), 1);
+    assert_eq!(report.findings[0].rule, "scoped/seen");
+    assert_eq!(report.findings[0].file, PathBuf::from("src/lib.rs"));
+    assert_eq!(report.findings[0].severity, FindingSeverity::Warning);
+}
@@ -3,8 +3,15 @@ use tempfile::TempDir;
 use tokio::fs;
 
 // Import the modules we're testing
-use csd::core::scanner::{FileInfo, ProjectScanner};
-use csd::utils::config::{Config, FilePatterns, InputPluginConfig, PluginSource};
+use csd::core::categories::FileCategory;
+use csd::core::scanner::{
+    truncate_to_tokens, unclaimed_patterns, Exclusion, FileInfo, ProjectScanner,
+};
+use csd::plugins::interface::{PluginInfo, PluginType};
+use csd::utils::config::{
+    CategoryRule, Config, FilePatterns, InputPluginConfig, PluginRuntime, PluginSource, ScanLevel,
+    TruncationStrategy,
+};
 
 // Helper function to create a test project structure
 async fn create_test_project(temp_dir: &TempDir) -> anyhow::Result<PathBuf> {
@@ -81,6 +88,7 @@ fn create_config_with_custom_patterns() -> Config {
             },
             enabled: true,
             config: None,
+            runtime: PluginRuntime::Python,
         },
     );
 
@@ -125,6 +133,30 @@ async fn test_scan_finds_expected_files() {
     assert!(!file_paths.iter().any(|p| p.contains(".hidden")));
 }
 
+#[tokio::test]
+async fn test_scan_skips_relocated_cache_dir() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let project_root = create_test_project(&temp_dir)
+        .await
+        .expect("Failed to create test project");
+    fs::create_dir_all(project_root.join("build/csd-cache"))
+        .await
+        .unwrap();
+    fs::write(project_root.join("build/csd-cache/matrix.json"), "{}")
+        .await
+        .unwrap();
+
+    let mut config = create_test_config();
+    config.cache_dir = Some("build/csd-cache".to_string());
+
+    let scanner = ProjectScanner::new(config).with_root(&project_root);
+    let files = scanner.scan().await.expect("Scan failed");
+
+    assert!(!files
+        .iter()
+        .any(|f| f.relative_path.starts_with("build/csd-cache")));
+}
+
 #[tokio::test]
 async fn test_scan_with_hidden_files_enabled() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
@@ -316,6 +348,7 @@ fn test_print_scan_results() {
             is_text: true,
             plugin_name: Some("rust".to_string()),
             content_hash: "test_hash".to_string(),
+            plugin_confidence: None,
         },
         FileInfo {
             path: PathBuf::from("/test/script.py"),
@@ -325,6 +358,7 @@ fn test_print_scan_results() {
             is_text: true,
             plugin_name: Some("python".to_string()),
             content_hash: "test_hash2".to_string(),
+            plugin_confidence: None,
         },
         FileInfo {
             path: PathBuf::from("/test/unknown.xyz"),
@@ -334,6 +368,7 @@ fn test_print_scan_results() {
             is_text: false,
             plugin_name: None,
             content_hash: "test_hash3".to_string(),
+            plugin_confidence: None,
         },
     ];
 
@@ -438,3 +473,833 @@ fn test_legacy_plugin_migration() {
     // Test that legacy field is None (no migration needed for default config)
     assert!(config.plugins.is_none());
 }
+
+// Config with a single plugin whose script doesn't exist
+fn create_config_with_missing_plugin() -> Config {
+    let mut config = create_test_config();
+    config.input_plugins.clear();
+    config.input_plugins.insert(
+        "missing".to_string(),
+        InputPluginConfig {
+            source: PluginSource::Local {
+                path: "does/not/exist.py".to_string(),
+            },
+            file_patterns: FilePatterns {
+                extensions: vec![".test".to_string()],
+                filenames: vec![],
+                glob_patterns: None,
+            },
+            enabled: true,
+            config: None,
+            runtime: PluginRuntime::Python,
+        },
+    );
+    config
+}
+
+#[test]
+fn test_unclaimed_patterns() {
+    let mut info = PluginInfo {
+        name: "python".to_string(),
+        version: "1.0.0".to_string(),
+        plugin_type: PluginType::Input,
+        supported_extensions: vec![".py".to_string()],
+        supported_filenames: vec![],
+        supported_output_types: None,
+        supported_formats: None,
+        capabilities: vec![],
+        config_schema: None,
+    };
+    let patterns = FilePatterns {
+        extensions: vec![".py".to_string(), ".pyi".to_string()],
+        filenames: vec![],
+        glob_patterns: None,
+    };
+
+    let warnings = unclaimed_patterns(&patterns, &info);
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains(".pyi"));
+
+    // No claimed extensions means no claim to contradict
+    info.supported_extensions.clear();
+    assert!(unclaimed_patterns(&patterns, &info).is_empty());
+}
+
+#[tokio::test]
+async fn test_warm_up_reports_missing_plugin() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let scanner =
+        ProjectScanner::new(create_config_with_missing_plugin()).with_root(temp_dir.path());
+
+    let probes = scanner.warm_up_plugins().await;
+    assert_eq!(probes.len(), 1);
+    assert_eq!(probes[0].name, "missing");
+    assert!(!probes[0].is_ready());
+    assert!(probes[0].error.as_ref().unwrap().contains("not found"));
+}
+
+#[tokio::test]
+async fn test_scan_to_matrix_skips_unready_plugins() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(temp_dir.path().join("a.test"), "content")
+        .await
+        .unwrap();
+
+    // Files for the broken plugin are still recorded, just without analysis
+    let scanner =
+        ProjectScanner::new(create_config_with_missing_plugin()).with_root(temp_dir.path());
+    let matrix = scanner.scan_to_matrix().await.expect("Scan should succeed");
+    let node = matrix
+        .files
+        .values()
+        .find(|f| f.relative_path == std::path::Path::new("a.test"))
+        .expect("a.test should be in the matrix");
+    assert!(node.elements.is_empty());
+
+    // Strict mode stops before analyzing anything
+    let mut config = create_config_with_missing_plugin();
+    config.scanning.fail_on_unready_plugins = true;
+    let scanner = ProjectScanner::new(config).with_root(temp_dir.path());
+    let err = scanner.scan_to_matrix().await.unwrap_err();
+    assert!(err.to_string().contains("missing"));
+}
+
+// Write a minimal standalone input plugin that claims `.test` files with a fixed confidence
+async fn write_fake_plugin(dir: &std::path::Path, name: &str, confidence: f32) -> String {
+    let script = format!(
+        r#"import json, sys
+msg = json.loads(sys.stdin.read())
+if msg["type"] == "get_info":
+    print(json.dumps({{"status": "info", "name": "{name}", "version": "1.0.0",
+        "plugin_type": "input", "supported_extensions": [".test"],
+        "supported_filenames": [], "supported_output_types": None,
+        "supported_formats": None}}))
+elif msg["type"] == "can_analyze":
+    print(json.dumps({{"status": "can_analyze", "can_analyze": True, "confidence": {confidence}}}))
+else:
+    print(json.dumps({{"status": "error", "message": "not implemented", "details": None}}))
+"#
+    );
+    let path = dir.join(format!("{name}.py"));
+    fs::write(&path, script).await.unwrap();
+    path.to_string_lossy().to_string()
+}
+
+#[tokio::test]
+async fn test_warm_up_rejects_config_breaking_plugin_schema() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let path = temp_dir.path().join("strict.py");
+    fs::write(
+        &path,
+        r#"import json, sys
+sys.stdin.read()
+print(json.dumps({"status": "info", "name": "strict", "version": "1.0.0",
+    "plugin_type": "input", "supported_extensions": [".test"],
+    "supported_filenames": [], "supported_output_types": None, "supported_formats": None,
+    "config_schema": {"type": "object", "properties": {"depth": {"type": "integer"}}}}))
+"#,
+    )
+    .await
+    .unwrap();
+
+    let mut config = create_config_with_missing_plugin();
+    let plugin = config.input_plugins.get_mut("missing").unwrap();
+    plugin.source = PluginSource::Local {
+        path: path.to_string_lossy().to_string(),
+    };
+    plugin.config = Some(serde_yaml::from_str("depth: deep").unwrap());
+    let scanner = ProjectScanner::new(config.clone()).with_root(temp_dir.path());
+
+    let probes = scanner.warm_up_plugins().await;
+    let error = probes[0].error.clone().unwrap();
+    if error.contains("did not respond") {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+    assert!(
+        error.contains("config.depth: expected integer, got string"),
+        "{error}"
+    );
+
+    config.input_plugins.get_mut("missing").unwrap().config =
+        Some(serde_yaml::from_str("depth: 2").unwrap());
+    let scanner = ProjectScanner::new(config).with_root(temp_dir.path());
+    assert!(scanner.warm_up_plugins().await[0].is_ready());
+}
+
+#[tokio::test]
+async fn test_scan_sends_project_context_to_plugins_that_ask() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let plugin_dir = TempDir::new().expect("Failed to create plugin dir");
+    fs::write(temp_dir.path().join("a.test"), "content")
+        .await
+        .unwrap();
+    fs::write(temp_dir.path().join("requirements.txt"), "requests>=2\n")
+        .await
+        .unwrap();
+    // Records the `project` it was sent next to itself
+    let received = plugin_dir.path().join("received.json");
+    let path = plugin_dir.path().join("aware.py");
+    fs::write(
+        &path,
+        format!(
+            r#"import json, sys
+msg = json.loads(sys.stdin.read())
+if msg["type"] == "get_info":
+    print(json.dumps({{"status": "info", "name": "aware", "version": "1.0.0",
+        "plugin_type": "input", "supported_extensions": [".test"],
+        "supported_filenames": [], "supported_output_types": None,
+        "supported_formats": None, "capabilities": ["project_context"]}}))
+elif msg["type"] == "can_analyze":
+    print(json.dumps({{"status": "can_analyze", "can_analyze": True, "confidence": 1.0}}))
+else:
+    with open({received:?}, "w") as f:
+        json.dump(msg["input"].get("project"), f)
+    print(json.dumps({{"status": "error", "message": "not implemented", "details": None}}))
+"#
+        ),
+    )
+    .await
+    .unwrap();
+
+    let mut config = create_test_config();
+    config.input_plugins.clear();
+    config.input_plugins.insert(
+        "aware".to_string(),
+        InputPluginConfig {
+            source: PluginSource::Local {
+                path: path.to_string_lossy().to_string(),
+            },
+            file_patterns: FilePatterns {
+                extensions: vec![".test".to_string()],
+                filenames: vec![],
+                glob_patterns: None,
+            },
+            enabled: true,
+            config: None,
+            runtime: PluginRuntime::Python,
+        },
+    );
+
+    let scanner = ProjectScanner::new(config).with_root(temp_dir.path());
+    if scanner
+        .warm_up_plugins()
+        .await
+        .iter()
+        .any(|p| !p.is_ready())
+    {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+    scanner.scan_to_matrix().await.expect("Scan should succeed");
+
+    let project: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&received).unwrap()).unwrap();
+    assert_eq!(
+        project["dependencies"]["python"],
+        serde_json::json!(["requests"])
+    );
+}
+
+#[tokio::test]
+async fn test_structure_scan_runs_no_plugins() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let plugin_dir = TempDir::new().expect("Failed to create plugin dir");
+    fs::write(temp_dir.path().join("main.py"), "from util import helper\n")
+        .await
+        .unwrap();
+    fs::write(temp_dir.path().join("util.py"), "def helper():\n    pass\n")
+        .await
+        .unwrap();
+    // Leaves a marker behind if it is ever started
+    let started = plugin_dir.path().join("started");
+    let path = plugin_dir.path().join("noisy.py");
+    fs::write(&path, format!("open({started:?}, 'w').close()\n"))
+        .await
+        .unwrap();
+
+    let mut config = create_test_config();
+    config.input_plugins.clear();
+    config.input_plugins.insert(
+        "noisy".to_string(),
+        InputPluginConfig {
+            source: PluginSource::Local {
+                path: path.to_string_lossy().to_string(),
+            },
+            file_patterns: FilePatterns {
+                extensions: vec![".py".to_string()],
+                filenames: vec![],
+                glob_patterns: None,
+            },
+            enabled: true,
+            config: None,
+            runtime: PluginRuntime::Python,
+        },
+    );
+    config.scanning.level = ScanLevel::Structure;
+
+    let scanner = ProjectScanner::new(config).with_root(temp_dir.path());
+    let matrix = scanner.scan_to_matrix().await.expect("Scan should succeed");
+
+    assert!(!started.exists(), "a structure scan started a plugin");
+    assert_eq!(matrix.metadata.scan_level, ScanLevel::Structure);
+    assert!(matrix.metadata.scan_stats.plugins.is_empty());
+    let util = matrix
+        .files
+        .values()
+        .find(|f| f.relative_path == std::path::Path::new("util.py"))
+        .unwrap();
+    assert_eq!(util.exports[0].name, "helper");
+    assert_eq!(matrix.relationships.len(), 1);
+    assert_eq!(matrix.relationships[0].from_file, PathBuf::from("main.py"));
+    assert_eq!(matrix.relationships[0].to_file, PathBuf::from("util.py"));
+}
+
+#[tokio::test]
+async fn test_scan_routes_ambiguous_files_by_confidence() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let plugin_dir = TempDir::new().expect("Failed to create plugin dir");
+    fs::write(temp_dir.path().join("a.test"), "content")
+        .await
+        .unwrap();
+
+    let mut config = create_test_config();
+    config.input_plugins.clear();
+    for (name, confidence) in [("aaa_low", 0.3), ("zzz_high", 0.9)] {
+        let path = write_fake_plugin(plugin_dir.path(), name, confidence).await;
+        config.input_plugins.insert(
+            name.to_string(),
+            InputPluginConfig {
+                source: PluginSource::Local { path },
+                file_patterns: FilePatterns {
+                    extensions: vec![".test".to_string()],
+                    filenames: vec![],
+                    glob_patterns: None,
+                },
+                enabled: true,
+                config: None,
+                runtime: PluginRuntime::Python,
+            },
+        );
+    }
+
+    let scanner = ProjectScanner::new(config).with_root(temp_dir.path());
+    let probes = scanner.warm_up_plugins().await;
+    if probes.iter().any(|p| !p.is_ready()) {
+        eprintln!("Skipping: python is not available to run the fake plugins");
+        return;
+    }
+
+    let matrix = scanner.scan_to_matrix().await.expect("Scan should succeed");
+    let node = matrix
+        .files
+        .values()
+        .find(|f| f.relative_path == std::path::Path::new("a.test"))
+        .expect("a.test should be in the matrix");
+
+    // Pattern order alone would have picked aaa_low
+    assert_eq!(node.plugin, "zzz_high");
+    let confidence = node.metadata["plugin_confidence"].as_f64().unwrap();
+    assert!((confidence - 0.9).abs() < 1e-6);
+
+    // The fake plugin can't analyze, so its file counts as failed
+    let stats = &matrix.metadata.scan_stats;
+    assert_eq!(stats.plugins["zzz_high"].files, 1);
+    assert_eq!(stats.plugins["zzz_high"].failed, 1);
+    assert!(!stats.plugins.contains_key("aaa_low"));
+    assert_eq!(stats.failed_files, 1);
+    assert_eq!(stats.cache_misses, 0);
+}
+
+#[tokio::test]
+async fn test_scan_past_time_limit_keeps_files_without_analysis() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let plugin_dir = TempDir::new().expect("Failed to create plugin dir");
+    for name in ["a.test", "b.test"] {
+        fs::write(temp_dir.path().join(name), "content")
+            .await
+            .unwrap();
+    }
+
+    let mut config = create_test_config();
+    config.input_plugins.clear();
+    let path = write_fake_plugin(plugin_dir.path(), "fake", 1.0).await;
+    config.input_plugins.insert(
+        "fake".to_string(),
+        InputPluginConfig {
+            source: PluginSource::Local { path },
+            file_patterns: FilePatterns {
+                extensions: vec![".test".to_string()],
+                filenames: vec![],
+                glob_patterns: None,
+            },
+            enabled: true,
+            config: None,
+            runtime: PluginRuntime::Python,
+        },
+    );
+    // Out of time before any plugin runs
+    config.scanning.max_duration_minutes = Some(0);
+
+    let scanner = ProjectScanner::new(config).with_root(temp_dir.path());
+    if scanner
+        .warm_up_plugins()
+        .await
+        .iter()
+        .any(|p| !p.is_ready())
+    {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+
+    let matrix = scanner
+        .scan_to_matrix()
+        .await
+        .expect("Scan should finish despite the limit");
+    assert_eq!(matrix.files.len(), 2);
+    let stats = &matrix.metadata.scan_stats;
+    assert_eq!(stats.skipped_deadline, 2);
+    assert_eq!(stats.failed_files, 0);
+}
+
+#[tokio::test]
+async fn test_scan_reads_within_in_flight_budget() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let plugin_dir = TempDir::new().expect("Failed to create plugin dir");
+    // Any two of these are over the budget, so batches go out one file at a time
+    for name in ["a.test", "b.test", "c.test"] {
+        fs::write(temp_dir.path().join(name), "x".repeat(700 * 1024))
+            .await
+            .unwrap();
+    }
+
+    let mut config = create_test_config();
+    config.input_plugins.clear();
+    let path = write_fake_plugin(plugin_dir.path(), "fake", 1.0).await;
+    config.input_plugins.insert(
+        "fake".to_string(),
+        InputPluginConfig {
+            source: PluginSource::Local { path },
+            file_patterns: FilePatterns {
+                extensions: vec![".test".to_string()],
+                filenames: vec![],
+                glob_patterns: None,
+            },
+            enabled: true,
+            config: None,
+            runtime: PluginRuntime::Python,
+        },
+    );
+    config.scanning.max_in_flight_mb = Some(1);
+
+    let scanner = ProjectScanner::new(config).with_root(temp_dir.path());
+    if scanner
+        .warm_up_plugins()
+        .await
+        .iter()
+        .any(|p| !p.is_ready())
+    {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+
+    let matrix = scanner.scan_to_matrix().await.expect("Scan should succeed");
+    assert_eq!(matrix.files.len(), 3);
+    let stats = &matrix.metadata.scan_stats;
+    assert_eq!(stats.plugins["fake"].files, 3);
+    // The fake plugin can't analyze
+    assert_eq!(stats.plugins["fake"].failed, 3);
+}
+
+#[tokio::test]
+async fn test_scan_to_matrix_records_skipped_files() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(temp_dir.path().join("empty.txt"), "")
+        .await
+        .unwrap();
+    fs::write(temp_dir.path().join("big.txt"), "too large")
+        .await
+        .unwrap();
+    fs::write(temp_dir.path().join("debug.log"), "ignored")
+        .await
+        .unwrap();
+
+    let mut config = create_test_config();
+    config.input_plugins.clear();
+    config.scanning.max_file_size_mb = 0;
+    let scanner = ProjectScanner::new(config).with_root(temp_dir.path());
+    let matrix = scanner.scan_to_matrix().await.expect("Scan should succeed");
+
+    let stats = &matrix.metadata.scan_stats;
+    assert_eq!(matrix.files.len(), 1);
+    assert_eq!(stats.skipped_files, 2);
+    assert_eq!(stats.failed_files, 0);
+    assert!(stats.plugins.is_empty());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_scan_records_permission_denied_paths() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(temp_dir.path().join("open.txt"), "readable")
+        .await
+        .unwrap();
+    let secret = temp_dir.path().join("secret.txt");
+    fs::write(&secret, "unreadable").await.unwrap();
+    std::fs::set_permissions(&secret, std::fs::Permissions::from_mode(0o000)).unwrap();
+    let locked = temp_dir.path().join("locked");
+    fs::create_dir(&locked).await.unwrap();
+    fs::write(locked.join("inner.txt"), "hidden").await.unwrap();
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+
+    // TempDir can't clean up a directory it may not enter
+    let restore = || {
+        let _ = std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755));
+    };
+    if std::fs::File::open(&secret).is_ok() {
+        restore();
+        eprintln!("Skipping: permissions aren't enforced for this user");
+        return;
+    }
+
+    let mut config = create_test_config();
+    config.input_plugins.clear();
+    let matrix = ProjectScanner::new(config.clone())
+        .with_root(temp_dir.path())
+        .scan_to_matrix()
+        .await;
+    config.scanning.fail_on_permission_denied = true;
+    let strict = ProjectScanner::new(config)
+        .with_root(temp_dir.path())
+        .scan_to_matrix()
+        .await;
+    restore();
+
+    let matrix = matrix.expect("Scan should skip unreadable paths");
+    let mut denied = matrix.metadata.scan_stats.skipped_permission.clone();
+    denied.sort();
+    assert_eq!(
+        denied,
+        vec![PathBuf::from("locked"), PathBuf::from("secret.txt")]
+    );
+    assert_eq!(matrix.files.len(), 1);
+
+    let error = strict.expect_err("Strict scan should fail").to_string();
+    assert!(error.contains("Permission denied for 2 paths"), "{error}");
+}
+
+#[tokio::test]
+async fn test_rescan_reuses_cached_token_counts() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(temp_dir.path().join("a.txt"), "unchanged content")
+        .await
+        .unwrap();
+    fs::write(temp_dir.path().join("b.txt"), "first version")
+        .await
+        .unwrap();
+
+    let mut config = create_test_config();
+    config.input_plugins.clear();
+    let first = ProjectScanner::new(config.clone())
+        .with_root(temp_dir.path())
+        .scan_to_matrix()
+        .await
+        .expect("Scan should succeed");
+    let stats = &first.metadata.scan_stats;
+    assert_eq!((stats.token_cache_hits, stats.token_cache_misses), (0, 2));
+
+    fs::write(temp_dir.path().join("b.txt"), "second, longer version")
+        .await
+        .unwrap();
+    let second = ProjectScanner::new(config)
+        .with_root(temp_dir.path())
+        .scan_to_matrix()
+        .await
+        .expect("Scan should succeed");
+    let stats = &second.metadata.scan_stats;
+    assert_eq!((stats.token_cache_hits, stats.token_cache_misses), (1, 1));
+
+    let tokens = |matrix: &csd::core::matrix::ProjectMatrix, name: &str| {
+        matrix
+            .files
+            .values()
+            .find(|f| f.relative_path == std::path::Path::new(name))
+            .map(|f| f.token_info.total_tokens)
+            .unwrap()
+    };
+    assert_eq!(tokens(&first, "a.txt"), tokens(&second, "a.txt"));
+    assert!(tokens(&second, "b.txt") > tokens(&first, "b.txt"));
+}
+
+#[tokio::test]
+async fn test_basic_file_nodes_count_comment_tokens() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(
+        temp_dir.path().join("lib.rs"),
+        "/// Documented\nfn f() {}\n// note\n",
+    )
+    .await
+    .unwrap();
+    fs::write(temp_dir.path().join("notes.txt"), "/// not a comment here")
+        .await
+        .unwrap();
+
+    // The Rust plugin can't start, so lib.rs is kept without analysis
+    let mut config = create_test_config();
+    config.input_plugins.get_mut("rust").unwrap().source = PluginSource::Local {
+        path: "missing_plugin.py".to_string(),
+    };
+    let matrix = ProjectScanner::new(config)
+        .with_root(temp_dir.path())
+        .scan_to_matrix()
+        .await
+        .expect("Scan should succeed");
+
+    let token_info = |name: &str| {
+        matrix
+            .files
+            .values()
+            .find(|f| f.relative_path == std::path::Path::new(name))
+            .map(|f| f.token_info.clone())
+            .unwrap()
+    };
+    let rust = token_info("lib.rs");
+    assert!(rust.documentation_tokens > 0);
+    assert!(rust.comment_tokens > 0);
+    assert_eq!(
+        rust.code_tokens,
+        rust.total_tokens - rust.documentation_tokens - rust.comment_tokens
+    );
+    let text = token_info("notes.txt");
+    assert_eq!((text.documentation_tokens, text.comment_tokens), (0, 0));
+    assert_eq!(text.code_tokens, text.total_tokens);
+}
+
+#[tokio::test]
+async fn test_scan_to_matrix_with_several_roots() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let workspace = temp_dir.path();
+    for (dir, file) in [
+        ("backend", "main.txt"),
+        ("frontend", "main.txt"),
+        ("nested/frontend", "app.txt"),
+    ] {
+        fs::create_dir_all(workspace.join(dir)).await.unwrap();
+        fs::write(workspace.join(dir).join(file), "content")
+            .await
+            .unwrap();
+    }
+
+    let mut config = create_test_config();
+    config.input_plugins.clear();
+    let scanner = ProjectScanner::new(config)
+        .with_root(workspace)
+        .with_roots(vec![
+            workspace.join("backend"),
+            workspace.join("frontend"),
+            workspace.join("nested/frontend"),
+        ]);
+    let matrix = scanner.scan_to_matrix().await.expect("Scan should succeed");
+
+    let names: Vec<&str> = matrix
+        .metadata
+        .roots
+        .iter()
+        .map(|root| root.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["backend", "frontend", "frontend-2"]);
+
+    // Same relative file in two roots stays distinct
+    let mut relative: Vec<PathBuf> = matrix
+        .files
+        .values()
+        .map(|f| f.relative_path.clone())
+        .collect();
+    relative.sort();
+    assert_eq!(
+        relative,
+        vec![
+            PathBuf::from("backend/main.txt"),
+            PathBuf::from("frontend/main.txt"),
+            PathBuf::from("frontend-2/app.txt"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_explain_reports_first_exclusion() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join(".git")).await.unwrap();
+    fs::create_dir_all(root.join("build")).await.unwrap();
+    fs::create_dir_all(root.join("src/gen")).await.unwrap();
+    fs::create_dir_all(root.join(".config")).await.unwrap();
+    fs::write(root.join(".gitignore"), "build/\n*.gen.py\n")
+        .await
+        .unwrap();
+    fs::write(root.join("src/.gitignore"), "!keep.gen.py\n")
+        .await
+        .unwrap();
+    for file in [
+        "build/out.py",
+        "src/main.py",
+        "src/skip.gen.py",
+        "src/keep.gen.py",
+        "src/debug.log",
+        ".config/settings.py",
+        "big.py",
+    ] {
+        fs::write(root.join(file), "x").await.unwrap();
+    }
+
+    let mut config = create_test_config();
+    config.scanning.max_file_size_mb = 0;
+    let scanner = ProjectScanner::new(config).with_root(root);
+    let exclusion = |file: &str| scanner.explain(&root.join(file)).exclusion;
+
+    assert!(matches!(
+        exclusion("build/out.py"),
+        Some(Exclusion::IgnoreFile { ref pattern, .. }) if pattern == "build/"
+    ));
+    assert!(matches!(
+        exclusion("src/skip.gen.py"),
+        Some(Exclusion::IgnoreFile { ref pattern, ref source })
+            if pattern == "*.gen.py" && source.as_deref() == Some(root.join(".gitignore").as_path())
+    ));
+    // A deeper negation re-includes the file; it then hits the size limit
+    assert!(matches!(
+        exclusion("src/keep.gen.py"),
+        Some(Exclusion::TooLarge { size_bytes: 1, .. })
+    ));
+    assert_eq!(
+        exclusion("src/debug.log"),
+        Some(Exclusion::IgnorePattern {
+            pattern: "*.log".to_string()
+        })
+    );
+    assert_eq!(
+        exclusion(".config/settings.py"),
+        Some(Exclusion::Hidden {
+            component: ".config".to_string()
+        })
+    );
+    assert_eq!(exclusion("missing.py"), Some(Exclusion::Missing));
+    assert_eq!(exclusion("src"), Some(Exclusion::Directory));
+
+    let explanation = scanner.explain(&root.join("src/main.py"));
+    assert_eq!(explanation.plugin.as_deref(), Some("python"));
+    assert_eq!(
+        explanation.relative_path,
+        Some(PathBuf::from("src/main.py"))
+    );
+}
+
+#[tokio::test]
+async fn test_explain_scanned_file_and_outside_root() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let other_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(temp_dir.path().join("main.py"), "print('hi')")
+        .await
+        .unwrap();
+    fs::write(other_dir.path().join("main.py"), "print('hi')")
+        .await
+        .unwrap();
+
+    let scanner = ProjectScanner::new(create_test_config()).with_root(temp_dir.path());
+    let explanation = scanner.explain(&temp_dir.path().join("main.py"));
+    assert!(explanation.is_scanned());
+    assert!(explanation.is_text);
+    assert_eq!(explanation.candidates, vec!["python".to_string()]);
+
+    let explanation = scanner.explain(&other_dir.path().join("main.py"));
+    assert_eq!(explanation.exclusion, Some(Exclusion::OutsideRoots));
+}
+
+#[test]
+fn test_truncate_to_tokens() {
+    assert!(truncate_to_tokens("short", 10, TruncationStrategy::Head).is_none());
+
+    let content = format!("{}{}{}", "a".repeat(400), "b".repeat(400), "c".repeat(400));
+    let head = truncate_to_tokens(&content, 30, TruncationStrategy::Head).unwrap();
+    assert_eq!(head, "a".repeat(120));
+
+    let sample = truncate_to_tokens(&content, 30, TruncationStrategy::Sample).unwrap();
+    let parts: Vec<&str> = sample.split('\n').collect();
+    assert_eq!(parts, vec!["a".repeat(39), "b".repeat(39), "c".repeat(39)]);
+
+    // Never splits a multi-byte character
+    let wide = "é".repeat(100);
+    let head = truncate_to_tokens(&wide, 5, TruncationStrategy::Head).unwrap();
+    assert_eq!(head, "é".repeat(10));
+}
+
+#[tokio::test]
+async fn test_scan_to_matrix_caps_file_tokens() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(temp_dir.path().join("dump.txt"), "word ".repeat(10_000))
+        .await
+        .unwrap();
+    fs::write(temp_dir.path().join("small.txt"), "word")
+        .await
+        .unwrap();
+
+    let mut config = create_test_config();
+    config.input_plugins.clear();
+    config.scanning.max_file_tokens = Some(100);
+    let scanner = ProjectScanner::new(config).with_root(temp_dir.path());
+    let matrix = scanner.scan_to_matrix().await.expect("Scan should succeed");
+
+    let node = |name: &str| {
+        matrix
+            .files
+            .values()
+            .find(|f| f.relative_path == std::path::Path::new(name))
+            .unwrap()
+    };
+    assert!(node("dump.txt").truncated);
+    assert!(node("dump.txt").token_info.total_tokens <= 100);
+    assert!(!node("small.txt").truncated);
+}
+
+#[tokio::test]
+async fn test_scan_to_matrix_assigns_categories() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    for (path, content) in [
+        ("src/main.txt", "fn main() {}"),
+        ("src/gen/models.txt", "generated"),
+        ("tests/it.txt", "assert"),
+        ("README.md", "# Project"),
+    ] {
+        fs::create_dir_all(root.join(path).parent().unwrap())
+            .await
+            .unwrap();
+        fs::write(root.join(path), content).await.unwrap();
+    }
+
+    let mut config = create_test_config();
+    config.input_plugins.clear();
+    config.categories.push(CategoryRule {
+        category: FileCategory::Generated,
+        patterns: vec!["src/gen/**".to_string()],
+    });
+    let scanner = ProjectScanner::new(config).with_root(root);
+    let matrix = scanner.scan_to_matrix().await.expect("Scan should succeed");
+
+    let category = |name: &str| {
+        matrix
+            .files
+            .values()
+            .find(|f| f.relative_path == std::path::Path::new(name))
+            .and_then(|f| f.category)
+    };
+    assert_eq!(category("src/main.txt"), Some(FileCategory::Source));
+    assert_eq!(
+        category("src/gen/models.txt"),
+        Some(FileCategory::Generated)
+    );
+    assert_eq!(category("tests/it.txt"), Some(FileCategory::Test));
+    assert_eq!(category("README.md"), Some(FileCategory::Docs));
+}
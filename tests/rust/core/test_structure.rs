@@ -0,0 +1,294 @@
+use std::path::{Path, PathBuf};
+
+use csd::core::matrix::{ImportType, ProjectMatrix};
+use csd::core::structure::{extract_structure, link_imports, STRUCTURE_SOURCE};
+
+fn exported(exports: &[csd::core::matrix::Export]) -> Vec<(&str, Option<&str>)> {
+    exports
+        .iter()
+        .map(|e| (e.name.as_str(), e.kind.as_deref()))
+        .collect()
+}
+
+#[test]
+fn test_python_imports_and_exports() {
+    let content = r#"import os, numpy as np
+from .models import (
+    User,
+    Group as G,
+)
+from pkg.util import helper  # trailing comment
+
+VERSION = "1.0"
+_private = 1
+
+class Service:
+    def method(self):
+        import json
+
+async def fetch():
+    pass
+
+def _hidden():
+    pass
+"#;
+    let (imports, exports) = extract_structure(Path::new("app/main.py"), content);
+
+    let modules: Vec<(&str, u32)> = imports
+        .iter()
+        .map(|i| (&*i.module, i.line_number))
+        .collect();
+    assert_eq!(
+        modules,
+        vec![
+            ("os", 1),
+            ("numpy", 1),
+            (".models", 2),
+            ("pkg.util", 6),
+            ("json", 13)
+        ]
+    );
+    assert_eq!(imports[1].alias.as_deref(), Some("np"));
+    assert_eq!(imports[2].items, vec!["User", "Group"]);
+    assert_eq!(imports[2].import_type, ImportType::Relative);
+    assert_eq!(imports[3].items, vec!["helper"]);
+    assert_eq!(
+        exported(&exports),
+        vec![
+            ("VERSION", Some("variable")),
+            ("Service", Some("class")),
+            ("fetch", Some("function")),
+        ]
+    );
+}
+
+#[test]
+fn test_javascript_imports_and_exports() {
+    let content = r#"import React, { useState } from "react";
+import {
+  a,
+  b as c,
+} from './lib';
+import "./styles.css";
+const fs = require("node:fs");
+export { helper as help } from "./helpers";
+export default function App() {}
+export const answer = 42;
+export async function load() {}
+export interface Props {}
+"#;
+    let (imports, exports) = extract_structure(Path::new("web/App.tsx"), content);
+
+    let modules: Vec<(&str, &ImportType)> = imports
+        .iter()
+        .map(|i| (&*i.module, &i.import_type))
+        .collect();
+    assert_eq!(
+        modules,
+        vec![
+            ("react", &ImportType::ThirdParty),
+            ("./lib", &ImportType::Relative),
+            ("./styles.css", &ImportType::Relative),
+            ("node:fs", &ImportType::Standard),
+            ("./helpers", &ImportType::Relative),
+        ]
+    );
+    assert_eq!(imports[0].items, vec!["React", "useState"]);
+    assert_eq!(imports[1].items, vec!["a", "b"]);
+    assert_eq!(imports[1].line_number, 2);
+    assert_eq!(
+        exported(&exports),
+        vec![
+            ("helper", Some("import")),
+            ("default", Some("default")),
+            ("answer", Some("variable")),
+            ("load", Some("function")),
+            ("Props", Some("interface")),
+        ]
+    );
+    assert_eq!(exports[0].alias.as_deref(), Some("help"));
+}
+
+#[test]
+fn test_rust_imports_and_exports() {
+    let content = r#"use std::collections::HashMap;
+use crate::core::{matrix, scanner::ProjectScanner};
+use serde::Serialize;
+pub use self::config::Config;
+
+pub mod config;
+mod helpers;
+
+pub struct Scanner;
+pub(crate) fn internal() {}
+pub async fn run() {}
+
+impl Scanner {
+    pub fn new() -> Self { Scanner }
+}
+
+#[cfg(test)]
+mod tests {}
+"#;
+    let (imports, exports) = extract_structure(Path::new("src/lib.rs"), content);
+
+    let modules: Vec<(&str, &ImportType)> = imports
+        .iter()
+        .map(|i| (&*i.module, &i.import_type))
+        .collect();
+    assert_eq!(
+        modules,
+        vec![
+            ("std::collections::HashMap", &ImportType::Standard),
+            ("crate::core", &ImportType::Local),
+            ("serde::Serialize", &ImportType::ThirdParty),
+            ("self::config::Config", &ImportType::Relative),
+            ("config", &ImportType::Local),
+            ("helpers", &ImportType::Local),
+        ]
+    );
+    assert_eq!(imports[1].items, vec!["matrix", "scanner::ProjectScanner"]);
+    assert_eq!(
+        exported(&exports),
+        vec![
+            ("Config", Some("import")),
+            ("config", Some("module")),
+            ("Scanner", Some("struct")),
+            ("run", Some("function")),
+        ]
+    );
+}
+
+#[test]
+fn test_go_imports_and_exports() {
+    let content = r#"package server
+
+import "fmt"
+import (
+    "net/http"
+    mux "github.com/gorilla/mux"
+)
+
+type Server struct{}
+func (s *Server) Start() {}
+func New() *Server { return nil }
+func helper() {}
+const Version = "1"
+"#;
+    let (imports, exports) = extract_structure(Path::new("server.go"), content);
+
+    let modules: Vec<(&str, &ImportType)> = imports
+        .iter()
+        .map(|i| (&*i.module, &i.import_type))
+        .collect();
+    assert_eq!(
+        modules,
+        vec![
+            ("fmt", &ImportType::Standard),
+            ("net/http", &ImportType::Standard),
+            ("github.com/gorilla/mux", &ImportType::ThirdParty),
+        ]
+    );
+    assert_eq!(imports[2].alias.as_deref(), Some("mux"));
+    assert_eq!(
+        exported(&exports),
+        vec![
+            ("Server", Some("type")),
+            ("New", Some("function")),
+            ("Version", Some("constant")),
+        ]
+    );
+}
+
+#[test]
+fn test_other_files_have_no_structure() {
+    let (imports, exports) = extract_structure(Path::new("README.md"), "import x from 'y'\n");
+    assert!(imports.is_empty());
+    assert!(exports.is_empty());
+}
+
+fn add_file(matrix: &mut ProjectMatrix, relative: &str, content: &str) {
+    let relative = PathBuf::from(relative);
+    let (imports, exports) = extract_structure(&relative, content);
+    matrix.add_file(csd::core::matrix::FileNode {
+        path: PathBuf::from("/project").join(&relative),
+        relative_path: relative,
+        hash: String::new(),
+        size_bytes: content.len() as u64,
+        plugin: "unknown".into(),
+        language: None,
+        is_text: true,
+        elements: Vec::new(),
+        imports,
+        exports,
+        file_summary: None,
+        token_info: csd::core::matrix::TokenInfo {
+            total_tokens: 0,
+            code_tokens: 0,
+            documentation_tokens: 0,
+            comment_tokens: 0,
+        },
+        metadata: serde_json::Value::Null,
+        truncated: false,
+        category: None,
+        coverage: None,
+    });
+}
+
+#[test]
+fn test_link_imports_resolves_project_files() {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/project"));
+    add_file(
+        &mut matrix,
+        "app/main.py",
+        "from .models import User\nimport app.util\nimport requests\n",
+    );
+    add_file(&mut matrix, "app/models.py", "class User:\n    pass\n");
+    add_file(&mut matrix, "app/util/__init__.py", "");
+    add_file(&mut matrix, "web/index.ts", "import { a } from './lib';\n");
+    add_file(&mut matrix, "web/lib/index.ts", "export const a = 1;\n");
+    add_file(&mut matrix, "src/main.rs", "mod scanner;\n");
+    add_file(&mut matrix, "src/scanner/mod.rs", "mod walk;\n");
+    add_file(&mut matrix, "src/scanner/walk.rs", "");
+
+    link_imports(&mut matrix);
+
+    let edges: Vec<(String, String)> = matrix
+        .relationships
+        .iter()
+        .map(|r| {
+            assert_eq!(r.source_plugin.as_deref(), Some(STRUCTURE_SOURCE));
+            (
+                r.from_file.display().to_string(),
+                r.to_file.display().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        edges,
+        vec![
+            ("app/main.py".to_string(), "app/models.py".to_string()),
+            (
+                "app/main.py".to_string(),
+                "app/util/__init__.py".to_string()
+            ),
+            ("src/main.rs".to_string(), "src/scanner/mod.rs".to_string()),
+            (
+                "src/scanner/mod.rs".to_string(),
+                "src/scanner/walk.rs".to_string()
+            ),
+            ("web/index.ts".to_string(), "web/lib/index.ts".to_string()),
+        ]
+    );
+
+    let main = &matrix.files[Path::new("/project/app/main.py")];
+    let types: Vec<&ImportType> = main.imports.iter().map(|i| &i.import_type).collect();
+    assert_eq!(
+        types,
+        vec![
+            &ImportType::Relative,
+            &ImportType::Local,
+            &ImportType::ThirdParty
+        ]
+    );
+}
@@ -0,0 +1,214 @@
+use std::path::PathBuf;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use csd::core::hotspots::HotspotReport;
+use csd::core::matrix::{CodeElement, ElementType, ProjectMatrix};
+use csd::core::rules::RuleReport;
+use csd::core::suggestions::{
+    draft_suggestions, evidence_for, parse_suggestions, SuggestedAction, SuggestionTarget,
+};
+use csd::llm::client::LlmClient;
+use csd::plugins::interface::{FindingSeverity, RuleFinding};
+use csd::utils::config::{Config, QualityConfig};
+
+use super::test_matrix::{create_test_file_node, create_test_relationship};
+
+fn function(name: &str, lines: (u32, u32), complexity: u32) -> CodeElement {
+    CodeElement {
+        element_type: ElementType::Function,
+        name: name.to_string(),
+        qualified_name: None,
+        parent: None,
+        signature: None,
+        line_start: lines.0,
+        line_end: lines.1,
+        summary: None,
+        complexity_score: Some(complexity),
+        calls: vec!["parse".to_string(), "render".to_string()],
+        metadata: serde_json::Value::Null,
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    }
+}
+
+fn sample_matrix() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    let mut big = create_test_file_node("src/big.rs", "rust");
+    big.token_info.total_tokens = 900;
+    big.elements = vec![
+        function("run", (1, 120), 25),
+        function("fine", (130, 140), 2),
+    ];
+    matrix.add_file(big);
+    matrix.add_file(create_test_file_node("src/lib.rs", "rust"));
+    matrix.add_relationship(create_test_relationship("src/big.rs", "src/lib.rs"));
+    matrix
+}
+
+fn run_target() -> SuggestionTarget {
+    SuggestionTarget::Function {
+        file: PathBuf::from("src/big.rs"),
+        name: "run".to_string(),
+    }
+}
+
+#[test]
+fn test_evidence_for_function() {
+    let evidence = evidence_for(&sample_matrix(), &run_target());
+    let facts: Vec<&str> = evidence.iter().map(|e| e.fact.as_str()).collect();
+    assert_eq!(
+        facts,
+        vec![
+            "src/big.rs has 900 tokens in 2 elements",
+            "run spans lines 1-120 (120 lines, complexity 25)",
+            "run calls parse, render",
+            "src/big.rs depends on 1 files: src/lib.rs (import)",
+        ]
+    );
+    assert_eq!(evidence[0].id, "E1");
+    assert_eq!(evidence[1].line, Some(1));
+    assert!(evidence
+        .iter()
+        .all(|e| e.file.as_path() == std::path::Path::new("src/big.rs")));
+}
+
+#[test]
+fn test_parse_suggestions_keeps_cited_and_valid() {
+    let matrix = sample_matrix();
+    let evidence = evidence_for(&matrix, &run_target());
+    let answer = r#"Sure! ```json
+{"suggestions": [
+  {"summary": "Split run into parsing and rendering", "evidence": ["E2", "E3", "E9"],
+   "action": {"kind": "split_function", "file": "src/big.rs", "function": "run",
+              "into": ["parse_input", "render_output"]}},
+  {"summary": "Move run next to its helpers", "evidence": ["E4"],
+   "action": {"kind": "move_element", "file": "src/big.rs", "element": "missing",
+              "to": "src/lib.rs"}},
+  {"summary": "Rewrite it", "evidence": []},
+  {"summary": "Move the file", "evidence": ["E7"],
+   "action": {"kind": "move_file", "from": "src/big.rs", "to": "src/app/big.rs"}}
+]}
+```"#;
+
+    let suggestions = parse_suggestions(answer, &matrix, &evidence).unwrap();
+    assert_eq!(suggestions.len(), 2);
+    assert!(suggestions.iter().all(|s| s.drafted));
+
+    // Unknown evidence ids are dropped, the known ones kept
+    let ids: Vec<&str> = suggestions[0]
+        .evidence
+        .iter()
+        .map(|e| e.id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["E2", "E3"]);
+    assert_eq!(
+        suggestions[0].action,
+        Some(SuggestedAction::SplitFunction {
+            file: PathBuf::from("src/big.rs"),
+            function: "run".to_string(),
+            into: vec!["parse_input".to_string(), "render_output".to_string()],
+        })
+    );
+
+    // An action on an element that doesn't exist leaves just the advice
+    assert_eq!(suggestions[1].summary, "Move run next to its helpers");
+    assert!(suggestions[1].action.is_none());
+
+    assert!(parse_suggestions("no idea", &matrix, &evidence).is_err());
+}
+
+#[test]
+fn test_llm_client_rejects_remote_endpoint_offline() {
+    let mut config = Config::default();
+    config.llm.base_url = "https://llm.example.com".to_string();
+    let err = LlmClient::new(&config.llm, true).err().unwrap();
+    assert!(err.to_string().contains("Offline mode"), "{err}");
+    assert!(LlmClient::new(&config.llm, false).is_ok());
+
+    config.llm.provider = "unknown".to_string();
+    assert!(LlmClient::new(&config.llm, false).is_err());
+}
+
+// Answers every request with `answer` as an Ollama generate response
+async fn mock_ollama(answer: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                let complete = text.split_once("\r\n\r\n").is_some_and(|(head, body)| {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    body.len() >= length
+                });
+                if n == 0 || complete {
+                    break;
+                }
+            }
+            let body = serde_json::json!({ "response": answer, "done": true }).to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn test_draft_suggestions_attaches_to_top_findings() {
+    let matrix = sample_matrix();
+    let thresholds = QualityConfig {
+        max_file_tokens: 300,
+        max_function_lines: 50,
+        max_function_complexity: 10,
+        top: 10,
+    };
+    let mut hotspots = HotspotReport::from_matrix(&matrix, &thresholds);
+    let mut rules = RuleReport {
+        findings: vec![RuleFinding {
+            rule: "naming/short".to_string(),
+            severity: FindingSeverity::Warning,
+            message: "Name is too short".to_string(),
+            file: PathBuf::from("src/big.rs"),
+            element: Some("run".to_string()),
+            line: None,
+            suggestions: Vec::new(),
+        }],
+        ..RuleReport::default()
+    };
+
+    let mut config = Config::default();
+    config.llm.base_url =
+        mock_ollama(r#"{"suggestions": [{"summary": "Split it up", "evidence": ["E1"]}]}"#).await;
+    let client = LlmClient::new(&config.llm, true).unwrap();
+
+    // Two slots: the long function, then the big file; the rule warning misses out
+    let summary = draft_suggestions(&client, &matrix, &mut hotspots, &mut rules, 2)
+        .await
+        .unwrap();
+    assert_eq!(
+        (summary.findings, summary.suggestions, summary.failed),
+        (2, 2, 0)
+    );
+    assert_eq!(hotspots.functions[0].suggestions[0].summary, "Split it up");
+    assert_eq!(hotspots.files[0].suggestions.len(), 1);
+    assert!(rules.findings[0].suggestions.is_empty());
+}
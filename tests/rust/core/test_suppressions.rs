@@ -0,0 +1,151 @@
+use chrono::NaiveDate;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use csd::core::hotspots::HotspotReport;
+use csd::core::matrix::{CodeElement, ElementType, ProjectMatrix};
+use csd::core::suppressions::{
+    pragmas_in, QualityCheck, SuppressedBy, Suppressions, SUPPRESSIONS_FILE,
+};
+use csd::utils::config::QualityConfig;
+
+use super::test_matrix::create_test_file_node;
+
+fn function(name: &str, lines: (u32, u32), complexity: u32) -> CodeElement {
+    CodeElement {
+        element_type: ElementType::Function,
+        name: name.to_string(),
+        qualified_name: None,
+        parent: None,
+        signature: None,
+        line_start: lines.0,
+        line_end: lines.1,
+        summary: None,
+        complexity_score: Some(complexity),
+        calls: vec![],
+        metadata: serde_json::Value::Null,
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    }
+}
+
+fn thresholds() -> QualityConfig {
+    QualityConfig {
+        max_file_tokens: 300,
+        max_function_lines: 50,
+        max_function_complexity: 10,
+        top: 10,
+    }
+}
+
+#[test]
+fn test_pragmas_in_comments() {
+    let source = "\
+// csd-ignore-file: file-size -- generated
+fn a() {}
+/* csd-ignore: complexity, function-length */
+let s = \"csd-ignored\"; // csd-ignored isn't a pragma
+// csd-ignore
+// csd-ignore: nonsense
+";
+    let pragmas = pragmas_in(Path::new("lib.rs"), source);
+    assert_eq!(pragmas.len(), 3);
+    assert!(pragmas[0].file_wide);
+    assert_eq!(pragmas[0].checks, vec![QualityCheck::FileSize]);
+    assert_eq!(pragmas[0].reason.as_deref(), Some("generated"));
+    assert_eq!(pragmas[1].line, 3);
+    assert_eq!(
+        pragmas[1].checks,
+        vec![QualityCheck::Complexity, QualityCheck::FunctionLength]
+    );
+    // Bare pragmas cover every check
+    assert_eq!(pragmas[2].line, 5);
+    assert!(pragmas[2].checks.is_empty());
+
+    let python = "# csd-ignore: complexity\ndef f():\n    pass\n";
+    assert_eq!(pragmas_in(Path::new("f.py"), python)[0].line, 1);
+    assert!(pragmas_in(Path::new("notes.txt"), "csd-ignore").is_empty());
+}
+
+#[test]
+fn test_hotspots_leave_out_suppressed_offenders() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    let mut source = String::from("fn parse() {\n    // csd-ignore: complexity -- state machine\n");
+    source.push_str(&"    step();\n".repeat(200));
+    std::fs::write(root.join("src/parser.rs"), source).unwrap();
+    std::fs::write(
+        root.join(SUPPRESSIONS_FILE),
+        "suppressions:
+  - path: src/legacy
+    checks: [file-size]
+    reason: rewrite planned
+    expires: 2030-01-01
+  - path: 'src/**/*.rs'
+    element: Billing::charge
+    expires: 2020-01-01
+",
+    )
+    .unwrap();
+
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    let mut parser = create_test_file_node("src/parser.rs", "rust");
+    parser.elements = vec![
+        // Complex but short, and suppressed by the pragma inside it
+        function("parse", (1, 30), 40),
+        // Long and complex; only complexity would be covered
+        function("render", (40, 160), 40),
+    ];
+    matrix.add_file(parser);
+    let mut legacy = create_test_file_node("src/legacy/old.rs", "rust");
+    legacy.token_info.total_tokens = 5000;
+    let mut charge = function("charge", (1, 90), 2);
+    charge.qualified_name = Some("Billing::charge".to_string());
+    legacy.elements = vec![charge];
+    matrix.add_file(legacy);
+
+    let suppressions = Suppressions::load(root)
+        .unwrap()
+        .with_today(NaiveDate::from_ymd_opt(2026, 6, 1).unwrap());
+    let report = HotspotReport::with_suppressions(&matrix, &thresholds(), &suppressions);
+
+    assert_eq!(report.files_over_limit, 0);
+    // `render` still counts, and so does `charge`: its entry has expired
+    let names: Vec<&str> = report.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["render", "Billing::charge"]);
+    assert_eq!(report.expired_suppressions.len(), 1);
+    assert_eq!(
+        report.expired_suppressions[0].element.as_deref(),
+        Some("Billing::charge")
+    );
+
+    assert_eq!(report.suppressed.len(), 2);
+    assert_eq!(
+        report.suppressed[0].path,
+        PathBuf::from("src/legacy/old.rs")
+    );
+    assert_eq!(report.suppressed[0].checks, vec![QualityCheck::FileSize]);
+    assert_eq!(
+        report.suppressed[0].by,
+        SuppressedBy::File {
+            reason: Some("rewrite planned".to_string()),
+            expires: NaiveDate::from_ymd_opt(2030, 1, 1),
+        }
+    );
+    assert_eq!(report.suppressed[1].name.as_deref(), Some("parse"));
+    assert_eq!(
+        report.suppressed[1].by,
+        SuppressedBy::Pragma {
+            line: 2,
+            reason: Some("state machine".to_string()),
+        }
+    );
+
+    // Without suppressions everything counts
+    let unsuppressed = HotspotReport::from_matrix(&matrix, &thresholds());
+    assert_eq!(unsuppressed.files_over_limit, 1);
+    assert_eq!(unsuppressed.functions_over_limit, 3);
+    assert!(unsuppressed.suppressed.is_empty());
+}
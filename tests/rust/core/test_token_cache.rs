@@ -0,0 +1,71 @@
+use tempfile::TempDir;
+use tokio::fs;
+
+use csd::core::token_cache::{TokenCache, TokenCounts, TOKENIZER_VERSION};
+
+#[tokio::test]
+async fn test_token_cache_roundtrip() {
+    let temp_dir = TempDir::new().unwrap();
+    let counts = TokenCounts::of("fn main() { println!(\"hello\"); }", None);
+
+    let cache = TokenCache::default();
+    cache.load(temp_dir.path()).await;
+    assert_eq!(cache.get("abc"), None);
+    cache.insert("abc", counts);
+    cache.save(temp_dir.path()).await.unwrap();
+
+    let reloaded = TokenCache::default();
+    reloaded.load(temp_dir.path()).await;
+    assert_eq!(reloaded.get("abc"), Some(counts));
+    assert_eq!(reloaded.get("def"), None);
+    assert_eq!(reloaded.stats(), (1, 1));
+}
+
+#[tokio::test]
+async fn test_token_cache_keeps_only_entries_used_by_the_scan() {
+    let temp_dir = TempDir::new().unwrap();
+    let cache = TokenCache::default();
+    cache.insert("kept", TokenCounts::of("kept", None));
+    cache.insert("removed", TokenCounts::of("removed", None));
+    cache.save(temp_dir.path()).await.unwrap();
+
+    let next_scan = TokenCache::default();
+    next_scan.load(temp_dir.path()).await;
+    assert!(next_scan.get("kept").is_some());
+    next_scan.save(temp_dir.path()).await.unwrap();
+
+    let reloaded = TokenCache::default();
+    reloaded.load(temp_dir.path()).await;
+    assert!(reloaded.get("kept").is_some());
+    assert!(reloaded.get("removed").is_none());
+}
+
+#[tokio::test]
+async fn test_token_cache_ignores_other_versions_and_corrupt_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = TokenCache::path(temp_dir.path());
+    let outdated = serde_json::json!({
+        "version": TOKENIZER_VERSION + 1,
+        "entries": { "abc": { "tokens": 1, "code_tokens": 1, "documentation_tokens": 0, "comment_tokens": 0 } }
+    });
+    fs::write(&path, outdated.to_string()).await.unwrap();
+
+    let cache = TokenCache::default();
+    cache.load(temp_dir.path()).await;
+    assert_eq!(cache.get("abc"), None);
+
+    fs::write(&path, "not json").await.unwrap();
+    cache.load(temp_dir.path()).await;
+    assert_eq!(cache.get("abc"), None);
+}
+
+#[tokio::test]
+async fn test_get_or_compute_reads_content_only_on_a_miss() {
+    let cache = TokenCache::default();
+    let computed = cache.get_or_compute("abc", None, || Some("let x = 1;".to_string()));
+    assert_eq!(computed, Some(TokenCounts::of("let x = 1;", None)));
+
+    let cached = cache.get_or_compute("abc", None, || panic!("content read on a cache hit"));
+    assert_eq!(cached, computed);
+    assert_eq!(cache.get_or_compute("def", None, || None), None);
+}
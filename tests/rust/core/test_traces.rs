@@ -0,0 +1,181 @@
+use std::path::{Path, PathBuf};
+
+use csd::core::matrix::{
+    CodeElement, ElementType, FileNode, ProjectMatrix, Relationship, RelationshipType,
+};
+use csd::core::traces::{apply_traces, Frame, TraceData, TraceFormat, TRACE_SOURCE};
+
+use super::test_matrix::{create_test_file_node, create_test_relationship};
+
+fn function(name: &str, parent: Option<&str>, lines: (u32, u32)) -> CodeElement {
+    CodeElement {
+        element_type: ElementType::Function,
+        name: name.to_string(),
+        qualified_name: parent.map(|parent| format!("{parent}.{name}")),
+        parent: parent.map(str::to_string),
+        signature: None,
+        line_start: lines.0,
+        line_end: lines.1,
+        summary: None,
+        complexity_score: None,
+        calls: vec![],
+        metadata: serde_json::Value::Null,
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    }
+}
+
+fn file(relative: &str, elements: Vec<CodeElement>) -> FileNode {
+    let mut node = create_test_file_node(relative, "python");
+    node.path = Path::new("/project").join(relative);
+    node.elements = elements;
+    node
+}
+
+fn key(relative: &str) -> String {
+    format!("/project/{relative}")
+}
+
+fn sample_matrix() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/project"));
+    matrix.add_file(file("app.py", vec![function("main", None, (1, 10))]));
+    matrix.add_file(file(
+        "parser.py",
+        vec![
+            function("Parser", None, (1, 30)),
+            function("parse", Some("Parser"), (5, 20)),
+        ],
+    ));
+    matrix.add_file(file("util.py", vec![function("trim", None, (1, 3))]));
+    matrix.add_file(file("unused.py", vec![function("legacy", None, (1, 3))]));
+    matrix.add_relationship(create_test_relationship(&key("app.py"), &key("parser.py")));
+    matrix.add_relationship(create_test_relationship(&key("app.py"), &key("unused.py")));
+    matrix
+}
+
+fn import_edge<'a>(matrix: &'a ProjectMatrix, to: &str) -> &'a Relationship {
+    matrix
+        .relationships
+        .iter()
+        .find(|r| {
+            r.relationship_type == RelationshipType::Import && r.to_file == Path::new(&key(to))
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_parse_folded_stacks() {
+    let folded = "\
+# py-spy record --format raw
+<module> (/ci/app.py:12);main (/ci/app.py:3);parse (/ci/parser.py:8) 40
+main (/ci/app.py:3);parse (/ci/parser.py:8);trim (/ci/util.py:2) 10
+main (/ci/app.py:3);main (/ci/app.py:5)
+perf;[unknown];Parser::parse+0x1a 2
+";
+    assert_eq!(TraceFormat::detect(folded), TraceFormat::Folded);
+    let data = TraceData::parse(folded, TraceFormat::Folded).unwrap();
+
+    let main = Frame {
+        function: "main".to_string(),
+        file: Some(PathBuf::from("/ci/app.py")),
+        line: Some(3),
+    };
+    let parse = Frame {
+        function: "parse".to_string(),
+        file: Some(PathBuf::from("/ci/parser.py")),
+        line: Some(8),
+    };
+    assert_eq!(data.calls[&(main.clone(), parse)], 50);
+    // Frames on different lines of one function are different frames
+    assert_eq!(
+        data.calls
+            .keys()
+            .filter(|(caller, _)| *caller == main)
+            .count(),
+        2
+    );
+    // Unknown frames drop out, leaving perf's symbol without its offset
+    let symbol = Frame {
+        function: "Parser::parse".to_string(),
+        file: None,
+        line: None,
+    };
+    assert!(data
+        .calls
+        .keys()
+        .any(|(caller, callee)| caller.function == "perf" && *callee == symbol));
+
+    assert!(TraceData::parse("\n# nothing\n", TraceFormat::Folded).is_err());
+}
+
+#[test]
+fn test_apply_traces_weights_exercised_edges() {
+    let mut matrix = sample_matrix();
+    let json = r#"{"calls": [
+        {"caller": {"function": "main", "file": "app.py", "line": 4},
+         "callee": {"function": "parse", "file": "parser.py", "line": 9}, "count": 100},
+        {"caller": {"function": "Parser.parse"}, "callee": {"function": "trim"}, "count": 3},
+        {"caller": {"function": "main", "file": "app.py"},
+         "callee": {"function": "mystery"}},
+        {"caller": {"function": "main", "file": "app.py"},
+         "callee": {"function": "main", "file": "app.py", "line": 2}}
+    ]}"#;
+    assert_eq!(TraceFormat::detect(json), TraceFormat::Json);
+    let data = TraceData::parse(json, TraceFormat::detect(json)).unwrap();
+
+    let import = apply_traces(&mut matrix, &data);
+    assert_eq!(import.calls, 103);
+    assert_eq!(import.added, 2);
+    assert_eq!(import.strengthened, 1);
+    assert_eq!(import.unmatched_frames, 1);
+    assert_eq!(import.unexercised_imports, 1);
+
+    // The import app -> parser is hot; the one to unused.py stays as scanned
+    assert_eq!(import_edge(&matrix, "parser.py").runtime_calls, Some(100));
+    assert_eq!(import_edge(&matrix, "parser.py").strength, 1.0);
+    assert_eq!(import_edge(&matrix, "unused.py").runtime_calls, None);
+    assert_eq!(import_edge(&matrix, "unused.py").strength, 0.8);
+
+    let calls: Vec<(&str, &str, Option<u64>)> = matrix
+        .relationships
+        .iter()
+        .filter(|r| r.relationship_type == RelationshipType::Call)
+        .map(|r| {
+            assert_eq!(r.source_plugin.as_deref(), Some(TRACE_SOURCE));
+            (
+                r.from_element.as_deref().unwrap(),
+                r.to_element.as_deref().unwrap(),
+                r.runtime_calls,
+            )
+        })
+        .collect();
+    // Resolved by line: the innermost element around it
+    assert_eq!(
+        calls,
+        vec![
+            ("main", "Parser.parse", Some(100)),
+            ("Parser.parse", "trim", Some(3)),
+        ]
+    );
+    let cold = matrix
+        .relationships
+        .iter()
+        .find(|r| r.to_element.as_deref() == Some("trim"))
+        .unwrap();
+    assert!(cold.strength > 0.5 && cold.strength < 1.0);
+
+    // Importing again replaces the trace's edges rather than adding to them
+    let data = TraceData::parse("main (app.py:2);trim (util.py:1) 5", TraceFormat::Folded).unwrap();
+    let import = apply_traces(&mut matrix, &data);
+    assert_eq!((import.added, import.strengthened), (1, 0));
+    assert_eq!(import_edge(&matrix, "parser.py").runtime_calls, None);
+    assert_eq!(
+        matrix
+            .relationships
+            .iter()
+            .filter(|r| r.source_plugin.as_deref() == Some(TRACE_SOURCE))
+            .count(),
+        1
+    );
+}
@@ -0,0 +1,245 @@
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use csd::core::matrix::{CodeElement, ElementType, ProjectMatrix, RelationshipType};
+use csd::core::transform::{apply_patch, run_transforms, PatchSummary};
+use csd::plugins::interface::{MatrixPatch, Relationship, SummaryUpdate, TagUpdate};
+use csd::utils::config::{Config, PluginRuntime, PluginSource, TransformPluginConfig};
+
+use super::test_matrix::create_test_file_node;
+
+fn matrix_with_files() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    let mut lib = create_test_file_node("src/lib.rs", "rust");
+    lib.elements.push(CodeElement {
+        element_type: ElementType::Function,
+        name: "parse".to_string(),
+        qualified_name: None,
+        parent: None,
+        signature: None,
+        line_start: 1,
+        line_end: 5,
+        summary: None,
+        complexity_score: None,
+        calls: vec![],
+        metadata: serde_json::json!({}),
+        tokens: 10,
+        element_id: String::new(),
+        coverage: None,
+    });
+    matrix.add_file(lib);
+    matrix.add_file(create_test_file_node("src/main.rs", "rust"));
+    matrix
+}
+
+fn tag(file: &str, tags: &[&str]) -> TagUpdate {
+    TagUpdate {
+        file: file.to_string(),
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_apply_patch_merges_tags() {
+    let mut matrix = matrix_with_files();
+    let patch = MatrixPatch {
+        tags: vec![
+            tag("src/lib.rs", &["core", "owned-by:parsing"]),
+            tag("src/lib.rs", &["core", "stable"]),
+            tag("src/missing.rs", &["core"]),
+        ],
+        ..Default::default()
+    };
+
+    let summary = apply_patch(&mut matrix, patch, "ownership");
+    assert_eq!(summary.tags, 2);
+    assert_eq!(summary.skipped, 1);
+
+    let lib = &matrix.files[&PathBuf::from("src/lib.rs")];
+    assert_eq!(
+        lib.metadata["tags"],
+        serde_json::json!(["core", "owned-by:parsing", "stable"])
+    );
+}
+
+#[test]
+fn test_apply_patch_updates_summaries() {
+    let mut matrix = matrix_with_files();
+    let patch = MatrixPatch {
+        summaries: vec![
+            SummaryUpdate {
+                file: "src/lib.rs".to_string(),
+                element: None,
+                summary: "Parsing entry points".to_string(),
+            },
+            SummaryUpdate {
+                file: "src/lib.rs".to_string(),
+                element: Some("parse".to_string()),
+                summary: "Parses one document".to_string(),
+            },
+            SummaryUpdate {
+                file: "src/lib.rs".to_string(),
+                element: Some("nope".to_string()),
+                summary: "Ignored".to_string(),
+            },
+        ],
+        ..Default::default()
+    };
+
+    let summary = apply_patch(&mut matrix, patch, "summaries");
+    assert_eq!(summary.summaries, 2);
+    assert_eq!(summary.skipped, 1);
+
+    let lib = &matrix.files[&PathBuf::from("src/lib.rs")];
+    assert_eq!(lib.file_summary.as_deref(), Some("Parsing entry points"));
+    assert_eq!(
+        lib.elements[0].summary.as_deref(),
+        Some("Parses one document")
+    );
+}
+
+#[test]
+fn test_apply_patch_adds_relationships_with_provenance() {
+    let mut matrix = matrix_with_files();
+    let relationship = Relationship {
+        from_file: "src/main.rs".to_string(),
+        to_file: "src/lib.rs".to_string(),
+        relationship_type: "call".to_string(),
+        details: "runtime dispatch".to_string(),
+        line_number: None,
+        strength: 0.5,
+        from_element: None,
+        to_element: None,
+    };
+    let patch = MatrixPatch {
+        relationships: vec![relationship.clone(), relationship],
+        ..Default::default()
+    };
+
+    let summary = apply_patch(&mut matrix, patch, "dispatch");
+    assert_eq!(
+        summary,
+        PatchSummary {
+            relationships: 2,
+            ..Default::default()
+        }
+    );
+
+    // Duplicates collapse like scanner-reported edges do
+    assert_eq!(matrix.relationships.len(), 1);
+    let added = &matrix.relationships[0];
+    assert_eq!(added.relationship_type, RelationshipType::Call);
+    assert_eq!(added.source_plugin.as_deref(), Some("dispatch"));
+    assert!(added.detected_at.is_some());
+}
+
+#[test]
+fn test_apply_patch_skips_updates_that_do_not_validate() {
+    let mut matrix = matrix_with_files();
+    let edge = |to: &str, relationship_type: &str, strength: f32| Relationship {
+        from_file: "src/main.rs".to_string(),
+        to_file: to.to_string(),
+        relationship_type: relationship_type.to_string(),
+        details: String::new(),
+        line_number: None,
+        strength,
+        from_element: None,
+        to_element: None,
+    };
+    let patch = MatrixPatch {
+        relationships: vec![
+            edge("src/missing.rs", "call", 0.5),
+            edge("src/lib.rs", "telepathy", 0.5),
+            edge("src/lib.rs", "call", 1.5),
+            edge("src/lib.rs", "call", f32::NAN),
+            edge("src/lib.rs", "import", 1.0),
+        ],
+        tags: vec![tag("src/lib.rs", &["", "  "])],
+        summaries: vec![SummaryUpdate {
+            file: "src/lib.rs".to_string(),
+            element: None,
+            summary: " \n".to_string(),
+        }],
+    };
+
+    let summary = apply_patch(&mut matrix, patch, "careless");
+    assert_eq!(
+        summary,
+        PatchSummary {
+            relationships: 1,
+            skipped: 6,
+            ..Default::default()
+        }
+    );
+    assert_eq!(matrix.relationships.len(), 1);
+    let lib = &matrix.files[&PathBuf::from("src/lib.rs")];
+    assert_eq!(lib.file_summary.as_deref(), Some("Test file summary"));
+    assert!(lib.metadata["tags"].is_null());
+}
+
+#[tokio::test]
+async fn test_run_transforms_in_configured_order() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let matrix_path = temp_dir.path().join("matrix.json");
+
+    // Each fake plugin tags every file it sees with its name plus the tags already present,
+    // so the second transform proves it read the first one's output
+    let script = r#"import json, sys
+msg = json.loads(sys.stdin.read())
+name = sys.argv[0].rsplit("/", 1)[-1][:-3]
+if msg["type"] == "transform":
+    matrix = json.load(open(msg["input"]["matrix_path"]))
+    tags = []
+    for node in matrix["files"].values():
+        seen = (node.get("metadata") or {}).get("tags", [])
+        tags.append({"file": node["relative_path"], "tags": [name + ":" + str(len(seen))]})
+    print(json.dumps({"status": "transform_success", "processing_time_ms": 1,
+        "patch": {"tags": tags}}))
+else:
+    print(json.dumps({"status": "error", "message": "unsupported", "details": None}))
+"#;
+
+    let mut config = Config::default();
+    for (name, order) in [("second", 10), ("first", -1)] {
+        let path = temp_dir.path().join(format!("{name}.py"));
+        tokio::fs::write(&path, script).await.unwrap();
+        config.transform_plugins.insert(
+            name.to_string(),
+            TransformPluginConfig {
+                source: PluginSource::Local {
+                    path: path.to_string_lossy().to_string(),
+                },
+                enabled: true,
+                order,
+                config: None,
+                runtime: PluginRuntime::Python,
+            },
+        );
+    }
+
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    matrix.add_file(create_test_file_node("src/lib.rs", "rust"));
+    matrix.save(&matrix_path).await.unwrap();
+
+    let applied = run_transforms(&mut matrix, &matrix_path, temp_dir.path(), &config)
+        .await
+        .expect("Transforms should run");
+    if applied.is_empty() {
+        eprintln!("Skipping: python is not available to run the fake transforms");
+        return;
+    }
+
+    let names: Vec<&str> = applied.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["first", "second"]);
+    assert_eq!(
+        matrix.files[&PathBuf::from("src/lib.rs")].metadata["tags"],
+        serde_json::json!(["first:0", "second:1"])
+    );
+
+    // The saved matrix carries the transforms' changes for output plugins
+    let saved = ProjectMatrix::load(&matrix_path).await.unwrap();
+    assert_eq!(
+        saved.files[&PathBuf::from("src/lib.rs")].metadata["tags"],
+        serde_json::json!(["first:0", "second:1"])
+    );
+}
@@ -0,0 +1,83 @@
+use std::path::Path;
+use tempfile::TempDir;
+
+use csd::core::validate::validate_plugin_configs;
+use csd::plugins::conformance::CheckStatus;
+use csd::utils::config::{Config, FilePatterns, InputPluginConfig, PluginRuntime, PluginSource};
+
+// Input plugin declaring that its config takes an integer `depth` and nothing else
+fn write_schema_plugin(dir: &Path) -> String {
+    let path = dir.join("schema_plugin.py");
+    std::fs::write(
+        &path,
+        r#"import json, sys
+sys.stdin.read()
+print(json.dumps({"status": "info", "name": "schema_plugin", "version": "1.0.0",
+    "plugin_type": "input", "supported_extensions": [".test"],
+    "supported_filenames": [], "supported_output_types": None, "supported_formats": None,
+    "config_schema": {"type": "object", "properties": {"depth": {"type": "integer"}},
+        "additionalProperties": False}}))
+"#,
+    )
+    .unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn plugin(path: &str, config: Option<&str>) -> InputPluginConfig {
+    InputPluginConfig {
+        source: PluginSource::Local {
+            path: path.to_string(),
+        },
+        file_patterns: FilePatterns {
+            extensions: vec![".test".to_string()],
+            filenames: vec![],
+            glob_patterns: None,
+        },
+        enabled: true,
+        config: config.map(|yaml| serde_yaml::from_str(yaml).unwrap()),
+        runtime: PluginRuntime::Python,
+    }
+}
+
+#[tokio::test]
+async fn test_validate_checks_plugin_configs_against_schemas() {
+    let temp_dir = TempDir::new().unwrap();
+    let script = write_schema_plugin(temp_dir.path());
+
+    let mut config = Config::default();
+    config.input_plugins.clear();
+    config.output_plugins.clear();
+    config
+        .input_plugins
+        .insert("good".to_string(), plugin(&script, Some("depth: 3")));
+    config.input_plugins.insert(
+        "bad".to_string(),
+        plugin(&script, Some("depth: deep\nextra: 1")),
+    );
+    config
+        .input_plugins
+        .insert("missing".to_string(), plugin("does/not/exist.py", None));
+
+    let report = validate_plugin_configs(&config, temp_dir.path())
+        .await
+        .unwrap();
+    let names: Vec<&str> = report.plugins.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["bad", "good", "missing"]);
+    if report.plugins[1].status != CheckStatus::Pass {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+
+    let bad = &report.plugins[0];
+    assert_eq!(bad.status, CheckStatus::Fail);
+    assert!(bad.has_schema);
+    assert_eq!(bad.violations.len(), 2);
+    assert_eq!(bad.violations[0].path, "depth");
+    // A plugin that can't be asked is left to `csd doctor`
+    assert_eq!(report.plugins[2].status, CheckStatus::Warn);
+    assert!(report.plugins[2].error.is_some());
+    assert!(!report.passed());
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["plugins"][0]["violations"][1]["path"], "extra");
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use csd::core::matrix::ProjectMatrix;
+use csd::core::view::{file_tags, MatrixView};
+
+use super::test_matrix::{create_test_file_node, create_test_relationship};
+
+// core.rs and api.rs are core, gen.rs generated core; api.rs -> core.rs -> gen.rs
+fn tagged_matrix() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    for (name, tags) in [
+        ("core.rs", vec!["layer:core"]),
+        ("api.rs", vec!["layer:api"]),
+        ("gen.rs", vec!["layer:core", "generated"]),
+        ("untagged.rs", vec![]),
+    ] {
+        let mut node = create_test_file_node(name, "rust");
+        if !tags.is_empty() {
+            node.metadata = serde_json::json!({ "tags": tags });
+        }
+        matrix.add_file(node);
+    }
+    matrix.add_relationship(create_test_relationship("api.rs", "core.rs"));
+    matrix.add_relationship(create_test_relationship("core.rs", "gen.rs"));
+    matrix
+}
+
+fn names(matrix: &ProjectMatrix) -> Vec<String> {
+    let mut names: Vec<String> = matrix
+        .files
+        .keys()
+        .map(|p| p.display().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_empty_view_keeps_everything() {
+    let view = MatrixView::default();
+    assert!(view.is_empty());
+    let matrix = view.apply(tagged_matrix()).unwrap();
+    assert_eq!(matrix.files.len(), 4);
+    assert_eq!(matrix.relationships.len(), 2);
+}
+
+#[test]
+fn test_tags_and_exclusions() {
+    let view = MatrixView::new(vec!["layer:core".to_string()], Vec::new());
+    let matrix = view.apply(tagged_matrix()).unwrap();
+    assert_eq!(names(&matrix), vec!["core.rs", "gen.rs"]);
+    assert_eq!(matrix.relationships.len(), 1);
+    assert_eq!(matrix.metadata.total_files, 2);
+
+    let view = MatrixView::new(
+        vec!["layer:core".to_string(), "layer:api".to_string()],
+        vec!["generated".to_string()],
+    );
+    let matrix = view.apply(tagged_matrix()).unwrap();
+    assert_eq!(names(&matrix), vec!["api.rs", "core.rs"]);
+    assert_eq!(matrix.relationships.len(), 1);
+
+    // Exclusions alone keep untagged files
+    let view = MatrixView::new(Vec::new(), vec!["generated".to_string()]);
+    let matrix = view.apply(tagged_matrix()).unwrap();
+    assert_eq!(names(&matrix), vec!["api.rs", "core.rs", "untagged.rs"]);
+    assert_eq!(
+        file_tags(&matrix.files[&PathBuf::from("api.rs")]),
+        vec!["layer:api"]
+    );
+}
+
+#[test]
+fn test_view_without_files_is_an_error() {
+    let view = MatrixView::new(vec!["layer:ui".to_string()], Vec::new());
+    let error = view.apply(tagged_matrix()).unwrap_err().to_string();
+    assert_eq!(error, "No files in the matrix match --tag layer:ui");
+}
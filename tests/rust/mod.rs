@@ -1,10 +1,23 @@
 // Integration tests for CSD Rust code
 // This directory structure mirrors the src/ directory for organization
 
+#[cfg(feature = "cli")]
 pub mod cli;
 pub mod core;
 pub mod plugins;
+pub mod server;
+pub mod test_ffi;
 pub mod utils;
 
 // Common test utilities and helpers can go here
-pub mod common {}
+pub mod common {
+    /// Whether a Python interpreter is on the PATH, for tests that run plugin scripts
+    pub fn python_available() -> bool {
+        ["python3", "python"].iter().any(|python| {
+            std::process::Command::new(python)
+                .arg("--version")
+                .output()
+                .is_ok_and(|output| output.status.success())
+        })
+    }
+}
@@ -1,7 +1,12 @@
 // Plugins module tests
 
+pub mod test_communication;
+pub mod test_config_schema;
+pub mod test_conformance;
 pub mod test_interface;
+pub mod test_logs;
+pub mod test_scaffold;
+pub mod test_transport;
 
 // Future plugins test modules:
-// pub mod test_communication;
 // pub mod test_manager;
@@ -0,0 +1,539 @@
+use std::path::Path;
+use std::time::Duration;
+use tempfile::TempDir;
+
+use csd::plugins::communication::{
+    InputPluginCommunicator, OutputPluginCommunicator, PluginLaunch, PluginPool, Transport,
+};
+use csd::plugins::interface::{
+    InputPluginInterface, OutputPluginInput, OutputPluginInterface, PluginInput, PluginInterface,
+    PluginProgress,
+};
+use csd::utils::config::{PluginPoolConfig, PluginRuntime};
+
+use crate::rust::common::python_available;
+
+/// Output plugin that runs `body` and then reports success with no outputs
+async fn fake_output_plugin(dir: &Path, body: &str) -> OutputPluginCommunicator {
+    let script = format!(
+        r#"import json, os, sys, time
+sys.stdin.read()
+{body}
+print(json.dumps({{"status": "output_success", "result": {{
+    "plugin_name": "slow_docs", "plugin_version": "0.1.0", "output_type": "documentation",
+    "outputs": [], "processing_time_ms": 1, "metadata": {{}}}}}}))
+"#
+    );
+    let plugin_path = dir.join("slow_docs.py");
+    tokio::fs::write(&plugin_path, script).await.unwrap();
+
+    OutputPluginCommunicator::new(plugin_path)
+        .with_cache_dir(dir.join("cache"))
+        .with_python_auto_detect()
+        .with_timeouts(Duration::from_millis(1500), Duration::from_millis(200))
+}
+
+fn input(dir: &Path) -> OutputPluginInput {
+    OutputPluginInput {
+        matrix_path: dir.join("matrix.json"),
+        project_root: dir.to_path_buf(),
+        output_dir: dir.to_path_buf(),
+        cache_dir: dir.join("cache").to_string_lossy().to_string(),
+        plugin_config: None,
+        format_options: serde_json::json!({}),
+        section_cache: None,
+        scope: None,
+    }
+}
+
+#[tokio::test]
+async fn test_progress_extends_generate_timeout() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+    // Runs well past the 1.5s timeout, but reports progress more often than that
+    let communicator = fake_output_plugin(
+        temp_dir.path(),
+        r#"for done in range(6):
+    with open(os.environ["CSD_PROGRESS_FILE"], "w") as f:
+        json.dump({"completed": done, "total": 6, "message": "section"}, f)
+    time.sleep(0.5)"#,
+    )
+    .await;
+
+    let result = communicator
+        .generate(input(temp_dir.path()))
+        .await
+        .expect("Progress should keep the plugin alive");
+    assert_eq!(result.plugin_name, "slow_docs");
+
+    // The progress file is cleaned up with the input file
+    let leftovers: Vec<_> = std::fs::read_dir(temp_dir.path().join("cache"))
+        .unwrap()
+        .collect();
+    assert!(leftovers.is_empty());
+}
+
+#[tokio::test]
+async fn test_generate_times_out_without_progress() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let communicator = fake_output_plugin(temp_dir.path(), "time.sleep(3)").await;
+
+    let err = communicator
+        .generate(input(temp_dir.path()))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("timed out"));
+}
+
+#[tokio::test]
+async fn test_run_dir_holds_scratch_files() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let run_dir = temp_dir.path().join("cache/runs/1");
+    let communicator = fake_output_plugin(
+        temp_dir.path(),
+        "assert os.path.dirname(os.environ['CSD_PROGRESS_FILE']).endswith('runs/1')",
+    )
+    .await
+    .with_run_dir(run_dir.clone());
+
+    communicator
+        .generate(input(temp_dir.path()))
+        .await
+        .expect("Plugin should run from the run directory");
+    assert!(run_dir.is_dir());
+}
+
+#[tokio::test]
+async fn test_cache_file_outside_cache_dir_is_rejected() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let plugin_path = temp_dir.path().join("escape_lang.py");
+    std::fs::write(
+        &plugin_path,
+        r#"import json, sys
+sys.stdin.read()
+print(json.dumps({"status": "success", "cache_file": "../outside.json", "processing_time_ms": 1}))
+"#,
+    )
+    .unwrap();
+    std::fs::write(temp_dir.path().join("outside.json"), "{}").unwrap();
+
+    let cache_dir = temp_dir.path().join("cache");
+    std::fs::create_dir_all(&cache_dir).unwrap();
+    let communicator = InputPluginCommunicator::new(plugin_path)
+        .with_cache_dir(cache_dir.clone())
+        .with_python_auto_detect();
+    let err = communicator
+        .analyze(PluginInput {
+            file_path: temp_dir.path().join("main.fake"),
+            relative_path: "main.fake".into(),
+            content: String::new(),
+            project_root: temp_dir.path().to_path_buf(),
+            cache_dir: cache_dir.to_string_lossy().to_string(),
+            plugin_config: None,
+            content_path: None,
+            truncated: false,
+            project: None,
+        })
+        .await
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("outside the cache directory"),
+        "{err:#}"
+    );
+}
+
+fn analyze_input(dir: &Path) -> PluginInput {
+    PluginInput {
+        file_path: dir.join("main.fake"),
+        relative_path: "main.fake".into(),
+        content: String::new(),
+        project_root: dir.to_path_buf(),
+        cache_dir: dir.join("cache").to_string_lossy().to_string(),
+        plugin_config: None,
+        content_path: None,
+        truncated: false,
+        project: None,
+    }
+}
+
+#[tokio::test]
+async fn test_file_timeout_stops_slow_analysis() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let plugin_path = temp_dir.path().join("slow_lang.py");
+    std::fs::write(
+        &plugin_path,
+        "import sys, time\nsys.stdin.read()\ntime.sleep(10)\n",
+    )
+    .unwrap();
+
+    let communicator = InputPluginCommunicator::new(plugin_path)
+        .with_cache_dir(temp_dir.path().join("cache"))
+        .with_python_auto_detect()
+        .with_file_timeout(Duration::from_secs(1));
+    let started = std::time::Instant::now();
+    let err = communicator
+        .analyze(analyze_input(temp_dir.path()))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("timed out after 1 seconds"),
+        "{err}"
+    );
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_passed_deadline_skips_plugin() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    // Never started, so it needn't exist
+    let communicator = InputPluginCommunicator::new(temp_dir.path().join("missing.py"))
+        .with_cache_dir(temp_dir.path().join("cache"))
+        .with_deadline(std::time::Instant::now());
+
+    let err = communicator
+        .analyze(analyze_input(temp_dir.path()))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("time limit"), "{err}");
+}
+
+fn python() -> &'static str {
+    ["python3", "python"]
+        .into_iter()
+        .find(|python| {
+            std::process::Command::new(python)
+                .arg("--version")
+                .output()
+                .is_ok_and(|output| output.status.success())
+        })
+        .unwrap_or("python3")
+}
+
+/// Persistent plugin answering each message with its pid, after some noise on stdout.
+/// `{"exit": true}` makes it exit without answering and `{"sleep": s}` answer late.
+/// With `CSD_SOCKET` set it connects there and speaks frames instead of lines.
+fn persistent_plugin(dir: &Path, transport: Transport) -> PluginLaunch {
+    let path = dir.join("pooled.py");
+    std::fs::write(
+        &path,
+        r#"import json, os, socket, struct, sys, time
+def respond(msg, send):
+    if msg.get("exit"):
+        sys.exit(0)
+    time.sleep(msg.get("sleep", 0))
+    print("not a response", flush=True)
+    send({"status": "info", "name": "pooled", "version": str(os.getpid()),
+        "plugin_type": "input", "supported_extensions": [], "supported_filenames": [],
+        "persistent": os.environ.get("CSD_PERSISTENT")})
+
+address = os.environ.get("CSD_SOCKET")
+if address:
+    kind, _, target = address.partition(":")
+    if kind == "unix":
+        conn = socket.socket(socket.AF_UNIX)
+        conn.connect(target)
+    else:
+        host, _, port = target.rpartition(":")
+        conn = socket.create_connection((host, int(port)))
+    f = conn.makefile("rb")
+    def send(payload):
+        data = json.dumps(payload).encode()
+        conn.sendall(struct.pack(">I", len(data)) + data)
+    send({"token": os.environ["CSD_SOCKET_TOKEN"]})
+    while True:
+        header = f.read(4)
+        if len(header) < 4:
+            break
+        respond(json.loads(f.read(struct.unpack(">I", header)[0])), send)
+else:
+    for line in sys.stdin:
+        respond(json.loads(line), lambda payload: print(json.dumps(payload), flush=True))
+"#,
+    )
+    .unwrap();
+    PluginLaunch {
+        runtime: PluginRuntime::Python,
+        interpreter: python().to_string(),
+        plugin_path: path,
+        offline: false,
+        transport,
+    }
+}
+
+async fn pid(pool: &PluginPool, plugin: &PluginLaunch, message: &str) -> anyhow::Result<String> {
+    let line = pool
+        .exchange(plugin, message, Duration::from_secs(5))
+        .await?;
+    let response: serde_json::Value = serde_json::from_str(&line)?;
+    assert_eq!(response["persistent"], "1");
+    Ok(response["version"].as_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn test_pool_reuses_and_replaces_processes() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let plugin = persistent_plugin(temp_dir.path(), Transport::Stdio);
+    let pool = PluginPool::new(PluginPoolConfig::default());
+
+    let first = pid(&pool, &plugin, "{}").await.unwrap();
+    assert_eq!(pid(&pool, &plugin, "{}").await.unwrap(), first);
+
+    // A process that dies mid-message isn't reused
+    assert!(pid(&pool, &plugin, r#"{"exit": true}"#).await.is_err());
+    let second = pid(&pool, &plugin, "{}").await.unwrap();
+    assert_ne!(second, first);
+
+    let err = pool
+        .exchange(&plugin, r#"{"sleep": 5}"#, Duration::from_millis(300))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("timed out"), "{err}");
+
+    let stats = &pool.stats()[&plugin.plugin_path];
+    assert_eq!((stats.spawned, stats.reused, stats.peak), (2, 3, 1));
+    assert_eq!(stats.unhealthy, 2);
+    pool.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_pool_caps_concurrent_processes() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let plugin = persistent_plugin(temp_dir.path(), Transport::Stdio);
+    let pool = PluginPool::new(PluginPoolConfig {
+        max_processes: 2,
+        ..PluginPoolConfig::default()
+    });
+
+    let message = r#"{"sleep": 0.3}"#;
+    let (a, b, c, d) = tokio::join!(
+        pid(&pool, &plugin, message),
+        pid(&pool, &plugin, message),
+        pid(&pool, &plugin, message),
+        pid(&pool, &plugin, message)
+    );
+    let mut pids: Vec<String> = [a, b, c, d].into_iter().map(Result::unwrap).collect();
+    pids.sort();
+    pids.dedup();
+    assert_eq!(pids.len(), 2);
+
+    let stats = &pool.stats()[&plugin.plugin_path];
+    assert_eq!((stats.spawned, stats.reused, stats.peak), (2, 2, 2));
+    pool.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_pool_retires_idle_processes() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let plugin = persistent_plugin(temp_dir.path(), Transport::Stdio);
+
+    let pool = PluginPool::new(PluginPoolConfig {
+        idle_timeout_secs: 0,
+        ..PluginPoolConfig::default()
+    });
+    let first = pid(&pool, &plugin, "{}").await.unwrap();
+    assert_ne!(pid(&pool, &plugin, "{}").await.unwrap(), first);
+    assert_eq!(pool.stats()[&plugin.plugin_path].retired_idle, 1);
+
+    // Kept however long it's idle
+    let pool = PluginPool::new(PluginPoolConfig {
+        idle_timeout_secs: 0,
+        min_processes: 1,
+        ..PluginPoolConfig::default()
+    });
+    let first = pid(&pool, &plugin, "{}").await.unwrap();
+    assert_eq!(pid(&pool, &plugin, "{}").await.unwrap(), first);
+    assert_eq!(pool.stats()[&plugin.plugin_path].retired_idle, 0);
+    pool.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_communicator_sends_messages_through_pool() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let plugin = persistent_plugin(temp_dir.path(), Transport::Stdio);
+    let pool = std::sync::Arc::new(PluginPool::new(PluginPoolConfig::default()));
+
+    let communicator = InputPluginCommunicator::new(plugin.plugin_path.clone())
+        .with_cache_dir(temp_dir.path().join("cache"))
+        .with_python_auto_detect()
+        .with_pool(pool.clone(), Transport::Socket);
+    let first = communicator.get_info().await.unwrap();
+    let second = communicator.get_info().await.unwrap();
+    assert_eq!(first.name, "pooled");
+    assert_eq!(first.version, second.version);
+    assert_eq!(pool.stats()[&plugin.plugin_path].spawned, 1);
+    pool.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_pool_talks_to_socket_plugins() {
+    if !python_available() {
+        eprintln!("Skipping: python is not available to run the fake plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let plugin = persistent_plugin(temp_dir.path(), Transport::Socket);
+
+    // Their stdout is theirs; only frames are responses
+    for tcp_sockets in [false, true] {
+        let pool = PluginPool::new(PluginPoolConfig {
+            tcp_sockets,
+            ..PluginPoolConfig::default()
+        });
+        let first = pid(&pool, &plugin, "{}").await.unwrap();
+        assert_eq!(pid(&pool, &plugin, "{}").await.unwrap(), first);
+        assert!(pid(&pool, &plugin, r#"{"exit": true}"#).await.is_err());
+
+        let stats = &pool.stats()[&plugin.plugin_path];
+        assert_eq!((stats.spawned, stats.reused, stats.unhealthy), (1, 2, 1));
+        pool.shutdown().await;
+    }
+
+    // One that never connects fails instead of hanging
+    let silent = temp_dir.path().join("silent.py");
+    std::fs::write(&silent, "import sys\nsys.exit(0)\n").unwrap();
+    let err = PluginPool::new(PluginPoolConfig::default())
+        .exchange(
+            &PluginLaunch {
+                plugin_path: silent,
+                ..plugin.clone()
+            },
+            "{}",
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("before connecting"), "{err}");
+}
+
+const INFO_RESPONSE: &str = r#"{"status": "info", "name": "NAME", "version": "0.1.0", "plugin_type": "input", "supported_extensions": [".x"], "supported_filenames": [], "supported_output_types": null, "supported_formats": null}"#;
+
+fn node_available() -> bool {
+    std::process::Command::new("node")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_binary_runtime_runs_plugin_directly() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let path = temp_dir.path().join("native_analyzer");
+    std::fs::write(
+        &path,
+        format!(
+            "#!/bin/sh\ncat > /dev/null\necho '{}'\n",
+            INFO_RESPONSE.replace("NAME", "native")
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    // A Python interpreter that doesn't exist shows it isn't used
+    let info = InputPluginCommunicator::new(path.clone())
+        .with_cache_dir(temp_dir.path().join("cache"))
+        .with_python_executable("/nonexistent/python".to_string())
+        .with_runtime(PluginRuntime::Binary)
+        .get_info()
+        .await
+        .unwrap();
+    assert_eq!(info.name, "native");
+
+    let err = InputPluginCommunicator::new(path)
+        .with_cache_dir(temp_dir.path().join("cache"))
+        .with_python_executable("/nonexistent/python".to_string())
+        .get_info()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("/nonexistent/python"), "{err}");
+}
+
+#[tokio::test]
+async fn test_node_runtime_runs_plugin_with_node() {
+    if !node_available() {
+        eprintln!("Skipping: node is not available to run the fake plugin");
+        return;
+    }
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let path = temp_dir.path().join("analyzer.js");
+    std::fs::write(
+        &path,
+        format!(
+            r#"const readline = require("readline");
+const info = {};
+if (process.env.CSD_PERSISTENT) {{
+  const lines = readline.createInterface({{ input: process.stdin }});
+  lines.on("line", () => console.log(JSON.stringify({{ ...info, name: "pid" + process.pid }})));
+}} else {{
+  process.stdin.resume();
+  process.stdin.on("end", () => console.log(JSON.stringify(info)));
+}}
+"#,
+            INFO_RESPONSE.replace("NAME", "node")
+        ),
+    )
+    .unwrap();
+
+    let communicator = InputPluginCommunicator::new(path.clone())
+        .with_cache_dir(temp_dir.path().join("cache"))
+        .with_runtime(PluginRuntime::Node)
+        .with_node_executable("node".to_string());
+    assert_eq!(communicator.get_info().await.unwrap().name, "node");
+
+    // Pooled processes start under the same interpreter
+    let pool = std::sync::Arc::new(PluginPool::new(PluginPoolConfig::default()));
+    let pooled = communicator.with_pool(pool.clone(), Transport::Stdio);
+    let first = pooled.get_info().await.unwrap().name;
+    assert!(first.starts_with("pid"), "{first}");
+    assert_eq!(pooled.get_info().await.unwrap().name, first);
+    pool.shutdown().await;
+}
+
+#[test]
+fn test_plugin_progress_display() {
+    let progress: PluginProgress =
+        serde_json::from_str(r#"{"completed": 3, "total": 10, "message": "section usage"}"#)
+            .unwrap();
+    assert_eq!(progress.to_string(), "3/10 - section usage");
+
+    let progress: PluginProgress = serde_json::from_str(r#"{"completed": 7}"#).unwrap();
+    assert_eq!(progress.to_string(), "7");
+}
@@ -0,0 +1,94 @@
+use serde_json::json;
+
+use csd::plugins::config_schema::{validate_config, validate_yaml_config, SchemaViolation};
+
+fn schema() -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "depth": {"type": "integer", "minimum": 1, "maximum": 10},
+            "style": {"enum": ["google", "numpy"]},
+            "exclude": {"type": "array", "items": {"type": "string", "minLength": 1}},
+            "timeout": {"anyOf": [{"type": "integer"}, {"const": "never"}]}
+        },
+        "required": ["style"],
+        "additionalProperties": false
+    })
+}
+
+fn messages(violations: &[SchemaViolation]) -> Vec<String> {
+    violations.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn test_valid_config_passes() {
+    let config = json!({"depth": 3, "style": "numpy", "exclude": ["tests"], "timeout": "never"});
+    assert!(validate_config(&schema(), Some(&config)).is_empty());
+    // Integers may be written as floats
+    let config = json!({"depth": 3.0, "style": "google", "timeout": 30});
+    assert!(validate_config(&schema(), Some(&config)).is_empty());
+}
+
+#[test]
+fn test_violations_name_the_setting() {
+    let config = json!({
+        "depth": "deep",
+        "style": "sphinx",
+        "exclude": ["ok", ""],
+        "timeout": "later",
+        "colour": true
+    });
+    let mut found = messages(&validate_config(&schema(), Some(&config)));
+    found.sort();
+    assert_eq!(
+        found,
+        vec![
+            "config.colour: is not a known setting (expected one of depth, exclude, style, timeout)",
+            "config.depth: expected integer, got string",
+            "config.exclude[1]: has 0 characters, fewer than 1",
+            "config.style: \"sphinx\" is not one of \"google\", \"numpy\"",
+            "config.timeout: matches none of the allowed forms",
+        ]
+    );
+
+    let config = json!({"depth": 11, "style": "google"});
+    assert_eq!(
+        messages(&validate_config(&schema(), Some(&config))),
+        vec!["config.depth: 11 is above the maximum of 10"]
+    );
+}
+
+#[test]
+fn test_missing_config_is_checked_as_empty() {
+    assert_eq!(
+        messages(&validate_config(&schema(), None)),
+        vec!["config.style: is required"]
+    );
+    // A config that isn't a mapping at all
+    assert_eq!(
+        messages(&validate_config(&schema(), Some(&json!([1])))),
+        vec!["config: expected object, got array"]
+    );
+}
+
+#[test]
+fn test_yaml_config_and_permissive_schemas() {
+    let config: serde_yaml::Value = serde_yaml::from_str("style: numpy\ndepth: 0\n").unwrap();
+    assert_eq!(
+        messages(&validate_yaml_config(&schema(), Some(&config))),
+        vec!["config.depth: 0 is below the minimum of 1"]
+    );
+
+    // Unknown keywords and `true` accept anything
+    let config = json!({"anything": [1, "two"]});
+    assert!(validate_config(&json!({"$ref": "#/defs/x"}), Some(&config)).is_empty());
+    assert!(validate_config(&json!(true), Some(&config)).is_empty());
+    assert!(!validate_config(&json!(false), Some(&config)).is_empty());
+
+    // oneOf rejects values matching several forms
+    let schema = json!({"oneOf": [{"type": "number"}, {"type": "integer"}]});
+    assert_eq!(
+        messages(&validate_config(&schema, Some(&json!(2)))),
+        vec!["config: matches more than one of the allowed forms"]
+    );
+}
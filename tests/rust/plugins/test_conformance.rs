@@ -0,0 +1,148 @@
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use csd::plugins::conformance::{
+    CheckStatus, ConformanceCheck, ConformanceReport, ConformanceRunner,
+};
+use csd::plugins::interface::PluginType;
+
+use crate::rust::common::python_available;
+
+/// Input plugin speaking the protocol directly; `info` and `cache_file` are Python
+/// expressions so tests can break one part of a response
+fn fake_input_plugin(dir: &Path, info: &str, cache_file: &str) -> PathBuf {
+    let script = format!(
+        r#"import json, os, sys
+message = json.loads(sys.stdin.read())
+kind = message["type"]
+if kind == "get_info":
+    print(json.dumps({info}))
+elif kind == "can_analyze":
+    print(json.dumps({{"status": "can_analyze", "can_analyze": True, "confidence": 0.9}}))
+elif kind == "analyze":
+    data = message["input"]
+    output = {{"file_path": data["file_path"], "file_hash": "", "exports": [],
+        "elements": [{{"element_type": "function", "name": "greet", "signature": None,
+            "line_start": 3, "line_end": 4, "summary": None, "complexity_score": 1,
+            "calls": [], "metadata": {{}}, "tokens": 5}}],
+        "imports": [], "relationships": [], "external_dependencies": [],
+        "file_summary": None, "processing_time_ms": 0, "plugin_version": "0.1.0",
+        "token_info": {{"total_tokens": 10, "code_tokens": 10,
+            "documentation_tokens": 0, "comment_tokens": 0}}, "metadata": None}}
+    name = {cache_file}
+    with open(os.path.join(data["cache_dir"], name), "w") as f:
+        json.dump(output, f)
+    print(json.dumps({{"status": "success", "cache_file": name, "processing_time_ms": 1}}))
+"#
+    );
+    let path = dir.join("fake_lang.py");
+    std::fs::write(&path, script).unwrap();
+    path
+}
+
+const GOOD_INFO: &str = r#"{"status": "info", "name": "fake_lang", "version": "0.1.0",
+    "plugin_type": "input", "supported_extensions": [".fake"], "supported_filenames": [],
+    "supported_output_types": None, "supported_formats": None}"#;
+
+async fn run(dir: &TempDir, plugin: PathBuf, expected: Option<PluginType>) -> ConformanceReport {
+    ConformanceRunner::new(plugin, dir.path().join("work"))
+        .with_expected_type(expected)
+        .run()
+        .await
+        .unwrap()
+}
+
+fn check<'a>(report: &'a ConformanceReport, name: &str) -> &'a ConformanceCheck {
+    report
+        .checks
+        .iter()
+        .find(|check| check.name == name)
+        .unwrap_or_else(|| panic!("no {name} check in {:?}", report.checks))
+}
+
+#[tokio::test]
+async fn test_conforming_input_plugin_passes() {
+    if !python_available() {
+        return;
+    }
+    let dir = TempDir::new().unwrap();
+    let plugin = fake_input_plugin(dir.path(), GOOD_INFO, r#""result.json""#);
+
+    let report = run(&dir, plugin, Some(PluginType::Input)).await;
+
+    assert!(report.passed(), "{:?}", report.checks);
+    assert_eq!(report.plugin_name.as_deref(), Some("fake_lang"));
+    let names: Vec<&str> = report.checks.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "get_info",
+            "can_analyze src/sample.fake",
+            "analyze src/sample.fake"
+        ]
+    );
+    // The work directory is cleaned up afterwards
+    assert!(!dir.path().join("work").exists());
+}
+
+#[tokio::test]
+async fn test_info_missing_fields_fails_schema_check() {
+    if !python_available() {
+        return;
+    }
+    let dir = TempDir::new().unwrap();
+    let info = r#"{"status": "info", "name": "fake_lang", "plugin_type": "input"}"#;
+    let plugin = fake_input_plugin(dir.path(), info, r#""result.json""#);
+
+    let report = run(&dir, plugin, None).await;
+
+    let info = check(&report, "get_info");
+    assert_eq!(info.status, CheckStatus::Fail);
+    assert!(
+        info.issues[0].contains("doesn't match the protocol"),
+        "{:?}",
+        info.issues
+    );
+    // Without valid info there is nothing more to drive
+    assert_eq!(report.checks.len(), 1);
+}
+
+#[tokio::test]
+async fn test_cache_file_outside_cache_dir_fails() {
+    if !python_available() {
+        return;
+    }
+    let dir = TempDir::new().unwrap();
+    let plugin = fake_input_plugin(dir.path(), GOOD_INFO, r#""../escaped.json""#);
+
+    let report = run(&dir, plugin, None).await;
+
+    assert!(!report.passed());
+    let analyze = check(&report, "analyze src/sample.fake");
+    assert_eq!(analyze.status, CheckStatus::Fail);
+    assert!(analyze.issues[0].contains("outside the cache directory"));
+}
+
+#[tokio::test]
+async fn test_plugin_type_mismatch_fails() {
+    if !python_available() {
+        return;
+    }
+    let dir = TempDir::new().unwrap();
+    let plugin = fake_input_plugin(dir.path(), GOOD_INFO, r#""result.json""#);
+
+    let report = run(&dir, plugin, Some(PluginType::Output)).await;
+
+    let info = check(&report, "get_info");
+    assert_eq!(info.status, CheckStatus::Fail);
+    assert!(info.issues[0].contains("registered as Output"));
+}
+
+#[tokio::test]
+async fn test_missing_plugin_is_an_error() {
+    let dir = TempDir::new().unwrap();
+    let result = ConformanceRunner::new(dir.path().join("missing.py"), dir.path().join("work"))
+        .run()
+        .await;
+    assert!(result.is_err());
+}
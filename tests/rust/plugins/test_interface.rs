@@ -2,17 +2,22 @@ use serde_json;
 use std::path::PathBuf;
 
 // Import the modules we're testing
+use csd::core::matrix::Export;
 use csd::plugins::interface::{
-    CodeElement, ExternalDependency, GeneratedOutput, Import, OutputPluginInput,
-    OutputPluginResult, PluginInfo, PluginInput, PluginMessage, PluginOutput, PluginResponse,
-    PluginType, Relationship,
+    plugin_script_path, BatchResult, CodeElement, ContentPreview, ExternalDependency,
+    FindingSeverity, GeneratedOutput, Import, OutputPluginInput, OutputPluginResult, PluginInfo,
+    PluginInput, PluginMessage, PluginOutput, PluginResponse, PluginType, Relationship,
+    SectionCache, CAPABILITY_CONTENT_PATH,
 };
+use csd::utils::config::PluginSource;
 
 // Helper function to create a test CodeElement
 fn create_test_code_element() -> CodeElement {
     CodeElement {
         element_type: "function".to_string(),
         name: "test_function".to_string(),
+        qualified_name: None,
+        parent: None,
         signature: Some("fn test_function(x: i32) -> bool".to_string()),
         line_start: 10,
         line_end: 20,
@@ -51,6 +56,8 @@ fn create_test_relationship() -> Relationship {
         details: "imports lib module".to_string(),
         line_number: Some(15),
         strength: 0.8,
+        from_element: None,
+        to_element: None,
     }
 }
 
@@ -72,7 +79,7 @@ fn create_test_plugin_output() -> PluginOutput {
         file_hash: "abc123def456".to_string(),
         elements: vec![create_test_code_element()],
         imports: vec![create_test_import()],
-        exports: vec!["main_function".to_string(), "helper".to_string()],
+        exports: vec![Export::named("main_function"), Export::named("helper")],
         relationships: vec![create_test_relationship()],
         external_dependencies: vec![create_test_external_dependency()],
         file_summary: Some("Main application file".to_string()),
@@ -103,6 +110,9 @@ fn create_test_plugin_input() -> PluginInput {
             "analyze_comments": true,
             "max_complexity": 10
         })),
+        content_path: None,
+        truncated: false,
+        project: None,
     }
 }
 
@@ -121,6 +131,8 @@ fn create_test_output_plugin_input() -> OutputPluginInput {
             "format": "markdown",
             "output_type": "documentation"
         }),
+        section_cache: None,
+        scope: None,
     }
 }
 
@@ -135,6 +147,7 @@ fn create_test_generated_output() -> GeneratedOutput {
             "sections": ["overview", "api", "examples"],
             "word_count": 350
         }),
+        issues: vec![],
     }
 }
 
@@ -150,9 +163,28 @@ fn create_test_output_plugin_result() -> OutputPluginResult {
             "total_files": 1,
             "total_size_mb": 0.002
         }),
+        patch: None,
     }
 }
 
+#[test]
+fn test_plugin_output_accepts_export_records() {
+    let mut json = serde_json::to_value(create_test_plugin_output()).unwrap();
+    json["exports"] = serde_json::json!([
+        "main_function",
+        {"name": "Parser", "kind": "struct", "source": "src/parser.rs"}
+    ]);
+
+    let output: PluginOutput = serde_json::from_value(json).unwrap();
+    assert_eq!(output.exports[0], Export::named("main_function"));
+    assert_eq!(output.exports[1].name, "Parser");
+    assert_eq!(output.exports[1].kind.as_deref(), Some("struct"));
+    assert_eq!(
+        output.exports[1].source,
+        Some(PathBuf::from("src/parser.rs"))
+    );
+}
+
 #[test]
 fn test_plugin_type_serialization() {
     let input_type = PluginType::Input;
@@ -173,6 +205,36 @@ fn test_plugin_type_serialization() {
     assert_eq!(deserialized_output, PluginType::Output);
 }
 
+#[test]
+fn test_plugin_script_path() {
+    let builtin = PluginSource::Builtin {
+        name: "tagger".to_string(),
+        plugin_type: "python".to_string(),
+    };
+    assert_eq!(
+        plugin_script_path(PluginType::Transform, &builtin),
+        Some(PathBuf::from("plugins/transform/python/tagger.py"))
+    );
+    assert_eq!(
+        plugin_script_path(PluginType::Rule, &builtin),
+        Some(PathBuf::from("plugins/rule/python/tagger.py"))
+    );
+
+    let local = PluginSource::Local {
+        path: "tools/check.py".to_string(),
+    };
+    assert_eq!(
+        plugin_script_path(PluginType::Input, &local),
+        Some(PathBuf::from("tools/check.py"))
+    );
+
+    let git = PluginSource::Git {
+        url: "https://example.com/plugin.git".to_string(),
+        branch: None,
+    };
+    assert_eq!(plugin_script_path(PluginType::Output, &git), None);
+}
+
 #[test]
 fn test_code_element_creation() {
     let element = create_test_code_element();
@@ -505,6 +567,9 @@ fn test_plugin_message_can_analyze_serialization() {
     let message = PluginMessage::CanAnalyze {
         file_path: PathBuf::from("test.py"),
         content_preview: "print('hello')".to_string(),
+        content_sample: None,
+        file_size: Some(14),
+        extension: Some("py".to_string()),
     };
 
     let json =
@@ -519,9 +584,15 @@ fn test_plugin_message_can_analyze_serialization() {
         PluginMessage::CanAnalyze {
             file_path,
             content_preview,
+            content_sample,
+            file_size,
+            extension,
         } => {
             assert_eq!(file_path, PathBuf::from("test.py"));
             assert_eq!(content_preview, "print('hello')");
+            assert!(content_sample.is_none());
+            assert_eq!(file_size, Some(14));
+            assert_eq!(extension.as_deref(), Some("py"));
         }
         _ => panic!("Expected CanAnalyze message"),
     }
@@ -687,6 +758,8 @@ fn test_plugin_response_info_serialization() {
         supported_filenames: vec!["requirements.txt".to_string()],
         supported_output_types: None,
         supported_formats: None,
+        capabilities: vec![],
+        config_schema: None,
     };
 
     let json = serde_json::to_string(&response).expect("Failed to serialize PluginResponse::Info");
@@ -706,6 +779,8 @@ fn test_plugin_response_info_serialization() {
             supported_filenames,
             supported_output_types,
             supported_formats,
+            capabilities,
+            config_schema,
         } => {
             assert_eq!(name, "python_analyzer");
             assert_eq!(version, "1.2.0");
@@ -714,6 +789,8 @@ fn test_plugin_response_info_serialization() {
             assert!(supported_filenames.contains(&"requirements.txt".to_string()));
             assert!(supported_output_types.is_none());
             assert!(supported_formats.is_none());
+            assert!(capabilities.is_empty());
+            assert!(config_schema.is_none());
         }
         _ => panic!("Expected Info response"),
     }
@@ -729,6 +806,8 @@ fn test_plugin_response_info_output_plugin_serialization() {
         supported_filenames: vec![],
         supported_output_types: Some(vec!["documentation".to_string(), "reports".to_string()]),
         supported_formats: Some(vec!["markdown".to_string(), "html".to_string()]),
+        capabilities: vec![],
+        config_schema: None,
     };
 
     let json = serde_json::to_string(&response).expect("Failed to serialize output plugin Info");
@@ -794,6 +873,8 @@ fn test_plugin_info_creation() {
         supported_filenames: vec!["Cargo.toml".to_string(), "Cargo.lock".to_string()],
         supported_output_types: None,
         supported_formats: None,
+        capabilities: vec![],
+        config_schema: None,
     };
 
     assert_eq!(info.name, "rust_analyzer");
@@ -815,6 +896,8 @@ fn test_plugin_info_output_plugin() {
         supported_filenames: vec![],
         supported_output_types: Some(vec!["documentation".to_string()]),
         supported_formats: Some(vec!["markdown".to_string(), "html".to_string()]),
+        capabilities: vec![],
+        config_schema: None,
     };
 
     assert_eq!(info.name, "doc_generator");
@@ -838,6 +921,8 @@ fn test_plugin_info_capabilities_description() {
         supported_filenames: vec!["requirements.txt".to_string()],
         supported_output_types: None,
         supported_formats: None,
+        capabilities: vec![],
+        config_schema: None,
     };
 
     let input_caps = input_info.get_capabilities_description();
@@ -853,6 +938,8 @@ fn test_plugin_info_capabilities_description() {
         supported_filenames: vec![],
         supported_output_types: Some(vec!["documentation".to_string(), "reports".to_string()]),
         supported_formats: Some(vec!["markdown".to_string(), "pdf".to_string()]),
+        capabilities: vec![],
+        config_schema: None,
     };
 
     let output_caps = output_info.get_capabilities_description();
@@ -865,6 +952,8 @@ fn test_code_element_with_empty_collections() {
     let element = CodeElement {
         element_type: "variable".to_string(),
         name: "my_var".to_string(),
+        qualified_name: None,
+        parent: None,
         signature: None,
         line_start: 5,
         line_end: 5,
@@ -887,11 +976,30 @@ fn test_code_element_with_empty_collections() {
     assert!(deserialized.tokens.is_none());
 }
 
+#[test]
+fn test_code_element_qualified_name_is_optional() {
+    let json = r#"{"element_type": "method", "name": "load", "signature": null,
+        "line_start": 3, "line_end": 9, "summary": null, "complexity_score": null,
+        "calls": [], "metadata": {}, "tokens": null}"#;
+    let element: CodeElement = serde_json::from_str(json).unwrap();
+    assert!(element.qualified_name.is_none());
+    assert!(element.parent.is_none());
+
+    let mut value: serde_json::Value = serde_json::from_str(json).unwrap();
+    value["qualified_name"] = "Config::load".into();
+    value["parent"] = "Config".into();
+    let element: CodeElement = serde_json::from_value(value).unwrap();
+    assert_eq!(element.qualified_name.as_deref(), Some("Config::load"));
+    assert_eq!(element.parent.as_deref(), Some("Config"));
+}
+
 #[test]
 fn test_code_element_with_tokens() {
     let element = CodeElement {
         element_type: "function".to_string(),
         name: "calculate".to_string(),
+        qualified_name: None,
+        parent: None,
         signature: Some("fn calculate(x: i32, y: i32) -> i32".to_string()),
         line_start: 10,
         line_end: 15,
@@ -940,6 +1048,8 @@ fn test_relationship_with_no_line_number() {
         details: "function call".to_string(),
         line_number: None, // No specific line number
         strength: 1.0,
+        from_element: None,
+        to_element: None,
     };
 
     let json = serde_json::to_string(&relationship).expect("Failed to serialize Relationship");
@@ -960,6 +1070,9 @@ fn test_plugin_input_with_no_config() {
         project_root: PathBuf::from("."),
         cache_dir: ".cache".to_string(),
         plugin_config: None, // No plugin configuration
+        content_path: None,
+        truncated: false,
+        project: None,
     };
 
     let json = serde_json::to_string(&input).expect("Failed to serialize PluginInput");
@@ -979,6 +1092,8 @@ fn test_output_plugin_input_with_minimal_config() {
         cache_dir: ".cache".to_string(),
         plugin_config: None,
         format_options: serde_json::Value::Null,
+        section_cache: None,
+        scope: None,
     };
 
     let json =
@@ -999,6 +1114,7 @@ fn test_generated_output_with_empty_metadata() {
         size_bytes: 100,
         checksum: "abc123".to_string(),
         metadata: serde_json::Value::Null,
+        issues: vec![],
     };
 
     let json = serde_json::to_string(&output).expect("Failed to serialize GeneratedOutput");
@@ -1018,6 +1134,7 @@ fn test_output_plugin_result_with_multiple_outputs() {
             size_bytes: 1024,
             checksum: "hash1".to_string(),
             metadata: serde_json::json!({"sections": 3}),
+            issues: vec![],
         },
         GeneratedOutput {
             output_path: PathBuf::from("API.md"),
@@ -1025,6 +1142,7 @@ fn test_output_plugin_result_with_multiple_outputs() {
             size_bytes: 2048,
             checksum: "hash2".to_string(),
             metadata: serde_json::json!({"functions": 15}),
+            issues: vec![],
         },
     ];
 
@@ -1035,6 +1153,7 @@ fn test_output_plugin_result_with_multiple_outputs() {
         outputs,
         processing_time_ms: 1000,
         metadata: serde_json::json!({"total_files": 2}),
+        patch: None,
     };
 
     let json = serde_json::to_string(&result).expect("Failed to serialize multi-output result");
@@ -1136,6 +1255,9 @@ fn test_plugin_message_round_trip() {
         PluginMessage::CanAnalyze {
             file_path: PathBuf::from("test.py"),
             content_preview: "test content".to_string(),
+            content_sample: None,
+            file_size: None,
+            extension: None,
         },
         PluginMessage::CanGenerate {
             output_type: "docs".to_string(),
@@ -1188,6 +1310,8 @@ fn test_plugin_response_round_trip() {
             supported_filenames: vec![],
             supported_output_types: None,
             supported_formats: None,
+            capabilities: vec![],
+            config_schema: None,
         },
         PluginResponse::Error {
             message: "Test error".to_string(),
@@ -1212,3 +1336,235 @@ fn test_plugin_response_round_trip() {
         }
     }
 }
+
+#[test]
+fn test_plugin_info_capabilities_default_to_empty() {
+    // Plugins built before capability negotiation don't send the field
+    let json = r#"{"status":"info","name":"old","version":"1.0.0","plugin_type":"input",
+        "supported_extensions":[".py"],"supported_filenames":[],
+        "supported_output_types":null,"supported_formats":null}"#;
+    match serde_json::from_str::<PluginResponse>(json).expect("Failed to parse legacy info") {
+        PluginResponse::Info { capabilities, .. } => assert!(capabilities.is_empty()),
+        _ => panic!("Expected Info response"),
+    }
+
+    let info = PluginInfo {
+        name: "python".to_string(),
+        version: "1.0.0".to_string(),
+        plugin_type: PluginType::Input,
+        supported_extensions: vec![],
+        supported_filenames: vec![],
+        supported_output_types: None,
+        supported_formats: None,
+        capabilities: vec![CAPABILITY_CONTENT_PATH.to_string()],
+        config_schema: None,
+    };
+    assert!(info.supports(CAPABILITY_CONTENT_PATH));
+    assert!(!info.supports("streaming"));
+}
+
+#[test]
+fn test_plugin_input_content_path() {
+    let mut input = create_test_plugin_input();
+    let json = serde_json::to_string(&input).expect("Failed to serialize PluginInput");
+    assert!(!json.contains("content_path"));
+
+    input.use_content_path();
+    assert!(input.content.is_empty());
+    assert_eq!(input.content_path, Some(input.file_path.clone()));
+
+    let json = serde_json::to_string(&input).expect("Failed to serialize PluginInput");
+    let deserialized: PluginInput =
+        serde_json::from_str(&json).expect("Failed to deserialize PluginInput");
+    assert_eq!(
+        deserialized.content_path,
+        Some(PathBuf::from("/project/src/main.rs"))
+    );
+}
+
+#[test]
+fn test_truncated_plugin_input_keeps_content_inline() {
+    let mut input = create_test_plugin_input();
+    input.truncated = true;
+    input.use_content_path();
+    assert!(!input.content.is_empty());
+    assert!(input.content_path.is_none());
+
+    let json = serde_json::to_string(&input).expect("Failed to serialize PluginInput");
+    assert!(json.contains("\"truncated\":true"));
+    assert!(!serde_json::to_string(&create_test_plugin_input())
+        .unwrap()
+        .contains("truncated"));
+}
+
+#[test]
+fn test_plugin_message_analyze_batch_serialization() {
+    let message = PluginMessage::AnalyzeBatch {
+        inputs: vec![create_test_plugin_input(), create_test_plugin_input()],
+    };
+
+    let json = serde_json::to_string(&message).expect("Failed to serialize AnalyzeBatch");
+    assert!(json.contains("\"type\":\"analyze_batch\""));
+
+    match serde_json::from_str::<PluginMessage>(&json).expect("Failed to deserialize AnalyzeBatch")
+    {
+        PluginMessage::AnalyzeBatch { inputs } => assert_eq!(inputs.len(), 2),
+        _ => panic!("Expected AnalyzeBatch message"),
+    }
+}
+
+#[test]
+fn test_plugin_response_batch_success_deserialization() {
+    // Failed files carry an error instead of a cache file
+    let json = r#"{"status":"batch_success","results":[
+        {"relative_path":"a.py","cache_file":"a.json","processing_time_ms":12},
+        {"relative_path":"b.py","error":"cannot parse"}]}"#;
+
+    match serde_json::from_str::<PluginResponse>(json).expect("Failed to parse batch response") {
+        PluginResponse::BatchSuccess { results } => {
+            let [ok, failed]: [BatchResult; 2] = results.try_into().expect("Expected 2 results");
+            assert_eq!(ok.cache_file.as_deref(), Some("a.json"));
+            assert_eq!(ok.processing_time_ms, 12);
+            assert!(ok.error.is_none());
+            assert_eq!(failed.relative_path, PathBuf::from("b.py"));
+            assert!(failed.cache_file.is_none());
+            assert_eq!(failed.error.as_deref(), Some("cannot parse"));
+        }
+        _ => panic!("Expected BatchSuccess response"),
+    }
+}
+
+#[test]
+fn test_plugin_response_transform_success_deserialization() {
+    // Patch sections a transform doesn't use may be omitted
+    let json = r#"{"status":"transform_success","processing_time_ms":3,
+        "patch":{"tags":[{"file":"src/lib.rs","tags":["core"]}]}}"#;
+
+    match serde_json::from_str::<PluginResponse>(json).expect("Failed to parse transform response")
+    {
+        PluginResponse::TransformSuccess { patch, .. } => {
+            assert_eq!(patch.tags.len(), 1);
+            assert_eq!(patch.tags[0].tags, vec!["core".to_string()]);
+            assert!(patch.relationships.is_empty());
+            assert!(patch.summaries.is_empty());
+        }
+        _ => panic!("Expected TransformSuccess response"),
+    }
+
+    let plugin_type: PluginType = serde_json::from_str("\"transform\"").unwrap();
+    assert_eq!(plugin_type, PluginType::Transform);
+}
+
+#[test]
+fn test_plugin_response_check_success_deserialization() {
+    // Severity defaults to warning; element and line are optional
+    let json = r#"{"status":"check_success","processing_time_ms":2,"findings":[
+        {"rule":"no-print","message":"print call","file":"src/app.py","line":4},
+        {"rule":"layering","severity":"error","message":"imports ui","file":"src/db.py",
+         "element":"connect"}]}"#;
+
+    match serde_json::from_str::<PluginResponse>(json).expect("Failed to parse check response") {
+        PluginResponse::CheckSuccess { findings, .. } => {
+            assert_eq!(findings.len(), 2);
+            assert_eq!(findings[0].severity, FindingSeverity::Warning);
+            assert_eq!(findings[0].line, Some(4));
+            assert!(findings[0].element.is_none());
+            assert_eq!(findings[1].severity, FindingSeverity::Error);
+            assert_eq!(findings[1].element.as_deref(), Some("connect"));
+        }
+        _ => panic!("Expected CheckSuccess response"),
+    }
+
+    let plugin_type: PluginType = serde_json::from_str("\"rule\"").unwrap();
+    assert_eq!(plugin_type, PluginType::Rule);
+    assert!(serde_json::from_str::<FindingSeverity>("\"fatal\"").is_err());
+}
+
+#[test]
+fn test_section_cache_completed_sections() {
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    let dir = temp_dir.path();
+    for file in [
+        "usage.md",
+        "usage.done",
+        "overview.md",
+        "overview.done",
+        "api.md",
+    ] {
+        std::fs::write(dir.join(file), "").unwrap();
+    }
+
+    // Content without a marker is an interrupted write, not a finished section
+    assert_eq!(
+        SectionCache::completed_sections(dir),
+        vec!["overview".to_string(), "usage".to_string()]
+    );
+    assert!(SectionCache::completed_sections(&dir.join("missing")).is_empty());
+
+    // Inputs without a section cache still parse
+    let input: OutputPluginInput = serde_json::from_value(serde_json::json!({
+        "matrix_path": "m.json", "project_root": ".", "output_dir": "docs",
+        "cache_dir": ".csd_cache", "plugin_config": null, "format_options": {}
+    }))
+    .unwrap();
+    assert!(input.section_cache.is_none());
+}
+
+#[tokio::test]
+async fn test_content_preview_skips_bom_and_samples_middle() {
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+
+    let script = temp_dir.path().join("tool");
+    std::fs::write(
+        &script,
+        b"\xEF\xBB\xBF#!/usr/bin/env python3\nprint('hi')\n",
+    )
+    .unwrap();
+    let preview = ContentPreview::read(&script, 64).await.unwrap();
+    assert!(preview.head.starts_with("#!/usr/bin/env python3"));
+    assert!(preview.sample.is_none());
+    assert_eq!(preview.size_bytes, Some(38));
+    assert!(preview.extension.is_none());
+
+    // Front matter fills the head; the sample reaches the body
+    let page = temp_dir.path().join("Page.MD");
+    let content = format!(
+        "---\n{}---\n{}# Title\n{}",
+        "key: value\n".repeat(20),
+        "intro ".repeat(20),
+        "é".repeat(200)
+    );
+    std::fs::write(&page, &content).unwrap();
+    let preview = ContentPreview::read(&page, 128).await.unwrap();
+    assert_eq!(preview.head.len(), 128);
+    assert!(preview.head.starts_with("---\nkey: value"));
+    let sample = preview.sample.expect("middle sample");
+    assert!(!sample.is_empty() && sample.len() <= 128);
+    assert!(!sample.contains('\u{fffd}'));
+    assert_eq!(preview.size_bytes, Some(content.len() as u64));
+    assert_eq!(preview.extension.as_deref(), Some("md"));
+}
+
+#[tokio::test]
+async fn test_content_preview_caps_large_reads() {
+    let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    let blob = temp_dir.path().join("dump.sql");
+    std::fs::write(&blob, "x".repeat(4 * ContentPreview::MAX_BYTES)).unwrap();
+
+    let preview = ContentPreview::read(&blob, usize::MAX).await.unwrap();
+    assert_eq!(preview.head.len(), ContentPreview::MAX_BYTES);
+    assert_eq!(preview.sample.unwrap().len(), ContentPreview::MAX_BYTES);
+    assert_eq!(
+        preview.size_bytes,
+        Some(4 * ContentPreview::MAX_BYTES as u64)
+    );
+}
+
+#[test]
+fn test_content_preview_from_text() {
+    let preview =
+        ContentPreview::from_text(std::path::Path::new("lib.rs"), "\u{feff}fn main() {}", 4);
+    assert_eq!(preview.head, "fn m");
+    assert_eq!(preview.extension.as_deref(), Some("rs"));
+    assert!(preview.size_bytes.is_none());
+}
@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+
+use chrono::Utc;
+use csd::plugins::communication::PluginCommunicator;
+use csd::plugins::interface::{PluginInput, PluginMessage, PluginResponse};
+use csd::plugins::logs::{log_path, prune_plugin_logs, Transcript};
+use csd::utils::config::PluginLogConfig;
+
+use crate::rust::common::python_available;
+
+fn analyze(file: &str) -> PluginMessage {
+    PluginMessage::Analyze {
+        input: PluginInput {
+            file_path: PathBuf::from(file),
+            relative_path: PathBuf::from(file),
+            content: String::new(),
+            project_root: PathBuf::from("."),
+            cache_dir: ".csd_cache".to_string(),
+            plugin_config: None,
+            content_path: None,
+            truncated: false,
+            project: None,
+        },
+    }
+}
+
+fn write_logs(dir: &Path, count: usize) -> Vec<PathBuf> {
+    std::fs::create_dir_all(dir).unwrap();
+    (0..count)
+        .map(|i| {
+            let path = dir.join(format!("{i}.log"));
+            std::fs::write(&path, "log").unwrap();
+            // Older files first, a minute apart
+            let modified = SystemTime::now() - Duration::from_secs(60 * (count - i) as u64);
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+            path
+        })
+        .collect()
+}
+
+#[test]
+fn test_log_path_is_keyed_by_plugin_and_file() {
+    let cache = Path::new(".csd_cache");
+    let plugin = Path::new("plugins/input/rust_analyzer.py");
+
+    let first = log_path(cache, plugin, &analyze("src/main.rs"));
+    assert!(first.starts_with(".csd_cache/logs/rust_analyzer"));
+    assert_eq!(first.extension().unwrap(), "log");
+
+    // Stable per file, distinct between files
+    assert_eq!(first, log_path(cache, plugin, &analyze("src/main.rs")));
+    assert_ne!(first, log_path(cache, plugin, &analyze("src/lib.rs")));
+
+    assert_eq!(
+        log_path(cache, plugin, &PluginMessage::GetInfo),
+        cache.join("logs/rust_analyzer/get_info.log")
+    );
+}
+
+#[test]
+fn test_transcript_render_includes_streams() {
+    let message = analyze("src/main.rs");
+    let transcript = Transcript {
+        plugin_path: Path::new("plugins/input/rust_analyzer.py"),
+        message: &message,
+        started: Utc::now(),
+        duration: Duration::from_millis(42),
+        outcome: "exit status: 1",
+        stdout: "partial output\n",
+        stderr: "Traceback (most recent call last):\n",
+    };
+
+    let log = transcript.render();
+    assert!(log.contains("message: analyze"));
+    assert!(log.contains("file: src/main.rs"));
+    assert!(log.contains("duration_ms: 42"));
+    assert!(log.contains("outcome: exit status: 1"));
+    assert!(log.contains("--- stdout ---\npartial output\n"));
+    assert!(log.contains("--- stderr ---\nTraceback"));
+}
+
+#[tokio::test]
+async fn test_prune_keeps_newest_logs_per_plugin() {
+    let dir = TempDir::new().unwrap();
+    let logs = write_logs(&dir.path().join("logs/rust_analyzer"), 5);
+    let other = write_logs(&dir.path().join("logs/python_analyzer"), 2);
+
+    let config = PluginLogConfig {
+        max_files_per_plugin: 3,
+        ..PluginLogConfig::default()
+    };
+    let removed = prune_plugin_logs(dir.path(), &config).await.unwrap();
+
+    assert_eq!(removed, 2);
+    assert!(!logs[0].exists() && !logs[1].exists());
+    assert!(logs[2..].iter().all(|log| log.exists()));
+    assert!(other.iter().all(|log| log.exists()));
+}
+
+#[tokio::test]
+async fn test_prune_removes_expired_logs() {
+    let dir = TempDir::new().unwrap();
+    let plugin_dir = dir.path().join("logs/rust_analyzer");
+    let logs = write_logs(&plugin_dir, 2);
+    let old = SystemTime::now() - Duration::from_secs(10 * 24 * 3600);
+    std::fs::File::options()
+        .write(true)
+        .open(&logs[0])
+        .unwrap()
+        .set_modified(old)
+        .unwrap();
+
+    let removed = prune_plugin_logs(dir.path(), &PluginLogConfig::default())
+        .await
+        .unwrap();
+
+    assert_eq!(removed, 1);
+    assert!(!logs[0].exists());
+    assert!(logs[1].exists());
+}
+
+#[tokio::test]
+async fn test_failing_plugin_error_references_log() {
+    if !python_available() {
+        return;
+    }
+    let dir = TempDir::new().unwrap();
+    let plugin = dir.path().join("broken_lang.py");
+    std::fs::write(
+        &plugin,
+        "import sys\nsys.stdin.read()\nprint('parser exploded', file=sys.stderr)\nsys.exit(3)\n",
+    )
+    .unwrap();
+    let cache = dir.path().join("cache");
+
+    let error = PluginCommunicator::new(plugin)
+        .with_cache_dir(cache.clone())
+        .with_python_auto_detect()
+        .send_message(PluginMessage::GetInfo)
+        .await
+        .unwrap_err()
+        .to_string();
+
+    let log = cache.join("logs/broken_lang/get_info.log");
+    assert!(
+        error.contains(&format!("(log: {})", log.display())),
+        "{error}"
+    );
+    let transcript = std::fs::read_to_string(&log).unwrap();
+    assert!(transcript.contains("parser exploded"));
+    assert!(transcript.contains("outcome: exit status: 3"));
+}
+
+#[tokio::test]
+async fn test_reported_error_is_logged_unless_disabled() {
+    if !python_available() {
+        return;
+    }
+    let dir = TempDir::new().unwrap();
+    let plugin = dir.path().join("quiet_lang.py");
+    std::fs::write(
+        &plugin,
+        "import json, sys\nsys.stdin.read()\nprint(json.dumps({'status': 'error', 'message': 'unsupported', 'details': None}))\n",
+    )
+    .unwrap();
+    let cache = dir.path().join("cache");
+    let communicator = PluginCommunicator::new(plugin)
+        .with_cache_dir(cache.clone())
+        .with_python_auto_detect();
+
+    // Errors reported through the protocol are logged and point at the log
+    match communicator
+        .send_message(PluginMessage::GetInfo)
+        .await
+        .unwrap()
+    {
+        PluginResponse::Error { message, .. } => assert!(message.contains("(log: "), "{message}"),
+        other => panic!("unexpected response: {other:?}"),
+    }
+    assert!(cache.join("logs/quiet_lang/get_info.log").exists());
+
+    // Disabled logging writes nothing
+    let disabled = TempDir::new().unwrap();
+    let communicator = communicator
+        .with_cache_dir(disabled.path().to_path_buf())
+        .with_logs(PluginLogConfig {
+            enabled: false,
+            ..PluginLogConfig::default()
+        });
+    communicator
+        .send_message(PluginMessage::GetInfo)
+        .await
+        .unwrap();
+    assert!(!disabled.path().join("logs").exists());
+}
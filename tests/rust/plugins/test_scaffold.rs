@@ -0,0 +1,122 @@
+use csd::plugins::interface::PluginType;
+use csd::plugins::scaffold::PluginScaffold;
+use csd::utils::config::{InputPluginConfig, PluginSource, TransformPluginConfig};
+use std::path::Path;
+use tempfile::TempDir;
+
+#[test]
+fn test_rejects_names_that_are_not_python_modules() {
+    for name in ["", "MyLang", "my-lang", "2lang", "_private", "my lang"] {
+        assert!(
+            PluginScaffold::new(name, PluginType::Input).is_err(),
+            "{name:?} should be rejected"
+        );
+    }
+    assert!(PluginScaffold::new("my_lang2", PluginType::Input).is_ok());
+}
+
+#[test]
+fn test_class_name_per_plugin_type() {
+    let class = |plugin_type| {
+        PluginScaffold::new("my_lang", plugin_type)
+            .unwrap()
+            .class_name()
+    };
+    assert_eq!(class(PluginType::Input), "MyLangAnalyzer");
+    assert_eq!(class(PluginType::Output), "MyLangOutput");
+    assert_eq!(class(PluginType::Transform), "MyLangTransform");
+}
+
+#[test]
+fn test_extensions_default_to_name_and_gain_leading_dot() {
+    let scaffold = PluginScaffold::new("zig", PluginType::Input).unwrap();
+    assert_eq!(scaffold.extensions, vec![".zig"]);
+
+    let scaffold = scaffold.with_extensions(&["ml".to_string(), ".mli".to_string()]);
+    assert_eq!(scaffold.extensions, vec![".ml", ".mli"]);
+}
+
+#[test]
+fn test_input_plugin_files_are_rendered() {
+    let scaffold = PluginScaffold::new("my_lang", PluginType::Input)
+        .unwrap()
+        .with_extensions(&["ml".to_string()]);
+    let files = scaffold.files();
+
+    let names: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["my_lang.py", "test_my_lang.py"]);
+
+    let (_, plugin) = &files[0];
+    assert!(plugin.contains("class MyLangAnalyzer(BaseAnalyzer):"));
+    assert!(plugin.contains("self.name = \"my_lang\""));
+    assert!(plugin.contains("self.supported_extensions = [\".ml\"]"));
+    assert!(plugin.contains("MyLangAnalyzer().run()"));
+
+    let (_, test) = &files[1];
+    assert!(test.contains("from my_lang import MyLangAnalyzer"));
+    assert!(test.contains("src/sample.ml"));
+    assert!(!plugin.contains("{{") && !test.contains("{{"));
+}
+
+#[test]
+fn test_output_and_transform_plugins_use_their_base_classes() {
+    let rendered = |plugin_type| {
+        PluginScaffold::new("report", plugin_type).unwrap().files()[0]
+            .1
+            .clone()
+    };
+    assert!(rendered(PluginType::Output).contains("class ReportOutput(BaseOutputPlugin):"));
+    assert!(rendered(PluginType::Transform).contains("class ReportTransform(BaseTransformPlugin):"));
+}
+
+#[test]
+fn test_write_refuses_to_overwrite_without_force() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let scaffold = PluginScaffold::new("my_lang", PluginType::Input).unwrap();
+
+    let written = scaffold.write(temp_dir.path(), false).unwrap();
+    let plugin_dir = temp_dir.path().join("input").join("my_lang");
+    assert_eq!(
+        written,
+        vec![
+            plugin_dir.join("my_lang.py"),
+            plugin_dir.join("test_my_lang.py")
+        ]
+    );
+    assert!(written.iter().all(|path| path.exists()));
+
+    std::fs::write(&written[0], "# edited").unwrap();
+    let err = scaffold.write(temp_dir.path(), false).unwrap_err();
+    assert!(err.to_string().contains("already exists"));
+    assert_eq!(std::fs::read_to_string(&written[0]).unwrap(), "# edited");
+
+    scaffold.write(temp_dir.path(), true).unwrap();
+    assert!(std::fs::read_to_string(&written[0])
+        .unwrap()
+        .contains("MyLangAnalyzer"));
+}
+
+#[test]
+fn test_config_value_registers_local_plugin() {
+    let scaffold = PluginScaffold::new("my_lang", PluginType::Input)
+        .unwrap()
+        .with_extensions(&["ml".to_string()]);
+    assert_eq!(scaffold.config_key(), "input_plugins.my_lang");
+
+    let value = scaffold
+        .config_value(Path::new("plugins/input/my_lang/my_lang.py"))
+        .unwrap();
+    let config: InputPluginConfig = serde_yaml::from_value(value).unwrap();
+    assert!(config.enabled);
+    assert_eq!(config.file_patterns.extensions, vec![".ml"]);
+    assert!(matches!(
+        config.source,
+        PluginSource::Local { ref path } if path == "plugins/input/my_lang/my_lang.py"
+    ));
+
+    let scaffold = PluginScaffold::new("tagger", PluginType::Transform).unwrap();
+    assert_eq!(scaffold.config_key(), "transform_plugins.tagger");
+    let value = scaffold.config_value(Path::new("tagger.py")).unwrap();
+    let config: TransformPluginConfig = serde_yaml::from_value(value).unwrap();
+    assert_eq!(config.order, 0);
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+use csd::plugins::transport::{read_frame, write_frame, PluginListener};
+
+#[tokio::test]
+async fn test_frames_round_trip() {
+    let (mut a, mut b) = tokio::io::duplex(64);
+    let writer = tokio::spawn(async move {
+        write_frame(&mut a, br#"{"type":"get_info"}"#)
+            .await
+            .unwrap();
+        write_frame(&mut a, &[b'x'; 1000]).await.unwrap();
+    });
+
+    assert_eq!(
+        read_frame(&mut b).await.unwrap().unwrap(),
+        br#"{"type":"get_info"}"#
+    );
+    assert_eq!(read_frame(&mut b).await.unwrap().unwrap().len(), 1000);
+    writer.await.unwrap();
+    // Closed between frames
+    assert!(read_frame(&mut b).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn testoversized_frames_are_refused() {
+    let (mut a, mut b) = tokio::io::duplex(64);
+    tokio::io::AsyncWriteExt::write_all(&mut a, &u32::MAX.to_be_bytes())
+        .await
+        .unwrap();
+    let err = read_frame(&mut b).await.unwrap_err();
+    assert!(err.to_string().contains("limit"), "{err}");
+}
+
+#[tokio::test]
+async fn test_listener_only_accepts_the_token() {
+    let listener = PluginListener::bind(true).await.unwrap();
+    let address = listener.address().unwrap();
+    let port = address
+        .strip_prefix("tcp:")
+        .expect("a TCP address")
+        .to_string();
+    let token = listener.token.clone();
+
+    let connect = |token: String| {
+        let port = port.clone();
+        async move {
+            let mut stream = tokio::net::TcpStream::connect(port).await.unwrap();
+            let hello = serde_json::json!({ "token": token }).to_string();
+            write_frame(&mut stream, hello.as_bytes()).await.unwrap();
+            stream
+        }
+    };
+    let _intruder = connect("guess".to_string()).await;
+    // Claims a frame far larger than any hello; refused without waiting for the bytes
+    let mut oversized = tokio::net::TcpStream::connect(port.clone()).await.unwrap();
+    oversized
+        .write_all(&(64u32 * 1024 * 1024).to_be_bytes())
+        .await
+        .unwrap();
+    let mut plugin = connect(token).await;
+
+    let mut accepted = tokio::time::timeout(Duration::from_secs(5), listener.accept())
+        .await
+        .expect("the plugin's connection is accepted")
+        .unwrap();
+    write_frame(&mut accepted, b"{}").await.unwrap();
+    assert_eq!(read_frame(&mut plugin).await.unwrap().unwrap(), b"{}");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_unix_socket_is_removed_with_listener() {
+    let listener = PluginListener::bind(false).await.unwrap();
+    let address = listener.address().unwrap();
+    let path = std::path::PathBuf::from(address.strip_prefix("unix:").expect("a Unix address"));
+    assert!(path.exists());
+    drop(listener);
+    assert!(!path.exists());
+}
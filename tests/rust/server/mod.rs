@@ -0,0 +1,6 @@
+// Server module tests
+
+pub mod test_auth;
+pub mod test_graphql;
+pub mod test_http;
+pub mod test_registry;
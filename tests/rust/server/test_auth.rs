@@ -0,0 +1,71 @@
+use csd::server::auth::{AuthError, Authenticator};
+use csd::utils::config::{ApiConfig, ApiRole, ApiTokenConfig};
+
+fn token(name: &str, secret: &str, role: ApiRole, projects: &[&str]) -> ApiTokenConfig {
+    ApiTokenConfig {
+        name: name.to_string(),
+        token: secret.to_string(),
+        role,
+        projects: projects.iter().map(|p| p.to_string()).collect(),
+    }
+}
+
+fn authenticator() -> Authenticator {
+    Authenticator::from_config(&ApiConfig {
+        tokens: vec![
+            token("dashboards", "read-secret", ApiRole::Read, &[]),
+            token("ci", "write-secret", ApiRole::Write, &["shop"]),
+            // An unset `${VAR}` leaves an empty token, which must not match an empty header
+            token("unset", "", ApiRole::Write, &[]),
+        ],
+    })
+}
+
+#[test]
+fn test_roles_and_projects() {
+    let auth = authenticator();
+    assert!(!auth.is_open());
+
+    assert_eq!(
+        auth.authorize(Some("Bearer read-secret"), ApiRole::Read, Some("billing")),
+        Ok(Some("dashboards"))
+    );
+    assert_eq!(
+        auth.authorize(Some("Bearer read-secret"), ApiRole::Write, Some("shop")),
+        Err(AuthError::Forbidden)
+    );
+    assert_eq!(
+        auth.authorize(Some("Bearer write-secret"), ApiRole::Write, Some("shop")),
+        Ok(Some("ci"))
+    );
+    // Scoped tokens reach only their projects
+    assert_eq!(
+        auth.authorize(Some("Bearer write-secret"), ApiRole::Read, Some("billing")),
+        Err(AuthError::Forbidden)
+    );
+    assert!(auth.can_see(Some("Bearer write-secret"), "shop"));
+    assert!(!auth.can_see(Some("Bearer write-secret"), "billing"));
+}
+
+#[test]
+fn test_missing_and_wrong_tokens() {
+    let auth = authenticator();
+    for header in [
+        None,
+        Some(""),
+        Some("Bearer "),
+        Some("Bearer nope"),
+        Some("read-secret"),
+    ] {
+        assert_eq!(
+            auth.authorize(header, ApiRole::Read, None),
+            Err(AuthError::Unauthenticated),
+            "{header:?}"
+        );
+    }
+
+    // Without tokens everything is allowed
+    let open = Authenticator::default();
+    assert!(open.is_open());
+    assert_eq!(open.authorize(None, ApiRole::Write, Some("shop")), Ok(None));
+}
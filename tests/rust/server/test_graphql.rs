@@ -0,0 +1,342 @@
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+use csd::core::annotations::{Annotations, Note};
+use csd::core::matrix::{CodeElement, ElementType, ProjectMatrix};
+use csd::server::graphql::{execute, GraphQLRequest, GraphQLResponse};
+use csd::server::http::{ApiServer, ProjectState, ServerState};
+
+use crate::rust::core::test_matrix::{create_test_file_node, create_test_relationship};
+
+fn function(name: &str, line_start: u32) -> CodeElement {
+    CodeElement {
+        element_type: ElementType::Function,
+        name: name.to_string(),
+        qualified_name: None,
+        parent: None,
+        signature: Some(format!("fn {name}()")),
+        line_start,
+        line_end: line_start + 4,
+        summary: None,
+        complexity_score: Some(2),
+        calls: Vec::new(),
+        metadata: Value::Null,
+        tokens: 30,
+        element_id: String::new(),
+        coverage: None,
+    }
+}
+
+fn sample_matrix() -> ProjectMatrix {
+    let mut matrix = ProjectMatrix::new("/project".into());
+    let mut main = create_test_file_node("src/main.rs", "rust");
+    main.elements = vec![function("main", 1), function("run", 8)];
+    matrix.add_file(main);
+    matrix.add_file(create_test_file_node("src/lib.rs", "rust"));
+    matrix.add_file(create_test_file_node("app.py", "python"));
+    matrix.add_relationship(create_test_relationship("src/main.rs", "src/lib.rs"));
+    matrix.add_relationship(create_test_relationship("app.py", "src/lib.rs"));
+    matrix
+}
+
+fn run(matrix: &mut ProjectMatrix, query: &str, variables: Value) -> GraphQLResponse {
+    let metrics = matrix.calculate_metrics();
+    let request = GraphQLRequest {
+        query: query.to_string(),
+        variables: variables.as_object().cloned(),
+        operation_name: None,
+    };
+    execute(matrix, &metrics, &Annotations::default(), &request)
+}
+
+#[test]
+fn test_nested_query_with_arguments_and_aliases() {
+    let mut matrix = sample_matrix();
+    let response = run(
+        &mut matrix,
+        r#"
+        query Rust($glob: String = "src/*.rs") {
+          project { totalFiles }
+          rust: files(path: $glob) {
+            path
+            elements(type: FUNCTION, first: 1) { name startLine file { path } }
+            outgoing { toPath }
+            incoming { from { path } }
+          }
+          python: files(language: "python") { ...Names }
+        }
+        fragment Names on File { path __typename }
+        "#,
+        json!({}),
+    );
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data.unwrap(),
+        json!({
+            "project": { "totalFiles": 3 },
+            "rust": [
+                {
+                    "path": "src/lib.rs",
+                    "elements": [],
+                    "outgoing": [],
+                    "incoming": [
+                        { "from": { "path": "src/main.rs" } },
+                        { "from": { "path": "app.py" } },
+                    ],
+                },
+                {
+                    "path": "src/main.rs",
+                    "elements": [
+                        { "name": "main", "startLine": 1, "file": { "path": "src/main.rs" } },
+                    ],
+                    "outgoing": [{ "toPath": "src/lib.rs" }],
+                    "incoming": [],
+                },
+            ],
+            "python": [{ "path": "app.py", "__typename": "File" }],
+        })
+    );
+}
+
+#[test]
+fn test_variables_directives_and_metrics() {
+    let mut matrix = sample_matrix();
+    let response = run(
+        &mut matrix,
+        r#"query ($path: String!, $withElements: Boolean!) {
+             file(path: $path) {
+               path
+               elements @include(if: $withElements) { name }
+             }
+             metrics { totalRelationships highlyCoupled { path dependents } }
+             relationships(to: "src/*", type: "import", first: 1) { fromPath }
+           }"#,
+        json!({ "path": "src/main.rs", "withElements": false }),
+    );
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data = response.data.unwrap();
+    assert_eq!(data["file"], json!({ "path": "src/main.rs" }));
+    assert_eq!(data["metrics"]["totalRelationships"], 2);
+    assert_eq!(
+        data["metrics"]["highlyCoupled"][0],
+        json!({ "path": "src/lib.rs", "dependents": 2 })
+    );
+    assert_eq!(data["relationships"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_invalid_queries_are_rejected() {
+    let mut matrix = sample_matrix();
+    for (query, message) in [
+        (
+            "{ files { nope } }",
+            "Cannot query field 'nope' on type 'File'",
+        ),
+        ("{ files }", "needs a selection of subfields"),
+        ("{ project { root { path } } }", "is a scalar"),
+        ("mutation { scan }", "read-only"),
+        ("{ files { path }", "Syntax error"),
+        (
+            "query ($path: String!) { file(path: $path) { path } }",
+            "$path is required",
+        ),
+    ] {
+        let response = run(&mut matrix, query, json!({}));
+        assert!(response.data.is_none(), "{query}");
+        assert!(
+            response.errors[0].message.contains(message),
+            "{query}: {}",
+            response.errors[0].message
+        );
+    }
+
+    // Argument errors only null the field they're on
+    let response = run(
+        &mut matrix,
+        r#"{ project { totalFiles } relationships(type: "teleport") { fromPath } }"#,
+        json!({}),
+    );
+    let data = response.data.unwrap();
+    assert_eq!(data["project"]["totalFiles"], 3);
+    assert!(data["relationships"].is_null());
+    assert_eq!(response.errors[0].path, vec![json!("relationships")]);
+}
+
+#[test]
+fn test_deeply_nested_documents_are_rejected_without_overflowing() {
+    let mut matrix = sample_matrix();
+    let deep = 100_000;
+    for query in [
+        format!(
+            "{{ files(path: {}1{}) {{ path }} }}",
+            "[".repeat(deep),
+            "]".repeat(deep)
+        ),
+        format!("{}{}", "{ files ".repeat(deep), "}".repeat(deep)),
+        format!("{{ {}path{} }}", "... { ".repeat(deep), " }".repeat(deep)),
+        format!(
+            "query ($x: {}String{}) {{ files {{ path }} }}",
+            "[".repeat(deep),
+            "]".repeat(deep)
+        ),
+    ] {
+        let response = run(&mut matrix, &query, json!({}));
+        assert!(response.data.is_none());
+        assert!(
+            response.errors[0].message.contains("nested more than"),
+            "{}",
+            response.errors[0].message
+        );
+    }
+}
+
+#[test]
+fn test_queries_over_budget_are_rejected() {
+    // Every file imports every other, so each `outgoing { to { ... } }` level multiplies
+    // the result by seven
+    let mut matrix = ProjectMatrix::new("/project".into());
+    let names: Vec<String> = (0..8).map(|i| format!("src/m{i}.rs")).collect();
+    for name in &names {
+        matrix.add_file(create_test_file_node(name, "rust"));
+    }
+    for from in &names {
+        for to in names.iter().filter(|to| *to != from) {
+            matrix.add_relationship(create_test_relationship(from, to));
+        }
+    }
+
+    let nested = format!(
+        "{{ files {}{{ path }}{} }}",
+        "{ outgoing { to ".repeat(5),
+        " } }".repeat(5)
+    );
+    let response = run(&mut matrix, &nested, json!({}));
+    assert!(response.data.is_none());
+    assert!(
+        response.errors[0].message.contains("nodes"),
+        "{}",
+        response.errors[0].message
+    );
+
+    let aliased: String = (0..40)
+        .map(|i| format!("f{i}: file(path: \"x\") {{ path }} "))
+        .collect();
+    let response = run(&mut matrix, &format!("{{ {aliased} }}"), json!({}));
+    assert!(response.errors[0].message.contains("root fields"));
+
+    let aliased: String = (0..40).map(|i| format!("p{i}: path ")).collect();
+    let response = run(
+        &mut matrix,
+        &format!("{{ files {{ {aliased} }} }}"),
+        json!({}),
+    );
+    assert!(response.errors[0].message.contains("aliases"));
+
+    // Within the budget the same graph still answers
+    let response = run(
+        &mut matrix,
+        "{ files { outgoing { to { path } } } }",
+        json!({}),
+    );
+    assert!(response.errors.is_empty());
+    assert_eq!(
+        response.data.unwrap()["files"][0]["outgoing"]
+            .as_array()
+            .unwrap()
+            .len(),
+        7
+    );
+}
+
+#[test]
+fn test_annotations_are_resolved() {
+    let mut matrix = sample_matrix();
+    let metrics = matrix.calculate_metrics();
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let mut annotations = Annotations::load(temp_dir.path()).unwrap();
+    annotations.file_mut("src/main.rs".as_ref()).owner = Some("cli".to_string());
+    let main = matrix
+        .files
+        .get(std::path::Path::new("src/main.rs"))
+        .unwrap();
+    annotations
+        .element_mut(main, &main.elements[1])
+        .notes
+        .push(Note {
+            text: "Entry for every subcommand".to_string(),
+            author: None,
+            date: chrono::Utc::now(),
+        });
+
+    let request = GraphQLRequest {
+        query: r#"{ file(path: "src/main.rs") {
+                      annotation { owner review }
+                      elements { name annotation { notes { text author } } }
+                    } }"#
+            .to_string(),
+        ..GraphQLRequest::default()
+    };
+    let response = execute(&matrix, &metrics, &annotations, &request);
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data.unwrap()["file"],
+        json!({
+            "annotation": { "owner": "cli", "review": null },
+            "elements": [
+                { "name": "main", "annotation": null },
+                {
+                    "name": "run",
+                    "annotation": {
+                        "notes": [{ "text": "Entry for every subcommand", "author": null }],
+                    },
+                },
+            ],
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_graphql_endpoint() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let matrix_path = temp_dir.path().join("matrix.json");
+    let state = Arc::new(ServerState::new(vec![Arc::new(ProjectState::new(
+        "default",
+        matrix_path.clone(),
+        temp_dir.path().to_path_buf(),
+    ))]));
+    let server = ApiServer::bind("127.0.0.1:0".parse().unwrap(), state).unwrap();
+    let url = format!("http://{}/graphql", server.local_addr().unwrap());
+    tokio::spawn(server.run());
+    let client = reqwest::Client::new();
+
+    let schema = client.get(&url).send().await.unwrap();
+    assert_eq!(schema.status(), 200);
+    assert!(schema.text().await.unwrap().contains("type Query {"));
+
+    let query = json!({ "query": "{ files { path } }" });
+    let missing = client.post(&url).json(&query).send().await.unwrap();
+    assert_eq!(missing.status(), 404);
+
+    sample_matrix().save(&matrix_path).await.unwrap();
+    let response: Value = client
+        .post(&url)
+        .json(&query)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(response["data"]["files"].as_array().unwrap().len(), 3);
+
+    let invalid = client.post(&url).body("not json").send().await.unwrap();
+    assert_eq!(invalid.status(), 400);
+    let body: Value = invalid.json().await.unwrap();
+    assert!(body["errors"][0]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Invalid request body"));
+}
@@ -0,0 +1,346 @@
+use std::sync::Arc;
+use tempfile::TempDir;
+
+use csd::core::annotations::{Annotations, ReviewStatus};
+use csd::core::events::{EventLog, MatrixChange, MatrixEvent};
+use csd::server::auth::Authenticator;
+use csd::server::http::{ApiServer, ProjectState, ScanRun, ServerState};
+use csd::utils::config::{ApiConfig, ApiRole, ApiTokenConfig};
+
+fn project(id: &str, dir: &std::path::Path) -> Arc<ProjectState> {
+    Arc::new(ProjectState::new(
+        id,
+        dir.join("matrix.json"),
+        dir.to_path_buf(),
+    ))
+}
+
+fn serve(projects: Vec<Arc<ProjectState>>) -> String {
+    serve_with(ServerState::new(projects))
+}
+
+fn serve_with(state: ServerState) -> String {
+    let state = Arc::new(state);
+    let server = ApiServer::bind("127.0.0.1:0".parse().unwrap(), state).unwrap();
+    let url = format!("http://{}", server.local_addr().unwrap());
+    tokio::spawn(server.run());
+    url
+}
+
+async fn start(temp_dir: &TempDir) -> (String, Arc<ProjectState>) {
+    let state = project("default", temp_dir.path());
+    (serve(vec![state.clone()]), state)
+}
+
+#[tokio::test]
+async fn test_health_and_unknown_endpoints() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let (url, _) = start(&temp_dir).await;
+    let client = reqwest::Client::new();
+
+    let health: serde_json::Value = client
+        .get(format!("{url}/health"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(health["status"], "ok");
+
+    let missing = client.get(format!("{url}/nope")).send().await.unwrap();
+    assert_eq!(missing.status(), 404);
+    let post = client.post(format!("{url}/health")).send().await.unwrap();
+    assert_eq!(post.status(), 405);
+}
+
+#[tokio::test]
+async fn test_matrix_history_and_status() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let (url, state) = start(&temp_dir).await;
+    let client = reqwest::Client::new();
+
+    // Before the first scan finishes there's no matrix and no history
+    let matrix = client.get(format!("{url}/matrix")).send().await.unwrap();
+    assert_eq!(matrix.status(), 404);
+    let history: Vec<serde_json::Value> = client
+        .get(format!("{url}/history"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(history.is_empty());
+
+    std::fs::write(temp_dir.path().join("matrix.json"), r#"{"files":{}}"#).unwrap();
+    {
+        let mut status = state.status.write().await;
+        status.scans = 1;
+        status.last_scan = Some(ScanRun {
+            started_at: chrono::Utc::now(),
+            finished_at: chrono::Utc::now(),
+            success: false,
+            gate: "failed: 2 plugin errors (--fail-on plugin-errors)".to_string(),
+            commit: None,
+        });
+    }
+
+    let matrix = client.get(format!("{url}/matrix")).send().await.unwrap();
+    assert_eq!(matrix.status(), 200);
+    assert_eq!(matrix.text().await.unwrap(), r#"{"files":{}}"#);
+
+    let status: serde_json::Value = client
+        .get(format!("{url}/status"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(status["scans"], 1);
+    assert_eq!(status["scanning"], false);
+    assert_eq!(status["last_scan"]["success"], false);
+}
+
+#[tokio::test]
+async fn test_projects_routes() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    for id in ["shop", "billing"] {
+        std::fs::create_dir_all(temp_dir.path().join(id)).unwrap();
+    }
+    std::fs::write(temp_dir.path().join("shop/matrix.json"), r#"{"id":"shop"}"#).unwrap();
+    let url = serve(vec![
+        project("shop", &temp_dir.path().join("shop")),
+        project("billing", &temp_dir.path().join("billing")),
+    ]);
+    let client = reqwest::Client::new();
+
+    let projects: Vec<serde_json::Value> = client
+        .get(format!("{url}/projects"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let ids: Vec<&str> = projects.iter().filter_map(|p| p["id"].as_str()).collect();
+    assert_eq!(ids, vec!["shop", "billing"]);
+
+    let shop = client
+        .get(format!("{url}/projects/shop/matrix"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(shop.text().await.unwrap(), r#"{"id":"shop"}"#);
+    let billing = client
+        .get(format!("{url}/projects/billing/matrix"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(billing.status(), 404);
+    let unknown = client
+        .get(format!("{url}/projects/crm/status"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(unknown.status(), 404);
+
+    // With several projects, top-level routes don't pick one
+    let ambiguous = client.get(format!("{url}/matrix")).send().await.unwrap();
+    assert_eq!(ambiguous.status(), 404);
+}
+
+#[tokio::test]
+async fn test_tokens_and_roles() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let shop = project("shop", temp_dir.path());
+    let auth = Authenticator::from_config(&ApiConfig {
+        tokens: vec![
+            ApiTokenConfig {
+                name: "dashboards".to_string(),
+                token: "read-secret".to_string(),
+                role: ApiRole::Read,
+                projects: Vec::new(),
+            },
+            ApiTokenConfig {
+                name: "ci".to_string(),
+                token: "write-secret".to_string(),
+                role: ApiRole::Write,
+                projects: Vec::new(),
+            },
+        ],
+    });
+    let url = serve_with(ServerState::new(vec![shop.clone()]).with_auth(auth));
+    let client = reqwest::Client::new();
+
+    // Health checks need no token; everything else does, known route or not
+    let health = client.get(format!("{url}/health")).send().await.unwrap();
+    assert_eq!(health.status(), 200);
+    for path in ["/status", "/projects", "/projects/nope/status"] {
+        let response = client.get(format!("{url}{path}")).send().await.unwrap();
+        assert_eq!(response.status(), 401, "{path}");
+        assert_eq!(response.headers()["www-authenticate"], "Bearer");
+    }
+
+    let status = client
+        .get(format!("{url}/projects/shop/status"))
+        .bearer_auth("read-secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(status.status(), 200);
+
+    // Reading doesn't allow requesting a scan
+    let scan = client
+        .post(format!("{url}/projects/shop/scan"))
+        .bearer_auth("read-secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(scan.status(), 403);
+    assert!(!shop.rescan.load(std::sync::atomic::Ordering::SeqCst));
+
+    let scan = client
+        .post(format!("{url}/scan"))
+        .bearer_auth("write-secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(scan.status(), 202);
+    assert!(shop.rescan.load(std::sync::atomic::Ordering::SeqCst));
+
+    let wrong_method = client
+        .get(format!("{url}/scan"))
+        .bearer_auth("write-secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(wrong_method.status(), 405);
+}
+
+#[tokio::test]
+async fn test_scoped_token_cannot_probe_other_projects() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let shop = project("shop", &temp_dir.path().join("shop"));
+    let billing = project("billing", &temp_dir.path().join("billing"));
+    let auth = Authenticator::from_config(&ApiConfig {
+        tokens: vec![ApiTokenConfig {
+            name: "shop-dashboard".to_string(),
+            token: "shop-secret".to_string(),
+            role: ApiRole::Read,
+            projects: vec!["shop".to_string()],
+        }],
+    });
+    let url = serve_with(ServerState::new(vec![shop, billing]).with_auth(auth));
+    let client = reqwest::Client::new();
+
+    // An existing project it can't see and one that doesn't exist look the same
+    let mut responses = Vec::new();
+    for id in ["billing", "nope"] {
+        let response = client
+            .get(format!("{url}/projects/{id}/status"))
+            .bearer_auth("shop-secret")
+            .send()
+            .await
+            .unwrap();
+        responses.push((response.status(), response.text().await.unwrap()));
+    }
+    assert_eq!(responses[0], responses[1]);
+    assert_eq!(responses[0].0, 403);
+
+    let status = client
+        .get(format!("{url}/projects/shop/status"))
+        .bearer_auth("shop-secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(status.status(), 200);
+}
+
+#[tokio::test]
+async fn test_events() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let (url, _) = start(&temp_dir).await;
+    let client = reqwest::Client::new();
+
+    let earlier = chrono::Utc::now() - chrono::Duration::hours(1);
+    let change = MatrixChange::EntrypointRemoved {
+        file: "src/main.rs".into(),
+        kind: "main".to_string(),
+    };
+    EventLog::new(temp_dir.path())
+        .append(&[
+            MatrixEvent::new(earlier, "default", change.clone()),
+            MatrixEvent::new(chrono::Utc::now(), "default", change),
+        ])
+        .await
+        .unwrap();
+
+    let events: Vec<serde_json::Value> = client
+        .get(format!("{url}/events"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["type"], "entrypoint_removed");
+
+    let recent: Vec<serde_json::Value> = client
+        .get(format!("{url}/events"))
+        .query(&[("since", earlier.to_rfc3339())])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(recent.len(), 1);
+
+    let invalid = client
+        .get(format!("{url}/events?since=yesterday"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(invalid.status(), 400);
+}
+
+#[tokio::test]
+async fn test_annotations() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let url = serve(vec![Arc::new(
+        ProjectState::new(
+            "default",
+            temp_dir.path().join("matrix.json"),
+            temp_dir.path().to_path_buf(),
+        )
+        .with_root(temp_dir.path().to_path_buf()),
+    )]);
+    let client = reqwest::Client::new();
+
+    let empty: serde_json::Value = client
+        .get(format!("{url}/annotations"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(empty, serde_json::json!({}));
+
+    // Edits between scans show up right away
+    let mut annotations = Annotations::load(temp_dir.path()).unwrap();
+    annotations.file_mut("src/lib.rs".as_ref()).review = Some(ReviewStatus::ChangesRequested);
+    annotations.save().unwrap();
+    let body: serde_json::Value = client
+        .get(format!("{url}/annotations"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["files"]["src/lib.rs"]["review"], "changes-requested");
+}
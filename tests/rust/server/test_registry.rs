@@ -0,0 +1,147 @@
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+use csd::server::registry::{project_config, ProjectRegistry, RegisteredProject};
+use csd::utils::config::Config;
+
+fn registered(id: &str, source: &str) -> RegisteredProject {
+    RegisteredProject {
+        id: id.to_string(),
+        source: source.to_string(),
+        branch: None,
+        config: None,
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+#[test]
+fn test_add_remove_and_reload() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let path = temp_dir.path().join("csd/registry.yaml");
+    let project_dir = temp_dir.path().join("shop");
+    std::fs::create_dir_all(&project_dir).unwrap();
+
+    let mut registry = ProjectRegistry::load(&path).unwrap();
+    assert!(registry.projects.is_empty());
+    registry
+        .add(registered("shop", &project_dir.to_string_lossy()))
+        .unwrap();
+    registry
+        .add(registered("billing", "https://github.com/acme/billing.git"))
+        .unwrap();
+
+    // Ids are unique and URL-safe; directories must exist
+    assert!(registry
+        .add(registered("shop", "https://github.com/acme/shop.git"))
+        .is_err());
+    assert!(registry
+        .add(registered("a/b", "https://github.com/acme/ab.git"))
+        .is_err());
+    assert!(registry
+        .add(registered(
+            "gone",
+            &temp_dir.path().join("gone").to_string_lossy()
+        ))
+        .is_err());
+    registry.save().unwrap();
+
+    let mut reloaded = ProjectRegistry::load(&path).unwrap();
+    assert_eq!(reloaded.projects, registry.projects);
+    let shop = reloaded.get("shop").unwrap();
+    assert!(!shop.is_remote());
+    assert_eq!(reloaded.root(shop), project_dir.canonicalize().unwrap());
+    let billing = reloaded.get("billing").unwrap();
+    assert!(billing.is_remote());
+    assert_eq!(
+        reloaded.root(billing),
+        temp_dir.path().join("csd/checkouts/billing")
+    );
+
+    reloaded.remove("shop").unwrap();
+    assert!(reloaded.remove("shop").is_err());
+    assert_eq!(reloaded.projects.len(), 1);
+}
+
+#[test]
+fn test_update_clones_then_fast_forwards() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let upstream = temp_dir.path().join("upstream");
+    std::fs::create_dir_all(&upstream).unwrap();
+    if !git(&upstream, &["init", "-q"]) {
+        eprintln!("Skipping: git is not available");
+        return;
+    }
+    let commit = |message: &str| {
+        git(&upstream, &["add", "-A"])
+            && git(
+                &upstream,
+                &[
+                    "-c",
+                    "user.name=csd",
+                    "-c",
+                    "user.email=csd@example.com",
+                    "commit",
+                    "-q",
+                    "-m",
+                    message,
+                ],
+            )
+    };
+    std::fs::write(upstream.join("lib.py"), "x = 1\n").unwrap();
+    assert!(commit("Initial"));
+
+    let mut registry = ProjectRegistry::load(&temp_dir.path().join("registry.yaml")).unwrap();
+    let url = format!("file://{}", upstream.display());
+    registry.add(registered("lib", &url)).unwrap();
+    let project = registry.get("lib").unwrap().clone();
+
+    // Offline, a project that was never cloned can't be scanned
+    assert!(registry.update(&project, true).is_err());
+
+    let root = registry.update(&project, false).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(root.join("lib.py")).unwrap(),
+        "x = 1\n"
+    );
+
+    std::fs::write(upstream.join("lib.py"), "x = 2\n").unwrap();
+    assert!(commit("Change"));
+    registry.update(&project, false).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(root.join("lib.py")).unwrap(),
+        "x = 2\n"
+    );
+}
+
+#[tokio::test]
+async fn test_project_config_is_read_from_the_project() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let config = Config {
+        output_dir: "site".to_string(),
+        ..Default::default()
+    };
+    config
+        .save(&temp_dir.path().join(".csdrc.yaml"))
+        .await
+        .unwrap();
+
+    let project = registered("shop", &temp_dir.path().to_string_lossy());
+    let loaded = project_config(&project, temp_dir.path()).await.unwrap();
+    assert_eq!(loaded.output_dir, "site");
+
+    // An explicit config file that doesn't exist is an error
+    let project = RegisteredProject {
+        config: Some("missing.yaml".into()),
+        ..project
+    };
+    assert!(project_config(&project, temp_dir.path()).await.is_err());
+}
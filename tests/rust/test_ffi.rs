@@ -0,0 +1,101 @@
+use std::ffi::{CStr, CString};
+use std::path::Path;
+use tempfile::TempDir;
+
+use csd::core::matrix::ProjectMatrix;
+use csd::ffi::*;
+
+use crate::rust::core::test_matrix::{create_test_file_node, create_test_relationship};
+
+async fn write_matrix(dir: &Path) -> CString {
+    let mut matrix = ProjectMatrix::new(dir.to_path_buf());
+    matrix.add_file(create_test_file_node("src/main.rs", "rust"));
+    let mut lib = create_test_file_node("src/lib.rs", "rust");
+    lib.file_summary = None;
+    matrix.add_file(lib);
+    matrix.add_relationship(create_test_relationship("src/main.rs", "src/lib.rs"));
+
+    let path = dir.join("matrix.json");
+    matrix.save(&path).await.expect("Failed to save matrix");
+    CString::new(path.to_str().unwrap()).unwrap()
+}
+
+/// Take ownership of a returned string
+unsafe fn take(value: *mut std::ffi::c_char) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+    let text = CStr::from_ptr(value).to_str().unwrap().to_string();
+    csd_string_free(value);
+    Some(text)
+}
+
+fn last_error() -> Option<String> {
+    let error = csd_last_error();
+    (!error.is_null()).then(|| {
+        unsafe { CStr::from_ptr(error) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    })
+}
+
+#[tokio::test]
+async fn test_ffi_queries_loaded_matrix() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let path = write_matrix(temp_dir.path()).await;
+    assert_eq!(csd_ffi_version(), CSD_FFI_VERSION);
+
+    // The loader runs its own runtime, so call it off the test's runtime
+    let handle = std::thread::spawn(move || unsafe { csd_matrix_load(path.as_ptr()) } as usize)
+        .join()
+        .unwrap() as *mut CsdMatrix;
+    assert!(!handle.is_null(), "load failed: {:?}", last_error());
+
+    let main = CString::new("src/main.rs").unwrap();
+    let lib = CString::new("src/lib.rs").unwrap();
+    unsafe {
+        assert_eq!(csd_matrix_file_count(handle), 2);
+        assert_eq!(
+            take(csd_matrix_dependencies(handle, main.as_ptr())).as_deref(),
+            Some(r#"["src/lib.rs"]"#)
+        );
+        assert_eq!(
+            take(csd_matrix_dependents(handle, lib.as_ptr())).as_deref(),
+            Some(r#"["src/main.rs"]"#)
+        );
+        assert_eq!(
+            take(csd_matrix_file_summary(handle, main.as_ptr())).as_deref(),
+            Some("Test file summary")
+        );
+
+        // No summary is not an error
+        assert!(take(csd_matrix_file_summary(handle, lib.as_ptr())).is_none());
+        assert!(last_error().is_none());
+
+        let missing = CString::new("src/missing.rs").unwrap();
+        assert!(csd_matrix_dependencies(handle, missing.as_ptr()).is_null());
+        assert!(last_error().unwrap().contains("File not in matrix"));
+
+        csd_matrix_free(handle);
+    }
+}
+
+#[test]
+fn test_ffi_reports_errors_for_bad_arguments() {
+    unsafe {
+        assert!(csd_matrix_load(std::ptr::null()).is_null());
+        assert_eq!(last_error().as_deref(), Some("path is NULL"));
+
+        let path = CString::new("/definitely/does/not/exist/matrix.json").unwrap();
+        assert!(csd_matrix_load(path.as_ptr()).is_null());
+        assert!(last_error().is_some());
+
+        assert_eq!(csd_matrix_file_count(std::ptr::null_mut()), 0);
+        assert_eq!(last_error().as_deref(), Some("matrix is NULL"));
+
+        // Freeing NULL is a no-op
+        csd_matrix_free(std::ptr::null_mut());
+        csd_string_free(std::ptr::null_mut());
+    }
+}
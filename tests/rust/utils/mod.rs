@@ -1,6 +1,13 @@
 // Utils module tests
 
+pub mod test_cache;
 pub mod test_config;
-
-// Future utils test modules:
-// pub mod test_file_utils;
+pub mod test_file_utils;
+pub mod test_git;
+pub mod test_hashing;
+pub mod test_hooks;
+pub mod test_intern;
+pub mod test_issues;
+pub mod test_notify;
+pub mod test_profile;
+pub mod test_secrets;
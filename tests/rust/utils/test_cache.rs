@@ -0,0 +1,87 @@
+use std::time::Duration;
+use tempfile::TempDir;
+use uuid::Uuid;
+
+use csd::utils::cache::{lock_path, prune_stale_runs, run_dir, CacheLock, CacheRun, RUNS_DIR};
+
+#[test]
+fn test_cache_run_directory_lifecycle() {
+    let cache = TempDir::new().unwrap();
+    let id = Uuid::new_v4();
+
+    let run = CacheRun::create(cache.path(), id).unwrap();
+    assert_eq!(run.id(), id);
+    assert_eq!(run.dir(), run_dir(cache.path(), id));
+    assert!(run.dir().starts_with(cache.path().join(RUNS_DIR)));
+    assert!(run.dir().is_dir());
+
+    // A second run can't claim the same directory while the first is alive
+    assert!(CacheRun::create(cache.path(), id).is_err());
+
+    let dir = run.dir().to_path_buf();
+    drop(run);
+    assert!(!dir.exists());
+}
+
+#[test]
+fn test_prune_stale_runs_leaves_live_runs() {
+    let cache = TempDir::new().unwrap();
+    let live = CacheRun::create(cache.path(), Uuid::new_v4()).unwrap();
+
+    // A crashed run leaves its directory and lock file behind, unlocked
+    let crashed = run_dir(cache.path(), Uuid::new_v4());
+    std::fs::create_dir_all(&crashed).unwrap();
+    std::fs::write(crashed.join(".lock"), "").unwrap();
+    std::fs::write(crashed.join("plugin_input_1.json"), "{}").unwrap();
+
+    // One still being set up has no lock file yet
+    let starting = run_dir(cache.path(), Uuid::new_v4());
+    std::fs::create_dir_all(&starting).unwrap();
+
+    assert_eq!(prune_stale_runs(cache.path()).unwrap(), 1);
+    assert!(!crashed.exists());
+    assert!(starting.exists());
+    assert!(live.dir().exists());
+}
+
+#[test]
+fn test_prune_stale_runs_without_runs_dir() {
+    let cache = TempDir::new().unwrap();
+    assert_eq!(prune_stale_runs(cache.path()).unwrap(), 0);
+}
+
+#[test]
+fn test_lock_path_is_hidden_sibling() {
+    let path = std::path::Path::new(".csd_cache/matrix.json");
+    assert_eq!(
+        lock_path(path),
+        std::path::Path::new(".csd_cache/.matrix.json.lock")
+    );
+}
+
+#[tokio::test]
+async fn test_cache_lock_excludes_writers() {
+    let cache = TempDir::new().unwrap();
+    let path = cache.path().join("history/metrics.jsonl");
+
+    // Readers share the lock
+    let first = CacheLock::shared(&path).await.unwrap();
+    let second = CacheLock::shared(&path).await.unwrap();
+    assert!(lock_path(&path).exists());
+
+    // A writer waits for both to finish
+    let writer = tokio::spawn({
+        let path = path.clone();
+        async move { CacheLock::exclusive(&path).await.map(|_| ()) }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!writer.is_finished());
+
+    drop(first);
+    drop(second);
+    tokio::time::timeout(Duration::from_secs(5), writer)
+        .await
+        .expect("writer should get the lock once readers are done")
+        .unwrap()
+        .unwrap();
+}
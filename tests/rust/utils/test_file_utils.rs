@@ -0,0 +1,70 @@
+use std::path::Path;
+use tempfile::TempDir;
+
+use csd::utils::file_utils::{resolve_inside, resolve_within};
+
+#[test]
+fn test_resolve_within_accepts_paths_inside_base() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let base = temp_dir.path();
+    std::fs::create_dir(base.join("en")).unwrap();
+    let canonical = base.canonicalize().unwrap();
+
+    assert_eq!(
+        resolve_within(base, Path::new("en/index.md")).unwrap(),
+        canonical.join("en/index.md")
+    );
+    assert_eq!(
+        resolve_within(base, &base.join("new/dir/../page.md")).unwrap(),
+        canonical.join("new/page.md")
+    );
+}
+
+#[test]
+fn test_resolve_within_rejects_escapes() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let base = temp_dir.path().join("docs");
+    std::fs::create_dir(&base).unwrap();
+
+    assert!(resolve_within(&base, Path::new("../matrix.json")).is_err());
+    assert!(resolve_within(&base, Path::new("en/../../outside.md")).is_err());
+    assert!(resolve_within(&base, Path::new("/etc/passwd")).is_err());
+}
+
+#[test]
+fn test_resolve_inside_takes_relative_paths_from_the_working_directory() {
+    // A relative output dir, like the default `docs`, with a plugin reporting the path
+    // it wrote to rather than one relative to the output dir
+    let temp_dir = tempfile::Builder::new()
+        .prefix(".resolve-inside")
+        .tempdir_in(".")
+        .expect("Failed to create temp dir");
+    let base = Path::new(temp_dir.path().file_name().unwrap());
+    assert!(base.is_relative());
+    let canonical = base.canonicalize().unwrap();
+
+    assert_eq!(
+        resolve_inside(base, &base.join("README.md")).unwrap(),
+        canonical.join("README.md")
+    );
+    assert_eq!(
+        resolve_inside(base, &canonical.join("en/index.md")).unwrap(),
+        canonical.join("en/index.md")
+    );
+    // Relative to the output dir it would be outside it
+    assert!(resolve_inside(base, Path::new("README.md")).is_err());
+    assert!(resolve_inside(base, &base.join("../matrix.json")).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_resolve_within_follows_symlinks() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let base = temp_dir.path().join("docs");
+    let outside = temp_dir.path().join("outside");
+    std::fs::create_dir(&base).unwrap();
+    std::fs::create_dir(&outside).unwrap();
+    std::os::unix::fs::symlink(&outside, base.join("link")).unwrap();
+
+    assert!(resolve_within(&base, Path::new("link/page.md")).is_err());
+}
@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+use csd::core::matrix::ProjectMatrix;
+use csd::utils::config::Config;
+use csd::utils::hashing::HashAlgorithm;
+
+use crate::rust::core::test_matrix::create_test_file_node;
+
+#[test]
+fn test_known_digests() {
+    assert_eq!(
+        HashAlgorithm::Sha256.hash(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        HashAlgorithm::Blake3.hash(b"abc"),
+        "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+    );
+
+    let xxh3 = HashAlgorithm::Xxh3.hash(b"abc");
+    assert_eq!(xxh3.len(), 32);
+    assert!(xxh3.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(xxh3, HashAlgorithm::Xxh3.hash(b"abc"));
+    assert_ne!(xxh3, HashAlgorithm::Xxh3.hash(b"abd"));
+}
+
+#[test]
+fn test_files_hash_in_chunks_like_buffers() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("artifact.bin");
+    // Several chunks and a partial one
+    let content: Vec<u8> = (0..200_003u32).map(|i| (i % 251) as u8).collect();
+    std::fs::write(&path, &content).unwrap();
+
+    for algorithm in [
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Blake3,
+        HashAlgorithm::Xxh3,
+    ] {
+        assert_eq!(
+            algorithm.hash_file(&path).unwrap(),
+            algorithm.hash(&content),
+            "{algorithm}"
+        );
+    }
+    assert!(HashAlgorithm::Sha256
+        .hash_file(&dir.path().join("missing"))
+        .is_err());
+}
+
+#[test]
+fn test_cache_keys_keep_algorithms_apart() {
+    assert_eq!(HashAlgorithm::Sha256.cache_key("ab12"), "ab12");
+    assert_eq!(HashAlgorithm::Xxh3.cache_key("ab12"), "xxh3:ab12");
+    assert_ne!(
+        HashAlgorithm::Blake3.cache_key("ab12"),
+        HashAlgorithm::Xxh3.cache_key("ab12")
+    );
+}
+
+#[test]
+fn test_hash_algorithm_config() {
+    let yaml = serde_yaml::to_string(&Config::default()).unwrap();
+    assert!(!yaml.contains("hash_algorithm"));
+    assert_eq!(
+        Config::default().scanning.hash_algorithm,
+        HashAlgorithm::Sha256
+    );
+
+    let config: Config =
+        serde_yaml::from_str(&yaml.replace("scanning:\n", "scanning:\n  hash_algorithm: xxh3\n"))
+            .expect("Config should parse");
+    assert_eq!(config.scanning.hash_algorithm, HashAlgorithm::Xxh3);
+    assert!(serde_yaml::from_str::<HashAlgorithm>("md5").is_err());
+}
+
+#[test]
+fn test_changed_files_refuses_mixed_algorithms() {
+    let mut before = ProjectMatrix::new(PathBuf::from("/test"));
+    for file in ["src/a.rs", "src/b.rs", "src/c.rs"] {
+        before.add_file(create_test_file_node(file, "rust"));
+    }
+    let mut after = before.clone();
+    after
+        .files
+        .get_mut(&PathBuf::from("src/a.rs"))
+        .unwrap()
+        .hash = "edited".to_string();
+    after.files.remove(&PathBuf::from("src/b.rs"));
+    after.add_file(create_test_file_node("src/d.rs", "rust"));
+
+    assert_eq!(
+        after.changed_files(&before).unwrap(),
+        vec![
+            PathBuf::from("src/a.rs"),
+            PathBuf::from("src/b.rs"),
+            PathBuf::from("src/d.rs")
+        ]
+    );
+
+    after.metadata.hash_algorithm = HashAlgorithm::Blake3;
+    let err = after.changed_files(&before).unwrap_err().to_string();
+    assert!(err.contains("blake3") && err.contains("sha256"), "{err}");
+}
+
+#[test]
+fn test_older_matrices_read_as_sha256() {
+    let matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    let mut json = serde_json::to_value(&matrix).unwrap();
+    assert_eq!(json["metadata"]["hash_algorithm"], "sha256");
+
+    json["metadata"]
+        .as_object_mut()
+        .unwrap()
+        .remove("hash_algorithm");
+    let loaded: ProjectMatrix = serde_json::from_value(json).unwrap();
+    assert_eq!(loaded.metadata.hash_algorithm, HashAlgorithm::Sha256);
+}
@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use csd::utils::config::{Config, HookConfig};
+use csd::utils::hooks::{run_hooks, HookContext, HookStage};
+
+fn context(root: &std::path::Path) -> HookContext {
+    HookContext {
+        project_root: root.to_path_buf(),
+        cache_dir: root.join(".csd_cache"),
+        matrix_path: Some(root.join(".csd_cache/matrix.json")),
+        output_dir: None,
+    }
+}
+
+#[test]
+fn test_hooks_accept_string_shorthand() {
+    let yaml = r#"
+pre_scan:
+  - "make proto"
+  - command: "./upload.sh"
+    allow_failure: true
+post_docs:
+  - plugin: "hooks/publish.py"
+"#;
+    let hooks: csd::utils::config::HooksConfig = serde_yaml::from_str(yaml).unwrap();
+
+    assert_eq!(
+        hooks.pre_scan,
+        vec![
+            HookConfig {
+                command: Some("make proto".to_string()),
+                ..Default::default()
+            },
+            HookConfig {
+                command: Some("./upload.sh".to_string()),
+                plugin: None,
+                allow_failure: true,
+            },
+        ]
+    );
+    assert_eq!(
+        hooks.post_docs[0].plugin.as_deref(),
+        Some("hooks/publish.py")
+    );
+    assert!(hooks.post_scan.is_empty());
+
+    // Configs without hooks don't grow an empty section when saved
+    let yaml = serde_yaml::to_string(&Config::default()).unwrap();
+    assert!(!yaml.contains("hooks"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_run_hooks_passes_paths_in_environment() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let mut config = Config::default();
+    config.hooks.pre_scan.push(HookConfig {
+        command: Some("echo \"$CSD_HOOK_STAGE $CSD_MATRIX_PATH\" > hook.out".to_string()),
+        ..Default::default()
+    });
+
+    run_hooks(&config, HookStage::PreScan, &context(temp_dir.path()))
+        .await
+        .expect("Hook should succeed");
+
+    // Runs from the project root with absolute paths
+    let written = std::fs::read_to_string(temp_dir.path().join("hook.out")).unwrap();
+    let expected = format!(
+        "pre_scan {}\n",
+        temp_dir.path().join(".csd_cache/matrix.json").display()
+    );
+    assert_eq!(written, expected);
+
+    // Hooks for other stages don't run
+    std::fs::remove_file(temp_dir.path().join("hook.out")).unwrap();
+    run_hooks(&config, HookStage::PostScan, &context(temp_dir.path()))
+        .await
+        .unwrap();
+    assert!(!temp_dir.path().join("hook.out").exists());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_offline_mode_is_passed_to_hooks() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let mut config = Config::default();
+    config.hooks.pre_scan.push(HookConfig {
+        command: Some("echo \"offline=$CSD_OFFLINE\" > hook.out".to_string()),
+        ..Default::default()
+    });
+
+    run_hooks(&config, HookStage::PreScan, &context(temp_dir.path()))
+        .await
+        .unwrap();
+    let written = std::fs::read_to_string(temp_dir.path().join("hook.out")).unwrap();
+    assert_eq!(written, "offline=\n");
+
+    config.offline = true;
+    run_hooks(&config, HookStage::PreScan, &context(temp_dir.path()))
+        .await
+        .unwrap();
+    let written = std::fs::read_to_string(temp_dir.path().join("hook.out")).unwrap();
+    assert_eq!(written, "offline=1\n");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_failing_hook_stops_unless_allowed() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let mut config = Config::default();
+    config.hooks.post_docs.push(HookConfig {
+        command: Some("exit 3".to_string()),
+        ..Default::default()
+    });
+
+    let err = run_hooks(&config, HookStage::PostDocs, &context(temp_dir.path()))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("post_docs hook failed"));
+
+    config.hooks.post_docs[0].allow_failure = true;
+    config.hooks.post_docs.push(HookConfig {
+        command: Some("touch after".to_string()),
+        ..Default::default()
+    });
+    run_hooks(&config, HookStage::PostDocs, &context(temp_dir.path()))
+        .await
+        .expect("Allowed failure should not stop later hooks");
+    assert!(PathBuf::from(temp_dir.path()).join("after").exists());
+}
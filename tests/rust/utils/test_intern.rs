@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use csd::core::matrix::{FileNode, ProjectMatrix};
+use csd::utils::intern::IStr;
+
+use crate::rust::core::test_matrix::create_test_file_node;
+
+#[test]
+fn test_equal_values_share_one_allocation() {
+    let a = IStr::new("python");
+    let b: IStr = String::from("python").into();
+    let c = IStr::from("rust");
+
+    assert_eq!(a, b);
+    assert!(a.ptr_eq(&b));
+    assert!(!a.ptr_eq(&c));
+    assert_eq!(a, "python");
+    assert_eq!(a.len(), 6);
+    assert_eq!(format!("{a} {c:?}"), "python \"rust\"");
+}
+
+#[test]
+fn test_released_values_are_pruned() {
+    let kept = IStr::new("intern-kept");
+    drop(IStr::new("intern-released"));
+    assert!(IStr::is_interned("intern-released"));
+
+    // Other tests intern concurrently, so grow the table until a prune runs
+    let mut i = 0;
+    while IStr::is_interned("intern-released") {
+        drop(IStr::new(&format!("intern-filler-{i}")));
+        i += 1;
+        assert!(i < 1_000_000, "released values were never pruned");
+    }
+    assert!(IStr::is_interned("intern-kept"));
+    assert!(IStr::new("intern-kept").ptr_eq(&kept));
+}
+
+#[test]
+fn test_serializes_as_plain_string() {
+    let value = IStr::new("cargo");
+    assert_eq!(serde_json::to_string(&value).unwrap(), "\"cargo\"");
+
+    let parsed: IStr = serde_json::from_str("\"cargo\"").unwrap();
+    assert!(parsed.ptr_eq(&value));
+    assert!(serde_json::from_str::<IStr>("42").is_err());
+}
+
+#[test]
+fn test_matrix_json_format_is_unchanged() {
+    let file = create_test_file_node("src/lib.rs", "rust");
+    let json: serde_json::Value = serde_json::to_value(&file).unwrap();
+    assert_eq!(json["plugin"], "rust");
+    assert_eq!(json["language"], "rust");
+
+    let mut matrix = ProjectMatrix::new(PathBuf::from("/test"));
+    matrix.add_file(create_test_file_node("a.rs", "rust"));
+    matrix.add_file(create_test_file_node("b.rs", "rust"));
+    let reloaded: ProjectMatrix =
+        serde_json::from_str(&serde_json::to_string(&matrix).unwrap()).unwrap();
+
+    let plugins: Vec<&FileNode> = reloaded.files.values().collect();
+    assert!(plugins[0].plugin.ptr_eq(&plugins[1].plugin));
+}
@@ -0,0 +1,112 @@
+use std::io::{Read, Write};
+use std::net::TcpListener;
+
+use csd::utils::config::{IssueTrackerConfig, TrackerKind};
+use csd::utils::issues::{issue_keys, tracker_for, IssueClient, IssueStatus};
+
+fn tracker(kind: TrackerKind, projects: &[&str]) -> IssueTrackerConfig {
+    IssueTrackerConfig {
+        kind,
+        url: None,
+        repo: Some("acme/shop".to_string()),
+        projects: projects.iter().map(|p| p.to_string()).collect(),
+        token_env: None,
+    }
+}
+
+/// Answer one HTTP request with `body` and return the request line
+fn serve_once(body: &'static str) -> (String, std::thread::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buffer = [0u8; 4096];
+        let read = stream.read(&mut buffer).unwrap();
+        let request = String::from_utf8_lossy(&buffer[..read]).to_string();
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        request.lines().next().unwrap_or_default().to_string()
+    });
+    (url, handle)
+}
+
+#[test]
+fn test_issue_keys() {
+    assert_eq!(
+        issue_keys("TODO(PAY-12): retry, see #456 and PAY-12 again"),
+        vec!["PAY-12", "#456"]
+    );
+    // Encodings, digests, anchors and words that merely contain a key are not issues
+    assert!(issue_keys("UTF-8 and SHA-256, &#123; color #fff, XPAY-1x, a#1").is_empty());
+    assert!(issue_keys("P-1 is too short").is_empty());
+}
+
+#[test]
+fn test_tracker_for() {
+    let trackers = vec![
+        tracker(TrackerKind::Jira, &["PAY"]),
+        tracker(TrackerKind::Linear, &[]),
+        tracker(TrackerKind::GitHub, &[]),
+    ];
+    assert_eq!(
+        tracker_for(&trackers, "#4").unwrap().kind,
+        TrackerKind::GitHub
+    );
+    assert_eq!(
+        tracker_for(&trackers, "PAY-1").unwrap().kind,
+        TrackerKind::Jira
+    );
+    assert_eq!(
+        tracker_for(&trackers, "ENG-1").unwrap().kind,
+        TrackerKind::Linear
+    );
+    assert!(tracker_for(&trackers[..1], "ENG-1").is_none());
+}
+
+#[tokio::test]
+async fn test_github_status() {
+    let (url, server) = serve_once(r#"{"number": 456, "state": "closed"}"#);
+    let tracker = IssueTrackerConfig {
+        url: Some(url),
+        ..tracker(TrackerKind::GitHub, &[])
+    };
+    // Local trackers are allowed offline
+    let status = IssueClient::new(true)
+        .unwrap()
+        .status(&tracker, "#456")
+        .await
+        .unwrap();
+    assert_eq!(status, IssueStatus::Closed);
+    assert!(server
+        .join()
+        .unwrap()
+        .starts_with("GET /repos/acme/shop/issues/456 "));
+}
+
+#[tokio::test]
+async fn test_jira_status_and_offline_refusal() {
+    let (url, server) =
+        serve_once(r#"{"fields": {"status": {"statusCategory": {"key": "indeterminate"}}}}"#);
+    let jira = IssueTrackerConfig {
+        url: Some(url),
+        ..tracker(TrackerKind::Jira, &[])
+    };
+    let status = IssueClient::new(false)
+        .unwrap()
+        .status(&jira, "PAY-12")
+        .await
+        .unwrap();
+    assert_eq!(status, IssueStatus::Open);
+    assert!(server
+        .join()
+        .unwrap()
+        .starts_with("GET /rest/api/2/issue/PAY-12?fields=status "));
+
+    let linear = tracker(TrackerKind::Linear, &[]);
+    let offline = IssueClient::new(true).unwrap();
+    assert!(offline.status(&linear, "ENG-1").await.is_err());
+}
@@ -0,0 +1,217 @@
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::time::Duration;
+
+use csd::core::events::{MatrixChange, MatrixEvent};
+use csd::utils::config::{NotificationsConfig, NotifyCommand, WebhookConfig};
+use csd::utils::notify::{events_payload, format_duration, with_delta, Notification, Notifier};
+
+fn notification(success: bool) -> Notification {
+    Notification {
+        command: NotifyCommand::Scan,
+        project: "shop".to_string(),
+        success,
+        gate: if success {
+            "passed".to_string()
+        } else {
+            "failed: 2 plugin errors (--fail-on plugin-errors)".to_string()
+        },
+        duration: Duration::from_secs(192),
+        facts: vec![("Files".to_string(), "120 (+3)".to_string())],
+        artifacts: vec![("Matrix".to_string(), ".csd_cache/matrix.json".to_string())],
+    }
+}
+
+fn webhook(url: Option<String>, commands: Vec<NotifyCommand>) -> WebhookConfig {
+    WebhookConfig {
+        url,
+        url_env: None,
+        commands,
+        only_failures: false,
+        link_base: None,
+    }
+}
+
+/// Answer one HTTP request with 200 and return its request line and body
+fn serve_once() -> (String, std::thread::JoinHandle<(String, String)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!(
+        "http://{}/hooks/T000/B000/secret",
+        listener.local_addr().unwrap()
+    );
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 4096];
+        // Read until the headers and the whole body have arrived
+        loop {
+            let read = stream.read(&mut buffer).unwrap();
+            request.extend_from_slice(&buffer[..read]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length = head
+                    .lines()
+                    .find_map(|line| {
+                        line.to_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                if body.len() >= length || read == 0 {
+                    break;
+                }
+            }
+        }
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+            .unwrap();
+        let text = String::from_utf8_lossy(&request).to_string();
+        let (head, body) = text.split_once("\r\n\r\n").unwrap();
+        (
+            head.lines().next().unwrap_or_default().to_string(),
+            body.to_string(),
+        )
+    });
+    (url, handle)
+}
+
+#[test]
+fn test_slack_payload() {
+    let payload = notification(false).slack_payload(None);
+    let text = payload["text"].as_str().unwrap();
+    assert_eq!(text, "❌ csd scan failed for shop in 3m 12s");
+
+    let fields = payload["blocks"][1]["fields"].as_array().unwrap();
+    assert_eq!(
+        fields[0]["text"],
+        "*Quality gate*\nfailed: 2 plugin errors (--fail-on plugin-errors)"
+    );
+    assert_eq!(fields[1]["text"], "*Files*\n120 (+3)");
+    assert_eq!(
+        payload["blocks"][2]["elements"][0]["text"],
+        "Matrix: `.csd_cache/matrix.json`"
+    );
+
+    let linked = notification(true).slack_payload(Some("https://ci.example.com/artifacts/"));
+    assert!(linked["text"].as_str().unwrap().starts_with("✅"));
+    assert_eq!(
+        linked["blocks"][2]["elements"][0]["text"],
+        "<https://ci.example.com/artifacts/.csd_cache/matrix.json|Matrix>"
+    );
+}
+
+#[test]
+fn test_summary_formatting() {
+    assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+    assert_eq!(format_duration(Duration::from_secs(3725)), "1h 02m");
+    assert_eq!(with_delta(120, Some(117)), "120 (+3)");
+    assert_eq!(with_delta(118, Some(120)), "118 (-2)");
+    assert_eq!(with_delta(120, Some(120)), "120");
+    assert_eq!(with_delta(120, None), "120");
+}
+
+#[test]
+fn test_webhooks_for() {
+    let yaml = r#"
+webhooks:
+  - url: https://hooks.slack.com/services/T/B/X
+  - url_env: NIGHTLY_WEBHOOK
+    commands: [docs]
+    only_failures: true
+"#;
+    let config: NotificationsConfig = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(
+        config.webhooks[0].commands,
+        vec![NotifyCommand::Scan, NotifyCommand::Docs]
+    );
+    assert_eq!(config.webhooks_for(NotifyCommand::Scan, false).count(), 1);
+    assert_eq!(config.webhooks_for(NotifyCommand::Docs, true).count(), 1);
+    assert_eq!(config.webhooks_for(NotifyCommand::Docs, false).count(), 2);
+
+    let err = config.webhooks[1].resolve_url().unwrap_err();
+    assert!(format!("{err:#}").contains("NIGHTLY_WEBHOOK"));
+}
+
+#[tokio::test]
+async fn test_notifier_posts_payload() {
+    let (url, server) = serve_once();
+    let notifier = Notifier::new(true).unwrap();
+    notifier
+        .send(
+            &webhook(Some(url), vec![NotifyCommand::Scan]),
+            &notification(true),
+        )
+        .await
+        .unwrap();
+    let (request_line, body) = server.join().unwrap();
+    assert_eq!(request_line, "POST /hooks/T000/B000/secret HTTP/1.1");
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["text"], "✅ csd scan finished for shop in 3m 12s");
+
+    // Offline mode refuses remote webhooks, and the error keeps the URL's secret out
+    let remote = webhook(
+        Some("https://hooks.slack.com/services/T/B/secret".to_string()),
+        vec![NotifyCommand::Scan],
+    );
+    let err = notifier
+        .send(&remote, &notification(true))
+        .await
+        .unwrap_err();
+    let message = format!("{err:#}");
+    assert!(message.contains("hooks.slack.com"));
+    assert!(!message.contains("secret"));
+}
+
+#[tokio::test]
+async fn test_events_go_to_subscribed_webhooks() {
+    let resolved = MatrixEvent::new(
+        chrono::Utc::now(),
+        "shop",
+        MatrixChange::CycleResolved {
+            files: vec!["a.rs".into(), "b.rs".into()],
+        },
+    );
+    let introduced = MatrixEvent::new(
+        chrono::Utc::now(),
+        "shop",
+        MatrixChange::CycleIntroduced {
+            files: vec!["c.rs".into(), "d.rs".into()],
+        },
+    );
+    let payload = events_payload("shop", &[introduced, resolved.clone()]);
+    assert_eq!(
+        payload["text"],
+        "⚠️ csd found 2 architectural changes in shop"
+    );
+    assert_eq!(
+        payload["blocks"][1]["text"]["text"],
+        "• *New dependency cycle: c.rs → d.rs*\n• Dependency cycle resolved: a.rs → b.rs"
+    );
+    assert_eq!(payload["events"][0]["type"], "cycle_introduced");
+
+    // Failure-only webhooks only hear about warnings
+    let (url, server) = serve_once();
+    let config = NotificationsConfig {
+        webhooks: vec![
+            WebhookConfig {
+                only_failures: true,
+                ..webhook(
+                    Some("http://127.0.0.1:9/unreachable".to_string()),
+                    vec![NotifyCommand::Events],
+                )
+            },
+            webhook(Some(url), vec![NotifyCommand::Events]),
+            webhook(
+                Some("http://127.0.0.1:9/scans".to_string()),
+                vec![NotifyCommand::Scan],
+            ),
+        ],
+    };
+    let notifier = Notifier::new(true).unwrap();
+    notifier
+        .notify_events(&config, "shop", std::slice::from_ref(&resolved))
+        .await;
+    let (_, body) = server.join().unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["events"][0]["summary"], resolved.summary);
+}
@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use csd::utils::profile::{self, Stage};
+
+// The profiler is process-wide, so this is its only test; spans from tests running
+// alongside may add to the counts, but never below what is recorded here
+#[test]
+fn test_profile_records_stages_while_enabled() {
+    profile::enable();
+    assert!(profile::is_enabled());
+    profile::record(Stage::Hash, Duration::from_millis(20));
+    profile::record(Stage::Hash, Duration::from_millis(10));
+    {
+        let _span = profile::span(Stage::Walk);
+        std::thread::sleep(Duration::from_millis(2));
+    }
+
+    let report = profile::finish("init").expect("profiling was enabled");
+    assert!(!profile::is_enabled());
+    assert_eq!(report.command, "init");
+
+    let hash = report
+        .stages
+        .iter()
+        .find(|t| t.stage == Stage::Hash)
+        .unwrap();
+    assert!(hash.calls >= 2);
+    assert!(hash.total_ms >= 30.0);
+    let walk = report
+        .stages
+        .iter()
+        .find(|t| t.stage == Stage::Walk)
+        .unwrap();
+    assert!(walk.total_ms >= 2.0);
+    assert!(report.wall_ms >= walk.total_ms);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["command"], "init");
+    assert!(json["stages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|t| t["stage"] == "hash"));
+
+    // Finished: nothing more to report
+    assert!(profile::finish("init").is_none());
+}
@@ -0,0 +1,92 @@
+use csd::utils::config::Config;
+use csd::utils::secrets::{
+    interpolate_config_values, interpolate_str, is_secret_key, keyring_reference,
+    redact_config_values, REDACTED,
+};
+use tempfile::TempDir;
+use tokio::fs;
+
+#[test]
+fn test_is_secret_key() {
+    assert!(is_secret_key("llm.api_key"));
+    assert!(is_secret_key("output_plugins.docs.config.github_token"));
+    assert!(is_secret_key("password"));
+    assert!(!is_secret_key("llm.model"));
+    assert!(!is_secret_key("tokens_per_request"));
+}
+
+#[test]
+fn test_interpolate_env_variables() {
+    std::env::set_var("CSD_TEST_SECRET_VALUE", "sk-test");
+    std::env::remove_var("CSD_TEST_SECRET_MISSING");
+
+    assert_eq!(
+        interpolate_str("${CSD_TEST_SECRET_VALUE}").unwrap(),
+        "sk-test"
+    );
+    assert_eq!(
+        interpolate_str("Bearer ${CSD_TEST_SECRET_VALUE}!").unwrap(),
+        "Bearer sk-test!"
+    );
+    assert_eq!(
+        interpolate_str("${CSD_TEST_SECRET_MISSING:-fallback}").unwrap(),
+        "fallback"
+    );
+    assert_eq!(interpolate_str("${CSD_TEST_SECRET_MISSING}").unwrap(), "");
+    assert_eq!(
+        interpolate_str("cost: $5 $${literal}").unwrap(),
+        "cost: $5 ${literal}"
+    );
+    assert!(interpolate_str("${UNTERMINATED").is_err());
+}
+
+#[test]
+fn test_interpolate_nested_values() {
+    std::env::set_var("CSD_TEST_NESTED_MODEL", "codellama");
+    let mut tree: serde_yaml::Value = serde_yaml::from_str(
+        "llm:\n  model: ${CSD_TEST_NESTED_MODEL}\nlist: [\"${CSD_TEST_NESTED_MODEL}\"]\n",
+    )
+    .unwrap();
+
+    interpolate_config_values(&mut tree).unwrap();
+
+    assert_eq!(tree["llm"]["model"].as_str(), Some("codellama"));
+    assert_eq!(tree["list"][0].as_str(), Some("codellama"));
+}
+
+#[test]
+fn test_redact_config_values() {
+    let mut config = Config::default();
+    config.llm.api_key = Some("sk-live-123".to_string());
+    let mut tree = serde_yaml::to_value(&config).unwrap();
+
+    redact_config_values(&mut tree);
+
+    assert_eq!(tree["llm"]["api_key"].as_str(), Some(REDACTED));
+    assert_eq!(tree["llm"]["model"].as_str(), Some("deepseek-coder"));
+    assert!(!format!("{:?}", config.llm).contains("sk-live-123"));
+    assert_eq!(keyring_reference("llm.api_key"), "${keyring:llm.api_key}");
+}
+
+#[tokio::test]
+async fn test_config_load_interpolates_api_key() {
+    std::env::set_var("CSD_TEST_LOAD_API_KEY", "sk-from-env");
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let config_path = temp_dir.path().join(".csdrc.yaml");
+
+    let mut config = Config::default();
+    config.llm.api_key = Some("${CSD_TEST_LOAD_API_KEY}".to_string());
+    config.save(&config_path).await.unwrap();
+
+    // The file keeps the reference, the loaded config has the value
+    let content = fs::read_to_string(&config_path).await.unwrap();
+    assert!(content.contains("${CSD_TEST_LOAD_API_KEY}"));
+
+    let loaded = Config::load(&config_path).await.unwrap();
+    assert_eq!(loaded.llm.api_key.as_deref(), Some("sk-from-env"));
+
+    let layered = Config::load_layered(None, Some(&config_path))
+        .await
+        .unwrap();
+    assert_eq!(layered.llm.api_key.as_deref(), Some("sk-from-env"));
+}